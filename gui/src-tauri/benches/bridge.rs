@@ -0,0 +1,158 @@
+//! Bridge throughput benchmarks (`cargo bench --bench bridge`)
+//!
+//! JSON-RPC parsing of a small and a 1 MB response, `call_tool` content
+//! extraction (including a 3,000-task list), the `tasks_list` response built
+//! from that list, and round trips through `fake-mcp-server` (the scripted
+//! backend of the bridge and integration tests) over piped stdio.
+
+use std::hint::black_box;
+use std::path::PathBuf;
+
+use apply_task_gui_lib::bench::{self, PythonBridge};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use serde_json::{json, Value};
+
+/// `tools/call` result carrying `text_bytes` of task text
+fn tool_result(text_bytes: usize) -> Value {
+    let envelope = json!({
+        "success": true,
+        "result": { "id": "TASK-001", "title": "Bench", "description": "x".repeat(text_bytes) }
+    });
+    json!({ "content": [{ "type": "json", "json": envelope }], "isError": false })
+}
+
+/// `tools/call` result of a `tasks_context` listing `count` copies of the
+/// fixture's first task
+fn task_list_result(count: usize) -> Value {
+    let fixture: Value =
+        serde_json::from_str(include_str!("../tests/fixtures/tasks_context.json")).unwrap();
+    let task = &fixture["result"]["tasks"][0];
+    let tasks: Vec<Value> = (0..count)
+        .map(|i| {
+            let mut task = task.clone();
+            task["id"] = json!(format!("TASK-{:04}", i + 1));
+            task
+        })
+        .collect();
+    let envelope = json!({ "success": true, "result": { "tasks": tasks } });
+    json!({ "content": [{ "type": "json", "json": envelope }], "isError": false })
+}
+
+fn response_line(text_bytes: usize) -> String {
+    json!({ "jsonrpc": "2.0", "id": 7, "result": tool_result(text_bytes) }).to_string()
+}
+
+fn parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_line");
+    for (name, text_bytes) in [("small", 64), ("1mb", 1 << 20)] {
+        let line = response_line(text_bytes);
+        group.throughput(Throughput::Bytes(line.len() as u64));
+        group.bench_function(name, |b| {
+            b.iter(|| bench::parse_line(black_box(&line)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn extraction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tool_content");
+    for (name, text_bytes) in [("small", 64), ("1mb", 1 << 20)] {
+        let result = tool_result(text_bytes);
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || result.clone(),
+                |result| bench::tool_content(result).unwrap(),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    let list = task_list_result(3000);
+    group.throughput(Throughput::Elements(3000));
+    group.bench_function("tasks_list_3000", |b| {
+        b.iter_batched(
+            || list.clone(),
+            |result| bench::tool_content(result).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn list_response(c: &mut Criterion) {
+    let mut group = c.benchmark_group("list_response");
+    let list = task_list_result(3000);
+    group.throughput(Throughput::Elements(3000));
+    group.bench_function("tasks_3000", |b| {
+        b.iter_batched(
+            || list.clone(),
+            |result| bench::task_list(result).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+/// Scenario dir with `tasks_show` echoing its arguments (removed on drop)
+struct Scenario(PathBuf);
+
+impl Scenario {
+    fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!("apply-task-bench-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let scenario = json!({ "tools": { "tasks_show": { "echo": true } } });
+        std::fs::write(dir.join("apply_task"), scenario.to_string()).unwrap();
+        Self(dir)
+    }
+
+    fn bridge(&self) -> PythonBridge {
+        PythonBridge::new(self.0.clone(), self.0.clone())
+            .with_python_path(Some(env!("CARGO_BIN_EXE_fake-mcp-server").to_string()))
+    }
+}
+
+impl Drop for Scenario {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn round_trips(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let scenario = Scenario::new();
+    let bridge = scenario.bridge();
+    runtime.block_on(bridge.connect()).unwrap();
+
+    let mut group = c.benchmark_group("round_trip");
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            runtime
+                .block_on(bridge.call_tool("tasks_show", json!({ "task": "TASK-001" })))
+                .unwrap()
+        })
+    });
+    group.throughput(Throughput::Elements(100));
+    group.bench_function("concurrent_100", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                let calls: Vec<_> = (0..100)
+                    .map(|i| {
+                        let bridge = bridge.clone();
+                        tokio::spawn(async move {
+                            bridge.call_tool("tasks_show", json!({ "task": i })).await
+                        })
+                    })
+                    .collect();
+                for call in calls {
+                    call.await.unwrap().unwrap();
+                }
+            })
+        })
+    });
+    group.finish();
+
+    runtime.block_on(bridge.shutdown()).unwrap();
+}
+
+criterion_group!(benches, parsing, extraction, list_response, round_trips);
+criterion_main!(benches);
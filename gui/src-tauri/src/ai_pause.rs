@@ -0,0 +1,305 @@
+//! Pause all / resume all
+//!
+//! `ai_pause_all` stops AI activity with one `pause` signal (the backend
+//! keeps one AI state per process, so it covers every namespace) and records
+//! what was going on: the AI status and the task it was working on, from a
+//! fresh `tasks_ai_status`. The record is kept in `AppState` and in
+//! `ai_pause.json` in the app data dir, so it survives a restart.
+//! `ai_resume_all` sends `resume` only while that record exists and the AI
+//! is still paused: an AI paused before, or resumed by hand since, is left
+//! alone. Both are idempotent, nothing to do comes back as a no-op. The tray
+//! menu and `pause_all_shortcut` (a toggle) run them through [`spawn`].
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::ai_status;
+use crate::error_catalog::{CatalogError, ErrorCode};
+use crate::settings::Settings;
+use crate::sidecar;
+use crate::signals::{self, SignalEntry};
+use crate::AppState;
+
+/// File name of the pause record inside the app data dir
+pub const PAUSE_FILE: &str = "ai_pause.json";
+
+const PAUSED: &str = "paused";
+
+/// What `ai_pause_all` stopped
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PauseRecord {
+    pub paused_at: DateTime<Utc>,
+    /// AI status before the pause (`executing`, `idle`, ...)
+    pub status: String,
+    /// Tasks the AI was working on
+    pub tasks: Vec<String>,
+    /// Signal history entry of the pause
+    pub signal_id: u64,
+}
+
+/// Outcome of a pause or resume
+#[derive(Debug, Clone, PartialEq)]
+pub struct PauseOutcome {
+    /// Nothing to do (already paused, nothing paused, resumed by hand)
+    pub no_op: bool,
+    /// Pause: the record in effect; resume: the record resumed or dropped
+    pub record: Option<PauseRecord>,
+    /// The signal sent
+    pub entry: Option<SignalEntry>,
+}
+
+/// The pause record, backed by the data dir file
+#[derive(Debug, Default)]
+pub struct PauseStore {
+    path: Option<PathBuf>,
+    record: Option<PauseRecord>,
+}
+
+impl PauseStore {
+    /// Load the record at `path` (missing or unreadable file -> none)
+    pub fn load(path: PathBuf) -> Self {
+        let record = sidecar::read_json(&path).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable pause record: {:#}", e);
+            None
+        });
+        Self {
+            path: Some(path),
+            record,
+        }
+    }
+
+    pub fn get(&self) -> Option<&PauseRecord> {
+        self.record.as_ref()
+    }
+
+    fn replace(&mut self, record: Option<PauseRecord>) -> Option<PauseRecord> {
+        let previous = std::mem::replace(&mut self.record, record);
+        if let Some(path) = &self.path {
+            if let Err(e) = sidecar::write_json(path, &self.record) {
+                log::warn!("Failed to persist pause record: {:#}", e);
+            }
+        }
+        previous
+    }
+}
+
+/// AI status and the tasks in progress of an `ai_status` payload
+fn prior(status: &Value) -> (String, Vec<String>) {
+    let state = status
+        .get("status")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown")
+        .to_string();
+    // `TASK-001.s:0` -> `TASK-001`
+    let tasks = status
+        .pointer("/current/path")
+        .and_then(Value::as_str)
+        .and_then(|p| p.split('.').next())
+        .filter(|id| !id.is_empty())
+        .map(|id| vec![id.to_string()])
+        .unwrap_or_default();
+    (state, tasks)
+}
+
+fn not_delivered(entry: &SignalEntry, error: Option<CatalogError>) -> CatalogError {
+    error.unwrap_or_else(|| {
+        let fallback = format!("The {} signal was not delivered", entry.signal);
+        CatalogError::from_envelope(entry.response.get("error"), &fallback)
+    })
+}
+
+/// Pause the AI unless this app already did, or it is paused anyway
+pub async fn pause_all(state: &AppState) -> Result<PauseOutcome, CatalogError> {
+    let mut store = state.ai_pause.lock().await;
+    if let Some(record) = store.get() {
+        return Ok(PauseOutcome {
+            no_op: true,
+            record: Some(record.clone()),
+            entry: None,
+        });
+    }
+    let status = ai_status::fetch(&state.bridge)
+        .await
+        .map_err(|e| CatalogError::from_anyhow(&e))?;
+    let (status, tasks) = prior(&status);
+    if status == PAUSED {
+        // Paused by someone else: not ours to resume
+        return Ok(PauseOutcome {
+            no_op: true,
+            record: None,
+            entry: None,
+        });
+    }
+    let (entry, error) = signals::send(state, "pause", "").await;
+    if !entry.delivered {
+        return Err(not_delivered(&entry, error));
+    }
+    let record = PauseRecord {
+        paused_at: entry.sent_at,
+        status,
+        tasks,
+        signal_id: entry.id,
+    };
+    store.replace(Some(record.clone()));
+    log::info!(
+        "Paused all AI activity ({} was {})",
+        record.tasks.join(", "),
+        record.status
+    );
+    Ok(PauseOutcome {
+        no_op: false,
+        record: Some(record),
+        entry: Some(entry),
+    })
+}
+
+/// Resume what [`pause_all`] paused, if it is still paused
+pub async fn resume_all(state: &AppState) -> Result<PauseOutcome, CatalogError> {
+    let mut store = state.ai_pause.lock().await;
+    let Some(record) = store.get().cloned() else {
+        return Ok(PauseOutcome {
+            no_op: true,
+            record: None,
+            entry: None,
+        });
+    };
+    // Unknown status: try anyway, the signal reports its own failure
+    let resumed_by_hand = ai_status::fetch(&state.bridge)
+        .await
+        .is_ok_and(|status| prior(&status).0 != PAUSED);
+    if resumed_by_hand {
+        store.replace(None);
+        return Ok(PauseOutcome {
+            no_op: true,
+            record: Some(record),
+            entry: None,
+        });
+    }
+    let (entry, error) = signals::send(state, "resume", "").await;
+    if !entry.delivered {
+        return Err(not_delivered(&entry, error));
+    }
+    store.replace(None);
+    log::info!("Resumed AI activity paused at {}", record.paused_at);
+    Ok(PauseOutcome {
+        no_op: false,
+        record: Some(record),
+        entry: Some(entry),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseAction {
+    Pause,
+    Resume,
+    /// Resume when this app paused the AI, pause otherwise
+    Toggle,
+}
+
+/// Run `action` in the background (tray, shortcut)
+pub fn spawn(app: &AppHandle, action: PauseAction) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app.try_state::<AppState>() else {
+            return;
+        };
+        let resume = match action {
+            PauseAction::Pause => false,
+            PauseAction::Resume => true,
+            PauseAction::Toggle => state.ai_pause.lock().await.get().is_some(),
+        };
+        let outcome = match resume {
+            true => resume_all(&state).await,
+            false => pause_all(&state).await,
+        };
+        if let Err(e) = outcome {
+            log::warn!("{:?} all failed: {}", action, e.render());
+        }
+    });
+}
+
+/// Register `pause_all_shortcut` (unset or empty: none); called after the
+/// quick-add shortcut, which clears all registrations first
+pub fn register_shortcut(app: &AppHandle, settings: &Settings) -> Option<CatalogError> {
+    let shortcut = settings
+        .pause_all_shortcut
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())?;
+    let registered = shortcut
+        .parse::<Shortcut>()
+        .map_err(|e| {
+            CatalogError::new(ErrorCode::ShortcutInvalid)
+                .with("shortcut", shortcut)
+                .with("detail", e.to_string())
+        })
+        .and_then(|parsed| {
+            app.global_shortcut()
+                .on_shortcut(parsed, |app, _, event| {
+                    if event.state == ShortcutState::Pressed {
+                        spawn(app, PauseAction::Toggle);
+                    }
+                })
+                .map_err(|e| {
+                    CatalogError::new(ErrorCode::ShortcutUnavailable)
+                        .with("shortcut", shortcut)
+                        .with("detail", e.to_string())
+                })
+        });
+    match registered {
+        Ok(()) => {
+            log::info!("Pause all shortcut: {}", shortcut);
+            None
+        }
+        Err(e) => {
+            log::warn!("Pause all shortcut: {}", e.render());
+            Some(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_prior_state() {
+        let busy = json!({
+            "status": "executing",
+            "current": { "op": "tasks_edit", "step": "s1", "path": "TASK-004.s:1" }
+        });
+        assert_eq!(
+            prior(&busy),
+            ("executing".to_string(), vec!["TASK-004".to_string()])
+        );
+        let idle = json!({ "status": "idle", "current": null });
+        assert_eq!(prior(&idle), ("idle".to_string(), vec![]));
+        assert_eq!(prior(&json!({})).0, "unknown");
+    }
+
+    #[test]
+    fn test_store_persists() {
+        let dir = std::env::temp_dir().join(format!("apply-task-pause-{}", std::process::id()));
+        let path = dir.join(PAUSE_FILE);
+        let _ = std::fs::remove_dir_all(&dir);
+        let record = PauseRecord {
+            paused_at: Utc::now(),
+            status: "executing".into(),
+            tasks: vec!["TASK-001".into()],
+            signal_id: 3,
+        };
+        let mut store = PauseStore::load(path.clone());
+        assert!(store.get().is_none());
+        store.replace(Some(record.clone()));
+        assert_eq!(PauseStore::load(path.clone()).get(), Some(&record));
+        assert_eq!(store.replace(None), Some(record));
+        assert!(PauseStore::load(path).get().is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
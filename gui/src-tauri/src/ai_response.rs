@@ -0,0 +1,385 @@
+//! `AIResponse` envelope returned by `ai_intent`
+//!
+//! Mirrors `intent_api.AIResponse.to_dict()`. Known fields are typed; anything
+//! else passes through untouched via `rest`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::duplicates::SimilarTask;
+use crate::error_catalog::{CatalogError, ErrorCode};
+use crate::intent_result::IntentResult;
+use crate::python::{BridgeError, ToolCallError};
+use crate::rate_limit::RateLimitError;
+use crate::read_only::ReadOnlyError;
+use crate::suggestions::{self, SuggestionError};
+use crate::versions::VERSION_CHECK_HINT;
+
+/// Keys that may hold suggestion lists
+const SUGGESTION_KEYS: [&str; 2] = ["suggestions", "next_steps"];
+/// Nested objects searched (one level deep) for suggestion lists
+const SUGGESTION_CONTAINERS: [&str; 2] = ["result", "data"];
+
+/// Suggestion the UI can run (`execute_suggestion`) or, without an
+/// intent, only show
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuggestionAction {
+    pub label: String,
+    pub intent: Option<String>,
+    pub params: Option<Value>,
+}
+
+impl SuggestionAction {
+    /// Accept `"text"` (see [`suggestions::recognize`]) or
+    /// `{text|label|reason, intent|action, params}`
+    fn from_value(value: &Value, default_task: Option<&str>) -> Option<Self> {
+        match value {
+            Value::String(text) => {
+                let text = text.trim();
+                if text.is_empty() {
+                    return None;
+                }
+                let (intent, params) = suggestions::recognize(text, default_task).unzip();
+                Some(Self {
+                    label: text.to_string(),
+                    intent,
+                    params,
+                })
+            }
+            Value::Object(obj) => {
+                let field = |keys: &[&str]| {
+                    keys.iter()
+                        .find_map(|k| obj.get(*k).and_then(|v| v.as_str()))
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(String::from)
+                };
+                let intent = field(&["intent", "action"]);
+                let label = field(&["text", "label", "reason"]).or_else(|| intent.clone())?;
+                let params = obj.get("params").filter(|p| !p.is_null()).cloned();
+                Some(Self {
+                    label,
+                    intent,
+                    params,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Canonical AI intent response
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AIResponse {
+    pub success: bool,
+    pub intent: String,
+    pub result: Value,
+    /// Suggestions exactly as the backend sent them (consumed by the frontend today)
+    pub suggestions: Vec<Value>,
+    /// Suggestions gathered from every known location, as actions
+    pub suggestion_items: Vec<SuggestionAction>,
+    pub error: Option<Value>,
+    /// MCP tool that actually ran (set by `ai_intent`)
+    pub resolved_tool: Option<String>,
+    /// `result` parsed for the tool that ran (`ai_intent`, on success)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typed: Option<IntentResult>,
+    /// Not sent: held in the offline mutation queue for replay
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub queued: bool,
+    /// warnings, context, meta, timestamp, summary, ...
+    #[serde(flatten)]
+    pub rest: Map<String, Value>,
+}
+
+impl AIResponse {
+    /// Parse a backend envelope and normalize its suggestions
+    pub fn from_value(value: Value) -> Self {
+        let suggestion_items = extract_suggestions(&value);
+        let mut response = match value {
+            Value::Object(_) => serde_json::from_value(value.clone()).unwrap_or_else(|_| Self {
+                success: value
+                    .get("success")
+                    .and_then(|s| s.as_bool())
+                    .unwrap_or(false),
+                result: value,
+                ..Self::default()
+            }),
+            other => Self {
+                success: true,
+                result: other,
+                ..Self::default()
+            },
+        };
+        response.suggestion_items = suggestion_items;
+        response
+    }
+
+    /// Envelope for failures that never reached the backend
+    pub fn bridge_error(intent: &str, message: String) -> Self {
+        Self::local_error(intent, "BRIDGE_ERROR", message)
+    }
+
+    /// Envelope for a failed bridge call (`TIMEOUT` carries `waited_ms`;
+    /// `TOOL_MISSING` suggests `check_backend_version`; a JSON-RPC error's
+    /// `data` is kept)
+    pub fn from_bridge_error(intent: &str, err: &anyhow::Error) -> Self {
+        if let Some(tool) = err
+            .downcast_ref::<ToolCallError>()
+            .and_then(ToolCallError::missing_tool)
+        {
+            let mut response = Self::local_error(
+                intent,
+                "TOOL_MISSING",
+                format!("The backend has no {} tool", tool),
+            );
+            response.suggestions = vec![json!(VERSION_CHECK_HINT)];
+            response.suggestion_items = vec![SuggestionAction {
+                label: VERSION_CHECK_HINT.to_string(),
+                intent: None,
+                params: None,
+            }];
+            return response;
+        }
+        match err.downcast_ref::<BridgeError>() {
+            Some(BridgeError::Timeout { waited_ms, .. }) => {
+                let mut response = Self::local_error(intent, "TIMEOUT", err.to_string());
+                if let Some(error) = response.error.as_mut() {
+                    error["waited_ms"] = json!(waited_ms);
+                }
+                response
+            }
+            _ => {
+                let mut response = Self::bridge_error(intent, err.to_string());
+                let data = err
+                    .downcast_ref::<ToolCallError>()
+                    .and_then(|e| e.data.clone());
+                if let (Some(error), Some(data)) = (response.error.as_mut(), data) {
+                    error["data"] = data;
+                }
+                response
+            }
+        }
+    }
+
+    /// Envelope for a mutation held in the offline queue
+    pub fn queued(intent: &str, message: String) -> Self {
+        let mut response = Self::local_error(intent, "QUEUED", message);
+        response.queued = true;
+        response
+    }
+
+    /// Refused creation: tasks with similar titles exist (listed under
+    /// `possible_duplicates`)
+    pub fn possible_duplicates(intent: &str, duplicates: &[SimilarTask]) -> Self {
+        let ids: Vec<&str> = duplicates.iter().map(|d| d.id.as_str()).collect();
+        let mut response = Self::local_error(
+            intent,
+            "POSSIBLE_DUPLICATE",
+            format!(
+                "Similar tasks exist: {} (pass allow_duplicate to create it anyway)",
+                ids.join(", ")
+            ),
+        );
+        response
+            .rest
+            .insert("possible_duplicates".to_string(), json!(duplicates));
+        response
+    }
+
+    /// Refused mutation: read-only mode is on
+    pub fn read_only(intent: &str) -> Self {
+        Self::local_error(intent, "READ_ONLY_MODE", ReadOnlyError.to_string())
+    }
+
+    /// Refused destructive tool: deletes go through `tasks_delete`, which
+    /// asks for confirmation
+    pub fn confirmation_required(intent: &str, tool: &str) -> Self {
+        let error = CatalogError::new(ErrorCode::ConfirmationRequired).with("tool", tool);
+        Self::local_error(intent, "CONFIRMATION_REQUIRED", error.render())
+    }
+
+    /// Refused by the limiter; `error.retry_after_ms` says when to retry
+    pub fn rate_limited(intent: &str, error: &RateLimitError) -> Self {
+        let mut response = Self::local_error(intent, "RATE_LIMITED", error.to_string());
+        if let Some(Value::Object(obj)) = response.error.as_mut() {
+            obj.insert("retry_after_ms".to_string(), json!(error.retry_after_ms));
+        }
+        response
+    }
+
+    /// Refused `execute_suggestion`: the action can't be run
+    pub fn invalid_suggestion(intent: &str, error: &SuggestionError) -> Self {
+        Self::local_error(intent, "VALIDATION_SUGGESTION_ACTION", error.to_string())
+    }
+
+    /// Envelope for an intent that resolves to no tool
+    pub fn unknown_intent(intent: &str) -> Self {
+        Self::local_error(
+            intent,
+            "UNKNOWN_INTENT",
+            format!("Unknown intent: {}", intent),
+        )
+    }
+
+    fn local_error(intent: &str, code: &str, message: String) -> Self {
+        let mut rest = Map::new();
+        rest.insert("warnings".to_string(), json!([]));
+        rest.insert("context".to_string(), json!({}));
+        rest.insert("meta".to_string(), json!({}));
+        rest.insert("timestamp".to_string(), json!(""));
+
+        Self {
+            success: false,
+            intent: intent.to_string(),
+            result: json!({}),
+            suggestions: Vec::new(),
+            suggestion_items: Vec::new(),
+            error: Some(json!({ "code": code, "message": message })),
+            resolved_tool: None,
+            typed: None,
+            queued: false,
+            rest,
+        }
+    }
+}
+
+/// Collect suggestions from the top level, then one level deep under common
+/// containers (plain ones default to the response's task)
+pub fn extract_suggestions(value: &Value) -> Vec<SuggestionAction> {
+    let mut out: Vec<SuggestionAction> = Vec::new();
+    let default_task = value.pointer("/context/task_id").and_then(Value::as_str);
+
+    let mut scopes = vec![value];
+    for container in SUGGESTION_CONTAINERS {
+        if let Some(nested) = value.get(container).filter(|v| v.is_object()) {
+            scopes.push(nested);
+        }
+    }
+
+    for scope in scopes {
+        for key in SUGGESTION_KEYS {
+            let Some(items) = scope.get(key).and_then(|v| v.as_array()) else {
+                continue;
+            };
+            let parsed = items
+                .iter()
+                .filter_map(|item| SuggestionAction::from_value(item, default_task));
+            for suggestion in parsed {
+                if !out.contains(&suggestion) {
+                    out.push(suggestion);
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Texts = Vec<(String, Option<String>)>;
+
+    fn texts(value: Value) -> Texts {
+        extract_suggestions(&value)
+            .into_iter()
+            .map(|s| (s.label, s.intent))
+            .collect()
+    }
+
+    #[test]
+    fn test_extract_suggestion_shapes() {
+        let s = |t: &str, i: Option<&str>| (t.to_string(), i.map(String::from));
+        let cases: Vec<(Value, Texts)> = vec![
+            (json!({}), vec![]),
+            (
+                json!({"suggestions": ["Run tests"]}),
+                vec![s("Run tests", None)],
+            ),
+            (
+                json!({"suggestions": [{"action": "verify", "reason": "Confirm step", "params": {"task": "T-1"}}]}),
+                vec![s("Confirm step", Some("verify"))],
+            ),
+            (
+                json!({"result": {"next_steps": ["Write docs", {"text": "Close", "intent": "done"}]}}),
+                vec![s("Write docs", None), s("Close", Some("done"))],
+            ),
+            (
+                json!({"data": {"suggestions": [{"label": "Split", "intent": "decompose"}]}}),
+                vec![s("Split", Some("decompose"))],
+            ),
+            (
+                json!({"suggestions": ["Same"], "result": {"suggestions": ["Same", "", 3, null]}}),
+                vec![s("Same", None)],
+            ),
+            (
+                json!({"result": {"deep": {"suggestions": ["ignored"]}}}),
+                vec![],
+            ),
+            (json!({"suggestions": {"not": "a list"}}), vec![]),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(texts(input.clone()), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_from_value_keeps_legacy_and_extra_fields() {
+        let response = AIResponse::from_value(json!({
+            "success": true,
+            "intent": "context",
+            "result": {"next_steps": ["Do it"]},
+            "suggestions": [{"action": "verify", "target": "tasks_verify", "reason": "Check"}],
+            "warnings": [],
+            "timestamp": "now"
+        }));
+
+        assert!(response.success);
+        assert_eq!(response.suggestions.len(), 1);
+        assert_eq!(response.suggestion_items.len(), 2);
+        assert_eq!(response.suggestion_items[0].params, None);
+
+        let out = serde_json::to_value(&response).unwrap();
+        assert_eq!(out["timestamp"], "now");
+        assert_eq!(out["suggestions"][0]["target"], "tasks_verify");
+        assert_eq!(out["suggestion_items"][1]["label"], "Do it");
+    }
+
+    #[test]
+    fn test_bridge_error_shape() {
+        let out = serde_json::to_value(AIResponse::bridge_error("list", "boom".into())).unwrap();
+        assert_eq!(out["success"], false);
+        assert_eq!(out["error"]["code"], "BRIDGE_ERROR");
+        assert_eq!(out["error"]["message"], "boom");
+        assert_eq!(out["timestamp"], "");
+    }
+
+    #[test]
+    fn test_missing_tool_suggests_version_check() {
+        let err = anyhow::Error::new(ToolCallError {
+            code: -32602,
+            message: "Unknown tool: tasks_set_due".into(),
+            data: None,
+        });
+        let response = AIResponse::from_bridge_error("due", &err);
+        assert_eq!(response.error.unwrap()["code"], "TOOL_MISSING");
+        assert_eq!(response.suggestions, vec![json!(VERSION_CHECK_HINT)]);
+        assert!(response.suggestion_items[0]
+            .label
+            .contains("check_backend_version"));
+
+        let other = anyhow::Error::new(ToolCallError {
+            code: -32603,
+            message: "boom".into(),
+            data: Some(json!({"checkpoint": "tests"})),
+        });
+        let response = AIResponse::from_bridge_error("due", &other);
+        let error = response.error.unwrap();
+        assert_eq!(error["code"], "BRIDGE_ERROR");
+        assert_eq!(error["data"], json!({"checkpoint": "tests"}));
+        assert!(response.suggestions.is_empty());
+    }
+}
@@ -0,0 +1,234 @@
+//! AI → user signals
+//!
+//! The AI can raise its own signals (needs input, blocked on a decision) in
+//! the `tasks_ai_status` payload, as a list under `ai_signals`, `signals` or
+//! `questions`. The status poller extracts them with [`extract`], keeps the
+//! pending ones in an [`AiSignalInbox`] and emits `ai-signal` for each one it
+//! hasn't seen yet. Entries without an id get one from a hash of their
+//! content, so an unchanged question keeps its id from poll to poll.
+//! `ai_signal_respond` answers a pending signal through `tasks_send_signal`.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::sidecar;
+
+/// Event emitted for each newly raised AI signal
+pub const AI_SIGNAL_EVENT: &str = "ai-signal";
+
+/// Status keys that may hold AI signals (and the type of an untyped entry)
+const SOURCES: [(&str, &str); 3] = [
+    ("ai_signals", "needs_input"),
+    ("signals", "needs_input"),
+    ("questions", "question"),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AiSignal {
+    pub id: String,
+    /// Signal type (`needs_input`, `question`, `blocked`, ...)
+    pub kind: String,
+    pub message: String,
+    pub task_id: Option<String>,
+    /// Step path, when the signal names one
+    pub path: Option<String>,
+    /// Sent back with the answer (the id unless the backend gave its own)
+    pub correlation_id: String,
+}
+
+fn text(item: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| match item.get(*key)? {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    })
+}
+
+fn signal(item: &Value, default_kind: &str, current_task: Option<&str>) -> Option<AiSignal> {
+    if let Value::String(message) = item {
+        return signal(
+            &serde_json::json!({ "message": message }),
+            default_kind,
+            current_task,
+        );
+    }
+    if !item.is_object() || ["answered", "resolved"].iter().any(|k| item[*k] == true) {
+        return None;
+    }
+    let message = text(item, &["message", "text", "question"])?;
+    let kind = text(item, &["type", "kind"]).unwrap_or_else(|| default_kind.to_string());
+    let path = text(item, &["path"]);
+    // `TASK-001.s:0` names its task
+    let task_id = text(item, &["task", "task_id"])
+        .or_else(|| {
+            path.as_deref()
+                .and_then(|p| p.split_once('.'))
+                .map(|(task, _)| task.to_string())
+        })
+        .or_else(|| current_task.map(String::from));
+    let id = text(item, &["id", "signal_id"]).unwrap_or_else(|| {
+        let content = format!(
+            "{}\0{}\0{}",
+            kind,
+            message,
+            task_id.as_deref().unwrap_or("")
+        );
+        format!("h-{:016x}", sidecar::fnv1a(content.as_bytes()))
+    });
+    let correlation_id = text(item, &["correlation_id"]).unwrap_or_else(|| id.clone());
+    Some(AiSignal {
+        id,
+        kind,
+        message,
+        task_id,
+        path,
+        correlation_id,
+    })
+}
+
+/// Pending AI signals of a status payload, in payload order (the first of
+/// duplicate ids wins)
+pub fn extract(status: &Value) -> Vec<AiSignal> {
+    // The AI's current task relates untagged signals
+    let current_task = status
+        .pointer("/current/path")
+        .and_then(Value::as_str)
+        .and_then(|p| p.split('.').next())
+        .filter(|id| !id.is_empty());
+    let mut ids = HashSet::new();
+    SOURCES
+        .iter()
+        .filter_map(|(key, kind)| Some((status.get(*key)?.as_array()?, *kind)))
+        .flat_map(|(items, kind)| {
+            items
+                .iter()
+                .filter_map(move |item| signal(item, kind, current_task))
+        })
+        .filter(|s| ids.insert(s.id.clone()))
+        .collect()
+}
+
+/// Signals the AI is waiting on
+#[derive(Debug, Default)]
+pub struct AiSignalInbox {
+    pending: Vec<AiSignal>,
+    /// Answered ids the payload still lists (the AI hasn't picked them up)
+    answered: HashSet<String>,
+}
+
+impl AiSignalInbox {
+    /// Take in the signals of a status poll, returning the new ones
+    pub fn observe(&mut self, signals: Vec<AiSignal>) -> Vec<AiSignal> {
+        let known: HashSet<&str> = self
+            .pending
+            .iter()
+            .map(|s| s.id.as_str())
+            .chain(self.answered.iter().map(String::as_str))
+            .collect();
+        let new: Vec<AiSignal> = signals
+            .iter()
+            .filter(|s| !known.contains(s.id.as_str()))
+            .cloned()
+            .collect();
+        self.answered
+            .retain(|id| signals.iter().any(|s| &s.id == id));
+        self.pending = signals
+            .into_iter()
+            .filter(|s| !self.answered.contains(&s.id))
+            .collect();
+        new
+    }
+
+    pub fn pending(&self) -> &[AiSignal] {
+        &self.pending
+    }
+
+    pub fn get(&self, id: &str) -> Option<&AiSignal> {
+        self.pending.iter().find(|s| s.id == id)
+    }
+
+    /// Mark `id` answered; it won't be raised again while the AI lists it
+    pub fn answer(&mut self, id: &str) -> Option<AiSignal> {
+        let index = self.pending.iter().position(|s| s.id == id)?;
+        self.answered.insert(id.to_string());
+        Some(self.pending.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_extract_shapes_and_hash_ids() {
+        let status = json!({
+            "status": "waiting",
+            "current": { "op": "verify", "path": "TASK-003.s:1" },
+            "signal": { "pending": "none", "message": "" },
+            "ai_signals": [
+                { "id": 7, "type": "blocked", "text": "Pick a database", "correlation_id": "c-7" },
+                { "message": "Which step next?", "path": "TASK-009.s:0" },
+                { "id": "done", "message": "old", "resolved": true },
+                { "id": "empty", "message": " " }
+            ],
+            "questions": ["Ship it?"]
+        });
+        let signals = extract(&status);
+        assert_eq!(signals.len(), 3);
+        assert_eq!(
+            signals[0],
+            AiSignal {
+                id: "7".into(),
+                kind: "blocked".into(),
+                message: "Pick a database".into(),
+                task_id: Some("TASK-003".into()),
+                path: None,
+                correlation_id: "c-7".into(),
+            }
+        );
+        assert_eq!(signals[1].kind, "needs_input");
+        assert_eq!(signals[1].task_id.as_deref(), Some("TASK-009"));
+        assert!(signals[1].id.starts_with("h-"));
+        assert_eq!(signals[1].correlation_id, signals[1].id);
+        assert_eq!(signals[2].kind, "question");
+        assert_eq!(signals[2].message, "Ship it?");
+
+        // Same content, same id; other content, other id
+        assert_eq!(extract(&status)[1].id, signals[1].id);
+        let other = json!({ "questions": ["Ship it now?"] });
+        assert_ne!(extract(&other)[0].id, signals[2].id);
+
+        // The user -> AI `signal` object isn't an AI signal
+        assert!(extract(&json!({ "signal": { "pending": "pause" } })).is_empty());
+    }
+
+    #[test]
+    fn test_inbox_dedupes_and_forgets() {
+        let signals = |ids: &[&str]| -> Vec<AiSignal> {
+            let items: Vec<Value> = ids
+                .iter()
+                .map(|id| json!({ "id": id, "message": format!("q {}", id) }))
+                .collect();
+            extract(&json!({ "ai_signals": items }))
+        };
+        let ids = |signals: &[AiSignal]| -> Vec<String> {
+            signals.iter().map(|s| s.id.clone()).collect()
+        };
+        let mut inbox = AiSignalInbox::default();
+        assert_eq!(ids(&inbox.observe(signals(&["a", "b"]))), ["a", "b"]);
+        assert!(inbox.observe(signals(&["a", "b"])).is_empty());
+        assert_eq!(ids(&inbox.observe(signals(&["b", "c"]))), ["c"]);
+
+        // Answered signals stay quiet until the AI drops them
+        assert_eq!(inbox.answer("b").unwrap().message, "q b");
+        assert!(inbox.answer("b").is_none());
+        assert!(inbox.observe(signals(&["b", "c"])).is_empty());
+        assert_eq!(ids(inbox.pending()), ["c"]);
+        inbox.observe(signals(&["c"]));
+        assert_eq!(ids(&inbox.observe(signals(&["b", "c"]))), ["b"]);
+        assert!(inbox.get("b").is_some());
+    }
+}
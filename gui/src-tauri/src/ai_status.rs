@@ -0,0 +1,275 @@
+//! AI status poller
+//!
+//! Polls `tasks_ai_status` inside Rust on behalf of subscribed windows and
+//! emits `ai-status-changed` to them only when the payload actually changes.
+//! One polling task is shared by all subscribers; it pauses while the
+//! backend process is down (it never spawns the backend itself).
+//! Each poll also acknowledges sent signals the backend has consumed and
+//! emits `ai-signal` for signals the AI raised since the last one;
+//! status transitions and backend crashes are passed to the notifier, and
+//! a changed status drops the cached project context.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use chrono::Utc;
+use serde_json::{json, Value};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::ai_signals::{self, AI_SIGNAL_EVENT};
+use crate::notifications;
+use crate::python::PythonBridge;
+use crate::signals::SIGNAL_ACK_EVENT;
+use crate::tray;
+use crate::AppState;
+
+/// Event emitted when the AI status payload changes
+pub const AI_STATUS_EVENT: &str = "ai-status-changed";
+/// Lower bound for the polling interval
+pub const MIN_INTERVAL_MS: u64 = 500;
+
+const STATUS_TOOL: &str = "tasks_ai_status";
+
+#[derive(Default)]
+struct PollerInner {
+    /// window label -> requested interval
+    subscribers: HashMap<String, u64>,
+    task: Option<JoinHandle<()>>,
+}
+
+/// Shared AI status poller (one per app)
+#[derive(Default)]
+pub struct AiStatusPoller {
+    inner: Arc<Mutex<PollerInner>>,
+    interval_ms: Arc<AtomicU64>,
+    latest: Arc<RwLock<Option<Value>>>,
+}
+
+impl AiStatusPoller {
+    /// Last status payload seen by the poller
+    pub fn latest(&self) -> Option<Value> {
+        self.latest.read().ok().and_then(|l| l.clone())
+    }
+
+    /// Register `label` and make sure the polling task runs.
+    ///
+    /// Returns the effective interval (fastest requested, clamped to the minimum).
+    pub fn subscribe(
+        &self,
+        app: &AppHandle,
+        bridge: PythonBridge,
+        label: &str,
+        interval_ms: u64,
+    ) -> u64 {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner
+            .subscribers
+            .insert(label.to_string(), interval_ms.max(MIN_INTERVAL_MS));
+
+        let effective = effective_interval(&inner.subscribers);
+        self.interval_ms.store(effective, Ordering::Relaxed);
+
+        if inner.task.is_none() {
+            log::info!("Starting AI status poller ({} ms)", effective);
+            inner.task = Some(tauri::async_runtime::spawn(poll_loop(
+                app.clone(),
+                bridge,
+                self.inner.clone(),
+                self.interval_ms.clone(),
+                self.latest.clone(),
+            )));
+        }
+        effective
+    }
+
+    /// Remove `label`; stops the polling task when nobody is left.
+    ///
+    /// Returns the number of remaining subscribers.
+    pub fn unsubscribe(&self, label: &str) -> usize {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.subscribers.remove(label);
+
+        if inner.subscribers.is_empty() {
+            if let Some(task) = inner.task.take() {
+                log::info!("Stopping AI status poller (no subscribers)");
+                task.abort();
+            }
+        } else {
+            let effective = effective_interval(&inner.subscribers);
+            self.interval_ms.store(effective, Ordering::Relaxed);
+        }
+        inner.subscribers.len()
+    }
+
+    /// Drop every subscriber and stop polling (app exit)
+    pub fn stop(&self) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.subscribers.clear();
+        if let Some(task) = inner.task.take() {
+            log::info!("Stopping AI status poller");
+            task.abort();
+        }
+    }
+}
+
+/// Windows to emit to
+fn subscriber_labels(inner: &Mutex<PollerInner>) -> Vec<String> {
+    let inner = inner.lock().unwrap_or_else(|e| e.into_inner());
+    inner.subscribers.keys().cloned().collect()
+}
+
+/// Emit `event` to each subscribed window
+fn emit_to_subscribers<S: serde::Serialize + Clone>(
+    app: &AppHandle,
+    inner: &Mutex<PollerInner>,
+    event: &str,
+    payload: &S,
+) {
+    for label in subscriber_labels(inner) {
+        if let Err(e) = app.emit_to(label.as_str(), event, payload.clone()) {
+            log::warn!("Failed to emit {} to {}: {}", event, label, e);
+        }
+    }
+}
+
+fn effective_interval(subscribers: &HashMap<String, u64>) -> u64 {
+    subscribers
+        .values()
+        .copied()
+        .min()
+        .unwrap_or(MIN_INTERVAL_MS)
+        .max(MIN_INTERVAL_MS)
+}
+
+/// Strip volatile envelope fields so identical statuses compare equal
+fn status_payload(response: Value) -> Value {
+    match response {
+        Value::Object(mut obj) => obj.remove("result").unwrap_or_else(|| {
+            obj.remove("timestamp");
+            Value::Object(obj)
+        }),
+        other => other,
+    }
+}
+
+/// One `tasks_ai_status` payload, outside the poller (spawns the backend
+/// if needed)
+pub async fn fetch(bridge: &PythonBridge) -> anyhow::Result<Value> {
+    Ok(status_payload(
+        bridge.call_tool(STATUS_TOOL, json!({})).await?,
+    ))
+}
+
+async fn poll_loop(
+    app: AppHandle,
+    bridge: PythonBridge,
+    inner: Arc<Mutex<PollerInner>>,
+    interval_ms: Arc<AtomicU64>,
+    latest: Arc<RwLock<Option<Value>>>,
+) {
+    let mut paused = false;
+    let mut last_error: Option<String> = None;
+
+    loop {
+        tokio::time::sleep(Duration::from_millis(interval_ms.load(Ordering::Relaxed))).await;
+
+        let polled_at = Utc::now();
+        let response = {
+            if !bridge.is_running().await {
+                if !paused {
+                    log::info!("AI status poller paused (backend not running)");
+                    paused = true;
+                    if let Some(status) = bridge.exit_status().await {
+                        notifications::backend_crashed(&app, &status.to_string());
+                    }
+                }
+                continue;
+            }
+            if paused {
+                log::info!("AI status poller resumed");
+                paused = false;
+            }
+            bridge.call_tool(STATUS_TOOL, json!({})).await
+        };
+
+        let payload = match response {
+            Ok(response) => {
+                last_error = None;
+                status_payload(response)
+            }
+            Err(e) => {
+                let message = e.to_string();
+                if last_error.as_deref() != Some(message.as_str()) {
+                    log::warn!("AI status poll failed: {}", message);
+                    last_error = Some(message);
+                }
+                continue;
+            }
+        };
+
+        if let Some(state) = app.try_state::<AppState>() {
+            let acked = state.signals.lock().await.acknowledge(&payload, polled_at);
+            if !acked.is_empty() {
+                emit_to_subscribers(&app, &inner, SIGNAL_ACK_EVENT, &acked);
+            }
+            let raised = state
+                .ai_signals
+                .lock()
+                .await
+                .observe(ai_signals::extract(&payload));
+            for signal in &raised {
+                emit_to_subscribers(&app, &inner, AI_SIGNAL_EVENT, signal);
+            }
+            notifications::ai_signals_raised(&app, &raised);
+        }
+
+        // Some(previous) when the payload changed
+        let changed = {
+            let mut latest = latest.write().unwrap_or_else(|e| e.into_inner());
+            (latest.as_ref() != Some(&payload)).then(|| latest.replace(payload.clone()))
+        };
+
+        if let Some(previous) = changed {
+            if let Some(state) = app.try_state::<AppState>() {
+                state.read_cache.lock().await.invalidate_context(None);
+            }
+            tray::update_status(&app, &payload);
+            notifications::ai_status_changed(&app, previous.as_ref(), &payload);
+            emit_to_subscribers(&app, &inner, AI_STATUS_EVENT, &payload);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effective_interval_uses_fastest_clamped() {
+        let mut subs = HashMap::new();
+        assert_eq!(effective_interval(&subs), MIN_INTERVAL_MS);
+        subs.insert("main".to_string(), 2000);
+        subs.insert("other".to_string(), 800);
+        assert_eq!(effective_interval(&subs), 800);
+        subs.insert("fast".to_string(), 100);
+        assert_eq!(effective_interval(&subs), MIN_INTERVAL_MS);
+    }
+
+    #[test]
+    fn test_status_payload_ignores_timestamp() {
+        let a = status_payload(
+            json!({"success": true, "result": {"state": "running"}, "timestamp": "1"}),
+        );
+        let b = status_payload(
+            json!({"success": true, "result": {"state": "running"}, "timestamp": "2"}),
+        );
+        assert_eq!(a, b);
+        assert_eq!(
+            status_payload(json!({"state": "idle", "timestamp": "1"})),
+            json!({"state": "idle"})
+        );
+    }
+}
@@ -0,0 +1,358 @@
+//! Audit log of mutating operations
+//!
+//! Every mutation sent to the backend (applied, queued or failed) is
+//! appended to `audit/<YYYY-MM>.jsonl` in the project sidecar dir: when, the
+//! OS user, the tool, the task ids, the parameters (secrets redacted, long
+//! strings cut) and the outcome. Unlike the journal it is never rewritten
+//! or cleared on a project switch; a new month starts a new file, and
+//! months older than `audit_retention_months` are deleted then. Writes run
+//! off the command's task: a failing write is logged, the mutation is not
+//! affected.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ai_response::AIResponse;
+use crate::crash;
+use crate::env_info;
+use crate::sidecar;
+use crate::AppState;
+
+/// Directory of the monthly files inside the project sidecar dir
+pub const AUDIT_DIR: &str = "audit";
+/// Months kept without an `audit_retention_months` setting
+pub const DEFAULT_RETENTION_MONTHS: u32 = 12;
+/// Longer parameter strings are cut to this many characters
+const MAX_PARAM_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Applied,
+    /// Waiting in the offline queue (its replay gets an entry of its own)
+    Queued,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub at: DateTime<Utc>,
+    /// OS account the app runs as
+    pub user: String,
+    /// Backend tool (`tasks_delete`)
+    pub command: String,
+    pub task_ids: Vec<String>,
+    /// Summarized arguments
+    pub params: Value,
+    pub outcome: Outcome,
+    pub error: Option<String>,
+}
+
+/// An operation to audit
+#[derive(Debug, Clone)]
+pub struct Audit {
+    command: String,
+    task_ids: Vec<String>,
+    params: Value,
+    outcome: Outcome,
+    error: Option<String>,
+}
+
+impl Audit {
+    /// An applied `command` on `task_ids`
+    pub fn new(command: &str, task_ids: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            command: command.to_string(),
+            task_ids: task_ids.into_iter().collect(),
+            params: Value::Null,
+            outcome: Outcome::Applied,
+            error: None,
+        }
+    }
+
+    pub fn params(mut self, params: &Value) -> Self {
+        self.params = summarize(params);
+        self
+    }
+
+    /// Outcome and error of a mutation `response`
+    pub fn response(mut self, response: &AIResponse) -> Self {
+        if response.queued {
+            self.outcome = Outcome::Queued;
+        } else if !response.success {
+            let message = response
+                .error
+                .as_ref()
+                .and_then(|e| e.get("message"))
+                .and_then(Value::as_str)
+                .unwrap_or("Backend rejected the mutation");
+            self = self.failed(message);
+        }
+        self
+    }
+
+    pub fn failed(mut self, error: impl ToString) -> Self {
+        self.outcome = Outcome::Failed;
+        self.error = Some(error.to_string());
+        self
+    }
+
+    fn entry(self, at: DateTime<Utc>) -> AuditEntry {
+        AuditEntry {
+            at,
+            user: os_user(),
+            command: self.command,
+            task_ids: self.task_ids,
+            params: self.params,
+            outcome: self.outcome,
+            error: self.error,
+        }
+    }
+}
+
+/// Serializes appends and pruning (kept in `AppState`)
+#[derive(Debug, Clone, Default)]
+pub struct AuditLog {
+    writes: Arc<Mutex<()>>,
+}
+
+fn os_user() -> String {
+    ["USER", "USERNAME", "LOGNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|user| !user.is_empty()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// `params` with secret-looking fields and the home directory redacted and
+/// long strings cut
+pub fn summarize(params: &Value) -> Value {
+    fn cut(value: &mut Value) {
+        match value {
+            Value::String(text) if text.chars().count() > MAX_PARAM_CHARS => {
+                *text = text.chars().take(MAX_PARAM_CHARS).chain(['…']).collect();
+            }
+            Value::Array(items) => items.iter_mut().for_each(cut),
+            Value::Object(obj) => obj.values_mut().for_each(cut),
+            _ => {}
+        }
+    }
+    let mut params = params.clone();
+    env_info::redact_value(&mut params, crash::home_dir().as_deref());
+    cut(&mut params);
+    params
+}
+
+/// Audit directory of the current project
+pub fn dir(state: &AppState) -> PathBuf {
+    state.project_dir().join(AUDIT_DIR)
+}
+
+/// Append `audit` in the background
+pub async fn record(state: &AppState, audit: Audit) {
+    let retention = state.settings.read().await.audit_retention_months();
+    let dir = dir(state);
+    let entry = audit.entry(Utc::now());
+    let writes = state.audit.writes.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let _guard = writes.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = append(&dir, &entry, retention) {
+            log::warn!("Failed to write audit entry ({}): {:#}", entry.command, e);
+        }
+    });
+}
+
+/// Months since year 0, for comparing file months
+fn month_index(at: DateTime<Utc>) -> i64 {
+    i64::from(at.year()) * 12 + i64::from(at.month0())
+}
+
+fn file_name(at: DateTime<Utc>) -> String {
+    format!("{:04}-{:02}.jsonl", at.year(), at.month())
+}
+
+/// Month index of an audit file name (`2024-05.jsonl`)
+fn file_month(name: &str) -> Option<i64> {
+    let (year, month) = name.strip_suffix(".jsonl")?.split_once('-')?;
+    let (year, month): (i64, i64) = (year.parse().ok()?, month.parse().ok()?);
+    (1..=12).contains(&month).then_some(year * 12 + month - 1)
+}
+
+/// Audit files in `dir`, oldest month first
+fn files(dir: &Path) -> Result<Vec<(i64, PathBuf)>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {:?}", dir))? {
+        let path = entry?.path();
+        let month = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(file_month);
+        if let Some(month) = month {
+            files.push((month, path));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Append `entry` to its month's file; starting a month removes the files
+/// past `retention_months` (0 keeps all, failing to remove one is logged)
+pub fn append(dir: &Path, entry: &AuditEntry, retention_months: u32) -> Result<()> {
+    let path = dir.join(file_name(entry.at));
+    if !path.exists() && retention_months > 0 {
+        let oldest_kept = month_index(entry.at) - i64::from(retention_months) + 1;
+        for (month, old) in files(dir)? {
+            if month < oldest_kept {
+                if let Err(e) = fs::remove_file(&old) {
+                    log::warn!("Failed to remove old audit log {:?}: {}", old, e);
+                }
+            }
+        }
+    }
+    sidecar::append_jsonl(&path, entry)
+}
+
+/// Entries between `from` and `to` (inclusive, either open), oldest first
+pub fn read(
+    dir: &Path,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<Vec<AuditEntry>> {
+    let mut entries = Vec::new();
+    for (month, path) in files(dir)? {
+        let outside = from.is_some_and(|from| month < month_index(from))
+            || to.is_some_and(|to| month > month_index(to));
+        if outside {
+            continue;
+        }
+        let mut read: Vec<AuditEntry> = sidecar::read_jsonl(&path)?;
+        read.retain(|e| from.is_none_or(|from| e.at >= from) && to.is_none_or(|to| e.at <= to));
+        entries.extend(read);
+    }
+    entries.sort_by_key(|e| e.at);
+    Ok(entries)
+}
+
+/// Newest first, touching `task_id` when given, at most `limit`
+pub fn query(
+    dir: &Path,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    task_id: Option<&str>,
+    limit: usize,
+) -> Result<Vec<AuditEntry>> {
+    let entries = read(dir, from, to)?;
+    Ok(entries
+        .into_iter()
+        .rev()
+        .filter(|e| task_id.is_none_or(|id| e.task_ids.iter().any(|t| t == id)))
+        .take(limit)
+        .collect())
+}
+
+/// Write every entry to `path` as JSON lines; returns how many
+pub fn export(dir: &Path, path: &Path) -> Result<usize> {
+    let entries = read(dir, None, None)?;
+    let mut text = String::new();
+    for entry in &entries {
+        text.push_str(&serde_json::to_string(entry)?);
+        text.push('\n');
+    }
+    fs::write(path, text).with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(entries.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("apply-task-audit-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn at(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_summarize_redacts_and_cuts() {
+        let params = json!({
+            "task": "TASK-087",
+            "api_token": "abc",
+            "note": "x".repeat(500),
+        });
+        let summary = summarize(&params);
+        assert_eq!(summary["task"], "TASK-087");
+        assert_eq!(summary["api_token"], env_info::REDACTED);
+        assert_eq!(
+            summary["note"].as_str().unwrap().chars().count(),
+            MAX_PARAM_CHARS + 1
+        );
+    }
+
+    #[test]
+    fn test_monthly_files_query_and_retention() {
+        let dir = temp_dir("rotate");
+        let entry = |when, task: &str, command: &str| {
+            Audit::new(command, [task.to_string()])
+                .params(&json!({ "task": task }))
+                .entry(when)
+        };
+        append(&dir, &entry(at(2024, 1, 5), "TASK-001", "tasks_edit"), 3).unwrap();
+        append(&dir, &entry(at(2024, 2, 5), "TASK-087", "tasks_delete"), 3).unwrap();
+        append(
+            &dir,
+            &entry(at(2024, 2, 6), "TASK-002", "tasks_complete"),
+            3,
+        )
+        .unwrap();
+        assert!(dir.join("2024-01.jsonl").exists());
+
+        let found = query(&dir, None, None, Some("TASK-087"), 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].command, "tasks_delete");
+        assert_eq!(found[0].outcome, Outcome::Applied);
+        let february = query(&dir, Some(at(2024, 2, 1)), None, None, 10).unwrap();
+        assert_eq!(february.len(), 2);
+        assert_eq!(february[0].task_ids, ["TASK-002"]);
+        assert_eq!(query(&dir, None, None, None, 1).unwrap().len(), 1);
+
+        // April keeps February..April (3 months): January goes
+        append(&dir, &entry(at(2024, 4, 1), "TASK-003", "tasks_edit"), 3).unwrap();
+        assert!(!dir.join("2024-01.jsonl").exists());
+        assert!(dir.join("2024-02.jsonl").exists());
+
+        let copy = dir.join("export.jsonl");
+        assert_eq!(export(&dir, &copy).unwrap(), 3);
+        let exported: Vec<AuditEntry> = sidecar::read_jsonl(&copy).unwrap();
+        assert_eq!(exported[0].task_ids, ["TASK-087"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_outcome_of_response() {
+        let failed = AIResponse::from_value(json!({
+            "success": false,
+            "error": { "code": "NOT_FOUND", "message": "No task TASK-9" },
+        }));
+        let audit = Audit::new("tasks_edit", None).response(&failed);
+        assert_eq!(audit.outcome, Outcome::Failed);
+        assert_eq!(audit.error.as_deref(), Some("No task TASK-9"));
+        assert_eq!(file_month("2024-05.jsonl"), Some(2024 * 12 + 4));
+        assert_eq!(file_month("2024-13.jsonl"), None);
+        assert_eq!(file_month("export.jsonl"), None);
+    }
+}
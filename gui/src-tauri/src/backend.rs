@@ -0,0 +1,201 @@
+//! Helpers over backend tool responses
+//!
+//! Every apply_task tool returns an `AIResponse` envelope
+//! (`{success, intent, result, error, ...}`); these helpers unwrap it for
+//! commands that post-process results in Rust.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::progress;
+use crate::python::PythonBridge;
+
+/// Failures read from backend responses
+#[derive(Debug, thiserror::Error)]
+pub enum BackendError {
+    /// `success: false`, with the tool's own error code if it sent one
+    #[error("{message}")]
+    Reported {
+        code: Option<String>,
+        message: String,
+    },
+    #[error("Task {0} not found")]
+    TaskNotFound(String),
+}
+
+/// Unwrap an `AIResponse` envelope into its `result`, turning failures into errors
+pub fn into_result(mut response: Value) -> Result<Value> {
+    let success = response
+        .get("success")
+        .and_then(|s| s.as_bool())
+        .unwrap_or(true);
+
+    if !success {
+        let error = response.get("error");
+        let message = error
+            .and_then(|e| e.get("message"))
+            .and_then(|m| m.as_str())
+            .unwrap_or("Backend reported failure");
+        return Err(BackendError::Reported {
+            code: error
+                .and_then(|e| e.get("code"))
+                .and_then(|c| c.as_str())
+                .map(String::from),
+            message: message.to_string(),
+        }
+        .into());
+    }
+
+    Ok(response
+        .get_mut("result")
+        .map(Value::take)
+        .unwrap_or_else(|| json!({})))
+}
+
+/// Status/domain/parent filters shared by `tasks_list` and the list refresher
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListFilters {
+    pub status: Option<String>,
+    pub domain: Option<String>,
+    pub parent: Option<String>,
+}
+
+impl ListFilters {
+    /// Extra `tasks_context` params (parent is filtered by the backend)
+    pub fn params(&self, compact: bool) -> Value {
+        let mut params = json!({ "compact": compact });
+        if let Some(parent) = &self.parent {
+            params["tasks_parent"] = json!(parent);
+        }
+        params
+    }
+
+    /// Drop tasks not matching status (code or name) and domain prefix
+    pub fn retain(&self, tasks: &mut Vec<Value>) {
+        let status = self
+            .status
+            .as_deref()
+            .unwrap_or_default()
+            .trim()
+            .to_uppercase();
+        let domain = self.domain.as_deref().unwrap_or_default().trim();
+        tasks.retain(|task| {
+            let task_status = task_str(task, "status_code")
+                .or_else(|| task_str(task, "status"))
+                .unwrap_or("");
+            (status.is_empty() || task_status.eq_ignore_ascii_case(&status))
+                && (domain.is_empty() || task_str(task, "domain").unwrap_or("").starts_with(domain))
+        });
+    }
+}
+
+/// Fetch all tasks (compact) via `tasks_context`
+pub async fn list_tasks(bridge: &PythonBridge, params: Option<Value>) -> Result<Vec<Value>> {
+    let mut args = json!({ "include_all": true, "compact": true });
+    if let (Some(Value::Object(extra)), Some(obj)) = (params, args.as_object_mut()) {
+        obj.extend(extra);
+    }
+
+    tasks_of(bridge.call_tool("tasks_context", args).await?)
+}
+
+/// Tasks (not plans) of a `tasks_context` envelope, moved out of it
+pub fn tasks_of(envelope: Value) -> Result<Vec<Value>> {
+    let mut result = into_result(envelope)?;
+    let Some(Value::Array(mut tasks)) = result.get_mut("tasks").map(Value::take) else {
+        return Ok(Vec::new());
+    };
+    tasks.retain(|t| t.get("kind").and_then(|k| k.as_str()).unwrap_or("task") == "task");
+    Ok(tasks)
+}
+
+/// Full task or plan payload via `tasks_resume` (with computed `progress`)
+pub async fn show_task(bridge: &PythonBridge, task_id: &str) -> Result<Value> {
+    let mut result = into_result(
+        bridge
+            .call_tool("tasks_resume", json!({ "task": task_id, "compact": false }))
+            .await?,
+    )?;
+    let key = match result.get("task") {
+        Some(_) => "task",
+        None => "plan",
+    };
+    let mut task = result
+        .get_mut(key)
+        .map(Value::take)
+        .filter(|t| t.is_object())
+        .ok_or_else(|| BackendError::TaskNotFound(task_id.to_string()))?;
+    progress::attach(&mut task);
+    Ok(task)
+}
+
+/// Whether `tool` is advertised and its input schema declares `field`
+pub fn tool_accepts(tools: &[Value], tool: &str, field: &str) -> bool {
+    tools
+        .iter()
+        .find(|t| t.get("name").and_then(|n| n.as_str()) == Some(tool))
+        .and_then(|t| t.pointer("/inputSchema/properties"))
+        .and_then(|p| p.as_object())
+        .is_some_and(|props| props.contains_key(field))
+}
+
+/// Whether `tool` is advertised at all
+pub fn has_tool(tools: &[Value], tool: &str) -> bool {
+    tools
+        .iter()
+        .any(|t| t.get("name").and_then(|n| n.as_str()) == Some(tool))
+}
+
+/// String field of a task payload
+pub fn task_str<'a>(task: &'a Value, key: &str) -> Option<&'a str> {
+    task.get(key).and_then(|v| v.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_result_unwraps_envelope() {
+        let ok = json!({"success": true, "result": {"tasks": []}});
+        assert_eq!(into_result(ok).unwrap(), json!({"tasks": []}));
+        assert_eq!(into_result(json!({"success": true})).unwrap(), json!({}));
+
+        let err = json!({"success": false, "error": {"code": "X", "message": "nope"}});
+        assert_eq!(into_result(err).unwrap_err().to_string(), "nope");
+    }
+
+    #[test]
+    fn test_tool_accepts_checks_schema() {
+        let tools = vec![json!({
+            "name": "tasks_edit",
+            "inputSchema": {"type": "object", "properties": {"task": {}, "due": {}}}
+        })];
+        assert!(tool_accepts(&tools, "tasks_edit", "due"));
+        assert!(!tool_accepts(&tools, "tasks_edit", "deadline"));
+        assert!(!tool_accepts(&tools, "tasks_create", "due"));
+        assert!(has_tool(&tools, "tasks_edit"));
+    }
+
+    #[test]
+    fn test_list_filters_status_and_domain() {
+        let filters = ListFilters {
+            status: Some(" active ".into()),
+            domain: Some("core".into()),
+            parent: None,
+        };
+        let mut tasks = vec![
+            json!({"id": "A", "status_code": "ACTIVE", "domain": "core/api"}),
+            json!({"id": "B", "status": "active", "domain": "gui"}),
+            json!({"id": "C", "status_code": "DONE", "domain": "core"}),
+        ];
+        filters.retain(&mut tasks);
+        assert_eq!(
+            tasks,
+            [json!({"id": "A", "status_code": "ACTIVE", "domain": "core/api"})]
+        );
+        assert_eq!(filters.params(true), json!({"compact": true}));
+    }
+}
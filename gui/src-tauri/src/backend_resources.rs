@@ -0,0 +1,481 @@
+//! Backend process resources
+//!
+//! While the backend is alive a background sampler reads its RSS and CPU
+//! every [`SAMPLE_INTERVAL`], refreshing only that one process. The last
+//! [`HISTORY_LIMIT`] samples and the peaks since app start come back with
+//! `bridge_metrics` and `bridge_status` and go into the support bundle.
+//! Crossing `backend_rss_warn_mb` emits `backend-resource-warning` once,
+//! again only after RSS was back under it. With `backend_rss_cap_mb` set,
+//! [`CAP_SAMPLES`] samples in a row over the cap stop the backend gracefully:
+//! `backend-killed-resource-limit` carries the numbers, the calls in flight
+//! fail with `BRIDGE_RESOURCE_LIMIT` and the next call spawns a fresh
+//! backend. When the backend exits the sampler waits for the next spawn;
+//! where sysinfo has no per-process stats it doesn't run at all and the
+//! usage says `supported: false`. So does a `container` or `ssh` backend:
+//! the local process is the docker or ssh client, not the backend, so it
+//! isn't sampled (and the cap can't stop it); `unavailable` says why.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::lifecycle::SHUTDOWN_GRACE;
+use crate::python::InstallMode;
+use crate::settings::Settings;
+use crate::AppState;
+
+/// Event emitted when the backend's RSS crosses the threshold
+pub const RESOURCE_WARNING_EVENT: &str = "backend-resource-warning";
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+/// Samples kept (five minutes)
+pub const HISTORY_LIMIT: usize = 60;
+/// Event emitted after the backend was stopped for its memory use
+pub const RESOURCE_KILL_EVENT: &str = "backend-killed-resource-limit";
+/// RSS threshold when `backend_rss_warn_mb` is unset
+pub const DEFAULT_RSS_WARN_MB: u64 = 1024;
+/// Consecutive samples over `backend_rss_cap_mb` before the backend is
+/// stopped (a short spike is let through)
+pub const CAP_SAMPLES: u32 = 3;
+
+const MB: u64 = 1024 * 1024;
+const NO_PROCESS_STATS: &str = "No per-process stats on this platform";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceSample {
+    pub at: DateTime<Utc>,
+    pub pid: u32,
+    pub rss_bytes: u64,
+    /// Of one core (over 100 when several are busy; 0 in the first sample)
+    pub cpu_percent: f32,
+}
+
+/// What the sampler has seen
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    /// The backend process can be sampled (per-process stats on this
+    /// platform, and the backend runs here)
+    pub supported: bool,
+    /// Why it can't be, when `supported` is false
+    pub unavailable: Option<String>,
+    /// Latest sample of the running backend
+    pub current: Option<ResourceSample>,
+    pub peak_rss_bytes: u64,
+    pub peak_cpu_percent: f32,
+    /// Oldest first
+    pub history: Vec<ResourceSample>,
+}
+
+/// `backend-resource-warning` payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceWarning {
+    pub pid: u32,
+    pub rss_bytes: u64,
+    pub threshold_bytes: u64,
+    pub at: DateTime<Utc>,
+}
+
+/// `backend-killed-resource-limit` payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceKill {
+    pub pid: u32,
+    /// RSS of the last sample
+    pub rss_bytes: u64,
+    pub cap_bytes: u64,
+    pub peak_rss_bytes: u64,
+    pub peak_cpu_percent: f32,
+    /// Samples in a row over the cap
+    pub samples_over: u32,
+    pub at: DateTime<Utc>,
+}
+
+/// Thresholds in bytes (0: off)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Limits {
+    pub warn_bytes: u64,
+    pub cap_bytes: u64,
+}
+
+impl Limits {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            warn_bytes: settings.backend_rss_warn_mb.unwrap_or(DEFAULT_RSS_WARN_MB) * MB,
+            cap_bytes: settings.backend_rss_cap_mb.unwrap_or(0) * MB,
+        }
+    }
+}
+
+/// What one sample calls for
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Verdict {
+    pub warning: Option<ResourceWarning>,
+    /// Stop the backend
+    pub kill: Option<ResourceKill>,
+}
+
+/// Where samples come from (sysinfo; tests feed synthetic ones)
+pub trait Sampler {
+    /// `pid`'s memory and CPU (`None` when the process is gone)
+    fn sample(&mut self, pid: u32) -> Option<ResourceSample>;
+}
+
+#[derive(Default)]
+struct SysinfoSampler(System);
+
+impl Sampler for SysinfoSampler {
+    fn sample(&mut self, pid: u32) -> Option<ResourceSample> {
+        sample(&mut self.0, pid)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Samples {
+    history: VecDeque<ResourceSample>,
+    alive: bool,
+    peak_rss_bytes: u64,
+    peak_cpu_percent: f32,
+    /// Over the threshold since the last warning
+    warned: bool,
+    /// Samples in a row over the cap
+    over_cap: u32,
+    /// Why the backend isn't sampled (see [`unavailable`])
+    unavailable: Option<&'static str>,
+}
+
+/// Samples of the backend process (one per app)
+#[derive(Debug, Default)]
+pub struct ResourceMonitor {
+    samples: Mutex<Samples>,
+}
+
+impl ResourceMonitor {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Samples> {
+        self.samples.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn usage(&self) -> ResourceUsage {
+        let samples = self.lock();
+        let unavailable = samples
+            .unavailable
+            .or((!sysinfo::IS_SUPPORTED_SYSTEM).then_some(NO_PROCESS_STATS));
+        ResourceUsage {
+            supported: unavailable.is_none(),
+            unavailable: unavailable.map(String::from),
+            current: samples
+                .alive
+                .then(|| samples.history.back().cloned())
+                .flatten(),
+            peak_rss_bytes: samples.peak_rss_bytes,
+            peak_cpu_percent: samples.peak_cpu_percent,
+            history: samples.history.iter().cloned().collect(),
+        }
+    }
+
+    /// Keep `sample` and judge it against `limits`
+    pub fn record(&self, sample: ResourceSample, limits: Limits) -> Verdict {
+        let mut samples = self.lock();
+        samples.alive = true;
+        samples.peak_rss_bytes = samples.peak_rss_bytes.max(sample.rss_bytes);
+        samples.peak_cpu_percent = samples.peak_cpu_percent.max(sample.cpu_percent);
+        let over = limits.warn_bytes > 0 && sample.rss_bytes >= limits.warn_bytes;
+        let warning = (over && !samples.warned).then_some(ResourceWarning {
+            pid: sample.pid,
+            rss_bytes: sample.rss_bytes,
+            threshold_bytes: limits.warn_bytes,
+            at: sample.at,
+        });
+        samples.warned = over;
+        let over_cap = limits.cap_bytes > 0 && sample.rss_bytes >= limits.cap_bytes;
+        samples.over_cap = if over_cap { samples.over_cap + 1 } else { 0 };
+        let kill = (samples.over_cap >= CAP_SAMPLES).then(|| ResourceKill {
+            pid: sample.pid,
+            rss_bytes: sample.rss_bytes,
+            cap_bytes: limits.cap_bytes,
+            peak_rss_bytes: samples.peak_rss_bytes,
+            peak_cpu_percent: samples.peak_cpu_percent,
+            samples_over: samples.over_cap,
+            at: sample.at,
+        });
+        samples.history.push_back(sample);
+        if samples.history.len() > HISTORY_LIMIT {
+            samples.history.pop_front();
+        }
+        Verdict { warning, kill }
+    }
+
+    /// Note how the backend is launched; false when it can't be sampled
+    pub fn set_mode(&self, mode: InstallMode) -> bool {
+        let reason = unavailable(mode);
+        self.lock().unavailable = reason;
+        reason.is_none()
+    }
+
+    /// The backend is gone: no current sample until it is back
+    pub fn exited(&self) {
+        let mut samples = self.lock();
+        samples.alive = false;
+        samples.warned = false;
+        samples.over_cap = 0;
+    }
+}
+
+/// Why a backend launched in `mode` can't be sampled (`None`: it can)
+pub fn unavailable(mode: InstallMode) -> Option<&'static str> {
+    match mode {
+        _ if !sysinfo::IS_SUPPORTED_SYSTEM => Some(NO_PROCESS_STATS),
+        InstallMode::Container => Some("The backend runs in a container"),
+        InstallMode::Ssh => Some("The backend runs on another host"),
+        _ => None,
+    }
+}
+
+/// Sample `pid` and judge it (`None` when the process is gone)
+fn observe(
+    monitor: &ResourceMonitor,
+    sampler: &mut impl Sampler,
+    pid: u32,
+    limits: Limits,
+) -> Option<Verdict> {
+    Some(monitor.record(sampler.sample(pid)?, limits))
+}
+
+/// Read `pid`'s memory and CPU (`None` when the process is gone)
+fn sample(system: &mut System, pid: u32) -> Option<ResourceSample> {
+    let id = Pid::from_u32(pid);
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[id]),
+        true,
+        ProcessRefreshKind::nothing().with_memory().with_cpu(),
+    );
+    let process = system.process(id)?;
+    Some(ResourceSample {
+        at: Utc::now(),
+        pid,
+        rss_bytes: process.memory(),
+        cpu_percent: process.cpu_usage(),
+    })
+}
+
+/// Start the sampler (called once from `setup`)
+pub fn spawn_sampler(app: AppHandle) {
+    if !sysinfo::IS_SUPPORTED_SYSTEM {
+        log::info!("Backend resource sampling unavailable on this platform");
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app.try_state::<AppState>() else {
+            return;
+        };
+        let mut spawns = state.bridge.subscribe_spawns();
+        let mut sampler = SysinfoSampler::default();
+        loop {
+            let limits = Limits::from_settings(&*state.settings.read().await);
+            let local = state.backend_resources.set_mode(state.bridge.launch().mode);
+            let verdict = match state.bridge.pid().await {
+                // That pid is the docker or ssh client
+                Some(_) if !local => None,
+                Some(pid) => observe(&state.backend_resources, &mut sampler, pid, limits),
+                None => None,
+            };
+            let Some(verdict) = verdict else {
+                state.backend_resources.exited();
+                // Nothing to sample until the next spawn
+                if spawns.changed().await.is_err() {
+                    return;
+                }
+                continue;
+            };
+            if let Some(warning) = verdict.warning {
+                log::warn!(
+                    "Backend RSS {} MB is over {} MB",
+                    warning.rss_bytes / MB,
+                    warning.threshold_bytes / MB
+                );
+                if let Err(e) = app.emit(RESOURCE_WARNING_EVENT, &warning) {
+                    log::warn!("Failed to emit {}: {}", RESOURCE_WARNING_EVENT, e);
+                }
+            }
+            if let Some(kill) = verdict.kill {
+                log::warn!(
+                    "Stopping the backend: RSS {} MB over the {} MB cap for {} samples",
+                    kill.rss_bytes / MB,
+                    kill.cap_bytes / MB,
+                    kill.samples_over
+                );
+                let stopped = state
+                    .bridge
+                    .terminate_over_limit(
+                        kill.pid,
+                        kill.rss_bytes / MB,
+                        kill.cap_bytes / MB,
+                        SHUTDOWN_GRACE,
+                    )
+                    .await;
+                match stopped {
+                    Ok(true) => {}
+                    // Already gone or respawned: that one starts from scratch
+                    Ok(false) => {
+                        state.backend_resources.exited();
+                        continue;
+                    }
+                    Err(e) => log::warn!("Failed to stop the backend: {:#}", e),
+                }
+                state.backend_resources.exited();
+                if let Err(e) = app.emit(RESOURCE_KILL_EVENT, &kill) {
+                    log::warn!("Failed to emit {}: {}", RESOURCE_KILL_EVENT, e);
+                }
+                continue;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(SAMPLE_INTERVAL) => {}
+                changed = spawns.changed() => {
+                    if changed.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(rss_bytes: u64, cpu_percent: f32) -> ResourceSample {
+        ResourceSample {
+            at: Utc::now(),
+            pid: 42,
+            rss_bytes,
+            cpu_percent,
+        }
+    }
+
+    fn warn_at(warn_bytes: u64) -> Limits {
+        Limits {
+            warn_bytes,
+            cap_bytes: 0,
+        }
+    }
+
+    /// Synthetic RSS values, then the process is gone
+    struct FakeSampler(std::collections::VecDeque<u64>);
+
+    impl Sampler for FakeSampler {
+        fn sample(&mut self, _pid: u32) -> Option<ResourceSample> {
+            Some(at(self.0.pop_front()?, 10.0))
+        }
+    }
+
+    #[test]
+    fn test_peaks_history_and_warnings() {
+        let monitor = ResourceMonitor::default();
+        let warned = |sample, limit| monitor.record(sample, warn_at(limit)).warning;
+        assert!(warned(at(10, 50.0), 100).is_none());
+        let warning = warned(at(150, 5.0), 100).unwrap();
+        assert_eq!((warning.rss_bytes, warning.threshold_bytes), (150, 100));
+        // Still over: no second warning until it dropped below
+        assert!(warned(at(120, 1.0), 100).is_none());
+        assert!(warned(at(90, 1.0), 100).is_none());
+        assert!(warned(at(110, 1.0), 100).is_some());
+        assert!(warned(at(900, 1.0), 0).is_none());
+
+        let usage = monitor.usage();
+        assert_eq!(usage.peak_rss_bytes, 900);
+        assert_eq!(usage.peak_cpu_percent, 50.0);
+        assert_eq!(usage.history.len(), 6);
+        assert_eq!(usage.current.unwrap().rss_bytes, 900);
+
+        monitor.exited();
+        let usage = monitor.usage();
+        assert!(usage.current.is_none());
+        assert_eq!(usage.peak_rss_bytes, 900);
+
+        for i in 0..HISTORY_LIMIT as u64 + 5 {
+            monitor.record(at(i, 0.0), Limits::default());
+        }
+        let history = monitor.usage().history;
+        assert_eq!(history.len(), HISTORY_LIMIT);
+        assert_eq!(history[0].rss_bytes, 5);
+    }
+
+    #[test]
+    fn test_cap_needs_consecutive_samples() {
+        let limits = Limits {
+            warn_bytes: 0,
+            cap_bytes: 1000,
+        };
+        let monitor = ResourceMonitor::default();
+        // Spikes of one and two samples pass, the third in a row kills
+        let mut sampler = FakeSampler([1200, 500, 1500, 1100, 900, 2000, 1000, 1300].into());
+        let kills: Vec<bool> = std::iter::from_fn(|| observe(&monitor, &mut sampler, 42, limits))
+            .map(|verdict| verdict.kill.is_some())
+            .collect();
+        assert_eq!(
+            kills,
+            [false, false, false, false, false, false, false, true]
+        );
+
+        // Stopped: the sampler marks it exited, the respawn counts afresh
+        monitor.exited();
+        let mut sampler = FakeSampler([1200, 1300, 1100].into());
+        let kill = std::iter::from_fn(|| observe(&monitor, &mut sampler, 42, limits))
+            .find_map(|verdict| verdict.kill)
+            .unwrap();
+        assert_eq!(kill.rss_bytes, 1100);
+        assert_eq!(kill.cap_bytes, 1000);
+        assert_eq!(kill.peak_rss_bytes, 2000);
+        assert_eq!(kill.samples_over, CAP_SAMPLES);
+
+        // Two over the cap, then exited: nothing carries over
+        monitor.exited();
+        let mut sampler = FakeSampler([1200, 1200].into());
+        assert!(
+            std::iter::from_fn(|| observe(&monitor, &mut sampler, 42, limits))
+                .all(|verdict| verdict.kill.is_none())
+        );
+        let mut sampler = FakeSampler([5000; 5].into());
+        assert!(
+            std::iter::from_fn(|| observe(&monitor, &mut sampler, 42, Limits::default()))
+                .all(|verdict| verdict.kill.is_none())
+        );
+        assert!(observe(&monitor, &mut FakeSampler([].into()), 42, limits).is_none());
+    }
+
+    #[test]
+    fn test_container_and_ssh_backends_are_not_sampled() {
+        let monitor = ResourceMonitor::default();
+        assert!(!monitor.set_mode(InstallMode::Ssh));
+        let usage = monitor.usage();
+        assert!(!usage.supported);
+        assert_eq!(
+            usage.unavailable.as_deref(),
+            Some(match sysinfo::IS_SUPPORTED_SYSTEM {
+                true => "The backend runs on another host",
+                false => NO_PROCESS_STATS,
+            })
+        );
+        assert!(unavailable(InstallMode::Container).is_some());
+
+        assert_eq!(
+            monitor.set_mode(InstallMode::Package),
+            sysinfo::IS_SUPPORTED_SYSTEM
+        );
+        assert_eq!(monitor.usage().supported, sysinfo::IS_SUPPORTED_SYSTEM);
+    }
+
+    #[test]
+    fn test_sample_own_process() {
+        if !sysinfo::IS_SUPPORTED_SYSTEM {
+            return;
+        }
+        let mut system = System::new();
+        let own = sample(&mut system, std::process::id()).unwrap();
+        assert!(own.rss_bytes > 0);
+        assert!(sample(&mut system, u32::MAX - 1).is_none());
+    }
+}
@@ -0,0 +1,193 @@
+//! Kanban board columns
+//!
+//! `tasks_board` groups the task list by status, one column per
+//! [`STATUSES`] entry (other statuses get columns after them). Cards follow
+//! the manual order `tasks_board_set_order` keeps in a per-project sidecar
+//! map; tasks missing from it come last in list order, and ids that left a
+//! column (moved or deleted) are pruned when the board is read.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::backend::task_str;
+
+/// File name of the card order map inside the project sidecar dir
+pub const BOARD_ORDER_FILE: &str = "board_order.json";
+/// Columns every board has, in display order
+pub const STATUSES: [&str; 3] = ["TODO", "ACTIVE", "DONE"];
+
+/// status -> card ids, top first
+pub type BoardOrder = BTreeMap<String, Vec<String>>;
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum BoardError {
+    #[error("status is required")]
+    StatusRequired,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardColumn {
+    pub status: String,
+    pub tasks: Vec<Value>,
+}
+
+/// Column of `task` (no status counts as `TODO`)
+pub fn status_of(task: &Value) -> String {
+    task_str(task, "status_code")
+        .or_else(|| task_str(task, "status"))
+        .map(|s| s.trim().to_uppercase())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| STATUSES[0].to_string())
+}
+
+/// Drop ids that are no longer in their column (or anywhere); `true` when
+/// something was dropped
+pub fn prune(order: &mut BoardOrder, tasks: &[Value]) -> bool {
+    let present: HashSet<(String, &str)> = tasks
+        .iter()
+        .filter_map(|task| Some((status_of(task), task_str(task, "id")?)))
+        .collect();
+    let mut changed = false;
+    order.retain(|status, ids| {
+        let before = ids.len();
+        ids.retain(|id| present.contains(&(status.clone(), id.as_str())));
+        changed |= ids.len() != before;
+        !ids.is_empty()
+    });
+    changed
+}
+
+/// `tasks` grouped into columns, each sorted by `order`
+pub fn columns(tasks: Vec<Value>, order: &BoardOrder) -> Vec<BoardColumn> {
+    let mut grouped: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+    for task in tasks {
+        grouped.entry(status_of(&task)).or_default().push(task);
+    }
+    let mut columns: Vec<BoardColumn> = STATUSES
+        .iter()
+        .map(|status| BoardColumn {
+            status: status.to_string(),
+            tasks: grouped.remove(*status).unwrap_or_default(),
+        })
+        .collect();
+    columns.extend(
+        grouped
+            .into_iter()
+            .map(|(status, tasks)| BoardColumn { status, tasks }),
+    );
+
+    for column in &mut columns {
+        let Some(ids) = order.get(&column.status) else {
+            continue;
+        };
+        let rank: HashMap<&str, usize> = ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (id.as_str(), index))
+            .collect();
+        // Stable, so unordered cards keep their list order at the end
+        column.tasks.sort_by_key(|task| {
+            task_str(task, "id")
+                .and_then(|id| rank.get(id).copied())
+                .unwrap_or(usize::MAX)
+        });
+    }
+    columns
+}
+
+/// Store `ids` as the order of `status` (trimmed, first occurrence wins);
+/// returns the normalized status and ids
+pub fn set_order(
+    order: &mut BoardOrder,
+    status: &str,
+    ids: Vec<String>,
+) -> Result<(String, Vec<String>)> {
+    let status = status.trim().to_uppercase();
+    if status.is_empty() {
+        return Err(BoardError::StatusRequired.into());
+    }
+    let mut seen = HashSet::new();
+    let ids: Vec<String> = ids
+        .into_iter()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty() && seen.insert(id.clone()))
+        .collect();
+    match ids.is_empty() {
+        true => order.remove(&status),
+        false => order.insert(status.clone(), ids.clone()),
+    };
+    Ok((status, ids))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ids(column: &BoardColumn) -> Vec<&str> {
+        column
+            .tasks
+            .iter()
+            .map(|t| t["id"].as_str().unwrap())
+            .collect()
+    }
+
+    fn fixture() -> Vec<Value> {
+        vec![
+            json!({"id": "T-1", "status_code": "TODO"}),
+            json!({"id": "T-2", "status_code": "ACTIVE"}),
+            json!({"id": "T-3", "status": "todo"}),
+            json!({"id": "T-4", "status_code": "TODO"}),
+            json!({"id": "T-5", "status_code": "BLOCKED"}),
+            json!({"id": "T-6"}),
+        ]
+    }
+
+    #[test]
+    fn test_columns_follow_the_stored_order() {
+        let mut order = BoardOrder::new();
+        set_order(&mut order, "todo", vec!["T-4".into(), "T-1".into()]).unwrap();
+        let columns = columns(fixture(), &order);
+
+        let statuses: Vec<&str> = columns.iter().map(|c| c.status.as_str()).collect();
+        assert_eq!(statuses, ["TODO", "ACTIVE", "DONE", "BLOCKED"]);
+        // Ordered cards first, the rest in list order
+        assert_eq!(ids(&columns[0]), ["T-4", "T-1", "T-3", "T-6"]);
+        assert_eq!(ids(&columns[1]), ["T-2"]);
+        assert!(columns[2].tasks.is_empty());
+    }
+
+    #[test]
+    fn test_prune_and_set_order() {
+        let mut order = BoardOrder::new();
+        let (status, stored) = set_order(
+            &mut order,
+            " active ",
+            vec!["T-2".into(), " T-2".into(), "T-1".into(), "".into()],
+        )
+        .unwrap();
+        assert_eq!(status, "ACTIVE");
+        assert_eq!(stored, ["T-2", "T-1"]);
+        set_order(&mut order, "TODO", vec!["T-9".into(), "T-3".into()]).unwrap();
+        set_order(&mut order, "DONE", vec!["T-gone".into()]).unwrap();
+
+        // T-1 moved to TODO, T-9 and T-gone were deleted
+        assert!(prune(&mut order, &fixture()));
+        assert_eq!(order["ACTIVE"], ["T-2"]);
+        assert_eq!(order["TODO"], ["T-3"]);
+        assert!(!order.contains_key("DONE"));
+        assert!(!prune(&mut order, &fixture()));
+
+        set_order(&mut order, "TODO", Vec::new()).unwrap();
+        assert!(!order.contains_key("TODO"));
+        assert_eq!(
+            set_order(&mut order, " ", Vec::new())
+                .unwrap_err()
+                .to_string(),
+            "status is required"
+        );
+    }
+}
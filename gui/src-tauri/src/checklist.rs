@@ -0,0 +1,591 @@
+//! Markdown checklists as tasks
+//!
+//! A plan pasted from notes becomes one task: the first heading (unless a
+//! title is given) is its title, each top-level `- [ ]` item a subtask, and
+//! the checklist items nested under one its checkpoints (deeper levels are
+//! flattened into the same subtask). `*`, `+` and `1.` bullets, tabs and
+//! uneven indentation are accepted; `**bold**`, `__bold__`, `~~strike~~`
+//! and `[links](url)` are reduced to their text. Lines that are none of
+//! these come back as warnings with their line number.
+//!
+//! The backend confirms a subtask's checkpoints together, so a checked
+//! subtask, or one whose checkpoints are all checked, is confirmed after
+//! creation; one that is only partly checked is left open with a warning.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Columns a tab stands for when comparing indentation
+const TAB_WIDTH: usize = 4;
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum ChecklistError {
+    #[error("The task needs a title")]
+    NoTitle,
+    #[error("The Markdown has no checklist items (- [ ] ...)")]
+    NoItems,
+    #[error("Tasks with subtasks need a parent plan (PLAN-###)")]
+    ParentRequired,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub title: String,
+    pub checked: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChecklistSubtask {
+    pub title: String,
+    pub checked: bool,
+    pub checkpoints: Vec<ChecklistItem>,
+}
+
+impl ChecklistSubtask {
+    /// Confirmed after creation: checked itself or in every checkpoint
+    pub fn confirmed(&self) -> bool {
+        self.checked || (!self.checkpoints.is_empty() && self.checkpoints.iter().all(|c| c.checked))
+    }
+}
+
+/// What a line became
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "role", rename_all = "snake_case")]
+pub enum LineRole {
+    Title,
+    Subtask {
+        subtask: usize,
+    },
+    Checkpoint {
+        subtask: usize,
+        checkpoint: usize,
+    },
+    /// Wrapped text appended to the item on line `item_line`
+    Continuation {
+        item_line: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineMapping {
+    /// 1-based
+    pub line: usize,
+    #[serde(flatten)]
+    pub role: LineRole,
+    /// Step path of the subtask (`s:0`), when the line belongs to one
+    pub path: Option<String>,
+}
+
+/// A line that was skipped or only partly understood
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineWarning {
+    /// 1-based
+    pub line: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checklist {
+    pub title: String,
+    pub subtasks: Vec<ChecklistSubtask>,
+    pub lines: Vec<LineMapping>,
+    pub warnings: Vec<LineWarning>,
+}
+
+/// Step path of subtask `index`
+pub fn step_path(index: usize) -> String {
+    format!("s:{}", index)
+}
+
+fn indent_of(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+/// `- [x] text` -> `(Some(true), "text")`; `- text` -> `(None, "text")`;
+/// `None` when the line isn't a list item
+fn list_item(line: &str) -> Option<(Option<bool>, &str)> {
+    let line = line.trim_start();
+    let marker_len = match line.chars().next()? {
+        '-' | '*' | '+' => 1,
+        c if c.is_ascii_digit() => {
+            let digits = line.chars().take_while(char::is_ascii_digit).count();
+            match line[digits..].chars().next() {
+                Some('.' | ')') => digits + 1,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    let rest = &line[marker_len..];
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let rest = rest.trim_start();
+    let checked = match rest.get(..3) {
+        Some("[ ]") => Some(false),
+        Some("[x]" | "[X]") => Some(true),
+        _ => None,
+    };
+    match checked {
+        Some(_) if rest[3..].is_empty() || rest[3..].starts_with(char::is_whitespace) => {
+            Some((checked, rest[3..].trim()))
+        }
+        _ => Some((None, rest.trim())),
+    }
+}
+
+/// `## Title ##` -> `Title`
+fn heading(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let rest = &line[level..];
+    ((1..=6).contains(&level) && (rest.is_empty() || rest.starts_with(char::is_whitespace)))
+        .then(|| rest.trim().trim_end_matches('#').trim_end())
+}
+
+/// Inline Markdown reduced to its text, whitespace collapsed
+fn plain(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    // [text](url) -> text
+    while let Some(open) = rest.find('[') {
+        let after = &rest[open + 1..];
+        let link = after.find("](").and_then(|close| {
+            let target = &after[close + 2..];
+            target.find(')').map(|end| (close, close + 2 + end + 1))
+        });
+        match link {
+            Some((close, end)) => {
+                out.push_str(&rest[..open]);
+                out.push_str(&after[..close]);
+                rest = &after[end..];
+            }
+            None => {
+                out.push_str(&rest[..=open]);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    let out = out.replace("**", "").replace("__", "").replace("~~", "");
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Default)]
+struct Parser {
+    title: Option<String>,
+    subtasks: Vec<ChecklistSubtask>,
+    lines: Vec<LineMapping>,
+    warnings: Vec<LineWarning>,
+    /// Indentation of the top-level items (the first item's)
+    base_indent: Option<usize>,
+    /// Indentation of the current subtask's checkpoints
+    checkpoint_indent: Option<usize>,
+    /// Checkpoints seen in the current subtask (lowercased)
+    seen: HashSet<String>,
+    /// Line of the item wrapped text continues, if the last line was one
+    open_item: Option<usize>,
+    /// The title was given rather than taken from a heading
+    title_given: bool,
+    in_code: bool,
+}
+
+impl Parser {
+    fn warn(&mut self, line: usize, text: &str, reason: impl Into<String>) {
+        self.warnings.push(LineWarning {
+            line,
+            text: text.trim().to_string(),
+            reason: reason.into(),
+        });
+    }
+
+    fn map(&mut self, line: usize, role: LineRole, subtask: Option<usize>) {
+        self.lines.push(LineMapping {
+            line,
+            role,
+            path: subtask.map(step_path),
+        });
+    }
+
+    fn item(&mut self, number: usize, raw: &str, indent: usize, checked: bool, text: &str) {
+        let title = plain(text);
+        if title.is_empty() {
+            self.warn(number, raw, "Checklist item without text");
+            self.open_item = None;
+            return;
+        }
+        let base = *self.base_indent.get_or_insert(indent);
+        let current = self.subtasks.len().checked_sub(1);
+        match current.filter(|_| indent > base) {
+            None => {
+                self.subtasks.push(ChecklistSubtask {
+                    title,
+                    checked,
+                    checkpoints: Vec::new(),
+                });
+                self.checkpoint_indent = None;
+                self.seen.clear();
+                let index = self.subtasks.len() - 1;
+                self.map(number, LineRole::Subtask { subtask: index }, Some(index));
+            }
+            Some(index) => {
+                let level = *self.checkpoint_indent.get_or_insert(indent);
+                if indent > level {
+                    self.warn(
+                        number,
+                        raw,
+                        "Nested more than two levels deep; kept as a checkpoint of its subtask",
+                    );
+                }
+                if !self.seen.insert(title.to_lowercase()) {
+                    self.warn(number, raw, "Same checkpoint twice in one subtask; skipped");
+                    self.open_item = None;
+                    return;
+                }
+                let checkpoints = &mut self.subtasks[index].checkpoints;
+                checkpoints.push(ChecklistItem { title, checked });
+                let checkpoint = checkpoints.len() - 1;
+                self.map(
+                    number,
+                    LineRole::Checkpoint {
+                        subtask: index,
+                        checkpoint,
+                    },
+                    Some(index),
+                );
+            }
+        }
+        self.open_item = Some(number);
+    }
+
+    /// Indented text right after an item extends its title
+    fn continuation(&mut self, number: usize, item_line: usize, text: &str) {
+        let Some(mapping) = self.lines.iter().find(|m| m.line == item_line) else {
+            return;
+        };
+        let (subtask, checkpoint) = match mapping.role {
+            LineRole::Subtask { subtask } => (subtask, None),
+            LineRole::Checkpoint {
+                subtask,
+                checkpoint,
+            } => (subtask, Some(checkpoint)),
+            _ => return,
+        };
+        let title = match checkpoint {
+            Some(c) => &mut self.subtasks[subtask].checkpoints[c].title,
+            None => &mut self.subtasks[subtask].title,
+        };
+        title.push(' ');
+        title.push_str(&plain(text));
+        self.map(number, LineRole::Continuation { item_line }, Some(subtask));
+    }
+
+    fn line(&mut self, number: usize, raw: &str) {
+        let trimmed = raw.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            if !self.in_code {
+                self.warn(number, raw, "Code block skipped");
+            }
+            self.in_code = !self.in_code;
+            self.open_item = None;
+            return;
+        }
+        if self.in_code {
+            return;
+        }
+        if trimmed.is_empty() {
+            self.open_item = None;
+            return;
+        }
+        if let Some(text) = heading(raw) {
+            self.open_item = None;
+            let text = plain(text);
+            if self.title.is_none() && !text.is_empty() {
+                self.title = Some(text);
+                self.map(number, LineRole::Title, None);
+            } else if self.title_given {
+                self.warn(number, raw, "Heading not used: a title was given");
+            } else {
+                self.warn(number, raw, "Only the first heading is used, as the title");
+            }
+            return;
+        }
+        let indent = indent_of(raw);
+        match list_item(raw) {
+            Some((Some(checked), text)) => self.item(number, raw, indent, checked, text),
+            Some((None, _)) => {
+                self.warn(number, raw, "List item without a checkbox (- [ ] ...)");
+                self.open_item = None;
+            }
+            None => match self.open_item {
+                Some(item_line) if indent > 0 => self.continuation(number, item_line, raw),
+                _ => self.warn(number, raw, "Not a heading or checklist item"),
+            },
+        }
+    }
+}
+
+/// Parse `markdown`; `title`, when not blank, replaces the heading
+pub fn parse(markdown: &str, title: Option<&str>) -> Result<Checklist, ChecklistError> {
+    let mut parser = Parser::default();
+    let given = title.map(plain).filter(|t| !t.is_empty());
+    parser.title_given = given.is_some();
+    parser.title = given;
+    for (index, raw) in markdown.lines().enumerate() {
+        parser.line(index + 1, raw);
+    }
+    let partial: Vec<LineWarning> = parser
+        .subtasks
+        .iter()
+        .zip(
+            parser
+                .lines
+                .iter()
+                .filter(|m| matches!(m.role, LineRole::Subtask { .. })),
+        )
+        .filter_map(|(subtask, mapping)| {
+            let done = subtask.checkpoints.iter().filter(|c| c.checked).count();
+            (!subtask.checked && done > 0 && done < subtask.checkpoints.len()).then(|| LineWarning {
+                line: mapping.line,
+                text: subtask.title.clone(),
+                reason: format!(
+                    "{} of {} checkpoints checked; the backend confirms them together, so the subtask stays open",
+                    done,
+                    subtask.checkpoints.len()
+                ),
+            })
+        })
+        .collect();
+    parser.warnings.extend(partial);
+    parser.warnings.sort_by_key(|w| w.line);
+
+    let title = parser.title.ok_or(ChecklistError::NoTitle)?;
+    if parser.subtasks.is_empty() {
+        return Err(ChecklistError::NoItems);
+    }
+    Ok(Checklist {
+        title,
+        subtasks: parser.subtasks,
+        lines: parser.lines,
+        warnings: parser.warnings,
+    })
+}
+
+impl Checklist {
+    /// `tasks_create` params for the task under `parent`: a step per
+    /// subtask, whose checkpoints (or, without any, its own title) are the
+    /// success criteria
+    pub fn create_params(&self, parent: &str) -> Value {
+        let steps: Vec<Value> = self
+            .subtasks
+            .iter()
+            .map(|subtask| {
+                let criteria: Vec<&str> = match subtask.checkpoints.as_slice() {
+                    [] => vec![subtask.title.as_str()],
+                    checkpoints => checkpoints.iter().map(|c| c.title.as_str()).collect(),
+                };
+                json!({ "title": subtask.title, "success_criteria": criteria })
+            })
+            .collect();
+        json!({
+            "kind": "task",
+            "title": self.title,
+            "parent": parent,
+            "steps": steps,
+        })
+    }
+
+    /// Step paths to confirm once the task exists
+    pub fn confirmed_paths(&self) -> Vec<String> {
+        self.subtasks
+            .iter()
+            .enumerate()
+            .filter(|(_, subtask)| subtask.confirmed())
+            .map(|(index, _)| step_path(index))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles(checklist: &Checklist) -> Vec<(String, Vec<String>)> {
+        checklist
+            .subtasks
+            .iter()
+            .map(|s| {
+                (
+                    s.title.clone(),
+                    s.checkpoints.iter().map(|c| c.title.clone()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn owned(subtasks: &[(&str, &[&str])]) -> Vec<(String, Vec<String>)> {
+        subtasks
+            .iter()
+            .map(|(t, c)| (t.to_string(), c.iter().map(|c| c.to_string()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_plan_with_checkpoints() {
+        let checklist = parse(
+            "# Release 2.0\n\
+             \n\
+             - [ ] Freeze **API**\n\
+             \x20 - [x] Review `v1` endpoints\n\
+             \x20 - [ ] Write the [migration guide](https://example.com/guide)\n\
+             - [x] Bump version\n",
+            None,
+        )
+        .unwrap();
+        assert_eq!(checklist.title, "Release 2.0");
+        assert_eq!(
+            titles(&checklist),
+            owned(&[
+                (
+                    "Freeze API",
+                    &["Review `v1` endpoints", "Write the migration guide"]
+                ),
+                ("Bump version", &[]),
+            ])
+        );
+        assert!(checklist.subtasks[0].checkpoints[0].checked);
+        assert_eq!(
+            checklist.lines.iter().map(|m| m.line).collect::<Vec<_>>(),
+            [1, 3, 4, 5, 6]
+        );
+        assert_eq!(
+            checklist.lines[2].role,
+            LineRole::Checkpoint {
+                subtask: 0,
+                checkpoint: 0
+            }
+        );
+        assert_eq!(checklist.lines[4].path.as_deref(), Some("s:1"));
+        // Bump version is checked; Freeze API only half
+        assert_eq!(checklist.confirmed_paths(), ["s:1"]);
+        assert_eq!(checklist.warnings.len(), 1);
+        assert_eq!(checklist.warnings[0].line, 3);
+        assert!(checklist.warnings[0].reason.starts_with("1 of 2"));
+    }
+
+    #[test]
+    fn test_parse_messy_list() {
+        // Tabs vs spaces, mixed bullets, numbered items, a three-level
+        // item, wrapped text, checkbox case and stray prose
+        let markdown = "Notes from Monday\n\
+            ## **Auth** rework ##\n\
+            * [ ] Sessions\n\
+            \t+ [X] Store in Redis\n\
+            \t    - [x] Pick a client\n\
+            \x20   * [x] TTL\n\
+            1. [ ] __Tokens__\n\
+            \x20  2) [ ] Rotate keys\n\
+            \x20      every night\n\
+            - [ ] ~~Old~~ Cleanup\n\
+            - plain bullet\n\
+            -[ ] no space\n\
+            - [ ]\n\
+            ### Later\n\
+            - [ ] Ship\n";
+        let checklist = parse(markdown, None).unwrap();
+        assert_eq!(checklist.title, "Auth rework");
+        assert_eq!(
+            titles(&checklist),
+            owned(&[
+                ("Sessions", &["Store in Redis", "Pick a client", "TTL"]),
+                ("Tokens", &["Rotate keys every night"]),
+                ("Old Cleanup", &[]),
+                ("Ship", &[]),
+            ])
+        );
+        // Every checkpoint of Sessions is checked
+        assert_eq!(checklist.confirmed_paths(), ["s:0"]);
+        assert_eq!(
+            checklist.lines[7],
+            LineMapping {
+                line: 9,
+                role: LineRole::Continuation { item_line: 8 },
+                path: Some("s:1".into()),
+            }
+        );
+        let warned: Vec<(usize, &str)> = checklist
+            .warnings
+            .iter()
+            .map(|w| (w.line, w.text.as_str()))
+            .collect();
+        assert_eq!(
+            warned,
+            [
+                (1, "Notes from Monday"),
+                (5, "- [x] Pick a client"),
+                (11, "- plain bullet"),
+                (12, "-[ ] no space"),
+                (13, "- [ ]"),
+                (14, "### Later"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_title_duplicates_and_code() {
+        let checklist = parse(
+            "# Ignored heading\n- [ ] Test\n  - [ ] Unit\n  - [ ] unit\n```\n- [ ] not an item\n```\n",
+            Some("  Given **title** "),
+        )
+        .unwrap();
+        assert_eq!(checklist.title, "Given title");
+        assert_eq!(titles(&checklist), owned(&[("Test", &["Unit"])]));
+        let lines: Vec<usize> = checklist.warnings.iter().map(|w| w.line).collect();
+        assert_eq!(lines, [1, 4, 5]);
+
+        // A checked subtask is confirmed whatever its checkpoints say
+        let checklist = parse("- [x] Done\n  - [ ] Leftover", Some("T")).unwrap();
+        assert_eq!(checklist.confirmed_paths(), ["s:0"]);
+        assert!(checklist.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse("- [ ] Item", None).unwrap_err(),
+            ChecklistError::NoTitle
+        );
+        assert_eq!(
+            parse("- [ ] Item", Some("  ")).unwrap_err(),
+            ChecklistError::NoTitle
+        );
+        assert_eq!(
+            parse("# Plan\n- one\n- two", None).unwrap_err(),
+            ChecklistError::NoItems
+        );
+        assert_eq!(parse("", Some("T")).unwrap_err(), ChecklistError::NoItems);
+    }
+
+    #[test]
+    fn test_create_params() {
+        let checklist = parse("# Ship\n- [ ] Build\n  - [ ] Green CI\n- [ ] Tag", None).unwrap();
+        assert_eq!(
+            checklist.create_params("PLAN-001"),
+            json!({
+                "kind": "task",
+                "title": "Ship",
+                "parent": "PLAN-001",
+                "steps": [
+                    { "title": "Build", "success_criteria": ["Green CI"] },
+                    { "title": "Tag", "success_criteria": ["Tag"] },
+                ],
+            })
+        );
+    }
+}
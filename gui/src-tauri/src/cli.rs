@@ -0,0 +1,182 @@
+//! Command-line arguments
+//!
+//! `apply-task-gui [DIR] [--project DIR] [--task ID] [--namespace NS]`.
+//! The project replaces the launch directory (as `APPLY_TASK_USER_CWD`
+//! does); the task is kept for the frontend as the startup intent. Deep
+//! links passed as arguments belong to the deep-link plugin and are skipped.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+pub const USAGE: &str = "\
+Usage: apply-task-gui [DIR] [--project DIR] [--task ID] [--namespace NS]
+
+  DIR, --project DIR   Open this apply_task project
+  --task ID            Open this task once the window is ready
+  --namespace NS       Namespace of the task (default: the project's)
+  --headless COMMAND   Run one command without a window (see --headless --help)
+  -h, --help           Show this help";
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum CliError {
+    #[error("help requested")]
+    Help,
+    #[error("{0}")]
+    Usage(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CliArgs {
+    /// `--project` (validated by the caller)
+    pub project: Option<PathBuf>,
+    /// Positional directory (used only if it exists)
+    pub dir: Option<PathBuf>,
+    pub task: Option<String>,
+    pub namespace: Option<String>,
+}
+
+/// Where the frontend should go once it is up
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StartupIntent {
+    pub task: String,
+    pub namespace: Option<String>,
+}
+
+impl CliArgs {
+    /// Requested directory: `--project`, else an existing positional `DIR`
+    pub fn project_dir(&self, cwd: &Path) -> Option<PathBuf> {
+        match &self.project {
+            Some(project) => Some(cwd.join(project)),
+            None => self
+                .dir
+                .as_ref()
+                .map(|d| cwd.join(d))
+                .filter(|d| d.is_dir()),
+        }
+    }
+
+    pub fn startup_intent(&self) -> Option<StartupIntent> {
+        Some(StartupIntent {
+            task: self.task.clone()?,
+            namespace: self.namespace.clone(),
+        })
+    }
+}
+
+/// Parse the arguments after the executable name
+pub fn parse(args: &[String]) -> Result<CliArgs, CliError> {
+    let mut parsed = CliArgs::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        // Deep links, and the process serial number macOS adds from Finder
+        if arg.contains("://") || arg.starts_with("-psn_") {
+            continue;
+        }
+        if !arg.starts_with('-') {
+            if parsed.dir.is_some() {
+                return Err(CliError::Usage(format!("unexpected argument: {}", arg)));
+            }
+            parsed.dir = Some(PathBuf::from(arg));
+            continue;
+        }
+
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) => (flag, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        if matches!(flag, "-h" | "--help") {
+            return Err(CliError::Help);
+        }
+        let slot = match flag {
+            "--project" => {
+                let value = flag_value(flag, inline, &mut iter)?;
+                parsed.project = Some(PathBuf::from(value));
+                continue;
+            }
+            "--task" => &mut parsed.task,
+            "--namespace" => &mut parsed.namespace,
+            _ => return Err(CliError::Usage(format!("unknown option: {}", flag))),
+        };
+        *slot = Some(flag_value(flag, inline, &mut iter)?);
+    }
+    Ok(parsed)
+}
+
+fn flag_value<'a>(
+    flag: &str,
+    inline: Option<String>,
+    rest: &mut impl Iterator<Item = &'a String>,
+) -> Result<String, CliError> {
+    inline
+        .or_else(|| rest.next().cloned())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty() && !v.starts_with('-'))
+        .ok_or_else(|| CliError::Usage(format!("{} needs a value", flag)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_flags_and_errors() {
+        let parsed = parse(&args(&[
+            "--project",
+            "~/api",
+            "--task=T-142",
+            "--namespace",
+            "api",
+        ]))
+        .unwrap();
+        assert_eq!(parsed.project, Some(PathBuf::from("~/api")));
+        assert_eq!(
+            parsed.startup_intent(),
+            Some(StartupIntent {
+                task: "T-142".into(),
+                namespace: Some("api".into()),
+            })
+        );
+
+        let parsed = parse(&args(&["apply-task://task/ns/T-1", "work", "-psn_0_123"])).unwrap();
+        assert_eq!(parsed.dir, Some(PathBuf::from("work")));
+        assert_eq!(parsed.startup_intent(), None);
+        assert_eq!(parse(&[]).unwrap(), CliArgs::default());
+
+        assert_eq!(parse(&args(&["-h"])), Err(CliError::Help));
+        for bad in [
+            &["--verbose"][..],
+            &["--task"],
+            &["--task", "--project"],
+            &["a", "b"],
+        ] {
+            assert!(
+                matches!(parse(&args(bad)), Err(CliError::Usage(_))),
+                "{:?}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_project_dir_prefers_the_flag() {
+        let cwd = std::env::temp_dir();
+        let parsed = CliArgs {
+            project: Some(PathBuf::from("missing-project")),
+            dir: Some(PathBuf::from(".")),
+            ..CliArgs::default()
+        };
+        // The flag is returned as given; the caller validates it
+        assert_eq!(parsed.project_dir(&cwd), Some(cwd.join("missing-project")));
+        let positional = |dir: &str| CliArgs {
+            dir: Some(PathBuf::from(dir)),
+            ..CliArgs::default()
+        };
+        assert_eq!(positional(".").project_dir(&cwd), Some(cwd.join(".")));
+        assert_eq!(positional("no-such-dir-here").project_dir(&cwd), None);
+    }
+}
@@ -0,0 +1,237 @@
+//! AI status commands
+//!
+//! Windows subscribe to `ai-status-changed` events instead of polling
+//! `tasks_ai_status` themselves. Signals sent to the AI are recorded and
+//! acknowledged by the poller; signals the AI raises arrive as `ai-signal`
+//! and are answered with `ai_signal_respond`. `ai_pause_all` and
+//! `ai_resume_all` are the global pause control (see [`crate::ai_pause`]).
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, State, Window};
+
+use crate::ai_pause::{self, PauseOutcome, PauseRecord};
+use crate::ai_signals::AiSignal;
+use crate::ai_status::MIN_INTERVAL_MS;
+use crate::backend;
+use crate::error_catalog::{CatalogError, ResponseError};
+use crate::signals::{self, SignalEntry, SignalError};
+use crate::AppState;
+
+/// Default number of entries returned by `tasks_signal_history`
+const SIGNAL_HISTORY_DEFAULT: usize = 50;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct AiStatusSubscription {
+    pub success: bool,
+    /// Effective polling interval shared by all subscribers
+    pub interval_ms: u64,
+    /// Last known status, so the window can render immediately
+    pub latest: Option<Value>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct AiStatusUnsubscribeResponse {
+    pub success: bool,
+    pub remaining_subscribers: usize,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SendSignalResponse {
+    pub success: bool,
+    /// Recorded history entry (absent when validation failed)
+    pub entry: Option<SignalEntry>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct AiSignalRespondResponse {
+    pub success: bool,
+    /// The answered signal (absent when it wasn't pending)
+    pub signal: Option<AiSignal>,
+    /// History entry of the answer
+    pub entry: Option<SignalEntry>,
+    /// Set when the backend can't take a correlation id and it went into
+    /// the message instead
+    pub warning: Option<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct AiPauseResponse {
+    pub success: bool,
+    /// Nothing to do: already paused (by this app or before it), or nothing
+    /// left to resume
+    pub no_op: bool,
+    /// What the pause stopped (pause: in effect now; resume: just resumed)
+    pub paused: Option<PauseRecord>,
+    /// The signal sent
+    pub entry: Option<SignalEntry>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+impl From<Result<PauseOutcome, CatalogError>> for AiPauseResponse {
+    fn from(outcome: Result<PauseOutcome, CatalogError>) -> Self {
+        match outcome {
+            Ok(outcome) => Self {
+                success: true,
+                no_op: outcome.no_op,
+                paused: outcome.record,
+                entry: outcome.entry,
+                error: ResponseError::none(),
+            },
+            Err(e) => Self {
+                success: false,
+                no_op: false,
+                paused: None,
+                entry: None,
+                error: e.into(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SignalHistoryResponse {
+    pub success: bool,
+    /// Newest first
+    pub entries: Vec<SignalEntry>,
+}
+
+#[tauri::command]
+pub async fn ai_status_subscribe(
+    app: AppHandle,
+    window: Window,
+    state: State<'_, AppState>,
+    interval_ms: Option<u64>,
+) -> Result<AiStatusSubscription, String> {
+    let interval_ms = state.ai_status.subscribe(
+        &app,
+        state.bridge.clone(),
+        window.label(),
+        interval_ms.unwrap_or(2000).max(MIN_INTERVAL_MS),
+    );
+
+    Ok(AiStatusSubscription {
+        success: true,
+        interval_ms,
+        latest: state.ai_status.latest(),
+    })
+}
+
+#[tauri::command]
+pub async fn ai_status_unsubscribe(
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<AiStatusUnsubscribeResponse, String> {
+    Ok(AiStatusUnsubscribeResponse {
+        success: true,
+        remaining_subscribers: state.ai_status.unsubscribe(window.label()),
+    })
+}
+
+/// Send a user signal to the AI and record it in the signal history
+#[tauri::command]
+pub async fn tasks_send_signal(
+    state: State<'_, AppState>,
+    signal: String,
+    message: Option<String>,
+) -> Result<SendSignalResponse, String> {
+    let signal = match signals::validate_signal(&signal) {
+        Ok(signal) => signal,
+        Err(e) => {
+            return Ok(SendSignalResponse {
+                success: false,
+                entry: None,
+                error: ResponseError::from(&e),
+            })
+        }
+    };
+    let message = message.unwrap_or_default();
+    let (entry, error) = signals::send(&state, &signal, &message).await;
+
+    Ok(SendSignalResponse {
+        success: entry.delivered,
+        entry: Some(entry),
+        error: error.into(),
+    })
+}
+
+/// Answer a pending AI signal (sent as a `message` signal carrying the
+/// signal's correlation id)
+#[tauri::command]
+pub async fn ai_signal_respond(
+    state: State<'_, AppState>,
+    signal_id: String,
+    response: String,
+) -> Result<AiSignalRespondResponse, String> {
+    let signal_id = signal_id.trim();
+    let Some(signal) = state.ai_signals.lock().await.get(signal_id).cloned() else {
+        return Ok(AiSignalRespondResponse {
+            success: false,
+            signal: None,
+            entry: None,
+            warning: None,
+            error: CatalogError::from(&SignalError::NotPending(signal_id.to_string())).into(),
+        });
+    };
+    let response = response.trim();
+    let correlated =
+        state.bridge.list_tools().await.is_ok_and(|tools| {
+            backend::tool_accepts(&tools, "tasks_send_signal", "correlation_id")
+        });
+    let (message, args, warning) = if correlated {
+        let args = json!({
+            "signal": "message",
+            "message": response,
+            "correlation_id": signal.correlation_id,
+        });
+        (response.to_string(), args, None)
+    } else {
+        let message = format!("[re {}] {}", signal.correlation_id, response);
+        let args = json!({ "signal": "message", "message": message });
+        let warning = "The backend takes no correlation id; it was added to the message";
+        (message, args, Some(warning.to_string()))
+    };
+    let (entry, error) = signals::send_with(&state, "message", &message, args).await;
+    if entry.delivered {
+        state.ai_signals.lock().await.answer(&signal.id);
+    }
+
+    Ok(AiSignalRespondResponse {
+        success: entry.delivered,
+        signal: Some(signal),
+        entry: Some(entry),
+        warning,
+        error: error.into(),
+    })
+}
+
+/// Pause all AI activity, remembering what was running
+#[tauri::command]
+pub async fn ai_pause_all(state: State<'_, AppState>) -> Result<AiPauseResponse, String> {
+    Ok(ai_pause::pause_all(&state).await.into())
+}
+
+/// Resume what `ai_pause_all` paused
+#[tauri::command]
+pub async fn ai_resume_all(state: State<'_, AppState>) -> Result<AiPauseResponse, String> {
+    Ok(ai_pause::resume_all(&state).await.into())
+}
+
+#[tauri::command]
+pub async fn tasks_signal_history(
+    state: State<'_, AppState>,
+    limit: Option<usize>,
+) -> Result<SignalHistoryResponse, String> {
+    Ok(SignalHistoryResponse {
+        success: true,
+        entries: state
+            .signals
+            .lock()
+            .await
+            .recent(limit.unwrap_or(SIGNAL_HISTORY_DEFAULT)),
+    })
+}
@@ -0,0 +1,103 @@
+//! Audit log commands
+
+use chrono::{DateTime, Local, Utc};
+use tauri::State;
+
+use crate::audit::{self, AuditEntry};
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::timer;
+use crate::AppState;
+
+/// Entries returned when no limit is given
+const DEFAULT_LIMIT: usize = 100;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct AuditLogResponse {
+    pub success: bool,
+    /// Newest first
+    pub entries: Vec<AuditEntry>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct AuditExportResponse {
+    pub success: bool,
+    pub path: String,
+    pub count: usize,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Audited mutations of this project, newest first: those between
+/// `range_start` and `range_end` (RFC3339 or `YYYY-MM-DD`, either may be
+/// left out) touching `task_id`, at most `limit` (100)
+#[tauri::command]
+pub async fn audit_log_query(
+    state: State<'_, AppState>,
+    range_start: Option<String>,
+    range_end: Option<String>,
+    task_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<AuditLogResponse, String> {
+    let fail = |error: ResponseError| AuditLogResponse {
+        success: false,
+        entries: Vec::new(),
+        error,
+    };
+    let mut range: [Option<DateTime<Utc>>; 2] = [None, None];
+    for (slot, raw) in range.iter_mut().zip([&range_start, &range_end]) {
+        if let Some(raw) = raw.as_deref().filter(|s| !s.trim().is_empty()) {
+            match timer::parse_bound(raw, &Local) {
+                Some(bound) => *slot = Some(bound),
+                None => {
+                    let error =
+                        CatalogError::new(ErrorCode::ValidationInvalidDate).with("value", raw);
+                    return Ok(fail(error.into()));
+                }
+            }
+        }
+    }
+    let task_id = task_id.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    let [from, to] = range;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    Ok(
+        match audit::query(&audit::dir(&state), from, to, task_id, limit) {
+            Ok(entries) => AuditLogResponse {
+                success: true,
+                entries,
+                error: ResponseError::none(),
+            },
+            Err(e) => fail(ResponseError::from(&e)),
+        },
+    )
+}
+
+/// Copy the whole audit log of this project to `path` (JSON lines; a
+/// relative path is taken from the project directory)
+#[tauri::command]
+pub async fn audit_log_export(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<AuditExportResponse, String> {
+    let path = state.user_cwd().join(path.trim());
+    let exported = audit::export(&audit::dir(&state), &path);
+    let path = path.to_string_lossy().into_owned();
+    Ok(match exported {
+        Ok(count) => {
+            log::info!("Exported {} audit entries to {}", count, path);
+            AuditExportResponse {
+                success: true,
+                path,
+                count,
+                error: ResponseError::none(),
+            }
+        }
+        Err(e) => AuditExportResponse {
+            success: false,
+            path,
+            count: 0,
+            error: ResponseError::from(&e),
+        },
+    })
+}
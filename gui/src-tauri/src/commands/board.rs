@@ -0,0 +1,134 @@
+//! Kanban board commands
+//!
+//! Read side only: cards change column through `tasks_update_status`, these
+//! commands group the list and keep the manual card order.
+
+use serde_json::Value;
+use tauri::State;
+
+use crate::backend::ListFilters;
+use crate::board::{self, BoardColumn, BoardOrder, BOARD_ORDER_FILE};
+use crate::deep_link;
+use crate::error_catalog::{CatalogError, ResponseError};
+use crate::sidecar;
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct BoardResponse {
+    pub success: bool,
+    pub columns: Vec<BoardColumn>,
+    pub total: usize,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct BoardOrderResponse {
+    pub success: bool,
+    pub status: String,
+    pub ordered_ids: Vec<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+fn order_path(state: &AppState) -> std::path::PathBuf {
+    state.project_dir().join(BOARD_ORDER_FILE)
+}
+
+/// Stored order with ids that left their column dropped (and saved so)
+fn pruned_order(state: &AppState, tasks: &[Value]) -> anyhow::Result<BoardOrder> {
+    let path = order_path(state);
+    let mut order: BoardOrder = sidecar::read_json(&path)?;
+    if board::prune(&mut order, tasks) {
+        if let Err(e) = sidecar::write_json(&path, &order) {
+            log::warn!("Failed to save pruned board order: {}", e);
+        }
+    }
+    Ok(order)
+}
+
+/// Tasks grouped into status columns, in the saved card order.
+///
+/// `namespace` refuses the board unless it is the project's namespace;
+/// `domain` keeps only tasks under that domain prefix.
+#[tauri::command]
+pub async fn tasks_board(
+    state: State<'_, AppState>,
+    namespace: Option<String>,
+    domain: Option<String>,
+    force_refresh: Option<bool>,
+) -> Result<BoardResponse, String> {
+    let fail = |error: ResponseError| BoardResponse {
+        success: false,
+        columns: Vec::new(),
+        total: 0,
+        error,
+    };
+
+    if let Some(namespace) = &namespace {
+        if let Some(error) = deep_link::namespace_error(&state, namespace).await {
+            return Ok(fail(error.into()));
+        }
+    }
+
+    let tasks = match super::task::load_task_list(
+        &state,
+        &ListFilters::default(),
+        true,
+        None,
+        force_refresh,
+        None,
+    )
+    .await
+    {
+        Ok((tasks, _)) => tasks,
+        Err(e) => return Ok(fail(ResponseError::from(&e))),
+    };
+    // Pruned against every task, so a domain filter keeps other orders
+    let order = match pruned_order(&state, &tasks) {
+        Ok(order) => order,
+        Err(e) => return Ok(fail(ResponseError::from(&e))),
+    };
+
+    let mut tasks = tasks;
+    ListFilters {
+        domain,
+        ..ListFilters::default()
+    }
+    .retain(&mut tasks);
+    Ok(BoardResponse {
+        success: true,
+        total: tasks.len(),
+        columns: board::columns(tasks, &order),
+        error: ResponseError::none(),
+    })
+}
+
+/// Save the card order of one column after a drag and drop
+#[tauri::command]
+pub async fn tasks_board_set_order(
+    state: State<'_, AppState>,
+    status: String,
+    ordered_ids: Vec<String>,
+) -> Result<BoardOrderResponse, String> {
+    let path = order_path(&state);
+    let saved = sidecar::read_json(&path).and_then(|mut order: BoardOrder| {
+        let saved = board::set_order(&mut order, &status, ordered_ids)?;
+        sidecar::write_json(&path, &order)?;
+        Ok(saved)
+    });
+    Ok(match saved {
+        Ok((status, ordered_ids)) => BoardOrderResponse {
+            success: true,
+            status,
+            ordered_ids,
+            error: ResponseError::none(),
+        },
+        Err(e) => BoardOrderResponse {
+            success: false,
+            status,
+            ordered_ids: Vec::new(),
+            error: CatalogError::from_anyhow(&e).into(),
+        },
+    })
+}
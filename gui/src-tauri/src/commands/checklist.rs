@@ -0,0 +1,150 @@
+//! Markdown checklist command
+//!
+//! Creates one task from a pasted checklist, then confirms the subtasks
+//! that were already checked with `tasks_verify`.
+
+use serde_json::json;
+use tauri::{AppHandle, State};
+
+use crate::audit::{self, Audit};
+use crate::backend;
+use crate::checklist::{self, ChecklistError, LineMapping, LineWarning};
+use crate::deep_link;
+use crate::error_catalog::{CatalogError, ResponseError};
+use crate::journal::Mutation;
+use crate::quick_add;
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct MarkdownCreateResponse {
+    pub success: bool,
+    pub task_id: Option<String>,
+    pub title: Option<String>,
+    /// Which lines became the title, subtasks and checkpoints
+    pub lines: Vec<LineMapping>,
+    /// Lines that were skipped or only partly understood
+    pub warnings: Vec<LineWarning>,
+    /// Step paths confirmed after creation
+    pub confirmed: Vec<String>,
+    /// Created, but some checked subtasks could not be confirmed
+    pub warning: Option<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Create a task under `parent` from a Markdown checklist: the first
+/// heading (or `title`) is its title, top-level items its subtasks, nested
+/// items their checkpoints. `namespace`, when given, must be the open
+/// project's.
+#[tauri::command]
+pub async fn tasks_create_from_markdown(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    markdown: String,
+    title: Option<String>,
+    namespace: Option<String>,
+    parent: Option<String>,
+) -> Result<MarkdownCreateResponse, String> {
+    let response = create_from_markdown_response(
+        Some(&app),
+        &state,
+        &markdown,
+        title.as_deref(),
+        namespace.as_deref(),
+        parent.as_deref(),
+    )
+    .await;
+    state
+        .usage_metrics
+        .record_outcome("tasks_create_from_markdown", response.success);
+    Ok(response)
+}
+
+pub(crate) async fn create_from_markdown_response(
+    app: Option<&AppHandle>,
+    state: &AppState,
+    markdown: &str,
+    title: Option<&str>,
+    namespace: Option<&str>,
+    parent: Option<&str>,
+) -> MarkdownCreateResponse {
+    let fail = |error: ResponseError| MarkdownCreateResponse {
+        success: false,
+        task_id: None,
+        title: None,
+        lines: Vec::new(),
+        warnings: Vec::new(),
+        confirmed: Vec::new(),
+        warning: None,
+        error,
+    };
+    if let Some(namespace) = namespace.filter(|n| !n.trim().is_empty()) {
+        if let Some(error) = deep_link::namespace_error(state, namespace).await {
+            return fail(error.into());
+        }
+    }
+    let parsed = match checklist::parse(markdown, title) {
+        Ok(parsed) => parsed,
+        Err(e) => return fail(CatalogError::from(&e).into()),
+    };
+    let fail = |error: ResponseError| MarkdownCreateResponse {
+        lines: parsed.lines.clone(),
+        warnings: parsed.warnings.clone(),
+        ..fail(error)
+    };
+    let Some(parent) = parent.map(str::trim).filter(|p| !p.is_empty()) else {
+        return fail(CatalogError::from(&ChecklistError::ParentRequired).into());
+    };
+
+    let task_id =
+        match quick_add::create_task(app, state, parsed.create_params(parent), &[], None).await {
+            Ok((task_id, _)) => task_id,
+            Err(e) => return fail(ResponseError::from(&e)),
+        };
+    let mut confirmed = Vec::new();
+    let mut failed = Vec::new();
+    for path in parsed.confirmed_paths() {
+        let params = json!({
+            "task": task_id,
+            "path": path,
+            "checkpoints": { "criteria": { "confirmed": true } },
+        });
+        let verified = state.bridge.call_tool("tasks_verify", params.clone()).await;
+        state.storage_watch.mark_own_write();
+        let audited = Audit::new("tasks_verify", [task_id.clone()]).params(&params);
+        match verified.and_then(backend::into_result) {
+            Ok(_) => {
+                let mutation = Mutation::new("tasks_verify", Some(task_id.clone()))
+                    .change(None, Some("criteria".into()));
+                state.journal.lock().await.record(mutation);
+                audit::record(state, audited).await;
+                confirmed.push(path);
+            }
+            Err(e) => {
+                log::warn!("Created {} without confirming {}: {:#}", task_id, path, e);
+                audit::record(state, audited.failed(format!("{:#}", e))).await;
+                failed.push(format!("{} ({})", path, e));
+            }
+        }
+    }
+    if !confirmed.is_empty() || !failed.is_empty() {
+        state.read_cache.lock().await.invalidate(&[]);
+    }
+    let warning = (!failed.is_empty()).then(|| {
+        format!(
+            "Created {}, but these checked subtasks were not confirmed: {}",
+            task_id,
+            failed.join(", ")
+        )
+    });
+    MarkdownCreateResponse {
+        success: true,
+        task_id: Some(task_id),
+        title: Some(parsed.title),
+        lines: parsed.lines,
+        warnings: parsed.warnings,
+        confirmed,
+        warning,
+        error: ResponseError::none(),
+    }
+}
@@ -0,0 +1,101 @@
+//! Commits made during a task's lifetime
+//!
+//! `git log` of the project since the task was created (its `created_at`,
+//! else the first history operation on it), optionally only the commits
+//! whose message names the task. Without git history the list is empty and
+//! `git_available` false; that is not an error.
+
+use chrono::{DateTime, Local, Utc};
+use tauri::State;
+
+use crate::backend;
+use crate::error_catalog::ResponseError;
+use crate::git::{self, Commit, LogQuery};
+use crate::timeline;
+use crate::AppState;
+
+/// Commits listed when no limit is given
+const DEFAULT_LIMIT: usize = 50;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct RelatedCommitsResponse {
+    pub success: bool,
+    pub task_id: String,
+    /// When the task was created; the latest commits are listed when unknown
+    pub since: Option<DateTime<Utc>>,
+    /// Newest first
+    pub commits: Vec<Commit>,
+    pub git_available: bool,
+    /// Why there is no history (no git, not a repository, ...)
+    pub unavailable: Option<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Commits to the project since `task_id` was created, newest first, at
+/// most `limit` (50); with `mentioning`, only those whose message contains
+/// the task id
+#[tauri::command]
+pub async fn tasks_related_commits(
+    state: State<'_, AppState>,
+    task_id: String,
+    limit: Option<usize>,
+    mentioning: Option<bool>,
+) -> Result<RelatedCommitsResponse, String> {
+    let response =
+        related_commits_response(&state, task_id, limit, mentioning.unwrap_or(false)).await;
+    state
+        .usage_metrics
+        .record_outcome("tasks_related_commits", response.success);
+    Ok(response)
+}
+
+pub(crate) async fn related_commits_response(
+    state: &AppState,
+    task_id: String,
+    limit: Option<usize>,
+    mentioning: bool,
+) -> RelatedCommitsResponse {
+    let mut response = RelatedCommitsResponse {
+        success: false,
+        task_id,
+        since: None,
+        commits: Vec::new(),
+        git_available: false,
+        unavailable: None,
+        error: ResponseError::none(),
+    };
+    let task = match backend::show_task(&state.bridge, &response.task_id).await {
+        Ok(task) => task,
+        Err(e) => {
+            response.error = ResponseError::from(&e);
+            return response;
+        }
+    };
+    response.since = match timeline::milestones(&task, None, &Local).created {
+        Some(created) => Some(created),
+        None => super::timeline::history_operations(state)
+            .await
+            .map(|ops| timeline::from_history(&ops, &Local))
+            .and_then(|history| {
+                let milestones = history.get(&response.task_id);
+                timeline::milestones(&task, milestones, &Local).created
+            }),
+    };
+
+    let query = LogQuery {
+        since: response.since,
+        until: None,
+        mentioning: mentioning.then(|| response.task_id.clone()),
+        limit: limit.unwrap_or(DEFAULT_LIMIT),
+    };
+    response.success = true;
+    match git::log(&state.user_cwd(), &query).await {
+        Ok(commits) => {
+            response.commits = commits;
+            response.git_available = true;
+        }
+        Err(reason) => response.unavailable = Some(reason.to_string()),
+    }
+    response
+}
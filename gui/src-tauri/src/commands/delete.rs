@@ -0,0 +1,380 @@
+//! Task deletion
+//!
+//! Deleting is two-step unless `skip_confirmation` is set: the first call
+//! returns a summary and a single-use token, the second call (with the
+//! token) deletes. Tasks with children are refused unless `cascade` is set,
+//! in which case descendants are deleted first. The token confirms the
+//! cascade flag and descendants as planned when it was issued; the second
+//! call plans again and refuses it if either differs.
+//!
+//! With a `path`, only that step (or nested task) of the task is deleted,
+//! confirmed the same way.
+
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde_json::{json, Value};
+use tauri::State;
+
+use crate::audit::{self, Audit};
+use crate::backend;
+use crate::confirm::{Target, TOKEN_TTL};
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::evidence;
+use crate::journal::Mutation;
+use crate::python::PythonBridge;
+use crate::read_only;
+use crate::revision;
+use crate::task_tree::{self, CascadeReport};
+use crate::AppState;
+
+/// What a delete would remove
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct DeleteSummary {
+    pub title: String,
+    pub subtask_count: usize,
+    pub confirmed_checkpoints: u64,
+    /// Descendant tasks a cascade deletes too
+    pub descendants: Vec<String>,
+}
+
+impl DeleteSummary {
+    fn from_task(task: &Value, descendants: Vec<String>) -> Self {
+        Self {
+            title: backend::task_str(task, "title").unwrap_or("").to_string(),
+            subtask_count: task
+                .pointer("/progress/subtasks")
+                .and_then(|s| s.as_array())
+                .map(|s| s.len())
+                .unwrap_or(0),
+            confirmed_checkpoints: task
+                .pointer("/progress/done")
+                .and_then(|d| d.as_u64())
+                .unwrap_or(0),
+            descendants,
+        }
+    }
+
+    fn from_node(node: &Value) -> Self {
+        fn nested(node: &Value) -> usize {
+            [node.get("steps"), node.pointer("/plan/tasks")]
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_array)
+                .flatten()
+                .map(|child| 1 + nested(child))
+                .sum()
+        }
+        Self {
+            title: backend::task_str(node, "title").unwrap_or("").to_string(),
+            subtask_count: nested(node),
+            confirmed_checkpoints: evidence::checkpoints(node)
+                .iter()
+                .filter(|c| c.confirmed)
+                .count() as u64,
+            descendants: Vec::new(),
+        }
+    }
+}
+
+/// What the node at a delete's `path` is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    #[default]
+    Step,
+    Task,
+}
+
+impl NodeKind {
+    fn tool(self) -> &'static str {
+        match self {
+            Self::Step => "tasks_delete",
+            Self::Task => "tasks_task_delete",
+        }
+    }
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct DeleteResponse {
+    pub success: bool,
+    pub task_id: String,
+    pub deleted: bool,
+    pub needs_confirmation: bool,
+    pub confirm_token: Option<String>,
+    pub expires_in_secs: Option<u64>,
+    pub summary: Option<DeleteSummary>,
+    /// Refused because the task has children and `cascade` is off
+    pub has_children: bool,
+    pub children: Vec<String>,
+    /// Per-id outcome (descendants first, the task itself last)
+    pub report: Option<CascadeReport>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+impl DeleteResponse {
+    fn failed(task_id: &str, error: impl Into<ResponseError>) -> Self {
+        Self {
+            task_id: task_id.to_string(),
+            error: error.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Target payload and the ids a delete removes (descendants first, root last)
+async fn plan_delete(bridge: &PythonBridge, task_id: &str) -> anyhow::Result<(Value, Vec<String>)> {
+    let task = backend::show_task(bridge, task_id).await?;
+    let all_tasks = backend::list_tasks(bridge, None).await?;
+    let order = task_tree::delete_order(&task, &all_tasks);
+    Ok((task, order))
+}
+
+/// Where a delete's `path` points
+#[derive(Debug, Clone)]
+pub(crate) struct DeleteNode {
+    pub path: String,
+    pub kind: NodeKind,
+}
+
+/// Delete a task, or the step or nested task (`kind`) at `path` of it
+/// (two-step with `confirm_token` unless confirmation is skipped); with
+/// `expected_revision` (the task's `revision`), only if nobody changed it
+/// since
+#[tauri::command]
+pub async fn tasks_delete(
+    state: State<'_, AppState>,
+    task_id: String,
+    confirm_token: Option<String>,
+    cascade: Option<bool>,
+    expected_revision: Option<u64>,
+    path: Option<String>,
+    kind: Option<NodeKind>,
+) -> Result<DeleteResponse, String> {
+    let node = path.map(|path| DeleteNode {
+        path,
+        kind: kind.unwrap_or_default(),
+    });
+    let response = delete_response(
+        &state,
+        task_id,
+        confirm_token,
+        cascade,
+        expected_revision,
+        node,
+    )
+    .await;
+    state
+        .usage_metrics
+        .record_outcome("tasks_delete", response.success);
+    Ok(response)
+}
+
+/// Redeem `confirm_token` for `target`, or issue a token and answer with
+/// `summary`; `Err` is the response to send instead of deleting
+async fn confirm(
+    state: &AppState,
+    task_id: &str,
+    confirm_token: Option<String>,
+    target: Target,
+    summary: impl FnOnce() -> DeleteSummary,
+) -> Result<(), DeleteResponse> {
+    if state.settings.read().await.skip_confirmation {
+        return Ok(());
+    }
+    let mut tokens = state.confirm_tokens.lock().await;
+    match confirm_token {
+        Some(token) if tokens.consume(&token, &target, Instant::now()) => Ok(()),
+        Some(_) => Err(DeleteResponse::failed(
+            task_id,
+            CatalogError::new(ErrorCode::ConfirmTokenInvalid),
+        )),
+        None => Err(DeleteResponse {
+            task_id: task_id.to_string(),
+            needs_confirmation: true,
+            confirm_token: Some(tokens.issue(target, Instant::now())),
+            expires_in_secs: Some(TOKEN_TTL.as_secs()),
+            summary: Some(summary()),
+            ..DeleteResponse::default()
+        }),
+    }
+}
+
+/// `tasks_delete` (also run by `--headless`)
+pub(crate) async fn delete_response(
+    state: &AppState,
+    task_id: String,
+    confirm_token: Option<String>,
+    cascade: Option<bool>,
+    expected_revision: Option<u64>,
+    node: Option<DeleteNode>,
+) -> DeleteResponse {
+    if let Some(error) = read_only::refusal(state).await {
+        return DeleteResponse::failed(&task_id, error);
+    }
+
+    let planned = plan_delete(&state.bridge, &task_id).await;
+    let (task, order) = match planned {
+        Ok(planned) => planned,
+        Err(e) => return DeleteResponse::failed(&task_id, &e),
+    };
+    // Before any descendant goes; the root's own delete is checked again by
+    // the backend
+    if let Some(conflict) = revision::check(&task_id, &task, expected_revision) {
+        return DeleteResponse::failed(&task_id, conflict);
+    }
+    if let Some(node) = node {
+        let found = match evidence::node(&task, Some(&node.path)) {
+            Ok(found) => found,
+            Err(e) => return DeleteResponse::failed(&task_id, CatalogError::from(&e)),
+        };
+        let target = Target::new(&task_id, false, &[]).at(&node.path);
+        if let Err(response) = confirm(state, &task_id, confirm_token, target, || {
+            DeleteSummary::from_node(found)
+        })
+        .await
+        {
+            return response;
+        }
+        return delete_node(state, task_id, node, expected_revision).await;
+    }
+    let descendants = order[..order.len().saturating_sub(1)].to_vec();
+    let cascade = cascade.unwrap_or(false);
+
+    if !cascade && !descendants.is_empty() {
+        return DeleteResponse {
+            has_children: true,
+            children: descendants,
+            ..DeleteResponse::failed(&task_id, CatalogError::new(ErrorCode::DeleteHasChildren))
+        };
+    }
+
+    let target = Target::new(&task_id, cascade, &descendants);
+    if let Err(response) = confirm(state, &task_id, confirm_token, target, || {
+        DeleteSummary::from_task(&task, descendants)
+    })
+    .await
+    {
+        return response;
+    }
+
+    state.storage_watch.mark_own_write();
+    let bridge = state.bridge.clone();
+    let root = task_id.clone();
+    let conflict = Arc::new(Mutex::new(None::<CatalogError>));
+    let refused = conflict.clone();
+    let report = task_tree::delete_in_order(order, move |id| {
+        let bridge = bridge.clone();
+        let mut params = json!({ "task": &id });
+        if id == root {
+            revision::expect(&mut params, expected_revision);
+        }
+        let refused = refused.clone();
+        async move {
+            let envelope = bridge.call_tool("tasks_delete", params).await?;
+            let result = envelope.get("result").unwrap_or(&Value::Null);
+            if let Some(error) = revision::conflict(envelope.get("error"), result) {
+                *refused.lock().unwrap_or_else(|e| e.into_inner()) = Some(error);
+            }
+            let result = backend::into_result(envelope)?;
+            match result.get("deleted").and_then(|d| d.as_bool()) {
+                Some(false) => Err(anyhow::anyhow!("Backend did not delete {}", id)),
+                _ => Ok(()),
+            }
+        }
+    })
+    .await;
+    state.storage_watch.mark_own_write();
+    if !report.deleted.is_empty() {
+        state.read_cache.lock().await.invalidate(&report.deleted);
+        {
+            let mut journal = state.journal.lock().await;
+            for id in &report.deleted {
+                journal.record(Mutation::new("tasks_delete", Some(id.clone())));
+            }
+        }
+        let params = json!({ "task": task_id, "cascade": cascade });
+        let deleted = Audit::new("tasks_delete", report.deleted.clone()).params(&params);
+        audit::record(state, deleted).await;
+    }
+    if let Some(failure) = &report.failure {
+        let failed = Audit::new("tasks_delete", [failure.id.clone()])
+            .params(&json!({ "task": failure.id }))
+            .failed(&failure.error);
+        audit::record(state, failed).await;
+    }
+
+    let deleted = report.deleted.last() == Some(&task_id);
+    let conflict = conflict.lock().unwrap_or_else(|e| e.into_inner()).take();
+    DeleteResponse {
+        success: deleted,
+        deleted,
+        error: report
+            .failure
+            .as_ref()
+            .map(|f| {
+                conflict.unwrap_or_else(|| {
+                    CatalogError::new(ErrorCode::DeleteFailed)
+                        .with("task_id", f.id.as_str())
+                        .with("reason", f.error.as_str())
+                })
+            })
+            .into(),
+        report: Some(report),
+        task_id,
+        ..DeleteResponse::default()
+    }
+}
+
+/// Delete the node at `node.path` of `task_id`
+async fn delete_node(
+    state: &AppState,
+    task_id: String,
+    node: DeleteNode,
+    expected_revision: Option<u64>,
+) -> DeleteResponse {
+    let tool = node.kind.tool();
+    let mut params = json!({ "task": &task_id, "path": &node.path });
+    revision::expect(&mut params, expected_revision);
+    state.storage_watch.mark_own_write();
+    let called = state.bridge.call_tool(tool, params.clone()).await;
+    state.storage_watch.mark_own_write();
+    let error = match called {
+        Ok(envelope) => {
+            let result = envelope.get("result").cloned().unwrap_or(Value::Null);
+            let conflict = revision::conflict(envelope.get("error"), &result);
+            match backend::into_result(envelope) {
+                Ok(_) => None,
+                Err(e) => Some((e.to_string(), conflict)),
+            }
+        }
+        Err(e) => Some((e.to_string(), None)),
+    };
+    if let Some((reason, conflict)) = error {
+        let failed = Audit::new(tool, [task_id.clone()])
+            .params(&params)
+            .failed(&reason);
+        audit::record(state, failed).await;
+        let error = conflict.unwrap_or_else(|| {
+            CatalogError::new(ErrorCode::DeleteFailed)
+                .with("task_id", task_id.as_str())
+                .with("reason", reason)
+        });
+        return DeleteResponse::failed(&task_id, error);
+    }
+
+    state.read_cache.lock().await.invalidate(&[task_id.clone()]);
+    state
+        .journal
+        .lock()
+        .await
+        .record(Mutation::new(tool, Some(task_id.clone())));
+    audit::record(state, Audit::new(tool, [task_id.clone()]).params(&params)).await;
+    DeleteResponse {
+        success: true,
+        deleted: true,
+        task_id,
+        ..DeleteResponse::default()
+    }
+}
@@ -0,0 +1,206 @@
+//! Diagnostics commands
+
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State, WebviewWindow};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::crash::{self, CrashReport};
+use crate::detection::DetectionReport;
+use crate::doctor::{self, DoctorReport};
+use crate::env_info::{self, EnvInfo};
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::logging;
+use crate::python::BridgeStatus;
+use crate::support_bundle::{self, SupportBundle};
+use crate::usage_metrics::Metrics;
+use crate::versions::{self, BackendVersionCheck, Versions};
+use crate::AppState;
+
+/// How the apply_task root was found at startup, with every probed path
+#[tauri::command]
+pub async fn detection_report(state: State<'_, AppState>) -> Result<DetectionReport, String> {
+    let mut report = state.detection.clone();
+    report.install_mode = Some(state.bridge.launch().mode);
+    Ok(report)
+}
+
+/// Backend process state, launch command (installation mode included) and
+/// resource usage
+#[tauri::command]
+pub async fn bridge_status(state: State<'_, AppState>) -> Result<BridgeStatus, String> {
+    let mut status = state.bridge.status().await;
+    status.resources = Some(state.backend_resources.usage());
+    Ok(status)
+}
+
+/// Support checklist: interpreter, install, entry point, root, storage and
+/// MCP handshake (against a throwaway backend)
+#[tauri::command]
+pub async fn doctor(state: State<'_, AppState>) -> Result<DoctorReport, String> {
+    Ok(doctor::run_checks(&state).await)
+}
+
+/// GUI, Tauri, Python and backend versions (cached), with `compat_warning`
+/// when the backend is outside the supported range
+#[tauri::command]
+pub async fn versions(app: AppHandle, state: State<'_, AppState>) -> Result<Versions, String> {
+    Ok(versions::versions(&app, &state).await)
+}
+
+/// Backend version against the minimum this GUI needs, with the upgrade
+/// command for the way it is installed
+#[tauri::command]
+pub async fn check_backend_version(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<BackendVersionCheck, String> {
+    let backend = versions::versions(&app, &state).await.backend;
+    let mode = state.bridge.launch().mode;
+    Ok(versions::check_backend(backend.as_deref(), mode))
+}
+
+/// Effective project root, cwd, interpreter, entry point, namespace and
+/// transport, each with the source that won (home as `~`, secrets redacted)
+#[tauri::command]
+pub async fn env_info(state: State<'_, AppState>) -> Result<EnvInfo, String> {
+    Ok(env_info::collect(&state).await)
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SupportBundleResponse {
+    pub success: bool,
+    pub bundle: Option<SupportBundle>,
+    /// Whether the file manager was launched
+    pub revealed: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Zip the log tail, recent JSON-RPC traffic, `doctor`, `env_info` and
+/// `versions` (redacted) into the temp directory, optionally revealing it
+#[tauri::command]
+pub async fn create_support_bundle(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    reveal: Option<bool>,
+) -> Result<SupportBundleResponse, String> {
+    let parts = support_bundle::gather(&app, &state).await;
+    let bundle = match support_bundle::write(&parts, &std::env::temp_dir()) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            return Ok(SupportBundleResponse {
+                success: false,
+                bundle: None,
+                revealed: false,
+                error: CatalogError::from_anyhow(&e).into(),
+            })
+        }
+    };
+    log::info!(
+        "Support bundle written to {} ({} bytes)",
+        bundle.path,
+        bundle.bytes
+    );
+    let mut error = ResponseError::none();
+    let revealed = match reveal.unwrap_or(false) {
+        false => false,
+        true => match app.opener().reveal_item_in_dir(&bundle.path) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("Failed to reveal {}: {}", bundle.path, e);
+                // The path is still returned for headless sessions
+                error = CatalogError::new(ErrorCode::OpenFailed)
+                    .with("detail", e.to_string())
+                    .into();
+                false
+            }
+        },
+    };
+    Ok(SupportBundleResponse {
+        success: true,
+        bundle: Some(bundle),
+        revealed,
+        error,
+    })
+}
+
+/// Current log file (`apply-task.log` in the app log directory)
+#[tauri::command]
+pub fn get_log_path() -> Result<String, String> {
+    logging::log_path()
+        .map(|p| p.to_string_lossy().into_owned())
+        .ok_or_else(|| "Logging to stderr only (no app log directory)".to_string())
+}
+
+/// Open the log directory in the file manager
+#[tauri::command]
+pub fn open_log_folder(app: AppHandle) -> Result<(), String> {
+    let dir = app.path().app_log_dir().map_err(|e| e.to_string())?;
+    app.opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| e.to_string())
+}
+
+/// Write a frontend message to the log (`[frontend]`, limited per window);
+/// `false` when it was dropped by the limit
+#[tauri::command]
+pub fn log_from_frontend(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    level: String,
+    message: String,
+    context: Option<Value>,
+) -> Result<bool, String> {
+    state
+        .frontend_log
+        .log(window.label(), &level, &message, context.as_ref())
+        .map_err(|e| e.to_string())
+}
+
+/// Crash reports left by earlier runs, newest first
+#[tauri::command]
+pub fn get_crash_reports() -> Vec<CrashReport> {
+    crash::report_dir().map(crash::list).unwrap_or_default()
+}
+
+/// Discard a crash report
+#[tauri::command]
+pub fn delete_crash_report(id: String) -> Result<(), String> {
+    let dir = crash::report_dir().ok_or("Crash reports are unavailable (no app log directory)")?;
+    crash::delete(dir, &id).map_err(|e| e.to_string())
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct UsageMetricsResponse {
+    pub success: bool,
+    /// `analytics_enabled`; counts are only recorded while on
+    pub enabled: bool,
+    /// The plain JSON file the counts are kept in
+    pub path: String,
+    pub metrics: Metrics,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+fn usage_metrics_response(state: &AppState, error: ResponseError) -> UsageMetricsResponse {
+    UsageMetricsResponse {
+        success: error.is_none(),
+        enabled: state.usage_metrics.enabled(),
+        path: state.usage_metrics.path().to_string_lossy().into_owned(),
+        metrics: state.usage_metrics.snapshot(),
+        error,
+    }
+}
+
+/// Local command/intent counters (never uploaded)
+#[tauri::command]
+pub fn usage_metrics_get(state: State<'_, AppState>) -> UsageMetricsResponse {
+    usage_metrics_response(&state, ResponseError::none())
+}
+
+/// Drop all counters and their file
+#[tauri::command]
+pub fn usage_metrics_reset(state: State<'_, AppState>) -> UsageMetricsResponse {
+    let error = state.usage_metrics.reset().err();
+    usage_metrics_response(&state, error.map(|e| CatalogError::from_anyhow(&e)).into())
+}
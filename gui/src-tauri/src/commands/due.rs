@@ -0,0 +1,286 @@
+//! Due date commands
+//!
+//! Due dates go to the backend when it advertises a field for them,
+//! otherwise to the per-project sidecar.
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use chrono::{Local, NaiveDate, Utc};
+use serde_json::{json, Value};
+use tauri::State;
+
+use crate::audit::{self, Audit};
+use crate::backend::{self, ListFilters};
+use crate::due::{self, DueDates, DueFilter, DueTask, DUE_DATES_FILE};
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::ics;
+use crate::journal::Mutation;
+use crate::read_only;
+use crate::sidecar;
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SetDueResponse {
+    pub success: bool,
+    pub task_id: String,
+    pub due: Option<NaiveDate>,
+    /// Where the date was written: `backend` or `sidecar`
+    pub storage: String,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct DueListResponse {
+    pub success: bool,
+    pub filter: DueFilter,
+    pub tasks: Vec<DueTask>,
+    pub overdue_count: usize,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct IcsExportResponse {
+    pub success: bool,
+    pub path: Option<String>,
+    /// VTODO entries written
+    pub count: usize,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+fn due_path(state: &AppState) -> std::path::PathBuf {
+    state.project_dir().join(DUE_DATES_FILE)
+}
+
+fn update_sidecar(state: &AppState, task_id: &str, due: Option<NaiveDate>) -> anyhow::Result<()> {
+    let path = due_path(state);
+    let mut dates: DueDates = sidecar::read_json(&path)?;
+    let changed = match due {
+        Some(date) => dates.insert(task_id.to_string(), date) != Some(date),
+        None => dates.remove(task_id).is_some(),
+    };
+    if changed {
+        sidecar::write_json(&path, &dates)?;
+    }
+    Ok(())
+}
+
+/// Backend tool + args able to store a due date, if the backend has one
+fn backend_due_call(
+    tools: &[Value],
+    task_id: &str,
+    due: Option<NaiveDate>,
+) -> Option<(&'static str, Value)> {
+    let due = due.map(|d| d.to_string());
+    if backend::has_tool(tools, "tasks_set_due") {
+        return Some(("tasks_set_due", json!({ "task": task_id, "due": due })));
+    }
+    if backend::tool_accepts(tools, "tasks_edit", "due") {
+        return Some(("tasks_edit", json!({ "task": task_id, "due": due })));
+    }
+    None
+}
+
+#[tauri::command]
+pub async fn tasks_set_due(
+    state: State<'_, AppState>,
+    task_id: String,
+    due: Option<String>,
+) -> Result<SetDueResponse, String> {
+    let task_id = task_id.trim().to_string();
+    let fail = |storage: &str, due: Option<NaiveDate>, error: CatalogError| SetDueResponse {
+        success: false,
+        task_id: task_id.clone(),
+        due,
+        storage: storage.to_string(),
+        error: error.into(),
+    };
+
+    if task_id.is_empty() {
+        return Ok(fail(
+            "sidecar",
+            None,
+            CatalogError::new(ErrorCode::ValidationTaskIdRequired),
+        ));
+    }
+
+    let due = match due.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        Some(raw) => match due::parse_due(raw) {
+            Some(date) => Some(date),
+            None => {
+                let error =
+                    CatalogError::new(ErrorCode::ValidationInvalidDueDate).with("value", raw);
+                return Ok(fail("sidecar", None, error));
+            }
+        },
+        None => None,
+    };
+
+    if let Some(error) = read_only::refusal(&state).await {
+        return Ok(fail("sidecar", due, error));
+    }
+    match write_due(&state, &task_id, due).await {
+        (storage, Ok(())) => Ok(SetDueResponse {
+            success: true,
+            task_id,
+            due,
+            storage: storage.to_string(),
+            error: ResponseError::none(),
+        }),
+        (storage, Err(e)) => Ok(fail(storage, due, CatalogError::from_anyhow(&e))),
+    }
+}
+
+/// Store (or clear) the due date of `task_id`, in the backend when it can
+/// hold one; returns where it went (`backend` or `sidecar`) and how it went
+pub(crate) async fn write_due(
+    state: &AppState,
+    task_id: &str,
+    due: Option<NaiveDate>,
+) -> (&'static str, anyhow::Result<()>) {
+    let tools = state.bridge.list_tools().await.unwrap_or_else(|e| {
+        log::warn!("tools/list failed, storing due date locally: {}", e);
+        Vec::new()
+    });
+
+    let (storage, tool, params, written) = match backend_due_call(&tools, task_id, due) {
+        Some((tool, args)) => {
+            let written = state.bridge.call_tool(tool, args.clone()).await;
+            state.storage_watch.mark_own_write();
+            let written = written.and_then(backend::into_result).map(|_| ());
+            if written.is_ok() {
+                let mutation = Mutation::new(tool, Some(task_id.to_string()))
+                    .change(None, due.map(|d| d.to_string()));
+                state.journal.lock().await.record(mutation);
+                // Drop any stale local copy now that the backend owns the value
+                if let Err(e) = update_sidecar(state, task_id, None) {
+                    log::warn!("Failed to clear sidecar due date: {}", e);
+                }
+            }
+            ("backend", tool, args, written)
+        }
+        None => (
+            "sidecar",
+            "tasks_set_due",
+            json!({ "task": task_id, "due": due.map(|d| d.to_string()) }),
+            update_sidecar(state, task_id, due),
+        ),
+    };
+    let audited = Audit::new(tool, [task_id.to_string()]).params(&params);
+    let audited = match &written {
+        Ok(()) => audited,
+        Err(e) => audited.failed(format!("{:#}", e)),
+    };
+    audit::record(state, audited).await;
+    (storage, written)
+}
+
+#[tauri::command]
+pub async fn tasks_due(
+    state: State<'_, AppState>,
+    filter: DueFilter,
+) -> Result<DueListResponse, String> {
+    let fail = |e: anyhow::Error| DueListResponse {
+        success: false,
+        filter,
+        tasks: Vec::new(),
+        overdue_count: 0,
+        error: ResponseError::from(&e),
+    };
+
+    let dates: DueDates = match sidecar::read_json(&due_path(&state)) {
+        Ok(dates) => dates,
+        Err(e) => return Ok(fail(e)),
+    };
+
+    let tasks = match backend::list_tasks(&state.bridge, None).await {
+        Ok(tasks) => tasks,
+        Err(e) => return Ok(fail(e)),
+    };
+
+    let today = Local::now().date_naive();
+    Ok(DueListResponse {
+        success: true,
+        filter,
+        tasks: due::select(&tasks, &dates, filter, today),
+        overdue_count: due::overdue_count(&tasks, &dates, today),
+        error: ResponseError::none(),
+    })
+}
+
+/// Where an export without a path goes: the downloads folder (the app has
+/// no save dialog), else the home directory
+fn default_ics_path(state: &AppState) -> Option<PathBuf> {
+    let dir = dirs::download_dir().or_else(dirs::home_dir)?;
+    Some(dir.join(format!(
+        "apply-task-{}.ics",
+        sidecar::project_key(&state.user_cwd())
+    )))
+}
+
+async fn export_ics(
+    state: &AppState,
+    path: PathBuf,
+    filter: Option<DueFilter>,
+) -> anyhow::Result<usize> {
+    let dates: DueDates = sidecar::read_json(&due_path(state))?;
+    // Full payloads, for the descriptions
+    let (tasks, _) =
+        super::task::load_task_list(state, &ListFilters::default(), false, None, None, None)
+            .await?;
+    let entries = match filter {
+        Some(filter) => due::select(&tasks, &dates, filter, Local::now().date_naive()),
+        None => due::resolve(&tasks, &dates)
+            .into_iter()
+            .map(|(task, _)| task)
+            .collect(),
+    };
+    let key = sidecar::project_key(&state.user_cwd());
+    let calendar = ics::calendar(&entries, &tasks, &key, Utc::now());
+    std::fs::write(&path, calendar).with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(entries.len())
+}
+
+/// Write the tasks with a due date (those matching `filter`, all when unset)
+/// as an iCalendar file. A relative `path` is taken from the project
+/// directory.
+#[tauri::command]
+pub async fn tasks_export_ics(
+    state: State<'_, AppState>,
+    path: Option<String>,
+    filter: Option<DueFilter>,
+) -> Result<IcsExportResponse, String> {
+    let path = match path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => Some(state.user_cwd().join(path)),
+        None => default_ics_path(&state),
+    };
+    let Some(path) = path else {
+        return Ok(IcsExportResponse {
+            success: false,
+            path: None,
+            count: 0,
+            error: CatalogError::internal("No downloads or home directory to export to").into(),
+        });
+    };
+    let shown = path.to_string_lossy().into_owned();
+    Ok(match export_ics(&state, path, filter).await {
+        Ok(count) => {
+            log::info!("Exported {} due dates to {}", count, shown);
+            IcsExportResponse {
+                success: true,
+                path: Some(shown),
+                count,
+                error: ResponseError::none(),
+            }
+        }
+        Err(e) => IcsExportResponse {
+            success: false,
+            path: Some(shown),
+            count: 0,
+            error: ResponseError::from(&e),
+        },
+    })
+}
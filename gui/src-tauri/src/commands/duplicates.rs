@@ -0,0 +1,107 @@
+//! Duplicate detection commands
+//!
+//! `tasks_find_similar` lists tasks of the open project with a title like
+//! the given one. `ai_intent` runs the same check before `tasks_create` and
+//! refuses with `possible_duplicates` unless `allow_duplicate` is passed or
+//! `skip_duplicate_check` is set.
+
+use serde_json::Value;
+use tauri::State;
+
+use crate::backend::ListFilters;
+use crate::deep_link;
+use crate::duplicates::{self, SimilarTask};
+use crate::error_catalog::ResponseError;
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SimilarTasksResponse {
+    pub success: bool,
+    /// Best first
+    pub matches: Vec<SimilarTask>,
+    pub threshold: f64,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Tasks (from the cached list) whose title scores at least `threshold`
+/// against `title`
+pub(crate) async fn similar_tasks(
+    state: &AppState,
+    title: &str,
+    threshold: f64,
+) -> anyhow::Result<Vec<SimilarTask>> {
+    let (tasks, _) =
+        super::task::load_task_list(state, &ListFilters::default(), true, None, None, None).await?;
+    Ok(duplicates::find_similar(title, &tasks, threshold))
+}
+
+/// Pre-flight of a `tasks_create` call: the similar tasks refusing it, or
+/// `None` to go ahead. Takes `allow_duplicate` out of `params` (the backend
+/// doesn't know it); a failed lookup lets the creation through.
+pub(crate) async fn check_create(state: &AppState, params: &mut Value) -> Option<Vec<SimilarTask>> {
+    let allowed = params
+        .as_object_mut()
+        .and_then(|params| params.remove("allow_duplicate"))
+        .and_then(|allow| allow.as_bool())
+        .unwrap_or(false);
+    if allowed || state.settings.read().await.skip_duplicate_check {
+        return None;
+    }
+    let title = params.get("title").and_then(Value::as_str)?;
+    match similar_tasks(state, title, duplicates::DEFAULT_THRESHOLD).await {
+        Ok(matches) if !matches.is_empty() => Some(matches),
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("Duplicate check skipped: {:#}", e);
+            None
+        }
+    }
+}
+
+/// Tasks with a title like `title`, best first (`threshold` 0..=1, default
+/// 0.75)
+#[tauri::command]
+pub async fn tasks_find_similar(
+    state: State<'_, AppState>,
+    title: String,
+    namespace: Option<String>,
+    threshold: Option<f64>,
+) -> Result<SimilarTasksResponse, String> {
+    let response = find_similar_response(&state, &title, namespace, threshold).await;
+    state
+        .usage_metrics
+        .record_outcome("tasks_find_similar", response.success);
+    Ok(response)
+}
+
+pub(crate) async fn find_similar_response(
+    state: &AppState,
+    title: &str,
+    namespace: Option<String>,
+    threshold: Option<f64>,
+) -> SimilarTasksResponse {
+    let threshold = threshold
+        .unwrap_or(duplicates::DEFAULT_THRESHOLD)
+        .clamp(0.0, 1.0);
+    let mut response = SimilarTasksResponse {
+        success: false,
+        matches: Vec::new(),
+        threshold,
+        error: ResponseError::none(),
+    };
+    if let Some(namespace) = &namespace {
+        if let Some(error) = deep_link::namespace_error(state, namespace).await {
+            response.error = error.into();
+            return response;
+        }
+    }
+    match similar_tasks(state, title, threshold).await {
+        Ok(matches) => {
+            response.success = true;
+            response.matches = matches;
+        }
+        Err(e) => response.error = ResponseError::from(&e),
+    }
+    response
+}
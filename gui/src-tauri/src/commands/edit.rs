@@ -0,0 +1,158 @@
+//! Field edits through `tasks_edit`
+//!
+//! `debounce_ms` coalesces a burst of edits to one task (priority slider)
+//! into one call, like `tasks_update_status`; it carries the fields of
+//! every edit in the burst, the latest value of each.
+
+use serde_json::{Map, Value};
+use tauri::{AppHandle, State};
+
+use crate::ai_response::AIResponse;
+use crate::audit::{self, Audit};
+use crate::debounce;
+use crate::error_catalog::{CatalogError, ResponseError};
+use crate::journal::{self, Mutation};
+use crate::mutation_queue;
+use crate::read_only;
+use crate::revision;
+use crate::AppState;
+
+const EDIT_TOOL: &str = "tasks_edit";
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TaskEditResponse {
+    pub success: bool,
+    pub task_id: String,
+    pub result: Option<AIResponse>,
+    /// Held in the offline queue; replayed when the backend is back
+    pub queued: bool,
+    /// A newer debounced edit of the task replaced this one; nothing was sent
+    pub superseded: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Apply `changes` (`tasks_edit` arguments besides `task`) to a task; with
+/// `expected_revision` (the task's `revision`), only if nobody changed it since
+#[tauri::command]
+pub async fn tasks_edit(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    task_id: String,
+    changes: Map<String, Value>,
+    debounce_ms: Option<u64>,
+    expected_revision: Option<u64>,
+) -> Result<TaskEditResponse, String> {
+    let response = edit_response(
+        Some(&app),
+        &state,
+        task_id,
+        changes,
+        debounce_ms,
+        expected_revision,
+    )
+    .await;
+    state
+        .usage_metrics
+        .record_outcome("tasks_edit", response.success);
+    Ok(response)
+}
+
+/// `tasks_edit`; without `app` the offline queue is not replayed first
+pub(crate) async fn edit_response(
+    app: Option<&AppHandle>,
+    state: &AppState,
+    task_id: String,
+    changes: Map<String, Value>,
+    debounce_ms: Option<u64>,
+    expected_revision: Option<u64>,
+) -> TaskEditResponse {
+    let changes = match debounce::quiet_period(debounce_ms) {
+        Some(quiet) => {
+            let key = debounce::key(EDIT_TOOL, &task_id);
+            match state.debouncer.wait_merged(&key, quiet, changes).await {
+                Some(merged) => merged,
+                None => {
+                    return TaskEditResponse {
+                        success: true,
+                        task_id,
+                        result: None,
+                        queued: false,
+                        superseded: true,
+                        error: ResponseError::none(),
+                    }
+                }
+            }
+        }
+        None => changes,
+    };
+
+    if let Some(error) = read_only::refusal(state).await {
+        return TaskEditResponse {
+            success: false,
+            task_id,
+            result: None,
+            queued: false,
+            superseded: false,
+            error: error.into(),
+        };
+    }
+
+    let mut params = changes;
+    params.insert("task".to_string(), Value::String(task_id.clone()));
+    let mut params = Value::Object(params);
+    revision::expect(&mut params, expected_revision);
+
+    if let Some(app) = app {
+        if state.mutation_queue.lock().await.has_untried() {
+            mutation_queue::replay_queue(app).await;
+        }
+    }
+    state.storage_watch.mark_own_write();
+    let response = match state.bridge.call_tool(EDIT_TOOL, params.clone()).await {
+        Ok(result) => AIResponse::from_value(result),
+        Err(e) => {
+            match mutation_queue::queue_if_offline(state, &e, "edit", EDIT_TOOL, &params).await {
+                Some(entry) => mutation_queue::queued_response("edit", &entry),
+                None => AIResponse::bridge_error("edit", e.to_string()),
+            }
+        }
+    };
+    state.storage_watch.mark_own_write();
+
+    if response.success {
+        state
+            .read_cache
+            .lock()
+            .await
+            .invalidate(std::slice::from_ref(&task_id));
+    }
+    let fields: Vec<&str> = params
+        .as_object()
+        .into_iter()
+        .flat_map(|p| p.keys())
+        .map(String::as_str)
+        .filter(|key| !matches!(*key, "task" | "expected_revision"))
+        .collect();
+    let mutation =
+        Mutation::new(EDIT_TOOL, Some(task_id.clone())).change(None, Some(fields.join(", ")));
+    journal::record(state, &response, mutation).await;
+    let audited = Audit::new(EDIT_TOOL, [task_id.clone()])
+        .params(&params)
+        .response(&response);
+    audit::record(state, audited).await;
+    let error = (!response.success).then(|| {
+        revision::conflict(response.error.as_ref(), &response.result).unwrap_or_else(|| {
+            CatalogError::from_envelope(response.error.as_ref(), "Failed to edit task")
+        })
+    });
+
+    TaskEditResponse {
+        success: response.success,
+        task_id,
+        queued: response.queued,
+        superseded: false,
+        result: Some(response),
+        error: error.into(),
+    }
+}
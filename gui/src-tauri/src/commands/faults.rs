@@ -0,0 +1,70 @@
+//! Fault injection commands (developer mode)
+//!
+//! Let frontend developers see how the UI handles timeouts, tool errors
+//! and backend crashes without breaking their Python install. Rules live
+//! in memory only; see `python::faults` for how they fire.
+
+use tauri::State;
+
+use crate::error_catalog::ResponseError;
+use crate::python::{FaultError, FaultRule};
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct FaultInjectionResponse {
+    pub success: bool,
+    /// Armed rules, with the failures each has injected so far
+    pub rules: Vec<FaultRule>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Replace the fault rules (refused unless developer mode is on)
+#[tauri::command]
+pub async fn set_fault_injection(
+    state: State<'_, AppState>,
+    rules: Vec<FaultRule>,
+) -> Result<FaultInjectionResponse, String> {
+    let faults = state.bridge.faults();
+    let result = match state.settings.read().await.developer_mode {
+        true => faults.set(rules),
+        false => Err(FaultError::DeveloperModeOff.into()),
+    };
+    Ok(match result {
+        Ok(rules) => FaultInjectionResponse {
+            success: true,
+            rules,
+            error: ResponseError::none(),
+        },
+        Err(e) => FaultInjectionResponse {
+            success: false,
+            rules: faults.rules(),
+            error: ResponseError::from(&e),
+        },
+    })
+}
+
+/// Armed fault rules
+#[tauri::command]
+pub async fn get_fault_injection(
+    state: State<'_, AppState>,
+) -> Result<FaultInjectionResponse, String> {
+    Ok(FaultInjectionResponse {
+        success: true,
+        rules: state.bridge.faults().rules(),
+        error: ResponseError::none(),
+    })
+}
+
+/// Disarm every fault rule
+#[tauri::command]
+pub async fn clear_fault_injection(
+    state: State<'_, AppState>,
+) -> Result<FaultInjectionResponse, String> {
+    state.bridge.faults().clear();
+    Ok(FaultInjectionResponse {
+        success: true,
+        rules: Vec::new(),
+        error: ResponseError::none(),
+    })
+}
@@ -0,0 +1,99 @@
+//! Quick-switcher command
+//!
+//! Fuzzy matches over the cached compact task list; an empty query lists
+//! the recently viewed tasks instead.
+
+use chrono::Utc;
+use tauri::State;
+
+use crate::backend::{task_str, ListFilters};
+use crate::error_catalog::ResponseError;
+use crate::fuzzy::{self, FuzzyMatch, MatchField};
+use crate::recent_tasks::{self, RecentTask, RECENT_TASKS_FILE};
+use crate::sidecar;
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct FuzzyResponse {
+    pub success: bool,
+    pub query: String,
+    pub matches: Vec<FuzzyMatch>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+fn recent_path(state: &AppState) -> std::path::PathBuf {
+    state.project_dir().join(RECENT_TASKS_FILE)
+}
+
+/// Remember that `task_id` was viewed (failures are only logged)
+pub(crate) fn record_view(state: &AppState, task_id: &str) {
+    let path = recent_path(state);
+    let recorded = sidecar::read_json::<Vec<RecentTask>>(&path).and_then(|mut recent| {
+        if recent_tasks::touch(&mut recent, task_id, Utc::now()) {
+            sidecar::write_json(&path, &recent)?;
+        }
+        Ok(())
+    });
+    if let Err(e) = recorded {
+        log::warn!("Failed to record {} as recently viewed: {:#}", task_id, e);
+    }
+}
+
+/// Tasks matching `query` best first, or the recently viewed ones (newest
+/// first) when it is blank
+#[tauri::command]
+pub async fn tasks_fuzzy(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<FuzzyResponse, String> {
+    let limit = limit.unwrap_or(fuzzy::DEFAULT_LIMIT);
+    let fail = |e: anyhow::Error| FuzzyResponse {
+        success: false,
+        query: query.clone(),
+        matches: Vec::new(),
+        error: ResponseError::from(&e),
+    };
+
+    let tasks =
+        match super::task::load_task_list(&state, &ListFilters::default(), true, None, None, None)
+            .await
+        {
+            Ok((tasks, _)) => tasks,
+            Err(e) => return Ok(fail(e)),
+        };
+    let recent: Vec<String> = match sidecar::read_json::<Vec<RecentTask>>(&recent_path(&state)) {
+        Ok(recent) => recent.into_iter().map(|r| r.task_id).collect(),
+        Err(e) => {
+            log::warn!("Ignoring unreadable recent tasks: {:#}", e);
+            Vec::new()
+        }
+    };
+
+    let matches = if query.trim().is_empty() {
+        // Viewed tasks still in the list, newest first
+        recent
+            .iter()
+            .filter_map(|id| {
+                let task = tasks.iter().find(|t| task_str(t, "id") == Some(id))?;
+                Some(FuzzyMatch {
+                    task_id: id.clone(),
+                    title: task_str(task, "title").unwrap_or_default().to_string(),
+                    score: 0,
+                    field: MatchField::Title,
+                    indices: Vec::new(),
+                })
+            })
+            .take(limit)
+            .collect()
+    } else {
+        fuzzy::rank(&tasks, &query, &recent, limit)
+    };
+    Ok(FuzzyResponse {
+        success: true,
+        query,
+        matches,
+        error: ResponseError::none(),
+    })
+}
@@ -0,0 +1,259 @@
+//! Background job commands
+//!
+//! `ai_intent_background` resolves the intent like `ai_intent`, then runs the
+//! tool call detached from the invoking webview. Cancelling sends MCP
+//! `notifications/cancelled`; whatever the backend already wrote stays.
+
+use std::time::Instant;
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::ai_response::AIResponse;
+use crate::audit::{self, Audit};
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::intent_result::IntentResult;
+use crate::intents;
+use crate::jobs::{self, JobInfo, JobProgress, JobStatus, JOB_FINISHED_EVENT, JOB_PROGRESS_EVENT};
+use crate::python::PythonBridge;
+use crate::rate_limit;
+use crate::read_cache;
+use crate::read_only;
+use crate::AppState;
+
+use super::task::resolve_tool;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct JobStartResponse {
+    pub success: bool,
+    pub job_id: Option<String>,
+    pub tool: Option<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct JobCancelResponse {
+    pub success: bool,
+    pub cancelled: bool,
+    pub job: Option<JobInfo>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct JobListResponse {
+    pub success: bool,
+    pub jobs: Vec<JobInfo>,
+}
+
+fn emit_finished(app: &AppHandle, info: &JobInfo) {
+    if let Err(e) = app.emit(JOB_FINISHED_EVENT, info) {
+        log::warn!("Failed to emit {}: {}", JOB_FINISHED_EVENT, e);
+    }
+}
+
+/// Run the tool call for `job_id` to completion, reporting through events
+async fn run_job(
+    app: AppHandle,
+    bridge: PythonBridge,
+    job_id: String,
+    intent: String,
+    tool: String,
+    params: Value,
+) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let writes = intents::writes_storage(&tool);
+    let target = read_cache::target_task(&params);
+    if writes {
+        state.storage_watch.mark_own_write();
+    }
+
+    // Subscribe before sending so no early progress is missed
+    let mut notifications = bridge.subscribe_notifications();
+    let outcome = match bridge
+        .begin_tool_call(&tool, params.clone(), Some(&job_id))
+        .await
+    {
+        Ok(call) => {
+            if !state
+                .jobs
+                .lock()
+                .await
+                .set_request(&job_id, call.request_id)
+            {
+                // Cancelled while the request was being sent
+                let _ = bridge.cancel_request(call.request_id, "Cancelled").await;
+                return;
+            }
+            let finish = call.finish();
+            tokio::pin!(finish);
+            loop {
+                tokio::select! {
+                    outcome = &mut finish => break outcome,
+                    notification = notifications.recv() => {
+                        // Lagged or closed: keep waiting for the result
+                        let Ok(notification) = notification else { continue };
+                        let Some(progress) = jobs::progress_for(&notification, &job_id) else {
+                            continue;
+                        };
+                        if state.jobs.lock().await.progress(&job_id, progress.clone()) {
+                            let payload = JobProgress { job_id: job_id.clone(), progress };
+                            if let Err(e) = app.emit(JOB_PROGRESS_EVENT, &payload) {
+                                log::warn!("Failed to emit {}: {}", JOB_PROGRESS_EVENT, e);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Err(e) => Err(e),
+    };
+    if writes {
+        state.storage_watch.mark_own_write();
+    }
+
+    let normalized_intent = intents::normalize(&intent);
+    let mut response = match outcome {
+        Ok(result) => AIResponse {
+            typed: IntentResult::from_envelope(&tool, &result),
+            ..AIResponse::from_value(result)
+        },
+        Err(e) => AIResponse::bridge_error(&normalized_intent, e.to_string()),
+    };
+    state.usage_metrics.record_intent(&tool, response.success);
+    if writes {
+        let audited = Audit::new(&tool, target.clone())
+            .params(&params)
+            .response(&response);
+        audit::record(&state, audited).await;
+    }
+    response.resolved_tool = Some(tool);
+    if writes && response.success {
+        let ids: Vec<String> = target.into_iter().collect();
+        state.read_cache.lock().await.invalidate(&ids);
+    }
+    let status = if response.success {
+        JobStatus::Succeeded
+    } else {
+        JobStatus::Failed
+    };
+
+    let finished = state
+        .jobs
+        .lock()
+        .await
+        .finish(&job_id, status, Some(response), Instant::now());
+    if let Some(info) = finished {
+        emit_finished(&app, &info);
+    }
+}
+
+/// Start an intent as a background job and return its id immediately
+#[tauri::command]
+pub async fn ai_intent_background(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    intent: String,
+    params: Option<Value>,
+    strict: Option<bool>,
+) -> Result<JobStartResponse, String> {
+    let user_aliases = state.settings.read().await.intent_aliases.clone();
+    // The job keeps a handle, not the state lock
+    let bridge = state.bridge.clone();
+
+    let Some(tool) = resolve_tool(&bridge, &user_aliases, &intent, strict.unwrap_or(true)).await
+    else {
+        return Ok(JobStartResponse {
+            success: false,
+            job_id: None,
+            tool: None,
+            error: CatalogError::new(ErrorCode::UnknownIntent)
+                .with("intent", intents::normalize(&intent))
+                .into(),
+        });
+    };
+
+    if let Some(error) = read_only::refusal_for(&state, &tool).await {
+        return Ok(JobStartResponse {
+            success: false,
+            job_id: None,
+            tool: Some(tool),
+            error: error.into(),
+        });
+    }
+    if let Err(e) = rate_limit::check(&state, &tool).await {
+        return Ok(JobStartResponse {
+            success: false,
+            job_id: None,
+            tool: Some(tool),
+            error: CatalogError::from(&e).into(),
+        });
+    }
+
+    let job_id = state
+        .jobs
+        .lock()
+        .await
+        .create(&intent, &tool, Instant::now());
+    tauri::async_runtime::spawn(run_job(
+        app,
+        bridge,
+        job_id.clone(),
+        intent,
+        tool.clone(),
+        params.unwrap_or(json!({})),
+    ));
+
+    Ok(JobStartResponse {
+        success: true,
+        job_id: Some(job_id),
+        tool: Some(tool),
+        error: ResponseError::none(),
+    })
+}
+
+/// Cancel a running job
+#[tauri::command]
+pub async fn job_cancel(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<JobCancelResponse, String> {
+    let Some((info, request_id)) = state.jobs.lock().await.cancel(&job_id, Instant::now()) else {
+        return Ok(JobCancelResponse {
+            success: false,
+            cancelled: false,
+            job: None,
+            error: CatalogError::new(ErrorCode::JobNotFound)
+                .with("job_id", job_id.as_str())
+                .into(),
+        });
+    };
+
+    if let Some(request_id) = request_id {
+        let bridge = &state.bridge;
+        if let Err(e) = bridge.cancel_request(request_id, "Cancelled by user").await {
+            log::warn!("Failed to send cancellation for {}: {}", job_id, e);
+        }
+    }
+    emit_finished(&app, &info);
+
+    Ok(JobCancelResponse {
+        success: true,
+        cancelled: true,
+        job: Some(info),
+        error: ResponseError::none(),
+    })
+}
+
+/// Running and recently finished jobs
+#[tauri::command]
+pub async fn job_list(state: State<'_, AppState>) -> Result<JobListResponse, String> {
+    Ok(JobListResponse {
+        success: true,
+        jobs: state.jobs.lock().await.list(Instant::now()),
+    })
+}
@@ -0,0 +1,243 @@
+//! Mutation journal and undo commands
+//!
+//! `journal_list` / `journal_latest_for` read what this app changed.
+//! `tasks_undo` is two-phase: the preview names the backend's latest
+//! undoable operation (described from the journal when this window made
+//! it), the confirmation undoes it unless another change took its place.
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, State};
+
+use crate::ai_response::AIResponse;
+use crate::audit::{self, Audit};
+use crate::backend;
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::journal::{self, JournalEntry, Mutation};
+use crate::mutation_queue;
+use crate::read_only;
+use crate::AppState;
+
+const UNDO_TOOL: &str = "tasks_undo";
+/// History operations searched for the undo target
+const HISTORY_LIMIT: u64 = 50;
+/// Entries listed when no limit is given
+const DEFAULT_LIMIT: usize = 50;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct JournalResponse {
+    pub success: bool,
+    /// Newest first
+    pub entries: Vec<JournalEntry>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct JournalLatestResponse {
+    pub success: bool,
+    pub task_id: String,
+    pub entry: Option<JournalEntry>,
+    /// `Status change on TASK-042 (ACTIVE → DONE)`
+    pub description: Option<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// The backend history operation an undo would revert
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UndoTarget {
+    pub operation_id: String,
+    pub intent: Option<String>,
+    pub task_id: Option<String>,
+    pub timestamp: Option<String>,
+    pub description: String,
+    /// Whether this app's journal has it (else another client made it)
+    pub from_this_window: bool,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct UndoResponse {
+    pub success: bool,
+    pub preview: bool,
+    /// `None` when there is nothing to undo
+    pub target: Option<UndoTarget>,
+    pub undone: bool,
+    /// The `tasks_undo` answer
+    pub result: Option<AIResponse>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Journal entries, newest first, at most `limit` (50)
+#[tauri::command]
+pub async fn journal_list(
+    state: State<'_, AppState>,
+    limit: Option<usize>,
+) -> Result<JournalResponse, String> {
+    Ok(JournalResponse {
+        success: true,
+        entries: state
+            .journal
+            .lock()
+            .await
+            .list(limit.unwrap_or(DEFAULT_LIMIT)),
+        error: ResponseError::none(),
+    })
+}
+
+/// This app's latest change to `task_id`, for "last changed here" hints
+#[tauri::command]
+pub async fn journal_latest_for(
+    state: State<'_, AppState>,
+    task_id: String,
+) -> Result<JournalLatestResponse, String> {
+    let entry = state.journal.lock().await.latest_for(&task_id).cloned();
+    Ok(JournalLatestResponse {
+        success: true,
+        description: entry.as_ref().map(JournalEntry::describe),
+        task_id,
+        entry,
+        error: ResponseError::none(),
+    })
+}
+
+/// The latest history operation not undone yet, described
+async fn undo_target(state: &AppState) -> anyhow::Result<Option<UndoTarget>> {
+    let history = state
+        .bridge
+        .call_tool("tasks_history", json!({ "limit": HISTORY_LIMIT }))
+        .await?;
+    let history = backend::into_result(history)?;
+    let Some(op) = history
+        .get("operations")
+        .and_then(Value::as_array)
+        .and_then(|ops| {
+            ops.iter()
+                .rev()
+                .find(|op| !op.get("undone").and_then(Value::as_bool).unwrap_or(false))
+        })
+    else {
+        return Ok(None);
+    };
+    let Some(operation_id) = op.get("id").and_then(journal::id_text) else {
+        return Ok(None);
+    };
+    let text = |key: &str| op.get(key).and_then(Value::as_str).map(String::from);
+    let (intent, task_id) = (text("intent"), text("task_id"));
+    let journal = state.journal.lock().await;
+    let entry = journal.by_history_id(&operation_id);
+    let description = match entry {
+        Some(entry) => entry.describe(),
+        None => journal::describe(intent.as_deref().unwrap_or("change"), task_id.as_deref()),
+    };
+    Ok(Some(UndoTarget {
+        from_this_window: entry.is_some(),
+        operation_id,
+        intent,
+        task_id,
+        timestamp: text("timestamp"),
+        description,
+    }))
+}
+
+/// Undo the backend's latest operation. With `preview`, only name it
+/// ("Undo: status change on TASK-042 (ACTIVE → DONE)"); pass its
+/// `operation_id` back to undo exactly that one.
+#[tauri::command]
+pub async fn tasks_undo(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    preview: Option<bool>,
+    operation_id: Option<String>,
+) -> Result<UndoResponse, String> {
+    let response = undo_response(Some(&app), &state, preview.unwrap_or(false), operation_id).await;
+    state
+        .usage_metrics
+        .record_outcome("tasks_undo", response.success);
+    Ok(response)
+}
+
+pub(crate) async fn undo_response(
+    app: Option<&AppHandle>,
+    state: &AppState,
+    preview: bool,
+    operation_id: Option<String>,
+) -> UndoResponse {
+    let mut response = UndoResponse {
+        success: false,
+        preview,
+        target: None,
+        undone: false,
+        result: None,
+        error: ResponseError::none(),
+    };
+    response.target = match undo_target(state).await {
+        Ok(target) => target,
+        Err(e) => {
+            response.error = ResponseError::from(&e);
+            return response;
+        }
+    };
+    if preview {
+        response.success = true;
+        return response;
+    }
+    if let Some(error) = read_only::refusal(state).await {
+        response.error = error.into();
+        return response;
+    }
+    if let Some(previewed) = operation_id {
+        let current = response.target.as_ref().map(|t| t.operation_id.as_str());
+        if current != Some(previewed.as_str()) {
+            response.error = CatalogError::new(ErrorCode::UndoTargetChanged)
+                .with("previewed", previewed.as_str())
+                .into();
+            return response;
+        }
+    }
+
+    if let Some(app) = app {
+        if state.mutation_queue.lock().await.has_untried() {
+            mutation_queue::replay_queue(app).await;
+        }
+    }
+    state.storage_watch.mark_own_write();
+    // Not queued offline: the target may be another one by the time it runs
+    let result = match state.bridge.call_tool(UNDO_TOOL, json!({})).await {
+        Ok(result) => AIResponse::from_value(result),
+        Err(e) => AIResponse::bridge_error("undo", e.to_string()),
+    };
+    state.storage_watch.mark_own_write();
+
+    let task_id = response.target.as_ref().and_then(|t| t.task_id.clone());
+    if result.success {
+        let mut cache = state.read_cache.lock().await;
+        match &task_id {
+            Some(task_id) => cache.invalidate(std::slice::from_ref(task_id)),
+            None => cache.clear(),
+        }
+    }
+    let description = response.target.as_ref().map(|t| t.description.clone());
+    journal::record(
+        state,
+        &result,
+        Mutation::new(UNDO_TOOL, task_id.clone()).change(None, description),
+    )
+    .await;
+    let params = json!({ "operation_id": response.target.as_ref().map(|t| &t.operation_id) });
+    audit::record(
+        state,
+        Audit::new(UNDO_TOOL, task_id)
+            .params(&params)
+            .response(&result),
+    )
+    .await;
+
+    response.success = result.success;
+    response.undone = result.success;
+    response.error = (!result.success)
+        .then(|| CatalogError::from_envelope(result.error.as_ref(), "Failed to undo"))
+        .into();
+    response.result = Some(result);
+    response
+}
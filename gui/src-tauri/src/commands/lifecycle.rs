@@ -0,0 +1,27 @@
+//! App startup and exit commands
+
+use std::sync::atomic::Ordering;
+
+use tauri::{AppHandle, State};
+
+use crate::cli::StartupIntent;
+use crate::AppState;
+
+/// Quit even though a write is in flight (answer to `exit-confirm-requested`)
+#[tauri::command]
+pub async fn confirm_exit(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    state.exit_confirmed.store(true, Ordering::Relaxed);
+    app.exit(0);
+    Ok(())
+}
+
+/// `--task`/`--namespace` given at launch; returned once, so a webview reload
+/// doesn't navigate again
+#[tauri::command]
+pub async fn startup_intent(state: State<'_, AppState>) -> Result<Option<StartupIntent>, String> {
+    Ok(state
+        .startup_intent
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take())
+}
@@ -0,0 +1,58 @@
+//! Task link commands
+
+use tauri::{AppHandle, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::deep_link;
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct CopyLinkResponse {
+    pub success: bool,
+    pub link: Option<String>,
+    /// Whether the link made it to the system clipboard
+    pub copied: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Build an `apply-task://task/<namespace>/<id>` link and copy it to the clipboard.
+///
+/// `namespace` defaults to the current project's namespace.
+#[tauri::command]
+pub async fn tasks_copy_link(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    task_id: String,
+    namespace: Option<String>,
+) -> Result<CopyLinkResponse, String> {
+    let namespace = match namespace.filter(|n| !n.trim().is_empty()) {
+        Some(namespace) => Some(namespace),
+        None => deep_link::current_namespace(&state).await,
+    };
+    let Some(namespace) = namespace else {
+        return Ok(CopyLinkResponse {
+            success: false,
+            link: None,
+            copied: false,
+            error: CatalogError::new(ErrorCode::ProjectNamespaceUnknown).into(),
+        });
+    };
+
+    let link = deep_link::build_task_link(namespace.trim(), task_id.trim());
+    let copied = app.clipboard().write_text(link.clone());
+    if let Err(e) = &copied {
+        log::warn!("Failed to copy task link: {}", e);
+    }
+
+    Ok(CopyLinkResponse {
+        success: true,
+        copied: copied.is_ok(),
+        error: copied
+            .err()
+            .map(|e| CatalogError::new(ErrorCode::ClipboardFailed).with("detail", e.to_string()))
+            .into(),
+        link: Some(link),
+    })
+}
@@ -2,6 +2,79 @@
 //!
 //! Exposes Python bridge functionality to the React frontend.
 
+mod ai;
+mod audit;
+mod board;
+mod checklist;
+mod commits;
+mod delete;
+mod diagnostics;
+mod due;
+mod duplicates;
+mod edit;
+mod faults;
+mod fuzzy;
+mod jobs;
+mod journal;
+mod lifecycle;
+mod link;
+mod progress;
+mod projects;
+mod queue;
+mod quick_add;
+mod report;
+mod search;
+mod session;
+mod settings;
+mod status;
+mod storage;
+mod stream;
+mod suggestions;
 mod task;
+mod templates;
+mod timeline;
+mod timer;
+mod todos;
+mod updates;
+mod verify;
+mod window;
 
+pub use ai::*;
+pub use audit::*;
+pub use board::*;
+pub use checklist::*;
+pub use commits::*;
+pub use delete::*;
+pub use diagnostics::*;
+pub use due::*;
+pub use duplicates::*;
+pub use edit::*;
+pub use faults::*;
+pub use fuzzy::*;
+pub use jobs::*;
+pub use journal::*;
+pub use lifecycle::*;
+pub use link::*;
+pub use progress::*;
+pub use projects::*;
+pub use queue::*;
+pub use quick_add::*;
+pub use report::*;
+pub use search::*;
+pub use session::*;
+pub use settings::*;
+pub use status::*;
+pub use storage::*;
+pub use stream::*;
+pub use suggestions::*;
 pub use task::*;
+pub use templates::*;
+pub use timeline::*;
+pub use timer::*;
+pub use todos::*;
+pub use updates::*;
+pub use verify::*;
+pub use window::*;
+
+#[cfg(test)]
+mod tests;
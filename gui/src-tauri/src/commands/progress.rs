@@ -0,0 +1,97 @@
+//! Subtask progress stream commands
+//!
+//! `tasks_progress_stream_start` follows a subtask for the calling window
+//! (see [`crate::subtask_progress`]); entries arrive as `subtask-progress`
+//! events, the backlog so far comes with the response.
+
+use tauri::{AppHandle, State, Window};
+
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::subtask_progress::{self, StreamMode, SubtaskProgress};
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ProgressStreamResponse {
+    pub success: bool,
+    pub task_id: String,
+    pub path: String,
+    /// `push` (backend notifications) or `poll`
+    pub mode: Option<StreamMode>,
+    /// Entries of this session so far, oldest first
+    pub backlog: Vec<SubtaskProgress>,
+    /// Stop: whether the window was following
+    pub stopped: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Follow the subtask at `path` of `task_id` in the calling window
+#[tauri::command]
+pub async fn tasks_progress_stream_start(
+    app: AppHandle,
+    window: Window,
+    state: State<'_, AppState>,
+    task_id: String,
+    path: String,
+) -> Result<ProgressStreamResponse, String> {
+    let (task_id, path) = (task_id.trim().to_string(), path.trim().to_string());
+    let mut response = ProgressStreamResponse {
+        success: false,
+        task_id: task_id.clone(),
+        path: path.clone(),
+        mode: None,
+        backlog: Vec::new(),
+        stopped: false,
+        error: ResponseError::none(),
+    };
+    if task_id.is_empty() {
+        response.error = CatalogError::new(ErrorCode::ValidationTaskIdRequired).into();
+        return Ok(response);
+    }
+    // An unreachable backend can't push; polling picks up once it is back
+    let push = state.bridge.connect().await.is_ok() && state.bridge.server_capability("logging");
+    let mode = if push {
+        StreamMode::Push
+    } else {
+        StreamMode::Poll
+    };
+    let (backlog, mode) =
+        state
+            .progress_streams
+            .follow(&task_id, &path, window.label(), mode, || {
+                tauri::async_runtime::spawn(subtask_progress::run(
+                    app.clone(),
+                    task_id.clone(),
+                    path.clone(),
+                    mode,
+                ))
+            });
+    response.success = true;
+    response.mode = Some(mode);
+    response.backlog = backlog;
+    Ok(response)
+}
+
+/// Stop following the subtask in the calling window (the stream ends with
+/// its last window; the backlog stays for this session)
+#[tauri::command]
+pub async fn tasks_progress_stream_stop(
+    window: Window,
+    state: State<'_, AppState>,
+    task_id: String,
+    path: String,
+) -> Result<ProgressStreamResponse, String> {
+    let (task_id, path) = (task_id.trim().to_string(), path.trim().to_string());
+    let stopped = state
+        .progress_streams
+        .unfollow(&task_id, &path, window.label());
+    Ok(ProgressStreamResponse {
+        success: true,
+        task_id,
+        path,
+        mode: None,
+        backlog: Vec::new(),
+        stopped,
+        error: ResponseError::none(),
+    })
+}
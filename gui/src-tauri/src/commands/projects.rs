@@ -0,0 +1,160 @@
+//! Project registry commands
+//!
+//! `projects_open` is the project switch: the bridge respawns in the new
+//! directory and per-project state (read cache, signal history, mutation
+//! queue, storage watcher) follows it. A running timer is stopped into the
+//! project being left, and pending AI signals are dropped.
+
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+
+use tauri::{AppHandle, State};
+
+use crate::ai_signals::AiSignalInbox;
+use crate::env_info::ConfigSource;
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::journal::JOURNAL_FILE;
+use crate::mutation_queue::{MutationQueue, QUEUE_FILE};
+use crate::projects::{self, ProjectInfo};
+use crate::signals::{SignalLog, SIGNALS_FILE};
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ProjectsResponse {
+    pub success: bool,
+    pub projects: Vec<ProjectInfo>,
+    /// Directory the backend runs in
+    pub current: String,
+    /// Launched without a project hint and nothing opened yet
+    pub needs_selection: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ProjectOpenResponse {
+    pub success: bool,
+    pub path: Option<String>,
+    /// The backend was restarted in the new project
+    pub restarted: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+async fn projects_response(state: &AppState, error: ResponseError) -> ProjectsResponse {
+    let current = state.user_cwd();
+    ProjectsResponse {
+        success: error.is_none(),
+        projects: state.projects.lock().await.list(&current),
+        current: current.to_string_lossy().to_string(),
+        needs_selection: state.needs_project.load(Ordering::Relaxed),
+        error,
+    }
+}
+
+/// Known projects, most recently opened first
+#[tauri::command]
+pub async fn projects_list(state: State<'_, AppState>) -> Result<ProjectsResponse, String> {
+    Ok(projects_response(&state, ResponseError::none()).await)
+}
+
+/// Register a project (the directory itself or a project directly inside it)
+#[tauri::command]
+pub async fn projects_add(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<ProjectsResponse, String> {
+    let added = state.projects.lock().await.add(&PathBuf::from(path));
+    let error = added.err().map(|e| CatalogError::from_anyhow(&e));
+    Ok(projects_response(&state, error.into()).await)
+}
+
+/// Forget a project (its task storage is left alone)
+#[tauri::command]
+pub async fn projects_remove(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<ProjectsResponse, String> {
+    let error = match state.projects.lock().await.remove(&PathBuf::from(&path)) {
+        Ok(true) => None,
+        Ok(false) => Some(CatalogError::new(ErrorCode::ProjectNotRegistered).with("path", path)),
+        Err(e) => Some(CatalogError::from_anyhow(&e)),
+    };
+    Ok(projects_response(&state, error.into()).await)
+}
+
+/// Switch the backend to another project, registering it if needed
+#[tauri::command]
+pub async fn projects_open(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<ProjectOpenResponse, String> {
+    let response = open_response(&app, &state, path).await;
+    state
+        .usage_metrics
+        .record_outcome("projects_open", response.success);
+    Ok(response)
+}
+
+pub(crate) async fn open_response(
+    app: &AppHandle,
+    state: &AppState,
+    path: String,
+) -> ProjectOpenResponse {
+    let Some(root) = projects::project_root(&PathBuf::from(&path)) else {
+        return ProjectOpenResponse {
+            success: false,
+            path: None,
+            restarted: false,
+            error: CatalogError::new(ErrorCode::ProjectNotFound)
+                .with("path", path)
+                .into(),
+        };
+    };
+
+    let previous_dir = state.project_dir();
+    let restarted = match state.bridge.set_user_cwd(root.clone()).await {
+        Ok(restarted) => restarted,
+        Err(e) => {
+            return ProjectOpenResponse {
+                success: false,
+                path: Some(root.to_string_lossy().to_string()),
+                restarted: false,
+                error: ResponseError::from(&e),
+            }
+        }
+    };
+    *state.user_cwd.lock().unwrap_or_else(|e| e.into_inner()) = root.clone();
+    *state
+        .user_cwd_source
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = ConfigSource::Selected;
+    state.needs_project.store(false, Ordering::Relaxed);
+
+    if restarted {
+        super::timer::stop_for_project_switch(state, &previous_dir).await;
+        *state.ai_signals.lock().await = AiSignalInbox::default();
+        let project_dir = state.project_dir();
+        state.read_cache.lock().await.clear();
+        *state.signals.lock().await = SignalLog::load(project_dir.join(SIGNALS_FILE));
+        *state.mutation_queue.lock().await = MutationQueue::load(project_dir.join(QUEUE_FILE));
+        state
+            .journal
+            .lock()
+            .await
+            .restart(project_dir.join(JOURNAL_FILE));
+        state.rate_limiter.lock().await.reset();
+        super::storage::refollow_storage(app, state).await;
+    }
+    if let Err(e) = state.projects.lock().await.touch(&root) {
+        log::warn!("Failed to update project registry: {:#}", e);
+    }
+
+    ProjectOpenResponse {
+        success: true,
+        path: Some(root.to_string_lossy().to_string()),
+        restarted,
+        error: ResponseError::none(),
+    }
+}
@@ -0,0 +1,87 @@
+//! Offline mutation queue commands
+//!
+//! Inspect and prune the queue, send rejected entries again, and restart
+//! the backend by hand (which also replays whatever is queued).
+
+use tauri::{AppHandle, State};
+
+use crate::error_catalog::{CatalogError, ResponseError};
+use crate::mutation_queue::{self, QueuedMutation};
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct MutationQueueResponse {
+    pub success: bool,
+    /// Whether failing mutations are queued (`offline_queue` setting)
+    pub enabled: bool,
+    pub entries: Vec<QueuedMutation>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct BridgeRestartResponse {
+    pub success: bool,
+    /// Queued mutations still waiting after the replay
+    pub queued: usize,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+async fn queue_response(state: &AppState, error: ResponseError) -> MutationQueueResponse {
+    MutationQueueResponse {
+        success: error.is_none(),
+        enabled: state.settings.read().await.offline_queue,
+        entries: state.mutation_queue.lock().await.entries().to_vec(),
+        error,
+    }
+}
+
+/// Queued mutations in replay order
+#[tauri::command]
+pub async fn mutation_queue_list(
+    state: State<'_, AppState>,
+) -> Result<MutationQueueResponse, String> {
+    Ok(queue_response(&state, ResponseError::none()).await)
+}
+
+/// Drop the queued mutation at `index` (e.g. one the backend keeps rejecting)
+#[tauri::command]
+pub async fn mutation_queue_discard(
+    state: State<'_, AppState>,
+    index: usize,
+) -> Result<MutationQueueResponse, String> {
+    let result = state.mutation_queue.lock().await.discard(index);
+    let error = result.err().map(|e| CatalogError::from_anyhow(&e));
+    Ok(queue_response(&state, error.into()).await)
+}
+
+/// Replay the queue now, sending mutations the backend rejected before again
+#[tauri::command]
+pub async fn mutation_queue_retry(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<MutationQueueResponse, String> {
+    mutation_queue::retry_queue(&app).await;
+    Ok(queue_response(&state, ResponseError::none()).await)
+}
+
+/// Restart the Python backend, then replay queued mutations
+#[tauri::command]
+pub async fn bridge_restart(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<BridgeRestartResponse, String> {
+    let restarted = state.bridge.restart().await;
+    state.read_cache.lock().await.clear();
+    mutation_queue::replay_queue(&app).await;
+
+    Ok(BridgeRestartResponse {
+        success: restarted.is_ok(),
+        queued: state.mutation_queue.lock().await.entries().len(),
+        error: restarted
+            .err()
+            .map(|e| CatalogError::from_anyhow(&e))
+            .into(),
+    })
+}
@@ -0,0 +1,123 @@
+//! Quick-add commands
+//!
+//! Used by the quick-add window and the inline new-task field: preview how
+//! a line parses, create a task from it, and close the window when done.
+//! The main window reads the shortcut state to report a registration
+//! failure.
+
+use tauri::{AppHandle, State, WebviewWindow};
+
+use crate::deep_link;
+use crate::error_catalog::{CatalogError, ResponseError};
+use crate::quick_add::{self, ShortcutStatus};
+use crate::quick_parse::{self, ParsedTask};
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct QuickCreateResponse {
+    pub success: bool,
+    pub task_id: Option<String>,
+    pub title: Option<String>,
+    /// Created, but tags, domain or due date could not be set
+    pub warning: Option<String>,
+    /// Markers the parser kept in the title
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ParseQuickTaskResponse {
+    pub success: bool,
+    pub task: Option<ParsedTask>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// How a quick-add line parses, for a live preview (nothing is created)
+#[tauri::command]
+pub fn parse_quick_task(text: String) -> ParseQuickTaskResponse {
+    match quick_parse::quick_parse(&text) {
+        Ok(task) => ParseQuickTaskResponse {
+            success: true,
+            task: Some(task),
+            error: ResponseError::none(),
+        },
+        Err(e) => ParseQuickTaskResponse {
+            success: false,
+            task: None,
+            error: CatalogError::from(&e).into(),
+        },
+    }
+}
+
+/// Create a task from a quick-add line (`Fix login #auth !high @backend`);
+/// an `ns:` marker must name the open project's namespace
+#[tauri::command]
+pub async fn quick_create(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    text: String,
+) -> Result<QuickCreateResponse, String> {
+    let response = quick_create_response(Some(&app), &state, &text).await;
+    state
+        .usage_metrics
+        .record_outcome("quick_create", response.success);
+    Ok(response)
+}
+
+/// `quick_create` (also run by `--headless`, without `app`)
+pub(crate) async fn quick_create_response(
+    app: Option<&AppHandle>,
+    state: &AppState,
+    text: &str,
+) -> QuickCreateResponse {
+    let fail = |error: ResponseError| QuickCreateResponse {
+        success: false,
+        task_id: None,
+        title: None,
+        warning: None,
+        warnings: Vec::new(),
+        error,
+    };
+    let task = match quick_parse::quick_parse(text) {
+        Ok(task) => task,
+        Err(e) => return fail(CatalogError::from(&e).into()),
+    };
+    if let Some(namespace) = &task.namespace {
+        if let Some(error) = deep_link::namespace_error(state, namespace).await {
+            return fail(error.into());
+        }
+    }
+    match quick_add::create(app, state, task).await {
+        Ok(created) => QuickCreateResponse {
+            success: true,
+            task_id: Some(created.task_id),
+            title: Some(created.task.title),
+            warning: created.warning,
+            warnings: created.task.warnings,
+            error: ResponseError::none(),
+        },
+        Err(e) => fail(ResponseError::from(&e)),
+    }
+}
+
+/// Close the quick-add window (Escape, focus loss, after creating)
+#[tauri::command]
+pub fn quick_add_dismiss(window: WebviewWindow) -> Result<(), String> {
+    if window.label() != quick_add::WINDOW_LABEL {
+        return Err(format!("Not the quick-add window: {}", window.label()));
+    }
+    window.close().map_err(|e| e.to_string())
+}
+
+/// Configured shortcut and whether the OS accepted it
+#[tauri::command]
+pub fn quick_add_status(state: State<'_, AppState>) -> ShortcutStatus {
+    state
+        .quick_add
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
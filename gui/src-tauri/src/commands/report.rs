@@ -0,0 +1,135 @@
+//! Report commands
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use chrono::{DateTime, Local, Utc};
+use tauri::State;
+
+use crate::backend::ListFilters;
+use crate::deep_link;
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::git::{self, LogQuery};
+use crate::report::{self, ReportPeriod};
+use crate::AppState;
+
+/// Most commits listed in a report
+const COMMIT_LIMIT: usize = 200;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ReportResponse {
+    pub success: bool,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub markdown: String,
+    /// Where the report was written, when asked to
+    pub path: Option<String>,
+    pub completed: usize,
+    pub in_progress: usize,
+    pub created: usize,
+    /// Commits were asked for but git history is not available (the
+    /// report has no commits section)
+    pub git_unavailable: Option<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+impl ReportResponse {
+    fn failed(
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        error: ResponseError,
+    ) -> Self {
+        Self {
+            success: false,
+            from,
+            to,
+            markdown: String::new(),
+            path: None,
+            completed: 0,
+            in_progress: 0,
+            created: 0,
+            git_unavailable: None,
+            error,
+        }
+    }
+}
+
+/// Markdown report of what was completed, is in progress and was created
+/// in `period`, optionally also written to `path` (relative to the project
+/// directory). With `include_commits`, the period's commits are listed too.
+#[tauri::command]
+pub async fn tasks_report(
+    state: State<'_, AppState>,
+    period: ReportPeriod,
+    namespace: Option<String>,
+    path: Option<String>,
+    include_commits: Option<bool>,
+) -> Result<ReportResponse, String> {
+    let (from, to) = match period.bounds(Local::now()) {
+        Ok(bounds) => bounds,
+        Err(raw) => {
+            let error = CatalogError::new(ErrorCode::ValidationInvalidDate).with("value", raw);
+            return Ok(ReportResponse::failed(None, None, error.into()));
+        }
+    };
+    let fail = |error: ResponseError| ReportResponse::failed(Some(from), Some(to), error);
+
+    if let Some(namespace) = &namespace {
+        if let Some(error) = deep_link::namespace_error(&state, namespace).await {
+            return Ok(fail(error.into()));
+        }
+    }
+
+    // Full payloads: compact ones carry no timestamps, tags or priority
+    let tasks =
+        match super::task::load_task_list(&state, &ListFilters::default(), false, None, None, None)
+            .await
+        {
+            Ok((tasks, _)) => tasks,
+            Err(e) => return Ok(fail(ResponseError::from(&e))),
+        };
+    let operations = super::timeline::history_operations(&state)
+        .await
+        .unwrap_or_default();
+    let mut report = report::gather(&tasks, &operations, from, to, &Local);
+    let mut git_unavailable = None;
+    if include_commits.unwrap_or(false) {
+        let query = LogQuery {
+            since: Some(from),
+            until: Some(to),
+            mentioning: None,
+            limit: COMMIT_LIMIT,
+        };
+        match git::log(&state.user_cwd(), &query).await {
+            Ok(commits) => report.commits = Some(commits),
+            Err(reason) => git_unavailable = Some(reason.to_string()),
+        }
+    }
+    let markdown = report::render(&report, &Local);
+
+    let path: Option<PathBuf> = path
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| state.user_cwd().join(p));
+    if let Some(path) = &path {
+        let written =
+            std::fs::write(path, &markdown).with_context(|| format!("Failed to write {:?}", path));
+        if let Err(e) = written {
+            return Ok(fail(ResponseError::from(&e)));
+        }
+    }
+    Ok(ReportResponse {
+        success: true,
+        from: Some(from),
+        to: Some(to),
+        markdown,
+        path: path.map(|p| p.to_string_lossy().into_owned()),
+        completed: report.completed.len(),
+        in_progress: report.in_progress.len(),
+        created: report.created.len(),
+        git_unavailable,
+        error: ResponseError::none(),
+    })
+}
@@ -0,0 +1,129 @@
+//! Full-text search command
+//!
+//! Answers from the search index when it matches the read cache's
+//! generation; otherwise from a title/id substring match while the index is
+//! rebuilt in the background.
+
+use std::time::Instant;
+
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State};
+
+use crate::backend::ListFilters;
+use crate::error_catalog::ResponseError;
+use crate::search_index::{self, SearchHit, SearchIndex, SearchSource};
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SearchResponse {
+    pub success: bool,
+    pub query: String,
+    pub hits: Vec<SearchHit>,
+    /// `index`, or `fallback` while the index is cold
+    pub source: SearchSource,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Ranked full-text hits for `query` (titles, descriptions, progress notes)
+#[tauri::command]
+pub async fn tasks_search_indexed(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<SearchResponse, String> {
+    let (response, rebuild) = search_response(&state, &query, limit).await;
+    state
+        .usage_metrics
+        .record_outcome("tasks_search_indexed", response.success);
+    if let Some((generation, tasks)) = rebuild {
+        spawn_rebuild(app, generation, tasks);
+    }
+    Ok(response)
+}
+
+/// The response, plus the tasks to index when the index was cold and this
+/// call claimed its rebuild
+pub(crate) async fn search_response(
+    state: &AppState,
+    query: &str,
+    limit: Option<usize>,
+) -> (SearchResponse, Option<(u64, Vec<Value>)>) {
+    let limit = limit.unwrap_or(search_index::DEFAULT_LIMIT);
+    let respond = |hits, source, error| SearchResponse {
+        success: true,
+        query: query.to_string(),
+        hits,
+        source,
+        error,
+    };
+
+    let generation = state.read_cache.lock().await.generation();
+    let fresh = state
+        .search_index
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .fresh(generation);
+    if let Some(index) = fresh {
+        let hits = index.search(query, limit);
+        return (
+            respond(hits, SearchSource::Index, ResponseError::none()),
+            None,
+        );
+    }
+
+    let tasks =
+        match super::task::load_task_list(state, &ListFilters::default(), false, None, None, None)
+            .await
+        {
+            Ok((tasks, _)) => tasks,
+            Err(e) => {
+                let mut response =
+                    respond(Vec::new(), SearchSource::Fallback, ResponseError::from(&e));
+                response.success = false;
+                return (response, None);
+            }
+        };
+    let hits = search_index::substring(&tasks, query, limit);
+    let claimed = state
+        .search_index
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .start_build(generation);
+    (
+        respond(hits, SearchSource::Fallback, ResponseError::none()),
+        claimed.then_some((generation, tasks)),
+    )
+}
+
+/// Build the index on the blocking pool and store it in the app state
+fn spawn_rebuild(app: AppHandle, generation: u64, tasks: Vec<Value>) {
+    tauri::async_runtime::spawn(async move {
+        let started = Instant::now();
+        let built =
+            tauri::async_runtime::spawn_blocking(move || SearchIndex::build(&tasks, generation))
+                .await;
+        let built = match built {
+            Ok(index) => {
+                log::debug!(
+                    "Indexed {} tasks for search in {:?}",
+                    index.len(),
+                    started.elapsed()
+                );
+                Some(index)
+            }
+            Err(e) => {
+                log::warn!("Search index build failed: {}", e);
+                None
+            }
+        };
+        if let Some(state) = app.try_state::<AppState>() {
+            state
+                .search_index
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .finish(generation, built);
+        }
+    });
+}
@@ -0,0 +1,184 @@
+//! Session restore commands
+//!
+//! `session_get` runs once at startup. With no project given on the command
+//! line it reopens the saved one; then the namespace and task are checked
+//! against the open project. Whatever no longer exists is dropped from the
+//! saved session and the rest comes back with `restored_partial`. A backend
+//! that can't be reached proves nothing, so it prunes nothing.
+
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use serde_json::Value;
+use tauri::{AppHandle, State};
+
+use crate::backend;
+use crate::error_catalog::{CatalogError, ResponseError};
+use crate::projects;
+use crate::python::BridgeError;
+use crate::session::{self, Session};
+use crate::sidecar;
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SessionResponse {
+    pub success: bool,
+    pub session: Session,
+    /// Parts of the saved session were dropped as stale
+    pub restored_partial: bool,
+    /// `session_get` reopened the saved project
+    pub project_opened: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+impl SessionResponse {
+    fn ok(session: Session) -> Self {
+        Self {
+            success: true,
+            session,
+            restored_partial: false,
+            project_opened: false,
+            error: ResponseError::none(),
+        }
+    }
+}
+
+fn same_project(a: &Path, b: &Path) -> bool {
+    sidecar::project_key(a) == sidecar::project_key(b)
+}
+
+/// Whether `namespace` is known to the open project's storage
+pub(crate) async fn namespace_exists(state: &AppState, namespace: &str) -> Option<bool> {
+    let storage = super::storage::fetch_storage(state).await.ok()?;
+    Some(
+        storage.namespace.as_deref() == Some(namespace)
+            || storage
+                .namespaces
+                .iter()
+                .any(|ns| ns.namespace == namespace),
+    )
+}
+
+/// Whether `task_id` resolves in the open project
+async fn task_exists(state: &AppState, task_id: &str) -> Option<bool> {
+    match backend::show_task(&state.bridge, task_id).await {
+        Ok(_) => Some(true),
+        Err(e) if BridgeError::is_transport(&e) => None,
+        Err(_) => Some(false),
+    }
+}
+
+/// The saved session, checked (and without an `app`, never reopening the
+/// project)
+pub(crate) async fn restore_response(app: Option<&AppHandle>, state: &AppState) -> SessionResponse {
+    let saved = state.session.get();
+    let mut session = saved.clone();
+    let mut project_opened = false;
+
+    // Namespace and task only mean something in their own project
+    let mut in_project = true;
+    if let Some(project) = saved.project.as_deref() {
+        match projects::project_root(Path::new(project)) {
+            None => {
+                session.project = None;
+                in_project = false;
+            }
+            Some(root) if !same_project(&root, &state.user_cwd()) => {
+                let reopen = app.filter(|_| state.needs_project.load(Ordering::Relaxed));
+                match reopen {
+                    Some(app) => {
+                        let opened =
+                            super::projects::open_response(app, state, project.to_string()).await;
+                        project_opened = opened.success;
+                        in_project = opened.success;
+                        if !opened.success {
+                            log::warn!("Failed to reopen {}: {:?}", project, opened.error);
+                        }
+                    }
+                    // Launched on another project: that one wins
+                    None => in_project = false,
+                }
+            }
+            Some(_) => {}
+        }
+    }
+    if !in_project {
+        session.namespace = None;
+        session.task_id = None;
+    }
+    if let Some(namespace) = session.namespace.clone() {
+        if namespace_exists(state, &namespace).await == Some(false) {
+            session.namespace = None;
+        }
+    }
+    if let Some(task_id) = session.task_id.clone() {
+        if task_exists(state, &task_id).await == Some(false) {
+            session.task_id = None;
+        }
+    }
+
+    let restored_partial = session != saved;
+    if restored_partial {
+        log::info!("Dropped stale parts of the saved session: {:?}", saved);
+        state.session.set(session.clone());
+        if let Err(e) = state.session.save() {
+            log::warn!("Failed to save session: {:#}", e);
+        }
+    }
+    SessionResponse {
+        restored_partial,
+        project_opened,
+        ..SessionResponse::ok(session)
+    }
+}
+
+/// The saved session to restore at startup, stale parts dropped
+#[tauri::command]
+pub async fn session_get(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<SessionResponse, String> {
+    let response = restore_response(Some(&app), &state).await;
+    state
+        .usage_metrics
+        .record_outcome("session_get", response.success);
+    Ok(response)
+}
+
+/// Merge `partial_state` (`project`, `namespace`, `task_id`, `view`; `null`
+/// clears one) into the session; saved once navigation settles
+#[tauri::command]
+pub async fn session_update(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    partial_state: Value,
+) -> Result<SessionResponse, String> {
+    let session = match state.session.get().merged(&partial_state) {
+        Ok(session) => session,
+        Err(e) => {
+            return Ok(SessionResponse {
+                success: false,
+                error: CatalogError::from(&e).into(),
+                ..SessionResponse::ok(state.session.get())
+            })
+        }
+    };
+    if let Some(generation) = state.session.set(session.clone()) {
+        session::save_later(&app, generation);
+    }
+    Ok(SessionResponse::ok(session))
+}
+
+/// Forget the saved session
+#[tauri::command]
+pub async fn session_clear(state: State<'_, AppState>) -> Result<SessionResponse, String> {
+    Ok(match state.session.clear() {
+        Ok(()) => SessionResponse::ok(Session::default()),
+        Err(e) => SessionResponse {
+            success: false,
+            error: ResponseError::from(&e),
+            ..SessionResponse::ok(Session::default())
+        },
+    })
+}
@@ -0,0 +1,295 @@
+//! Settings commands
+//!
+//! Settings are read from `settings.json` at startup and can be changed at
+//! runtime; changes apply to the next call that reads them. A changed
+//! `python_path` restarts the backend, a changed `quick_add_shortcut` or
+//! `pause_all_shortcut` is registered again. Turning `developer_mode` off disarms fault injection.
+//! Switching `read_only` is announced to every window.
+
+use serde_json::Value;
+use tauri::{AppHandle, State};
+
+use crate::ai_pause;
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::intents::{self, UserAliases};
+use crate::notifications::NotificationPrefs;
+use crate::quick_add;
+use crate::read_only;
+use crate::settings::Settings;
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SettingsResponse {
+    pub success: bool,
+    pub settings: Settings,
+    /// The backend was restarted to apply the change
+    pub restarted: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct IntentAliasesResponse {
+    pub success: bool,
+    pub aliases: UserAliases,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ReadOnlyResponse {
+    pub success: bool,
+    pub read_only: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct NotificationPrefsResponse {
+    pub success: bool,
+    pub prefs: NotificationPrefs,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Current user aliases (`reload` re-reads the settings file first)
+#[tauri::command]
+pub async fn get_intent_aliases(
+    state: State<'_, AppState>,
+    reload: Option<bool>,
+) -> Result<IntentAliasesResponse, String> {
+    let mut settings = state.settings.write().await;
+    if reload.unwrap_or(false) {
+        *settings = Settings::load(&state.config_dir);
+    }
+
+    Ok(IntentAliasesResponse {
+        success: true,
+        aliases: settings.intent_aliases.clone(),
+        error: ResponseError::none(),
+    })
+}
+
+/// Replace the user alias map and persist it
+#[tauri::command]
+pub async fn set_intent_aliases(
+    state: State<'_, AppState>,
+    aliases: UserAliases,
+    confirm_destructive: Option<bool>,
+) -> Result<IntentAliasesResponse, String> {
+    let mut settings = state.settings.write().await;
+
+    let result = intents::validate_aliases(&aliases, confirm_destructive.unwrap_or(false))
+        .and_then(|aliases| {
+            let mut updated = settings.clone();
+            updated.intent_aliases = aliases;
+            updated.save(&state.config_dir)?;
+            Ok(updated)
+        });
+
+    match result {
+        Ok(updated) => {
+            *settings = updated;
+            Ok(IntentAliasesResponse {
+                success: true,
+                aliases: settings.intent_aliases.clone(),
+                error: ResponseError::none(),
+            })
+        }
+        Err(e) => Ok(IntentAliasesResponse {
+            success: false,
+            aliases: settings.intent_aliases.clone(),
+            error: ResponseError::from(&e),
+        }),
+    }
+}
+
+/// Whether mutations are refused
+#[tauri::command]
+pub async fn get_read_only(state: State<'_, AppState>) -> Result<ReadOnlyResponse, String> {
+    Ok(ReadOnlyResponse {
+        success: true,
+        read_only: read_only::enabled(&state).await,
+        error: ResponseError::none(),
+    })
+}
+
+/// Turn read-only mode on or off and persist it (`read-only-changed` is
+/// emitted when it changes)
+#[tauri::command]
+pub async fn set_read_only(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<ReadOnlyResponse, String> {
+    let mut settings = state.settings.write().await;
+
+    let mut updated = settings.clone();
+    updated.read_only = enabled;
+    if let Err(e) = updated.save(&state.config_dir) {
+        return Ok(ReadOnlyResponse {
+            success: false,
+            read_only: settings.read_only,
+            error: ResponseError::from(&e),
+        });
+    }
+    let changed = settings.read_only != enabled;
+    *settings = updated;
+    drop(settings);
+    if changed {
+        read_only::emit_changed(&app, enabled);
+    }
+    Ok(ReadOnlyResponse {
+        success: true,
+        read_only: enabled,
+        error: ResponseError::none(),
+    })
+}
+
+/// Which OS notifications are sent
+#[tauri::command]
+pub async fn notification_prefs_get(
+    state: State<'_, AppState>,
+) -> Result<NotificationPrefsResponse, String> {
+    Ok(NotificationPrefsResponse {
+        success: true,
+        prefs: state.settings.read().await.notifications.clone(),
+        error: ResponseError::none(),
+    })
+}
+
+/// Replace the notification preferences and persist them
+#[tauri::command]
+pub async fn notification_prefs_set(
+    state: State<'_, AppState>,
+    prefs: NotificationPrefs,
+) -> Result<NotificationPrefsResponse, String> {
+    let mut settings = state.settings.write().await;
+
+    let mut updated = settings.clone();
+    updated.notifications = prefs;
+    match updated.save(&state.config_dir) {
+        Ok(()) => {
+            *settings = updated;
+            Ok(NotificationPrefsResponse {
+                success: true,
+                prefs: settings.notifications.clone(),
+                error: ResponseError::none(),
+            })
+        }
+        Err(e) => Ok(NotificationPrefsResponse {
+            success: false,
+            prefs: settings.notifications.clone(),
+            error: ResponseError::from(&e),
+        }),
+    }
+}
+
+/// All settings, including keys this version doesn't know
+#[tauri::command]
+pub async fn get_settings(state: State<'_, AppState>) -> Result<SettingsResponse, String> {
+    Ok(SettingsResponse {
+        success: true,
+        settings: state.settings.read().await.clone(),
+        restarted: false,
+        error: ResponseError::none(),
+    })
+}
+
+/// Merge `patch` (top-level keys; `null` resets one), validate, persist, apply
+#[tauri::command]
+pub async fn set_settings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    patch: Value,
+    confirm_destructive: Option<bool>,
+) -> Result<SettingsResponse, String> {
+    let mut settings = state.settings.write().await;
+
+    let result = settings.merged(&patch).and_then(|mut updated| {
+        if patch.get("intent_aliases").is_some() {
+            updated.intent_aliases = intents::validate_aliases(
+                &updated.intent_aliases,
+                confirm_destructive.unwrap_or(false),
+            )?;
+        }
+        updated.save(&state.config_dir)?;
+        Ok(updated)
+    });
+    let updated = match result {
+        Ok(updated) => updated,
+        Err(e) => {
+            return Ok(SettingsResponse {
+                success: false,
+                settings: settings.clone(),
+                restarted: false,
+                error: ResponseError::from(&e),
+            })
+        }
+    };
+
+    let respawn = settings.spawn_changed(&updated);
+    let shortcut_changed = settings.quick_add_shortcut != updated.quick_add_shortcut
+        || settings.pause_all_shortcut != updated.pause_all_shortcut;
+    let read_only_changed = settings.read_only != updated.read_only;
+    state.usage_metrics.set_enabled(updated.analytics_enabled);
+    let bridge = &state.bridge;
+    bridge.set_strict_protocol(updated.strict_protocol);
+    bridge.set_max_message_bytes(updated.max_message_bytes());
+    if !updated.developer_mode {
+        bridge.faults().clear();
+    }
+    *settings = updated;
+    let python_path = settings.python_path.clone();
+    let framing = settings.stdio_framing;
+    let env_policy = settings.env_policy();
+    let container = settings.container.clone();
+    let ssh = settings.ssh.clone();
+    let response_settings = settings.clone();
+    drop(settings);
+    if read_only_changed {
+        read_only::emit_changed(&app, response_settings.read_only);
+    }
+
+    let shortcut_error = shortcut_changed
+        .then(|| {
+            let quick_add = quick_add::apply_shortcut(&app, &response_settings).error;
+            // After quick add, which clears every registration
+            let pause_all = ai_pause::register_shortcut(&app, &response_settings);
+            quick_add.message.or_else(|| pause_all.map(|e| e.render()))
+        })
+        .flatten();
+
+    let restarted = if respawn {
+        *state.versions.lock().await = None;
+        async {
+            let reframed = bridge.set_framing(framing).await?;
+            let isolated = bridge.set_env_policy(env_policy).await?;
+            let contained = bridge.set_container(container).await?;
+            let remote = bridge.set_ssh(ssh).await?;
+            let respawned = bridge.set_python_path(python_path).await?;
+            anyhow::Ok(reframed || isolated || contained || remote || respawned)
+        }
+        .await
+    } else {
+        Ok(false)
+    };
+    Ok(match restarted {
+        Ok(restarted) => SettingsResponse {
+            success: shortcut_error.is_none(),
+            settings: response_settings,
+            restarted,
+            error: shortcut_error
+                .map(|e| CatalogError::new(ErrorCode::SavedShortcutFailed).with("reason", e))
+                .into(),
+        },
+        Err(e) => SettingsResponse {
+            success: false,
+            settings: response_settings,
+            restarted: false,
+            error: CatalogError::new(ErrorCode::SavedRestartFailed)
+                .with("reason", e.to_string())
+                .into(),
+        },
+    })
+}
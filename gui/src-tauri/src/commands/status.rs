@@ -0,0 +1,205 @@
+//! Status updates with optimistic-UI information
+//!
+//! The response always carries the status the task had before the call and
+//! a per-session `mutation_seq`, so the frontend can apply a change at once
+//! and roll back (or drop a stale reply) without another fetch. With
+//! `debounce_ms`, a burst of updates to one task sends only the last.
+
+use std::sync::atomic::Ordering;
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, State};
+
+use crate::ai_response::AIResponse;
+use crate::audit::{self, Audit};
+use crate::backend;
+use crate::debounce::{self, Turn};
+use crate::error_catalog::{CatalogError, ResponseError};
+use crate::journal::{self, Mutation};
+use crate::mutation_queue;
+use crate::read_only;
+use crate::revision;
+use crate::AppState;
+
+const STATUS_TOOL: &str = "tasks_complete";
+
+/// What the UI can show before the backend answers
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct OptimisticStatus {
+    /// Status before the update (`None` if the task couldn't be loaded)
+    pub previous_status: Option<String>,
+    pub requested_status: String,
+    /// `cache` or `backend`
+    pub previous_source: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct StatusUpdateResponse {
+    pub success: bool,
+    pub task_id: String,
+    /// Increases with every update this session; higher wins
+    pub mutation_seq: u64,
+    pub optimistic: OptimisticStatus,
+    pub result: Option<AIResponse>,
+    /// Held in the offline queue; replayed when the backend is back
+    pub queued: bool,
+    /// A newer debounced update to the task replaced this one; nothing was sent
+    pub superseded: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+fn status_of(task: &Value) -> Option<String> {
+    backend::task_str(task, "status_code")
+        .or_else(|| backend::task_str(task, "status"))
+        .map(String::from)
+}
+
+/// Set a task's status (`TODO`/`ACTIVE`/`DONE`) via `tasks_complete`; with
+/// `expected_revision` (the task's `revision`), only if nobody changed it since
+#[tauri::command]
+pub async fn tasks_update_status(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    task_id: String,
+    status: String,
+    debounce_ms: Option<u64>,
+    expected_revision: Option<u64>,
+) -> Result<StatusUpdateResponse, String> {
+    let response = status_response(
+        Some(&app),
+        &state,
+        task_id,
+        status,
+        debounce_ms,
+        expected_revision,
+    )
+    .await;
+    state
+        .usage_metrics
+        .record_outcome("tasks_update_status", response.success);
+    Ok(response)
+}
+
+/// `tasks_update_status`; without `app` (`--headless`) the offline queue is
+/// not replayed first
+pub(crate) async fn status_response(
+    app: Option<&AppHandle>,
+    state: &AppState,
+    task_id: String,
+    status: String,
+    debounce_ms: Option<u64>,
+    expected_revision: Option<u64>,
+) -> StatusUpdateResponse {
+    let mutation_seq = state.mutation_seq.fetch_add(1, Ordering::SeqCst) + 1;
+    let requested_status = status.trim().to_uppercase();
+    let bridge = &state.bridge;
+
+    if let Some(quiet) = debounce::quiet_period(debounce_ms) {
+        let key = debounce::key(STATUS_TOOL, &task_id);
+        if state.debouncer.wait(&key, quiet).await == Turn::Superseded {
+            return StatusUpdateResponse {
+                success: true,
+                task_id,
+                mutation_seq,
+                optimistic: OptimisticStatus {
+                    previous_status: None,
+                    requested_status,
+                    previous_source: None,
+                },
+                result: None,
+                queued: false,
+                superseded: true,
+                error: ResponseError::none(),
+            };
+        }
+    }
+
+    if let Some(error) = read_only::refusal(state).await {
+        return StatusUpdateResponse {
+            success: false,
+            task_id,
+            mutation_seq,
+            optimistic: OptimisticStatus {
+                previous_status: None,
+                requested_status,
+                previous_source: None,
+            },
+            result: None,
+            queued: false,
+            superseded: false,
+            error: error.into(),
+        };
+    }
+
+    let cached = state.read_cache.lock().await.find_task(&task_id);
+    let (previous_status, previous_source) = match cached.as_ref().and_then(status_of) {
+        Some(previous) => (Some(previous), Some("cache")),
+        None => match backend::show_task(bridge, &task_id).await {
+            Ok(task) => (status_of(&task), Some("backend")),
+            Err(e) => {
+                log::warn!("Previous status of {} unavailable: {}", task_id, e);
+                (None, None)
+            }
+        },
+    };
+    let optimistic = OptimisticStatus {
+        previous_status,
+        requested_status: requested_status.clone(),
+        previous_source: previous_source.map(String::from),
+    };
+
+    if let Some(app) = app {
+        if state.mutation_queue.lock().await.has_untried() {
+            mutation_queue::replay_queue(app).await;
+        }
+    }
+    state.storage_watch.mark_own_write();
+    let mut params = json!({ "task": task_id, "status": requested_status });
+    revision::expect(&mut params, expected_revision);
+    let response = match bridge.call_tool(STATUS_TOOL, params.clone()).await {
+        Ok(result) => AIResponse::from_value(result),
+        Err(e) => {
+            match mutation_queue::queue_if_offline(state, &e, "complete", STATUS_TOOL, &params)
+                .await
+            {
+                Some(entry) => mutation_queue::queued_response("complete", &entry),
+                None => AIResponse::bridge_error("complete", e.to_string()),
+            }
+        }
+    };
+    state.storage_watch.mark_own_write();
+
+    if response.success {
+        state
+            .read_cache
+            .lock()
+            .await
+            .invalidate(std::slice::from_ref(&task_id));
+    }
+    let mutation = Mutation::new(STATUS_TOOL, Some(task_id.clone())).change(
+        optimistic.previous_status.clone(),
+        Some(optimistic.requested_status.clone()),
+    );
+    journal::record(state, &response, mutation).await;
+    let audited = Audit::new(STATUS_TOOL, [task_id.clone()])
+        .params(&params)
+        .response(&response);
+    audit::record(state, audited).await;
+    let error = (!response.success).then(|| {
+        revision::conflict(response.error.as_ref(), &response.result).unwrap_or_else(|| {
+            CatalogError::from_envelope(response.error.as_ref(), "Failed to update status")
+        })
+    });
+
+    StatusUpdateResponse {
+        success: response.success,
+        task_id,
+        mutation_seq,
+        optimistic,
+        queued: response.queued,
+        superseded: false,
+        result: Some(response),
+        error: error.into(),
+    }
+}
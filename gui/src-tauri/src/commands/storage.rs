@@ -0,0 +1,273 @@
+//! Storage info commands
+//!
+//! Typed `tasks_storage` result plus disk usage computed in Rust, revealing
+//! the storage (or a task file) in the system file manager, and watching it
+//! for changes made outside the GUI.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+use tauri::{AppHandle, State};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::backend;
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::storage::{self, StorageInfo, MAX_WALK_DEPTH, MAX_WALK_ENTRIES};
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct StorageResponse {
+    pub success: bool,
+    pub storage: Option<StorageInfo>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct RevealResponse {
+    pub success: bool,
+    /// Path that was (or should be) opened; returned even if opening failed
+    pub path: Option<String>,
+    /// Whether the file manager was launched
+    pub opened: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct WatchResponse {
+    pub success: bool,
+    pub watching: bool,
+    pub path: Option<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+impl RevealResponse {
+    fn failed(path: Option<&Path>, error: impl Into<ResponseError>) -> Self {
+        Self {
+            success: false,
+            path: path.map(|p| p.to_string_lossy().to_string()),
+            opened: false,
+            error: error.into(),
+        }
+    }
+}
+
+pub(crate) async fn fetch_storage(state: &AppState) -> anyhow::Result<StorageInfo> {
+    let bridge = &state.bridge;
+    let raw = backend::into_result(bridge.call_tool("tasks_storage", json!({})).await?)?;
+    Ok(StorageInfo::from_result(raw))
+}
+
+/// Existing storage root of the current project
+async fn storage_root(state: &AppState) -> Result<PathBuf, RevealResponse> {
+    let info = fetch_storage(state)
+        .await
+        .map_err(|e| RevealResponse::failed(None, &e))?;
+    let root = PathBuf::from(&info.path);
+    if info.path.is_empty() || !root.is_dir() {
+        return Err(RevealResponse::failed(
+            Some(&root),
+            CatalogError::new(ErrorCode::StorageMissing),
+        ));
+    }
+    Ok(root)
+}
+
+/// Re-point an active watcher when the storage moved (project or mode switch)
+pub(crate) fn follow_storage(app: &AppHandle, state: &AppState, path: &str) {
+    let Some(watched) = state.storage_watch.root() else {
+        return;
+    };
+    let root = PathBuf::from(path);
+    if path.is_empty() || watched == root {
+        return;
+    }
+    if let Err(e) = state.storage_watch.start(app, root) {
+        log::warn!("Storage watching disabled: {:#}", e);
+        state.storage_watch.stop();
+    }
+}
+
+/// Ask the backend for the storage root again and follow it if watching
+pub(crate) async fn refollow_storage(app: &AppHandle, state: &AppState) {
+    if state.storage_watch.root().is_none() {
+        return;
+    }
+    let bridge = &state.bridge;
+    let raw = bridge.call_tool("tasks_storage", json!({})).await.ok();
+    if let Some(result) = raw.and_then(|raw| backend::into_result(raw).ok()) {
+        follow_storage(app, state, &StorageInfo::from_result(result).path);
+    }
+}
+
+/// Open `path` (a directory, or a file to select) unless it escapes `root`
+fn reveal(app: &AppHandle, root: &Path, path: &Path) -> RevealResponse {
+    if !storage::within_root(root, path) {
+        return RevealResponse::failed(
+            Some(path),
+            CatalogError::new(ErrorCode::PathOutsideStorage),
+        );
+    }
+
+    let opener = app.opener();
+    let opened = if path.is_dir() {
+        opener.open_path(path.to_string_lossy(), None::<&str>)
+    } else {
+        opener.reveal_item_in_dir(path)
+    };
+
+    match opened {
+        Ok(()) => RevealResponse {
+            success: true,
+            path: Some(path.to_string_lossy().to_string()),
+            opened: true,
+            error: ResponseError::none(),
+        },
+        Err(e) => {
+            log::warn!("Failed to open {:?}: {}", path, e);
+            // Headless/remote sessions: hand the path back instead
+            RevealResponse {
+                success: true,
+                path: Some(path.to_string_lossy().to_string()),
+                opened: false,
+                error: CatalogError::new(ErrorCode::OpenFailed)
+                    .with("detail", e.to_string())
+                    .into(),
+            }
+        }
+    }
+}
+
+/// Storage location, namespaces and (unless `skip_size`) on-disk size
+#[tauri::command]
+pub async fn tasks_storage(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    skip_size: Option<bool>,
+) -> Result<StorageResponse, String> {
+    let mut info = match fetch_storage(&state).await {
+        Ok(info) => info,
+        Err(e) => {
+            return Ok(StorageResponse {
+                success: false,
+                storage: None,
+                error: ResponseError::from(&e),
+            })
+        }
+    };
+
+    follow_storage(&app, &state, &info.path);
+
+    let path = PathBuf::from(&info.path);
+    if !skip_size.unwrap_or(false) && !info.path.is_empty() && path.is_dir() {
+        match tauri::async_runtime::spawn_blocking(move || {
+            storage::disk_usage(&path, MAX_WALK_DEPTH, MAX_WALK_ENTRIES)
+        })
+        .await
+        {
+            Ok(usage) => info.apply_usage(usage),
+            Err(e) => log::warn!("Storage size walk failed: {}", e),
+        }
+    }
+
+    Ok(StorageResponse {
+        success: true,
+        storage: Some(info),
+        error: ResponseError::none(),
+    })
+}
+
+/// Open the storage directory in the system file manager
+#[tauri::command]
+pub async fn reveal_storage(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RevealResponse, String> {
+    Ok(match storage_root(&state).await {
+        Ok(root) => reveal(&app, &root, &root),
+        Err(response) => response,
+    })
+}
+
+/// Select a task's `.task` file in the system file manager
+#[tauri::command]
+pub async fn reveal_task_file(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    task_id: String,
+) -> Result<RevealResponse, String> {
+    let root = match storage_root(&state).await {
+        Ok(root) => root,
+        Err(response) => return Ok(response),
+    };
+
+    let search_root = root.clone();
+    let found = tauri::async_runtime::spawn_blocking(move || {
+        storage::find_task_file(&search_root, &task_id)
+    })
+    .await
+    .ok()
+    .flatten();
+
+    Ok(match found {
+        Some(file) => reveal(&app, &root, &file),
+        None => RevealResponse::failed(Some(&root), CatalogError::new(ErrorCode::TaskFileNotFound)),
+    })
+}
+
+/// Watch the storage directory and emit `tasks-storage-changed` on changes
+#[tauri::command]
+pub async fn watch_storage_start(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<WatchResponse, String> {
+    let root = match storage_root(&state).await {
+        Ok(root) => root,
+        Err(response) => {
+            return Ok(WatchResponse {
+                success: false,
+                watching: false,
+                path: response.path,
+                error: response.error,
+            })
+        }
+    };
+
+    let path = Some(root.to_string_lossy().to_string());
+    Ok(match state.storage_watch.start(&app, root) {
+        Ok(()) => WatchResponse {
+            success: true,
+            watching: true,
+            path,
+            error: ResponseError::none(),
+        },
+        Err(e) => {
+            // No notification support (e.g. some network filesystems)
+            log::warn!("Storage watching disabled: {:#}", e);
+            WatchResponse {
+                success: false,
+                watching: false,
+                path,
+                error: CatalogError::internal(format!("{:#}", e)).into(),
+            }
+        }
+    })
+}
+
+/// Stop watching the storage directory
+#[tauri::command]
+pub async fn watch_storage_stop(state: State<'_, AppState>) -> Result<WatchResponse, String> {
+    let path = state
+        .storage_watch
+        .root()
+        .map(|p| p.to_string_lossy().to_string());
+    state.storage_watch.stop();
+    Ok(WatchResponse {
+        success: true,
+        watching: false,
+        path,
+        error: ResponseError::none(),
+    })
+}
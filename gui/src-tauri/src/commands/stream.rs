@@ -0,0 +1,150 @@
+//! Streaming task-list commands
+//!
+//! `tasks_list_stream` runs the `tasks_list` pipeline in a spawned task and
+//! delivers the result in chunks through events (see [`crate::task_stream`]).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::backend::ListFilters;
+use crate::error_catalog::ResponseError;
+use crate::task_stream::{
+    self, TasksChunk, TasksStreamEnd, CHUNK_PACING, TASKS_CHUNK_EVENT, TASKS_STREAM_END_EVENT,
+};
+use crate::AppState;
+
+use super::task::load_task_list;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TaskStreamResponse {
+    pub success: bool,
+    pub stream_id: String,
+    /// Effective chunk size (clamped)
+    pub chunk_size: usize,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TaskStreamCancelResponse {
+    pub success: bool,
+    /// False when the stream had already ended
+    pub cancelled: bool,
+}
+
+/// Send `tasks` in chunks, pausing between them; returns how many went out
+async fn emit_chunks(
+    app: &AppHandle,
+    stream_id: &str,
+    cancelled: &AtomicBool,
+    tasks: Vec<Value>,
+    chunk_size: usize,
+) -> usize {
+    let mut sent = 0;
+    let mut rest = tasks.into_iter();
+    for index in 0.. {
+        let tasks: Vec<Value> = rest.by_ref().take(chunk_size).collect();
+        if tasks.is_empty() {
+            break;
+        }
+        if index > 0 {
+            tokio::time::sleep(CHUNK_PACING).await;
+        }
+        if cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+        let count = tasks.len();
+        let chunk = TasksChunk {
+            stream_id: stream_id.to_string(),
+            index,
+            tasks,
+        };
+        if let Err(e) = app.emit(TASKS_CHUNK_EVENT, &chunk) {
+            log::warn!("Failed to emit {}: {}", TASKS_CHUNK_EVENT, e);
+        }
+        sent += count;
+    }
+    sent
+}
+
+async fn run_stream(
+    app: AppHandle,
+    stream_id: String,
+    cancelled: Arc<AtomicBool>,
+    filters: ListFilters,
+    compact: bool,
+    projection: Option<Vec<String>>,
+    chunk_size: usize,
+) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let end = match load_task_list(&state, &filters, compact, projection, None, None).await {
+        Ok((tasks, _)) => {
+            let total = tasks.len();
+            let sent = emit_chunks(&app, &stream_id, &cancelled, tasks, chunk_size).await;
+            TasksStreamEnd {
+                stream_id: stream_id.clone(),
+                success: true,
+                total,
+                sent,
+                cancelled: sent < total,
+                error: ResponseError::none(),
+            }
+        }
+        Err(e) => TasksStreamEnd {
+            stream_id: stream_id.clone(),
+            success: false,
+            total: 0,
+            sent: 0,
+            cancelled: cancelled.load(Ordering::Relaxed),
+            error: ResponseError::from(&e),
+        },
+    };
+    state.task_streams.close(&stream_id);
+    if let Err(e) = app.emit(TASKS_STREAM_END_EVENT, &end) {
+        log::warn!("Failed to emit {}: {}", TASKS_STREAM_END_EVENT, e);
+    }
+}
+
+/// Start streaming the task list (same filters and projection as
+/// `tasks_list`); chunks follow as `tasks-chunk` events
+#[tauri::command]
+pub async fn tasks_list_stream(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    filters: Option<ListFilters>,
+    compact: Option<bool>,
+    projection: Option<Vec<String>>,
+    chunk_size: Option<usize>,
+) -> Result<TaskStreamResponse, String> {
+    let chunk_size = task_stream::chunk_size(chunk_size);
+    let (stream_id, cancelled) = state.task_streams.open();
+    tauri::async_runtime::spawn(run_stream(
+        app.clone(),
+        stream_id.clone(),
+        cancelled,
+        filters.unwrap_or_default(),
+        compact.unwrap_or(true),
+        projection,
+        chunk_size,
+    ));
+    Ok(TaskStreamResponse {
+        success: true,
+        stream_id,
+        chunk_size,
+    })
+}
+
+/// Stop a stream before its next chunk (its end event still follows)
+#[tauri::command]
+pub async fn tasks_stream_cancel(
+    state: State<'_, AppState>,
+    stream_id: String,
+) -> Result<TaskStreamCancelResponse, String> {
+    Ok(TaskStreamCancelResponse {
+        success: true,
+        cancelled: state.task_streams.cancel(&stream_id),
+    })
+}
@@ -0,0 +1,41 @@
+//! Suggestion commands
+
+use tauri::{AppHandle, Runtime, State};
+
+use crate::ai_response::{AIResponse, SuggestionAction};
+use crate::intents;
+use crate::suggestions;
+use crate::AppState;
+
+/// Run a suggestion's intent with its params through `ai_intent`
+/// (destructive tools and label-only suggestions are refused)
+#[tauri::command]
+pub async fn execute_suggestion(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    action: SuggestionAction,
+) -> Result<AIResponse, String> {
+    let response = execute_response(&app, &state, action).await;
+    state
+        .usage_metrics
+        .record_outcome("execute_suggestion", response.success);
+    Ok(response)
+}
+
+pub(crate) async fn execute_response<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AppState,
+    action: SuggestionAction,
+) -> AIResponse {
+    let user_aliases = state.settings.read().await.intent_aliases.clone();
+    match suggestions::validate(&action, &user_aliases) {
+        Ok(intent) => {
+            log::info!("Running suggestion {:?} as {}", action.label, intent);
+            super::task::intent_response(app, state, intent, action.params, Some(true), None).await
+        }
+        Err(e) => {
+            let intent = action.intent.as_deref().map(intents::normalize);
+            AIResponse::invalid_suggestion(&intent.unwrap_or_default(), &e)
+        }
+    }
+}
@@ -2,9 +2,31 @@
 //!
 //! These commands are invoked from the React frontend via Tauri's invoke API.
 
+use std::collections::HashMap;
+use std::time::Instant;
+
 use serde_json::{json, Value};
-use tauri::State;
+use tauri::{AppHandle, Runtime, State, Window};
 
+use crate::ai_response::AIResponse;
+use crate::audit::{self, Audit};
+use crate::backend::{self, ListFilters};
+use crate::context::ContextResponse;
+use crate::deep_link;
+use crate::error_catalog::ResponseError;
+use crate::intent_result::IntentResult;
+use crate::intents::{self, UserAliases};
+use crate::journal::{self, Mutation};
+use crate::mutation_queue;
+use crate::prefetch;
+use crate::progress;
+use crate::projection;
+use crate::python::{BridgeMetrics, PythonBridge};
+use crate::rate_limit;
+use crate::read_cache;
+use crate::read_only;
+use crate::revision;
+use crate::task_tree;
 use crate::AppState;
 
 /// Backend storage mode response
@@ -13,62 +35,691 @@ pub struct BackendStorageModeResponse {
     pub success: bool,
     pub mode: String,
     pub restarted: bool,
-    pub error: Option<String>,
-}
-
-fn bridge_error(intent: &str, message: String) -> Value {
-    json!({
-        "success": false,
-        "intent": intent,
-        "result": {},
-        "warnings": [],
-        "context": {},
-        "suggestions": [],
-        "meta": {},
-        "error": { "code": "BRIDGE_ERROR", "message": message },
-        "timestamp": ""
-    })
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Tool for `intent`; `tools/list` is only fetched for intents not pinned by an alias
+pub(crate) async fn resolve_tool(
+    bridge: &PythonBridge,
+    user_aliases: &UserAliases,
+    intent: &str,
+    strict: bool,
+) -> Option<String> {
+    let tools = if intents::pinned(intent, user_aliases).is_some() {
+        None
+    } else {
+        bridge
+            .list_tools()
+            .await
+            .map_err(|e| log::warn!("tools/list unavailable: {}", e))
+            .ok()
+    };
+    intents::resolve(intent, user_aliases, tools.as_deref(), strict)
 }
 
-/// Execute AI intent (transparent proxy to MCP tools: tasks_<intent>)
+/// Execute AI intent (user aliases, alias table, then `tools/list`, then `tasks_<intent>` unless strict)
+///
+/// `tasks_create` is refused when similar tasks exist (see
+/// [`super::duplicates::check_create`]) unless `allow_duplicate` is passed.
+/// A successful result also comes back parsed under `typed`. Tools that
+/// delete data are refused unless `skip_confirmation` is set; deletes go
+/// through [`super::tasks_delete`] and its confirmation token.
 #[tauri::command]
 pub async fn ai_intent(
+    app: AppHandle,
     state: State<'_, AppState>,
     intent: String,
     params: Option<Value>,
-) -> Result<Value, String> {
-    let bridge = state.bridge.lock().await;
+    strict: Option<bool>,
+    timeout_ms: Option<u64>,
+) -> Result<AIResponse, String> {
+    let response = intent_response(&app, &state, intent, params, strict, timeout_ms).await;
+    state
+        .usage_metrics
+        .record_outcome("ai_intent", response.success);
+    Ok(response)
+}
+
+/// [`ai_intent`] for any runtime
+pub(crate) async fn intent_response<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AppState,
+    intent: String,
+    params: Option<Value>,
+    strict: Option<bool>,
+    timeout_ms: Option<u64>,
+) -> AIResponse {
+    let user_aliases = state.settings.read().await.intent_aliases.clone();
+    let bridge = state.bridge.clone();
 
-    let normalized_intent = intent.trim().to_lowercase();
-    let tool_name = format!("tasks_{}", normalized_intent);
+    let normalized_intent = intents::normalize(&intent);
+    let Some(tool_name) =
+        resolve_tool(&bridge, &user_aliases, &intent, strict.unwrap_or(true)).await
+    else {
+        return AIResponse::unknown_intent(&normalized_intent);
+    };
 
-    let request_params = params.unwrap_or(json!({}));
+    if intents::is_destructive(&tool_name) && !state.settings.read().await.skip_confirmation {
+        return AIResponse::confirmation_required(&normalized_intent, &tool_name);
+    }
+    if intents::writes_storage(&tool_name) && read_only::enabled(state).await {
+        return AIResponse::read_only(&normalized_intent);
+    }
+    if let Err(e) = rate_limit::check(state, &tool_name).await {
+        return AIResponse::rate_limited(&normalized_intent, &e);
+    }
+    let mut request_params = params.unwrap_or(json!({}));
+    if tool_name == "tasks_create" {
+        if let Some(similar) = super::duplicates::check_create(state, &mut request_params).await {
+            return AIResponse::possible_duplicates(&normalized_intent, &similar);
+        }
+    }
+    let target = read_cache::target_task(&request_params);
+    let timeout = state
+        .settings
+        .read()
+        .await
+        .call_timeout(&tool_name, timeout_ms);
+    let bridge = bridge.with_timeout(timeout);
+
+    let writes = intents::writes_storage(&tool_name);
+    if writes {
+        // Earlier queued mutations go first
+        if state.mutation_queue.lock().await.has_untried() {
+            mutation_queue::replay_queue(app).await;
+        }
+        state.storage_watch.mark_own_write();
+    }
+    let mut response = match bridge
+        .invoke(&tool_name, Some(request_params.clone()))
+        .await
+    {
+        Ok(result) => AIResponse {
+            typed: IntentResult::from_envelope(&tool_name, &result),
+            ..AIResponse::from_value(result)
+        },
+        Err(e) if writes => {
+            match mutation_queue::queue_if_offline(
+                state,
+                &e,
+                &normalized_intent,
+                &tool_name,
+                &request_params,
+            )
+            .await
+            {
+                Some(entry) => mutation_queue::queued_response(&normalized_intent, &entry),
+                None => AIResponse::from_bridge_error(&normalized_intent, &e),
+            }
+        }
+        Err(e) => AIResponse::from_bridge_error(&normalized_intent, &e),
+    };
+    if writes {
+        state.storage_watch.mark_own_write();
+        if response.success {
+            let ids: Vec<String> = target.iter().cloned().collect();
+            state.read_cache.lock().await.invalidate(&ids);
+        }
+        journal::record(state, &response, Mutation::new(&tool_name, target.clone())).await;
+        let audited = Audit::new(&tool_name, target)
+            .params(&request_params)
+            .response(&response);
+        audit::record(state, audited).await;
+    }
+    state
+        .usage_metrics
+        .record_intent(&tool_name, response.success);
+    response.resolved_tool = Some(tool_name);
+    response
+}
+
+/// Cached value for `key` unless refreshing (miss/hit counted)
+async fn cached(state: &AppState, key: &str, force_refresh: Option<bool>) -> Option<Value> {
+    if force_refresh.unwrap_or(false) {
+        return None;
+    }
+    let ttl = state.settings.read().await.cache_ttl();
+    state.read_cache.lock().await.get(key, ttl, Instant::now())
+}
+
+async fn store(state: &AppState, key: String, value: Value, scope: Option<Vec<String>>) {
+    state
+        .read_cache
+        .lock()
+        .await
+        .put(key, value, scope, Instant::now());
+}
+
+/// Project context with typed fields (`ai_intent("context")` keeps the raw envelope)
+#[tauri::command]
+pub async fn tasks_context(
+    state: State<'_, AppState>,
+    params: Option<Value>,
+    force_refresh: Option<bool>,
+    timeout_ms: Option<u64>,
+) -> Result<ContextResponse, String> {
+    let params = params.unwrap_or(json!({}));
+    let key = read_cache::context_key(&params);
+    if !force_refresh.unwrap_or(false) {
+        let ttl = state.settings.read().await.context_cache_ttl();
+        let cached = state.read_cache.lock().await.get(&key, ttl, Instant::now());
+        if let Some(response) = cached {
+            return Ok(ContextResponse::from_response(response));
+        }
+    }
+    // Context of one task is about that task; `include_all` is about all
+    let scope = match params.get("include_all").and_then(|v| v.as_bool()) {
+        Some(true) => None,
+        _ => read_cache::target_task(&params).map(|task| vec![task]),
+    };
+
+    let timeout = state
+        .settings
+        .read()
+        .await
+        .call_timeout("tasks_context", timeout_ms);
+    let bridge = state.bridge.clone().with_timeout(timeout);
+    match bridge.call_tool("tasks_context", params).await {
+        Ok(response) => {
+            if response.get("success").and_then(|s| s.as_bool()) != Some(false) {
+                store(&state, key, response.clone(), scope).await;
+            }
+            Ok(ContextResponse::from_response(response))
+        }
+        Err(e) => Ok(ContextResponse {
+            error: ResponseError::from(&e),
+            ..ContextResponse::default()
+        }),
+    }
+}
 
-    match bridge.invoke(&tool_name, Some(request_params)).await {
-        Ok(result) => Ok(result),
-        Err(e) => Ok(bridge_error(&normalized_intent, e.to_string())),
+/// Refetch the context of `task` (the project when unset), dropping every
+/// cached context about it first
+#[tauri::command]
+pub async fn context_refresh(
+    state: State<'_, AppState>,
+    task: Option<String>,
+    include_all: Option<bool>,
+    timeout_ms: Option<u64>,
+) -> Result<ContextResponse, String> {
+    state
+        .read_cache
+        .lock()
+        .await
+        .invalidate_context(task.as_deref());
+    let mut params = json!({});
+    if let Some(task) = task {
+        params["task"] = json!(task);
+    }
+    if let Some(include_all) = include_all {
+        params["include_all"] = json!(include_all);
     }
+    tasks_context(state, Some(params), Some(true), timeout_ms).await
+}
+
+/// Single task response (`children` nested when requested)
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TaskShowResponse {
+    pub success: bool,
+    pub task: Option<Value>,
+    /// The task's backend `revision`; pass it back as `expected_revision` to
+    /// refuse edits over someone else's
+    pub revision: Option<u64>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+fn show_key(task_id: &str, include_children: Option<bool>, depth: Option<u8>) -> String {
+    read_cache::key(
+        "tasks_show",
+        &json!({ "task_id": task_id, "include_children": include_children, "depth": depth }),
+    )
+}
+
+/// Show a task, optionally with its child-task subtree (`depth` levels, default 1)
+#[tauri::command]
+pub async fn tasks_show(
+    state: State<'_, AppState>,
+    task_id: String,
+    include_children: Option<bool>,
+    depth: Option<u8>,
+    force_refresh: Option<bool>,
+    timeout_ms: Option<u64>,
+) -> Result<TaskShowResponse, String> {
+    let response = show_response(
+        &state,
+        &task_id,
+        include_children,
+        depth,
+        force_refresh,
+        timeout_ms,
+    )
+    .await;
+    state
+        .usage_metrics
+        .record_outcome("tasks_show", response.success);
+    if response.success {
+        super::fuzzy::record_view(&state, task_id.trim());
+    }
+    Ok(response)
+}
+
+/// `tasks_show` (also run by `--headless`)
+pub(crate) async fn show_response(
+    state: &AppState,
+    task_id: &str,
+    include_children: Option<bool>,
+    depth: Option<u8>,
+    force_refresh: Option<bool>,
+    timeout_ms: Option<u64>,
+) -> TaskShowResponse {
+    let key = show_key(task_id, include_children, depth);
+    if let Some(task) = cached(state, &key, force_refresh).await {
+        return TaskShowResponse {
+            success: true,
+            revision: revision::of(&task),
+            task: Some(task),
+            error: ResponseError::none(),
+        };
+    }
+
+    let timeout = state
+        .settings
+        .read()
+        .await
+        .call_timeout("tasks_resume", timeout_ms);
+    let loaded = {
+        let bridge = state.bridge.clone().with_timeout(timeout);
+        match backend::show_task(&bridge, task_id).await {
+            Ok(task) if include_children.unwrap_or(false) => backend::list_tasks(&bridge, None)
+                .await
+                .map(|all| (task, all)),
+            Ok(task) => Ok((task, Vec::new())),
+            Err(e) => Err(e),
+        }
+    };
+
+    let (task, all_tasks) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            return TaskShowResponse {
+                success: false,
+                task: None,
+                revision: None,
+                error: ResponseError::from(&e),
+            }
+        }
+    };
+
+    let task = if include_children.unwrap_or(false) {
+        let bridge = state.bridge.clone().with_timeout(timeout);
+        task_tree::build_tree(task, depth.unwrap_or(1), &all_tasks, move |id| {
+            let bridge = bridge.clone();
+            async move { backend::show_task(&bridge, &id).await }
+        })
+        .await
+    } else {
+        task
+    };
+    store(state, key, task.clone(), Some(read_cache::scope_of(&task))).await;
+
+    TaskShowResponse {
+        success: true,
+        revision: revision::of(&task),
+        task: Some(task),
+        error: ResponseError::none(),
+    }
+}
+
+/// One id of a `tasks_prefetch` batch
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct PrefetchOutcome {
+    pub task_id: String,
+    pub success: bool,
+    /// Already in the read cache, so not fetched
+    pub cached: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TaskPrefetchResponse {
+    pub success: bool,
+    /// In input order, without duplicates
+    pub results: Vec<PrefetchOutcome>,
+    /// Ids over the batch cap, not fetched
+    pub dropped: usize,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Warm the read cache for `tasks_show` (without children) of up to
+/// [`prefetch::MAX_BATCH`] ids; returns only whether each id made it.
+///
+/// `namespace`, when given, must be the open project's.
+#[tauri::command]
+pub async fn tasks_prefetch(
+    state: State<'_, AppState>,
+    task_ids: Vec<String>,
+    namespace: Option<String>,
+    timeout_ms: Option<u64>,
+) -> Result<TaskPrefetchResponse, String> {
+    if let Some(namespace) = namespace.filter(|n| !n.trim().is_empty()) {
+        if let Some(error) = deep_link::namespace_error(&state, &namespace).await {
+            return Ok(TaskPrefetchResponse {
+                success: false,
+                results: Vec::new(),
+                dropped: 0,
+                error: error.into(),
+            });
+        }
+    }
+
+    let (ids, dropped) = prefetch::batch(task_ids);
+    let mut outcomes = HashMap::new();
+    let mut missing = Vec::new();
+    for id in &ids {
+        if cached(&state, &show_key(id, None, None), None)
+            .await
+            .is_some()
+        {
+            outcomes.insert(
+                id.clone(),
+                PrefetchOutcome {
+                    task_id: id.clone(),
+                    success: true,
+                    cached: true,
+                    error: ResponseError::none(),
+                },
+            );
+        } else {
+            missing.push(id.clone());
+        }
+    }
+
+    let timeout = state
+        .settings
+        .read()
+        .await
+        .call_timeout("tasks_resume", timeout_ms);
+    let bridge = state.bridge.clone().with_timeout(timeout);
+    let fetched = prefetch::fetch_all(missing, |id| {
+        let bridge = bridge.clone();
+        async move { backend::show_task(&bridge, &id).await }
+    })
+    .await;
+    for (id, result) in fetched {
+        let error = match result {
+            Ok(task) => {
+                let scope = read_cache::scope_of(&task);
+                store(&state, show_key(&id, None, None), task, Some(scope)).await;
+                ResponseError::none()
+            }
+            Err(e) => ResponseError::from(&e),
+        };
+        outcomes.insert(
+            id.clone(),
+            PrefetchOutcome {
+                task_id: id,
+                success: error.is_none(),
+                cached: false,
+                error,
+            },
+        );
+    }
+
+    Ok(TaskPrefetchResponse {
+        success: true,
+        results: ids.iter().filter_map(|id| outcomes.remove(id)).collect(),
+        dropped,
+        error: ResponseError::none(),
+    })
+}
+
+/// Payload diagnostics (developer mode only)
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TaskListDebug {
+    /// Serialized size of `tasks`
+    pub payload_bytes: usize,
+    /// Keys kept by the projection (`None` = full payloads)
+    pub projection: Option<Vec<String>>,
+}
+
+/// Task list response
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TaskListResponse {
+    pub success: bool,
+    pub tasks: Vec<Value>,
+    pub total: usize,
+    #[serde(flatten)]
+    pub error: ResponseError,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug: Option<TaskListDebug>,
+}
+
+/// Filtered tasks with `progress`, projected to `projection` (also returns
+/// the resolved projection keys); shared by `tasks_list` and
+/// `tasks_list_stream`
+pub(crate) async fn load_task_list(
+    state: &AppState,
+    filters: &ListFilters,
+    compact: bool,
+    projection: Option<Vec<String>>,
+    force_refresh: Option<bool>,
+    timeout_ms: Option<u64>,
+) -> anyhow::Result<(Vec<Value>, Option<Vec<String>>)> {
+    // Cached before projection, so every projection shares the entry
+    let key = read_cache::key(
+        "tasks_list",
+        &json!({ "filters": filters, "compact": compact }),
+    );
+
+    let cached_tasks = match cached(state, &key, force_refresh).await {
+        Some(Value::Array(tasks)) => Some(tasks),
+        _ => None,
+    };
+    let mut tasks = match cached_tasks {
+        Some(tasks) => tasks,
+        None => {
+            let timeout = state
+                .settings
+                .read()
+                .await
+                .call_timeout("tasks_context", timeout_ms);
+            let bridge = state.bridge.clone().with_timeout(timeout);
+            let mut tasks = backend::list_tasks(&bridge, Some(filters.params(compact))).await?;
+            filters.retain(&mut tasks);
+            tasks.iter_mut().for_each(progress::attach);
+            store(state, key, Value::Array(tasks.clone()), None).await;
+            tasks
+        }
+    };
+
+    let keys = projection.map(|p| projection::resolve_keys(&p));
+    if let Some(keys) = &keys {
+        tasks = tasks
+            .into_iter()
+            .map(|task| projection::project(task, keys))
+            .collect();
+    }
+    Ok((tasks, keys))
+}
+
+/// List tasks, filtered by status/domain/parent.
+///
+/// `progress` is computed in Rust when the payloads carry steps
+/// (`compact: false`); compact payloads keep the backend value.
+/// `projection` keeps only the listed keys (presets like `list-view` allowed).
+/// `timeout_ms` (also on `tasks_show`/`tasks_context`/`ai_intent`) bounds the
+/// backend wait, clamped by [`crate::settings::Settings::call_timeout`].
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn tasks_list(
+    state: State<'_, AppState>,
+    status: Option<String>,
+    domain: Option<String>,
+    parent: Option<String>,
+    compact: Option<bool>,
+    projection: Option<Vec<String>>,
+    force_refresh: Option<bool>,
+    timeout_ms: Option<u64>,
+) -> Result<TaskListResponse, String> {
+    let filters = ListFilters {
+        status,
+        domain,
+        parent,
+    };
+    let response = list_response(
+        &state,
+        &filters,
+        compact,
+        projection,
+        force_refresh,
+        timeout_ms,
+    )
+    .await;
+    state
+        .usage_metrics
+        .record_outcome("tasks_list", response.success);
+    Ok(response)
+}
+
+/// `tasks_list` (also run by `--headless`)
+pub(crate) async fn list_response(
+    state: &AppState,
+    filters: &ListFilters,
+    compact: Option<bool>,
+    projection: Option<Vec<String>>,
+    force_refresh: Option<bool>,
+    timeout_ms: Option<u64>,
+) -> TaskListResponse {
+    let compact = compact.unwrap_or(true);
+    let loaded = load_task_list(
+        state,
+        filters,
+        compact,
+        projection,
+        force_refresh,
+        timeout_ms,
+    )
+    .await;
+    let (tasks, keys) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            return TaskListResponse {
+                success: false,
+                tasks: Vec::new(),
+                total: 0,
+                error: ResponseError::from(&e),
+                debug: None,
+            }
+        }
+    };
+
+    let debug = if state.settings.read().await.developer_mode {
+        Some(TaskListDebug {
+            payload_bytes: serde_json::to_vec(&tasks).map(|b| b.len()).unwrap_or(0),
+            projection: keys,
+        })
+    } else {
+        None
+    };
+
+    TaskListResponse {
+        success: true,
+        total: tasks.len(),
+        tasks,
+        error: ResponseError::none(),
+        debug,
+    }
+}
+
+/// Bridge traffic and read cache counters (and rate limit buckets in
+/// developer mode)
+#[tauri::command]
+pub async fn bridge_metrics(state: State<'_, AppState>) -> Result<BridgeMetrics, String> {
+    let mut metrics = state.bridge.metrics();
+    let cache = state.read_cache.lock().await;
+    metrics.cache_hits = cache.hits();
+    metrics.cache_misses = cache.misses();
+    (metrics.context_cache_hits, metrics.context_cache_misses) = cache.counts("tasks_context");
+    if state.settings.read().await.developer_mode {
+        metrics.rate_limits = Some(state.rate_limiter.lock().await.levels(Instant::now()));
+    }
+    metrics.resources = Some(state.backend_resources.usage());
+    Ok(metrics)
+}
+
+/// List refresher state
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ListAutorefreshResponse {
+    pub success: bool,
+    pub running: bool,
+    pub interval_ms: Option<u64>,
+}
+
+/// Refresh the task list in the background, emitting `tasks-diff` with
+/// changes only (to the calling window)
+#[tauri::command]
+pub async fn list_autorefresh_start(
+    app: AppHandle,
+    window: Window,
+    state: State<'_, AppState>,
+    interval_ms: Option<u64>,
+    filters: Option<ListFilters>,
+) -> Result<ListAutorefreshResponse, String> {
+    let interval_ms = state.list_refresh.start(
+        &app,
+        state.bridge.clone(),
+        window.label(),
+        interval_ms.unwrap_or(5000),
+        filters.unwrap_or_default(),
+    );
+    Ok(ListAutorefreshResponse {
+        success: true,
+        running: true,
+        interval_ms: Some(interval_ms),
+    })
+}
+
+#[tauri::command]
+pub async fn list_autorefresh_stop(
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<ListAutorefreshResponse, String> {
+    state.list_refresh.stop(window.label());
+    Ok(ListAutorefreshResponse {
+        success: true,
+        running: false,
+        interval_ms: None,
+    })
 }
 
 #[tauri::command]
 pub async fn backend_set_storage_mode(
+    app: AppHandle,
     state: State<'_, AppState>,
     mode: String,
 ) -> Result<BackendStorageModeResponse, String> {
-    let bridge = state.bridge.lock().await;
-
-    match bridge.set_storage_mode(&mode).await {
-        Ok(restarted) => Ok(BackendStorageModeResponse {
+    let response = match state.bridge.set_storage_mode(&mode).await {
+        Ok(restarted) => BackendStorageModeResponse {
             success: true,
-            mode: bridge.storage_mode_str().to_string(),
+            mode: state.bridge.storage_mode_str().to_string(),
             restarted,
-            error: None,
-        }),
-        Err(e) => Ok(BackendStorageModeResponse {
+            error: ResponseError::none(),
+        },
+        Err(e) => BackendStorageModeResponse {
             success: false,
             mode,
             restarted: false,
-            error: Some(e.to_string()),
-        }),
+            error: ResponseError::from(&e),
+        },
+    };
+    if response.restarted {
+        // Possibly a different storage now
+        state.read_cache.lock().await.clear();
+        super::storage::refollow_storage(&app, &state).await;
     }
+    Ok(response)
 }
@@ -0,0 +1,356 @@
+//! Task template commands
+//!
+//! Templates live in app data, not the project sidecar, so every project
+//! sees the same ones. `tasks_template_subtasks` asks the backend for its
+//! own step templates instead.
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use serde_json::{json, Value};
+use tauri::{AppHandle, State};
+
+use crate::backend;
+use crate::error_catalog::ResponseError;
+use crate::quick_add;
+use crate::sidecar;
+use crate::subtask_plan::SubtaskPlan;
+use crate::templates::{
+    self, TaskTemplate, TemplateError, TemplateOverrides, Templates, TEMPLATES_FILE,
+};
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TemplateResponse {
+    pub success: bool,
+    pub name: String,
+    /// As stored (trimmed)
+    pub template: Option<TaskTemplate>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TemplatesResponse {
+    pub success: bool,
+    pub templates: Templates,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TemplateDeleteResponse {
+    pub success: bool,
+    pub name: String,
+    /// Whether there was such a template
+    pub deleted: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TemplateExportResponse {
+    pub success: bool,
+    pub name: String,
+    pub path: String,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TemplateCreateResponse {
+    pub success: bool,
+    pub task_id: Option<String>,
+    pub title: Option<String>,
+    /// Created, but tags or domain could not be set
+    pub warning: Option<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TemplateSubtasksResponse {
+    pub success: bool,
+    pub plan: Option<SubtaskPlan>,
+    /// Options the backend does not take, dropped from the call
+    pub warnings: Vec<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+fn templates_path(state: &AppState) -> PathBuf {
+    state.data_dir.join(TEMPLATES_FILE)
+}
+
+fn load(state: &AppState) -> anyhow::Result<Templates> {
+    sidecar::read_json(&templates_path(state))
+}
+
+/// Store `template` as `name`; refuses to overwrite unless `replace`
+fn store(
+    state: &AppState,
+    name: &str,
+    template: &TaskTemplate,
+    replace: bool,
+) -> anyhow::Result<(String, TaskTemplate)> {
+    let name = templates::normalize_name(name)?;
+    let template = templates::validate(template)?;
+    let mut all = load(state)?;
+    if !replace && all.contains_key(&name) {
+        return Err(TemplateError::Exists(name).into());
+    }
+    all.insert(name.clone(), template.clone());
+    sidecar::write_json(&templates_path(state), &all)?;
+    Ok((name, template))
+}
+
+fn template_response(
+    name: String,
+    stored: anyhow::Result<(String, TaskTemplate)>,
+) -> TemplateResponse {
+    match stored {
+        Ok((name, template)) => TemplateResponse {
+            success: true,
+            name,
+            template: Some(template),
+            error: ResponseError::none(),
+        },
+        Err(e) => TemplateResponse {
+            success: false,
+            name,
+            template: None,
+            error: ResponseError::from(&e),
+        },
+    }
+}
+
+/// Save (or replace) the template `name`, checked first
+#[tauri::command]
+pub async fn templates_save(
+    state: State<'_, AppState>,
+    name: String,
+    definition: TaskTemplate,
+) -> Result<TemplateResponse, String> {
+    let stored = store(&state, &name, &definition, true);
+    Ok(template_response(name, stored))
+}
+
+#[tauri::command]
+pub async fn templates_list(state: State<'_, AppState>) -> Result<TemplatesResponse, String> {
+    Ok(match load(&state) {
+        Ok(templates) => TemplatesResponse {
+            success: true,
+            templates,
+            error: ResponseError::none(),
+        },
+        Err(e) => TemplatesResponse {
+            success: false,
+            templates: Templates::new(),
+            error: ResponseError::from(&e),
+        },
+    })
+}
+
+#[tauri::command]
+pub async fn templates_delete(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<TemplateDeleteResponse, String> {
+    let name = name.trim().to_string();
+    let deleted = load(&state).and_then(|mut all| {
+        let deleted = all.remove(&name).is_some();
+        if deleted {
+            sidecar::write_json(&templates_path(&state), &all)?;
+        }
+        Ok(deleted)
+    });
+    Ok(match deleted {
+        Ok(deleted) => TemplateDeleteResponse {
+            success: true,
+            name,
+            deleted,
+            error: ResponseError::none(),
+        },
+        Err(e) => TemplateDeleteResponse {
+            success: false,
+            name,
+            deleted: false,
+            error: ResponseError::from(&e),
+        },
+    })
+}
+
+/// Write the template `name` to its own JSON file (`path` relative to the
+/// project directory)
+#[tauri::command]
+pub async fn templates_export(
+    state: State<'_, AppState>,
+    name: String,
+    path: String,
+) -> Result<TemplateExportResponse, String> {
+    let name = name.trim().to_string();
+    let path = state.user_cwd().join(path.trim());
+    let written = load(&state).and_then(|all| {
+        let template = all
+            .get(&name)
+            .ok_or_else(|| TemplateError::NotFound(name.clone()))?;
+        std::fs::write(&path, templates::to_file(&name, template)?)
+            .with_context(|| format!("Failed to write {:?}", path))
+    });
+    Ok(TemplateExportResponse {
+        success: written.is_ok(),
+        name,
+        path: path.to_string_lossy().into_owned(),
+        error: match written {
+            Ok(()) => ResponseError::none(),
+            Err(e) => ResponseError::from(&e),
+        },
+    })
+}
+
+/// Save the template in an exported file, as `name` when given (else the
+/// file's name); an existing template is only replaced with `replace`
+#[tauri::command]
+pub async fn templates_import(
+    state: State<'_, AppState>,
+    path: String,
+    name: Option<String>,
+    replace: Option<bool>,
+) -> Result<TemplateResponse, String> {
+    let path = state.user_cwd().join(path.trim());
+    let stored = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {:?}", path))
+        .and_then(|text| templates::from_file(&text))
+        .and_then(|(file_name, template)| {
+            let name = name.as_deref().unwrap_or(&file_name);
+            store(&state, name, &template, replace.unwrap_or(false))
+        });
+    let name = match &stored {
+        Ok((name, _)) => name.clone(),
+        Err(_) => name.unwrap_or_default(),
+    };
+    Ok(template_response(name, stored))
+}
+
+/// Create a task called `title` from the template `name`: one step per
+/// subtask, tags and domain set right after
+#[tauri::command]
+pub async fn tasks_create_from_template(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+    title: String,
+    overrides: Option<TemplateOverrides>,
+) -> Result<TemplateCreateResponse, String> {
+    let response =
+        create_from_template_response(Some(&app), &state, &name, &title, overrides).await;
+    state
+        .usage_metrics
+        .record_outcome("tasks_create_from_template", response.success);
+    Ok(response)
+}
+
+pub(crate) async fn create_from_template_response(
+    app: Option<&AppHandle>,
+    state: &AppState,
+    name: &str,
+    title: &str,
+    overrides: Option<TemplateOverrides>,
+) -> TemplateCreateResponse {
+    let created = async {
+        let name = name.trim();
+        let template = load(state)?
+            .remove(name)
+            .ok_or_else(|| TemplateError::NotFound(name.to_string()))?;
+        let expanded = templates::expand(&template, title, overrides.unwrap_or_default())?;
+        let title = expanded.params["title"].as_str().map(String::from);
+        let (task_id, warning) = quick_add::create_task(
+            app,
+            state,
+            expanded.params,
+            &expanded.tags,
+            expanded.domain.as_deref(),
+        )
+        .await?;
+        anyhow::Ok((task_id, title, warning))
+    };
+    match created.await {
+        Ok((task_id, title, warning)) => TemplateCreateResponse {
+            success: true,
+            task_id: Some(task_id),
+            title,
+            warning,
+            error: ResponseError::none(),
+        },
+        Err(e) => TemplateCreateResponse {
+            success: false,
+            task_id: None,
+            title: None,
+            warning: None,
+            error: ResponseError::from(&e),
+        },
+    }
+}
+
+/// `count` subtasks from the backend's templates, of `kind` and fitted to
+/// `context` where the backend takes them
+#[tauri::command]
+pub async fn tasks_template_subtasks(
+    state: State<'_, AppState>,
+    count: u32,
+    kind: Option<String>,
+    context: Option<String>,
+) -> Result<TemplateSubtasksResponse, String> {
+    let response = template_subtasks_response(&state, count, kind, context).await;
+    state
+        .usage_metrics
+        .record_outcome("tasks_template_subtasks", response.success);
+    Ok(response)
+}
+
+pub(crate) async fn template_subtasks_response(
+    state: &AppState,
+    count: u32,
+    kind: Option<String>,
+    context: Option<String>,
+) -> TemplateSubtasksResponse {
+    const TOOL: &str = "tasks_template_subtasks";
+    let tools = state.bridge.list_tools().await.unwrap_or_default();
+    let mut args = json!({ "count": count });
+    let mut warnings = Vec::new();
+    for (field, value) in [("kind", kind), ("context", context)] {
+        let Some(value) = value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+        else {
+            continue;
+        };
+        if backend::tool_accepts(&tools, TOOL, field) {
+            args[field] = Value::String(value);
+        } else {
+            warnings.push(format!(
+                "The backend takes no {}; {:?} was ignored",
+                field, value
+            ));
+        }
+    }
+    let plan = async {
+        let result = backend::into_result(state.bridge.call_tool(TOOL, args).await?)?;
+        SubtaskPlan::from_result(TOOL, &result)
+            .with_context(|| format!("{} sent no subtasks", TOOL))
+    };
+    match plan.await {
+        Ok(plan) => TemplateSubtasksResponse {
+            success: true,
+            plan: Some(plan),
+            warnings,
+            error: ResponseError::none(),
+        },
+        Err(e) => TemplateSubtasksResponse {
+            success: false,
+            plan: None,
+            warnings,
+            error: ResponseError::from(&e),
+        },
+    }
+}
@@ -0,0 +1,2046 @@
+//! Command handlers against the fake MCP server
+//!
+//! Each test manages an [`AppState`] whose bridge runs `fake-mcp-server`
+//! (see `python::fake_server`) in a mock Tauri app, calls the command
+//! functions directly and checks both the `tools/call` arguments the server
+//! logged and the response the frontend would get. Commands that need an
+//! `AppHandle` of the real runtime are covered by their domain modules.
+
+use std::path::PathBuf;
+
+use chrono::Local;
+use serde_json::{json, Value};
+use tauri::test::MockRuntime;
+use tauri::{App, Manager, State};
+
+use super::*;
+use crate::ai_response::AIResponse;
+use crate::due::DueFilter;
+use crate::error_catalog::ErrorCode;
+use crate::evidence::{EvidenceItem, EvidenceKind};
+use crate::journal::Mutation;
+use crate::python::fake_server::FakeServer;
+use crate::session::Session;
+use crate::AppState;
+
+const RESUME_TASK: &str = include_str!("../../tests/fixtures/tasks_resume_task.json");
+const RESUME_PLAN: &str = include_str!("../../tests/fixtures/tasks_resume_plan.json");
+const CONTEXT: &str = include_str!("../../tests/fixtures/tasks_context.json");
+const SUGGESTIONS: &str = include_str!("../../tests/fixtures/ai_intent_suggestions.json");
+
+struct Harness {
+    app: App<MockRuntime>,
+    log: PathBuf,
+    _server: FakeServer,
+}
+
+impl Harness {
+    /// App whose backend answers `tools` (scenario `tools` entries)
+    fn new(name: &str, tools: Value) -> Self {
+        let log = std::env::temp_dir().join(format!(
+            "apply-task-cmd-{}-{}.log",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&log);
+        let server = FakeServer::new(
+            &format!("cmd-{}", name),
+            &json!({ "log": log, "tools": tools }),
+        );
+        let app = tauri::test::mock_app();
+        app.manage(AppState::for_tests(server.bridge(), &server.dir));
+        Self {
+            app,
+            log,
+            _server: server,
+        }
+    }
+
+    fn state(&self) -> State<'_, AppState> {
+        self.app.state()
+    }
+
+    /// `(tool, arguments)` of every `tools/call` the server received
+    fn calls(&self) -> Vec<(String, Value)> {
+        std::fs::read_to_string(&self.log)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            .filter(|message| message["method"] == "tools/call")
+            .map(|message| {
+                let params = &message["params"];
+                let tool = params["name"].as_str().unwrap_or_default().to_string();
+                (tool, params["arguments"].clone())
+            })
+            .collect()
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.log);
+    }
+}
+
+/// Scenario entry answering with the backend envelope `envelope`
+fn answer(envelope: Value) -> Value {
+    json!({ "result": { "content": [{ "type": "json", "json": envelope }] } })
+}
+
+fn answer_fixture(fixture: &str) -> Value {
+    answer(serde_json::from_str(fixture).unwrap())
+}
+
+fn ok(result: Value) -> Value {
+    answer(json!({ "success": true, "result": result }))
+}
+
+fn reported(code: &str, message: &str) -> Value {
+    answer(json!({ "success": false, "error": { "code": code, "message": message } }))
+}
+
+/// Plain prose where JSON was expected
+fn garbled() -> Value {
+    json!({ "result": { "content": [{ "type": "text", "text": "Traceback (most recent call last):" }] } })
+}
+
+#[tokio::test]
+async fn test_tasks_show() {
+    let harness = Harness::new(
+        "show",
+        json!({ "tasks_resume": answer_fixture(RESUME_TASK) }),
+    );
+    let shown = tasks_show(harness.state(), "TASK-001".into(), None, None, None, None)
+        .await
+        .unwrap();
+    assert!(shown.success);
+    let task = shown.task.unwrap();
+    assert_eq!(task["id"], "TASK-001");
+    // Computed from the fixture's steps
+    assert_eq!(task["progress"]["total"], 4);
+    assert_eq!(
+        harness.calls(),
+        [(
+            "tasks_resume".to_string(),
+            json!({ "task": "TASK-001", "compact": false })
+        )]
+    );
+
+    // A second look is a cache hit
+    tasks_show(harness.state(), "TASK-001".into(), None, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(harness.calls().len(), 1);
+
+    // Plans come back under `plan`
+    let harness = Harness::new(
+        "show-plan",
+        json!({ "tasks_resume": answer_fixture(RESUME_PLAN) }),
+    );
+    let shown = tasks_show(harness.state(), "PLAN-001".into(), None, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(shown.task.unwrap()["id"], "PLAN-001");
+}
+
+#[tokio::test]
+async fn test_tasks_show_failures() {
+    let cases = [
+        (
+            "show-reported",
+            reported("NOT_FOUND", "No task TASK-9"),
+            ErrorCode::Backend,
+        ),
+        ("show-garbled", garbled(), ErrorCode::Internal),
+        // Envelope without `task` or `plan`
+        ("show-empty", ok(json!({})), ErrorCode::TaskNotFound),
+    ];
+    for (name, entry, code) in cases {
+        let harness = Harness::new(name, json!({ "tasks_resume": entry }));
+        let shown = tasks_show(harness.state(), "TASK-9".into(), None, None, None, None)
+            .await
+            .unwrap();
+        assert!(!shown.success, "{}", name);
+        assert!(shown.task.is_none());
+        assert_eq!(shown.error.code, Some(code), "{}", name);
+    }
+}
+
+#[tokio::test]
+async fn test_coalesced_reads_keep_the_bridge_error() {
+    // Both go through the merged read path, and the backend dies mid-call
+    let harness = Harness::new(
+        "reads-disconnected",
+        json!({ "tasks_resume": { "exit": 3 }, "tasks_context": { "exit": 3 } }),
+    );
+    let shown = tasks_show(harness.state(), "TASK-001".into(), None, None, None, None)
+        .await
+        .unwrap();
+    assert!(!shown.success);
+    assert_eq!(shown.error.code, Some(ErrorCode::BridgeDisconnected));
+
+    let listed = tasks_list(harness.state(), None, None, None, None, None, None, None)
+        .await
+        .unwrap();
+    assert!(!listed.success);
+    assert_eq!(listed.error.code, Some(ErrorCode::BridgeDisconnected));
+}
+
+#[tokio::test]
+async fn test_tasks_context_and_refresh() {
+    let harness = Harness::new(
+        "context",
+        json!({ "tasks_context": answer_fixture(CONTEXT) }),
+    );
+    let params = json!({ "task": "TASK-001" });
+    let context = tasks_context(harness.state(), Some(params.clone()), None, None)
+        .await
+        .unwrap();
+    assert!(context.success);
+    assert_eq!(context.focus_id.as_deref(), Some("TASK-001"));
+    tasks_context(harness.state(), Some(params.clone()), None, None)
+        .await
+        .unwrap();
+
+    let refreshed = context_refresh(harness.state(), Some("TASK-001".into()), None, None)
+        .await
+        .unwrap();
+    assert!(refreshed.success);
+    assert_eq!(
+        harness.calls(),
+        [
+            ("tasks_context".to_string(), params.clone()),
+            ("tasks_context".to_string(), params),
+        ]
+    );
+
+    let metrics = bridge_metrics(harness.state()).await.unwrap();
+    assert_eq!(
+        (metrics.context_cache_hits, metrics.context_cache_misses),
+        (1, 1)
+    );
+}
+
+#[tokio::test]
+async fn test_tasks_context_failures() {
+    let harness = Harness::new(
+        "context-reported",
+        json!({ "tasks_context": reported("STORAGE", "Storage is locked") }),
+    );
+    let context = tasks_context(harness.state(), None, None, None)
+        .await
+        .unwrap();
+    assert!(!context.success);
+    assert_eq!(context.error.message.as_deref(), Some("Storage is locked"));
+
+    let harness = Harness::new("context-garbled", json!({ "tasks_context": garbled() }));
+    let context = tasks_context(harness.state(), None, None, None)
+        .await
+        .unwrap();
+    assert!(!context.success);
+    assert_eq!(context.error.code, Some(ErrorCode::Internal));
+}
+
+#[tokio::test]
+async fn test_tasks_list() {
+    let harness = Harness::new("list", json!({ "tasks_context": answer_fixture(CONTEXT) }));
+    let listed = tasks_list(
+        harness.state(),
+        Some("todo".into()),
+        None,
+        Some("PLAN-001".into()),
+        None,
+        Some(vec!["id".into(), "title".into()]),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(listed.success);
+    assert_eq!(listed.total, listed.tasks.len());
+    assert!(listed.total > 0);
+    // Plans are dropped, and the projection keeps only the asked keys
+    assert!(listed.tasks.iter().all(|t| t.get("kind").is_none()));
+    assert!(listed.tasks.iter().all(|t| t.get("title").is_some()));
+    assert_eq!(
+        harness.calls(),
+        [(
+            "tasks_context".to_string(),
+            json!({ "include_all": true, "compact": true, "tasks_parent": "PLAN-001" })
+        )]
+    );
+
+    let harness = Harness::new("list-garbled", json!({ "tasks_context": garbled() }));
+    let listed = tasks_list(harness.state(), None, None, None, None, None, None, None)
+        .await
+        .unwrap();
+    assert!(!listed.success);
+    assert!(listed.tasks.is_empty());
+    assert_eq!(listed.error.code, Some(ErrorCode::Internal));
+}
+
+#[tokio::test]
+async fn test_tasks_prefetch() {
+    let harness = Harness::new(
+        "prefetch",
+        json!({ "tasks_resume": answer_fixture(RESUME_TASK) }),
+    );
+    let ids = ["TASK-001", "TASK-002", "TASK-001", " "].map(String::from);
+    let fetched = tasks_prefetch(harness.state(), ids.to_vec(), None, None)
+        .await
+        .unwrap();
+    assert!(fetched.success);
+    let ids: Vec<&str> = fetched.results.iter().map(|r| r.task_id.as_str()).collect();
+    assert_eq!(ids, ["TASK-001", "TASK-002"]);
+    assert!(fetched.results.iter().all(|r| r.success && !r.cached));
+    assert_eq!(harness.calls().len(), 2);
+
+    let again = tasks_prefetch(harness.state(), vec!["TASK-002".into()], None, None)
+        .await
+        .unwrap();
+    assert!(again.results[0].cached);
+    assert_eq!(harness.calls().len(), 2);
+    // tasks_show is served from what the prefetch cached
+    tasks_show(harness.state(), "TASK-001".into(), None, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(harness.calls().len(), 2);
+
+    let harness = Harness::new(
+        "prefetch-reported",
+        json!({ "tasks_resume": reported("NOT_FOUND", "No task") }),
+    );
+    let failed = tasks_prefetch(harness.state(), vec!["TASK-9".into()], None, None)
+        .await
+        .unwrap();
+    assert!(failed.success);
+    assert!(!failed.results[0].success);
+    assert_eq!(failed.results[0].error.code, Some(ErrorCode::Backend));
+}
+
+#[tokio::test]
+async fn test_tasks_set_due_and_due() {
+    let harness = Harness::new(
+        "due-backend",
+        json!({ "tasks_set_due": ok(json!({ "task": "TASK-001" })) }),
+    );
+    let set = tasks_set_due(
+        harness.state(),
+        "TASK-001".into(),
+        Some("2026-10-20".into()),
+    )
+    .await
+    .unwrap();
+    assert!(set.success);
+    assert_eq!(set.storage, "backend");
+    assert_eq!(
+        harness.calls(),
+        [(
+            "tasks_set_due".to_string(),
+            json!({ "task": "TASK-001", "due": "2026-10-20" })
+        )]
+    );
+    let state = harness.state();
+    let journaled = state.journal.lock().await.list(1).remove(0);
+    assert_eq!(journaled.tool, "tasks_set_due");
+    assert_eq!(journaled.after.as_deref(), Some("2026-10-20"));
+    let dir = crate::audit::dir(&state);
+    let mut entries = Vec::new();
+    for _ in 0..100 {
+        entries = crate::audit::query(&dir, None, None, Some("TASK-001"), 10).unwrap();
+        if !entries.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(entries.len(), 1, "{:?}", entries);
+    assert_eq!(entries[0].command, "tasks_set_due");
+    assert_eq!(entries[0].outcome, crate::audit::Outcome::Applied);
+
+    let invalid = tasks_set_due(harness.state(), "TASK-001".into(), Some("soon".into()))
+        .await
+        .unwrap();
+    assert!(!invalid.success);
+    assert_eq!(
+        invalid.error.code,
+        Some(ErrorCode::ValidationInvalidDueDate)
+    );
+    assert_eq!(harness.calls().len(), 1);
+
+    // No backend tool: the sidecar keeps the date and `tasks_due` reads it
+    let harness = Harness::new(
+        "due-sidecar",
+        json!({ "tasks_context": answer_fixture(CONTEXT) }),
+    );
+    let today = Local::now().date_naive().to_string();
+    let set = tasks_set_due(harness.state(), "TASK-001".into(), Some(today))
+        .await
+        .unwrap();
+    assert_eq!(set.storage, "sidecar");
+    let due = tasks_due(harness.state(), DueFilter::Today).await.unwrap();
+    assert!(due.success);
+    assert_eq!(due.tasks.len(), 1);
+    assert_eq!(due.tasks[0].task_id, "TASK-001");
+    assert_eq!(due.tasks[0].source, "sidecar");
+    assert_eq!(
+        harness.calls(),
+        [(
+            "tasks_context".to_string(),
+            json!({ "include_all": true, "compact": true })
+        )]
+    );
+}
+
+#[tokio::test]
+async fn test_signals() {
+    let harness = Harness::new(
+        "signal",
+        json!({ "tasks_send_signal": answer(json!({ "success": true, "result": { "queued": true } })) }),
+    );
+    let sent = tasks_send_signal(harness.state(), "Pause".into(), Some("lunch".into()))
+        .await
+        .unwrap();
+    assert!(sent.success);
+    assert_eq!(sent.entry.as_ref().unwrap().signal, "pause");
+    assert_eq!(
+        harness.calls(),
+        [(
+            "tasks_send_signal".to_string(),
+            json!({ "signal": "pause", "message": "lunch" })
+        )]
+    );
+
+    let unknown = tasks_send_signal(harness.state(), "jump".into(), None)
+        .await
+        .unwrap();
+    assert!(!unknown.success);
+    assert!(unknown.entry.is_none());
+    assert_eq!(harness.calls().len(), 1);
+
+    let history = tasks_signal_history(harness.state(), None).await.unwrap();
+    assert_eq!(history.entries.len(), 1);
+    assert!(history.entries[0].delivered);
+
+    let harness = Harness::new(
+        "signal-reported",
+        json!({ "tasks_send_signal": reported("NO_AGENT", "No agent is running") }),
+    );
+    let sent = tasks_send_signal(harness.state(), "stop".into(), None)
+        .await
+        .unwrap();
+    assert!(!sent.success);
+    assert!(!sent.entry.unwrap().delivered);
+    assert_eq!(sent.error.message.as_deref(), Some("No agent is running"));
+}
+
+#[tokio::test]
+async fn test_ai_pause_all_is_idempotent() {
+    let busy = json!({
+        "status": "executing",
+        "current": { "op": "tasks_edit", "path": "TASK-001.s:0" }
+    });
+    let harness = Harness::new(
+        "pause-all",
+        json!({
+            "tasks_ai_status": answer(busy),
+            "tasks_send_signal": ok(json!({ "queued": true })),
+        }),
+    );
+    let paused = ai_pause_all(harness.state()).await.unwrap();
+    assert!(paused.success);
+    assert!(!paused.no_op);
+    let record = paused.paused.unwrap();
+    assert_eq!(record.status, "executing");
+    assert_eq!(record.tasks, ["TASK-001"]);
+    assert!(harness
+        ._server
+        .dir
+        .join("data")
+        .join("ai_pause.json")
+        .exists());
+
+    let again = ai_pause_all(harness.state()).await.unwrap();
+    assert!(again.success && again.no_op);
+    assert_eq!(again.paused, Some(record.clone()));
+
+    // The AI runs again (resumed elsewhere): nothing of ours to resume
+    let resumed = ai_resume_all(harness.state()).await.unwrap();
+    assert!(resumed.success && resumed.no_op);
+    let nothing = ai_resume_all(harness.state()).await.unwrap();
+    assert!(nothing.success && nothing.no_op && nothing.paused.is_none());
+    let signals: Vec<Value> = harness
+        .calls()
+        .into_iter()
+        .filter(|(tool, _)| tool == "tasks_send_signal")
+        .map(|(_, args)| args)
+        .collect();
+    assert_eq!(signals, [json!({ "signal": "pause", "message": "" })]);
+}
+
+#[tokio::test]
+async fn test_ai_resume_all_only_resumes_own_pause() {
+    let harness = Harness::new(
+        "resume-all",
+        json!({
+            "tasks_ai_status": answer(json!({ "status": "paused", "current": null })),
+            "tasks_send_signal": ok(json!({ "queued": true })),
+        }),
+    );
+    // Paused before: pause all leaves it to whoever paused it
+    let paused = ai_pause_all(harness.state()).await.unwrap();
+    assert!(paused.success && paused.no_op && paused.paused.is_none());
+    let resumed = ai_resume_all(harness.state()).await.unwrap();
+    assert!(resumed.no_op);
+    assert!(harness
+        .calls()
+        .iter()
+        .all(|(tool, _)| tool != "tasks_send_signal"));
+
+    // Restarted with a pause of ours on disk
+    let dir = harness._server.dir.join("data");
+    let record = json!({
+        "paused_at": "2026-10-14T09:00:00Z",
+        "status": "thinking",
+        "tasks": ["TASK-002"],
+        "signal_id": 1
+    });
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("ai_pause.json"), record.to_string()).unwrap();
+    *harness.state().ai_pause.lock().await =
+        crate::ai_pause::PauseStore::load(dir.join("ai_pause.json"));
+    let resumed = ai_resume_all(harness.state()).await.unwrap();
+    assert!(resumed.success && !resumed.no_op);
+    assert_eq!(resumed.paused.unwrap().tasks, ["TASK-002"]);
+    assert_eq!(resumed.entry.unwrap().signal, "resume");
+    assert!(harness.state().ai_pause.lock().await.get().is_none());
+}
+
+#[tokio::test]
+async fn test_ai_signal_respond() {
+    let status = json!({
+        "ai_signals": [
+            { "id": "q1", "message": "Pick a schema", "task": "TASK-001", "correlation_id": "c-1" },
+            { "message": "Ship it?" }
+        ]
+    });
+    let harness = Harness::new(
+        "ai-signal",
+        json!({ "tasks_send_signal": ok(json!({ "queued": true })) }),
+    );
+    let raised = harness
+        .state()
+        .ai_signals
+        .lock()
+        .await
+        .observe(crate::ai_signals::extract(&status));
+    let hashed = raised[1].id.clone();
+
+    let missing = ai_signal_respond(harness.state(), "nope".into(), "yes".into())
+        .await
+        .unwrap();
+    assert!(!missing.success);
+    assert_eq!(missing.error.code, Some(ErrorCode::AiSignalNotFound));
+    assert!(harness.calls().is_empty());
+
+    // No `correlation_id` in the schema: it goes into the message
+    let answered = ai_signal_respond(harness.state(), hashed.clone(), " Yes ".into())
+        .await
+        .unwrap();
+    assert!(answered.success);
+    assert!(answered.warning.is_some());
+    assert_eq!(
+        harness.calls(),
+        [(
+            "tasks_send_signal".to_string(),
+            json!({ "signal": "message", "message": format!("[re {}] Yes", hashed) })
+        )]
+    );
+    assert_eq!(
+        harness.state().ai_signals.lock().await.pending().len(),
+        1,
+        "answered signals leave the inbox"
+    );
+
+    let mut send = ok(json!({ "queued": true }));
+    send["input_schema"] = json!({ "type": "object", "properties": { "correlation_id": {} } });
+    let harness = Harness::new("ai-signal-correlated", json!({ "tasks_send_signal": send }));
+    harness
+        .state()
+        .ai_signals
+        .lock()
+        .await
+        .observe(crate::ai_signals::extract(&status));
+    let answered = ai_signal_respond(harness.state(), "q1".into(), "Postgres".into())
+        .await
+        .unwrap();
+    assert!(answered.success);
+    assert!(answered.warning.is_none());
+    assert_eq!(
+        answered.signal.unwrap().task_id.as_deref(),
+        Some("TASK-001")
+    );
+    assert_eq!(
+        harness.calls(),
+        [(
+            "tasks_send_signal".to_string(),
+            json!({ "signal": "message", "message": "Postgres", "correlation_id": "c-1" })
+        )]
+    );
+}
+
+#[tokio::test]
+async fn test_timers() {
+    let harness = Harness::new("timer", json!({}));
+    let started = tasks_timer_start(harness.state(), "TASK-001".into())
+        .await
+        .unwrap();
+    assert!(started.success);
+    assert_eq!(started.timer.as_ref().unwrap().task_id, "TASK-001");
+
+    let switched = tasks_timer_start(harness.state(), "TASK-002".into())
+        .await
+        .unwrap();
+    assert_eq!(switched.auto_stopped.unwrap().task_id, "TASK-001");
+
+    let stopped = tasks_timer_stop(harness.state(), "TASK-002".into(), Some(" done ".into()))
+        .await
+        .unwrap();
+    assert!(stopped.success);
+    assert_eq!(stopped.entry.unwrap().note.as_deref(), Some("done"));
+    let again = tasks_timer_stop(harness.state(), "TASK-002".into(), None)
+        .await
+        .unwrap();
+    assert_eq!(again.error.code, Some(ErrorCode::TimerNotRunning));
+
+    let report = tasks_time_report(harness.state(), None).await.unwrap();
+    assert!(report.success);
+    assert!(report.running.is_none());
+
+    // A project switch stops the timer into the project being left
+    tasks_timer_start(harness.state(), "TASK-003".into())
+        .await
+        .unwrap();
+    let state = harness.state();
+    let left = state.project_dir();
+    let stopped = stop_for_project_switch(&state, &left).await.unwrap();
+    assert_eq!(stopped.task_id, "TASK-003");
+    *state.user_cwd.lock().unwrap() = left.join("other-project");
+    let report = tasks_time_report(harness.state(), None).await.unwrap();
+    assert!(report.running.is_none());
+    assert!(report.report.by_task.is_empty());
+    let entries: Vec<crate::timer::TimeEntry> =
+        crate::sidecar::read_jsonl(&left.join(crate::timer::TIME_ENTRIES_FILE)).unwrap();
+    assert_eq!(entries.last().unwrap().task_id, "TASK-003");
+    // Timers never reach the backend
+    assert!(harness.calls().is_empty());
+}
+
+#[tokio::test]
+async fn test_local_state_commands() {
+    let harness = Harness::new("local", json!({}));
+    assert!(job_list(harness.state()).await.unwrap().jobs.is_empty());
+    assert!(mutation_queue_list(harness.state())
+        .await
+        .unwrap()
+        .entries
+        .is_empty());
+    let cancelled = tasks_stream_cancel(harness.state(), "stream-1".into())
+        .await
+        .unwrap();
+    assert!(!cancelled.cancelled);
+    let metrics = bridge_metrics(harness.state()).await.unwrap();
+    assert_eq!(metrics.round_trips, 0);
+    assert!(harness.calls().is_empty());
+}
+
+#[test]
+fn test_ai_intent_suggestions_fixture() {
+    let response = AIResponse::from_value(serde_json::from_str(SUGGESTIONS).unwrap());
+    assert!(response.success);
+    // Raw suggestions pass through as sent
+    assert_eq!(response.suggestions.len(), 3);
+    let items: Vec<(&str, Option<&str>)> = response
+        .suggestion_items
+        .iter()
+        .map(|s| (s.label.as_str(), s.intent.as_deref()))
+        .collect();
+    assert_eq!(
+        items,
+        [
+            ("Run the test suite", None),
+            ("Continue TASK-001", Some("resume")),
+            ("Confirm tests for s:1", Some("verify")),
+        ]
+    );
+    assert_eq!(response.suggestion_items[1].params, None);
+    assert_eq!(
+        response.suggestion_items[2].params.as_ref().unwrap()["checkpoint"],
+        "tests"
+    );
+}
+
+#[tokio::test]
+async fn test_recognized_suggestion_runs_its_intent() {
+    let harness = Harness::new(
+        "suggestion",
+        json!({ "tasks_verify": ok(json!({
+            "task_id": "TASK-001",
+            "checkpoints_after": { "tests": { "confirmed": true } }
+        })) }),
+    );
+    let response = AIResponse::from_value(json!({
+        "success": true,
+        "intent": "radar",
+        "context": { "task_id": "TASK-001" },
+        "suggestions": ["Confirm tests for subtask 2", "Run the test suite"]
+    }));
+    let [recognized, label_only] = response.suggestion_items.as_slice() else {
+        panic!("expected two suggestions");
+    };
+
+    let ran = execute_response(harness.app.handle(), &harness.state(), recognized.clone()).await;
+    assert!(ran.success);
+    assert_eq!(ran.resolved_tool.as_deref(), Some("tasks_verify"));
+    assert!(matches!(
+        ran.typed,
+        Some(crate::intent_result::IntentResult::Verified { .. })
+    ));
+    assert_eq!(
+        harness.calls(),
+        [(
+            "tasks_verify".to_string(),
+            json!({
+                "task": "TASK-001",
+                "kind": "step",
+                "path": "s:1",
+                "checkpoints": { "tests": { "confirmed": true } }
+            })
+        )]
+    );
+
+    let refused =
+        execute_response(harness.app.handle(), &harness.state(), label_only.clone()).await;
+    assert!(!refused.success);
+    assert_eq!(
+        refused.error.unwrap()["code"],
+        "VALIDATION_SUGGESTION_ACTION"
+    );
+    assert_eq!(harness.calls().len(), 1, "refused before the backend");
+}
+
+#[tokio::test]
+async fn test_fault_injection_needs_developer_mode() {
+    let harness = Harness::new(
+        "faults",
+        json!({ "tasks_resume": answer_fixture(RESUME_TASK) }),
+    );
+    let rules: Vec<crate::python::FaultRule> = serde_json::from_value(
+        json!([{ "tool": "tasks_resume", "failure": "tool_error", "count": 1 }]),
+    )
+    .unwrap();
+    let refused = set_fault_injection(harness.state(), rules.clone())
+        .await
+        .unwrap();
+    assert!(!refused.success);
+    assert_eq!(refused.error.code, Some(ErrorCode::FaultInjectionDisabled));
+    assert!(get_fault_injection(harness.state())
+        .await
+        .unwrap()
+        .rules
+        .is_empty());
+
+    harness.state().settings.write().await.developer_mode = true;
+    assert!(
+        set_fault_injection(harness.state(), rules)
+            .await
+            .unwrap()
+            .success
+    );
+    // The injected error is catalogued like a real one
+    let shown = tasks_show(harness.state(), "TASK-001".into(), None, None, None, None)
+        .await
+        .unwrap();
+    assert!(!shown.success);
+    assert_eq!(shown.error.code, Some(ErrorCode::Backend));
+    assert_eq!(
+        get_fault_injection(harness.state()).await.unwrap().rules[0].injected,
+        1
+    );
+    assert!(harness.calls().is_empty());
+
+    let shown = tasks_show(harness.state(), "TASK-001".into(), None, None, None, None)
+        .await
+        .unwrap();
+    assert!(shown.success);
+    clear_fault_injection(harness.state()).await.unwrap();
+    assert!(get_fault_injection(harness.state())
+        .await
+        .unwrap()
+        .rules
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_task_templates() {
+    use crate::templates::{SubtaskTemplate, TaskTemplate, TemplateOverrides};
+
+    let harness = Harness::new(
+        "templates",
+        json!({
+            "tasks_create": ok(json!({ "task_id": "TASK-010" })),
+            "tasks_edit": ok(json!({ "task": { "id": "TASK-010" } })),
+        }),
+    );
+    let bugfix = TaskTemplate {
+        title_pattern: "Bugfix: {title}".into(),
+        tags: vec!["bug".into()],
+        subtasks: vec![SubtaskTemplate {
+            title: "Reproduce {title}".into(),
+            checkpoints: vec!["Failing test".into(), "failing TEST".into()],
+        }],
+        ..TaskTemplate::default()
+    };
+    let refused = templates_save(harness.state(), "bugfix".into(), bugfix.clone())
+        .await
+        .unwrap();
+    assert_eq!(
+        refused.error.code,
+        Some(ErrorCode::ValidationTemplateDuplicateCheckpoint)
+    );
+
+    let mut bugfix = bugfix;
+    bugfix.subtasks[0].checkpoints.pop();
+    let saved = templates_save(harness.state(), " bugfix ".into(), bugfix)
+        .await
+        .unwrap();
+    assert!(saved.success, "{:?}", saved.error);
+    assert_eq!(saved.name, "bugfix");
+    let listed = templates_list(harness.state()).await.unwrap();
+    assert_eq!(listed.templates.keys().collect::<Vec<_>>(), ["bugfix"]);
+
+    let created = create_from_template_response(
+        None,
+        &harness.state(),
+        "bugfix",
+        "Login loop",
+        Some(TemplateOverrides {
+            parent: Some("PLAN-001".into()),
+            ..TemplateOverrides::default()
+        }),
+    )
+    .await;
+    assert!(created.success, "{:?}", created.error);
+    assert_eq!(created.task_id.as_deref(), Some("TASK-010"));
+    assert_eq!(
+        harness.calls(),
+        [
+            (
+                "tasks_create".to_string(),
+                json!({
+                    "kind": "task",
+                    "title": "Bugfix: Login loop",
+                    "parent": "PLAN-001",
+                    "steps": [{ "title": "Reproduce Login loop", "success_criteria": ["Failing test"] }],
+                })
+            ),
+            (
+                "tasks_edit".to_string(),
+                json!({ "task": "TASK-010", "tags": ["bug"] })
+            ),
+        ]
+    );
+
+    // Shared as a file, imported under another name
+    let file = harness._server.dir.join("bugfix.json");
+    let file_path = file.to_string_lossy().into_owned();
+    let exported = templates_export(harness.state(), "bugfix".into(), file_path.clone())
+        .await
+        .unwrap();
+    assert!(exported.success, "{:?}", exported.error);
+    let clash = templates_import(harness.state(), file_path.clone(), None, None)
+        .await
+        .unwrap();
+    assert_eq!(clash.error.code, Some(ErrorCode::TemplateExists));
+    let imported = templates_import(harness.state(), file_path, Some("bug".into()), None)
+        .await
+        .unwrap();
+    assert!(imported.success, "{:?}", imported.error);
+    assert_eq!(imported.template, saved.template);
+
+    let deleted = templates_delete(harness.state(), "bugfix".into())
+        .await
+        .unwrap();
+    assert!(deleted.deleted);
+    let missing = create_from_template_response(None, &harness.state(), "bugfix", "x", None).await;
+    assert_eq!(missing.error.code, Some(ErrorCode::TemplateNotFound));
+    assert_eq!(
+        templates_list(harness.state())
+            .await
+            .unwrap()
+            .templates
+            .keys()
+            .collect::<Vec<_>>(),
+        ["bug"]
+    );
+}
+
+#[tokio::test]
+async fn test_template_subtasks_forward_only_what_the_backend_takes() {
+    let plan = ok(json!({
+        "kind": "bugfix",
+        "subtasks": [
+            { "title": "Reproduce", "success_criteria": ["bug reproduced"] },
+            { "title": "Fix", "success_criteria": ["test passes"], "tests": ["cargo test"] },
+        ]
+    }));
+
+    let harness = Harness::new(
+        "subtasks-old",
+        json!({ "tasks_template_subtasks": plan.clone() }),
+    );
+    let response = template_subtasks_response(
+        &harness.state(),
+        2,
+        Some("bugfix".into()),
+        Some("login redirect".into()),
+    )
+    .await;
+    assert!(response.success);
+    assert_eq!(response.warnings.len(), 2);
+    assert!(response.warnings[0].contains("kind"));
+    assert!(response.warnings[1].contains("context"));
+    assert_eq!(
+        harness.calls(),
+        [("tasks_template_subtasks".to_string(), json!({ "count": 2 }))]
+    );
+    let steps = response.plan.unwrap().steps;
+    assert_eq!(steps[1].title, "Fix");
+    assert_eq!(steps[1].tests, ["cargo test"]);
+
+    let mut newer = plan;
+    newer["input_schema"] = json!({
+        "type": "object",
+        "properties": { "count": {}, "kind": {}, "context": {} }
+    });
+    let harness = Harness::new("subtasks-new", json!({ "tasks_template_subtasks": newer }));
+    let response = template_subtasks_response(
+        &harness.state(),
+        2,
+        Some(" bugfix ".into()),
+        Some("login redirect".into()),
+    )
+    .await;
+    assert!(response.warnings.is_empty());
+    assert_eq!(
+        harness.calls(),
+        [(
+            "tasks_template_subtasks".to_string(),
+            json!({ "count": 2, "kind": "bugfix", "context": "login redirect" })
+        )]
+    );
+    let plan = response.plan.unwrap();
+    assert_eq!(plan.kind.as_deref(), Some("bugfix"));
+    assert_eq!(plan.steps[0].success_criteria, ["bug reproduced"]);
+}
+
+#[tokio::test]
+async fn test_create_from_markdown() {
+    let harness = Harness::new(
+        "markdown",
+        json!({
+            "tasks_create": ok(json!({ "task_id": "TASK-020" })),
+            "tasks_verify": ok(json!({ "task": { "id": "TASK-020" } })),
+        }),
+    );
+    let markdown = "# Launch\n\
+        - [ ] Prepare\n\
+        \x20 * [x] Changelog\n\
+        \x20 * [x] **Screenshots**\n\
+        - [ ] Announce\n\
+        some stray note\n";
+
+    let refused =
+        create_from_markdown_response(None, &harness.state(), markdown, None, None, None).await;
+    assert_eq!(
+        refused.error.code,
+        Some(ErrorCode::ValidationChecklistParent)
+    );
+    // The parse is still reported
+    assert_eq!(refused.lines.len(), 5);
+    assert!(harness.calls().is_empty());
+
+    let created = create_from_markdown_response(
+        None,
+        &harness.state(),
+        markdown,
+        None,
+        None,
+        Some("PLAN-002"),
+    )
+    .await;
+    assert!(created.success, "{:?}", created.error);
+    assert_eq!(created.task_id.as_deref(), Some("TASK-020"));
+    assert_eq!(created.confirmed, ["s:0"]);
+    assert_eq!(created.warning, None);
+    let journaled = harness.state().journal.lock().await.list(1).remove(0);
+    assert_eq!(journaled.tool, "tasks_verify");
+    assert_eq!(journaled.task_id.as_deref(), Some("TASK-020"));
+    assert_eq!(created.warnings.len(), 1);
+    assert_eq!(created.warnings[0].line, 6);
+    assert_eq!(
+        harness.calls(),
+        [
+            (
+                "tasks_create".to_string(),
+                json!({
+                    "kind": "task",
+                    "title": "Launch",
+                    "parent": "PLAN-002",
+                    "steps": [
+                        { "title": "Prepare", "success_criteria": ["Changelog", "Screenshots"] },
+                        { "title": "Announce", "success_criteria": ["Announce"] },
+                    ],
+                })
+            ),
+            (
+                "tasks_verify".to_string(),
+                json!({
+                    "task": "TASK-020",
+                    "path": "s:0",
+                    "checkpoints": { "criteria": { "confirmed": true } },
+                })
+            ),
+        ]
+    );
+    assert_eq!(
+        json!(created.lines[2]),
+        json!({ "line": 3, "role": "checkpoint", "subtask": 0, "checkpoint": 0, "path": "s:0" })
+    );
+
+    let empty =
+        create_from_markdown_response(None, &harness.state(), "# Title only", None, None, None)
+            .await;
+    assert_eq!(empty.error.code, Some(ErrorCode::ValidationChecklistEmpty));
+}
+
+#[tokio::test]
+async fn test_todos_scan_and_import_once() {
+    let harness = Harness::new(
+        "todos",
+        json!({
+            "tasks_create": ok(json!({ "plan_id": "PLAN-030" })),
+            "tasks_edit": ok(json!({ "task": { "id": "PLAN-030" } })),
+        }),
+    );
+    let code = harness._server.dir.join("code");
+    std::fs::create_dir_all(&code).unwrap();
+    std::fs::write(
+        code.join("app.rs"),
+        "fn main() {\n    // TODO: handle --help\n}\n",
+    )
+    .unwrap();
+
+    let scanned = scan_response(&harness.state(), Some("code".into()), Vec::new(), None).await;
+    assert!(scanned.success, "{:?}", scanned.error);
+    assert_eq!(scanned.scan.total, 1);
+    let todo = scanned.scan.files[0].todos[0].clone();
+    assert_eq!((todo.file.as_str(), todo.line), ("app.rs", 2));
+
+    let created = create_from_todos_response(None, &harness.state(), vec![todo.clone()]).await;
+    assert_eq!(created.results[0].task_id.as_deref(), Some("PLAN-030"));
+    assert!(!created.results[0].duplicate);
+    assert_eq!(
+        harness.calls(),
+        [
+            (
+                "tasks_create".to_string(),
+                json!({
+                    "title": "handle --help",
+                    "description": "TODO comment at `app.rs:2`\n\n```rs\nfn main() {\n    // TODO: handle --help\n}\n```",
+                })
+            ),
+            (
+                "tasks_edit".to_string(),
+                json!({ "task": "PLAN-030", "tags": ["from-todo"] })
+            ),
+        ]
+    );
+
+    // Proposed and created once only
+    let rescanned = scan_response(&harness.state(), Some("code".into()), Vec::new(), None).await;
+    assert_eq!(
+        (rescanned.scan.total, rescanned.scan.already_imported),
+        (0, 1)
+    );
+    let again = create_from_todos_response(None, &harness.state(), vec![todo]).await;
+    assert!(again.results[0].duplicate);
+    assert_eq!(harness.calls().len(), 2);
+
+    let missing = scan_response(&harness.state(), Some("nope".into()), Vec::new(), None).await;
+    assert_eq!(missing.error.code, Some(ErrorCode::ValidationTodoRoot));
+}
+
+#[tokio::test]
+async fn test_verify_attaches_git_info() {
+    let harness = Harness::new(
+        "verify",
+        json!({ "tasks_verify": ok(json!({ "task": { "id": "TASK-001" } })) }),
+    );
+    let args = || {
+        json!({
+            "path": "s:0",
+            "checkpoints": {
+                "criteria": { "confirmed": true, "note": " Reviewed " },
+                "tests": { "confirmed": true },
+            },
+        })
+        .as_object()
+        .cloned()
+        .unwrap()
+    };
+
+    // Not a repository: confirmed anyway, notes as given
+    let plain = verify_response(
+        None,
+        &harness.state(),
+        "TASK-001".into(),
+        args(),
+        true,
+        &[],
+        false,
+    )
+    .await;
+    assert!(plain.success, "{:?}", plain.error);
+    assert_eq!(plain.git, None);
+    assert!(plain.git_unavailable.is_some());
+    assert_eq!(
+        harness.calls()[0].1["checkpoints"]["criteria"]["note"],
+        " Reviewed "
+    );
+
+    let dir = &harness._server.dir;
+    for args in [
+        &["init", "--quiet", "--initial-branch=feature"][..],
+        &["commit", "--quiet", "--allow-empty", "-m", "init"],
+    ] {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .status();
+        if !status.is_ok_and(|s| s.success()) {
+            // No git here
+            return;
+        }
+    }
+    let info = git_info(harness.state()).await.unwrap();
+    let label = info.label.unwrap();
+    assert!(label.starts_with("feature@"), "{}", label);
+
+    let noted = verify_response(
+        None,
+        &harness.state(),
+        "TASK-001".into(),
+        args(),
+        true,
+        &[],
+        false,
+    )
+    .await;
+    assert_eq!(noted.git.as_deref(), Some(label.as_str()));
+    assert_eq!(
+        harness.calls()[1],
+        (
+            "tasks_verify".to_string(),
+            json!({
+                "task": "TASK-001",
+                "path": "s:0",
+                "checkpoints": {
+                    "criteria": { "confirmed": true, "note": format!("Reviewed ({})", label) },
+                    "tests": { "confirmed": true, "note": label },
+                },
+            })
+        )
+    );
+}
+
+fn evidence_item(kind: EvidenceKind, value: &str, label: Option<&str>) -> EvidenceItem {
+    EvidenceItem {
+        kind,
+        value: value.into(),
+        label: label.map(String::from),
+        stored: None,
+    }
+}
+
+#[tokio::test]
+async fn test_verify_attaches_evidence() {
+    let mut verify = ok(json!({ "task": { "id": "TASK-001" } }));
+    verify["input_schema"] = json!({ "type": "object", "properties": { "attachments": {} } });
+    let resume = answer_fixture(include_str!(
+        "../../tests/fixtures/tasks_resume_evidence.json"
+    ));
+    let harness = Harness::new(
+        "evidence",
+        json!({ "tasks_verify": verify, "tasks_resume": resume }),
+    );
+    let dir = &harness._server.dir;
+    std::fs::create_dir_all(dir.join("docs")).unwrap();
+    std::fs::write(dir.join("docs/review.md"), "# Review").unwrap();
+    let args = json!({ "path": "s:0", "checkpoints": { "criteria": { "confirmed": true } } });
+    let args = args.as_object().cloned().unwrap();
+    let evidence = [
+        evidence_item(EvidenceKind::File, "docs/review.md", Some("Review notes")),
+        evidence_item(EvidenceKind::CommandOutput, "3 passed", Some("pytest")),
+        evidence_item(EvidenceKind::Url, "https://ci.example.com/run/42", None),
+        evidence_item(EvidenceKind::Text, "Checked by hand", None),
+    ];
+
+    let missing = [evidence_item(EvidenceKind::File, "docs/gone.md", None)];
+    let refused = verify_response(
+        None,
+        &harness.state(),
+        "TASK-001".into(),
+        args.clone(),
+        false,
+        &missing,
+        false,
+    )
+    .await;
+    assert_eq!(refused.error.code, Some(ErrorCode::EvidenceFileNotFound));
+    assert!(harness.calls().is_empty());
+
+    let sent = verify_response(
+        None,
+        &harness.state(),
+        "TASK-001".into(),
+        args,
+        false,
+        &evidence,
+        true,
+    )
+    .await;
+    assert!(sent.success, "{:?}", sent.error);
+    assert_eq!(sent.evidence_warning, None);
+    let stored = sent.evidence[0].stored.clone().unwrap();
+    assert_eq!(std::fs::read_to_string(&stored).unwrap(), "# Review");
+    let (tool, params) = harness.calls().remove(0);
+    assert_eq!(tool, "tasks_verify");
+    assert_eq!(
+        params["attachments"],
+        json!([
+            { "kind": "file", "path": "docs/review.md", "uri": stored, "meta": { "label": "Review notes" } },
+            { "kind": "command_output", "meta": { "label": "pytest", "text": "3 passed" } },
+            { "kind": "url", "external_uri": "https://ci.example.com/run/42" },
+            { "kind": "text", "meta": { "text": "Checked by hand" } },
+        ])
+    );
+    assert_eq!(
+        params["checkpoints"]["criteria"],
+        json!({ "confirmed": true })
+    );
+
+    let read = checkpoints_response(&harness.state(), "TASK-001".into(), Some("s:0".into())).await;
+    assert!(read.success, "{:?}", read.error);
+    let criteria = &read.checkpoints[0];
+    assert_eq!(criteria.name, "criteria");
+    assert_eq!(criteria.evidence.len(), 4);
+    assert_eq!(criteria.evidence[1].value, "https://ci.example.com/run/42");
+    let nowhere =
+        checkpoints_response(&harness.state(), "TASK-001".into(), Some("s:9".into())).await;
+    assert_eq!(nowhere.error.code, Some(ErrorCode::SubtaskNotFound));
+}
+
+#[tokio::test]
+async fn test_verify_evidence_falls_back_to_notes() {
+    // The fake server's schema declares no `attachments`
+    let harness = Harness::new(
+        "evidence-note",
+        json!({ "tasks_verify": ok(json!({ "task": { "id": "TASK-001" } })) }),
+    );
+    let args = json!({
+        "checkpoints": {
+            "criteria": { "confirmed": true, "note": "Reviewed" },
+            "tests": { "confirmed": true },
+        },
+    });
+    let evidence = [
+        evidence_item(
+            EvidenceKind::Url,
+            "https://ci.example.com/run/42",
+            Some("CI run"),
+        ),
+        evidence_item(EvidenceKind::Text, "Checked by hand", None),
+    ];
+    let sent = verify_response(
+        None,
+        &harness.state(),
+        "TASK-001".into(),
+        args.as_object().cloned().unwrap(),
+        false,
+        &evidence,
+        false,
+    )
+    .await;
+    assert!(sent.success, "{:?}", sent.error);
+    assert!(sent.evidence_warning.is_some());
+    let lines =
+        "Evidence [url] CI run: https://ci.example.com/run/42\nEvidence [text]: Checked by hand";
+    let params = &harness.calls()[0].1;
+    assert_eq!(params.get("attachments"), None);
+    assert_eq!(
+        params["checkpoints"]["criteria"]["note"],
+        format!("Reviewed\n{}", lines)
+    );
+    assert_eq!(params["checkpoints"]["tests"]["note"], lines);
+
+    // Read back from the notes the backend keeps
+    let step = json!({
+        "path": "s:0",
+        "criteria_confirmed": true,
+        "criteria_notes": [format!("Reviewed\n{}", lines)],
+    });
+    let read = crate::evidence::checkpoints(&step);
+    assert_eq!(read[0].evidence, evidence);
+}
+
+#[tokio::test]
+async fn test_verification_command_confirms_by_exit_status() {
+    let harness = Harness::new(
+        "verification",
+        json!({ "tasks_verify": ok(json!({ "task": { "id": "TASK-001" } })) }),
+    );
+    let state = harness.state();
+    let run = |command: &str, use_shell: bool| {
+        verification_command_response(
+            None,
+            &state,
+            "TASK-001".into(),
+            "s:0".into(),
+            "tests".into(),
+            command.into(),
+            None,
+            use_shell,
+        )
+    };
+
+    let refused = run("echo all passed", false).await;
+    assert!(!refused.success);
+    assert_eq!(refused.error.code, Some(ErrorCode::CommandNotAllowed));
+    assert!(refused.outcome.is_none());
+    assert!(harness.calls().is_empty());
+
+    let project = state.user_cwd().to_string_lossy().into_owned();
+    state.settings.write().await.verification_commands.insert(
+        project,
+        vec!["echo *".to_string(), "echo 2 failed; exit 1".to_string()],
+    );
+    let passed = run("echo all passed", false).await;
+    assert!(passed.success, "{:?}", passed.error);
+    assert!(passed.confirmed);
+    let outcome = passed.outcome.unwrap();
+    assert_eq!(outcome.exit_code, Some(0));
+    assert_eq!(outcome.output, "all passed\n");
+    let (tool, args) = harness.calls()[0].clone();
+    assert_eq!(tool, "tasks_verify");
+    assert_eq!(args["path"], "s:0");
+    assert_eq!(args["checkpoints"]["tests"]["confirmed"], true);
+    let note = args["checkpoints"]["tests"]["note"].as_str().unwrap();
+    assert!(
+        note.starts_with("`echo all passed` exited with 0"),
+        "{}",
+        note
+    );
+    assert!(note.ends_with("\n\nall passed"), "{}", note);
+
+    // Only exact entries run through the shell
+    let chained = run("echo ok; rm -rf x", true).await;
+    assert_eq!(chained.error.code, Some(ErrorCode::CommandNotAllowed));
+    if cfg!(unix) {
+        let failed = run("echo 2 failed; exit 1", true).await;
+        assert!(failed.success, "{:?}", failed.error);
+        assert!(!failed.confirmed);
+        assert_eq!(failed.outcome.unwrap().exit_code, Some(1));
+        assert_eq!(
+            harness.calls()[1].1["checkpoints"]["tests"]["confirmed"],
+            false
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_create_preflight_finds_similar_titles() {
+    let harness = Harness::new(
+        "similar",
+        json!({ "tasks_context": ok(json!({ "tasks": [
+            { "id": "TASK-001", "title": "Fix flaky login tests", "status": "ACTIVE", "kind": "task" },
+            { "id": "TASK-002", "title": "Fix flaky signup test", "status": "TODO", "kind": "task" },
+        ] })) }),
+    );
+    let state = harness.state();
+
+    let found = find_similar_response(&state, "fix the flaky login test", None, None).await;
+    assert!(found.success, "{:?}", found.error);
+    assert_eq!(found.threshold, crate::duplicates::DEFAULT_THRESHOLD);
+    let ids: Vec<_> = found.matches.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, ["TASK-001"]);
+    assert_eq!(found.matches[0].status, "ACTIVE");
+    let loose = find_similar_response(&state, "fix the flaky login test", None, Some(0.5)).await;
+    assert_eq!(loose.matches.len(), 2);
+
+    let mut params = json!({ "title": "Fix flaky login test", "kind": "plan" });
+    let refused = check_create(&state, &mut params).await.unwrap();
+    assert_eq!(refused[0].id, "TASK-001");
+    let response = AIResponse::possible_duplicates("create", &refused);
+    assert!(!response.success);
+    assert_eq!(response.rest["possible_duplicates"][0]["id"], "TASK-001");
+    assert_eq!(response.error.unwrap()["code"], "POSSIBLE_DUPLICATE");
+
+    // `allow_duplicate` goes through, and doesn't reach the backend
+    let mut params = json!({ "title": "Fix flaky login test", "allow_duplicate": true });
+    assert_eq!(check_create(&state, &mut params).await, None);
+    assert_eq!(params, json!({ "title": "Fix flaky login test" }));
+    let mut params = json!({ "title": "Write release notes" });
+    assert_eq!(check_create(&state, &mut params).await, None);
+
+    state.settings.write().await.skip_duplicate_check = true;
+    let mut params = json!({ "title": "Fix flaky login test" });
+    assert_eq!(check_create(&state, &mut params).await, None);
+    // One list call: the rest came from the cache or were skipped
+    assert_eq!(harness.calls().len(), 1);
+}
+
+#[tokio::test]
+async fn test_related_commits_since_creation() {
+    let harness = Harness::new(
+        "commits",
+        json!({ "tasks_resume": ok(json!({ "task": {
+            "id": "TASK-007",
+            "title": "Parser",
+            "created_at": "2026-03-01T00:00:00Z",
+        } })) }),
+    );
+    let state = harness.state();
+
+    let none = related_commits_response(&state, "TASK-007".into(), None, false).await;
+    assert!(none.success, "{:?}", none.error);
+    assert!(!none.git_available);
+    assert!(none.unavailable.is_some());
+    assert_eq!(
+        none.since.map(|s| s.to_rfc3339()).as_deref(),
+        Some("2026-03-01T00:00:00+00:00")
+    );
+
+    let dir = &harness._server.dir;
+    let commit = |date: &str, message: &str| {
+        std::process::Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(["commit", "--quiet", "--allow-empty", "-m", message])
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date)
+            .current_dir(dir)
+            .status()
+            .is_ok_and(|s| s.success())
+    };
+    let init = std::process::Command::new("git")
+        .args(["init", "--quiet"])
+        .current_dir(dir)
+        .status();
+    if !init.is_ok_and(|s| s.success()) {
+        // No git here
+        return;
+    }
+    assert!(commit("2026-02-01T10:00:00Z", "Before the task"));
+    assert!(commit("2026-03-02T10:00:00Z", "Refactor lexer"));
+    assert!(commit("2026-03-03T10:00:00Z", "Parse blocks (TASK-007)"));
+
+    let subjects = |response: &RelatedCommitsResponse| -> Vec<String> {
+        response.commits.iter().map(|c| c.subject.clone()).collect()
+    };
+    let since = related_commits_response(&state, "TASK-007".into(), None, false).await;
+    assert!(since.git_available, "{:?}", since.unavailable);
+    assert_eq!(
+        subjects(&since),
+        ["Parse blocks (TASK-007)", "Refactor lexer"]
+    );
+    let mentioning = related_commits_response(&state, "TASK-007".into(), Some(5), true).await;
+    assert_eq!(subjects(&mentioning), ["Parse blocks (TASK-007)"]);
+}
+
+#[tokio::test]
+async fn test_search_falls_back_until_indexed() {
+    use crate::search_index::{SearchIndex, SearchSource};
+
+    let harness = Harness::new(
+        "search",
+        json!({ "tasks_context": answer_fixture(CONTEXT) }),
+    );
+    let state = harness.state();
+    let (cold, rebuild) = search_response(&state, "redirect", None).await;
+    assert!(cold.success, "{:?}", cold.error);
+    assert_eq!(cold.source, SearchSource::Fallback);
+    assert_eq!(cold.hits[0].task_id, "TASK-001");
+    let (generation, tasks) = rebuild.expect("first cold search claims the build");
+    // A second cold search doesn't start another build
+    assert!(search_response(&state, "docs", None).await.1.is_none());
+
+    state
+        .search_index
+        .lock()
+        .unwrap()
+        .finish(generation, Some(SearchIndex::build(&tasks, generation)));
+    let (warm, rebuild) = search_response(&state, "writing docs", Some(5)).await;
+    assert_eq!(warm.source, SearchSource::Index);
+    assert_eq!(warm.hits[0].task_id, "TASK-002");
+    assert!(rebuild.is_none());
+
+    // Any invalidation makes the index stale again
+    state.read_cache.lock().await.invalidate(&[]);
+    let (stale, rebuild) = search_response(&state, "redirect", None).await;
+    assert_eq!(stale.source, SearchSource::Fallback);
+    assert_eq!(rebuild.unwrap().0, generation + 1);
+}
+
+#[tokio::test]
+async fn test_fuzzy_switcher_and_recents() {
+    let harness = Harness::new(
+        "fuzzy",
+        json!({
+            "tasks_context": answer_fixture(CONTEXT),
+            "tasks_resume": answer_fixture(RESUME_TASK),
+        }),
+    );
+    let blank = tasks_fuzzy(harness.state(), " ".into(), None)
+        .await
+        .unwrap();
+    assert!(blank.success, "{:?}", blank.error);
+    assert!(blank.matches.is_empty());
+
+    let shown = tasks_show(harness.state(), "TASK-001".into(), None, None, None, None)
+        .await
+        .unwrap();
+    assert!(shown.success, "{:?}", shown.error);
+    let blank = tasks_fuzzy(harness.state(), String::new(), None)
+        .await
+        .unwrap();
+    let recent: Vec<&str> = blank.matches.iter().map(|m| m.task_id.as_str()).collect();
+    assert_eq!(recent, ["TASK-001"]);
+    assert_eq!(blank.matches[0].title, "Fix login redirect");
+
+    let found = tasks_fuzzy(harness.state(), "flr".into(), Some(5))
+        .await
+        .unwrap();
+    assert_eq!(found.matches[0].task_id, "TASK-001");
+    assert_eq!(found.matches[0].indices, [0, 4, 10]);
+    assert!(tasks_fuzzy(harness.state(), "zzz".into(), None)
+        .await
+        .unwrap()
+        .matches
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_undo_preview_uses_the_journal() {
+    let operations = json!([
+        { "id": "op-1", "intent": "create", "task_id": "TASK-001", "undone": false },
+        { "id": "op-2", "intent": "complete", "task_id": "TASK-001", "undone": false },
+        { "id": "op-3", "intent": "edit", "task_id": "TASK-001", "undone": true },
+    ]);
+    let harness = Harness::new(
+        "undo",
+        json!({
+            "tasks_history": ok(json!({ "operations": operations, "can_undo": true })),
+            "tasks_undo": ok(json!({ "undone_operation": { "id": "op-2" } })),
+        }),
+    );
+    let state = harness.state();
+
+    let other_client = undo_response(None, &state, true, None).await;
+    assert!(other_client.success, "{:?}", other_client.error);
+    let target = other_client.target.unwrap();
+    assert_eq!(target.operation_id, "op-2");
+    assert_eq!(target.description, "Status change on TASK-001");
+    assert!(!target.from_this_window);
+
+    state.journal.lock().await.record(
+        Mutation {
+            history_id: Some("op-2".into()),
+            ..Mutation::new("tasks_complete", Some("TASK-001".into()))
+        }
+        .change(Some("ACTIVE".into()), Some("DONE".into())),
+    );
+    let preview = undo_response(None, &state, true, None).await;
+    let target = preview.target.unwrap();
+    assert_eq!(
+        target.description,
+        "Status change on TASK-001 (ACTIVE → DONE)"
+    );
+    assert!(target.from_this_window);
+    assert!(!preview.undone);
+    assert!(harness
+        .calls()
+        .iter()
+        .all(|(tool, _)| tool == "tasks_history"));
+
+    let stale = undo_response(None, &state, false, Some("op-1".into())).await;
+    assert!(!stale.success);
+    assert_eq!(stale.error.code, Some(ErrorCode::UndoTargetChanged));
+    assert!(harness
+        .calls()
+        .iter()
+        .all(|(tool, _)| tool == "tasks_history"));
+
+    let undone = undo_response(None, &state, false, Some("op-2".into())).await;
+    assert!(undone.success, "{:?}", undone.error);
+    assert!(undone.undone);
+    assert_eq!(harness.calls().last().unwrap().0, "tasks_undo");
+    let latest = state.journal.lock().await.list(1).remove(0);
+    assert_eq!(latest.tool, "tasks_undo");
+    assert_eq!(latest.task_id.as_deref(), Some("TASK-001"));
+}
+
+#[tokio::test]
+async fn test_expected_revision_is_checked_by_the_backend() {
+    let harness = Harness::new(
+        "revision",
+        json!({
+            "tasks_resume": ok(json!({ "task": { "id": "TASK-001", "title": "Parser", "status": "TODO", "revision": 5 } })),
+            "tasks_complete": answer(json!({
+                "success": false,
+                "error": { "code": "REVISION_MISMATCH", "message": "revision не совпадает: expected=4, current=5" },
+                "result": { "task": "TASK-001", "expected_revision": 4, "current_revision": 5 },
+            })),
+        }),
+    );
+    let state = harness.state();
+    let shown = show_response(&state, "TASK-001", None, None, Some(true), None).await;
+    assert_eq!(shown.revision, Some(5));
+    let update = |expected: Option<u64>| {
+        status_response(
+            None,
+            &state,
+            "TASK-001".into(),
+            "ACTIVE".into(),
+            None,
+            expected,
+        )
+    };
+
+    let stale = update(Some(4)).await;
+    assert!(!stale.success);
+    assert_eq!(stale.error.code, Some(ErrorCode::RevisionConflict));
+    let params = stale.error.params.unwrap();
+    assert_eq!(params["task_id"], "TASK-001");
+    assert_eq!(params["expected"], 4);
+    assert_eq!(params["current"], 5);
+    // Forwarded to the write itself, with no extra load first
+    let calls = harness.calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(
+        calls[1],
+        (
+            "tasks_complete".to_string(),
+            json!({ "task": "TASK-001", "status": "ACTIVE", "expected_revision": 4 })
+        )
+    );
+
+    // Not opted in: nothing is sent to check
+    update(None).await;
+    assert!(harness.calls()[2].1.get("expected_revision").is_none());
+}
+
+#[tokio::test]
+async fn test_debounced_bursts_reach_the_backend_once() {
+    let harness = Harness::new(
+        "debounce",
+        json!({
+            "tasks_resume": ok(json!({ "task": { "id": "TASK-001", "status": "TODO" } })),
+            "tasks_complete": ok(json!({ "task": { "id": "TASK-001" } })),
+            "tasks_edit": ok(json!({ "task": { "id": "TASK-001" } })),
+        }),
+    );
+    let state = harness.state();
+    let pause = |i: u64| tokio::time::sleep(std::time::Duration::from_millis(i * 5));
+    let calls_of = |tool: &str| -> Vec<Value> {
+        harness
+            .calls()
+            .into_iter()
+            .filter(|(name, _)| name == tool)
+            .map(|(_, arguments)| arguments)
+            .collect()
+    };
+
+    let status = |i: u64, status: &str| {
+        let status = status.to_string();
+        let state = &state;
+        async move {
+            pause(i).await;
+            status_response(None, state, "TASK-001".into(), status, Some(50), None).await
+        }
+    };
+    let updates = tokio::join!(
+        status(0, "ACTIVE"),
+        status(1, "TODO"),
+        status(2, "ACTIVE"),
+        status(3, "TODO"),
+        status(4, "DONE"),
+    );
+    let updates = [updates.0, updates.1, updates.2, updates.3, updates.4];
+    assert!(updates.iter().all(|u| u.success));
+    assert_eq!(updates.iter().filter(|u| u.superseded).count(), 4);
+    assert!(!updates[4].superseded);
+    assert_eq!(
+        calls_of("tasks_complete"),
+        [json!({ "task": "TASK-001", "status": "DONE" })]
+    );
+
+    let edit = |i: u64, changes: Value| {
+        let state = &state;
+        async move {
+            pause(i).await;
+            let changes = changes.as_object().cloned().unwrap();
+            edit_response(None, state, "TASK-001".into(), changes, Some(50), None).await
+        }
+    };
+    let edits = tokio::join!(
+        edit(0, json!({ "title": "Parser" })),
+        edit(1, json!({ "priority": "LOW" })),
+        edit(2, json!({ "priority": "MEDIUM" })),
+        edit(3, json!({ "description": "Tokens first" })),
+        edit(4, json!({ "priority": "HIGH" })),
+    );
+    let edits = [edits.0, edits.1, edits.2, edits.3, edits.4];
+    assert!(edits.iter().all(|e| e.success));
+    assert_eq!(edits.iter().filter(|e| e.superseded).count(), 4);
+    assert!(!edits[4].superseded);
+    // One call with every field of the burst, the latest value of each
+    assert_eq!(
+        calls_of("tasks_edit"),
+        [json!({
+            "task": "TASK-001",
+            "title": "Parser",
+            "priority": "HIGH",
+            "description": "Tokens first"
+        })]
+    );
+}
+
+#[tokio::test]
+async fn test_read_only_mode_refuses_mutations_before_the_bridge() {
+    let harness = Harness::new(
+        "read-only",
+        json!({
+            "tasks_resume": ok(json!({ "task": { "id": "TASK-001", "title": "Parser" } })),
+            "tasks_complete": ok(json!({ "task": { "id": "TASK-001" } })),
+        }),
+    );
+    let state = harness.state();
+    state.settings.write().await.read_only = true;
+
+    let status = status_response(None, &state, "TASK-001".into(), "DONE".into(), None, None).await;
+    assert_eq!(status.error.code, Some(ErrorCode::ReadOnlyMode));
+    let deleted = delete_response(&state, "TASK-001".into(), None, Some(true), None, None).await;
+    assert_eq!(deleted.error.code, Some(ErrorCode::ReadOnlyMode));
+    let verified = verify_response(
+        None,
+        &state,
+        "TASK-001".into(),
+        Default::default(),
+        false,
+        &[],
+        false,
+    )
+    .await;
+    assert_eq!(verified.error.code, Some(ErrorCode::ReadOnlyMode));
+    let created = quick_create_response(None, &state, "Write docs").await;
+    assert_eq!(created.error.code, Some(ErrorCode::ReadOnlyMode));
+    assert!(harness.calls().is_empty());
+
+    // Reads still work
+    let shown = show_response(&state, "TASK-001", None, None, None, None).await;
+    assert!(shown.success, "{:?}", shown.error);
+
+    state.settings.write().await.read_only = false;
+    let status = status_response(None, &state, "TASK-001".into(), "DONE".into(), None, None).await;
+    assert!(status.success, "{:?}", status.error);
+}
+
+#[tokio::test]
+async fn test_delete_token_only_confirms_what_was_summarized() {
+    let harness = Harness::new(
+        "delete-token",
+        json!({
+            "tasks_resume": ok(json!({ "task": {
+                "id": "TASK-001",
+                "title": "Parser",
+                "steps": [{ "path": "s:0", "title": "Lexer", "steps": [{ "path": "s:0.s:0", "title": "Tokens" }] }],
+            } })),
+            "tasks_context": ok(json!({ "tasks": [{ "id": "TASK-001", "title": "Parser" }] })),
+            "tasks_delete": ok(json!({ "deleted": true })),
+        }),
+    );
+    let state = harness.state();
+    let asked = delete_response(&state, "TASK-001".into(), None, None, None, None).await;
+    assert!(asked.needs_confirmation);
+    let token = asked.confirm_token.unwrap();
+
+    // Issued without cascade: replaying it with cascade is refused
+    let replayed = delete_response(
+        &state,
+        "TASK-001".into(),
+        Some(token),
+        Some(true),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(replayed.error.code, Some(ErrorCode::ConfirmTokenInvalid));
+    let deletes = |calls: Vec<(String, Value)>| {
+        calls
+            .iter()
+            .filter(|(tool, _)| tool == "tasks_delete")
+            .count()
+    };
+    assert_eq!(deletes(harness.calls()), 0);
+
+    let asked = delete_response(&state, "TASK-001".into(), None, None, None, None).await;
+    let confirmed = delete_response(
+        &state,
+        "TASK-001".into(),
+        asked.confirm_token,
+        None,
+        None,
+        None,
+    )
+    .await;
+    assert!(confirmed.deleted, "{:?}", confirmed.error);
+    assert_eq!(deletes(harness.calls()), 1);
+
+    // A step of it: confirmed the same way, then sent with its path
+    let step = || {
+        Some(DeleteNode {
+            path: "s:0".into(),
+            kind: NodeKind::Step,
+        })
+    };
+    let asked = delete_response(&state, "TASK-001".into(), None, None, None, step()).await;
+    let summary = asked.summary.unwrap();
+    assert_eq!(
+        (summary.title.as_str(), summary.subtask_count),
+        ("Lexer", 1)
+    );
+    let whole_task = delete_response(
+        &state,
+        "TASK-001".into(),
+        asked.confirm_token,
+        None,
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(whole_task.error.code, Some(ErrorCode::ConfirmTokenInvalid));
+    let asked = delete_response(&state, "TASK-001".into(), None, None, None, step()).await;
+    let confirmed = delete_response(
+        &state,
+        "TASK-001".into(),
+        asked.confirm_token,
+        None,
+        None,
+        step(),
+    )
+    .await;
+    assert!(confirmed.deleted, "{:?}", confirmed.error);
+    assert_eq!(
+        harness.calls().last().unwrap(),
+        &(
+            "tasks_delete".to_string(),
+            json!({ "task": "TASK-001", "path": "s:0" })
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_ai_intent_leaves_deletes_to_tasks_delete() {
+    let harness = Harness::new(
+        "intent-delete",
+        json!({ "tasks_delete": ok(json!({ "task_id": "TASK-001", "deleted": true })) }),
+    );
+    let state = harness.state();
+    let delete = || {
+        intent_response(
+            harness.app.handle(),
+            &state,
+            "delete".into(),
+            Some(json!({ "task": "TASK-001" })),
+            None,
+            None,
+        )
+    };
+
+    let refused = delete().await;
+    assert!(!refused.success);
+    let error = refused.error.unwrap();
+    assert_eq!(error["code"], "CONFIRMATION_REQUIRED");
+    assert!(error["message"].as_str().unwrap().contains("tasks_delete"));
+    assert!(harness.calls().is_empty(), "refused before the backend");
+
+    // Opted out of confirmation
+    state.settings.write().await.skip_confirmation = true;
+    let deleted = delete().await;
+    assert!(deleted.success, "{:?}", deleted.error);
+    assert_eq!(harness.calls().len(), 1);
+}
+
+#[tokio::test]
+async fn test_rate_limit_refuses_verify_with_a_retry_hint() {
+    let harness = Harness::new(
+        "rate-limit",
+        json!({ "tasks_verify": ok(json!({ "task": { "id": "TASK-001" } })) }),
+    );
+    let state = harness.state();
+    state
+        .settings
+        .write()
+        .await
+        .rate_limits_per_min
+        .insert("tasks_verify".into(), 1);
+
+    let verified = verify_response(
+        None,
+        &state,
+        "TASK-001".into(),
+        Default::default(),
+        false,
+        &[],
+        false,
+    )
+    .await;
+    assert!(verified.success, "{:?}", verified.error);
+    let limited = verify_response(
+        None,
+        &state,
+        "TASK-001".into(),
+        Default::default(),
+        false,
+        &[],
+        false,
+    )
+    .await;
+    assert_eq!(limited.error.code, Some(ErrorCode::RateLimited));
+    let params = limited.error.params.unwrap();
+    assert_eq!(params["tool"], "tasks_verify");
+    assert!(params["retry_after_ms"].as_u64().unwrap() > 1000);
+    assert_eq!(harness.calls().len(), 1);
+
+    // A project switch starts the buckets full again
+    state.rate_limiter.lock().await.reset();
+    let verified = verify_response(
+        None,
+        &state,
+        "TASK-001".into(),
+        Default::default(),
+        false,
+        &[],
+        false,
+    )
+    .await;
+    assert!(verified.success, "{:?}", verified.error);
+}
+
+#[tokio::test]
+async fn test_mutations_are_audited_with_redacted_params() {
+    let harness = Harness::new(
+        "audit",
+        json!({
+            "tasks_resume": ok(json!({ "task": { "id": "TASK-087", "status": "ACTIVE" } })),
+            "tasks_complete": ok(json!({ "task": { "id": "TASK-087" } })),
+            "tasks_edit": ok(json!({ "task": { "id": "TASK-087" } })),
+        }),
+    );
+    let state = harness.state();
+    let status = status_response(None, &state, "TASK-087".into(), "DONE".into(), None, None).await;
+    assert!(status.success, "{:?}", status.error);
+    let args = [("path", json!("0")), ("api_key", json!("hunter2"))]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect();
+    verify_response(None, &state, "TASK-087".into(), args, false, &[], false).await;
+
+    let dir = crate::audit::dir(&state);
+    let mut entries = Vec::new();
+    for _ in 0..100 {
+        entries = crate::audit::query(&dir, None, None, Some("TASK-087"), 10).unwrap();
+        if entries.len() == 2 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(entries.len(), 2, "{:?}", entries);
+    // Newest first; the verify had no scripted answer
+    assert_eq!(entries[0].command, "tasks_verify");
+    assert_eq!(entries[0].outcome, crate::audit::Outcome::Failed);
+    assert!(entries[0].error.is_some());
+    assert_eq!(entries[0].params["api_key"], crate::env_info::REDACTED);
+    assert_eq!(entries[0].params["path"], "0");
+    assert_eq!(entries[1].command, "tasks_complete");
+    assert_eq!(entries[1].outcome, crate::audit::Outcome::Applied);
+    assert_eq!(entries[1].params["status"], "DONE");
+    assert!(!entries[1].user.is_empty());
+    assert!(crate::audit::query(&dir, None, None, Some("TASK-001"), 10)
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_session_restore_drops_what_is_gone() {
+    let harness = Harness::new(
+        "session",
+        json!({
+            "tasks_resume": reported("NOT_FOUND", "Task TASK-404 not found"),
+            "tasks_storage": ok(json!({
+                "current_storage": "/p/.tasks",
+                "current_namespace": "app",
+                "namespaces": [{ "namespace": "app", "path": "/p/.tasks", "task_count": 3 }],
+            })),
+        }),
+    );
+    let state = harness.state();
+    let project = state.user_cwd();
+    std::fs::create_dir_all(project.join(".tasks")).unwrap();
+    let saved = Session {
+        project: Some(project.to_string_lossy().into_owned()),
+        namespace: Some("app".into()),
+        task_id: Some("TASK-404".into()),
+        view: Some("board".into()),
+    };
+    state.session.set(saved.clone());
+
+    let restored = restore_response(None, &state).await;
+    assert!(restored.success);
+    assert!(restored.restored_partial);
+    assert!(!restored.project_opened);
+    assert_eq!(
+        restored.session,
+        Session {
+            task_id: None,
+            ..saved.clone()
+        }
+    );
+    // Pruned for good
+    assert_eq!(state.session.get(), restored.session);
+    assert!(!restore_response(None, &state).await.restored_partial);
+
+    state.session.set(Session {
+        project: Some(project.join("gone").to_string_lossy().into_owned()),
+        ..saved
+    });
+    let restored = restore_response(None, &state).await;
+    assert!(restored.restored_partial);
+    assert_eq!(
+        restored.session,
+        Session {
+            view: Some("board".into()),
+            ..Session::default()
+        }
+    );
+}
@@ -0,0 +1,112 @@
+//! Timeline commands
+
+use chrono::{DateTime, Local, Utc};
+use serde_json::{json, Value};
+use tauri::State;
+
+use crate::backend::{self, ListFilters};
+use crate::deep_link;
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::timeline::{self, Timeline, HISTORY_LIMIT};
+use crate::timer;
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TimelineResponse {
+    pub success: bool,
+    pub range_start: Option<DateTime<Utc>>,
+    pub range_end: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub timeline: Timeline,
+    /// Whether milestones came from `tasks_history`
+    pub from_history: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Operations of `tasks_history`, or `None` when the backend has none
+pub(crate) async fn history_operations(state: &AppState) -> Option<Vec<Value>> {
+    let tools = state.bridge.list_tools().await.ok()?;
+    if !backend::has_tool(&tools, "tasks_history") {
+        return None;
+    }
+    let response = state
+        .bridge
+        .call_tool("tasks_history", json!({ "limit": HISTORY_LIMIT }))
+        .await;
+    match response.and_then(backend::into_result) {
+        Ok(mut result) => match result.get_mut("operations").map(Value::take) {
+            Some(Value::Array(operations)) => Some(operations),
+            _ => None,
+        },
+        Err(e) => {
+            log::warn!("tasks_history failed, using task timestamps: {}", e);
+            None
+        }
+    }
+}
+
+/// Bars from start to completion of every task, clipped to the range.
+///
+/// `range_start`/`range_end` are RFC3339 or `YYYY-MM-DD` (local midnight);
+/// either may be left out. Tasks without any timestamp are `unscheduled`.
+#[tauri::command]
+pub async fn tasks_timeline(
+    state: State<'_, AppState>,
+    range_start: Option<String>,
+    range_end: Option<String>,
+    namespace: Option<String>,
+) -> Result<TimelineResponse, String> {
+    let fail = |range: [Option<DateTime<Utc>>; 2], error: ResponseError| TimelineResponse {
+        success: false,
+        range_start: range[0],
+        range_end: range[1],
+        timeline: Timeline::default(),
+        from_history: false,
+        error,
+    };
+
+    let mut range = [None, None];
+    for (slot, raw) in range.iter_mut().zip([&range_start, &range_end]) {
+        if let Some(raw) = raw.as_deref().filter(|s| !s.trim().is_empty()) {
+            match timer::parse_bound(raw, &Local) {
+                Some(bound) => *slot = Some(bound),
+                None => {
+                    let error =
+                        CatalogError::new(ErrorCode::ValidationInvalidDate).with("value", raw);
+                    return Ok(fail([None, None], error.into()));
+                }
+            }
+        }
+    }
+
+    if let Some(namespace) = &namespace {
+        if let Some(error) = deep_link::namespace_error(&state, namespace).await {
+            return Ok(fail(range, error.into()));
+        }
+    }
+
+    // Full payloads: compact ones carry no timestamps
+    let tasks =
+        match super::task::load_task_list(&state, &ListFilters::default(), false, None, None, None)
+            .await
+        {
+            Ok((tasks, _)) => tasks,
+            Err(e) => return Ok(fail(range, ResponseError::from(&e))),
+        };
+    let operations = history_operations(&state).await;
+    let milestones = operations
+        .as_deref()
+        .map(|ops| timeline::from_history(ops, &Local))
+        .unwrap_or_default();
+
+    let [from, to] = range;
+    Ok(TimelineResponse {
+        success: true,
+        range_start: from,
+        range_end: to,
+        timeline: timeline::build(&tasks, &milestones, from, to, Utc::now(), &Local),
+        from_history: operations.is_some(),
+        error: ResponseError::none(),
+    })
+}
@@ -0,0 +1,216 @@
+//! Time tracking commands
+//!
+//! Timer state lives in `AppState`; completed intervals are persisted to the
+//! project sidecar so reports survive restarts.
+
+use chrono::{DateTime, Local, Utc};
+use tauri::State;
+
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::sidecar;
+use crate::timer::{self, RunningTimer, TimeEntry, TimeReport, TIME_ENTRIES_FILE};
+use crate::AppState;
+
+/// Time report range (`YYYY-MM-DD` or RFC3339; `to` is exclusive)
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct TimeRange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TimerStartResponse {
+    pub success: bool,
+    pub timer: Option<RunningTimer>,
+    /// Timer that was running for another task and got stopped by this call
+    pub auto_stopped: Option<TimeEntry>,
+    pub message: Option<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TimerStopResponse {
+    pub success: bool,
+    pub entry: Option<TimeEntry>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TimeReportResponse {
+    pub success: bool,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub report: TimeReport,
+    /// Running timer (not included in the totals)
+    pub running: Option<RunningTimer>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+fn entries_path(state: &AppState) -> std::path::PathBuf {
+    state.project_dir().join(TIME_ENTRIES_FILE)
+}
+
+fn format_duration(seconds: i64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Stop the running timer into the entries of `project_dir`, the project
+/// being left, so the interval isn't reported in the next one
+pub(crate) async fn stop_for_project_switch(
+    state: &AppState,
+    project_dir: &std::path::Path,
+) -> Option<TimeEntry> {
+    let entry = state
+        .timer
+        .lock()
+        .await
+        .stop_running(Utc::now(), "project switched")?;
+    if let Err(e) = sidecar::append_jsonl(&project_dir.join(TIME_ENTRIES_FILE), &entry) {
+        log::error!("Failed to persist time entry: {}", e);
+    }
+    Some(entry)
+}
+
+#[tauri::command]
+pub async fn tasks_timer_start(
+    state: State<'_, AppState>,
+    task_id: String,
+) -> Result<TimerStartResponse, String> {
+    let task_id = task_id.trim().to_string();
+    if task_id.is_empty() {
+        return Ok(TimerStartResponse {
+            success: false,
+            timer: None,
+            auto_stopped: None,
+            message: None,
+            error: CatalogError::new(ErrorCode::ValidationTaskIdRequired).into(),
+        });
+    }
+
+    let mut timers = state.timer.lock().await;
+
+    if let Some(running) = timers.running.as_ref().filter(|t| t.task_id == task_id) {
+        return Ok(TimerStartResponse {
+            success: true,
+            timer: Some(running.clone()),
+            auto_stopped: None,
+            message: Some(format!("Timer for {} is already running", task_id)),
+            error: ResponseError::none(),
+        });
+    }
+
+    let auto_stopped = timers.start(&task_id, Utc::now());
+    let message = match &auto_stopped {
+        Some(entry) => {
+            if let Err(e) = sidecar::append_jsonl(&entries_path(&state), entry) {
+                log::error!("Failed to persist time entry: {}", e);
+            }
+            Some(format!(
+                "Stopped running timer for {} ({})",
+                entry.task_id,
+                format_duration(entry.seconds)
+            ))
+        }
+        None => None,
+    };
+
+    Ok(TimerStartResponse {
+        success: true,
+        timer: timers.running.clone(),
+        auto_stopped,
+        message,
+        error: ResponseError::none(),
+    })
+}
+
+#[tauri::command]
+pub async fn tasks_timer_stop(
+    state: State<'_, AppState>,
+    task_id: String,
+    note: Option<String>,
+) -> Result<TimerStopResponse, String> {
+    let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    let mut timers = state.timer.lock().await;
+
+    let Some(entry) = timers.stop(task_id.trim(), Utc::now(), note) else {
+        return Ok(TimerStopResponse {
+            success: false,
+            entry: None,
+            error: CatalogError::new(ErrorCode::TimerNotRunning)
+                .with("task_id", task_id.as_str())
+                .into(),
+        });
+    };
+
+    match sidecar::append_jsonl(&entries_path(&state), &entry) {
+        Ok(()) => Ok(TimerStopResponse {
+            success: true,
+            entry: Some(entry),
+            error: ResponseError::none(),
+        }),
+        Err(e) => Ok(TimerStopResponse {
+            success: false,
+            entry: Some(entry),
+            error: ResponseError::from(&e),
+        }),
+    }
+}
+
+#[tauri::command]
+pub async fn tasks_time_report(
+    state: State<'_, AppState>,
+    range: Option<TimeRange>,
+) -> Result<TimeReportResponse, String> {
+    let range = range.unwrap_or_default();
+    let running = state.timer.lock().await.running.clone();
+
+    let mut bounds = [None, None];
+    for (slot, raw) in bounds.iter_mut().zip([&range.from, &range.to]) {
+        if let Some(raw) = raw.as_deref().filter(|s| !s.trim().is_empty()) {
+            match timer::parse_bound(raw, &Local) {
+                Some(bound) => *slot = Some(bound),
+                None => {
+                    return Ok(TimeReportResponse {
+                        success: false,
+                        from: None,
+                        to: None,
+                        report: TimeReport::default(),
+                        running,
+                        error: CatalogError::new(ErrorCode::ValidationInvalidDate)
+                            .with("value", raw)
+                            .into(),
+                    })
+                }
+            }
+        }
+    }
+    let [from, to] = bounds;
+
+    match sidecar::read_jsonl::<TimeEntry>(&entries_path(&state)) {
+        Ok(entries) => Ok(TimeReportResponse {
+            success: true,
+            from,
+            to,
+            report: timer::aggregate(&entries, from, to, &Local),
+            running,
+            error: ResponseError::none(),
+        }),
+        Err(e) => Ok(TimeReportResponse {
+            success: false,
+            from,
+            to,
+            report: TimeReport::default(),
+            running,
+            error: ResponseError::from(&e),
+        }),
+    }
+}
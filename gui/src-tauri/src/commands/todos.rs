@@ -0,0 +1,194 @@
+//! TODO comment commands
+//!
+//! Scan the project for TODO/FIXME/HACK comments, then turn the selected
+//! ones into tasks tagged `from-todo`. Imported comments are remembered in
+//! the project sidecar and left out of later scans.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use chrono::Utc;
+use serde_json::json;
+use tauri::{AppHandle, State};
+
+use crate::error_catalog::ResponseError;
+use crate::quick_add;
+use crate::sidecar;
+use crate::todos::{self, ImportedTodo, ImportedTodos, TodoRef, TodoScan, IMPORTED_TODOS_FILE};
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TodoScanResponse {
+    pub success: bool,
+    /// The directory scanned
+    pub root: String,
+    #[serde(flatten)]
+    pub scan: TodoScan,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// What became of one selected comment
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TodoCreated {
+    pub file: String,
+    pub line: usize,
+    pub task_id: Option<String>,
+    /// Imported before (by `task_id`); nothing was created
+    pub duplicate: bool,
+    /// Created, but the tag could not be set
+    pub warning: Option<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TodoCreateResponse {
+    pub success: bool,
+    /// In selection order
+    pub results: Vec<TodoCreated>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+fn imported_path(state: &AppState) -> PathBuf {
+    state.project_dir().join(IMPORTED_TODOS_FILE)
+}
+
+/// TODO/FIXME/HACK comments under `root` (the project directory when
+/// unset), by file; `globs` narrow the files (`src/**/*.rs`, `!vendor/**`)
+/// and `limit` caps the comments returned. Comments imported before are
+/// left out.
+#[tauri::command]
+pub async fn scan_todos(
+    state: State<'_, AppState>,
+    root: Option<PathBuf>,
+    globs: Option<Vec<String>>,
+    limit: Option<usize>,
+) -> Result<TodoScanResponse, String> {
+    let response = scan_response(&state, root, globs.unwrap_or_default(), limit).await;
+    state
+        .usage_metrics
+        .record_outcome("scan_todos", response.success);
+    Ok(response)
+}
+
+pub(crate) async fn scan_response(
+    state: &AppState,
+    root: Option<PathBuf>,
+    globs: Vec<String>,
+    limit: Option<usize>,
+) -> TodoScanResponse {
+    let cwd = state.user_cwd();
+    let root = root.map_or_else(|| cwd.clone(), |root| cwd.join(root));
+    let imported_file = imported_path(state);
+    let scan_root = root.clone();
+    let scanned = tauri::async_runtime::spawn_blocking(move || {
+        let imported: ImportedTodos = sidecar::read_json(&imported_file)?;
+        let hashes: HashSet<String> = imported.into_keys().collect();
+        todos::scan(
+            &scan_root,
+            &globs,
+            limit.unwrap_or(todos::DEFAULT_LIMIT),
+            &hashes,
+        )
+    })
+    .await
+    .map_err(anyhow::Error::from)
+    .and_then(|scanned| scanned);
+    let root = root.to_string_lossy().into_owned();
+    match scanned {
+        Ok(scan) => TodoScanResponse {
+            success: true,
+            root,
+            scan,
+            error: ResponseError::none(),
+        },
+        Err(e) => TodoScanResponse {
+            success: false,
+            root,
+            scan: TodoScan::default(),
+            error: ResponseError::from(&e),
+        },
+    }
+}
+
+/// One task per selected comment: its text as the title, its place and
+/// code in the description, tagged `from-todo`
+#[tauri::command]
+pub async fn tasks_create_from_todos(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    selected: Vec<TodoRef>,
+) -> Result<TodoCreateResponse, String> {
+    let response = create_from_todos_response(Some(&app), &state, selected).await;
+    state
+        .usage_metrics
+        .record_outcome("tasks_create_from_todos", response.success);
+    Ok(response)
+}
+
+pub(crate) async fn create_from_todos_response(
+    app: Option<&AppHandle>,
+    state: &AppState,
+    selected: Vec<TodoRef>,
+) -> TodoCreateResponse {
+    let path = imported_path(state);
+    let mut imported: ImportedTodos = match sidecar::read_json(&path) {
+        Ok(imported) => imported,
+        Err(e) => {
+            return TodoCreateResponse {
+                success: false,
+                results: Vec::new(),
+                error: ResponseError::from(&e),
+            }
+        }
+    };
+    let tags = [todos::TODO_TAG.to_string()];
+    let mut results = Vec::with_capacity(selected.len());
+    for todo in selected {
+        let hash = todo.hash();
+        let mut result = TodoCreated {
+            file: todo.file.clone(),
+            line: todo.line,
+            task_id: None,
+            duplicate: false,
+            warning: None,
+            error: ResponseError::none(),
+        };
+        if let Some(previous) = imported.get(&hash) {
+            result.task_id = Some(previous.task_id.clone());
+            result.duplicate = true;
+            results.push(result);
+            continue;
+        }
+
+        let params = json!({ "title": todo.title(), "description": todo.description() });
+        match quick_add::create_task(app, state, params, &tags, None).await {
+            Ok((task_id, warning)) => {
+                imported.insert(
+                    hash,
+                    ImportedTodo {
+                        task_id: task_id.clone(),
+                        file: todo.file,
+                        line: todo.line,
+                        imported_at: Utc::now(),
+                    },
+                );
+                // Saved per task, so a failure later doesn't lose earlier ones
+                if let Err(e) = sidecar::write_json(&path, &imported) {
+                    log::warn!("Failed to record imported TODO of {}: {:#}", task_id, e);
+                }
+                result.task_id = Some(task_id);
+                result.warning = warning;
+            }
+            Err(e) => result.error = ResponseError::from(&e),
+        }
+        results.push(result);
+    }
+    TodoCreateResponse {
+        success: true,
+        results,
+        error: ResponseError::none(),
+    }
+}
@@ -0,0 +1,36 @@
+//! Update check command
+
+use tauri::State;
+
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::update_check::{self, UpdateInfo};
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct UpdateCheckResponse {
+    pub success: bool,
+    /// `current`, `latest`, `update_available`, ... (absent on failure)
+    #[serde(flatten)]
+    pub update: Option<UpdateInfo>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Ask GitHub for the latest release now (ignores the startup cache and setting)
+#[tauri::command]
+pub async fn check_for_updates(state: State<'_, AppState>) -> Result<UpdateCheckResponse, String> {
+    Ok(match update_check::check(&state.data_dir).await {
+        Ok(info) => UpdateCheckResponse {
+            success: true,
+            update: Some(info),
+            error: ResponseError::none(),
+        },
+        Err(e) => UpdateCheckResponse {
+            success: false,
+            update: None,
+            error: CatalogError::new(ErrorCode::UpdateCheckFailed)
+                .with("detail", format!("{:#}", e))
+                .into(),
+        },
+    })
+}
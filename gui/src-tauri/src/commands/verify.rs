@@ -0,0 +1,469 @@
+//! Checkpoint confirmation through `tasks_verify`, and the project's git
+//! branch and commit
+//!
+//! The backend confirms checkpoints with `tasks_verify` only (there is no
+//! separate checkpoint tool). With `attach_git_info`, `branch@commit` is
+//! appended to each confirmed checkpoint's note; when git can't tell, the
+//! confirmation goes ahead without it. `evidence` items are attached as
+//! described in [`crate::evidence`], and `tasks_get_checkpoints` reads them
+//! back. `run_verification_command` confirms one checkpoint by the exit
+//! status of an allowlisted command, its output tail as the note.
+
+use std::time::Duration;
+
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::ai_response::AIResponse;
+use crate::audit::{self, Audit};
+use crate::backend;
+use crate::error_catalog::{CatalogError, ResponseError};
+use crate::evidence::{self, Checkpoint, EvidenceItem};
+use crate::git::{self, GitInfo};
+use crate::journal::{self, Mutation};
+use crate::mutation_queue;
+use crate::rate_limit;
+use crate::read_only;
+use crate::revision;
+use crate::verification::{self, OutputLine, RunOutcome, VerificationError, VERIFY_OUTPUT_EVENT};
+use crate::AppState;
+
+const VERIFY_TOOL: &str = "tasks_verify";
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct GitInfoResponse {
+    pub success: bool,
+    pub info: Option<GitInfo>,
+    /// `branch@commit`
+    pub label: Option<String>,
+    /// Why there is no info (no git, not a repository, ...)
+    pub unavailable: Option<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TaskVerifyResponse {
+    pub success: bool,
+    pub task_id: String,
+    pub result: Option<AIResponse>,
+    /// Held in the offline queue; replayed when the backend is back
+    pub queued: bool,
+    /// What was appended to the notes
+    pub git: Option<String>,
+    /// Git info was asked for but could not be read (the checkpoints were
+    /// confirmed without it)
+    pub git_unavailable: Option<String>,
+    /// Evidence as sent (file paths relative to the project root)
+    #[serde(default)]
+    pub evidence: Vec<EvidenceItem>,
+    /// Set when the evidence went into the notes instead of attachments
+    pub evidence_warning: Option<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct CheckpointsResponse {
+    pub success: bool,
+    pub task_id: String,
+    pub path: Option<String>,
+    pub checkpoints: Vec<Checkpoint>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct VerificationCommandResponse {
+    pub success: bool,
+    pub task_id: String,
+    /// Whether the checkpoint was confirmed (exit status 0)
+    pub confirmed: bool,
+    /// `None` when the command didn't run
+    #[serde(flatten)]
+    pub outcome: Option<RunOutcome>,
+    /// The `tasks_verify` call that recorded it
+    pub verify: Option<TaskVerifyResponse>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Branch and commit of the open project, for the header
+#[tauri::command]
+pub async fn git_info(state: State<'_, AppState>) -> Result<GitInfoResponse, String> {
+    Ok(match git::info(&state.user_cwd()).await {
+        Ok(info) => GitInfoResponse {
+            success: true,
+            label: Some(info.label()),
+            info: Some(info),
+            unavailable: None,
+            error: ResponseError::none(),
+        },
+        Err(reason) => GitInfoResponse {
+            success: true,
+            info: None,
+            label: None,
+            unavailable: Some(reason.to_string()),
+            error: ResponseError::none(),
+        },
+    })
+}
+
+/// Every checkpoint note in `tasks_verify` `args` rewritten by `update`
+/// (given the trimmed note, empty when there is none)
+fn rewrite_notes(args: &mut Map<String, Value>, update: impl Fn(&str) -> String) {
+    let Some(Value::Object(checkpoints)) = args.get_mut("checkpoints") else {
+        return;
+    };
+    for item in checkpoints.values_mut() {
+        if !item.is_object() {
+            *item = Value::Object(Map::new());
+        }
+        let Value::Object(item) = item else {
+            continue;
+        };
+        let note = item
+            .get("note")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .unwrap_or_default();
+        item.insert("note".to_string(), Value::String(update(note)));
+    }
+}
+
+/// `label` at the end of every checkpoint note
+fn append_to_notes(args: &mut Map<String, Value>, label: &str) {
+    rewrite_notes(args, |note| match note {
+        "" => label.to_string(),
+        note => format!("{} ({})", note, label),
+    });
+}
+
+/// `lines` below every checkpoint note
+fn append_lines_to_notes(args: &mut Map<String, Value>, lines: &str) {
+    rewrite_notes(args, |note| match note {
+        "" => lines.to_string(),
+        note => format!("{}\n{}", note, lines),
+    });
+}
+
+/// Confirm checkpoints of a task (`args` are the `tasks_verify` arguments
+/// besides `task`: `path`, `checkpoints`, ...), optionally noting the git
+/// branch and commit and attaching `evidence` (files copied into the
+/// attachments store with `copy_evidence`); with `expected_revision` (the
+/// task's `revision`), only if nobody changed the task since
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn tasks_verify(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    task_id: String,
+    mut args: Map<String, Value>,
+    attach_git_info: Option<bool>,
+    expected_revision: Option<u64>,
+    evidence: Option<Vec<EvidenceItem>>,
+    copy_evidence: Option<bool>,
+) -> Result<TaskVerifyResponse, String> {
+    if let Some(expected) = expected_revision {
+        args.insert("expected_revision".to_string(), Value::from(expected));
+    }
+    let response = verify_response(
+        Some(&app),
+        &state,
+        task_id,
+        args,
+        attach_git_info.unwrap_or(false),
+        &evidence.unwrap_or_default(),
+        copy_evidence.unwrap_or(false),
+    )
+    .await;
+    state
+        .usage_metrics
+        .record_outcome("tasks_verify", response.success);
+    Ok(response)
+}
+
+fn failed(task_id: String, error: ResponseError) -> TaskVerifyResponse {
+    TaskVerifyResponse {
+        success: false,
+        task_id,
+        queued: false,
+        result: None,
+        git: None,
+        git_unavailable: None,
+        evidence: Vec::new(),
+        evidence_warning: None,
+        error,
+    }
+}
+
+pub(crate) async fn verify_response(
+    app: Option<&AppHandle>,
+    state: &AppState,
+    task_id: String,
+    mut args: Map<String, Value>,
+    attach_git_info: bool,
+    evidence: &[EvidenceItem],
+    copy_evidence: bool,
+) -> TaskVerifyResponse {
+    if let Some(error) = read_only::refusal(state).await {
+        return failed(task_id, error.into());
+    }
+    let root = state.user_cwd();
+    let mut evidence = match evidence::check(evidence, &root) {
+        Ok(evidence) => evidence,
+        Err(e) => return failed(task_id, CatalogError::from(&e).into()),
+    };
+    if let Err(e) = rate_limit::check(state, VERIFY_TOOL).await {
+        return failed(task_id, CatalogError::from(&e).into());
+    }
+    if copy_evidence {
+        let store = state.project_dir().join(evidence::STORE_DIR);
+        if let Err(e) = evidence::copy_files(&mut evidence, &root, &store) {
+            return failed(task_id, CatalogError::from(&e).into());
+        }
+    }
+    let (mut git_label, mut git_unavailable) = (None, None);
+    if attach_git_info {
+        match git::info(&state.user_cwd()).await {
+            Ok(info) => {
+                let label = info.label();
+                append_to_notes(&mut args, &label);
+                git_label = Some(label);
+            }
+            Err(reason) => {
+                log::info!("Confirming {} without git info: {}", task_id, reason);
+                git_unavailable = Some(reason.to_string());
+            }
+        }
+    }
+    let mut evidence_warning = None;
+    if !evidence.is_empty() {
+        let attachments = state
+            .bridge
+            .list_tools()
+            .await
+            .is_ok_and(|tools| backend::tool_accepts(&tools, VERIFY_TOOL, "attachments"));
+        if attachments {
+            let items = evidence.iter().map(evidence::to_attachment).collect();
+            args.insert("attachments".to_string(), Value::Array(items));
+        } else {
+            append_lines_to_notes(&mut args, &evidence::note(&evidence));
+            evidence_warning = Some(evidence::NOTE_FALLBACK.to_string());
+        }
+    }
+    args.insert("task".to_string(), Value::String(task_id.clone()));
+    let params = Value::Object(args);
+
+    if let Some(app) = app {
+        if state.mutation_queue.lock().await.has_untried() {
+            mutation_queue::replay_queue(app).await;
+        }
+    }
+    state.storage_watch.mark_own_write();
+    let response = match state.bridge.call_tool(VERIFY_TOOL, params.clone()).await {
+        Ok(result) => AIResponse::from_value(result),
+        Err(e) => {
+            match mutation_queue::queue_if_offline(state, &e, "verify", VERIFY_TOOL, &params).await
+            {
+                Some(entry) => mutation_queue::queued_response("verify", &entry),
+                None => AIResponse::bridge_error("verify", e.to_string()),
+            }
+        }
+    };
+    state.storage_watch.mark_own_write();
+
+    if response.success {
+        state
+            .read_cache
+            .lock()
+            .await
+            .invalidate(std::slice::from_ref(&task_id));
+    }
+    let checkpoints = params
+        .get("checkpoints")
+        .and_then(Value::as_object)
+        .map(|checkpoints| checkpoints.keys().cloned().collect::<Vec<_>>().join(", "));
+    let mutation = Mutation::new(VERIFY_TOOL, Some(task_id.clone())).change(None, checkpoints);
+    journal::record(state, &response, mutation).await;
+    let audited = Audit::new(VERIFY_TOOL, [task_id.clone()])
+        .params(&params)
+        .response(&response);
+    audit::record(state, audited).await;
+    let error = (!response.success).then(|| {
+        revision::conflict(response.error.as_ref(), &response.result).unwrap_or_else(|| {
+            CatalogError::from_envelope(response.error.as_ref(), "Failed to confirm checkpoints")
+        })
+    });
+    TaskVerifyResponse {
+        success: response.success,
+        task_id,
+        queued: response.queued,
+        result: Some(response),
+        git: git_label,
+        git_unavailable,
+        evidence,
+        evidence_warning,
+        error: error.into(),
+    }
+}
+
+/// Checkpoints of a task, or of its subtask at `path`, with the evidence
+/// attached or noted on each
+#[tauri::command]
+pub async fn tasks_get_checkpoints(
+    state: State<'_, AppState>,
+    task_id: String,
+    path: Option<String>,
+) -> Result<CheckpointsResponse, String> {
+    let response = checkpoints_response(&state, task_id, path).await;
+    state
+        .usage_metrics
+        .record_outcome("tasks_get_checkpoints", response.success);
+    Ok(response)
+}
+
+pub(crate) async fn checkpoints_response(
+    state: &AppState,
+    task_id: String,
+    path: Option<String>,
+) -> CheckpointsResponse {
+    let mut response = CheckpointsResponse {
+        success: false,
+        task_id,
+        path,
+        checkpoints: Vec::new(),
+        error: ResponseError::none(),
+    };
+    let task = match backend::show_task(&state.bridge, &response.task_id).await {
+        Ok(task) => task,
+        Err(e) => {
+            response.error = ResponseError::from(&e);
+            return response;
+        }
+    };
+    match evidence::node(&task, response.path.as_deref()) {
+        Ok(node) => {
+            response.checkpoints = evidence::checkpoints(node);
+            response.success = true;
+        }
+        Err(e) => response.error = CatalogError::from(&e).into(),
+    }
+    response
+}
+
+/// Run `command` in the project root (split into arguments, or through the
+/// shell with `use_shell`) and confirm `checkpoint` of the subtask at
+/// `path` when it exits with 0; the output tail becomes the note. Output
+/// lines are emitted as `verify-output` events. Only commands on the
+/// project's `verification_commands` allowlist run.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn run_verification_command(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    task_id: String,
+    path: String,
+    checkpoint: String,
+    command: String,
+    timeout_ms: Option<u64>,
+    use_shell: Option<bool>,
+) -> Result<VerificationCommandResponse, String> {
+    let response = verification_command_response(
+        Some(&app),
+        &state,
+        task_id,
+        path,
+        checkpoint,
+        command,
+        timeout_ms,
+        use_shell.unwrap_or(false),
+    )
+    .await;
+    state
+        .usage_metrics
+        .record_outcome("run_verification_command", response.success);
+    Ok(response)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn verification_command_response(
+    app: Option<&AppHandle>,
+    state: &AppState,
+    task_id: String,
+    path: String,
+    checkpoint: String,
+    command: String,
+    timeout_ms: Option<u64>,
+    use_shell: bool,
+) -> VerificationCommandResponse {
+    let mut response = VerificationCommandResponse {
+        success: false,
+        task_id: task_id.clone(),
+        confirmed: false,
+        outcome: None,
+        verify: None,
+        error: ResponseError::none(),
+    };
+    if let Some(error) = read_only::refusal(state).await {
+        response.error = error.into();
+        return response;
+    }
+    let command = command.trim().to_string();
+    let root = state.user_cwd();
+    let allowed = verification::is_allowed(
+        state.settings.read().await.verification_commands(&root),
+        &command,
+        use_shell,
+    );
+    if command.is_empty() || !allowed {
+        let error = if command.is_empty() {
+            VerificationError::Empty
+        } else {
+            VerificationError::NotAllowed(command)
+        };
+        response.error = CatalogError::from(&error).into();
+        return response;
+    }
+
+    let timeout = timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(verification::DEFAULT_TIMEOUT)
+        .min(verification::MAX_TIMEOUT);
+    let on_line = |stream, line: &str| {
+        let Some(app) = app else {
+            return;
+        };
+        let payload = OutputLine {
+            task_id: task_id.clone(),
+            path: path.clone(),
+            checkpoint: checkpoint.clone(),
+            stream,
+            line: line.to_string(),
+        };
+        if let Err(e) = app.emit(VERIFY_OUTPUT_EVENT, &payload) {
+            log::warn!("Failed to emit {}: {}", VERIFY_OUTPUT_EVENT, e);
+        }
+    };
+    let outcome = match verification::run(&root, &command, use_shell, timeout, on_line).await {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            response.error = CatalogError::from(&e).into();
+            return response;
+        }
+    };
+
+    let mut args = Map::new();
+    args.insert("path".to_string(), Value::String(path.clone()));
+    args.insert(
+        "checkpoints".to_string(),
+        json!({ checkpoint.as_str(): { "confirmed": outcome.passed(), "note": outcome.note(&command) } }),
+    );
+    let verify = verify_response(app, state, task_id, args, false, &[], false).await;
+    response.success = verify.success;
+    response.confirmed = verify.success && outcome.passed();
+    response.error = verify.error.clone();
+    response.outcome = Some(outcome);
+    response.verify = Some(verify);
+    response
+}
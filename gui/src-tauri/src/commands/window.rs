@@ -0,0 +1,83 @@
+//! Window commands
+
+use tauri::{AppHandle, State, Window};
+
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::window_state;
+use crate::windows::{self, WindowScope};
+use crate::AppState;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct WindowScopeResponse {
+    pub success: bool,
+    /// Label of the (new or calling) window
+    pub label: String,
+    pub scope: WindowScope,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Forget saved window geometry and put open windows back to the default
+/// size, centered (for a window stuck off-screen or at an odd size)
+#[tauri::command]
+pub fn reset_window_state(app: AppHandle) -> Result<(), String> {
+    window_state::reset(&app).map_err(|e| e.to_string())
+}
+
+/// Open another window on `namespace` and `view` (either may be left out)
+#[tauri::command]
+pub async fn open_window(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    namespace: Option<String>,
+    view: Option<String>,
+) -> Result<WindowScopeResponse, String> {
+    let text = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let scope = WindowScope {
+        namespace: text(namespace),
+        view: text(view),
+    };
+    let fail = |scope: WindowScope, error: ResponseError| WindowScopeResponse {
+        success: false,
+        label: String::new(),
+        scope,
+        error,
+    };
+
+    if let Some(namespace) = scope.namespace.as_deref() {
+        // An unreachable backend proves nothing; the window's calls will say
+        if super::session::namespace_exists(&state, namespace).await == Some(false) {
+            let error =
+                CatalogError::new(ErrorCode::ProjectNamespaceNotFound).with("namespace", namespace);
+            return Ok(fail(scope, error.into()));
+        }
+    }
+    Ok(match windows::open(&app, &state, scope.clone()) {
+        Ok(label) => WindowScopeResponse {
+            success: true,
+            label,
+            scope,
+            error: ResponseError::none(),
+        },
+        Err(e) => fail(scope, ResponseError::from(&e)),
+    })
+}
+
+/// Namespace and view the calling window was opened on (empty for the main
+/// window)
+#[tauri::command]
+pub async fn window_scope(
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<WindowScopeResponse, String> {
+    Ok(WindowScopeResponse {
+        success: true,
+        label: window.label().to_string(),
+        scope: state.windows.get(window.label()),
+        error: ResponseError::none(),
+    })
+}
@@ -0,0 +1,142 @@
+//! Confirmation tokens for destructive commands
+//!
+//! A token is issued for one [`Target`] (the task or a node of it, whether
+//! descendants go too, and which), expires after [`TOKEN_TTL`] and is consumed by the
+//! first attempt to use it (matching or not). Redeeming compares the target
+//! as planned again at that point, so a token can't be replayed with
+//! `cascade` or after the subtree changed.
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, Instant};
+
+/// How long a confirmation token stays valid
+pub const TOKEN_TTL: Duration = Duration::from_secs(60);
+
+/// What a token confirms
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub task_id: String,
+    /// Step or nested task of the task, when only that goes
+    pub path: Option<String>,
+    pub cascade: bool,
+    /// Descendants deleted along with the task (empty without `cascade`)
+    pub descendants: Vec<String>,
+}
+
+impl Target {
+    pub fn new(task_id: &str, cascade: bool, descendants: &[String]) -> Self {
+        Self {
+            task_id: task_id.to_string(),
+            path: None,
+            cascade,
+            descendants: descendants.to_vec(),
+        }
+    }
+
+    /// The node at `path` instead of the whole task
+    pub fn at(mut self, path: &str) -> Self {
+        self.path = Some(path.to_string());
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Pending {
+    target: Target,
+    expires_at: Instant,
+}
+
+/// Outstanding tokens (kept in `AppState`)
+#[derive(Debug, Default)]
+pub struct ConfirmTokens {
+    pending: HashMap<String, Pending>,
+    issued: u64,
+    hasher: RandomState,
+}
+
+impl ConfirmTokens {
+    /// Issue a token for `target`
+    pub fn issue(&mut self, target: Target, now: Instant) -> String {
+        self.pending.retain(|_, p| p.expires_at > now);
+
+        self.issued += 1;
+        let mut hasher = self.hasher.build_hasher();
+        hasher.write(target.task_id.as_bytes());
+        hasher.write_u64(self.issued);
+        hasher.write_u128(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0),
+        );
+        let token = format!("{:016x}", hasher.finish());
+
+        self.pending.insert(
+            token.clone(),
+            Pending {
+                target,
+                expires_at: now + TOKEN_TTL,
+            },
+        );
+        token
+    }
+
+    /// Redeem `token` for `target`; the token is gone afterwards either way
+    pub fn consume(&mut self, token: &str, target: &Target, now: Instant) -> bool {
+        match self.pending.remove(token) {
+            Some(p) => p.target == *target && p.expires_at > now,
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(task_id: &str) -> Target {
+        Target::new(task_id, false, &[])
+    }
+
+    #[test]
+    fn test_tokens_are_single_use_scoped_and_expire() {
+        let mut tokens = ConfirmTokens::default();
+        let now = Instant::now();
+
+        let token = tokens.issue(target("TASK-1"), now);
+        assert!(!tokens.consume(&token, &target("TASK-2"), now));
+        assert!(!tokens.consume(&token, &target("TASK-1"), now));
+
+        let token = tokens.issue(target("TASK-1"), now);
+        assert_ne!(token, tokens.issue(target("TASK-1"), now));
+        assert!(tokens.consume(&token, &target("TASK-1"), now + Duration::from_secs(59)));
+        assert!(!tokens.consume(&token, &target("TASK-1"), now));
+
+        let token = tokens.issue(target("TASK-1"), now);
+        assert!(!tokens.consume(&token, &target("TASK-1"), now + TOKEN_TTL));
+    }
+
+    #[test]
+    fn test_tokens_are_bound_to_path_cascade_and_descendants() {
+        let mut tokens = ConfirmTokens::default();
+        let now = Instant::now();
+        let children = ["TASK-2".to_string()];
+
+        let token = tokens.issue(target("TASK-1"), now);
+        assert!(!tokens.consume(&token, &Target::new("TASK-1", true, &children), now));
+
+        let token = tokens.issue(Target::new("TASK-1", true, &children), now);
+        let grown = ["TASK-2".to_string(), "TASK-3".to_string()];
+        assert!(!tokens.consume(&token, &Target::new("TASK-1", true, &grown), now));
+
+        let token = tokens.issue(Target::new("TASK-1", true, &children), now);
+        assert!(tokens.consume(&token, &Target::new("TASK-1", true, &children), now));
+
+        let token = tokens.issue(target("TASK-1").at("s:0"), now);
+        assert!(!tokens.consume(&token, &target("TASK-1"), now));
+        let token = tokens.issue(target("TASK-1").at("s:0"), now);
+        assert!(tokens.consume(&token, &target("TASK-1").at("s:0"), now));
+    }
+}
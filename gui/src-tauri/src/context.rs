@@ -0,0 +1,271 @@
+//! Typed `tasks_context` response
+//!
+//! Parses the backend's context payload leniently: every field has a default,
+//! and the untouched payload is kept under `raw`.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error_catalog::{CatalogError, ResponseError};
+
+/// Maximum number of history events carried on the response
+const RECENT_HISTORY_LIMIT: usize = 20;
+
+/// Checkpoints tracked per subtask
+const CHECKPOINTS: [&str; 2] = ["criteria", "tests"];
+
+/// Project-wide counts
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectSummary {
+    pub plans: u64,
+    pub tasks: u64,
+    pub by_status: BTreeMap<String, u64>,
+}
+
+/// Unconfirmed checkpoint on an open subtask
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingCheckpoint {
+    pub path: String,
+    pub title: String,
+    pub checkpoint: String,
+}
+
+/// `tasks_context` result
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContextResponse {
+    pub success: bool,
+    pub focus_id: Option<String>,
+    /// Focused task or plan (`current_task` / `current_plan`)
+    pub active_task: Option<Value>,
+    /// Subtree path if requested, else the first open subtask of the active task
+    pub current_subtask_path: Option<String>,
+    pub pending_checkpoints: Vec<PendingCheckpoint>,
+    /// Most recent events of the active task (newest last)
+    pub recent_history: Vec<Value>,
+    pub summary: ProjectSummary,
+    pub tasks: Vec<Value>,
+    pub plans: Vec<Value>,
+    pub warnings: Vec<String>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+    /// Full backend `result` object
+    pub raw: Value,
+}
+
+impl ContextResponse {
+    /// Parse a full `AIResponse` envelope from `tasks_context`
+    pub fn from_response(response: Value) -> Self {
+        let success = response
+            .get("success")
+            .and_then(|s| s.as_bool())
+            .unwrap_or(false);
+        let error = response
+            .get("error")
+            .filter(|e| e.get("message").is_some_and(|m| m.is_string()))
+            .map(|e| CatalogError::from_envelope(Some(e), ""));
+        let focus_id = response
+            .pointer("/context/focus_id")
+            .and_then(|f| f.as_str())
+            .map(String::from);
+        let warnings = response
+            .get("warnings")
+            .and_then(|w| w.as_array())
+            .map(|w| {
+                w.iter()
+                    .filter_map(|s| s.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let raw = response.get("result").cloned().unwrap_or(Value::Null);
+        let active_task = raw
+            .get("current_task")
+            .or_else(|| raw.get("current_plan"))
+            .filter(|t| t.is_object())
+            .cloned();
+
+        let mut pending_checkpoints = Vec::new();
+        let mut first_open = None;
+        if let Some(steps) = active_task.as_ref().and_then(|t| t.get("steps")) {
+            collect_steps(steps, &mut pending_checkpoints, &mut first_open);
+        }
+
+        let current_subtask_path = raw
+            .pointer("/subtree/path")
+            .and_then(|p| p.as_str())
+            .map(String::from)
+            .or(first_open);
+
+        let recent_history = active_task
+            .as_ref()
+            .and_then(|t| t.get("events"))
+            .and_then(|e| e.as_array())
+            .map(|events| {
+                let skip = events.len().saturating_sub(RECENT_HISTORY_LIMIT);
+                events[skip..].to_vec()
+            })
+            .unwrap_or_default();
+
+        let summary = ProjectSummary {
+            plans: raw
+                .pointer("/counts/plans")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0),
+            tasks: raw
+                .pointer("/counts/tasks")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0),
+            by_status: raw
+                .get("by_status")
+                .and_then(|b| serde_json::from_value(b.clone()).ok())
+                .unwrap_or_default(),
+        };
+
+        let list = |key: &str| {
+            raw.get(key)
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default()
+        };
+
+        Self {
+            success,
+            focus_id,
+            active_task,
+            current_subtask_path,
+            pending_checkpoints,
+            recent_history,
+            summary,
+            tasks: list("tasks"),
+            plans: list("plans"),
+            warnings,
+            error: error.into(),
+            raw,
+        }
+    }
+}
+
+fn flag(step: &Value, key: &str) -> bool {
+    step.get(key).and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+/// Walk steps depth-first, recording open checkpoints and the first open step
+fn collect_steps(
+    steps: &Value,
+    pending: &mut Vec<PendingCheckpoint>,
+    first_open: &mut Option<String>,
+) {
+    let Some(steps) = steps.as_array() else {
+        return;
+    };
+
+    for step in steps {
+        let path = step.get("path").and_then(|p| p.as_str()).unwrap_or("");
+        if !flag(step, "completed") {
+            if first_open.is_none() && !path.is_empty() {
+                *first_open = Some(path.to_string());
+            }
+            for checkpoint in CHECKPOINTS {
+                let confirmed = flag(step, &format!("{}_confirmed", checkpoint))
+                    || flag(step, &format!("{}_auto_confirmed", checkpoint));
+                if !confirmed {
+                    pending.push(PendingCheckpoint {
+                        path: path.to_string(),
+                        title: step
+                            .get("title")
+                            .and_then(|t| t.as_str())
+                            .unwrap_or("")
+                            .to_string(),
+                        checkpoint: checkpoint.to_string(),
+                    });
+                }
+            }
+        }
+
+        if let Some(children) = step.get("steps") {
+            collect_steps(children, pending, first_open);
+        }
+        if let Some(tasks) = step.pointer("/plan/tasks").and_then(|t| t.as_array()) {
+            for task in tasks {
+                if let Some(children) = task.get("steps") {
+                    collect_steps(children, pending, first_open);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_catalog::ErrorCode;
+    use serde_json::json;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/tasks_context.json");
+
+    #[test]
+    fn test_parses_captured_payload() {
+        let response: Value = serde_json::from_str(FIXTURE).unwrap();
+        let ctx = ContextResponse::from_response(response);
+
+        assert!(ctx.success);
+        assert_eq!(ctx.focus_id.as_deref(), Some("TASK-001"));
+        assert_eq!(
+            ctx.active_task.as_ref().unwrap()["title"],
+            "Fix login redirect"
+        );
+        assert_eq!(ctx.current_subtask_path.as_deref(), Some("s:0"));
+        assert_eq!(
+            ctx.pending_checkpoints,
+            vec![
+                PendingCheckpoint {
+                    path: "s:1".into(),
+                    title: "Fix".into(),
+                    checkpoint: "criteria".into(),
+                },
+                PendingCheckpoint {
+                    path: "s:1".into(),
+                    title: "Fix".into(),
+                    checkpoint: "tests".into(),
+                },
+            ]
+        );
+        assert_eq!(ctx.recent_history.len(), 2);
+        assert_eq!(ctx.summary.plans, 1);
+        assert_eq!(ctx.summary.tasks, 2);
+        assert_eq!(ctx.summary.by_status.get("TODO"), Some(&2));
+        assert_eq!(ctx.tasks.len(), 2);
+        assert_eq!(ctx.plans.len(), 1);
+        assert!(ctx.raw.get("tasks_pagination").is_some());
+    }
+
+    #[test]
+    fn test_lenient_on_missing_fields() {
+        let ctx = ContextResponse::from_response(json!({"success": true}));
+        assert!(ctx.success);
+        assert!(ctx.active_task.is_none());
+        assert!(ctx.pending_checkpoints.is_empty());
+        assert_eq!(ctx.summary, ProjectSummary::default());
+
+        let failed = ContextResponse::from_response(json!({
+            "success": false,
+            "error": {"code": "INVALID_ID", "message": "bad id"}
+        }));
+        assert!(!failed.success);
+        assert_eq!(failed.error.message.as_deref(), Some("bad id"));
+        assert_eq!(failed.error.code, Some(ErrorCode::Backend));
+    }
+
+    #[test]
+    fn test_subtree_path_wins() {
+        let ctx = ContextResponse::from_response(json!({
+            "success": true,
+            "result": {"subtree": {"path": "s:2.t:1"}, "current_task": {"steps": [{"path": "s:0"}]}}
+        }));
+        assert_eq!(ctx.current_subtask_path.as_deref(), Some("s:2.t:1"));
+    }
+}
@@ -0,0 +1,321 @@
+//! Crash reports
+//!
+//! A panic hook writes `crash-<time>.txt` into the app log directory: the
+//! panic message and location, a backtrace, GUI/backend versions, the last
+//! `LOG_LINES` log lines and the project path, with the home directory
+//! shown as `~`. The hook only uses non-blocking locks and never logs
+//! (the logger may be what panicked). Reports found at startup are
+//! announced with `crash-report-available` until they are deleted.
+
+use std::backtrace::Backtrace;
+use std::fs;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::logging;
+use crate::AppState;
+
+/// Event emitted at startup when crash reports exist
+pub const CRASH_REPORT_AVAILABLE_EVENT: &str = "crash-report-available";
+/// Log lines included in a report
+const LOG_LINES: usize = 100;
+const REPORT_PREFIX: &str = "crash-";
+const REPORT_EXT: &str = "txt";
+
+static REPORT_DIR: OnceLock<PathBuf> = OnceLock::new();
+static APP: OnceLock<AppHandle> = OnceLock::new();
+/// Set while a report is written (a panic inside the hook writes nothing)
+static WRITING: AtomicBool = AtomicBool::new(false);
+
+/// What goes into a report
+#[derive(Debug, Default)]
+pub struct CrashInfo {
+    pub message: String,
+    pub location: Option<String>,
+    pub thread: String,
+    pub backtrace: String,
+    pub gui_version: String,
+    pub backend_version: Option<String>,
+    pub project: Option<PathBuf>,
+    pub log_lines: Vec<String>,
+}
+
+/// A report on disk (`get_crash_reports`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    /// File stem, `crash-20261014-101500-123`
+    pub id: String,
+    /// First line of the panic message
+    pub summary: String,
+    /// RFC 3339 modification time
+    pub created: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReportAvailable {
+    pub count: usize,
+    pub latest: String,
+}
+
+/// `text` with `home` (and anything below it) shown as `~`
+pub fn redact_home(text: &str, home: Option<&Path>) -> String {
+    match home.and_then(|h| h.to_str()).filter(|h| h.len() > 1) {
+        Some(home) => text.replace(home.trim_end_matches(['/', '\\']), "~"),
+        None => text.to_string(),
+    }
+}
+
+pub(crate) fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+/// The report text (home directory redacted throughout)
+pub fn render(info: &CrashInfo, home: Option<&Path>) -> String {
+    let mut text = format!(
+        "Apply Task GUI crash report\n\n\
+         Panic: {}\n\
+         Location: {}\n\
+         Thread: {}\n\
+         GUI version: {}\n\
+         Backend version: {}\n\
+         Project: {}\n\
+         OS: {} {}\n\n\
+         Backtrace:\n{}\n\n\
+         Last {} log lines:\n",
+        info.message,
+        info.location.as_deref().unwrap_or("unknown"),
+        info.thread,
+        info.gui_version,
+        info.backend_version.as_deref().unwrap_or("unknown"),
+        info.project
+            .as_ref()
+            .map_or_else(|| "unknown".to_string(), |p| p.display().to_string()),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        info.backtrace.trim_end(),
+        info.log_lines.len(),
+    );
+    for line in &info.log_lines {
+        text.push_str(line);
+        text.push('\n');
+    }
+    redact_home(&text, home)
+}
+
+fn panic_message(info: &PanicHookInfo) -> String {
+    let payload = info.payload();
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".to_string())
+}
+
+/// Versions and project from the app state, without waiting for a lock
+fn app_context(info: &mut CrashInfo) {
+    let Some(state) = APP.get().and_then(|app| app.try_state::<AppState>()) else {
+        return;
+    };
+    if let Ok(versions) = state.versions.try_lock() {
+        info.backend_version = versions.as_ref().and_then(|v| v.backend.clone());
+    }
+    if let Ok(cwd) = state.user_cwd.try_lock() {
+        info.project = Some(cwd.clone());
+    };
+}
+
+fn write_report(panic: &PanicHookInfo) -> Option<PathBuf> {
+    let dir = REPORT_DIR.get()?;
+    let mut info = CrashInfo {
+        message: panic_message(panic),
+        location: panic
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+        thread: std::thread::current()
+            .name()
+            .unwrap_or("unnamed")
+            .to_string(),
+        backtrace: Backtrace::force_capture().to_string(),
+        gui_version: env!("CARGO_PKG_VERSION").to_string(),
+        log_lines: logging::try_recent_lines(LOG_LINES).unwrap_or_default(),
+        ..CrashInfo::default()
+    };
+    app_context(&mut info);
+
+    let id = format!(
+        "{}{}",
+        REPORT_PREFIX,
+        chrono::Local::now().format("%Y%m%d-%H%M%S-%3f")
+    );
+    let path = dir.join(format!("{}.{}", id, REPORT_EXT));
+    fs::create_dir_all(dir).ok()?;
+    fs::write(&path, render(&info, home_dir().as_deref())).ok()?;
+    Some(path)
+}
+
+/// Install the panic hook (first thing in `run`, before `attach`)
+pub fn install_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic| {
+        previous(panic);
+        if WRITING.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        if let Some(path) = write_report(panic) {
+            eprintln!("Crash report written to {}", path.display());
+        }
+        WRITING.store(false, Ordering::SeqCst);
+    }));
+}
+
+/// Where reports go from now on; announces reports left by earlier runs
+pub fn attach(app: &AppHandle, dir: PathBuf) {
+    let _ = APP.set(app.clone());
+    let reports = list(&dir);
+    let _ = REPORT_DIR.set(dir);
+    if let Some(latest) = reports.first() {
+        log::warn!(
+            "{} crash report(s) from earlier runs, latest {}",
+            reports.len(),
+            latest.id
+        );
+        let payload = CrashReportAvailable {
+            count: reports.len(),
+            latest: latest.id.clone(),
+        };
+        if let Err(e) = app.emit(CRASH_REPORT_AVAILABLE_EVENT, &payload) {
+            log::warn!("Failed to emit {}: {}", CRASH_REPORT_AVAILABLE_EVENT, e);
+        }
+    }
+}
+
+/// Report directory, once `attach` ran
+pub fn report_dir() -> Option<&'static Path> {
+    REPORT_DIR.get().map(PathBuf::as_path)
+}
+
+fn valid_id(id: &str) -> bool {
+    id.strip_prefix(REPORT_PREFIX).is_some_and(|rest| {
+        !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit() || c == '-')
+    })
+}
+
+/// Reports in `dir`, newest first
+pub fn list(dir: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != REPORT_EXT {
+                return None;
+            }
+            let id = path.file_stem()?.to_str()?.to_string();
+            if !valid_id(&id) {
+                return None;
+            }
+            let text = fs::read_to_string(&path).ok()?;
+            let summary = text
+                .lines()
+                .find_map(|l| l.strip_prefix("Panic: "))
+                .unwrap_or("")
+                .to_string();
+            let created = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .map(|t| chrono::DateTime::<chrono::Local>::from(t).to_rfc3339());
+            Some(CrashReport {
+                id,
+                summary,
+                created,
+                text,
+            })
+        })
+        .collect();
+    // Ids sort by time
+    reports.sort_by(|a, b| b.id.cmp(&a.id));
+    reports
+}
+
+pub fn delete(dir: &Path, id: &str) -> Result<()> {
+    if !valid_id(id) {
+        bail!("Invalid crash report id: {}", id);
+    }
+    let path = dir.join(format!("{}.{}", id, REPORT_EXT));
+    fs::remove_file(&path).with_context(|| format!("Failed to delete {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_redacts_home() {
+        let home = Path::new("/home/alice/");
+        let info = CrashInfo {
+            message: "called `Option::unwrap()` on a `None` value".into(),
+            location: Some("/home/alice/src/apply_task/gui/src/lib.rs:10:5".into()),
+            thread: "main".into(),
+            backtrace: "   0: std::panicking\n".into(),
+            gui_version: "0.1.0".into(),
+            backend_version: None,
+            project: Some(PathBuf::from("/home/alice/work/api")),
+            log_lines: vec!["INFO opened /home/alice/work/api".into()],
+        };
+        let text = render(&info, Some(home));
+        assert!(!text.contains("alice"), "{}", text);
+        assert!(text.contains("Project: ~/work/api"));
+        assert!(text.contains("Location: ~/src/apply_task/gui/src/lib.rs:10:5"));
+        assert!(text.contains("Backend version: unknown"));
+        assert!(text.ends_with("Last 1 log lines:\nINFO opened ~/work/api\n"));
+        assert_eq!(redact_home("/x", Some(Path::new("/"))), "/x");
+        assert_eq!(redact_home("/x", None), "/x");
+    }
+
+    #[test]
+    fn test_list_and_delete_reports() {
+        let dir = std::env::temp_dir().join(format!("apply-task-crash-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        assert!(list(&dir).is_empty());
+        fs::create_dir_all(&dir).unwrap();
+        for (name, text) in [
+            ("crash-20261001-090000-000.txt", "Panic: old\n"),
+            (
+                "crash-20261014-101500-123.txt",
+                "Intro\nPanic: boom\nLocation: x\n",
+            ),
+            ("apply-task.log", "Panic: not a report\n"),
+            ("crash-notes.txt", "Panic: not a report either\n"),
+        ] {
+            fs::write(dir.join(name), text).unwrap();
+        }
+
+        let reports = list(&dir);
+        let ids: Vec<&str> = reports.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            ["crash-20261014-101500-123", "crash-20261001-090000-000"]
+        );
+        assert_eq!(reports[0].summary, "boom");
+        assert!(reports[0].created.is_some());
+
+        assert!(delete(&dir, "../apply-task").is_err());
+        assert!(delete(&dir, "crash-notes").is_err());
+        delete(&dir, "crash-20261001-090000-000").unwrap();
+        assert!(delete(&dir, "crash-20261001-090000-000").is_err());
+        assert_eq!(list(&dir).len(), 1);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,194 @@
+//! Opt-in debounce of rapid repeated mutations
+//!
+//! A slider drag or a burst of checkbox toggles sends one mutation per step
+//! where only the last matters. Callers that pass `debounce_ms` wait out a
+//! quiet period per `(tool, task_id)` key: a newer call for the same key
+//! supersedes the waiting one at once, and only the caller still standing
+//! when the period ends goes on to the backend. Field edits add up instead:
+//! with [`Debouncer::wait_merged`] that caller gets the changes of every
+//! call it superseded, the later value of a field winning.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde_json::{Map, Value};
+use tokio::sync::oneshot;
+
+/// Longest accepted quiet period
+pub const MAX_DEBOUNCE_MS: u64 = 5_000;
+
+/// What a debounced caller does after waiting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    /// Nothing newer arrived; send the mutation
+    Flush,
+    /// A newer call for the key took over; send nothing
+    Superseded,
+}
+
+struct Waiting {
+    generation: u64,
+    /// Fired when a newer call arrives
+    supersede: oneshot::Sender<()>,
+    /// Changes of this call and the ones it superseded
+    changes: Map<String, Value>,
+}
+
+/// Latest waiting caller per key (kept in `AppState`)
+#[derive(Default)]
+pub struct Debouncer {
+    waiting: Mutex<HashMap<String, Waiting>>,
+    generations: Mutex<u64>,
+}
+
+/// Debounce key of a mutation
+pub fn key(tool: &str, task_id: &str) -> String {
+    format!("{}:{}", tool, task_id)
+}
+
+/// Quiet period for a requested `debounce_ms` (`None` or 0 = no debounce)
+pub fn quiet_period(debounce_ms: Option<u64>) -> Option<Duration> {
+    debounce_ms
+        .filter(|ms| *ms > 0)
+        .map(|ms| Duration::from_millis(ms.min(MAX_DEBOUNCE_MS)))
+}
+
+impl Debouncer {
+    /// Wait until `quiet` passes without a newer call for `key`
+    pub async fn wait(&self, key: &str, quiet: Duration) -> Turn {
+        match self.wait_merged(key, quiet, Map::new()).await {
+            Some(_) => Turn::Flush,
+            None => Turn::Superseded,
+        }
+    }
+
+    /// [`wait`](Self::wait) for a call carrying `changes`: the call that
+    /// flushes gets them merged with those of the calls it superseded
+    /// (`None` when superseded)
+    pub async fn wait_merged(
+        &self,
+        key: &str,
+        quiet: Duration,
+        changes: Map<String, Value>,
+    ) -> Option<Map<String, Value>> {
+        let (supersede, superseded) = oneshot::channel();
+        let generation = {
+            let mut generations = self.generations.lock().unwrap_or_else(|e| e.into_inner());
+            *generations += 1;
+            *generations
+        };
+        {
+            let mut waiting = self.waiting.lock().unwrap_or_else(|e| e.into_inner());
+            let mut merged = Map::new();
+            if let Some(previous) = waiting.remove(key) {
+                let _ = previous.supersede.send(());
+                merged = previous.changes;
+            }
+            merged.extend(changes);
+            waiting.insert(
+                key.to_string(),
+                Waiting {
+                    generation,
+                    supersede,
+                    changes: merged,
+                },
+            );
+        }
+
+        tokio::select! {
+            _ = superseded => None,
+            _ = tokio::time::sleep(quiet) => {
+                let mut waiting = self.waiting.lock().unwrap_or_else(|e| e.into_inner());
+                // A newer call may have replaced us just as the timer fired
+                match waiting.get(key) {
+                    Some(latest) if latest.generation == generation => {
+                        waiting.remove(key).map(|latest| latest.changes)
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_burst_flushes_only_the_last_value() {
+        let debouncer = Arc::new(Debouncer::default());
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let mut callers = Vec::new();
+        for value in 1..=5 {
+            let (debouncer, sent) = (debouncer.clone(), sent.clone());
+            callers.push(tokio::spawn(async move {
+                let turn = debouncer
+                    .wait(&key("tasks_edit", "T-1"), Duration::from_millis(50))
+                    .await;
+                if turn == Turn::Flush {
+                    // Stands in for the backend call
+                    sent.lock().unwrap().push(value);
+                }
+                turn
+            }));
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let mut turns = Vec::new();
+        for caller in callers {
+            turns.push(caller.await.unwrap());
+        }
+        assert_eq!(*sent.lock().unwrap(), [5]);
+        assert_eq!(turns.iter().filter(|t| **t == Turn::Superseded).count(), 4);
+        assert_eq!(turns[4], Turn::Flush);
+
+        // Other keys are independent
+        let other = debouncer
+            .wait(&key("tasks_edit", "T-2"), Duration::from_millis(1))
+            .await;
+        assert_eq!(other, Turn::Flush);
+        assert_eq!(quiet_period(Some(0)), None);
+        assert_eq!(
+            quiet_period(Some(60_000)),
+            Some(Duration::from_millis(MAX_DEBOUNCE_MS))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merged_changes_keep_every_field() {
+        let debouncer = Arc::new(Debouncer::default());
+        let edits = [
+            serde_json::json!({ "title": "Parser" }),
+            serde_json::json!({ "priority": "LOW" }),
+            serde_json::json!({ "priority": "HIGH" }),
+        ];
+        let mut callers = Vec::new();
+        for edit in edits {
+            let debouncer = debouncer.clone();
+            let changes = edit.as_object().cloned().unwrap();
+            callers.push(tokio::spawn(async move {
+                debouncer
+                    .wait_merged(
+                        &key("tasks_edit", "T-1"),
+                        Duration::from_millis(50),
+                        changes,
+                    )
+                    .await
+            }));
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let mut flushed = Vec::new();
+        for caller in callers {
+            flushed.push(caller.await.unwrap());
+        }
+        assert_eq!(flushed[..2], [None, None]);
+        assert_eq!(
+            flushed[2].clone().map(Value::Object),
+            Some(serde_json::json!({ "title": "Parser", "priority": "HIGH" }))
+        );
+    }
+}
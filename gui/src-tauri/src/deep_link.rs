@@ -0,0 +1,161 @@
+//! `apply-task://` deep links
+//!
+//! `apply-task://task/<namespace>/<task id>` opens the GUI on a task. Links
+//! arriving from the OS are parsed here and forwarded to the frontend as a
+//! `navigate-to-task` event; links for another project ask for a switch.
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager};
+use url::Url;
+
+use crate::backend;
+use crate::error_catalog::{CatalogError, ErrorCode};
+use crate::AppState;
+
+/// URL scheme registered with the OS (see `tauri.conf.json`)
+pub const SCHEME: &str = "apply-task";
+/// Event emitted when a task link is opened
+pub const NAVIGATE_EVENT: &str = "navigate-to-task";
+
+/// Characters escaped in link path segments (`-`, `_`, `.`, `~` stay as-is)
+const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskLink {
+    pub namespace: String,
+    pub task_id: String,
+}
+
+/// Payload of [`NAVIGATE_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NavigateToTask {
+    pub namespace: String,
+    pub task_id: String,
+    /// The link targets another project; the frontend should offer a switch
+    pub requires_project_switch: bool,
+    pub current_namespace: Option<String>,
+}
+
+/// `apply-task://task/<namespace>/<id>` with both segments percent-encoded
+pub fn build_task_link(namespace: &str, task_id: &str) -> String {
+    format!(
+        "{}://task/{}/{}",
+        SCHEME,
+        utf8_percent_encode(namespace, SEGMENT),
+        utf8_percent_encode(task_id, SEGMENT)
+    )
+}
+
+/// Parse a task link; `None` for anything malformed
+pub fn parse_task_link(url: &Url) -> Option<TaskLink> {
+    if url.scheme() != SCHEME || url.host_str() != Some("task") {
+        return None;
+    }
+    let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+    let [namespace, task_id] = segments.as_slice() else {
+        return None;
+    };
+    let decode = |s: &str| {
+        percent_decode_str(s)
+            .decode_utf8()
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    Some(TaskLink {
+        namespace: decode(namespace)?,
+        task_id: decode(task_id)?,
+    })
+}
+
+/// Current project namespace as reported by `tasks_storage`
+pub async fn current_namespace(state: &AppState) -> Option<String> {
+    let bridge = &state.bridge;
+    let response = bridge.call_tool("tasks_storage", json!({})).await.ok()?;
+    backend::into_result(response)
+        .ok()?
+        .get("current_namespace")
+        .and_then(|n| n.as_str())
+        .map(String::from)
+}
+
+/// Why `namespace` can't be used: not the open project's, or unknown
+pub async fn namespace_error(state: &AppState, namespace: &str) -> Option<CatalogError> {
+    let namespace = namespace.trim();
+    match current_namespace(state).await {
+        Some(current) if current == namespace => None,
+        Some(current) => Some(
+            CatalogError::new(ErrorCode::ProjectNamespaceMismatch)
+                .with("namespace", namespace)
+                .with("current", current),
+        ),
+        None => Some(CatalogError::new(ErrorCode::ProjectNamespaceUnknown)),
+    }
+}
+
+/// Emit `navigate-to-task` for `link` (checking it against the open project)
+pub fn open_link(app: &AppHandle, link: TaskLink) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let current = match app.try_state::<AppState>() {
+            Some(state) => current_namespace(&state).await,
+            None => None,
+        };
+        let payload = NavigateToTask {
+            requires_project_switch: current.as_deref().is_some_and(|ns| ns != link.namespace),
+            namespace: link.namespace,
+            task_id: link.task_id,
+            current_namespace: current,
+        };
+        if let Err(e) = app.emit(NAVIGATE_EVENT, &payload) {
+            log::warn!("Failed to emit {}: {}", NAVIGATE_EVENT, e);
+        }
+    });
+}
+
+/// Handle URLs delivered by the OS
+pub fn handle_urls(app: &AppHandle, urls: Vec<Url>) {
+    for url in urls {
+        match parse_task_link(&url) {
+            Some(link) => open_link(app, link),
+            None => log::warn!("Ignoring malformed deep link: {}", url),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_roundtrip_with_spaces() {
+        let link = build_task_link("my project", "TASK-001");
+        assert_eq!(link, "apply-task://task/my%20project/TASK-001");
+        assert_eq!(
+            parse_task_link(&Url::parse(&link).unwrap()),
+            Some(TaskLink {
+                namespace: "my project".into(),
+                task_id: "TASK-001".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_rejects_malformed_links() {
+        for raw in [
+            "https://task/ns/TASK-1",
+            "apply-task://plan/ns/TASK-1",
+            "apply-task://task/ns",
+            "apply-task://task/ns/TASK-1/extra",
+            "apply-task://task/%20/TASK-1",
+        ] {
+            assert_eq!(parse_task_link(&Url::parse(raw).unwrap()), None, "{}", raw);
+        }
+    }
+}
@@ -0,0 +1,556 @@
+//! apply_task root detection
+//!
+//! Finds the apply_task package root (where the Python backend lives) by
+//! trying, in order: `APPLY_TASK_PROJECT_ROOT`, the location of the GUI
+//! executable inside a cargo `target/` dir, the working directory and its
+//! ancestors, then an installed package the interpreter can import. Every candidate is recorded so a failure can show what
+//! was checked. The detected path is canonicalized, so a symlinked
+//! checkout maps to one project key.
+//!
+//! `APPLY_TASK_PROJECT_ROOT`, `APPLY_TASK_PATH` and `APPLY_TASK_PYTHON`
+//! naming a path that can't be used are skipped like unset ones, but also
+//! reported as [`ConfigWarning`]s (and a `config-warning` event at startup).
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error_catalog::{CatalogError, ResponseError};
+use crate::python::{self, InstallMode};
+
+/// Event emitted at startup when detection failed
+pub const DETECTION_FAILED_EVENT: &str = "detection-failed";
+/// Event emitted at startup for each [`ConfigWarning`]
+pub const CONFIG_WARNING_EVENT: &str = "config-warning";
+
+/// Ancestors of the working directory tried (`gui/src-tauri` -> root is 2)
+const CWD_ANCESTOR_DEPTH: usize = 6;
+/// Cargo output dirs the GUI binary runs from in a checkout
+const CARGO_PROFILES: [&str; 2] = ["debug", "release"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Strategy {
+    EnvVar,
+    ExeRelative,
+    CwdAncestor,
+    /// pip/uv install without a source checkout
+    Package,
+    /// `ssh.path` on the backend host (nothing detected locally)
+    Remote,
+}
+
+/// One checked candidate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Probe {
+    pub strategy: Strategy,
+    pub path: Option<PathBuf>,
+    pub matched: bool,
+    /// Why the candidate was rejected (or how it matched)
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedRoot {
+    pub path: PathBuf,
+    pub strategy: Strategy,
+    /// Every candidate up to and including the match
+    pub probes: Vec<Probe>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, thiserror::Error)]
+#[error("apply_task root not found ({} locations checked)", probes.len())]
+pub struct DetectionError {
+    pub probes: Vec<Probe>,
+}
+
+/// Environment variable set to a path that was ignored
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigWarning {
+    pub variable: String,
+    pub path: PathBuf,
+    /// `does not exist`, `is not a directory`, ...
+    pub reason: String,
+    pub message: String,
+}
+
+impl ConfigWarning {
+    fn new(variable: &str, path: &Path, reason: &str) -> Self {
+        Self {
+            variable: variable.to_string(),
+            path: path.to_path_buf(),
+            reason: reason.to_string(),
+            message: format!("{} {} {}; ignored", variable, path.display(), reason),
+        }
+    }
+}
+
+/// Markers of an apply_task root (`core/` or `tasks.py`)
+pub fn has_root_markers(dir: &Path) -> bool {
+    dir.join("core").exists() || dir.join("tasks.py").exists()
+}
+
+/// Inputs of [`detect`], read from the process environment by [`probe_env`]
+#[derive(Debug, Default)]
+pub struct DetectionInput {
+    pub env_root: Option<PathBuf>,
+    /// `APPLY_TASK_PATH` (the entry script)
+    pub env_entry: Option<PathBuf>,
+    /// `APPLY_TASK_PYTHON`
+    pub env_python: Option<String>,
+    pub exe_path: Option<PathBuf>,
+    pub cwd: Option<PathBuf>,
+    /// Interpreter asked to import an installed `apply_task`
+    pub python: Option<String>,
+}
+
+/// `cwd` is the launch directory (before any `chdir`)
+pub fn probe_env(python: &str, cwd: &Path) -> DetectionInput {
+    DetectionInput {
+        python: Some(python.to_string()),
+        env_root: std::env::var("APPLY_TASK_PROJECT_ROOT")
+            .ok()
+            .map(PathBuf::from),
+        env_entry: std::env::var("APPLY_TASK_PATH").ok().map(PathBuf::from),
+        env_python: std::env::var("APPLY_TASK_PYTHON").ok(),
+        exe_path: std::env::current_exe().ok(),
+        cwd: Some(cwd.to_path_buf()),
+    }
+}
+
+/// Why `path` can't be the apply_task root
+fn root_problem(path: &Path) -> Option<&'static str> {
+    if !path.exists() {
+        Some("does not exist")
+    } else if !path.is_dir() {
+        Some("is not a directory")
+    } else {
+        None
+    }
+}
+
+/// Variables in `input` that are set but unusable; unset ones are fine
+pub fn config_warnings(input: &DetectionInput) -> Vec<ConfigWarning> {
+    let mut warnings = Vec::new();
+    if let Some(path) = &input.env_root {
+        if let Some(reason) = root_problem(path) {
+            warnings.push(ConfigWarning::new("APPLY_TASK_PROJECT_ROOT", path, reason));
+        }
+    }
+    if let Some(path) = &input.env_entry {
+        let reason = match (path.exists(), path.is_file()) {
+            (false, _) => Some("does not exist"),
+            (true, false) => Some("is not a file"),
+            _ => None,
+        };
+        if let Some(reason) = reason {
+            warnings.push(ConfigWarning::new("APPLY_TASK_PATH", path, reason));
+        }
+    }
+    if let Some(python) = &input.env_python {
+        if let Some(reason) = python::interpreter_problem(python) {
+            warnings.push(ConfigWarning::new(
+                "APPLY_TASK_PYTHON",
+                Path::new(python),
+                reason,
+            ));
+        }
+    }
+    warnings
+}
+
+fn marker_probe(strategy: Strategy, path: &Path) -> Probe {
+    let matched = has_root_markers(path);
+    Probe {
+        strategy,
+        path: Some(path.to_path_buf()),
+        matched,
+        detail: if matched {
+            "found core/ or tasks.py".to_string()
+        } else {
+            "no core/ or tasks.py".to_string()
+        },
+    }
+}
+
+/// Path components as strings, also split on `\\` (Windows paths off Windows)
+fn component_names(path: &Path) -> Vec<String> {
+    path.components()
+        .flat_map(|c| {
+            c.as_os_str()
+                .to_string_lossy()
+                .split('\\')
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Whether `dir` is `.../target/debug` or `.../target/release`
+fn is_cargo_profile_dir(dir: &Path) -> bool {
+    match component_names(dir).as_slice() {
+        [.., target, profile] => {
+            target.eq_ignore_ascii_case("target")
+                && CARGO_PROFILES
+                    .iter()
+                    .any(|p| profile.eq_ignore_ascii_case(p))
+        }
+        _ => false,
+    }
+}
+
+/// Root for the GUI binary at `exe_path`
+///
+/// Binary location: `apply_task/gui/src-tauri/target/<profile>/apply-task-gui`,
+/// so the root is four levels above the binary's directory.
+fn exe_probe(exe_path: &Path) -> Probe {
+    let exe_dir = exe_path.parent().unwrap_or(exe_path);
+    let in_target = is_cargo_profile_dir(exe_dir);
+    match exe_dir.ancestors().nth(4).filter(|_| in_target) {
+        Some(root) => marker_probe(Strategy::ExeRelative, root),
+        None => Probe {
+            strategy: Strategy::ExeRelative,
+            path: Some(exe_dir.to_path_buf()),
+            matched: false,
+            detail: "not inside a target/debug or target/release build".to_string(),
+        },
+    }
+}
+
+pub fn detect(input: &DetectionInput) -> Result<DetectedRoot, DetectionError> {
+    let mut probes = Vec::new();
+    let found = |probes: Vec<Probe>| {
+        let last = probes.last().expect("matched probe");
+        let path = last.path.clone().unwrap_or_default();
+        DetectedRoot {
+            path: path.canonicalize().unwrap_or(path),
+            strategy: last.strategy,
+            probes,
+        }
+    };
+
+    // 1. Explicit environment variable (trusted when it is a directory)
+    match &input.env_root {
+        Some(path) => {
+            let problem = root_problem(path);
+            let matched = problem.is_none();
+            probes.push(Probe {
+                strategy: Strategy::EnvVar,
+                path: Some(path.clone()),
+                matched,
+                detail: match problem {
+                    None => "APPLY_TASK_PROJECT_ROOT".to_string(),
+                    Some(reason) => format!("APPLY_TASK_PROJECT_ROOT {}", reason),
+                },
+            });
+            if matched {
+                return Ok(found(probes));
+            }
+        }
+        None => probes.push(Probe {
+            strategy: Strategy::EnvVar,
+            path: None,
+            matched: false,
+            detail: "APPLY_TASK_PROJECT_ROOT not set".to_string(),
+        }),
+    }
+
+    // 2. Development build inside the repo
+    if let Some(exe_path) = &input.exe_path {
+        probes.push(exe_probe(exe_path));
+        if probes.last().is_some_and(|p| p.matched) {
+            return Ok(found(probes));
+        }
+    }
+
+    // 3. Working directory and its ancestors (gui/, gui/src-tauri/, ...)
+    if let Some(cwd) = &input.cwd {
+        for dir in cwd.ancestors().take(CWD_ANCESTOR_DEPTH + 1) {
+            probes.push(marker_probe(Strategy::CwdAncestor, dir));
+            if probes.last().is_some_and(|p| p.matched) {
+                return Ok(found(probes));
+            }
+        }
+    }
+
+    // 4. Installed package (no checkout needed)
+    if let Some(python) = &input.python {
+        let module = python::package_file(python);
+        probes.push(Probe {
+            strategy: Strategy::Package,
+            path: module
+                .as_ref()
+                .and_then(|m| m.parent())
+                .map(Path::to_path_buf),
+            matched: module.is_some(),
+            detail: match &module {
+                Some(module) => format!("{} imports {}", python, module.display()),
+                None => format!("apply_task not importable with {}", python),
+            },
+        });
+        if module.is_some() {
+            return Ok(found(probes));
+        }
+    }
+
+    Err(DetectionError { probes })
+}
+
+/// What `detection_report` returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectionReport {
+    pub success: bool,
+    /// Root the backend runs from (the cwd fallback on failure)
+    pub root: PathBuf,
+    pub strategy: Option<Strategy>,
+    pub probes: Vec<Probe>,
+    #[serde(flatten)]
+    pub error: ResponseError,
+    /// How the backend is launched (filled in by `detection_report`)
+    pub install_mode: Option<InstallMode>,
+    /// Variables that were set but ignored
+    pub warnings: Vec<ConfigWarning>,
+}
+
+impl DetectionReport {
+    pub fn new(result: &Result<DetectedRoot, DetectionError>, root: &Path) -> Self {
+        match result {
+            Ok(detected) => Self {
+                success: true,
+                root: root.to_path_buf(),
+                strategy: Some(detected.strategy),
+                probes: detected.probes.clone(),
+                error: ResponseError::none(),
+                install_mode: None,
+                warnings: Vec::new(),
+            },
+            Err(e) => Self {
+                success: false,
+                root: root.to_path_buf(),
+                strategy: None,
+                probes: e.probes.clone(),
+                error: CatalogError::from(e).into(),
+                install_mode: None,
+                warnings: Vec::new(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("apply-task-detect-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("repo/core")).unwrap();
+        fs::create_dir_all(dir.join("repo/gui/src-tauri/target/debug")).unwrap();
+        fs::create_dir_all(dir.join("repo/gui/src/features/tasks")).unwrap();
+        fs::create_dir_all(dir.join("elsewhere/1/2/3/4/5/6/7")).unwrap();
+        fs::write(dir.join("elsewhere/tasks.py"), "").unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    #[test]
+    fn test_env_var_wins_when_it_exists() {
+        let dir = temp_root("env");
+        let input = DetectionInput {
+            env_root: Some(dir.join("elsewhere")),
+            cwd: Some(dir.join("repo")),
+            ..DetectionInput::default()
+        };
+        let detected = detect(&input).unwrap();
+        assert_eq!(detected.strategy, Strategy::EnvVar);
+        assert_eq!(detected.path, dir.join("elsewhere"));
+
+        // A missing path is recorded and the next strategy is tried
+        let input = DetectionInput {
+            env_root: Some(dir.join("missing")),
+            cwd: Some(dir.join("repo")),
+            ..DetectionInput::default()
+        };
+        let detected = detect(&input).unwrap();
+        assert_eq!(detected.strategy, Strategy::CwdAncestor);
+        assert!(!detected.probes[0].matched);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_invalid_env_paths_are_warned_about() {
+        let dir = temp_root("warn");
+        let missing = dir.join("missing");
+        let input = DetectionInput {
+            env_root: Some(missing.clone()),
+            env_entry: Some(missing.join("apply_task")),
+            env_python: Some(missing.join("python3").to_string_lossy().into_owned()),
+            cwd: Some(dir.join("repo")),
+            ..DetectionInput::default()
+        };
+        let warnings = config_warnings(&input);
+        let variables: Vec<&str> = warnings.iter().map(|w| w.variable.as_str()).collect();
+        assert_eq!(
+            variables,
+            [
+                "APPLY_TASK_PROJECT_ROOT",
+                "APPLY_TASK_PATH",
+                "APPLY_TASK_PYTHON"
+            ]
+        );
+        assert!(warnings.iter().all(|w| w.reason == "does not exist"));
+        assert_eq!(warnings[0].path, missing);
+        // Detection still falls through to the next strategy
+        assert_eq!(detect(&input).unwrap().strategy, Strategy::CwdAncestor);
+
+        // A file is not a root, a directory is not a script
+        let input = DetectionInput {
+            env_root: Some(dir.join("elsewhere/tasks.py")),
+            env_entry: Some(dir.join("repo")),
+            cwd: Some(dir.join("repo")),
+            ..DetectionInput::default()
+        };
+        let reasons: Vec<String> = config_warnings(&input)
+            .into_iter()
+            .map(|w| w.reason)
+            .collect();
+        assert_eq!(reasons, ["is not a directory", "is not a file"]);
+        assert_eq!(detect(&input).unwrap().strategy, Strategy::CwdAncestor);
+
+        #[cfg(unix)]
+        {
+            let script = dir.join("elsewhere/tasks.py");
+            let input = DetectionInput {
+                env_python: Some(script.to_string_lossy().into_owned()),
+                ..DetectionInput::default()
+            };
+            assert_eq!(config_warnings(&input)[0].reason, "is not executable");
+        }
+
+        // Unset variables and bare command names are fine
+        let input = DetectionInput {
+            env_root: Some(dir.join("elsewhere")),
+            env_python: Some("python3.12".into()),
+            ..DetectionInput::default()
+        };
+        assert!(config_warnings(&input).is_empty());
+        assert!(config_warnings(&DetectionInput::default()).is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_exe_relative() {
+        let dir = temp_root("exe");
+        let input = DetectionInput {
+            exe_path: Some(dir.join("repo/gui/src-tauri/target/debug/apply-task-gui")),
+            ..DetectionInput::default()
+        };
+        let detected = detect(&input).unwrap();
+        assert_eq!(detected.strategy, Strategy::ExeRelative);
+        assert_eq!(detected.path, dir.join("repo"));
+
+        // An installed binary is not exe-relative
+        let input = DetectionInput {
+            exe_path: Some(dir.join("elsewhere/apply-task-gui")),
+            ..DetectionInput::default()
+        };
+        assert!(detect(&input).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_installed_package_is_last_resort() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = temp_root("package");
+        let stub = |name: &str, script: &str| {
+            let path = dir.join(name);
+            fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+            path.to_string_lossy().to_string()
+        };
+        let installed = stub("python-installed", "echo /venv/site/apply_task.py");
+        let missing = stub("python-missing", "exit 1");
+
+        let input = DetectionInput {
+            cwd: Some(dir.join("elsewhere/1/2/3/4/5/6/7")),
+            python: Some(installed.clone()),
+            ..DetectionInput::default()
+        };
+        let detected = detect(&input).unwrap();
+        assert_eq!(detected.strategy, Strategy::Package);
+        assert_eq!(detected.path, PathBuf::from("/venv/site"));
+
+        // A checkout is found before the interpreter is asked
+        let input = DetectionInput {
+            cwd: Some(dir.join("repo/gui")),
+            python: Some(installed),
+            ..DetectionInput::default()
+        };
+        assert_eq!(detect(&input).unwrap().strategy, Strategy::CwdAncestor);
+
+        let input = DetectionInput {
+            python: Some(missing),
+            ..DetectionInput::default()
+        };
+        let err = detect(&input).unwrap_err();
+        assert_eq!(err.probes.last().unwrap().strategy, Strategy::Package);
+        assert!(!err.probes.last().unwrap().matched);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_cargo_profile_dir_components() {
+        assert!(is_cargo_profile_dir(Path::new(
+            "/repo/gui/src-tauri/target/debug"
+        )));
+        assert!(is_cargo_profile_dir(Path::new(
+            r"C:\Users\me\apply_task\gui\src-tauri\target\release"
+        )));
+        assert!(!is_cargo_profile_dir(Path::new("/opt/target/debugger")));
+        assert!(!is_cargo_profile_dir(Path::new("/usr/bin")));
+        assert!(!is_cargo_profile_dir(Path::new("debug")));
+    }
+
+    #[test]
+    fn test_cwd_ancestor_and_failure_trail() {
+        let dir = temp_root("cwd");
+        // Nested anywhere below the root
+        let input = DetectionInput {
+            cwd: Some(dir.join("repo/gui/src/features/tasks")),
+            ..DetectionInput::default()
+        };
+        let detected = detect(&input).unwrap();
+        assert_eq!(detected.strategy, Strategy::CwdAncestor);
+        assert_eq!(detected.path, dir.join("repo"));
+        assert_eq!(detected.probes.len(), 1 + 5);
+
+        // Symlinked checkouts resolve to the real path
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.join("repo"), dir.join("link")).unwrap();
+            let input = DetectionInput {
+                cwd: Some(dir.join("link/gui")),
+                ..DetectionInput::default()
+            };
+            assert_eq!(detect(&input).unwrap().path, dir.join("repo"));
+        }
+
+        // Deeper than the cap: cwd + CWD_ANCESTOR_DEPTH ancestors, then give up
+        let input = DetectionInput {
+            exe_path: Some(dir.join("elsewhere/apply-task-gui")),
+            cwd: Some(dir.join("elsewhere/1/2/3/4/5/6/7")),
+            ..DetectionInput::default()
+        };
+        let err = detect(&input).unwrap_err();
+        let strategies: Vec<Strategy> = err.probes.iter().map(|p| p.strategy).collect();
+        assert_eq!(strategies[..2], [Strategy::EnvVar, Strategy::ExeRelative]);
+        assert_eq!(strategies.len(), 2 + CWD_ANCESTOR_DEPTH + 1);
+        assert!(err.probes.iter().all(|p| !p.matched));
+        let report = DetectionReport::new(&Err(err), &dir);
+        assert!(!report.success && !report.error.is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
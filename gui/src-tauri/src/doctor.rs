@@ -0,0 +1,498 @@
+//! `doctor` checklist
+//!
+//! Answers the usual support questions in one pass: which interpreter, is
+//! apply_task importable, how the backend is launched, where the root was
+//! found, whether the storage is writable and how long an MCP handshake
+//! takes. With `isolated_env` the interpreter probes run again in the
+//! trimmed environment, which can hide what pyenv, conda or a venv need to
+//! find the interpreter or the package. A `container` backend gets a Docker
+//! check (daemon, image) in place of the interpreter probes, and it and an
+//! `ssh` backend get a warning that their memory isn't sampled. The
+//! handshake runs against a throwaway backend, so the one serving the GUI
+//! is left alone. Every check has its own time limit. The report ends with
+//! the last log lines.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::backend;
+use crate::backend_resources;
+use crate::logging;
+use crate::python::{ChildEnv, ContainerConfig, EnvMode, InstallMode, PythonBridge, IMPORT_PROBE};
+use crate::storage::StorageInfo;
+use crate::AppState;
+
+/// Limit for each interpreter probe
+pub(crate) const PROCESS_TIMEOUT: Duration = Duration::from_secs(3);
+/// Limit for spawning the probe backend and completing the handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Limit for the probe backend's `tasks_storage` call
+const STORAGE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Oldest Python the backend supports (`python_requires` in setup.cfg)
+const MIN_PYTHON: (u32, u32) = (3, 10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+/// One checklist line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Check {
+    pub check: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to try when the check isn't `ok`
+    pub suggestion: Option<String>,
+}
+
+impl Check {
+    fn ok(check: &str, detail: String) -> Self {
+        Self {
+            check: check.to_string(),
+            status: CheckStatus::Ok,
+            detail,
+            suggestion: None,
+        }
+    }
+
+    fn warn(check: &str, detail: String, suggestion: &str) -> Self {
+        Self {
+            status: CheckStatus::Warn,
+            suggestion: Some(suggestion.to_string()),
+            ..Self::ok(check, detail)
+        }
+    }
+
+    fn fail(check: &str, detail: String, suggestion: &str) -> Self {
+        Self {
+            status: CheckStatus::Fail,
+            ..Self::warn(check, detail, suggestion)
+        }
+    }
+}
+
+/// What `doctor` returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorReport {
+    /// No check failed (warnings allowed)
+    pub success: bool,
+    pub checks: Vec<Check>,
+    pub elapsed_ms: u64,
+    /// Current log file (none before the log directory is known)
+    pub log_path: Option<String>,
+    /// Last `logging::RECENT_LINES` log lines, oldest first
+    pub recent_log: Vec<String>,
+    /// Errors the frontend logged this session
+    pub frontend_errors: u64,
+}
+
+/// Run `program args` outside any project, returning stdout and stderr combined
+async fn run(program: &str, args: &[&str], timeout: Duration) -> Result<String> {
+    let mut cmd = tokio::process::Command::new(program);
+    cmd.args(args).current_dir(std::env::temp_dir());
+    output(cmd, timeout).await
+}
+
+/// Output of `cmd` (killed after `timeout`); a non-zero exit is an error
+pub(crate) async fn output(mut cmd: tokio::process::Command, timeout: Duration) -> Result<String> {
+    let child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let output = tokio::time::timeout(timeout, child.wait_with_output())
+        .await
+        .map_err(|_| anyhow!("no answer within {} ms", timeout.as_millis()))??;
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    if !output.status.success() {
+        bail!("exited with {}: {}", output.status, text.trim());
+    }
+    Ok(text.trim().to_string())
+}
+
+/// `(major, minor)` from `Python 3.12.1`
+fn python_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.split_whitespace().nth(1)?;
+    let mut parts = version.split('.').map(|p| p.parse::<u32>().ok());
+    Some((parts.next()??, parts.next()??))
+}
+
+async fn interpreter_check(python: &str) -> Check {
+    const NAME: &str = "interpreter";
+    let suggestion = "Set python_path in settings (or PYTHON_PATH) to a Python 3.10+ interpreter";
+    match run(python, &["--version"], PROCESS_TIMEOUT).await {
+        Ok(output) => match python_version(&output) {
+            Some(version) if version >= MIN_PYTHON => {
+                Check::ok(NAME, format!("{} ({})", output, python))
+            }
+            _ => Check::warn(
+                NAME,
+                format!("{} ({}) is older than 3.10 or unrecognized", output, python),
+                suggestion,
+            ),
+        },
+        Err(e) => Check::fail(NAME, format!("{}: {}", python, e), suggestion),
+    }
+}
+
+async fn import_check(python: &str, mode: InstallMode) -> Check {
+    const NAME: &str = "apply_task importable";
+    match run(python, &["-c", IMPORT_PROBE], PROCESS_TIMEOUT).await {
+        Ok(module) => Check::ok(NAME, module),
+        // A checkout runs with PYTHONPATH, no installation needed
+        Err(_) if mode != InstallMode::Package && mode != InstallMode::Module => {
+            Check::ok(NAME, "not installed (running from a checkout)".to_string())
+        }
+        Err(e) => Check::fail(
+            NAME,
+            e.to_string(),
+            "Install apply_task for this interpreter (pip install -e <checkout>) or set APPLY_TASK_PROJECT_ROOT",
+        ),
+    }
+}
+
+/// The probes whose checks passed, again with the isolated environment
+/// (none without `isolated_env`)
+async fn isolation_check(python: &str, env: &ChildEnv, passed: &[&Check]) -> Option<Check> {
+    const NAME: &str = "isolated environment";
+    if env.mode != EnvMode::Isolated {
+        return None;
+    }
+    let suggestion = "Add the variables the interpreter needs (PYENV_ROOT, CONDA_PREFIX, VIRTUAL_ENV, ...) to isolated_env_allowlist";
+    for check in passed {
+        let args: &[&str] = match check.check.as_str() {
+            "interpreter" => &["--version"],
+            "apply_task importable" if check.detail.starts_with("not installed") => continue,
+            "apply_task importable" => &["-c", IMPORT_PROBE],
+            _ => continue,
+        };
+        let mut cmd = tokio::process::Command::new(python);
+        cmd.args(args)
+            .current_dir(std::env::temp_dir())
+            .env_clear()
+            .envs(env.vars());
+        if let Err(e) = output(cmd, PROCESS_TIMEOUT).await {
+            return Some(Check::warn(
+                NAME,
+                format!("{} fails only when isolated: {}", check.check, e),
+                suggestion,
+            ));
+        }
+    }
+    Some(Check::ok(
+        NAME,
+        format!("{} variables passed", env.vars().len()),
+    ))
+}
+
+/// The container CLI reaches its daemon and the image is there
+async fn container_check(config: &ContainerConfig) -> Check {
+    const NAME: &str = "container";
+    let program = config.program();
+    let image = config.image.trim();
+    let server = run(
+        program,
+        &["version", "--format", "{{.Server.Version}}"],
+        PROCESS_TIMEOUT,
+    )
+    .await;
+    let server = match server {
+        Ok(server) => server,
+        Err(e) => {
+            return Check::fail(
+                NAME,
+                format!("{} can't reach its daemon: {}", program, e),
+                "Start the Docker daemon or Docker Desktop, or clear the container setting",
+            )
+        }
+    };
+    let inspect = run(
+        program,
+        &["image", "inspect", "--format", "{{.Id}}", image],
+        PROCESS_TIMEOUT,
+    )
+    .await;
+    match inspect {
+        Ok(_) => Check::ok(NAME, format!("{} {}, image {}", program, server, image)),
+        Err(_) => Check::warn(
+            NAME,
+            format!("{} {}; image {} is not local", program, server, image),
+            "It is pulled on the first start if a registry has it; otherwise build it (docker build -t <image> .)",
+        ),
+    }
+}
+
+fn entry_point_check(bridge: &PythonBridge) -> Check {
+    const NAME: &str = "entry point";
+    let launch = bridge.launch();
+    let detail = format!(
+        "{:?}: {} {}",
+        launch.mode,
+        launch.program,
+        launch.args.join(" ")
+    );
+    match launch.mode {
+        InstallMode::Module => Check::warn(
+            NAME,
+            detail,
+            "No apply_task script or package found; set APPLY_TASK_PATH or install apply_task",
+        ),
+        _ => Check::ok(NAME, detail),
+    }
+}
+
+/// Whether the RSS warning and cap can see the backend
+fn resources_check(mode: InstallMode) -> Check {
+    const NAME: &str = "resources";
+    match backend_resources::unavailable(mode) {
+        Some(reason) => Check::warn(
+            NAME,
+            format!("{}; its memory and CPU aren't sampled", reason),
+            "backend_rss_warn_mb and backend_rss_cap_mb don't apply; limit the container (docker --memory) or watch the remote host",
+        ),
+        None => Check::ok(NAME, "Backend memory and CPU are sampled".to_string()),
+    }
+}
+
+fn detection_check(state: &AppState) -> Check {
+    const NAME: &str = "project root";
+    let report = &state.detection;
+    match report.strategy {
+        Some(strategy) => Check::ok(
+            NAME,
+            format!(
+                "{} ({:?}, {} locations checked)",
+                report.root.display(),
+                strategy,
+                report.probes.len()
+            ),
+        ),
+        None => Check::fail(
+            NAME,
+            format!(
+                "{}; using {}",
+                report.error.message.as_deref().unwrap_or("not found"),
+                report.root.display()
+            ),
+            "Set APPLY_TASK_PROJECT_ROOT to the apply_task checkout",
+        ),
+    }
+}
+
+/// `path` exists and a file can be created in it
+fn writable_check(path: &Path) -> Check {
+    const NAME: &str = "storage";
+    let suggestion = "Check permissions of the storage directory or switch the storage mode";
+    if path.as_os_str().is_empty() {
+        return Check::fail(
+            NAME,
+            "backend reported no storage path".to_string(),
+            suggestion,
+        );
+    }
+    if !path.is_dir() {
+        return Check::warn(
+            NAME,
+            format!("{} does not exist yet", path.display()),
+            "It is created with the first task; check the parent directory is writable",
+        );
+    }
+    let probe = path.join(format!(".apply-task-doctor-{}", std::process::id()));
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            Check::ok(NAME, format!("{} is writable", path.display()))
+        }
+        Err(e) => Check::fail(
+            NAME,
+            format!("{} is not writable: {}", path.display(), e),
+            suggestion,
+        ),
+    }
+}
+
+/// Handshake with a throwaway backend, then ask it for the storage path
+async fn backend_checks(bridge: &PythonBridge) -> Vec<Check> {
+    let probe = bridge.probe();
+    let started = Instant::now();
+    let handshake = tokio::time::timeout(HANDSHAKE_TIMEOUT, probe.connect()).await;
+    let elapsed = started.elapsed().as_millis();
+    let handshake = match handshake {
+        Ok(Ok(())) => Check::ok("mcp handshake", format!("{} ms", elapsed)),
+        Ok(Err(e)) => Check::fail(
+            "mcp handshake",
+            format!("{:#}", e),
+            "Run the entry point command in a terminal to see the backend error",
+        ),
+        Err(_) => Check::fail(
+            "mcp handshake",
+            format!("no answer within {} ms", HANDSHAKE_TIMEOUT.as_millis()),
+            "The backend starts but hangs; run the entry point command in a terminal",
+        ),
+    };
+
+    let storage = if handshake.status == CheckStatus::Ok {
+        let call = probe.call_tool("tasks_storage", json!({}));
+        match tokio::time::timeout(STORAGE_TIMEOUT, call).await {
+            Ok(Ok(raw)) => match backend::into_result(raw) {
+                Ok(result) => writable_check(Path::new(&StorageInfo::from_result(result).path)),
+                Err(e) => Check::fail("storage", e.to_string(), "Check the backend log"),
+            },
+            Ok(Err(e)) => Check::fail("storage", e.to_string(), "Check the backend log"),
+            Err(_) => Check::fail(
+                "storage",
+                format!(
+                    "tasks_storage gave no answer within {} ms",
+                    STORAGE_TIMEOUT.as_millis()
+                ),
+                "Check the backend log",
+            ),
+        }
+    } else {
+        Check::warn(
+            "storage",
+            "skipped: no backend".to_string(),
+            "Fix the handshake first",
+        )
+    };
+
+    if let Err(e) = probe.shutdown().await {
+        log::warn!("Failed to stop doctor backend: {}", e);
+    }
+    vec![handshake, storage]
+}
+
+/// Run every check (bounded by the per-check limits, about 10 s total)
+pub async fn run_checks(state: &AppState) -> DoctorReport {
+    let started = Instant::now();
+    let bridge = &state.bridge;
+    let python = bridge.python_path();
+    let mode = bridge.launch().mode;
+
+    let mut checks = match (bridge.ssh(), bridge.container()) {
+        // The handshake check covers the host, the login and the remote command
+        (Some(_), _) => Vec::new(),
+        (None, Some(config)) => vec![container_check(&config).await],
+        (None, None) => {
+            let (interpreter, import) =
+                tokio::join!(interpreter_check(&python), import_check(&python, mode));
+            let passed: Vec<&Check> = [&interpreter, &import]
+                .into_iter()
+                .filter(|c| c.status == CheckStatus::Ok)
+                .collect();
+            let isolation = isolation_check(&python, &bridge.child_env(), &passed).await;
+            let mut checks = vec![interpreter, import];
+            checks.extend(isolation);
+            checks
+        }
+    };
+    checks.extend([
+        entry_point_check(bridge),
+        resources_check(mode),
+        detection_check(state),
+    ]);
+    checks.extend(backend_checks(bridge).await);
+
+    DoctorReport {
+        success: checks.iter().all(|c| c.status != CheckStatus::Fail),
+        checks,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        log_path: logging::log_path().map(|p| p.to_string_lossy().into_owned()),
+        recent_log: logging::recent_lines(),
+        frontend_errors: state.frontend_log.errors(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::python::EnvPolicy;
+
+    #[test]
+    fn test_python_version_and_storage_checks() {
+        assert_eq!(python_version("Python 3.12.1"), Some((3, 12)));
+        assert_eq!(python_version("Python 3.9.18"), Some((3, 9)));
+        assert!(python_version("Python 3.9.18").unwrap() < MIN_PYTHON);
+        assert_eq!(python_version("command not found"), None);
+
+        let dir = std::env::temp_dir().join(format!("apply-task-doctor-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(writable_check(Path::new("")).status, CheckStatus::Fail);
+        assert_eq!(writable_check(&dir).status, CheckStatus::Warn);
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(writable_check(&dir).status, CheckStatus::Ok);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_run_times_out_and_reports_failures() {
+        let err = run("sh", &["-c", "sleep 5"], Duration::from_millis(100))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no answer"));
+        assert!(run("sh", &["-c", "exit 3"], PROCESS_TIMEOUT).await.is_err());
+        assert_eq!(
+            run("sh", &["-c", "echo Python 3.11.2"], PROCESS_TIMEOUT)
+                .await
+                .unwrap(),
+            "Python 3.11.2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_isolation_check_warns_about_missing_variables() {
+        let dir = std::env::temp_dir().join(format!("apply-task-isolated-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        // An interpreter that needs PYENV_VERSION to start
+        let python = dir.join("python3");
+        std::fs::write(
+            &python,
+            "#!/bin/sh\n[ -n \"$PYENV_VERSION\" ] || exit 1\necho Python 3.12.1\n",
+        )
+        .unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&python, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let python = python.to_string_lossy().into_owned();
+        let parent = || [("PYENV_VERSION".to_string(), "3.12.1".to_string())];
+        let passed = Check::ok("interpreter", "Python 3.12.1".into());
+
+        let inherited = EnvPolicy::default().build(parent(), &[]);
+        assert!(isolation_check(&python, &inherited, &[&passed])
+            .await
+            .is_none());
+        let mut policy = EnvPolicy {
+            isolated: true,
+            allowlist: vec![],
+        };
+        let check = isolation_check(&python, &policy.build(parent(), &[]), &[&passed])
+            .await
+            .unwrap();
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(check.detail.contains("fails only when isolated"));
+        policy.allowlist.push("PYENV_VERSION".into());
+        let check = isolation_check(&python, &policy.build(parent(), &[]), &[&passed])
+            .await
+            .unwrap();
+        assert_eq!(check.status, CheckStatus::Ok);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,185 @@
+//! Task due dates
+//!
+//! The backend has no due-date field in most versions, so dates are kept in
+//! a per-project sidecar map and merged with the live task list.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::backend::task_str;
+
+/// File name of the due-date map inside the project sidecar dir
+pub const DUE_DATES_FILE: &str = "due_dates.json";
+
+/// task id -> due date
+pub type DueDates = BTreeMap<String, NaiveDate>;
+
+/// Which tasks `tasks_due` returns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DueFilter {
+    /// Due before today and not done
+    Overdue,
+    /// Due today
+    Today,
+    /// Due within the next 7 days (today included)
+    Week,
+}
+
+/// Task with its resolved due date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DueTask {
+    pub task_id: String,
+    pub title: String,
+    pub status: String,
+    pub due: NaiveDate,
+    /// `backend` or `sidecar`
+    pub source: String,
+}
+
+/// Parse a due date: `YYYY-MM-DD` or RFC3339 (date part kept)
+pub fn parse_due(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Some(date);
+    }
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|ts| ts.date_naive())
+}
+
+fn is_done(task: &Value) -> bool {
+    let status = task_str(task, "status_code")
+        .or_else(|| task_str(task, "status"))
+        .unwrap_or("");
+    status.eq_ignore_ascii_case("DONE")
+}
+
+/// Due date reported by the backend itself, if any
+fn backend_due(task: &Value) -> Option<NaiveDate> {
+    ["due", "due_date"]
+        .iter()
+        .find_map(|key| task_str(task, key))
+        .and_then(parse_due)
+}
+
+/// Resolve due dates for every task (backend value wins, sidecar fills in)
+pub fn resolve(tasks: &[Value], sidecar: &DueDates) -> Vec<(DueTask, bool)> {
+    tasks
+        .iter()
+        .filter_map(|task| {
+            let id = task_str(task, "id")?;
+            let (due, source) = match backend_due(task) {
+                Some(due) => (due, "backend"),
+                None => (*sidecar.get(id)?, "sidecar"),
+            };
+            Some((
+                DueTask {
+                    task_id: id.to_string(),
+                    title: task_str(task, "title").unwrap_or("").to_string(),
+                    status: task_str(task, "status_code")
+                        .or_else(|| task_str(task, "status"))
+                        .unwrap_or("")
+                        .to_string(),
+                    due,
+                    source: source.to_string(),
+                },
+                is_done(task),
+            ))
+        })
+        .collect()
+}
+
+/// Tasks matching `filter`, sorted by due date then id
+pub fn select(
+    tasks: &[Value],
+    sidecar: &DueDates,
+    filter: DueFilter,
+    today: NaiveDate,
+) -> Vec<DueTask> {
+    let week_end = today + Duration::days(7);
+    let mut matching: Vec<DueTask> = resolve(tasks, sidecar)
+        .into_iter()
+        .filter(|(task, done)| match filter {
+            DueFilter::Overdue => !done && task.due < today,
+            DueFilter::Today => task.due == today,
+            DueFilter::Week => task.due >= today && task.due < week_end,
+        })
+        .map(|(task, _)| task)
+        .collect();
+
+    matching.sort_by(|a, b| a.due.cmp(&b.due).then_with(|| a.task_id.cmp(&b.task_id)));
+    matching
+}
+
+/// Number of overdue, not-done tasks
+pub fn overdue_count(tasks: &[Value], sidecar: &DueDates, today: NaiveDate) -> usize {
+    select(tasks, sidecar, DueFilter::Overdue, today).len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn fixture() -> (Vec<Value>, DueDates) {
+        let tasks = vec![
+            json!({"id": "T-1", "title": "Late", "status_code": "ACTIVE"}),
+            json!({"id": "T-2", "title": "Late but done", "status_code": "DONE"}),
+            json!({"id": "T-3", "title": "Today", "status_code": "TODO"}),
+            json!({"id": "T-4", "title": "Backend due", "status_code": "TODO", "due": "2025-03-12"}),
+            json!({"id": "T-5", "title": "No due", "status_code": "TODO"}),
+        ];
+        let mut dates = DueDates::new();
+        dates.insert("T-1".into(), date("2025-03-01"));
+        dates.insert("T-2".into(), date("2025-03-02"));
+        dates.insert("T-3".into(), date("2025-03-10"));
+        dates.insert("T-4".into(), date("2030-01-01"));
+        dates.insert("T-gone".into(), date("2025-03-01"));
+        (tasks, dates)
+    }
+
+    #[test]
+    fn test_parse_due_formats() {
+        assert_eq!(parse_due("2025-03-01"), Some(date("2025-03-01")));
+        assert_eq!(
+            parse_due("2025-03-01T23:00:00+02:00"),
+            Some(date("2025-03-01"))
+        );
+        assert_eq!(parse_due("tomorrow"), None);
+    }
+
+    #[test]
+    fn test_select_filters_and_sorts() {
+        let (tasks, dates) = fixture();
+        let today = date("2025-03-10");
+
+        let overdue = select(&tasks, &dates, DueFilter::Overdue, today);
+        assert_eq!(
+            overdue
+                .iter()
+                .map(|t| t.task_id.as_str())
+                .collect::<Vec<_>>(),
+            ["T-1"]
+        );
+
+        let today_tasks = select(&tasks, &dates, DueFilter::Today, today);
+        assert_eq!(today_tasks[0].task_id, "T-3");
+
+        let week = select(&tasks, &dates, DueFilter::Week, today);
+        assert_eq!(
+            week.iter().map(|t| t.task_id.as_str()).collect::<Vec<_>>(),
+            ["T-3", "T-4"]
+        );
+        assert_eq!(week[1].source, "backend");
+
+        assert_eq!(overdue_count(&tasks, &dates, today), 1);
+    }
+}
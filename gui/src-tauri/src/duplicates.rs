@@ -0,0 +1,182 @@
+//! Duplicate task titles
+//!
+//! Titles are normalized (lowercase, punctuation and filler words dropped)
+//! and compared by the Dice coefficient of their word trigrams, padded like
+//! `pg_trgm` does: word order and small spelling changes barely move the
+//! score, a different key word does. [`DEFAULT_THRESHOLD`] was picked from
+//! the examples in the tests.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::backend::task_str;
+use crate::board;
+
+/// Scores from here on count as a possible duplicate
+pub const DEFAULT_THRESHOLD: f64 = 0.75;
+/// Most matches reported
+pub const MAX_MATCHES: usize = 5;
+/// Words that don't tell tasks apart
+const FILLER_WORDS: [&str; 10] = ["a", "an", "the", "to", "of", "for", "in", "on", "and", "is"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimilarTask {
+    pub id: String,
+    pub title: String,
+    pub status: String,
+    /// 0..=1, rounded to 3 decimals
+    pub score: f64,
+}
+
+/// Lowercase words of `title` without punctuation and filler words
+pub fn normalize(title: &str) -> String {
+    let title: String = title
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    title
+        .split_whitespace()
+        .filter(|word| !FILLER_WORDS.contains(word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Trigrams of each word of a normalized title, padded with two spaces in
+/// front and one behind
+fn trigrams(normalized: &str) -> HashSet<[char; 3]> {
+    let mut out = HashSet::new();
+    for word in normalized.split(' ').filter(|w| !w.is_empty()) {
+        let padded: Vec<char> = "  ".chars().chain(word.chars()).chain([' ']).collect();
+        out.extend(padded.windows(3).map(|w| [w[0], w[1], w[2]]));
+    }
+    out
+}
+
+fn dice(a: &HashSet<[char; 3]>, b: &HashSet<[char; 3]>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    2.0 * a.intersection(b).count() as f64 / (a.len() + b.len()) as f64
+}
+
+/// Tasks whose title scores at least `threshold` against `title`, best
+/// first, at most [`MAX_MATCHES`]
+pub fn find_similar(title: &str, tasks: &[Value], threshold: f64) -> Vec<SimilarTask> {
+    let wanted = trigrams(&normalize(title));
+    let mut matches: Vec<SimilarTask> = tasks
+        .iter()
+        .filter_map(|task| {
+            let id = task_str(task, "id")?;
+            let other = task_str(task, "title")?;
+            let score = dice(&wanted, &trigrams(&normalize(other)));
+            (score >= threshold).then(|| SimilarTask {
+                id: id.to_string(),
+                title: other.to_string(),
+                status: board::status_of(task),
+                score: (score * 1000.0).round() / 1000.0,
+            })
+        })
+        .collect();
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+    matches.truncate(MAX_MATCHES);
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn similarity(a: &str, b: &str) -> f64 {
+        dice(&trigrams(&normalize(a)), &trigrams(&normalize(b)))
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize("  Fix the flaky login-test!! "),
+            "fix flaky login test"
+        );
+        assert_eq!(normalize("Écrire la DOC"), "écrire la doc");
+        assert_eq!(normalize("?!"), "");
+    }
+
+    #[test]
+    fn test_threshold_separates_examples() {
+        let duplicates = [
+            ("Fix flaky login test", "fix flaky login tests"),
+            ("Fix flaky login test", "Fix the flaky login test"),
+            ("Fix flaky login test", "Flaky login test fix"),
+            ("Fix flaky login test", "Fix flaky login-test!"),
+            ("Fix flaky login test", "Fix flakey login test"),
+            ("Fix flaky login test", "Login test is flaky"),
+            ("Update README", "Update the README"),
+            ("Add dark mode", "Add dark mode toggle"),
+            (
+                "Migrate database to Postgres 16",
+                "Migrate DB to Postgres 16",
+            ),
+        ];
+        for (a, b) in duplicates {
+            let score = similarity(a, b);
+            assert!(score >= DEFAULT_THRESHOLD, "{:?} vs {:?}: {}", a, b, score);
+        }
+        let distinct = [
+            ("Fix flaky login test", "Fix flaky signup test"),
+            ("Fix flaky login test", "Add login test"),
+            ("Fix flaky login test", "Fix flaky login page styling"),
+            ("Fix flaky login test", "Write docs for login"),
+            ("Fix flaky login test", "Fix login"),
+            ("Update README", "Update CHANGELOG"),
+            ("Add dark mode", "Remove dark mode"),
+            ("Export to CSV", "Export to PDF"),
+            ("The", "A"),
+        ];
+        for (a, b) in distinct {
+            let score = similarity(a, b);
+            assert!(score < DEFAULT_THRESHOLD, "{:?} vs {:?}: {}", a, b, score);
+        }
+        assert_eq!(
+            similarity("Fix flaky login test", "flaky: fix login TEST"),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_find_similar_ranks_and_caps() {
+        let mut tasks = vec![
+            json!({ "id": "TASK-001", "title": "Fix flaky login tests", "status": "DONE" }),
+            json!({ "id": "TASK-002", "title": "Fix flaky signup test" }),
+            json!({ "id": "TASK-003", "title": "fix flaky login test", "status_code": "ACTIVE" }),
+            json!({ "id": "TASK-004" }),
+        ];
+        let found = find_similar("Fix flaky login test", &tasks, DEFAULT_THRESHOLD);
+        assert_eq!(
+            found,
+            [
+                SimilarTask {
+                    id: "TASK-003".into(),
+                    title: "fix flaky login test".into(),
+                    status: "ACTIVE".into(),
+                    score: 1.0,
+                },
+                SimilarTask {
+                    id: "TASK-001".into(),
+                    title: "Fix flaky login tests".into(),
+                    status: "DONE".into(),
+                    score: 0.927,
+                },
+            ]
+        );
+
+        tasks = (0..10)
+            .map(|i| json!({ "id": format!("TASK-{:03}", i), "title": "Same title" }))
+            .collect();
+        let capped = find_similar("same title", &tasks, DEFAULT_THRESHOLD);
+        assert_eq!(capped.len(), MAX_MATCHES);
+        assert_eq!(capped[0].id, "TASK-000");
+    }
+}
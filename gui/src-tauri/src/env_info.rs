@@ -0,0 +1,452 @@
+//! Effective bridge configuration and where each value came from
+//!
+//! Values come from the environment, the command line, `settings.json`,
+//! auto-detection or built-in defaults; `env_info` reports the winner for
+//! each one. Paths below the home directory are shown with `~`, and values
+//! whose key looks like a secret are replaced by [`REDACTED`] (in free text
+//! too, for `KEY=value` and `"key": "value"` pairs; see [`redact_secrets`]).
+
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::crash;
+use crate::detection::Strategy;
+use crate::python::{EnvMode, Framing, InstallMode};
+use crate::AppState;
+
+/// Shown instead of a secret-looking value
+pub const REDACTED: &str = "[redacted]";
+/// Key fragments that mark a value as secret (compared case-insensitively)
+const SECRET_PATTERNS: [&str; 7] = [
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "KEY",
+    "AUTH",
+    "CREDENTIAL",
+];
+/// Environment variables listed besides the effective settings
+const ENV_PREFIX: &str = "APPLY_TASK_";
+const PYTHON_ENV: [&str; 2] = ["PYTHON_PATH", "PYTHONPATH"];
+
+/// Where an effective value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    Env(&'static str),
+    /// `--project` or a directory argument
+    Cli,
+    Settings,
+    Detected,
+    /// Chosen in the GUI (project switcher, storage mode)
+    Selected,
+    Default,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Env(name) => write!(f, "env:{}", name),
+            Self::Cli => f.write_str("cli"),
+            Self::Settings => f.write_str("settings"),
+            Self::Detected => f.write_str("detected"),
+            Self::Selected => f.write_str("selected"),
+            Self::Default => f.write_str("default"),
+        }
+    }
+}
+
+/// One row of the table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: Option<String>,
+    /// `env:<NAME>`, `cli`, `settings`, `detected`, `selected` or `default`
+    pub source: String,
+}
+
+/// What `env_info` returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvInfo {
+    pub settings: Vec<ConfigEntry>,
+    /// `APPLY_TASK_*` and Python variables as the GUI sees them
+    pub environment: Vec<ConfigEntry>,
+    /// What the backend was started with
+    pub backend_env: BackendEnv,
+}
+
+/// Backend environment mode and the variables passed (names only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendEnv {
+    /// `inherit` (the whole GUI environment) or `isolated`
+    pub mode: EnvMode,
+    pub variables: Vec<String>,
+}
+
+fn is_secret(key: &str) -> bool {
+    let key = key.to_uppercase();
+    SECRET_PATTERNS.iter().any(|p| key.contains(p))
+}
+
+/// `value` of `key` redacted, or with the home directory as `~`
+fn shown(key: &str, value: &str, home: Option<&Path>) -> String {
+    match is_secret(key) {
+        true => REDACTED.to_string(),
+        false => crash::redact_home(value, home),
+    }
+}
+
+/// `value` with secret-looking fields replaced by [`REDACTED`] and the home
+/// directory shown as `~` in every string
+///
+/// A `{key, value}` row (as in this report) is judged by its `key`; the
+/// field names `key` and `keys` are names, not secrets.
+pub(crate) fn redact_value(value: &mut Value, home: Option<&Path>) {
+    match value {
+        Value::Object(obj) => {
+            let row_is_secret = obj
+                .get("key")
+                .and_then(|k| k.as_str())
+                .is_some_and(is_secret);
+            for (name, field) in obj.iter_mut() {
+                let secret = match name.as_str() {
+                    "key" | "keys" => false,
+                    "value" => row_is_secret,
+                    _ => is_secret(name),
+                };
+                match secret && !field.is_null() {
+                    true => *field = Value::String(REDACTED.to_string()),
+                    false => redact_value(field, home),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| redact_value(item, home)),
+        Value::String(text) => *text = crash::redact_home(&redact_secrets(text), home),
+        _ => {}
+    }
+}
+
+fn is_name_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'.')
+}
+
+/// Value span after the `=` at `eq`, when the name before it is secret
+fn assignment_value(text: &str, eq: usize) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let start = bytes[..eq]
+        .iter()
+        .rposition(|b| !is_name_byte(*b))
+        .map_or(0, |p| p + 1);
+    if start == eq || !is_secret(&text[start..eq]) {
+        return None;
+    }
+    let value = eq + 1;
+    let end = bytes[value..]
+        .iter()
+        .position(|b| {
+            b.is_ascii_whitespace()
+                || matches!(b, b'"' | b'\'' | b',' | b';' | b'&' | b')' | b']' | b'}')
+        })
+        .map_or(bytes.len(), |p| value + p);
+    (end > value).then_some((value, end))
+}
+
+/// Span of the string value of the `"name": "value"` pair opening at
+/// `quote`, when the name is secret
+fn json_string_value(text: &str, quote: usize) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let name_end = quote + 1 + bytes[quote + 1..].iter().position(|b| *b == b'"')?;
+    let name = &text[quote + 1..name_end];
+    if matches!(name, "key" | "keys") || !is_secret(name) {
+        return None;
+    }
+    let after_colon = text[name_end + 1..]
+        .trim_start()
+        .strip_prefix(':')?
+        .trim_start();
+    let value = text.len() - after_colon.len();
+    if bytes.get(value) != Some(&b'"') {
+        return None;
+    }
+    let mut at = value + 1;
+    while at < bytes.len() {
+        match bytes[at] {
+            b'\\' => at += 2,
+            b'"' => return Some((value + 1, at)),
+            _ => at += 1,
+        }
+    }
+    None
+}
+
+/// `text` (log lines, command lines) with the values of secret-looking
+/// `KEY=value` and `"key": "value"` pairs replaced by [`REDACTED`]
+pub(crate) fn redact_secrets(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut at = 0;
+    while at < text.len() {
+        let secret = match text.as_bytes()[at] {
+            b'=' => assignment_value(text, at),
+            b'"' => json_string_value(text, at),
+            _ => None,
+        };
+        match secret {
+            Some((start, end)) => {
+                out.push_str(&text[copied..start]);
+                out.push_str(REDACTED);
+                copied = end;
+                at = end;
+            }
+            None => at += 1,
+        }
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+fn entry(
+    key: &str,
+    value: Option<String>,
+    source: ConfigSource,
+    home: Option<&Path>,
+) -> ConfigEntry {
+    ConfigEntry {
+        key: key.to_string(),
+        value: value.map(|v| shown(key, &v, home)),
+        source: source.to_string(),
+    }
+}
+
+/// Source of the interpreter: `python_path` setting, then the variables
+/// `default_python_path` reads
+fn python_source(setting: Option<&str>, env: impl Fn(&str) -> bool) -> ConfigSource {
+    if setting.is_some_and(|p| !p.trim().is_empty()) {
+        return ConfigSource::Settings;
+    }
+    ["PYTHON_PATH", "APPLY_TASK_PYTHON"]
+        .into_iter()
+        .find(|name| env(name))
+        .map_or(ConfigSource::Default, ConfigSource::Env)
+}
+
+fn root_source(strategy: Option<Strategy>) -> ConfigSource {
+    match strategy {
+        Some(Strategy::EnvVar) => ConfigSource::Env("APPLY_TASK_PROJECT_ROOT"),
+        Some(Strategy::Remote) => ConfigSource::Settings,
+        Some(_) => ConfigSource::Detected,
+        None => ConfigSource::Default,
+    }
+}
+
+fn entry_point_source(mode: InstallMode) -> ConfigSource {
+    match mode {
+        InstallMode::EnvPath => ConfigSource::Env("APPLY_TASK_PATH"),
+        InstallMode::Container | InstallMode::Ssh => ConfigSource::Settings,
+        // Nothing found; `python -m apply_task` is the fallback
+        InstallMode::Module => ConfigSource::Default,
+        _ => ConfigSource::Detected,
+    }
+}
+
+fn environment(
+    vars: impl Iterator<Item = (String, String)>,
+    home: Option<&Path>,
+) -> Vec<ConfigEntry> {
+    let mut out: Vec<ConfigEntry> = vars
+        .filter(|(name, _)| name.starts_with(ENV_PREFIX) || PYTHON_ENV.contains(&name.as_str()))
+        .map(|(name, value)| ConfigEntry {
+            value: Some(shown(&name, &value, home)),
+            source: format!("env:{}", name),
+            key: name,
+        })
+        .collect();
+    out.sort_by(|a, b| a.key.cmp(&b.key));
+    out
+}
+
+/// Effective project root, user cwd, interpreter, entry point, namespace,
+/// storage mode and transport (asks the backend for the namespace)
+pub async fn collect(state: &AppState) -> EnvInfo {
+    let home = crash::home_dir();
+    let home = home.as_deref();
+    let bridge = &state.bridge;
+    let launch = bridge.launch();
+    let setting = state.settings.read().await.python_path.clone();
+    let storage_mode = bridge.storage_mode_str();
+    let user_cwd_source = *state
+        .user_cwd_source
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let path = |p: &Path| Some(p.to_string_lossy().into_owned());
+
+    let settings = vec![
+        entry(
+            "project_root",
+            path(&state.apply_task_root),
+            root_source(state.detection.strategy),
+            home,
+        ),
+        entry("user_cwd", path(&state.user_cwd()), user_cwd_source, home),
+        entry(
+            "python_path",
+            Some(bridge.python_path()),
+            python_source(setting.as_deref(), |name| std::env::var_os(name).is_some()),
+            home,
+        ),
+        entry(
+            "entry_point",
+            Some(format!("{} {}", launch.program, launch.args.join(" "))),
+            entry_point_source(launch.mode),
+            home,
+        ),
+        entry(
+            "namespace",
+            crate::deep_link::current_namespace(state).await,
+            ConfigSource::Detected,
+            home,
+        ),
+        entry(
+            "storage_mode",
+            Some(storage_mode.to_string()),
+            match storage_mode {
+                "local" => ConfigSource::Selected,
+                _ => ConfigSource::Default,
+            },
+            home,
+        ),
+        entry(
+            "transport",
+            Some(format!("stdio ({})", bridge.framing())),
+            match bridge.framing() {
+                Framing::Auto => ConfigSource::Default,
+                _ => ConfigSource::Settings,
+            },
+            home,
+        ),
+    ];
+
+    let child_env = bridge.child_env();
+    EnvInfo {
+        settings,
+        environment: environment(std::env::vars(), home),
+        backend_env: BackendEnv {
+            mode: child_env.mode,
+            variables: child_env.names(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sources() {
+        assert_eq!(
+            python_source(Some("/opt/py"), |_| true),
+            ConfigSource::Settings
+        );
+        assert_eq!(
+            python_source(Some(" "), |name| name == "APPLY_TASK_PYTHON").to_string(),
+            "env:APPLY_TASK_PYTHON"
+        );
+        assert_eq!(
+            python_source(None, |_| true),
+            ConfigSource::Env("PYTHON_PATH")
+        );
+        assert_eq!(python_source(None, |_| false), ConfigSource::Default);
+
+        assert_eq!(
+            root_source(Some(Strategy::EnvVar)).to_string(),
+            "env:APPLY_TASK_PROJECT_ROOT"
+        );
+        assert_eq!(
+            root_source(Some(Strategy::CwdAncestor)),
+            ConfigSource::Detected
+        );
+        assert_eq!(root_source(None), ConfigSource::Default);
+        assert_eq!(
+            entry_point_source(InstallMode::EnvPath).to_string(),
+            "env:APPLY_TASK_PATH"
+        );
+    }
+
+    #[test]
+    fn test_home_and_secrets_are_hidden() {
+        let home = Some(Path::new("/home/ada"));
+        let row = entry(
+            "user_cwd",
+            Some("/home/ada/work".into()),
+            ConfigSource::Cli,
+            home,
+        );
+        assert_eq!(row.value.as_deref(), Some("~/work"));
+        assert_eq!(row.source, "cli");
+
+        let vars = [
+            ("APPLY_TASK_API_TOKEN", "abc123"),
+            ("APPLY_TASK_PATH", "/home/ada/bin/apply_task"),
+            ("PYTHON_PATH", "/usr/bin/python3"),
+            ("AWS_SECRET_ACCESS_KEY", "nope"),
+            ("HOME", "/home/ada"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let env = environment(vars.into_iter(), home);
+        let keys: Vec<&str> = env.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            ["APPLY_TASK_API_TOKEN", "APPLY_TASK_PATH", "PYTHON_PATH"]
+        );
+        assert_eq!(env[0].value.as_deref(), Some(REDACTED));
+        assert_eq!(env[1].value.as_deref(), Some("~/bin/apply_task"));
+        assert_eq!(env[1].source, "env:APPLY_TASK_PATH");
+    }
+
+    #[test]
+    fn test_redact_value() {
+        let mut value = serde_json::json!({
+            "settings": [
+                { "key": "APPLY_TASK_API_TOKEN", "value": "abc" },
+                { "key": "user_cwd", "value": "/home/ada/work" },
+            ],
+            "auth_header": "Bearer x",
+            "keys": ["a"],
+            "password": null,
+            "log": ["opened /home/ada/notes"],
+        });
+        redact_value(&mut value, Some(Path::new("/home/ada")));
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "settings": [
+                    { "key": "APPLY_TASK_API_TOKEN", "value": REDACTED },
+                    { "key": "user_cwd", "value": "~/work" },
+                ],
+                "auth_header": REDACTED,
+                "keys": ["a"],
+                "password": null,
+                "log": ["opened ~/notes"],
+            })
+        );
+    }
+
+    #[test]
+    fn test_redact_secrets_in_text() {
+        let line = r#"Running: docker ["run", "-e", "GITHUB_TOKEN=ghp_abc123", "--api-key=k1", "-e", "TZ=UTC"]"#;
+        assert_eq!(
+            redact_secrets(line),
+            r#"Running: docker ["run", "-e", "GITHUB_TOKEN=[redacted]", "--api-key=[redacted]", "-e", "TZ=UTC"]"#
+        );
+        let json = r#"stderr: {"api_token": "s3\"cret", "key": "PATH", "title": "token=x"}"#;
+        assert_eq!(
+            redact_secrets(json),
+            r#"stderr: {"api_token": "[redacted]", "key": "PATH", "title": "token=[redacted]"}"#
+        );
+        assert_eq!(redact_secrets("PASSWORD= none"), "PASSWORD= none");
+        assert_eq!(redact_secrets("é AUTH=ü"), "é AUTH=[redacted]");
+    }
+}
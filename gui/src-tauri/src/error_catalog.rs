@@ -0,0 +1,1413 @@
+//! Error catalog
+//!
+//! Every failure shown to the user has a stable [`ErrorCode`] and a params
+//! map; command responses carry both next to `error`, the English text
+//! rendered from the code's template. Templates live in [`template`] only,
+//! so a translation can later be swapped in there (or in the frontend,
+//! keyed by `error_code`) without touching the commands. Errors nobody
+//! classified yet are `INTERNAL` with their text as `message`. `ai_intent`
+//! keeps the backend's own `AIResponse` error object (`{code, message}`).
+//!
+//! A JSON-RPC error's `data` is passed on as `error_detail`. When it lists
+//! failed fields (`{field, reason}`, alone, in a list or under `errors`),
+//! the error becomes `VALIDATION_FIELDS` with those fields as params.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::backend::BackendError;
+use crate::board::BoardError;
+use crate::checklist::ChecklistError;
+use crate::detection::DetectionError;
+use crate::evidence::{EvidenceError, SubtaskNotFound};
+use crate::intents::AliasError;
+use crate::logging::FrontendLogError;
+use crate::python::{BridgeError, FaultError, ToolCallError};
+use crate::quick_parse::QuickParseError;
+use crate::rate_limit::RateLimitError;
+use crate::read_only::ReadOnlyError;
+use crate::session::SessionPatchError;
+use crate::settings::SettingsError;
+use crate::signals::SignalError;
+use crate::suggestions::SuggestionError;
+use crate::support_bundle::BundleError;
+use crate::templates::TemplateError;
+use crate::todos::TodoError;
+use crate::verification::VerificationError;
+
+/// Stable identifier of a failure (`BRIDGE_TIMEOUT`, `TASK_NOT_FOUND`, ...)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    BridgeSpawn,
+    BridgeDisconnected,
+    BridgeTimeout,
+    BridgeResponseTooLarge,
+    BridgeResourceLimit,
+    BridgeContainerDaemon,
+    BridgeContainerImage,
+    BridgeSshHostKey,
+    BridgeSshAuth,
+    BridgeSshConnect,
+    /// Failure reported by a backend tool (`backend_code` is its own code)
+    Backend,
+    /// The backend has no such tool (likely older than the GUI)
+    BackendToolMissing,
+    TaskNotFound,
+    TaskFileNotFound,
+    SubtaskNotFound,
+    JobNotFound,
+    AiSignalNotFound,
+    TimerNotRunning,
+    ProjectNotFound,
+    ProjectNotRegistered,
+    ProjectNamespaceUnknown,
+    ProjectNamespaceMismatch,
+    ProjectNamespaceNotFound,
+    RootNotFound,
+    StorageMissing,
+    DeleteFailed,
+    DeleteHasChildren,
+    ConfirmTokenInvalid,
+    /// A destructive tool called as an intent, bypassing `tasks_delete`'s
+    /// confirmation
+    ConfirmationRequired,
+    /// Another change became the latest undoable one after the preview
+    UndoTargetChanged,
+    /// The task changed since the caller loaded it (`expected_revision`)
+    RevisionConflict,
+    /// The `read_only` setting refused a mutation
+    ReadOnlyMode,
+    /// Over a tool's `rate_limits_per_min` (`retry_after_ms` says when to
+    /// try again)
+    RateLimited,
+    PathOutsideStorage,
+    OpenFailed,
+    ClipboardFailed,
+    UpdateCheckFailed,
+    UnknownIntent,
+    ShortcutInvalid,
+    ShortcutUnavailable,
+    SavedShortcutFailed,
+    SavedRestartFailed,
+    /// Fault injection asked for without developer mode
+    FaultInjectionDisabled,
+    SupportBundleTooLarge,
+    TemplateNotFound,
+    TemplateExists,
+    ValidationTitleEmpty,
+    ValidationMultipleDomains,
+    ValidationMultipleNamespaces,
+    ValidationLogLevel,
+    ValidationTaskIdRequired,
+    ValidationInvalidDate,
+    ValidationInvalidDueDate,
+    ValidationUnknownSignal,
+    ValidationAliasName,
+    ValidationAliasTool,
+    ValidationAliasDestructive,
+    ValidationSettingsNotObject,
+    ValidationSettingsPatch,
+    ValidationCacheTtl,
+    ValidationToolTimeout,
+    ValidationPythonPath,
+    ValidationContainerImage,
+    ValidationSsh,
+    ValidationSshHost,
+    ValidationMessageLimit,
+    ValidationRateLimit,
+    ValidationSessionPatch,
+    ValidationSuggestionAction,
+    ValidationFaultRule,
+    ValidationBoardStatus,
+    ValidationTemplateName,
+    ValidationTemplateSubtaskTitle,
+    ValidationTemplateCheckpoints,
+    ValidationTemplateDuplicateCheckpoint,
+    ValidationTemplateParent,
+    ValidationTemplateFile,
+    ValidationChecklistEmpty,
+    ValidationChecklistParent,
+    ValidationTodoRoot,
+    ValidationTodoGlob,
+    ValidationCommandEmpty,
+    ValidationCommandSyntax,
+    /// Not on the project's `verification_commands` allowlist
+    CommandNotAllowed,
+    CommandSpawnFailed,
+    ValidationEvidenceEmpty,
+    EvidenceFileNotFound,
+    EvidenceOutsideProject,
+    EvidenceCopyFailed,
+    /// The backend rejected fields (`fields` is a `{field, reason}` list)
+    ValidationFields,
+    Internal,
+}
+
+impl ErrorCode {
+    pub const ALL: &'static [ErrorCode] = &[
+        ErrorCode::BridgeSpawn,
+        ErrorCode::BridgeDisconnected,
+        ErrorCode::BridgeTimeout,
+        ErrorCode::BridgeResponseTooLarge,
+        ErrorCode::BridgeResourceLimit,
+        ErrorCode::BridgeContainerDaemon,
+        ErrorCode::BridgeContainerImage,
+        ErrorCode::BridgeSshHostKey,
+        ErrorCode::BridgeSshAuth,
+        ErrorCode::BridgeSshConnect,
+        ErrorCode::Backend,
+        ErrorCode::BackendToolMissing,
+        ErrorCode::TaskNotFound,
+        ErrorCode::TaskFileNotFound,
+        ErrorCode::SubtaskNotFound,
+        ErrorCode::JobNotFound,
+        ErrorCode::AiSignalNotFound,
+        ErrorCode::TimerNotRunning,
+        ErrorCode::ProjectNotFound,
+        ErrorCode::ProjectNotRegistered,
+        ErrorCode::ProjectNamespaceUnknown,
+        ErrorCode::ProjectNamespaceMismatch,
+        ErrorCode::ProjectNamespaceNotFound,
+        ErrorCode::RootNotFound,
+        ErrorCode::StorageMissing,
+        ErrorCode::DeleteFailed,
+        ErrorCode::DeleteHasChildren,
+        ErrorCode::ConfirmTokenInvalid,
+        ErrorCode::ConfirmationRequired,
+        ErrorCode::UndoTargetChanged,
+        ErrorCode::RevisionConflict,
+        ErrorCode::ReadOnlyMode,
+        ErrorCode::RateLimited,
+        ErrorCode::PathOutsideStorage,
+        ErrorCode::OpenFailed,
+        ErrorCode::ClipboardFailed,
+        ErrorCode::UpdateCheckFailed,
+        ErrorCode::UnknownIntent,
+        ErrorCode::ShortcutInvalid,
+        ErrorCode::ShortcutUnavailable,
+        ErrorCode::SavedShortcutFailed,
+        ErrorCode::SavedRestartFailed,
+        ErrorCode::FaultInjectionDisabled,
+        ErrorCode::SupportBundleTooLarge,
+        ErrorCode::TemplateNotFound,
+        ErrorCode::TemplateExists,
+        ErrorCode::ValidationTitleEmpty,
+        ErrorCode::ValidationMultipleDomains,
+        ErrorCode::ValidationMultipleNamespaces,
+        ErrorCode::ValidationLogLevel,
+        ErrorCode::ValidationTaskIdRequired,
+        ErrorCode::ValidationInvalidDate,
+        ErrorCode::ValidationInvalidDueDate,
+        ErrorCode::ValidationUnknownSignal,
+        ErrorCode::ValidationAliasName,
+        ErrorCode::ValidationAliasTool,
+        ErrorCode::ValidationAliasDestructive,
+        ErrorCode::ValidationSettingsNotObject,
+        ErrorCode::ValidationSettingsPatch,
+        ErrorCode::ValidationCacheTtl,
+        ErrorCode::ValidationToolTimeout,
+        ErrorCode::ValidationPythonPath,
+        ErrorCode::ValidationContainerImage,
+        ErrorCode::ValidationSsh,
+        ErrorCode::ValidationSshHost,
+        ErrorCode::ValidationMessageLimit,
+        ErrorCode::ValidationRateLimit,
+        ErrorCode::ValidationSessionPatch,
+        ErrorCode::ValidationSuggestionAction,
+        ErrorCode::ValidationFaultRule,
+        ErrorCode::ValidationBoardStatus,
+        ErrorCode::ValidationTemplateName,
+        ErrorCode::ValidationTemplateSubtaskTitle,
+        ErrorCode::ValidationTemplateCheckpoints,
+        ErrorCode::ValidationTemplateDuplicateCheckpoint,
+        ErrorCode::ValidationTemplateParent,
+        ErrorCode::ValidationTemplateFile,
+        ErrorCode::ValidationChecklistEmpty,
+        ErrorCode::ValidationChecklistParent,
+        ErrorCode::ValidationTodoRoot,
+        ErrorCode::ValidationTodoGlob,
+        ErrorCode::ValidationCommandEmpty,
+        ErrorCode::ValidationCommandSyntax,
+        ErrorCode::CommandNotAllowed,
+        ErrorCode::CommandSpawnFailed,
+        ErrorCode::ValidationEvidenceEmpty,
+        ErrorCode::EvidenceFileNotFound,
+        ErrorCode::EvidenceOutsideProject,
+        ErrorCode::EvidenceCopyFailed,
+        ErrorCode::ValidationFields,
+        ErrorCode::Internal,
+    ];
+}
+
+/// English text of `code`; `{name}` is replaced by the param `name`
+pub fn template(code: ErrorCode) -> &'static str {
+    match code {
+        ErrorCode::BridgeSpawn => "Failed to start Python backend: {detail}",
+        ErrorCode::BridgeDisconnected => "Python backend unavailable: {detail}",
+        ErrorCode::BridgeTimeout => "{tool} timed out after {waited_ms} ms",
+        ErrorCode::BridgeResponseTooLarge => {
+            "{tool} returned more than {limit} bytes; page through the results or ask for compact output"
+        }
+        ErrorCode::BridgeContainerDaemon => {
+            "Docker is not available ({detail}); start the Docker daemon or Docker Desktop, or clear the container setting to run the backend with Python"
+        }
+        ErrorCode::BridgeContainerImage => {
+            "Docker image {image} was not found; build it or pull it (docker pull {image}), or change container.image"
+        }
+        ErrorCode::BridgeSshHostKey => {
+            "The SSH host key of {host} is unknown or has changed; run ssh {host} once in a terminal to verify it, or fix ~/.ssh/known_hosts"
+        }
+        ErrorCode::BridgeSshAuth => {
+            "SSH login to {host} failed; load a key into ssh-agent or pass one in ssh.args (-i <key>)"
+        }
+        ErrorCode::BridgeSshConnect => "Could not connect to {host} over SSH: {detail}",
+        ErrorCode::BridgeResourceLimit => {
+            "{tool} was cut off: the backend used {rss_mb} MB, over its {cap_mb} MB memory cap, and was stopped; it restarts on the next request"
+        }
+        ErrorCode::Backend => "{message}",
+        ErrorCode::BackendToolMissing => {
+            "The backend has no {tool} tool; run check_backend_version for upgrade steps"
+        }
+        ErrorCode::TaskNotFound => "Task {task_id} not found",
+        ErrorCode::TaskFileNotFound => "Task file not found in storage",
+        ErrorCode::SubtaskNotFound => "Task {task_id} has no subtask at {path}",
+        ErrorCode::JobNotFound => "No running job {job_id}",
+        ErrorCode::AiSignalNotFound => "No pending AI signal {signal_id}",
+        ErrorCode::TimerNotRunning => "No running timer for {task_id}",
+        ErrorCode::ProjectNotFound => "{path} is not an apply_task project",
+        ErrorCode::ProjectNotRegistered => "Project not registered: {path}",
+        ErrorCode::ProjectNamespaceUnknown => "Could not determine the project namespace",
+        ErrorCode::ProjectNamespaceMismatch => {
+            "{namespace} is not the open project ({current})"
+        }
+        ErrorCode::ProjectNamespaceNotFound => "No namespace {namespace} in the task storage",
+        ErrorCode::RootNotFound => "apply_task root not found ({checked} locations checked)",
+        ErrorCode::StorageMissing => "Storage directory does not exist",
+        ErrorCode::DeleteFailed => "Failed to delete {task_id}: {reason}",
+        ErrorCode::DeleteHasChildren => "Task has children; pass cascade to delete them too",
+        ErrorCode::ConfirmTokenInvalid => {
+            "Confirmation token is invalid, expired or for a different delete; confirm again"
+        }
+        ErrorCode::ConfirmationRequired => {
+            "{tool} deletes data and needs confirmation; use the tasks_delete command"
+        }
+        ErrorCode::UndoTargetChanged => {
+            "The latest change is no longer the one previewed ({previewed}); preview again"
+        }
+        ErrorCode::RevisionConflict => {
+            "{task_id} changed since it was loaded (revision {expected}, now {current})"
+        }
+        ErrorCode::ReadOnlyMode => "Read-only mode is on; turn it off to make changes",
+        ErrorCode::RateLimited => "Too many {tool} calls; retry in {retry_after_ms} ms",
+        ErrorCode::PathOutsideStorage => "Path is outside the task storage",
+        ErrorCode::OpenFailed => "{detail}",
+        ErrorCode::ClipboardFailed => "{detail}",
+        ErrorCode::UpdateCheckFailed => "Could not check for updates: {detail}",
+        ErrorCode::UnknownIntent => "Unknown intent: {intent}",
+        ErrorCode::ShortcutInvalid => "Invalid shortcut '{shortcut}': {detail}",
+        ErrorCode::ShortcutUnavailable => {
+            "Cannot register {shortcut} (used by another app?): {detail}"
+        }
+        ErrorCode::SavedShortcutFailed => {
+            "Saved, but the shortcut could not be registered: {reason}"
+        }
+        ErrorCode::SavedRestartFailed => "Saved, but the backend restart failed: {reason}",
+        ErrorCode::FaultInjectionDisabled => "Fault injection needs developer_mode in settings",
+        ErrorCode::SupportBundleTooLarge => "Support bundle is over {max} bytes",
+        ErrorCode::TemplateNotFound => "No template named \"{name}\"",
+        ErrorCode::TemplateExists => "A template named \"{name}\" already exists",
+        ErrorCode::ValidationTitleEmpty => "The task needs a title",
+        ErrorCode::ValidationMultipleDomains => {
+            "Only one @domain per task (got @{first} and @{second})"
+        }
+        ErrorCode::ValidationMultipleNamespaces => {
+            "Only one ns: per task (got ns:{first} and ns:{second})"
+        }
+        ErrorCode::ValidationLogLevel => {
+            "Unknown log level '{level}' (use error, warn, info, debug or trace)"
+        }
+        ErrorCode::ValidationTaskIdRequired => "task_id is required",
+        ErrorCode::ValidationInvalidDate => "Invalid date: {value}",
+        ErrorCode::ValidationInvalidDueDate => "Invalid due date: {value}",
+        ErrorCode::ValidationUnknownSignal => "Unknown signal '{signal}'. Valid signals: {valid}",
+        ErrorCode::ValidationAliasName => "Invalid alias name: \"{name}\"",
+        ErrorCode::ValidationAliasTool => "Invalid tool for alias \"{name}\": \"{tool}\"",
+        ErrorCode::ValidationAliasDestructive => {
+            "Aliases to destructive tools need confirm_destructive: {aliases}"
+        }
+        ErrorCode::ValidationSettingsNotObject => "Settings patch must be an object",
+        ErrorCode::ValidationSettingsPatch => "Invalid settings patch",
+        ErrorCode::ValidationCacheTtl => "{setting} must be at most {max}",
+        ErrorCode::ValidationToolTimeout => {
+            "tool_timeout_max_ms.{tool} must be between {min} and {max}"
+        }
+        ErrorCode::ValidationPythonPath => "python_path must not be empty",
+        ErrorCode::ValidationContainerImage => "container.image must not be empty",
+        ErrorCode::ValidationSsh => "ssh.host and ssh.path must not be empty",
+        ErrorCode::ValidationSshHost => "ssh.host must not start with '-'",
+        ErrorCode::ValidationMessageLimit => "max_message_bytes must be at least {min}",
+        ErrorCode::ValidationRateLimit => "rate_limits_per_min.{tool} must be at most {max}",
+        ErrorCode::ValidationSessionPatch => {
+            "Session update must be an object of project, namespace, task_id and view (text or null)"
+        }
+        ErrorCode::ValidationSuggestionAction => "Cannot run this suggestion: {reason}",
+        ErrorCode::ValidationFaultRule => "Fault rule {index}: {detail}",
+        ErrorCode::ValidationBoardStatus => "status is required",
+        ErrorCode::ValidationTemplateName => "Invalid template name: \"{name}\"",
+        ErrorCode::ValidationTemplateSubtaskTitle => "Subtask {index} has no title",
+        ErrorCode::ValidationTemplateCheckpoints => {
+            "Subtask \"{subtask}\" needs at least one checkpoint"
+        }
+        ErrorCode::ValidationTemplateDuplicateCheckpoint => {
+            "Subtask \"{subtask}\" lists checkpoint \"{checkpoint}\" twice"
+        }
+        ErrorCode::ValidationTemplateParent => {
+            "Tasks from templates need a parent plan (PLAN-###)"
+        }
+        ErrorCode::ValidationTemplateFile => "Not a task template file: {detail}",
+        ErrorCode::ValidationChecklistEmpty => "The Markdown has no checklist items (- [ ] ...)",
+        ErrorCode::ValidationChecklistParent => {
+            "Tasks with subtasks need a parent plan (PLAN-###)"
+        }
+        ErrorCode::ValidationTodoRoot => "Not a directory: {path}",
+        ErrorCode::ValidationTodoGlob => "Invalid glob \"{glob}\": {detail}",
+        ErrorCode::ValidationCommandEmpty => "Command is empty",
+        ErrorCode::ValidationCommandSyntax => "Unbalanced quotes in command: {command}",
+        ErrorCode::CommandNotAllowed => {
+            "Command is not in this project's verification allowlist: {command}"
+        }
+        ErrorCode::CommandSpawnFailed => "Failed to start {command}: {detail}",
+        ErrorCode::ValidationEvidenceEmpty => "Evidence of kind {kind} needs a value",
+        ErrorCode::EvidenceFileNotFound => "Evidence file not found: {path}",
+        ErrorCode::EvidenceOutsideProject => "Evidence file is outside the project: {path}",
+        ErrorCode::EvidenceCopyFailed => {
+            "Failed to copy {path} into the attachments store: {detail}"
+        }
+        ErrorCode::ValidationFields => "{message} ({summary})",
+        ErrorCode::Internal => "{message}",
+    }
+}
+
+/// `template` with params filled in (unknown names are left as written)
+pub fn render(code: ErrorCode, params: &BTreeMap<String, Value>) -> String {
+    let mut out = String::new();
+    let mut rest = template(code);
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let name = &after[..end];
+        match params.get(name) {
+            Some(Value::String(s)) => out.push_str(s),
+            Some(value) => out.push_str(&value.to_string()),
+            None => out.push_str(&rest[start..start + end + 2]),
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// A classified failure
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogError {
+    pub code: ErrorCode,
+    pub params: BTreeMap<String, Value>,
+    /// Backend-supplied detail, passed on verbatim
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<Value>,
+}
+
+/// One rejected field from a JSON-RPC error's `data`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub reason: String,
+}
+
+impl FieldError {
+    fn from_value(value: &Value) -> Option<Self> {
+        let text = |key: &str| value.get(key)?.as_str().map(String::from);
+        Some(Self {
+            field: text("field")?,
+            reason: text("reason")?,
+        })
+    }
+
+    /// `{field, reason}`, a list of them, or `{errors: [...]}`; `None` for
+    /// any other shape (or a list with something else in it)
+    pub fn parse_all(data: &Value) -> Option<Vec<Self>> {
+        let list = match data {
+            Value::Array(items) => items,
+            Value::Object(obj) => match obj.get("errors") {
+                Some(Value::Array(items)) => items,
+                _ => return Self::from_value(data).map(|e| vec![e]),
+            },
+            _ => return None,
+        };
+        let fields: Option<Vec<Self>> = list.iter().map(Self::from_value).collect();
+        fields.filter(|f| !f.is_empty())
+    }
+}
+
+impl CatalogError {
+    pub fn new(code: ErrorCode) -> Self {
+        Self {
+            code,
+            params: BTreeMap::new(),
+            detail: None,
+        }
+    }
+
+    pub fn with(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.params.insert(name.to_string(), value.into());
+        self
+    }
+
+    /// Unclassified failure shown as is
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal).with("message", message.into())
+    }
+
+    /// Failure from a backend `AIResponse` envelope's `error` object
+    pub fn from_envelope(error: Option<&Value>, fallback: &str) -> Self {
+        let message = error
+            .and_then(|e| e.get("message"))
+            .and_then(|m| m.as_str())
+            .unwrap_or(fallback);
+        let mut err = Self::new(ErrorCode::Backend).with("message", message);
+        if let Some(code) = error.and_then(|e| e.get("code")).and_then(|c| c.as_str()) {
+            err = err.with("backend_code", code);
+        }
+        err
+    }
+
+    /// Classify `err` by the typed error at its root (falls back to `INTERNAL`)
+    pub fn from_anyhow(err: &anyhow::Error) -> Self {
+        if let Some(e) = err.downcast_ref::<BridgeError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<BackendError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<ToolCallError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<QuickParseError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<FrontendLogError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<SignalError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<AliasError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<SettingsError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<DetectionError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<FaultError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<BundleError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<BoardError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<TemplateError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<ChecklistError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<TodoError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<VerificationError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<ReadOnlyError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<RateLimitError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<SessionPatchError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<SuggestionError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<EvidenceError>() {
+            return e.into();
+        }
+        if let Some(e) = err.downcast_ref::<SubtaskNotFound>() {
+            return e.into();
+        }
+        Self::internal(err.to_string())
+    }
+
+    pub fn render(&self) -> String {
+        render(self.code, &self.params)
+    }
+}
+
+impl From<&BridgeError> for CatalogError {
+    fn from(err: &BridgeError) -> Self {
+        match err {
+            BridgeError::Spawn(detail) => {
+                Self::new(ErrorCode::BridgeSpawn).with("detail", detail.as_str())
+            }
+            BridgeError::Disconnected(detail) => {
+                Self::new(ErrorCode::BridgeDisconnected).with("detail", detail.as_str())
+            }
+            BridgeError::Timeout { tool, waited_ms } => Self::new(ErrorCode::BridgeTimeout)
+                .with("tool", tool.as_str())
+                .with("waited_ms", *waited_ms),
+            BridgeError::ResponseTooLarge { limit, tool } => {
+                Self::new(ErrorCode::BridgeResponseTooLarge)
+                    .with("tool", tool.as_str())
+                    .with("limit", *limit)
+            }
+            BridgeError::ContainerDaemon(detail) => {
+                Self::new(ErrorCode::BridgeContainerDaemon).with("detail", detail.as_str())
+            }
+            BridgeError::ContainerImage { image } => {
+                Self::new(ErrorCode::BridgeContainerImage).with("image", image.as_str())
+            }
+            BridgeError::SshHostKey { host } => {
+                Self::new(ErrorCode::BridgeSshHostKey).with("host", host.as_str())
+            }
+            BridgeError::SshAuth { host } => {
+                Self::new(ErrorCode::BridgeSshAuth).with("host", host.as_str())
+            }
+            BridgeError::SshConnect { host, detail } => Self::new(ErrorCode::BridgeSshConnect)
+                .with("host", host.as_str())
+                .with("detail", detail.as_str()),
+            BridgeError::ResourceLimit {
+                tool,
+                rss_mb,
+                cap_mb,
+            } => Self::new(ErrorCode::BridgeResourceLimit)
+                .with("tool", tool.as_str())
+                .with("rss_mb", *rss_mb)
+                .with("cap_mb", *cap_mb),
+        }
+    }
+}
+
+impl From<&BackendError> for CatalogError {
+    fn from(err: &BackendError) -> Self {
+        match err {
+            BackendError::Reported { code, message } => {
+                let err = Self::new(ErrorCode::Backend).with("message", message.as_str());
+                match code {
+                    Some(code) => err.with("backend_code", code.as_str()),
+                    None => err,
+                }
+            }
+            BackendError::TaskNotFound(task_id) => {
+                Self::new(ErrorCode::TaskNotFound).with("task_id", task_id.as_str())
+            }
+        }
+    }
+}
+
+impl From<&ToolCallError> for CatalogError {
+    fn from(err: &ToolCallError) -> Self {
+        let fields = err.data.as_ref().and_then(FieldError::parse_all);
+        let mut catalogued = match (err.missing_tool(), fields) {
+            (Some(tool), _) => Self::new(ErrorCode::BackendToolMissing).with("tool", tool),
+            (None, Some(fields)) => {
+                let summary = fields
+                    .iter()
+                    .map(|f| format!("{}: {}", f.field, f.reason))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                Self::new(ErrorCode::ValidationFields)
+                    .with("message", err.message.as_str())
+                    .with("summary", summary)
+                    .with("fields", serde_json::to_value(fields).unwrap_or_default())
+                    .with("backend_code", err.code)
+            }
+            (None, None) => Self::new(ErrorCode::Backend)
+                .with("message", err.message.as_str())
+                .with("backend_code", err.code),
+        };
+        catalogued.detail = err.data.clone();
+        catalogued
+    }
+}
+
+impl From<&QuickParseError> for CatalogError {
+    fn from(err: &QuickParseError) -> Self {
+        match err {
+            QuickParseError::EmptyTitle => Self::new(ErrorCode::ValidationTitleEmpty),
+            QuickParseError::MultipleDomains(first, second) => {
+                Self::new(ErrorCode::ValidationMultipleDomains)
+                    .with("first", first.as_str())
+                    .with("second", second.as_str())
+            }
+            QuickParseError::MultipleNamespaces(first, second) => {
+                Self::new(ErrorCode::ValidationMultipleNamespaces)
+                    .with("first", first.as_str())
+                    .with("second", second.as_str())
+            }
+        }
+    }
+}
+
+impl From<&FrontendLogError> for CatalogError {
+    fn from(err: &FrontendLogError) -> Self {
+        match err {
+            FrontendLogError::UnknownLevel(level) => {
+                Self::new(ErrorCode::ValidationLogLevel).with("level", level.as_str())
+            }
+        }
+    }
+}
+
+impl From<&SignalError> for CatalogError {
+    fn from(err: &SignalError) -> Self {
+        match err {
+            SignalError::Unknown(signal) => Self::new(ErrorCode::ValidationUnknownSignal)
+                .with("signal", signal.as_str())
+                .with("valid", crate::signals::KNOWN_SIGNALS.join(", ")),
+            SignalError::NotPending(id) => {
+                Self::new(ErrorCode::AiSignalNotFound).with("signal_id", id.as_str())
+            }
+        }
+    }
+}
+
+impl From<&AliasError> for CatalogError {
+    fn from(err: &AliasError) -> Self {
+        match err {
+            AliasError::InvalidName(name) => {
+                Self::new(ErrorCode::ValidationAliasName).with("name", name.as_str())
+            }
+            AliasError::InvalidTool { name, tool } => Self::new(ErrorCode::ValidationAliasTool)
+                .with("name", name.as_str())
+                .with("tool", tool.as_str()),
+            AliasError::DestructiveUnconfirmed(aliases) => {
+                Self::new(ErrorCode::ValidationAliasDestructive).with("aliases", aliases.as_str())
+            }
+        }
+    }
+}
+
+impl From<&SettingsError> for CatalogError {
+    fn from(err: &SettingsError) -> Self {
+        match err {
+            SettingsError::NotAnObject => Self::new(ErrorCode::ValidationSettingsNotObject),
+            SettingsError::InvalidPatch(e) => {
+                Self::new(ErrorCode::ValidationSettingsPatch).with("detail", e.to_string())
+            }
+            SettingsError::CacheTtl { setting, max } => Self::new(ErrorCode::ValidationCacheTtl)
+                .with("setting", *setting)
+                .with("max", *max),
+            SettingsError::ToolTimeout { tool, min, max } => {
+                Self::new(ErrorCode::ValidationToolTimeout)
+                    .with("tool", tool.as_str())
+                    .with("min", *min)
+                    .with("max", *max)
+            }
+            SettingsError::EmptyPythonPath => Self::new(ErrorCode::ValidationPythonPath),
+            SettingsError::EmptyContainerImage => Self::new(ErrorCode::ValidationContainerImage),
+            SettingsError::IncompleteSsh => Self::new(ErrorCode::ValidationSsh),
+            SettingsError::SshHostOption => Self::new(ErrorCode::ValidationSshHost),
+            SettingsError::MessageLimit { min } => {
+                Self::new(ErrorCode::ValidationMessageLimit).with("min", *min)
+            }
+            SettingsError::RateLimit { tool, max } => Self::new(ErrorCode::ValidationRateLimit)
+                .with("tool", tool.as_str())
+                .with("max", *max),
+        }
+    }
+}
+
+impl From<&FaultError> for CatalogError {
+    fn from(err: &FaultError) -> Self {
+        match err {
+            FaultError::DeveloperModeOff => Self::new(ErrorCode::FaultInjectionDisabled),
+            FaultError::InvalidRule { index, detail } => Self::new(ErrorCode::ValidationFaultRule)
+                .with("index", *index)
+                .with("detail", detail.as_str()),
+        }
+    }
+}
+
+impl From<&BundleError> for CatalogError {
+    fn from(err: &BundleError) -> Self {
+        match err {
+            BundleError::TooLarge { max } => {
+                Self::new(ErrorCode::SupportBundleTooLarge).with("max", *max)
+            }
+        }
+    }
+}
+
+impl From<&BoardError> for CatalogError {
+    fn from(err: &BoardError) -> Self {
+        match err {
+            BoardError::StatusRequired => Self::new(ErrorCode::ValidationBoardStatus),
+        }
+    }
+}
+
+impl From<&TemplateError> for CatalogError {
+    fn from(err: &TemplateError) -> Self {
+        match err {
+            TemplateError::InvalidName(name) => {
+                Self::new(ErrorCode::ValidationTemplateName).with("name", name.as_str())
+            }
+            TemplateError::NotFound(name) => {
+                Self::new(ErrorCode::TemplateNotFound).with("name", name.as_str())
+            }
+            TemplateError::Exists(name) => {
+                Self::new(ErrorCode::TemplateExists).with("name", name.as_str())
+            }
+            TemplateError::EmptySubtaskTitle { index } => {
+                Self::new(ErrorCode::ValidationTemplateSubtaskTitle).with("index", *index)
+            }
+            TemplateError::NoCheckpoints { subtask } => {
+                Self::new(ErrorCode::ValidationTemplateCheckpoints)
+                    .with("subtask", subtask.as_str())
+            }
+            TemplateError::DuplicateCheckpoint {
+                subtask,
+                checkpoint,
+            } => Self::new(ErrorCode::ValidationTemplateDuplicateCheckpoint)
+                .with("subtask", subtask.as_str())
+                .with("checkpoint", checkpoint.as_str()),
+            TemplateError::ParentRequired => Self::new(ErrorCode::ValidationTemplateParent),
+            TemplateError::InvalidFile(detail) => {
+                Self::new(ErrorCode::ValidationTemplateFile).with("detail", detail.as_str())
+            }
+        }
+    }
+}
+
+impl From<&ChecklistError> for CatalogError {
+    fn from(err: &ChecklistError) -> Self {
+        match err {
+            ChecklistError::NoTitle => Self::new(ErrorCode::ValidationTitleEmpty),
+            ChecklistError::NoItems => Self::new(ErrorCode::ValidationChecklistEmpty),
+            ChecklistError::ParentRequired => Self::new(ErrorCode::ValidationChecklistParent),
+        }
+    }
+}
+
+impl From<&TodoError> for CatalogError {
+    fn from(err: &TodoError) -> Self {
+        match err {
+            TodoError::NotADirectory(path) => {
+                Self::new(ErrorCode::ValidationTodoRoot).with("path", path.as_str())
+            }
+            TodoError::InvalidGlob { glob, detail } => Self::new(ErrorCode::ValidationTodoGlob)
+                .with("glob", glob.as_str())
+                .with("detail", detail.as_str()),
+        }
+    }
+}
+
+impl From<&VerificationError> for CatalogError {
+    fn from(err: &VerificationError) -> Self {
+        match err {
+            VerificationError::Empty => Self::new(ErrorCode::ValidationCommandEmpty),
+            VerificationError::Syntax(command) => {
+                Self::new(ErrorCode::ValidationCommandSyntax).with("command", command.as_str())
+            }
+            VerificationError::NotAllowed(command) => {
+                Self::new(ErrorCode::CommandNotAllowed).with("command", command.as_str())
+            }
+            VerificationError::Spawn { command, detail } => {
+                Self::new(ErrorCode::CommandSpawnFailed)
+                    .with("command", command.as_str())
+                    .with("detail", detail.as_str())
+            }
+        }
+    }
+}
+
+impl From<&ReadOnlyError> for CatalogError {
+    fn from(_: &ReadOnlyError) -> Self {
+        Self::new(ErrorCode::ReadOnlyMode)
+    }
+}
+
+impl From<&SessionPatchError> for CatalogError {
+    fn from(_: &SessionPatchError) -> Self {
+        Self::new(ErrorCode::ValidationSessionPatch)
+    }
+}
+
+impl From<&SuggestionError> for CatalogError {
+    fn from(err: &SuggestionError) -> Self {
+        Self::new(ErrorCode::ValidationSuggestionAction).with("reason", err.reason.as_str())
+    }
+}
+
+impl From<&EvidenceError> for CatalogError {
+    fn from(err: &EvidenceError) -> Self {
+        match err {
+            EvidenceError::Empty(kind) => {
+                Self::new(ErrorCode::ValidationEvidenceEmpty).with("kind", *kind)
+            }
+            EvidenceError::FileNotFound(path) => {
+                Self::new(ErrorCode::EvidenceFileNotFound).with("path", path.as_str())
+            }
+            EvidenceError::OutsideProject(path) => {
+                Self::new(ErrorCode::EvidenceOutsideProject).with("path", path.as_str())
+            }
+            EvidenceError::Copy { path, detail } => Self::new(ErrorCode::EvidenceCopyFailed)
+                .with("path", path.as_str())
+                .with("detail", detail.as_str()),
+        }
+    }
+}
+
+impl From<&SubtaskNotFound> for CatalogError {
+    fn from(err: &SubtaskNotFound) -> Self {
+        Self::new(ErrorCode::SubtaskNotFound)
+            .with("task_id", err.task_id.as_str())
+            .with("path", err.path.as_str())
+    }
+}
+
+impl From<&RateLimitError> for CatalogError {
+    fn from(err: &RateLimitError) -> Self {
+        Self::new(ErrorCode::RateLimited)
+            .with("tool", err.tool.as_str())
+            .with("retry_after_ms", err.retry_after_ms)
+    }
+}
+
+impl From<&DetectionError> for CatalogError {
+    fn from(err: &DetectionError) -> Self {
+        Self::new(ErrorCode::RootNotFound).with("checked", err.probes.len())
+    }
+}
+
+/// `{error, error_code, error_params, error_detail}` of a command response
+///
+/// Flattened into the response structs; `error` keeps the English text
+/// older frontends read.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResponseError {
+    #[serde(rename = "error")]
+    pub message: Option<String>,
+    #[serde(rename = "error_code")]
+    pub code: Option<ErrorCode>,
+    #[serde(rename = "error_params")]
+    pub params: Option<BTreeMap<String, Value>>,
+    /// `data` of the backend's JSON-RPC error, as sent
+    #[serde(rename = "error_detail", default)]
+    pub detail: Option<Value>,
+}
+
+impl ResponseError {
+    /// No failure
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.code.is_none()
+    }
+}
+
+impl From<CatalogError> for ResponseError {
+    fn from(err: CatalogError) -> Self {
+        Self {
+            message: Some(err.render()),
+            code: Some(err.code),
+            params: Some(err.params),
+            detail: err.detail,
+        }
+    }
+}
+
+impl From<Option<CatalogError>> for ResponseError {
+    fn from(err: Option<CatalogError>) -> Self {
+        err.map(Self::from).unwrap_or_default()
+    }
+}
+
+impl From<&anyhow::Error> for ResponseError {
+    fn from(err: &anyhow::Error) -> Self {
+        CatalogError::from_anyhow(err).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// `error` must stay what the typed error always printed
+    fn assert_catalogued(err: anyhow::Error, code: ErrorCode) {
+        let catalogued = CatalogError::from_anyhow(&err);
+        assert_eq!(catalogued.code, code, "{}", err);
+        assert_ne!(code, ErrorCode::Internal);
+        assert_eq!(catalogued.render(), err.to_string());
+    }
+
+    #[test]
+    fn test_every_bridge_error_has_a_code() {
+        for (err, code) in [
+            (
+                BridgeError::Spawn("no python".into()),
+                ErrorCode::BridgeSpawn,
+            ),
+            (
+                BridgeError::Disconnected("stdin closed".into()),
+                ErrorCode::BridgeDisconnected,
+            ),
+            (
+                BridgeError::Timeout {
+                    tool: "tasks_list".into(),
+                    waited_ms: 30000,
+                },
+                ErrorCode::BridgeTimeout,
+            ),
+            (
+                BridgeError::ResponseTooLarge {
+                    limit: 16 * 1024 * 1024,
+                    tool: "tasks_list".into(),
+                },
+                ErrorCode::BridgeResponseTooLarge,
+            ),
+            (
+                BridgeError::ResourceLimit {
+                    tool: "tasks_list".into(),
+                    rss_mb: 2300,
+                    cap_mb: 2048,
+                },
+                ErrorCode::BridgeResourceLimit,
+            ),
+            (
+                BridgeError::ContainerDaemon("Cannot connect to the Docker daemon".into()),
+                ErrorCode::BridgeContainerDaemon,
+            ),
+            (
+                BridgeError::ContainerImage {
+                    image: "apply-task:latest".into(),
+                },
+                ErrorCode::BridgeContainerImage,
+            ),
+            (
+                BridgeError::SshHostKey {
+                    host: "dev.example.com".into(),
+                },
+                ErrorCode::BridgeSshHostKey,
+            ),
+            (
+                BridgeError::SshAuth {
+                    host: "dev.example.com".into(),
+                },
+                ErrorCode::BridgeSshAuth,
+            ),
+            (
+                BridgeError::SshConnect {
+                    host: "dev.example.com".into(),
+                    detail: "Connection refused".into(),
+                },
+                ErrorCode::BridgeSshConnect,
+            ),
+        ] {
+            assert_catalogued(err.into(), code);
+        }
+        let timeout = CatalogError::from(&BridgeError::Timeout {
+            tool: "tasks_list".into(),
+            waited_ms: 30000,
+        });
+        assert_eq!(timeout.params["waited_ms"], json!(30000));
+    }
+
+    #[test]
+    fn test_every_validation_error_has_a_code() {
+        let cases: Vec<(anyhow::Error, ErrorCode)> = vec![
+            (
+                QuickParseError::EmptyTitle.into(),
+                ErrorCode::ValidationTitleEmpty,
+            ),
+            (
+                QuickParseError::MultipleDomains("api".into(), "ui".into()).into(),
+                ErrorCode::ValidationMultipleDomains,
+            ),
+            (
+                QuickParseError::MultipleNamespaces("work".into(), "home".into()).into(),
+                ErrorCode::ValidationMultipleNamespaces,
+            ),
+            (
+                FrontendLogError::UnknownLevel("loud".into()).into(),
+                ErrorCode::ValidationLogLevel,
+            ),
+            (
+                SignalError::Unknown("reboot".into()).into(),
+                ErrorCode::ValidationUnknownSignal,
+            ),
+            (
+                SignalError::NotPending("h-01".into()).into(),
+                ErrorCode::AiSignalNotFound,
+            ),
+            (
+                AliasError::InvalidName("two words".into()).into(),
+                ErrorCode::ValidationAliasName,
+            ),
+            (
+                AliasError::InvalidTool {
+                    name: "go".into(),
+                    tool: "".into(),
+                }
+                .into(),
+                ErrorCode::ValidationAliasTool,
+            ),
+            (
+                AliasError::DestructiveUnconfirmed("rm -> tasks_delete".into()).into(),
+                ErrorCode::ValidationAliasDestructive,
+            ),
+            (
+                SettingsError::NotAnObject.into(),
+                ErrorCode::ValidationSettingsNotObject,
+            ),
+            (
+                SettingsError::InvalidPatch(serde_json::from_str::<u8>("x").unwrap_err()).into(),
+                ErrorCode::ValidationSettingsPatch,
+            ),
+            (
+                SettingsError::CacheTtl {
+                    setting: "cache_ttl_ms",
+                    max: 60000,
+                }
+                .into(),
+                ErrorCode::ValidationCacheTtl,
+            ),
+            (
+                SettingsError::ToolTimeout {
+                    tool: "tasks_list".into(),
+                    min: 1000,
+                    max: 600000,
+                }
+                .into(),
+                ErrorCode::ValidationToolTimeout,
+            ),
+            (
+                SettingsError::EmptyPythonPath.into(),
+                ErrorCode::ValidationPythonPath,
+            ),
+            (
+                SettingsError::EmptyContainerImage.into(),
+                ErrorCode::ValidationContainerImage,
+            ),
+            (
+                SettingsError::IncompleteSsh.into(),
+                ErrorCode::ValidationSsh,
+            ),
+            (
+                SettingsError::SshHostOption.into(),
+                ErrorCode::ValidationSshHost,
+            ),
+            (
+                SettingsError::MessageLimit { min: 65536 }.into(),
+                ErrorCode::ValidationMessageLimit,
+            ),
+            (
+                SettingsError::RateLimit {
+                    tool: "tasks_decompose".into(),
+                    max: 6000,
+                }
+                .into(),
+                ErrorCode::ValidationRateLimit,
+            ),
+            (
+                FaultError::DeveloperModeOff.into(),
+                ErrorCode::FaultInjectionDisabled,
+            ),
+            (
+                FaultError::InvalidRule {
+                    index: 0,
+                    detail: "tool must not be empty".into(),
+                }
+                .into(),
+                ErrorCode::ValidationFaultRule,
+            ),
+            (
+                BundleError::TooLarge { max: 8388608 }.into(),
+                ErrorCode::SupportBundleTooLarge,
+            ),
+            (
+                BoardError::StatusRequired.into(),
+                ErrorCode::ValidationBoardStatus,
+            ),
+            (
+                TemplateError::InvalidName("a b".into()).into(),
+                ErrorCode::ValidationTemplateName,
+            ),
+            (
+                TemplateError::NotFound("spike".into()).into(),
+                ErrorCode::TemplateNotFound,
+            ),
+            (
+                TemplateError::Exists("spike".into()).into(),
+                ErrorCode::TemplateExists,
+            ),
+            (
+                TemplateError::EmptySubtaskTitle { index: 2 }.into(),
+                ErrorCode::ValidationTemplateSubtaskTitle,
+            ),
+            (
+                TemplateError::NoCheckpoints {
+                    subtask: "Fix".into(),
+                }
+                .into(),
+                ErrorCode::ValidationTemplateCheckpoints,
+            ),
+            (
+                TemplateError::DuplicateCheckpoint {
+                    subtask: "Fix".into(),
+                    checkpoint: "Reviewed".into(),
+                }
+                .into(),
+                ErrorCode::ValidationTemplateDuplicateCheckpoint,
+            ),
+            (
+                TemplateError::ParentRequired.into(),
+                ErrorCode::ValidationTemplateParent,
+            ),
+            (
+                TemplateError::InvalidFile("expected a map".into()).into(),
+                ErrorCode::ValidationTemplateFile,
+            ),
+            (
+                ChecklistError::NoTitle.into(),
+                ErrorCode::ValidationTitleEmpty,
+            ),
+            (
+                ChecklistError::NoItems.into(),
+                ErrorCode::ValidationChecklistEmpty,
+            ),
+            (
+                ChecklistError::ParentRequired.into(),
+                ErrorCode::ValidationChecklistParent,
+            ),
+            (
+                TodoError::NotADirectory("/nope".into()).into(),
+                ErrorCode::ValidationTodoRoot,
+            ),
+            (
+                TodoError::InvalidGlob {
+                    glob: "[".into(),
+                    detail: "unclosed character class".into(),
+                }
+                .into(),
+                ErrorCode::ValidationTodoGlob,
+            ),
+            (
+                VerificationError::Empty.into(),
+                ErrorCode::ValidationCommandEmpty,
+            ),
+            (
+                VerificationError::Syntax("echo 'a".into()).into(),
+                ErrorCode::ValidationCommandSyntax,
+            ),
+            (
+                VerificationError::NotAllowed("rm -rf /".into()).into(),
+                ErrorCode::CommandNotAllowed,
+            ),
+            (
+                VerificationError::Spawn {
+                    command: "nope".into(),
+                    detail: "not found".into(),
+                }
+                .into(),
+                ErrorCode::CommandSpawnFailed,
+            ),
+            (ReadOnlyError.into(), ErrorCode::ReadOnlyMode),
+            (
+                RateLimitError {
+                    tool: "tasks_decompose".into(),
+                    retry_after_ms: 2000,
+                }
+                .into(),
+                ErrorCode::RateLimited,
+            ),
+            (SessionPatchError.into(), ErrorCode::ValidationSessionPatch),
+            (
+                SuggestionError {
+                    reason: "it has no intent".into(),
+                }
+                .into(),
+                ErrorCode::ValidationSuggestionAction,
+            ),
+            (
+                EvidenceError::Empty("url").into(),
+                ErrorCode::ValidationEvidenceEmpty,
+            ),
+            (
+                EvidenceError::FileNotFound("docs/a.md".into()).into(),
+                ErrorCode::EvidenceFileNotFound,
+            ),
+            (
+                EvidenceError::OutsideProject("../a.md".into()).into(),
+                ErrorCode::EvidenceOutsideProject,
+            ),
+            (
+                EvidenceError::Copy {
+                    path: "docs/a.md".into(),
+                    detail: "denied".into(),
+                }
+                .into(),
+                ErrorCode::EvidenceCopyFailed,
+            ),
+            (
+                SubtaskNotFound {
+                    task_id: "TASK-1".into(),
+                    path: "s:3".into(),
+                }
+                .into(),
+                ErrorCode::SubtaskNotFound,
+            ),
+        ];
+        for (err, code) in cases {
+            assert_catalogued(err, code);
+        }
+    }
+
+    #[test]
+    fn test_backend_errors_and_context() {
+        assert_catalogued(
+            BackendError::TaskNotFound("TASK-7".into()).into(),
+            ErrorCode::TaskNotFound,
+        );
+        let reported = CatalogError::from_anyhow(
+            &BackendError::Reported {
+                code: Some("INVALID_ID".into()),
+                message: "bad id".into(),
+            }
+            .into(),
+        );
+        assert_eq!(reported.code, ErrorCode::Backend);
+        assert_eq!(reported.params["backend_code"], "INVALID_ID");
+        assert_eq!(reported.render(), "bad id");
+        let missing = CatalogError::from_anyhow(
+            &ToolCallError {
+                code: -32602,
+                message: "Unknown tool: tasks_set_due".into(),
+                data: None,
+            }
+            .into(),
+        );
+        assert_eq!(missing.code, ErrorCode::BackendToolMissing);
+        assert!(missing.render().contains("check_backend_version"));
+
+        // Still found below added context
+        let wrapped = anyhow::Error::new(BridgeError::Disconnected("gone".into())).context("x");
+        assert_eq!(
+            CatalogError::from_anyhow(&wrapped).code,
+            ErrorCode::BridgeDisconnected
+        );
+        let plain = CatalogError::from_anyhow(&anyhow::anyhow!("disk full"));
+        assert_eq!(plain.code, ErrorCode::Internal);
+        assert_eq!(plain.render(), "disk full");
+    }
+
+    #[test]
+    fn test_json_rpc_error_data() {
+        let tool_error = |data: Option<Value>| {
+            CatalogError::from_anyhow(
+                &ToolCallError {
+                    code: -32602,
+                    message: "Invalid arguments".into(),
+                    data,
+                }
+                .into(),
+            )
+        };
+
+        let plain = tool_error(None);
+        assert_eq!(plain.code, ErrorCode::Backend);
+        assert_eq!(ResponseError::from(plain).detail, None);
+
+        let data = json!({"errors": [
+            {"field": "title", "reason": "must not be empty"},
+            {"field": "due", "reason": "not a date"},
+        ]});
+        let fields = tool_error(Some(data.clone()));
+        assert_eq!(fields.code, ErrorCode::ValidationFields);
+        assert_eq!(
+            fields.render(),
+            "Invalid arguments (title: must not be empty; due: not a date)"
+        );
+        assert_eq!(fields.params["fields"], data["errors"]);
+        let response = serde_json::to_value(ResponseError::from(fields)).unwrap();
+        assert_eq!(response["error_detail"], data);
+
+        // A bare list or a single object is read the same way
+        let single = json!({"field": "title", "reason": "too long"});
+        assert_eq!(
+            tool_error(Some(json!([single.clone()]))).code,
+            ErrorCode::ValidationFields
+        );
+        assert_eq!(
+            tool_error(Some(single)).params["fields"],
+            json!([{"field": "title", "reason": "too long"}])
+        );
+
+        // Other shapes are passed on without folding
+        for data in [
+            json!({"checkpoint": "tests", "blocked": true}),
+            json!("Traceback (most recent call last): ..."),
+            json!([{"field": "title"}]),
+            json!([]),
+            json!(42),
+        ] {
+            let other = tool_error(Some(data.clone()));
+            assert_eq!(other.code, ErrorCode::Backend, "{}", data);
+            assert_eq!(other.render(), "Invalid arguments");
+            assert_eq!(ResponseError::from(other).detail, Some(data));
+        }
+    }
+
+    #[test]
+    fn test_render_and_response_shape() {
+        for code in ErrorCode::ALL {
+            assert!(!template(*code).is_empty());
+        }
+        // Values are inserted once, never re-expanded
+        let err = CatalogError::new(ErrorCode::UnknownIntent).with("intent", "{intent}");
+        assert_eq!(err.render(), "Unknown intent: {intent}");
+        assert_eq!(
+            render(ErrorCode::JobNotFound, &BTreeMap::new()),
+            "No running job {job_id}"
+        );
+
+        let value = serde_json::to_value(ResponseError::from(
+            CatalogError::new(ErrorCode::JobNotFound).with("job_id", "job-1"),
+        ))
+        .unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "error": "No running job job-1",
+                "error_code": "JOB_NOT_FOUND",
+                "error_params": {"job_id": "job-1"},
+                "error_detail": null
+            })
+        );
+        assert!(ResponseError::none().is_none());
+    }
+}
@@ -0,0 +1,492 @@
+//! Evidence on checkpoint confirmations
+//!
+//! `tasks_verify` takes `evidence` items: a file in the project, a command's
+//! output, a URL or plain text. They go to the backend as `attachments`,
+//! which it links to every checkpoint the call confirms. A backend whose
+//! `tasks_verify` doesn't declare `attachments` gets them as `Evidence ...`
+//! lines in each checkpoint note instead, and the response says so. File
+//! items must exist inside the project root; with `copy_evidence` they are
+//! also copied into the project's attachments store in the data dir, so the
+//! evidence outlives later edits. [`checkpoints`] reads both forms back.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::sidecar;
+use crate::storage;
+
+/// Checkpoints of a step, in the backend's order
+pub const CHECKPOINTS: [&str; 5] = ["criteria", "tests", "security", "perf", "docs"];
+/// Folder of copied files inside the project's sidecar dir
+pub const STORE_DIR: &str = "attachments";
+/// Warning of a confirmation whose evidence went into the notes
+pub const NOTE_FALLBACK: &str =
+    "The backend's tasks_verify takes no attachments; the evidence was added to the checkpoint notes";
+
+/// Start of a note line carrying an evidence item
+const NOTE_PREFIX: &str = "Evidence [";
+/// Longest value written into a note (command output is cut)
+const NOTE_VALUE_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceKind {
+    /// `value` is a path (relative to the project root)
+    File,
+    CommandOutput,
+    Url,
+    Text,
+}
+
+impl EvidenceKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::CommandOutput => "command_output",
+            Self::Url => "url",
+            Self::Text => "text",
+        }
+    }
+
+    fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "file" => Some(Self::File),
+            // `cmd_output` is what `tasks_evidence_capture` writes
+            "command_output" | "cmd_output" => Some(Self::CommandOutput),
+            "url" => Some(Self::Url),
+            "text" => Some(Self::Text),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvidenceItem {
+    pub kind: EvidenceKind,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Copy of a file item in the attachments store
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stored: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum EvidenceError {
+    #[error("Evidence of kind {0} needs a value")]
+    Empty(&'static str),
+    #[error("Evidence file not found: {0}")]
+    FileNotFound(String),
+    #[error("Evidence file is outside the project: {0}")]
+    OutsideProject(String),
+    #[error("Failed to copy {path} into the attachments store: {detail}")]
+    Copy { path: String, detail: String },
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Task {task_id} has no subtask at {path}")]
+pub struct SubtaskNotFound {
+    pub task_id: String,
+    pub path: String,
+}
+
+/// One checkpoint of a task or subtask, its evidence typed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub name: String,
+    pub confirmed: bool,
+    pub auto_confirmed: bool,
+    /// Notes as written (evidence lines included)
+    pub notes: Vec<String>,
+    pub evidence: Vec<EvidenceItem>,
+}
+
+/// `items` trimmed, file paths made relative to `root` (and checked to be
+/// files inside it)
+pub fn check(items: &[EvidenceItem], root: &Path) -> Result<Vec<EvidenceItem>, EvidenceError> {
+    items
+        .iter()
+        .map(|item| {
+            let value = item.value.trim();
+            if value.is_empty() {
+                return Err(EvidenceError::Empty(item.kind.as_str()));
+            }
+            let value = match item.kind {
+                EvidenceKind::File => relative_file(root, value)?,
+                _ => value.to_string(),
+            };
+            Ok(EvidenceItem {
+                kind: item.kind,
+                value,
+                label: item
+                    .label
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .map(String::from),
+                stored: None,
+            })
+        })
+        .collect()
+}
+
+fn relative_file(root: &Path, value: &str) -> Result<String, EvidenceError> {
+    let path = root.join(value);
+    if !path.is_file() {
+        return Err(EvidenceError::FileNotFound(value.to_string()));
+    }
+    if !storage::within_root(root, &path) {
+        return Err(EvidenceError::OutsideProject(value.to_string()));
+    }
+    let (Ok(root), Ok(path)) = (root.canonicalize(), path.canonicalize()) else {
+        return Err(EvidenceError::FileNotFound(value.to_string()));
+    };
+    let relative = path.strip_prefix(&root).unwrap_or(&path);
+    Ok(relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+/// Copy the file items (already [`check`]ed) into `store`, as
+/// `<content hash>-<file name>` so the same content is kept once
+pub fn copy_files(
+    items: &mut [EvidenceItem],
+    root: &Path,
+    store: &Path,
+) -> Result<(), EvidenceError> {
+    for item in items.iter_mut().filter(|i| i.kind == EvidenceKind::File) {
+        let failed = |e: std::io::Error| EvidenceError::Copy {
+            path: item.value.clone(),
+            detail: e.to_string(),
+        };
+        let source = root.join(&item.value);
+        let bytes = fs::read(&source).map_err(failed)?;
+        let name = source
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let target: PathBuf = store.join(format!("{:016x}-{}", sidecar::fnv1a(&bytes), name));
+        fs::create_dir_all(store).map_err(failed)?;
+        if !target.is_file() {
+            fs::write(&target, &bytes).map_err(failed)?;
+        }
+        item.stored = Some(target.to_string_lossy().to_string());
+    }
+    Ok(())
+}
+
+/// `tasks_verify` `attachments` entry of `item`
+pub fn to_attachment(item: &EvidenceItem) -> Value {
+    let mut meta = Map::new();
+    if let Some(label) = &item.label {
+        meta.insert("label".to_string(), json!(label));
+    }
+    let mut attachment = Map::new();
+    attachment.insert("kind".to_string(), json!(item.kind.as_str()));
+    match item.kind {
+        EvidenceKind::File => {
+            attachment.insert("path".to_string(), json!(item.value));
+            if let Some(stored) = &item.stored {
+                attachment.insert("uri".to_string(), json!(stored));
+            }
+        }
+        EvidenceKind::Url => {
+            attachment.insert("external_uri".to_string(), json!(item.value));
+        }
+        EvidenceKind::CommandOutput | EvidenceKind::Text => {
+            meta.insert("text".to_string(), json!(item.value));
+        }
+    }
+    if !meta.is_empty() {
+        attachment.insert("meta".to_string(), Value::Object(meta));
+    }
+    Value::Object(attachment)
+}
+
+/// Item of a backend attachment (`None` for kinds without a typed form)
+pub fn from_attachment(attachment: &Value) -> Option<EvidenceItem> {
+    let text = |pointer: &str| {
+        attachment
+            .pointer(pointer)
+            .and_then(Value::as_str)
+            .filter(|v| !v.is_empty())
+            .map(String::from)
+    };
+    let kind = EvidenceKind::parse(attachment.get("kind")?.as_str()?)?;
+    let value = match kind {
+        EvidenceKind::File => text("/path"),
+        EvidenceKind::Url => text("/external_uri").or_else(|| text("/uri")),
+        EvidenceKind::CommandOutput | EvidenceKind::Text => {
+            text("/meta/text").or_else(|| text("/meta/output"))
+        }
+    }?;
+    Some(EvidenceItem {
+        kind,
+        value,
+        label: text("/meta/label"),
+        stored: (kind == EvidenceKind::File).then(|| text("/uri")).flatten(),
+    })
+}
+
+/// Note lines for `items` (one per item, values on one line)
+pub fn note(items: &[EvidenceItem]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            let mut value = item.value.split_whitespace().collect::<Vec<_>>().join(" ");
+            if value.chars().count() > NOTE_VALUE_CHARS {
+                value = value.chars().take(NOTE_VALUE_CHARS).collect::<String>() + "…";
+            }
+            match &item.label {
+                Some(label) => format!(
+                    "{}{}] {}: {}",
+                    NOTE_PREFIX,
+                    item.kind.as_str(),
+                    label,
+                    value
+                ),
+                None => format!("{}{}]: {}", NOTE_PREFIX, item.kind.as_str(), value),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Items written by [`note`] into `note`
+fn from_note(note: &str) -> Vec<EvidenceItem> {
+    note.lines()
+        .filter_map(|line| {
+            let (kind, rest) = line.trim().strip_prefix(NOTE_PREFIX)?.split_once(']')?;
+            let kind = EvidenceKind::parse(kind)?;
+            let (label, value) = match rest.strip_prefix(": ") {
+                Some(value) => (None, value),
+                None => {
+                    let (label, value) = rest.trim_start().split_once(": ")?;
+                    (Some(label.to_string()), value)
+                }
+            };
+            Some(EvidenceItem {
+                kind,
+                value: value.to_string(),
+                label,
+                stored: None,
+            })
+        })
+        .collect()
+}
+
+/// The node at `path` (`s:0.t:1.s:2`) of a `tasks_show` payload, the task
+/// itself without one
+pub fn node<'a>(task: &'a Value, path: Option<&str>) -> Result<&'a Value, SubtaskNotFound> {
+    let Some(path) = path.map(str::trim).filter(|p| !p.is_empty()) else {
+        return Ok(task);
+    };
+    fn find<'a>(nodes: Option<&'a Value>, path: &str) -> Option<&'a Value> {
+        nodes?.as_array()?.iter().find_map(|node| {
+            if node.get("path").and_then(Value::as_str) == Some(path) {
+                return Some(node);
+            }
+            find(node.get("steps"), path).or_else(|| find(node.pointer("/plan/tasks"), path))
+        })
+    }
+    find(task.get("steps"), path).ok_or_else(|| SubtaskNotFound {
+        task_id: task
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        path: path.to_string(),
+    })
+}
+
+/// Checkpoints of `node` with their evidence: attachments the backend
+/// linked to them (`<name>_evidence_refs`), then evidence lines of notes
+pub fn checkpoints(node: &Value) -> Vec<Checkpoint> {
+    let attachments: Vec<(&str, EvidenceItem)> = node
+        .get("attachments")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|a| Some((a.get("digest")?.as_str()?, from_attachment(a)?)))
+        .collect();
+    let strings = |key: String| -> Vec<String> {
+        node.get(&key)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect()
+    };
+    let flag = |key: String| node.get(&key).and_then(Value::as_bool).unwrap_or(false);
+
+    CHECKPOINTS
+        .iter()
+        .map(|name| {
+            let refs = strings(format!("{}_evidence_refs", name));
+            let notes = strings(format!("{}_notes", name));
+            let mut evidence: Vec<EvidenceItem> = attachments
+                .iter()
+                .filter(|(digest, _)| refs.iter().any(|r| r == digest))
+                .map(|(_, item)| item.clone())
+                .collect();
+            evidence.extend(notes.iter().flat_map(|n| from_note(n)));
+            Checkpoint {
+                name: name.to_string(),
+                confirmed: flag(format!("{}_confirmed", name)),
+                auto_confirmed: flag(format!("{}_auto_confirmed", name)),
+                notes,
+                evidence,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(kind: EvidenceKind, value: &str, label: Option<&str>) -> EvidenceItem {
+        EvidenceItem {
+            kind,
+            value: value.into(),
+            label: label.map(String::from),
+            stored: None,
+        }
+    }
+
+    #[test]
+    fn test_each_kind_round_trips_as_attachment() {
+        let items = [
+            item(EvidenceKind::File, "docs/review.md", Some("Review notes")),
+            item(
+                EvidenceKind::Url,
+                "https://ci.example.com/run/42",
+                Some("CI run"),
+            ),
+            item(EvidenceKind::CommandOutput, "3 passed", Some("pytest")),
+            item(EvidenceKind::Text, "Checked by hand", None),
+        ];
+        let attachments: Vec<Value> = items.iter().map(to_attachment).collect();
+        assert_eq!(
+            attachments,
+            [
+                json!({ "kind": "file", "path": "docs/review.md", "meta": { "label": "Review notes" } }),
+                json!({ "kind": "url", "external_uri": "https://ci.example.com/run/42", "meta": { "label": "CI run" } }),
+                json!({ "kind": "command_output", "meta": { "label": "pytest", "text": "3 passed" } }),
+                json!({ "kind": "text", "meta": { "text": "Checked by hand" } }),
+            ]
+        );
+        let back: Vec<EvidenceItem> = attachments.iter().filter_map(from_attachment).collect();
+        assert_eq!(back, items);
+        assert_eq!(
+            from_attachment(&json!({ "kind": "diff", "path": "a" })),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_files_and_store() {
+        let dir = std::env::temp_dir().join(format!("apply-task-evidence-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let root = dir.join("project");
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("docs/review.md"), "# Review").unwrap();
+        fs::write(dir.join("secret.txt"), "x").unwrap();
+
+        let checked = check(
+            &[
+                item(EvidenceKind::File, " ./docs/review.md ", Some(" ")),
+                item(EvidenceKind::Url, "https://example.com", None),
+            ],
+            &root,
+        )
+        .unwrap();
+        assert_eq!(checked[0].value, "docs/review.md");
+        assert_eq!(checked[0].label, None);
+        assert_eq!(
+            check(&[item(EvidenceKind::File, "missing.md", None)], &root),
+            Err(EvidenceError::FileNotFound("missing.md".into()))
+        );
+        assert_eq!(
+            check(&[item(EvidenceKind::File, "../secret.txt", None)], &root),
+            Err(EvidenceError::OutsideProject("../secret.txt".into()))
+        );
+        assert_eq!(
+            check(&[item(EvidenceKind::Text, "  ", None)], &root),
+            Err(EvidenceError::Empty("text"))
+        );
+
+        let mut items = checked;
+        copy_files(&mut items, &root, &dir.join(STORE_DIR)).unwrap();
+        let stored = items[0].stored.clone().unwrap();
+        assert!(stored.ends_with("-review.md"), "{}", stored);
+        assert_eq!(fs::read_to_string(&stored).unwrap(), "# Review");
+        assert_eq!(items[1].stored, None);
+        assert_eq!(to_attachment(&items[0])["uri"], json!(stored));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_note_lines_parse_back() {
+        let items = [
+            item(
+                EvidenceKind::Url,
+                "https://ci.example.com/run/42",
+                Some("CI run"),
+            ),
+            item(EvidenceKind::CommandOutput, "collected 3\n3 passed", None),
+        ];
+        let note = note(&items);
+        assert_eq!(
+            note,
+            "Evidence [url] CI run: https://ci.example.com/run/42\nEvidence [command_output]: collected 3 3 passed"
+        );
+        let back = from_note(&format!("Reviewed\n{}", note));
+        assert_eq!(back[0], items[0]);
+        assert_eq!(back[1].value, "collected 3 3 passed");
+    }
+
+    #[test]
+    fn test_checkpoints_of_resume_fixture() {
+        let envelope: Value =
+            serde_json::from_str(include_str!("../tests/fixtures/tasks_resume_evidence.json"))
+                .unwrap();
+        let task = &envelope["result"]["task"];
+        let step = node(task, Some("s:0")).unwrap();
+        let checkpoints = checkpoints(step);
+        let names: Vec<&str> = checkpoints.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, CHECKPOINTS);
+
+        let criteria = &checkpoints[0];
+        assert!(criteria.confirmed);
+        assert_eq!(criteria.notes, ["Reproduced locally"]);
+        let kinds: Vec<EvidenceKind> = criteria.evidence.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                EvidenceKind::File,
+                EvidenceKind::Url,
+                EvidenceKind::CommandOutput,
+                EvidenceKind::Text
+            ]
+        );
+        assert_eq!(criteria.evidence[0].value, "docs/review.md");
+        assert_eq!(criteria.evidence[2].label.as_deref(), Some("pytest"));
+        assert!(!checkpoints[1].confirmed);
+        assert!(checkpoints[1].evidence.is_empty());
+
+        assert!(std::ptr::eq(node(task, None).unwrap(), task));
+        assert_eq!(
+            node(task, Some("s:4")),
+            Err(SubtaskNotFound {
+                task_id: "TASK-001".into(),
+                path: "s:4".into()
+            })
+        );
+    }
+}
@@ -0,0 +1,387 @@
+//! Fuzzy matching for the quick-switcher
+//!
+//! An fzf-style scorer: the query's characters must appear in order
+//! (case-insensitively), and a Smith–Waterman pass picks the placement with
+//! the best score. Every matched character scores, more so at the start of a
+//! word or a camelCase hump and right after the previous match; gaps cost a
+//! little to open and less to extend. `rflg` thus prefers the word starts of
+//! "Refactor login flow" over scattered letters.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::backend::task_str;
+use crate::timeline;
+
+/// Matches returned when the caller doesn't set a limit
+pub const DEFAULT_LIMIT: usize = 50;
+
+const SCORE_MATCH: i32 = 16;
+const SCORE_GAP_START: i32 = -3;
+const SCORE_GAP_EXTENSION: i32 = -1;
+const BONUS_BOUNDARY: i32 = 8;
+const BONUS_CAMEL: i32 = 7;
+const BONUS_CONSECUTIVE: i32 = 4;
+/// The first query character's bonus counts this many times
+const FIRST_CHAR_MULTIPLIER: i32 = 2;
+
+/// No placement reaches this cell
+const NONE: i32 = i32::MIN / 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchField {
+    Title,
+    Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FuzzyMatch {
+    pub task_id: String,
+    pub title: String,
+    pub score: i32,
+    /// Which text `indices` point into
+    pub field: MatchField,
+    /// Matched characters, as UTF-16 offsets (how JavaScript strings index)
+    pub indices: Vec<usize>,
+}
+
+/// Bonus for matching `current` right after `previous`
+fn bonus(previous: Option<char>, current: char) -> i32 {
+    match previous {
+        None => BONUS_BOUNDARY,
+        Some(p) if !p.is_alphanumeric() && current.is_alphanumeric() => BONUS_BOUNDARY,
+        Some(p) if p.is_lowercase() && current.is_uppercase() => BONUS_CAMEL,
+        Some(p) if p.is_alphabetic() && current.is_numeric() => BONUS_CAMEL,
+        _ => 0,
+    }
+}
+
+fn fold(c: char) -> char {
+    if c.is_ascii() {
+        c.to_ascii_lowercase()
+    } else {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+/// Buffers reused from one text to the next
+#[derive(Default)]
+struct Scratch {
+    chars: Vec<char>,
+    folded: Vec<char>,
+    bonuses: Vec<i32>,
+    cell: Vec<i32>,
+}
+
+/// Best score of `query` in `text` and the char indices it matched, or
+/// `None` when the query isn't a subsequence (an empty query never matches)
+pub fn score(query: &[char], text: &str) -> Option<(i32, Vec<usize>)> {
+    let mut scratch = Scratch::default();
+    let (end, best) = scratch.fill(query, text)?;
+    Some((best, scratch.backtrack(query.len(), end)?))
+}
+
+impl Scratch {
+    /// Fill the table for `text`; the best score and where its last match
+    /// ends
+    fn fill(&mut self, query: &[char], text: &str) -> Option<(usize, i32)> {
+        self.chars.clear();
+        self.chars.extend(text.chars());
+        let chars = &self.chars;
+        let (m, n) = (query.len(), chars.len());
+        if m == 0 || m > n {
+            return None;
+        }
+        self.folded.clear();
+        self.folded.extend(chars.iter().map(|c| fold(*c)));
+        let folded = &self.folded;
+        // Cheap subsequence check before the table
+        let mut rest = folded.iter();
+        if !query.iter().all(|q| rest.any(|c| c == q)) {
+            return None;
+        }
+
+        self.bonuses.clear();
+        self.bonuses
+            .extend((0..n).map(|j| bonus(j.checked_sub(1).map(|p| chars[p]), chars[j])));
+        let bonuses = &self.bonuses;
+        // cell[i * n + j]: best score with query[i] matched at chars[j]
+        self.cell.clear();
+        self.cell.resize(m * n, NONE);
+        let cell = &mut self.cell;
+        for (i, &q) in query.iter().enumerate() {
+            // Best previous-row placement ending two or more chars back,
+            // already charged for the gap up to j - 1
+            let mut gap_best = NONE;
+            for j in i..n {
+                if i > 0 && j >= 2 {
+                    let opened = cell[(i - 1) * n + j - 2];
+                    gap_best = (gap_best + SCORE_GAP_EXTENSION).max(opened + SCORE_GAP_START);
+                }
+                if folded[j] != q {
+                    continue;
+                }
+                let base = SCORE_MATCH
+                    + if i == 0 {
+                        bonuses[j] * FIRST_CHAR_MULTIPLIER
+                    } else {
+                        bonuses[j]
+                    };
+                cell[i * n + j] = if i == 0 {
+                    base
+                } else {
+                    let consecutive = match j {
+                        0 => NONE,
+                        _ => cell[(i - 1) * n + j - 1] + BONUS_CONSECUTIVE,
+                    };
+                    let previous = consecutive.max(gap_best);
+                    if previous <= NONE / 2 {
+                        continue;
+                    }
+                    base + previous
+                };
+            }
+        }
+
+        let last = (m - 1) * n;
+        (0..n)
+            .map(|j| (j, cell[last + j]))
+            .filter(|(_, s)| *s > NONE / 2)
+            .max_by_key(|(j, s)| (*s, Reverse(*j)))
+    }
+
+    /// Walk the last filled table back from `end` to the placement that
+    /// produced its best score
+    fn backtrack(&self, m: usize, end: usize) -> Option<Vec<usize>> {
+        let (cell, bonuses, n) = (&self.cell, &self.bonuses, self.chars.len());
+        let mut indices = vec![end];
+        let mut j = end;
+        for i in (1..m).rev() {
+            let base = SCORE_MATCH + bonuses[j];
+            let target = cell[i * n + j] - base;
+            let previous = (0..j).rev().find(|&k| {
+                let prior = cell[(i - 1) * n + k];
+                prior > NONE / 2
+                    && target
+                        == prior
+                            + if k + 1 == j {
+                                BONUS_CONSECUTIVE
+                            } else {
+                                SCORE_GAP_START + SCORE_GAP_EXTENSION * (j - k - 2) as i32
+                            }
+            })?;
+            indices.push(previous);
+            j = previous;
+        }
+        indices.reverse();
+        Some(indices)
+    }
+}
+
+/// Char indices of `text` as UTF-16 offsets
+fn utf16_offsets(text: &str, indices: &[usize]) -> Vec<usize> {
+    let offsets: Vec<usize> = text
+        .chars()
+        .scan(0, |offset, c| {
+            let at = *offset;
+            *offset += c.len_utf16();
+            Some(at)
+        })
+        .collect();
+    indices.iter().map(|&i| offsets[i]).collect()
+}
+
+/// Best `limit` tasks for `query`, by score, then most recently updated
+/// (when the payloads say), then most recently viewed (`recent` ids, newest
+/// first)
+pub fn rank(tasks: &[Value], query: &str, recent: &[String], limit: usize) -> Vec<FuzzyMatch> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(fold)
+        .collect();
+    let viewed: HashMap<&str, usize> = recent
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (id.as_str(), i))
+        .collect();
+
+    struct Candidate<'a> {
+        task_id: &'a str,
+        title: &'a str,
+        score: i32,
+        field: MatchField,
+        updated: Option<DateTime<Utc>>,
+        viewed: usize,
+    }
+
+    let mut scratch = Scratch::default();
+    let mut candidates: Vec<Candidate> = tasks
+        .iter()
+        .filter_map(|task| {
+            let task_id = task_str(task, "id")?;
+            let title = task_str(task, "title").unwrap_or_default();
+            let by_title = scratch
+                .fill(&query, title)
+                .map(|(_, s)| (s, MatchField::Title));
+            let by_id = scratch
+                .fill(&query, task_id)
+                .map(|(_, s)| (s, MatchField::Id));
+            let (score, field) = match (by_title, by_id) {
+                (Some(t), Some(i)) => std::cmp::max_by_key(i, t, |m| m.0),
+                (t, i) => t.or(i)?,
+            };
+            let updated = ["updated_at", "updated"]
+                .iter()
+                .find_map(|key| task.get(*key))
+                .and_then(|v| timeline::parse_timestamp(v, &Utc));
+            Some(Candidate {
+                task_id,
+                title,
+                score,
+                field,
+                updated,
+                viewed: viewed.get(task_id).copied().unwrap_or(usize::MAX),
+            })
+        })
+        .collect();
+    let order = |a: &Candidate, b: &Candidate| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| b.updated.cmp(&a.updated))
+            .then_with(|| a.viewed.cmp(&b.viewed))
+            .then_with(|| a.task_id.cmp(b.task_id))
+    };
+    // Only the top `limit` need a full sort
+    if limit < candidates.len() {
+        if limit == 0 {
+            return Vec::new();
+        }
+        candidates.select_nth_unstable_by(limit - 1, order);
+        candidates.truncate(limit);
+    }
+    candidates.sort_by(order);
+    // Match indices only for what is returned
+    candidates
+        .into_iter()
+        .map(|c| {
+            let text = match c.field {
+                MatchField::Title => c.title,
+                MatchField::Id => c.task_id,
+            };
+            let indices = score(&query, text).map(|(_, i)| i).unwrap_or_default();
+            FuzzyMatch {
+                task_id: c.task_id.to_string(),
+                title: c.title.to_string(),
+                score: c.score,
+                field: c.field,
+                indices: utf16_offsets(text, &indices),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Instant;
+
+    fn q(query: &str) -> Vec<char> {
+        query.chars().map(fold).collect()
+    }
+
+    #[test]
+    fn test_score_prefers_word_starts() {
+        let (_, indices) = score(&q("rflg"), "Refactor login flow").unwrap();
+        // r, then f of "Refactor", then the "l" of "login" and its "g"
+        assert_eq!(indices, [0, 2, 9, 11]);
+
+        let (_, indices) = score(&q("lf"), "Refactor login flow").unwrap();
+        assert_eq!(indices, [9, 15]);
+
+        // Word starts outscore letters scattered mid-word
+        let starts = score(&q("lf"), "login flow").unwrap().0;
+        let scattered = score(&q("lf"), "calfskin").unwrap().0;
+        assert!(starts > scattered, "{} vs {}", starts, scattered);
+        // Consecutive letters outscore a gap
+        let tight = score(&q("log"), "login").unwrap().0;
+        let loose = score(&q("log"), "lxoxg").unwrap().0;
+        assert!(tight > loose, "{} vs {}", tight, loose);
+        // camelCase humps count as word starts
+        assert_eq!(score(&q("ct"), "cacheToken").unwrap().1, [0, 5]);
+    }
+
+    #[test]
+    fn test_score_rejects_and_edges() {
+        assert_eq!(score(&q("xyz"), "Refactor login flow"), None);
+        assert_eq!(score(&q("flr"), "Refactor login flow"), None);
+        assert_eq!(score(&q(""), "anything"), None);
+        assert_eq!(score(&q("long query"), "short"), None);
+        assert_eq!(score(&q("LOGIN"), "login").unwrap().1, [0, 1, 2, 3, 4]);
+        assert_eq!(score(&q("ёл"), "Ёлка").unwrap().1, [0, 1]);
+        assert_eq!(score(&q("t1"), "TASK-001").unwrap().1, [0, 7]);
+    }
+
+    #[test]
+    fn test_rank_orders_and_offsets() {
+        let tasks = vec![
+            json!({"id": "TASK-001", "title": "Refactor login flow", "updated_at": "2026-10-01 10:00"}),
+            json!({"id": "TASK-002", "title": "Rollback flag setting", "updated_at": "2026-10-02 10:00"}),
+            json!({"id": "TASK-003", "title": "🔐 Refactor login flow", "updated_at": "2026-10-03 10:00"}),
+            json!({"id": "TASK-004", "title": "Write docs"}),
+        ];
+        let hits = rank(&tasks, "rflg", &[], 10);
+        let ids: Vec<&str> = hits.iter().map(|h| h.task_id.as_str()).collect();
+        // "flag" keeps f-l together; the two equal titles go most recently
+        // updated first
+        assert_eq!(ids, ["TASK-002", "TASK-003", "TASK-001"]);
+        assert_eq!(hits[1].score, hits[2].score);
+        // Offsets skip the two UTF-16 units of the emoji and the space
+        assert_eq!(hits[1].indices, [3, 5, 12, 14]);
+        assert_eq!(hits[2].indices, [0, 2, 9, 11]);
+
+        // Ids match too
+        let hits = rank(&tasks, "task004", &[], 10);
+        assert_eq!(hits[0].task_id, "TASK-004");
+        assert_eq!(hits[0].field, MatchField::Id);
+
+        // Without timestamps, recently viewed breaks ties
+        let plain: Vec<Value> = ["A-1", "A-2"]
+            .iter()
+            .map(|id| json!({"id": id, "title": "Same title"}))
+            .collect();
+        let hits = rank(&plain, "same", &["A-2".to_string()], 10);
+        assert_eq!(hits[0].task_id, "A-2");
+        assert_eq!(rank(&plain, "same", &[], 1).len(), 1);
+        assert!(rank(&plain, "   ", &[], 10).is_empty());
+    }
+
+    /// `cargo test --release -- --ignored --nocapture fuzzy_rank` prints the
+    /// time per keystroke
+    #[test]
+    #[ignore = "timing test, run explicitly in release"]
+    fn test_fuzzy_rank_time() {
+        let words = ["refactor", "login", "flow", "cache", "token", "docs", "api"];
+        let tasks: Vec<Value> = (0..5_000)
+            .map(|i| {
+                let title: Vec<&str> = (0..6)
+                    .map(|k| words[(i * 7 + k * 3) % words.len()])
+                    .collect();
+                json!({"id": format!("TASK-{:04}", i), "title": title.join(" ")})
+            })
+            .collect();
+        let started = Instant::now();
+        for query in ["r", "rf", "rfl", "rflg", "rflgx"] {
+            rank(&tasks, query, &[], DEFAULT_LIMIT);
+        }
+        let per_keystroke = started.elapsed() / 5;
+        println!("ranked 5000 tasks in {:?} per keystroke", per_keystroke);
+        assert!(per_keystroke.as_millis() < 5, "{:?}", per_keystroke);
+    }
+}
@@ -0,0 +1,313 @@
+//! Git branch, commit and log of the project
+//!
+//! Asked of the `git` binary (`rev-parse`, `log`) with a short timeout; a
+//! missing binary, a directory outside any repository or a slow git are
+//! reported, not raised, so callers can go on without the information.
+
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::doctor;
+
+/// Per `git rev-parse` call
+pub const GIT_TIMEOUT: Duration = Duration::from_millis(1500);
+/// Per `git log` call
+pub const LOG_TIMEOUT: Duration = Duration::from_secs(5);
+/// Separates the fields of a `git log` line
+const FIELD_SEPARATOR: char = '\u{1f}';
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitInfo {
+    /// `None` on a detached HEAD
+    pub branch: Option<String>,
+    /// Abbreviated
+    pub commit: String,
+}
+
+impl GitInfo {
+    /// `main@1a2b3c4`, or just the commit when detached
+    pub fn label(&self) -> String {
+        match &self.branch {
+            Some(branch) => format!("{}@{}", branch, self.commit),
+            None => self.commit.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Commit {
+    pub hash: String,
+    pub short_hash: String,
+    pub author: String,
+    /// Author date
+    pub date: DateTime<Utc>,
+    pub subject: String,
+}
+
+/// Commits to list with [`log`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogQuery {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Only commits whose message contains this (case-insensitive)
+    pub mentioning: Option<String>,
+    pub limit: usize,
+}
+
+/// Why there is no [`GitInfo`] or log
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum GitUnavailable {
+    #[error("git is not installed")]
+    NotInstalled,
+    #[error("Not a git repository (or it has no commits yet)")]
+    NotARepository,
+    #[error("git failed: {0}")]
+    Failed(String),
+}
+
+async fn run(
+    program: &str,
+    root: &Path,
+    args: &[&str],
+    timeout: Duration,
+) -> Result<String, GitUnavailable> {
+    let mut cmd = tokio::process::Command::new(program);
+    cmd.args(args)
+        .current_dir(root)
+        // Reads need no index lock; don't contend with a running git
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .env("GIT_TERMINAL_PROMPT", "0");
+    doctor::output(cmd, timeout).await.map_err(|e| {
+        let message = format!("{:#}", e);
+        match e.downcast_ref::<std::io::Error>() {
+            Some(io) if io.kind() == std::io::ErrorKind::NotFound => GitUnavailable::NotInstalled,
+            _ if message.contains("not a git repository")
+                || message.contains("unknown revision")
+                || message.contains("ambiguous argument 'HEAD'")
+                || message.contains("does not have any commits") =>
+            {
+                GitUnavailable::NotARepository
+            }
+            _ => GitUnavailable::Failed(message),
+        }
+    })
+}
+
+async fn info_with(program: &str, root: &Path) -> Result<GitInfo, GitUnavailable> {
+    let (branch, commit) = tokio::join!(
+        run(
+            program,
+            root,
+            &["rev-parse", "--abbrev-ref", "HEAD"],
+            GIT_TIMEOUT
+        ),
+        run(
+            program,
+            root,
+            &["rev-parse", "--short", "HEAD"],
+            GIT_TIMEOUT
+        ),
+    );
+    let branch = branch?;
+    Ok(GitInfo {
+        branch: (branch != "HEAD").then_some(branch),
+        commit: commit?,
+    })
+}
+
+/// Branch and commit checked out in `root`
+pub async fn info(root: &Path) -> Result<GitInfo, GitUnavailable> {
+    info_with("git", root).await
+}
+
+/// One `hash, short hash, author, date, subject` line of [`log`]'s format
+fn parse_commit(line: &str) -> Option<Commit> {
+    let mut fields = line.split(FIELD_SEPARATOR);
+    let mut next = || fields.next().map(str::to_string);
+    let (hash, short_hash, author, date, subject) = (next()?, next()?, next()?, next()?, next()?);
+    Some(Commit {
+        hash,
+        short_hash,
+        author,
+        date: DateTime::parse_from_rfc3339(&date)
+            .ok()?
+            .with_timezone(&Utc),
+        subject,
+    })
+}
+
+async fn log_with(
+    program: &str,
+    root: &Path,
+    query: &LogQuery,
+) -> Result<Vec<Commit>, GitUnavailable> {
+    let stamp = |at: DateTime<Utc>| at.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let mut args = vec![
+        "log".to_string(),
+        "--no-color".to_string(),
+        format!("--max-count={}", query.limit),
+        "--pretty=format:%H%x1f%h%x1f%an%x1f%aI%x1f%s".to_string(),
+    ];
+    if let Some(since) = query.since {
+        args.push(format!("--since={}", stamp(since)));
+    }
+    if let Some(until) = query.until {
+        args.push(format!("--until={}", stamp(until)));
+    }
+    if let Some(text) = query.mentioning.as_deref().filter(|t| !t.is_empty()) {
+        args.push("--fixed-strings".to_string());
+        args.push("--regexp-ignore-case".to_string());
+        args.push(format!("--grep={}", text));
+    }
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = run(program, root, &args, LOG_TIMEOUT).await?;
+    Ok(output.lines().filter_map(parse_commit).collect())
+}
+
+/// Commits of the checked-out branch in `root` matching `query`, newest
+/// first
+pub async fn log(root: &Path, query: &LogQuery) -> Result<Vec<Commit>, GitUnavailable> {
+    log_with("git", root, query).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    #[tokio::test]
+    async fn test_info_of_repository() {
+        if std::process::Command::new("git")
+            .arg("--version")
+            .output()
+            .is_err()
+        {
+            return;
+        }
+        let dir = std::env::temp_dir().join(format!("apply-task-git-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert_eq!(info(&dir).await, Err(GitUnavailable::NotARepository));
+        git(&dir, &["init", "--quiet", "--initial-branch=work"]);
+        // No commit yet
+        assert_eq!(info(&dir).await, Err(GitUnavailable::NotARepository));
+
+        git(&dir, &["commit", "--quiet", "--allow-empty", "-m", "init"]);
+        let found = info(&dir).await.unwrap();
+        assert_eq!(found.branch.as_deref(), Some("work"));
+        assert!(found.commit.len() >= 4, "{:?}", found);
+        assert_eq!(found.label(), format!("work@{}", found.commit));
+
+        git(&dir, &["checkout", "--quiet", "--detach"]);
+        let detached = info(&dir).await.unwrap();
+        assert_eq!(detached.branch, None);
+        assert_eq!(detached.label(), found.commit);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_log_since_and_mentioning() {
+        if std::process::Command::new("git")
+            .arg("--version")
+            .output()
+            .is_err()
+        {
+            return;
+        }
+        let dir = std::env::temp_dir().join(format!("apply-task-git-log-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let query = LogQuery {
+            limit: 10,
+            ..LogQuery::default()
+        };
+        assert_eq!(log(&dir, &query).await, Err(GitUnavailable::NotARepository));
+
+        git(&dir, &["init", "--quiet"]);
+        assert_eq!(log(&dir, &query).await, Err(GitUnavailable::NotARepository));
+        for (date, message) in [
+            ("2026-01-01T10:00:00Z", "Old work"),
+            ("2026-03-01T10:00:00Z", "Start task-12"),
+            ("2026-03-02T10:00:00Z", "Unrelated | fix"),
+            ("2026-03-03T10:00:00Z", "Finish TASK-12"),
+        ] {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=Ann Lee", "-c", "user.email=a@example.com"])
+                .args(["commit", "--quiet", "--allow-empty", "-m", message])
+                .env("GIT_AUTHOR_DATE", date)
+                .env("GIT_COMMITTER_DATE", date)
+                .current_dir(&dir)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+
+        let all = log(&dir, &query).await.unwrap();
+        let subjects: Vec<_> = all.iter().map(|c| c.subject.as_str()).collect();
+        assert_eq!(
+            subjects,
+            [
+                "Finish TASK-12",
+                "Unrelated | fix",
+                "Start task-12",
+                "Old work"
+            ]
+        );
+        assert_eq!(all[0].author, "Ann Lee");
+        assert_eq!(all[0].date.to_rfc3339(), "2026-03-03T10:00:00+00:00");
+        assert!(all[0].hash.starts_with(&all[0].short_hash));
+
+        let since = DateTime::parse_from_rfc3339("2026-02-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let recent = LogQuery {
+            since: Some(since),
+            limit: 2,
+            ..LogQuery::default()
+        };
+        let subjects: Vec<_> = log(&dir, &recent)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| c.subject)
+            .collect();
+        assert_eq!(subjects, ["Finish TASK-12", "Unrelated | fix"]);
+
+        let mentioning = LogQuery {
+            since: Some(since),
+            mentioning: Some("Task-12".to_string()),
+            limit: 10,
+            ..LogQuery::default()
+        };
+        let subjects: Vec<_> = log(&dir, &mentioning)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| c.subject)
+            .collect();
+        assert_eq!(subjects, ["Finish TASK-12", "Start task-12"]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_missing_git() {
+        assert_eq!(
+            info_with("apply-task-no-such-git", &std::env::temp_dir()).await,
+            Err(GitUnavailable::NotInstalled)
+        );
+    }
+}
@@ -0,0 +1,458 @@
+//! Headless command runner
+//!
+//! `apply-task-gui --headless [--project DIR] [--namespace NS] COMMAND
+//! [--ARG VALUE]...` runs one command handler without a window, on the
+//! `AppState`, settings and backend the GUI would use, prints the response
+//! JSON on stdout and exits 1 when it says `success: false` (2 on a usage
+//! error). Argument names are the command's (`--task-id` for `task_id`);
+//! values are strings, except for the boolean, number and list arguments
+//! ([`TYPED_ARGS`]), which are read as JSON, and a flag without a value is
+//! `true`.
+//! There is no app, so nothing is emitted and the offline queue is not
+//! replayed first.
+
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::backend::ListFilters;
+use crate::cli::{CliArgs, CliError};
+use crate::commands;
+use crate::deep_link;
+use crate::doctor;
+use crate::error_catalog::ResponseError;
+use crate::lifecycle;
+use crate::settings::{self, Settings};
+use crate::{new_state, AppState, Startup};
+
+/// First argument that selects this mode
+pub const FLAG: &str = "--headless";
+/// Commands that can run headless
+pub const COMMANDS: [&str; 6] = [
+    "tasks_list",
+    "tasks_show",
+    "quick_create",
+    "tasks_update_status",
+    "tasks_delete",
+    "doctor",
+];
+
+/// Arguments that aren't strings: their values are read as JSON
+pub const TYPED_ARGS: [&str; 9] = [
+    "compact",
+    "include_children",
+    "depth",
+    "projection",
+    "timeout_ms",
+    "debounce_ms",
+    "cascade",
+    "force_refresh",
+    "expected_revision",
+];
+
+pub const USAGE: &str = "\
+Usage: apply-task-gui --headless [--project DIR] [--namespace NS] COMMAND [--ARG VALUE]...
+
+Commands:
+  tasks_list            [--status S] [--domain D] [--parent ID] [--compact BOOL]
+                        [--projection '[\"id\",\"title\"]']
+  tasks_show            --task-id ID [--include-children] [--depth N]
+  quick_create          --text 'Fix login #auth !high @backend'
+  tasks_update_status   --task-id ID --status TODO|ACTIVE|DONE [--expected-revision N]
+  tasks_delete          --task-id ID [--cascade] [--yes] [--expected-revision N]
+                        [--path s:0 [--kind step|task]]
+  doctor
+
+  --project DIR         Run in this apply_task project
+  --namespace NS        Refuse unless NS is the project's namespace
+  --yes                 Delete without the confirmation step
+  -h, --help            Show this help";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Invocation {
+    pub project: Option<PathBuf>,
+    pub namespace: Option<String>,
+    pub command: String,
+    /// Command arguments by name (`task_id`, not `--task-id`), as given
+    pub args: Map<String, Value>,
+    pub yes: bool,
+}
+
+/// Parse the arguments after `--headless`
+pub fn parse(args: &[String]) -> Result<Invocation, CliError> {
+    let mut parsed = Invocation::default();
+    let mut iter = args.iter().peekable();
+    while let Some(arg) = iter.next() {
+        let Some(name) = arg.strip_prefix("--") else {
+            if arg == "-h" {
+                return Err(CliError::Help);
+            }
+            if !parsed.command.is_empty() {
+                return Err(CliError::Usage(format!("unexpected argument: {}", arg)));
+            }
+            if !COMMANDS.contains(&arg.as_str()) {
+                return Err(CliError::Usage(format!("unknown command: {}", arg)));
+            }
+            parsed.command = arg.clone();
+            continue;
+        };
+
+        let (name, inline) = match name.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (name, None),
+        };
+        let value = inline.or_else(|| iter.next_if(|next| !next.starts_with("--")).cloned());
+        match name {
+            "help" => return Err(CliError::Help),
+            "yes" => parsed.yes = true,
+            "project" | "namespace" => {
+                let value = value
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+                    .ok_or_else(|| CliError::Usage(format!("--{} needs a value", name)))?;
+                match name {
+                    "project" => parsed.project = Some(PathBuf::from(value)),
+                    _ => parsed.namespace = Some(value),
+                }
+            }
+            _ => {
+                let value = value.map(Value::String).unwrap_or(Value::Bool(true));
+                parsed.args.insert(name.replace('-', "_"), value);
+            }
+        }
+    }
+    if parsed.command.is_empty() {
+        return Err(CliError::Usage("no command given".to_string()));
+    }
+    Ok(parsed)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ListArgs {
+    status: Option<String>,
+    domain: Option<String>,
+    parent: Option<String>,
+    compact: Option<bool>,
+    projection: Option<Vec<String>>,
+    force_refresh: Option<bool>,
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ShowArgs {
+    task_id: String,
+    include_children: Option<bool>,
+    depth: Option<u8>,
+    force_refresh: Option<bool>,
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CreateArgs {
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StatusArgs {
+    task_id: String,
+    status: String,
+    debounce_ms: Option<u64>,
+    expected_revision: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DeleteArgs {
+    task_id: String,
+    confirm_token: Option<String>,
+    cascade: Option<bool>,
+    expected_revision: Option<u64>,
+    path: Option<String>,
+    kind: Option<commands::NodeKind>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NoArgs {}
+
+/// Response of a refused invocation
+#[derive(Serialize)]
+struct Refused {
+    success: bool,
+    #[serde(flatten)]
+    error: ResponseError,
+}
+
+fn args_of<T: DeserializeOwned>(invocation: &Invocation) -> Result<T, String> {
+    let args = invocation
+        .args
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                Value::String(text) if TYPED_ARGS.contains(&name.as_str()) => {
+                    serde_json::from_str(text).unwrap_or_else(|_| value.clone())
+                }
+                _ => value.clone(),
+            };
+            (name.clone(), value)
+        })
+        .collect();
+    serde_json::from_value(Value::Object(args))
+        .map_err(|e| format!("{}: {}", invocation.command, e))
+}
+
+fn to_json(response: impl Serialize) -> Value {
+    serde_json::to_value(response)
+        .unwrap_or_else(|e| json!({ "success": false, "error": e.to_string() }))
+}
+
+/// Response of the invocation, or what is wrong with its arguments
+async fn execute(state: &AppState, invocation: &Invocation) -> Result<Value, String> {
+    if let Some(namespace) = &invocation.namespace {
+        if let Some(error) = deep_link::namespace_error(state, namespace).await {
+            return Ok(to_json(Refused {
+                success: false,
+                error: error.into(),
+            }));
+        }
+    }
+    Ok(match invocation.command.as_str() {
+        "tasks_list" => {
+            let args: ListArgs = args_of(invocation)?;
+            let filters = ListFilters {
+                status: args.status,
+                domain: args.domain,
+                parent: args.parent,
+            };
+            to_json(
+                commands::list_response(
+                    state,
+                    &filters,
+                    args.compact,
+                    args.projection,
+                    args.force_refresh,
+                    args.timeout_ms,
+                )
+                .await,
+            )
+        }
+        "tasks_show" => {
+            let args: ShowArgs = args_of(invocation)?;
+            to_json(
+                commands::show_response(
+                    state,
+                    &args.task_id,
+                    args.include_children,
+                    args.depth,
+                    args.force_refresh,
+                    args.timeout_ms,
+                )
+                .await,
+            )
+        }
+        "quick_create" => {
+            let args: CreateArgs = args_of(invocation)?;
+            to_json(commands::quick_create_response(None, state, &args.text).await)
+        }
+        "tasks_update_status" => {
+            let args: StatusArgs = args_of(invocation)?;
+            to_json(
+                commands::status_response(
+                    None,
+                    state,
+                    args.task_id,
+                    args.status,
+                    args.debounce_ms,
+                    args.expected_revision,
+                )
+                .await,
+            )
+        }
+        "tasks_delete" => {
+            let args: DeleteArgs = args_of(invocation)?;
+            let node = args.path.map(|path| commands::DeleteNode {
+                path,
+                kind: args.kind.unwrap_or_default(),
+            });
+            let mut response = commands::delete_response(
+                state,
+                args.task_id.clone(),
+                args.confirm_token,
+                args.cascade,
+                args.expected_revision,
+                node.clone(),
+            )
+            .await;
+            // What the GUI sends once the user confirms
+            if let Some(token) = response.confirm_token.clone().filter(|_| invocation.yes) {
+                response = commands::delete_response(
+                    state,
+                    args.task_id,
+                    Some(token),
+                    args.cascade,
+                    args.expected_revision,
+                    node,
+                )
+                .await;
+            }
+            to_json(response)
+        }
+        _ => {
+            let NoArgs {} = args_of(invocation)?;
+            to_json(doctor::run_checks(state).await)
+        }
+    })
+}
+
+/// Run the invocation in `args` (after `--headless`); returns the exit status
+pub(crate) fn run(
+    args: &[String],
+    launch_dir: PathBuf,
+    env_hint: Option<PathBuf>,
+    identifier: &str,
+) -> i32 {
+    let invocation = match parse(args) {
+        Ok(invocation) => invocation,
+        Err(CliError::Help) => {
+            println!("{}", USAGE);
+            return 0;
+        }
+        Err(CliError::Usage(e)) => {
+            eprintln!("apply-task-gui: {}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+    // Where Tauri's path resolver puts them
+    let (Some(data_dir), Some(config_dir)) = (dirs::data_dir(), dirs::config_dir()) else {
+        eprintln!("apply-task-gui: no app data or config directory");
+        return 1;
+    };
+    let (data_dir, config_dir) = (data_dir.join(identifier), config_dir.join(identifier));
+
+    let cli = CliArgs {
+        project: invocation.project.clone(),
+        ..CliArgs::default()
+    };
+    let startup = Startup::resolve(&cli, launch_dir, env_hint);
+    settings::migrate_legacy(&config_dir, &data_dir);
+    let state = new_state(&startup, Settings::load(&config_dir), data_dir, config_dir);
+
+    let response = tauri::async_runtime::block_on(async {
+        let response = execute(&state, &invocation).await;
+        if let Err(e) = state
+            .bridge
+            .shutdown_graceful(lifecycle::SHUTDOWN_GRACE)
+            .await
+        {
+            log::warn!("Failed to stop Python bridge: {}", e);
+        }
+        response
+    });
+    match response {
+        Ok(response) => {
+            println!("{}", response);
+            match response.get("success") {
+                Some(Value::Bool(false)) => 1,
+                _ => 0,
+            }
+        }
+        Err(e) => {
+            eprintln!("apply-task-gui: {}\n\n{}", e, USAGE);
+            2
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let parsed = parse(&args(&[
+            "--namespace",
+            "work",
+            "tasks_list",
+            "--status=TODO",
+            "--compact",
+            "false",
+            "--projection",
+            r#"["id","title"]"#,
+        ]))
+        .unwrap();
+        assert_eq!(parsed.namespace.as_deref(), Some("work"));
+        assert_eq!(parsed.command, "tasks_list");
+        assert_eq!(
+            Value::Object(parsed.args.clone()),
+            json!({ "status": "TODO", "compact": "false", "projection": r#"["id","title"]"# })
+        );
+        let list: ListArgs = args_of(&parsed).unwrap();
+        assert_eq!(list.compact, Some(false));
+        assert_eq!(list.projection.unwrap(), ["id", "title"]);
+
+        // Strings stay strings, whatever they look like
+        let parsed = parse(&args(&[
+            "tasks_list",
+            "--parent",
+            "123",
+            "--status",
+            "null",
+        ]))
+        .unwrap();
+        let list: ListArgs = args_of(&parsed).unwrap();
+        assert_eq!(list.parent.as_deref(), Some("123"));
+        assert_eq!(list.status.as_deref(), Some("null"));
+        let parsed = parse(&args(&["quick_create", "--text", r#""quoted""#])).unwrap();
+        let create: CreateArgs = args_of(&parsed).unwrap();
+        assert_eq!(create.text, r#""quoted""#);
+
+        let parsed = parse(&args(&[
+            "tasks_delete",
+            "--task-id",
+            "TASK-1",
+            "--cascade",
+            "--yes",
+            "--project",
+            "~/p",
+        ]))
+        .unwrap();
+        assert_eq!(
+            Value::Object(parsed.args),
+            json!({ "task_id": "TASK-1", "cascade": true })
+        );
+        assert!(parsed.yes);
+        assert_eq!(parsed.project, Some(PathBuf::from("~/p")));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let usage = |list: &[&str]| match parse(&args(list)) {
+            Err(CliError::Usage(e)) => e,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(usage(&[]), "no command given");
+        assert_eq!(usage(&["tasks_edit"]), "unknown command: tasks_edit");
+        assert_eq!(usage(&["doctor", "doctor"]), "unexpected argument: doctor");
+        assert_eq!(usage(&["doctor", "--project"]), "--project needs a value");
+        assert_eq!(parse(&args(&["doctor", "--help"])), Err(CliError::Help));
+
+        // Unknown and mistyped arguments are caught against the command
+        let invocation = parse(&args(&["tasks_show", "--task", "TASK-1"])).unwrap();
+        assert!(args_of::<ShowArgs>(&invocation)
+            .unwrap_err()
+            .starts_with("tasks_show: unknown field `task`"));
+        let invocation =
+            parse(&args(&["tasks_show", "--task-id", "7", "--depth", "deep"])).unwrap();
+        assert!(args_of::<ShowArgs>(&invocation).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+//! iCalendar export of due dates
+//!
+//! One VTODO per task with a due date (`DUE;VALUE=DATE`), so calendar apps
+//! show it on that day. The UID is the task id plus the project's sidecar
+//! key, which keeps it stable across exports of the same checkout: importing
+//! again updates entries instead of duplicating them. Text values are
+//! escaped and lines folded at 75 octets as RFC 5545 asks.
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::backend::task_str;
+use crate::due::DueTask;
+
+/// Longest content line, in octets, before folding
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Escape a TEXT value (backslash, `;`, `,` and line breaks)
+pub fn escape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                out.push_str("\\n");
+            }
+            '\n' => out.push_str("\\n"),
+            // Other controls are not allowed in TEXT
+            c if c.is_control() && c != '\t' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// `line` split into CRLF-terminated lines of at most 75 octets, without
+/// cutting a UTF-8 sequence (continuations start with a space)
+pub fn fold(line: &str) -> String {
+    let mut out = String::with_capacity(line.len() + 8);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+/// VTODO `STATUS` of a task status
+pub fn todo_status(status: &str) -> &'static str {
+    match status.trim().to_uppercase().as_str() {
+        "ACTIVE" | "IN_PROGRESS" => "IN-PROCESS",
+        "DONE" => "COMPLETED",
+        _ => "NEEDS-ACTION",
+    }
+}
+
+/// UID of `task_id` in the project with sidecar key `project_key`
+pub fn uid(task_id: &str, project_key: &str) -> String {
+    format!("{}.{}@apply-task", task_id, project_key)
+}
+
+/// The calendar: one VTODO per entry of `due`, descriptions looked up by id
+/// in `tasks` (the full payloads)
+pub fn calendar(
+    due: &[DueTask],
+    tasks: &[Value],
+    project_key: &str,
+    stamp: DateTime<Utc>,
+) -> String {
+    let mut out = String::new();
+    let mut line = |name: &str, value: &str| out.push_str(&fold(&format!("{}:{}", name, value)));
+    line("BEGIN", "VCALENDAR");
+    line("VERSION", "2.0");
+    line("PRODID", "-//apply_task//apply-task-gui//EN");
+    line("CALSCALE", "GREGORIAN");
+    let stamp = stamp.format("%Y%m%dT%H%M%SZ").to_string();
+    for entry in due {
+        let description = tasks
+            .iter()
+            .find(|task| task_str(task, "id") == Some(entry.task_id.as_str()))
+            .and_then(|task| task_str(task, "description"))
+            .map(str::trim)
+            .unwrap_or_default();
+        line("BEGIN", "VTODO");
+        line("UID", &escape_text(&uid(&entry.task_id, project_key)));
+        line("DTSTAMP", &stamp);
+        line("SUMMARY", &escape_text(&entry.title));
+        if !description.is_empty() {
+            line("DESCRIPTION", &escape_text(description));
+        }
+        line("DUE;VALUE=DATE", &entry.due.format("%Y%m%d").to_string());
+        line("STATUS", todo_status(&entry.status));
+        line("END", "VTODO");
+    }
+    line("END", "VCALENDAR");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use serde_json::json;
+
+    /// Undo `fold` (RFC 5545 unfolding)
+    fn unfold(text: &str) -> String {
+        text.replace("\r\n ", "")
+    }
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(
+            escape_text("a\\b; c, d\r\ne\nf\rg"),
+            "a\\\\b\\; c\\, d\\ne\\nf\\ng"
+        );
+        // Already escaped-looking text is escaped again, not passed through
+        assert_eq!(escape_text("\\n"), "\\\\n");
+        assert_eq!(escape_text("tab\tbell\u{7}"), "tab\tbell");
+        assert_eq!(escape_text("colon: fine"), "colon: fine");
+    }
+
+    #[test]
+    fn test_fold_at_75_octets() {
+        assert_eq!(fold("SUMMARY:short"), "SUMMARY:short\r\n");
+
+        let long = format!("DESCRIPTION:{}", "x".repeat(200));
+        let folded = fold(&long);
+        for line in folded.split_terminator("\r\n") {
+            assert!(line.len() <= MAX_LINE_OCTETS, "{:?}", line);
+        }
+        assert_eq!(folded.split_terminator("\r\n").next().unwrap().len(), 75);
+        assert_eq!(unfold(&folded), format!("{}\r\n", long));
+
+        // Multi-byte characters are never split across lines
+        let wide = format!("DESCRIPTION:{}", "ж€😀".repeat(40));
+        let folded = fold(&wide);
+        for line in folded.split_terminator("\r\n") {
+            assert!(line.len() <= MAX_LINE_OCTETS, "{:?}", line);
+        }
+        assert_eq!(unfold(&folded), format!("{}\r\n", wide));
+
+        // Exactly 75 octets stays on one line
+        let exact = "x".repeat(75);
+        assert_eq!(fold(&exact), format!("{}\r\n", exact));
+    }
+
+    #[test]
+    fn test_calendar() {
+        let due = vec![
+            DueTask {
+                task_id: "TASK-001".into(),
+                title: "Ship v2, finally; really".into(),
+                status: "ACTIVE".into(),
+                due: NaiveDate::from_ymd_opt(2026, 10, 20).unwrap(),
+                source: "sidecar".into(),
+            },
+            DueTask {
+                task_id: "TASK-002".into(),
+                title: "No description".into(),
+                status: "DONE".into(),
+                due: NaiveDate::from_ymd_opt(2026, 11, 1).unwrap(),
+                source: "backend".into(),
+            },
+        ];
+        let description = format!("Line one\nLine two, with \\ and ;\n{}", "долго ".repeat(30));
+        let tasks = vec![
+            json!({ "id": "TASK-001", "description": description }),
+            json!({ "id": "TASK-002", "description": "  " }),
+        ];
+        let stamp = DateTime::parse_from_rfc3339("2026-10-14T08:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let ics = calendar(&due, &tasks, "crate-00ff", stamp);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VTODO\r\nEND:VCALENDAR\r\n"));
+        for line in ics.split_terminator("\r\n") {
+            assert!(line.len() <= MAX_LINE_OCTETS, "{:?}", line);
+        }
+        let lines: Vec<String> = unfold(&ics)
+            .split_terminator("\r\n")
+            .map(String::from)
+            .collect();
+        assert!(lines.contains(&"UID:TASK-001.crate-00ff@apply-task".to_string()));
+        assert!(lines.contains(&"SUMMARY:Ship v2\\, finally\\; really".to_string()));
+        assert!(lines.contains(&format!("DESCRIPTION:{}", escape_text(description.trim()))));
+        assert!(lines.contains(&"DUE;VALUE=DATE:20261020".to_string()));
+        assert!(lines.contains(&"STATUS:IN-PROCESS".to_string()));
+        assert!(lines.contains(&"STATUS:COMPLETED".to_string()));
+        assert!(lines.contains(&"DTSTAMP:20261014T080000Z".to_string()));
+        // Blank descriptions are left out
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|l| l.starts_with("DESCRIPTION"))
+                .count(),
+            1
+        );
+    }
+}
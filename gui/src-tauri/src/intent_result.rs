@@ -0,0 +1,222 @@
+//! Typed views of `ai_intent` results
+//!
+//! `AIResponse.typed` carries the result of the intents the GUI renders in a
+//! fixed shape, picked by the tool that ran. A successful result that doesn't
+//! parse (or a tool without a variant) comes back as `Raw`, failures as no
+//! `typed` at all, so the frontend can move over one intent at a time.
+//! Serialized as `{ "kind": ..., "data": ... }`; history and raw results
+//! aren't objects, so the tag can't live inside them.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::context::ContextResponse;
+use crate::subtask_plan::SubtaskPlan;
+
+/// Checkpoint state after `tasks_verify`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CheckpointState {
+    pub confirmed: bool,
+    pub auto_confirmed: bool,
+    pub notes_count: u64,
+    pub evidence_refs_count: u64,
+}
+
+/// One `tasks_history` operation (snapshots and payloads left out)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryEntry {
+    pub id: String,
+    /// Unix seconds
+    pub timestamp: f64,
+    pub intent: String,
+    pub task_id: Option<String>,
+    /// `ops` (undoable writes) or `audit`
+    pub stream: String,
+    /// `write` or `read`
+    pub effect: String,
+    pub task_file: Option<String>,
+    pub undone: bool,
+}
+
+/// Recommended next step (`tasks_radar` `next`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NextAction {
+    pub action: String,
+    /// Tool to call
+    pub target: String,
+    pub reason: String,
+    pub priority: String,
+    pub validated: bool,
+    pub params: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+pub enum IntentResult {
+    Context(Box<ContextResponse>),
+    /// New task or plan
+    Created {
+        task_id: String,
+    },
+    /// Checkpoints of the verified node
+    Verified {
+        checkpoints: BTreeMap<String, CheckpointState>,
+    },
+    /// Oldest first
+    History(Vec<HistoryEntry>),
+    Next(NextAction),
+    Subtasks(SubtaskPlan),
+    /// `result` as the backend sent it
+    Raw(Value),
+}
+
+impl IntentResult {
+    /// Typed result of a `tool` envelope (`None` when it failed)
+    pub fn from_envelope(tool: &str, envelope: &Value) -> Option<Self> {
+        if envelope.get("success").and_then(Value::as_bool) != Some(true) {
+            return None;
+        }
+        let result = envelope.get("result").cloned().unwrap_or(Value::Null);
+        let typed = match tool {
+            "tasks_context" => Some(Self::Context(Box::new(ContextResponse::from_response(
+                envelope.clone(),
+            )))),
+            "tasks_create" => ["task_id", "plan_id"]
+                .iter()
+                .find_map(|key| result.get(*key).and_then(Value::as_str))
+                .map(|id| Self::Created {
+                    task_id: id.to_string(),
+                }),
+            "tasks_verify" => parse(result.get("checkpoints_after"))
+                .map(|checkpoints| Self::Verified { checkpoints }),
+            "tasks_history" => parse(result.get("operations")).map(Self::History),
+            "tasks_radar" => parse(result.pointer("/next/0")).map(Self::Next),
+            "tasks_decompose" => SubtaskPlan::from_result(tool, &result).map(Self::Subtasks),
+            _ => None,
+        };
+        Some(typed.unwrap_or(Self::Raw(result)))
+    }
+}
+
+fn parse<T: serde::de::DeserializeOwned>(value: Option<&Value>) -> Option<T> {
+    serde_json::from_value(value?.clone()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fixture(tool: &str) -> IntentResult {
+        let text = match tool {
+            "tasks_context" => include_str!("../tests/fixtures/tasks_context.json"),
+            "tasks_create" => include_str!("../tests/fixtures/intents/tasks_create.json"),
+            "tasks_verify" => include_str!("../tests/fixtures/intents/tasks_verify.json"),
+            "tasks_history" => include_str!("../tests/fixtures/intents/tasks_history.json"),
+            "tasks_radar" => include_str!("../tests/fixtures/intents/tasks_radar.json"),
+            "tasks_decompose" => include_str!("../tests/fixtures/tasks_decompose.json"),
+            other => panic!("no fixture for {}", other),
+        };
+        let envelope: Value = serde_json::from_str(text).unwrap();
+        IntentResult::from_envelope(tool, &envelope).unwrap()
+    }
+
+    #[test]
+    fn test_context_fixture() {
+        let IntentResult::Context(ctx) = fixture("tasks_context") else {
+            panic!("not a context");
+        };
+        assert_eq!(ctx.focus_id.as_deref(), Some("TASK-001"));
+        assert_eq!(ctx.tasks.len(), 2);
+    }
+
+    #[test]
+    fn test_created_fixture() {
+        let IntentResult::Created { task_id } = fixture("tasks_create") else {
+            panic!("not a creation");
+        };
+        assert_eq!(task_id, "TASK-001");
+
+        let plan = json!({ "success": true, "result": { "plan_id": "PLAN-002" } });
+        assert!(matches!(
+            IntentResult::from_envelope("tasks_create", &plan),
+            Some(IntentResult::Created { task_id }) if task_id == "PLAN-002"
+        ));
+    }
+
+    #[test]
+    fn test_verified_fixture() {
+        let IntentResult::Verified { checkpoints } = fixture("tasks_verify") else {
+            panic!("not a verification");
+        };
+        assert_eq!(
+            checkpoints["criteria"],
+            CheckpointState {
+                confirmed: true,
+                auto_confirmed: false,
+                notes_count: 1,
+                evidence_refs_count: 1,
+            }
+        );
+        assert!(!checkpoints["tests"].confirmed);
+    }
+
+    #[test]
+    fn test_history_fixture() {
+        let IntentResult::History(entries) = fixture("tasks_history") else {
+            panic!("not a history");
+        };
+        let intents: Vec<&str> = entries.iter().map(|e| e.intent.as_str()).collect();
+        assert_eq!(intents, ["create", "create", "verify"]);
+        assert_eq!(entries[0].task_id.as_deref(), Some("PLAN-001"));
+        assert_eq!(entries[0].task_file.as_deref(), Some("PLAN-001.task"));
+        assert_eq!(entries[0].effect, "write");
+        assert!(!entries[0].undone);
+    }
+
+    #[test]
+    fn test_next_fixture() {
+        let IntentResult::Next(next) = fixture("tasks_radar") else {
+            panic!("not a next action");
+        };
+        assert_eq!(next.action, "patch");
+        assert_eq!(next.target, "tasks_patch");
+        assert!(next.validated);
+        assert_eq!(next.params.unwrap()["task"], "TASK-001");
+    }
+
+    #[test]
+    fn test_decompose_fixture() {
+        let IntentResult::Subtasks(plan) = fixture("tasks_decompose") else {
+            panic!("not a subtask plan");
+        };
+        assert_eq!(plan.task_id.as_deref(), Some("TASK-001"));
+        let titles: Vec<&str> = plan.steps.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["Reproduce", "Write the failing test"]);
+        assert_eq!(plan.steps[1].path.as_deref(), Some("s:1"));
+        assert_eq!(plan.steps[1].success_criteria, ["test fails on main"]);
+    }
+
+    #[test]
+    fn test_raw_fallback_and_failures() {
+        let radar = json!({ "success": true, "result": { "next": [] } });
+        assert!(matches!(
+            IntentResult::from_envelope("tasks_radar", &radar),
+            Some(IntentResult::Raw(result)) if result == json!({ "next": [] })
+        ));
+        let note = json!({ "success": true, "result": ["a"] });
+        let typed = IntentResult::from_envelope("tasks_note", &note).unwrap();
+        assert_eq!(
+            serde_json::to_value(typed).unwrap(),
+            json!({ "kind": "raw", "data": ["a"] })
+        );
+
+        let failed = json!({ "success": false, "error": { "code": "NOT_FOUND" } });
+        assert!(IntentResult::from_envelope("tasks_create", &failed).is_none());
+    }
+}
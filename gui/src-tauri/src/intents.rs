@@ -0,0 +1,240 @@
+//! Intent → MCP tool resolution
+//!
+//! `ai_intent` resolves in order: user aliases from the settings file, the
+//! static alias table (stable names the GUI relies on), then tools advertised
+//! by `tools/list`, and finally — only when `strict` is off — the unlisted
+//! `tasks_<intent>` fallback.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde_json::Value;
+
+/// Rejected user aliases
+#[derive(Debug, thiserror::Error)]
+pub enum AliasError {
+    #[error("Invalid alias name: \"{0}\"")]
+    InvalidName(String),
+    #[error("Invalid tool for alias \"{name}\": \"{tool}\"")]
+    InvalidTool { name: String, tool: String },
+    #[error("Aliases to destructive tools need confirm_destructive: {0}")]
+    DestructiveUnconfirmed(String),
+}
+
+/// User-defined intent -> tool map
+pub type UserAliases = BTreeMap<String, String>;
+
+/// Tools that remove data; aliasing them needs explicit confirmation
+const DESTRUCTIVE_TOOLS: [&str; 3] = ["tasks_delete", "tasks_task_delete", "tasks_batch"];
+
+/// Tools that only read storage (calling anything else may write task
+/// files). The one list behind cache invalidation, offline queueing and
+/// read-only mode: a tool missing here counts as a mutation.
+const READ_ONLY_TOOLS: &[&str] = &[
+    "tasks_ai_status",
+    "tasks_context",
+    "tasks_context_pack",
+    "tasks_delta",
+    "tasks_focus_get",
+    "tasks_handoff",
+    "tasks_history",
+    "tasks_lint",
+    "tasks_mirror",
+    "tasks_radar",
+    "tasks_resume",
+    "tasks_storage",
+    "tasks_templates_list",
+];
+
+/// Tool name prefix used by the backend
+const TOOL_PREFIX: &str = "tasks_";
+
+/// Intents the GUI depends on, pinned to their tool names
+const INTENT_ALIASES: &[(&str, &str)] = &[
+    ("batch", "tasks_batch"),
+    ("block", "tasks_block"),
+    ("close_step", "tasks_close_step"),
+    ("close_task", "tasks_close_task"),
+    ("complete", "tasks_complete"),
+    ("context", "tasks_context"),
+    ("contract", "tasks_contract"),
+    ("create", "tasks_create"),
+    ("decompose", "tasks_decompose"),
+    ("define", "tasks_define"),
+    ("delete", "tasks_delete"),
+    ("done", "tasks_done"),
+    ("edit", "tasks_edit"),
+    ("handoff", "tasks_handoff"),
+    ("history", "tasks_history"),
+    ("mirror", "tasks_mirror"),
+    ("note", "tasks_note"),
+    ("patch", "tasks_patch"),
+    ("plan", "tasks_plan"),
+    ("progress", "tasks_progress"),
+    ("radar", "tasks_radar"),
+    ("redo", "tasks_redo"),
+    ("resume", "tasks_resume"),
+    ("storage", "tasks_storage"),
+    ("task_add", "tasks_task_add"),
+    ("task_define", "tasks_task_define"),
+    ("task_delete", "tasks_task_delete"),
+    ("templates_list", "tasks_templates_list"),
+    ("undo", "tasks_undo"),
+    ("verify", "tasks_verify"),
+];
+
+/// Lowercased, trimmed intent
+pub fn normalize(intent: &str) -> String {
+    intent.trim().to_lowercase()
+}
+
+/// Tool pinned to `intent` in the static table
+pub fn alias(intent: &str) -> Option<&'static str> {
+    let intent = normalize(intent);
+    INTENT_ALIASES
+        .iter()
+        .find(|(name, _)| *name == intent)
+        .map(|(_, tool)| *tool)
+}
+
+/// Tool pinned to `intent` by user aliases or the static table
+pub fn pinned(intent: &str, user: &UserAliases) -> Option<String> {
+    user.get(&normalize(intent))
+        .cloned()
+        .or_else(|| alias(intent).map(String::from))
+}
+
+/// Whether calling `tool` may modify the task storage
+pub fn writes_storage(tool: &str) -> bool {
+    !READ_ONLY_TOOLS.contains(&tool)
+}
+
+/// Whether `tool` removes data
+pub fn is_destructive(tool: &str) -> bool {
+    DESTRUCTIVE_TOOLS.contains(&tool)
+}
+
+/// Validate and normalize a user alias map.
+///
+/// Aliases pointing at destructive tools are rejected unless
+/// `confirm_destructive` is set (the built-in name of that tool is exempt).
+pub fn validate_aliases(aliases: &UserAliases, confirm_destructive: bool) -> Result<UserAliases> {
+    let mut out = UserAliases::new();
+    let mut needs_confirm = Vec::new();
+
+    for (name, tool) in aliases {
+        let name = normalize(name);
+        let tool = tool.trim().to_string();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(AliasError::InvalidName(name).into());
+        }
+        if tool.is_empty() || tool.contains(char::is_whitespace) {
+            return Err(AliasError::InvalidTool { name, tool }.into());
+        }
+        if is_destructive(&tool) && alias(&name) != Some(tool.as_str()) {
+            needs_confirm.push(format!("{} -> {}", name, tool));
+        }
+        out.insert(name, tool);
+    }
+
+    if !needs_confirm.is_empty() && !confirm_destructive {
+        return Err(AliasError::DestructiveUnconfirmed(needs_confirm.join(", ")).into());
+    }
+    Ok(out)
+}
+
+fn tool_names(tools: &[Value]) -> impl Iterator<Item = &str> {
+    tools
+        .iter()
+        .filter_map(|t| t.get("name").and_then(|n| n.as_str()))
+}
+
+/// Resolve `intent` to a tool name, or `None` for an unknown intent.
+///
+/// `tools` is the cached `tools/list` (if available). The raw intent may
+/// name a tool directly (`tasks_lint`) or without the prefix (`lint`).
+pub fn resolve(
+    intent: &str,
+    user: &UserAliases,
+    tools: Option<&[Value]>,
+    strict: bool,
+) -> Option<String> {
+    if let Some(tool) = pinned(intent, user) {
+        return Some(tool);
+    }
+
+    let raw = intent.trim();
+    let normalized = normalize(intent);
+    if normalized.is_empty() {
+        return None;
+    }
+    let prefixed = format!("{}{}", TOOL_PREFIX, normalized);
+
+    if let Some(tools) = tools {
+        if let Some(name) = tool_names(tools).find(|n| *n == raw || *n == prefixed) {
+            return Some(name.to_string());
+        }
+    }
+
+    (!strict).then_some(prefixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_resolution_order() {
+        let tools = vec![
+            json!({"name": "tasks_context"}),
+            json!({"name": "tasks_lint"}),
+            json!({"name": "tasks_focus_get"}),
+        ];
+        let listed = Some(tools.as_slice());
+        let mut user = UserAliases::new();
+        user.insert("ship".into(), "tasks_complete".into());
+        user.insert("verify".into(), "tasks_lint".into());
+
+        let cases = [
+            (" Context ", listed, true, Some("tasks_context")),
+            ("done", None, true, Some("tasks_done")),
+            ("Ship", None, true, Some("tasks_complete")),
+            ("verify", None, true, Some("tasks_lint")),
+            ("lint", listed, true, Some("tasks_lint")),
+            ("tasks_focus_get", listed, true, Some("tasks_focus_get")),
+            ("lint", None, true, None),
+            ("ai_status", listed, true, None),
+            ("ai_status", listed, false, Some("tasks_ai_status")),
+            ("  ", listed, false, None),
+        ];
+
+        for (intent, tools, strict, expected) in cases {
+            assert_eq!(
+                resolve(intent, &user, tools, strict).as_deref(),
+                expected,
+                "intent {:?} strict {}",
+                intent,
+                strict
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_aliases_guards_destructive_targets() {
+        let mut aliases = UserAliases::new();
+        aliases.insert(" Ship ".into(), "tasks_complete".into());
+        aliases.insert("delete".into(), "tasks_delete".into());
+        let ok = validate_aliases(&aliases, false).unwrap();
+        assert_eq!(ok["ship"], "tasks_complete");
+
+        aliases.insert("tidy".into(), "tasks_delete".into());
+        let err = validate_aliases(&aliases, false).unwrap_err().to_string();
+        assert!(err.contains("tidy -> tasks_delete"), "{}", err);
+        assert!(!err.contains("delete -> "), "{}", err);
+        assert!(validate_aliases(&aliases, true).is_ok());
+
+        aliases.insert("two words".into(), "tasks_note".into());
+        assert!(validate_aliases(&aliases, true).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+//! Background jobs
+//!
+//! A job is one tool call running in a spawned task, detached from the
+//! webview that started it. Progress and completion are reported through
+//! `job-progress` / `job-finished` events; finished jobs are kept for
+//! [`RETENTION`] so a reloaded frontend can still pick up the result.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ai_response::AIResponse;
+
+/// Event carrying a job's latest progress notification
+pub const JOB_PROGRESS_EVENT: &str = "job-progress";
+/// Event carrying the finished [`JobInfo`]
+pub const JOB_FINISHED_EVENT: &str = "job-finished";
+/// How long finished jobs stay listed
+pub const RETENTION: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobInfo {
+    pub id: String,
+    pub intent: String,
+    pub tool: String,
+    pub status: JobStatus,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Params of the last `notifications/progress` for this job
+    pub progress: Option<Value>,
+    /// Tool response once finished (none when cancelled)
+    pub response: Option<AIResponse>,
+}
+
+/// Payload of [`JOB_PROGRESS_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobProgress {
+    pub job_id: String,
+    pub progress: Value,
+}
+
+/// Params of a `notifications/progress` addressed to `job_id`
+///
+/// Jobs use their id as the MCP `progressToken`.
+pub fn progress_for(notification: &Value, job_id: &str) -> Option<Value> {
+    if notification.get("method").and_then(|m| m.as_str()) != Some("notifications/progress") {
+        return None;
+    }
+    let params = notification.get("params")?;
+    (params.get("progressToken").and_then(|t| t.as_str()) == Some(job_id)).then(|| params.clone())
+}
+
+struct Entry {
+    info: JobInfo,
+    /// JSON-RPC id of the tool call, once sent
+    request_id: Option<u64>,
+    finished: Option<Instant>,
+}
+
+/// Running and recently finished jobs (kept in `AppState`)
+#[derive(Default)]
+pub struct JobRegistry {
+    jobs: HashMap<String, Entry>,
+    issued: u64,
+}
+
+impl JobRegistry {
+    /// Register a running job and return its id
+    pub fn create(&mut self, intent: &str, tool: &str, now: Instant) -> String {
+        self.prune(now);
+        self.issued += 1;
+        let id = format!("job-{}", self.issued);
+        self.jobs.insert(
+            id.clone(),
+            Entry {
+                info: JobInfo {
+                    id: id.clone(),
+                    intent: intent.to_string(),
+                    tool: tool.to_string(),
+                    status: JobStatus::Running,
+                    started_at: Utc::now(),
+                    finished_at: None,
+                    progress: None,
+                    response: None,
+                },
+                request_id: None,
+                finished: None,
+            },
+        );
+        id
+    }
+
+    /// Record the request id; `false` if the job was cancelled meanwhile
+    pub fn set_request(&mut self, id: &str, request_id: u64) -> bool {
+        match self.running(id) {
+            Some(entry) => {
+                entry.request_id = Some(request_id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Store a progress update; `false` if the job is no longer running
+    pub fn progress(&mut self, id: &str, progress: Value) -> bool {
+        match self.running(id) {
+            Some(entry) => {
+                entry.info.progress = Some(progress);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Finish a running job; `None` if it already finished (e.g. cancelled)
+    pub fn finish(
+        &mut self,
+        id: &str,
+        status: JobStatus,
+        response: Option<AIResponse>,
+        now: Instant,
+    ) -> Option<JobInfo> {
+        let entry = self.running(id)?;
+        entry.info.status = status;
+        entry.info.response = response;
+        entry.info.finished_at = Some(Utc::now());
+        entry.finished = Some(now);
+        Some(entry.info.clone())
+    }
+
+    /// Mark a running job cancelled; returns it and its request id
+    pub fn cancel(&mut self, id: &str, now: Instant) -> Option<(JobInfo, Option<u64>)> {
+        let request_id = self.running(id)?.request_id;
+        let info = self.finish(id, JobStatus::Cancelled, None, now)?;
+        Some((info, request_id))
+    }
+
+    /// Mark every running job cancelled (app exit); returns how many
+    pub fn cancel_all(&mut self, now: Instant) -> usize {
+        let running: Vec<String> = self
+            .jobs
+            .iter()
+            .filter(|(_, e)| e.info.status == JobStatus::Running)
+            .map(|(id, _)| id.clone())
+            .collect();
+        running
+            .iter()
+            .filter(|id| self.cancel(id, now).is_some())
+            .count()
+    }
+
+    /// Running and retained jobs, oldest first
+    pub fn list(&mut self, now: Instant) -> Vec<JobInfo> {
+        self.prune(now);
+        let mut jobs: Vec<JobInfo> = self.jobs.values().map(|e| e.info.clone()).collect();
+        jobs.sort_by_key(|j| j.started_at);
+        jobs
+    }
+
+    fn running(&mut self, id: &str) -> Option<&mut Entry> {
+        self.jobs
+            .get_mut(id)
+            .filter(|e| e.info.status == JobStatus::Running)
+    }
+
+    fn prune(&mut self, now: Instant) {
+        self.jobs.retain(|_, e| {
+            e.finished
+                .is_none_or(|at| now.saturating_duration_since(at) < RETENTION)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_job_lifecycle_and_retention() {
+        let mut jobs = JobRegistry::default();
+        let now = Instant::now();
+
+        let done = jobs.create("decompose", "tasks_decompose", now);
+        assert!(jobs.set_request(&done, 7));
+        assert!(jobs.progress(&done, json!({"progress": 1, "total": 3})));
+        let info = jobs
+            .finish(
+                &done,
+                JobStatus::Succeeded,
+                Some(AIResponse::default()),
+                now,
+            )
+            .unwrap();
+        assert_eq!(info.status, JobStatus::Succeeded);
+        assert!(!jobs.progress(&done, json!({})));
+        assert!(jobs.cancel(&done, now).is_none());
+
+        let cancelled = jobs.create("decompose", "tasks_decompose", now);
+        assert!(jobs.set_request(&cancelled, 8));
+        let (info, request_id) = jobs.cancel(&cancelled, now).unwrap();
+        assert_eq!((info.status, request_id), (JobStatus::Cancelled, Some(8)));
+        // A late result does not overwrite the cancellation
+        assert!(jobs
+            .finish(&cancelled, JobStatus::Succeeded, None, now)
+            .is_none());
+        assert!(!jobs.set_request(&cancelled, 9));
+
+        let running = jobs.create("plan", "tasks_plan", now);
+        assert_eq!(jobs.list(now).len(), 3);
+
+        let later = now + RETENTION;
+        let ids: Vec<String> = jobs.list(later).into_iter().map(|j| j.id).collect();
+        assert_eq!(ids, [running]);
+        assert_eq!(jobs.cancel_all(later), 1);
+        assert_eq!(jobs.cancel_all(later), 0);
+    }
+
+    #[test]
+    fn test_progress_for_matches_token() {
+        let note = json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/progress",
+            "params": {"progressToken": "job-2", "progress": 1, "total": 4}
+        });
+        assert_eq!(progress_for(&note, "job-2").unwrap()["total"], 4);
+        assert_eq!(progress_for(&note, "job-3"), None);
+        assert_eq!(
+            progress_for(
+                &json!({"method": "notifications/message", "params": {}}),
+                "job-2"
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,314 @@
+//! Local mutation journal
+//!
+//! Every mutation this app made successfully, newest last, in
+//! `journal.jsonl` (per project): the tool, the task, a before/after summary
+//! when the command knew it, and the backend's history operation id when
+//! the response carried one. The backend's undo stack is shared by every
+//! client; the journal is what lets the GUI say what *it* did ("Undo:
+//! status change on TASK-042"). A session is the time spent on one
+//! project: switching projects starts an empty journal, a restart on the
+//! same project keeps it.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ai_response::AIResponse;
+use crate::sidecar;
+use crate::AppState;
+
+/// Journal file inside the project sidecar dir
+pub const JOURNAL_FILE: &str = "journal.jsonl";
+/// Entries kept (oldest dropped first)
+pub const MAX_ENTRIES: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub seq: u64,
+    pub tool: String,
+    pub task_id: Option<String>,
+    /// Short summary of the state the mutation replaced (`ACTIVE`)
+    pub before: Option<String>,
+    /// Short summary of the state it left (`DONE`)
+    pub after: Option<String>,
+    pub at: DateTime<Utc>,
+    /// `meta.operation_id` of the response: the backend history entry
+    pub history_id: Option<String>,
+}
+
+impl JournalEntry {
+    /// `Status change on TASK-042 (ACTIVE → DONE)`
+    pub fn describe(&self) -> String {
+        let mut text = describe(&self.tool, self.task_id.as_deref());
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => text.push_str(&format!(" ({} → {})", before, after)),
+            (None, Some(after)) => text.push_str(&format!(" ({})", after)),
+            _ => {}
+        }
+        text
+    }
+}
+
+/// `Status change on TASK-042` for a tool (`tasks_complete`) or backend
+/// history intent (`complete`)
+pub fn describe(tool_or_intent: &str, task_id: Option<&str>) -> String {
+    let name = tool_or_intent
+        .strip_prefix("tasks_")
+        .unwrap_or(tool_or_intent);
+    let action = match name {
+        "create" | "scaffold" => "Creation".to_string(),
+        "complete" | "status" => "Status change".to_string(),
+        "edit" | "patch" => "Edit".to_string(),
+        "verify" => "Checkpoint confirmation".to_string(),
+        "delete" => "Deletion".to_string(),
+        "decompose" | "task_add" => "New subtasks".to_string(),
+        other => {
+            let words = other.replace('_', " ");
+            let mut chars = words.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => "Change".to_string(),
+            }
+        }
+    };
+    match task_id {
+        Some(task_id) => format!("{} on {}", action, task_id),
+        None => action,
+    }
+}
+
+/// A history operation id as text (the backend may send a number)
+pub fn id_text(id: &Value) -> Option<String> {
+    match id {
+        Value::String(id) if !id.is_empty() => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+/// The backend history operation id of a mutation response
+pub fn history_id(response: &AIResponse) -> Option<String> {
+    response
+        .rest
+        .get("meta")
+        .and_then(|meta| meta.get("operation_id"))
+        .and_then(id_text)
+}
+
+/// A mutation to journal
+#[derive(Debug, Clone, Default)]
+pub struct Mutation {
+    pub tool: String,
+    pub task_id: Option<String>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub history_id: Option<String>,
+}
+
+impl Mutation {
+    pub fn new(tool: &str, task_id: Option<String>) -> Self {
+        Self {
+            tool: tool.to_string(),
+            task_id,
+            ..Self::default()
+        }
+    }
+
+    pub fn change(mut self, before: Option<String>, after: Option<String>) -> Self {
+        self.before = before;
+        self.after = after;
+        self
+    }
+}
+
+/// Journal `mutation` if `response` says it was applied (not failed, not
+/// just queued: replay journals those)
+pub async fn record(state: &AppState, response: &AIResponse, mutation: Mutation) {
+    if !response.success || response.queued {
+        return;
+    }
+    let mutation = Mutation {
+        history_id: history_id(response),
+        ..mutation
+    };
+    state.journal.lock().await.record(mutation);
+}
+
+pub struct Journal {
+    path: PathBuf,
+    entries: Vec<JournalEntry>,
+}
+
+impl Journal {
+    /// Load the journal at `path` (missing or unreadable file -> empty)
+    pub fn load(path: PathBuf) -> Self {
+        let mut entries = sidecar::read_jsonl(&path).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable journal: {:#}", e);
+            Vec::new()
+        });
+        let excess = entries.len().saturating_sub(MAX_ENTRIES);
+        entries.drain(..excess);
+        Self { path, entries }
+    }
+
+    /// Start an empty journal at `path` (a project switch); the old file
+    /// is removed
+    pub fn restart(&mut self, path: PathBuf) {
+        for old in [&self.path, &path] {
+            if let Err(e) = fs::remove_file(old) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log::warn!("Failed to remove journal {:?}: {}", old, e);
+                }
+            }
+        }
+        self.entries.clear();
+        self.path = path;
+    }
+
+    /// Append `mutation`; persisting is best effort (the entry is kept in
+    /// memory either way)
+    pub fn record(&mut self, mutation: Mutation) -> JournalEntry {
+        let entry = JournalEntry {
+            seq: self.entries.last().map_or(1, |e| e.seq + 1),
+            tool: mutation.tool,
+            task_id: mutation.task_id,
+            before: mutation.before,
+            after: mutation.after,
+            at: Utc::now(),
+            history_id: mutation.history_id,
+        };
+        self.entries.push(entry.clone());
+        let saved = if self.entries.len() > MAX_ENTRIES {
+            let excess = self.entries.len() - MAX_ENTRIES;
+            self.entries.drain(..excess);
+            self.save()
+        } else {
+            sidecar::append_jsonl(&self.path, &entry)
+        };
+        if let Err(e) = saved {
+            log::warn!("Failed to persist journal entry {}: {:#}", entry.seq, e);
+        }
+        entry
+    }
+
+    /// Newest first, at most `limit`
+    pub fn list(&self, limit: usize) -> Vec<JournalEntry> {
+        self.entries.iter().rev().take(limit).cloned().collect()
+    }
+
+    pub fn latest_for(&self, task_id: &str) -> Option<&JournalEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| e.task_id.as_deref() == Some(task_id))
+    }
+
+    /// The entry of backend history operation `id`
+    pub fn by_history_id(&self, id: &str) -> Option<&JournalEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| e.history_id.as_deref() == Some(id))
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {:?}", parent))?;
+        }
+        let mut text = String::new();
+        for entry in &self.entries {
+            text.push_str(&serde_json::to_string(entry)?);
+            text.push('\n');
+        }
+        fs::write(&self.path, text).with_context(|| format!("Failed to write {:?}", self.path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "apply-task-journal-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join(JOURNAL_FILE)
+    }
+
+    fn response(meta: Value) -> AIResponse {
+        AIResponse::from_value(json!({ "success": true, "result": {}, "meta": meta }))
+    }
+
+    #[test]
+    fn test_record_persist_and_cap() {
+        let path = temp_path("cap");
+        let mut journal = Journal::load(path.clone());
+        let status = Mutation {
+            history_id: history_id(&response(json!({ "operation_id": "op-7" }))),
+            ..Mutation::new("tasks_complete", Some("TASK-042".into()))
+        }
+        .change(Some("ACTIVE".into()), Some("DONE".into()));
+        let entry = journal.record(status);
+        assert_eq!(entry.seq, 1);
+        assert_eq!(entry.history_id.as_deref(), Some("op-7"));
+        assert_eq!(
+            entry.describe(),
+            "Status change on TASK-042 (ACTIVE → DONE)"
+        );
+        journal.record(Mutation::new("tasks_note", Some("TASK-001".into())));
+
+        let reloaded = Journal::load(path.clone());
+        assert_eq!(reloaded.list(10).len(), 2);
+        assert_eq!(reloaded.list(1)[0].tool, "tasks_note");
+        assert_eq!(reloaded.latest_for("TASK-042").map(|e| e.seq), Some(1));
+        assert_eq!(reloaded.by_history_id("op-7").map(|e| e.seq), Some(1));
+        assert!(reloaded.by_history_id("op-8").is_none());
+
+        for _ in 0..MAX_ENTRIES {
+            journal.record(Mutation::new("tasks_edit", None));
+        }
+        let capped = Journal::load(path.clone());
+        assert_eq!(capped.entries.len(), MAX_ENTRIES);
+        assert_eq!(capped.entries[0].seq, 3);
+        assert_eq!(capped.list(1)[0].seq, MAX_ENTRIES as u64 + 2);
+
+        let other = temp_path("cap-other");
+        journal.restart(other.clone());
+        assert!(journal.list(10).is_empty());
+        assert!(!path.exists());
+        journal.record(Mutation::new("tasks_edit", None));
+        assert_eq!(Journal::load(other.clone()).list(10)[0].seq, 1);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+        let _ = fs::remove_dir_all(other.parent().unwrap());
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(describe("create", Some("TASK-1")), "Creation on TASK-1");
+        assert_eq!(describe("tasks_verify", None), "Checkpoint confirmation");
+        assert_eq!(describe("tasks_add_note", Some("T")), "Add note on T");
+        let entry = JournalEntry {
+            seq: 1,
+            tool: "tasks_edit".into(),
+            task_id: Some("TASK-9".into()),
+            before: None,
+            after: Some("title, priority".into()),
+            at: Utc::now(),
+            history_id: None,
+        };
+        assert_eq!(entry.describe(), "Edit on TASK-9 (title, priority)");
+        assert_eq!(
+            history_id(&response(json!({ "operation_id": 12 }))).as_deref(),
+            Some("12")
+        );
+    }
+}
@@ -3,127 +3,748 @@
 //! Desktop GUI for apply_task using Tauri 2.0 + React 19.
 //! Communicates with Python backend via JSON-RPC 2.0.
 
+mod ai_pause;
+mod ai_response;
+mod ai_signals;
+mod ai_status;
+mod audit;
+mod backend;
+mod backend_resources;
+mod board;
+mod checklist;
+mod cli;
 mod commands;
+mod confirm;
+mod context;
+mod crash;
+mod debounce;
+mod deep_link;
+mod detection;
+mod doctor;
+mod due;
+mod duplicates;
+mod env_info;
+mod error_catalog;
+mod evidence;
+mod fuzzy;
+mod git;
+mod headless;
+mod ics;
+mod intent_result;
+mod intents;
+mod jobs;
+mod journal;
+mod lifecycle;
+mod list_refresh;
+mod logging;
+mod mutation_queue;
+mod notifications;
+mod prefetch;
+mod progress;
+mod projection;
+mod projects;
 mod python;
+mod quick_add;
+mod quick_parse;
+mod rate_limit;
+mod read_cache;
+mod read_only;
+mod recent_tasks;
+mod report;
+mod revision;
+mod search_index;
+mod session;
+mod settings;
+mod sidecar;
+mod signals;
+mod single_instance;
+mod storage;
+mod storage_watch;
+mod subtask_plan;
+mod subtask_progress;
+mod suggestions;
+mod support_bundle;
+mod task_stream;
+mod task_tree;
+mod templates;
+mod timeline;
+mod timer;
+mod todos;
+mod tray;
+mod update_check;
+mod usage_metrics;
+mod verification;
+mod versions;
+mod window_state;
+mod windows;
+
+#[doc(hidden)]
+pub use python::bench;
 
 use std::env;
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64};
 
-use tokio::sync::Mutex;
+use tauri::{Emitter, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tokio::sync::{Mutex, RwLock};
 
+use ai_pause::{PauseStore, PAUSE_FILE};
+use ai_signals::AiSignalInbox;
+use ai_status::AiStatusPoller;
+use audit::AuditLog;
+use backend_resources::ResourceMonitor;
+use cli::{CliArgs, CliError, StartupIntent};
+use confirm::ConfirmTokens;
+use debounce::Debouncer;
+use detection::{
+    ConfigWarning, DetectedRoot, DetectionError, DetectionReport, CONFIG_WARNING_EVENT,
+    DETECTION_FAILED_EVENT,
+};
+use env_info::ConfigSource;
+use jobs::JobRegistry;
+use journal::{Journal, JOURNAL_FILE};
+use list_refresh::ListRefresher;
+use logging::FrontendLog;
+use mutation_queue::{MutationQueue, QUEUE_FILE};
+use notifications::Notifier;
+use projects::{ProjectRegistry, PROJECTS_FILE};
 use python::PythonBridge;
+use quick_add::ShortcutStatus;
+use rate_limit::RateLimiter;
+use read_cache::ReadCache;
+use search_index::IndexSlot;
+use session::{SessionStore, SESSION_FILE};
+use settings::Settings;
+use signals::{SignalLog, SIGNALS_FILE};
+use storage_watch::StorageWatcher;
+use subtask_progress::ProgressStreams;
+use task_stream::TaskStreams;
+use timer::TimerState;
+use usage_metrics::{UsageMetrics, USAGE_METRICS_FILE};
+use versions::Versions;
+use window_state::{WindowStates, WINDOW_STATE_FILE};
+use windows::WindowScopes;
 
 /// Application state shared across all commands
 pub struct AppState {
-    pub bridge: Arc<Mutex<PythonBridge>>,
+    /// Clones share one backend process and can call it concurrently, so
+    /// no lock is held across a round trip
+    pub bridge: PythonBridge,
     /// Path to apply_task package (for finding Python scripts)
     pub apply_task_root: PathBuf,
-    /// User's working directory when GUI was launched (for project detection)
-    pub user_cwd: PathBuf,
+    /// How `apply_task_root` was found (or what was checked)
+    pub detection: DetectionReport,
+    /// Project directory the backend runs in (launch cwd until `projects_open`)
+    pub user_cwd: std::sync::Mutex<PathBuf>,
+    /// Tauri app data directory (GUI-only sidecar data)
+    pub data_dir: PathBuf,
+    /// Running task timer
+    pub timer: Mutex<TimerState>,
+    /// Shared `tasks_ai_status` poller
+    pub ai_status: AiStatusPoller,
+    /// Tauri app config directory (`settings.json`, `projects.json`)
+    pub config_dir: PathBuf,
+    /// GUI settings (`settings.json` in the config dir)
+    pub settings: RwLock<Settings>,
+    /// Sent user signals and their acknowledgement
+    pub signals: Mutex<SignalLog>,
+    /// Signals the AI raised and waits on (`ai-signal`)
+    pub ai_signals: Mutex<AiSignalInbox>,
+    /// What `ai_pause_all` paused (`ai_pause.json` in the data dir)
+    pub ai_pause: Mutex<PauseStore>,
+    /// Backend RSS/CPU samples (`bridge_metrics`, `bridge_status`)
+    pub backend_resources: ResourceMonitor,
+    /// Outstanding delete confirmation tokens
+    pub confirm_tokens: Mutex<ConfirmTokens>,
+    /// Debounced mutations waiting out their quiet period
+    pub debouncer: Debouncer,
+    /// Sequence number of status mutations this session
+    pub mutation_seq: AtomicU64,
+    /// Cached list/show/context results
+    pub read_cache: Mutex<ReadCache>,
+    /// Full-text index of the task list (`tasks_search_indexed`)
+    pub search_index: std::sync::Mutex<IndexSlot>,
+    /// Background jobs (`ai_intent_background`)
+    pub jobs: Mutex<JobRegistry>,
+    /// Background task-list refreshers by window (`tasks-diff` events)
+    pub list_refresh: ListRefresher,
+    /// Running `tasks_list_stream` deliveries
+    pub task_streams: TaskStreams,
+    /// Followed subtasks and their progress backlog (`subtask-progress`)
+    pub progress_streams: ProgressStreams,
+    /// Task storage file watcher (off until `watch_storage_start`)
+    pub storage_watch: StorageWatcher,
+    /// Mutations waiting for the backend (`offline_queue` setting)
+    pub mutation_queue: Mutex<MutationQueue>,
+    /// Mutations this app made in the current project
+    pub journal: Mutex<Journal>,
+    /// Call budgets of expensive tools (`rate_limits_per_min`)
+    pub rate_limiter: Mutex<RateLimiter>,
+    /// Writes to the mutation audit log (`audit_log_query`)
+    pub audit: AuditLog,
+    /// Recent projects (`projects.json` in the config dir)
+    pub projects: Mutex<ProjectRegistry>,
+    /// Where `user_cwd` came from (`env_info`)
+    pub user_cwd_source: std::sync::Mutex<ConfigSource>,
+    /// Launched without a project hint; the frontend should offer the registry
+    pub needs_project: AtomicBool,
+    /// Probed versions (cleared when the interpreter changes)
+    pub versions: Mutex<Option<Versions>>,
+    /// The user chose to quit despite writes in flight (`confirm_exit`)
+    pub exit_confirmed: AtomicBool,
+    /// `--task`/`--namespace` from the command line, until the frontend asks
+    pub startup_intent: std::sync::Mutex<Option<StartupIntent>>,
+    /// Last seen tasks and rate limit of OS notifications
+    pub notifications: Notifier,
+    /// Registration state of the quick-add shortcut
+    pub quick_add: std::sync::Mutex<ShortcutStatus>,
+    /// Saved window geometry (`window-state.json` in the config dir)
+    pub window_state: WindowStates,
+    /// Namespace and view of windows opened with `open_window`
+    pub windows: WindowScopes,
+    /// Project, namespace, task and view to restore (`session.json` in the
+    /// data dir)
+    pub session: SessionStore,
+    /// Rate limit and error count of `log_from_frontend`
+    pub frontend_log: FrontendLog,
+    /// Opt-in command/intent counters (`analytics_enabled`)
+    pub usage_metrics: UsageMetrics,
 }
 
-/// Get apply_task package root (where Python scripts are located)
-fn get_apply_task_root() -> PathBuf {
-    // 1. Check explicit environment variable
-    if let Ok(path) = env::var("APPLY_TASK_PROJECT_ROOT") {
-        let path = PathBuf::from(path);
-        if path.exists() {
-            return path;
-        }
+impl AppState {
+    pub fn user_cwd(&self) -> PathBuf {
+        self.user_cwd
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
     }
 
-    // 2. Use executable path to find apply_task root
-    //    Binary location: apply_task/gui/src-tauri/target/debug/apply-task-gui
-    //    Need to go up: debug -> target -> src-tauri -> gui -> apply_task
-    if let Ok(exe_path) = env::current_exe() {
-        if let Some(exe_dir) = exe_path.parent() {
-            // Check if we're in target/debug or target/release
-            let path_str = exe_dir.to_string_lossy();
-            if path_str.contains("target/debug") || path_str.contains("target/release") {
-                // Navigate up: debug/release -> target -> src-tauri -> gui -> apply_task
-                if let Some(target_dir) = exe_dir.parent() {
-                    if let Some(src_tauri_dir) = target_dir.parent() {
-                        if let Some(gui_dir) = src_tauri_dir.parent() {
-                            if let Some(apply_task_root) = gui_dir.parent() {
-                                if apply_task_root.join("core").exists()
-                                    || apply_task_root.join("tasks.py").exists()
-                                {
-                                    return apply_task_root.to_path_buf();
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    /// Sidecar directory of the current project
+    pub fn project_dir(&self) -> PathBuf {
+        sidecar::project_dir(&self.data_dir, &self.user_cwd())
+    }
+}
+
+#[cfg(test)]
+impl AppState {
+    /// Default state around `bridge`, with data and config files under `dir`
+    pub(crate) fn for_tests(bridge: PythonBridge, dir: &Path) -> Self {
+        let detected = Ok(DetectedRoot {
+            path: dir.to_path_buf(),
+            strategy: detection::Strategy::CwdAncestor,
+            probes: Vec::new(),
+        });
+        Self {
+            bridge,
+            apply_task_root: dir.to_path_buf(),
+            detection: DetectionReport::new(&detected, dir),
+            user_cwd: std::sync::Mutex::new(dir.to_path_buf()),
+            user_cwd_source: std::sync::Mutex::new(ConfigSource::Default),
+            data_dir: dir.join("data"),
+            config_dir: dir.join("config"),
+            timer: Mutex::new(TimerState::default()),
+            ai_status: AiStatusPoller::default(),
+            settings: RwLock::new(Settings::default()),
+            signals: Mutex::new(SignalLog::load(dir.join("data").join(SIGNALS_FILE))),
+            ai_signals: Mutex::new(AiSignalInbox::default()),
+            ai_pause: Mutex::new(PauseStore::load(dir.join("data").join(PAUSE_FILE))),
+            backend_resources: ResourceMonitor::default(),
+            confirm_tokens: Mutex::new(ConfirmTokens::default()),
+            debouncer: Debouncer::default(),
+            mutation_seq: AtomicU64::new(0),
+            read_cache: Mutex::new(ReadCache::default()),
+            search_index: std::sync::Mutex::new(IndexSlot::default()),
+            jobs: Mutex::new(JobRegistry::default()),
+            list_refresh: ListRefresher::default(),
+            task_streams: TaskStreams::default(),
+            progress_streams: ProgressStreams::default(),
+            storage_watch: StorageWatcher::default(),
+            mutation_queue: Mutex::new(MutationQueue::load(dir.join("data").join(QUEUE_FILE))),
+            journal: Mutex::new(Journal::load(dir.join("data").join(JOURNAL_FILE))),
+            rate_limiter: Mutex::new(RateLimiter::default()),
+            audit: AuditLog::default(),
+            projects: Mutex::new(ProjectRegistry::load(
+                dir.join("config").join(PROJECTS_FILE),
+            )),
+            needs_project: AtomicBool::new(false),
+            versions: Mutex::new(None),
+            exit_confirmed: AtomicBool::new(false),
+            startup_intent: std::sync::Mutex::new(None),
+            notifications: Notifier::default(),
+            quick_add: std::sync::Mutex::new(ShortcutStatus::default()),
+            window_state: WindowStates::load(dir.join("config").join(WINDOW_STATE_FILE)),
+            windows: WindowScopes::default(),
+            session: SessionStore::load(dir.join("data").join(SESSION_FILE)),
+            frontend_log: FrontendLog::default(),
+            usage_metrics: UsageMetrics::load(dir.join("data").join(USAGE_METRICS_FILE), false),
         }
     }
+}
 
-    // 3. Navigate up from current working directory
-    if let Ok(current) = env::current_dir() {
-        // Check if we're in src-tauri
-        if current.ends_with("src-tauri") {
-            if let Some(gui_dir) = current.parent() {
-                if let Some(project_root) = gui_dir.parent() {
-                    if project_root.join("core").exists() || project_root.join("tasks.py").exists()
-                    {
-                        return project_root.to_path_buf();
-                    }
-                }
-            }
+/// Get apply_task package root (where Python scripts are located)
+///
+/// `python` is only asked when no checkout is found (installed package).
+/// Also returns the environment variables that were set but ignored.
+fn get_apply_task_root(
+    python: &str,
+    launch_dir: &Path,
+) -> (Result<DetectedRoot, DetectionError>, Vec<ConfigWarning>) {
+    let input = detection::probe_env(python, launch_dir);
+    (
+        detection::detect(&input),
+        detection::config_warnings(&input),
+    )
+}
+
+/// Project and checkout a launch is for, from the command line and the
+/// environment
+struct Startup {
+    launch_dir: PathBuf,
+    user_cwd: PathBuf,
+    /// Where `user_cwd` came from (`env_info`)
+    user_cwd_source: ConfigSource,
+    /// Asked-for project; without one the frontend offers the registry
+    project_hint: Option<PathBuf>,
+    /// Where the checkout is looked for
+    detect_dir: PathBuf,
+    startup_intent: Option<StartupIntent>,
+}
+
+impl Startup {
+    /// Exits (status 2) when `--project` is not an apply_task project
+    fn resolve(cli: &CliArgs, launch_dir: PathBuf, env_hint: Option<PathBuf>) -> Self {
+        // Relative paths on the command line are the user's, like the cwd `run()`
+        // forwards to a running instance
+        let base_dir = env_hint.clone().unwrap_or_else(|| launch_dir.clone());
+        let project_root = cli.project.as_ref().map(|project| {
+            let dir = base_dir.join(project);
+            projects::project_root(&dir).unwrap_or_else(|| {
+                eprintln!(
+                    "apply-task-gui: --project {}: not an apply_task project",
+                    dir.display()
+                );
+                std::process::exit(2);
+            })
+        });
+        // `--project`, else a directory argument (`apply-task-gui ~/project`),
+        // else the environment
+        let cwd_hint = project_root
+            .clone()
+            .or_else(|| cli.project_dir(&base_dir))
+            .or_else(|| env_hint.clone());
+        let user_cwd_source = if project_root.is_some() || cli.project_dir(&base_dir).is_some() {
+            ConfigSource::Cli
+        } else if env_hint.is_some() {
+            ConfigSource::Env("APPLY_TASK_USER_CWD")
+        } else {
+            ConfigSource::Default
+        };
+        let user_cwd = cwd_hint.clone().unwrap_or_else(|| launch_dir.clone());
+        // Without an explicit hint, only a cwd that is a project counts
+        let project_hint = cwd_hint.or_else(|| projects::project_root(&user_cwd));
+        // `--project` also decides where the checkout is looked for
+        let detect_dir = project_root.unwrap_or_else(|| launch_dir.clone());
+        Self {
+            launch_dir,
+            user_cwd,
+            user_cwd_source,
+            project_hint,
+            detect_dir,
+            startup_intent: cli.startup_intent(),
         }
+    }
+}
 
-        // Check if we're in gui/
-        if current.ends_with("gui") {
-            if let Some(project_root) = current.parent() {
-                if project_root.join("core").exists() || project_root.join("tasks.py").exists() {
-                    return project_root.to_path_buf();
-                }
+/// State for a launch (windowless under `--headless`)
+fn new_state(
+    startup: &Startup,
+    settings: Settings,
+    data_dir: PathBuf,
+    config_dir: PathBuf,
+) -> AppState {
+    let python_path = settings
+        .python_path
+        .clone()
+        .unwrap_or_else(python::default_python_path);
+
+    // Started even on failure: the frontend shows the report and a picker
+    let (detected, config_warnings) = match &settings.ssh {
+        Some(ssh) => (
+            Ok(DetectedRoot {
+                path: PathBuf::from(ssh.path.trim()),
+                strategy: detection::Strategy::Remote,
+                probes: Vec::new(),
+            }),
+            Vec::new(),
+        ),
+        None => get_apply_task_root(&python_path, &startup.detect_dir),
+    };
+    for warning in &config_warnings {
+        log::warn!("{}", warning.message);
+    }
+    let apply_task_root = match &detected {
+        Ok(root) => {
+            log::info!("Apply task root: {:?} ({:?})", root.path, root.strategy);
+            root.path.clone()
+        }
+        Err(e) => {
+            log::error!("{}; falling back to the working directory", e);
+            for probe in &e.probes {
+                log::error!("  {:?} {:?}: {}", probe.strategy, probe.path, probe.detail);
             }
+            startup.launch_dir.clone()
         }
+    };
+    let mut detection = DetectionReport::new(&detected, &apply_task_root);
+    detection.warnings = config_warnings;
+    log::info!("User working directory: {:?}", startup.user_cwd);
 
-        // Check if current dir is the project root
-        if current.join("core").exists() || current.join("tasks.py").exists() {
-            return current;
+    let bridge = PythonBridge::new(apply_task_root.clone(), startup.user_cwd.clone())
+        .with_python_path(Some(python_path))
+        .with_framing(settings.stdio_framing)
+        .with_env_policy(settings.env_policy())
+        .with_container(settings.container.clone())
+        .with_ssh(settings.ssh.clone());
+    bridge.set_strict_protocol(settings.strict_protocol);
+    bridge.set_max_message_bytes(settings.max_message_bytes());
+    let signals =
+        SignalLog::load(sidecar::project_dir(&data_dir, &startup.user_cwd).join(SIGNALS_FILE));
+    let mutation_queue =
+        MutationQueue::load(sidecar::project_dir(&data_dir, &startup.user_cwd).join(QUEUE_FILE));
+    let journal =
+        Journal::load(sidecar::project_dir(&data_dir, &startup.user_cwd).join(JOURNAL_FILE));
+    let window_state = WindowStates::load(config_dir.join(WINDOW_STATE_FILE));
+    let session = SessionStore::load(data_dir.join(SESSION_FILE));
+    let ai_pause = PauseStore::load(data_dir.join(PAUSE_FILE));
+    let usage_metrics = UsageMetrics::load(
+        data_dir.join(USAGE_METRICS_FILE),
+        settings.analytics_enabled,
+    );
+    let mut projects = ProjectRegistry::load(config_dir.join(PROJECTS_FILE));
+    if let Some(root) = startup
+        .project_hint
+        .as_deref()
+        .and_then(projects::project_root)
+    {
+        if let Err(e) = projects.touch(&root) {
+            log::warn!("Failed to update project registry: {:#}", e);
         }
     }
 
-    // 4. Fallback to current directory
-    env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+    AppState {
+        bridge,
+        apply_task_root,
+        detection,
+        user_cwd: std::sync::Mutex::new(startup.user_cwd.clone()),
+        user_cwd_source: std::sync::Mutex::new(startup.user_cwd_source),
+        data_dir,
+        config_dir,
+        timer: Mutex::new(TimerState::default()),
+        ai_status: AiStatusPoller::default(),
+        settings: RwLock::new(settings),
+        signals: Mutex::new(signals),
+        ai_signals: Mutex::new(AiSignalInbox::default()),
+        ai_pause: Mutex::new(ai_pause),
+        backend_resources: ResourceMonitor::default(),
+        confirm_tokens: Mutex::new(ConfirmTokens::default()),
+        debouncer: Debouncer::default(),
+        mutation_seq: AtomicU64::new(0),
+        read_cache: Mutex::new(ReadCache::default()),
+        search_index: std::sync::Mutex::new(IndexSlot::default()),
+        jobs: Mutex::new(JobRegistry::default()),
+        list_refresh: ListRefresher::default(),
+        task_streams: TaskStreams::default(),
+        progress_streams: ProgressStreams::default(),
+        storage_watch: StorageWatcher::default(),
+        mutation_queue: Mutex::new(mutation_queue),
+        journal: Mutex::new(journal),
+        rate_limiter: Mutex::new(RateLimiter::default()),
+        audit: AuditLog::default(),
+        projects: Mutex::new(projects),
+        needs_project: AtomicBool::new(startup.project_hint.is_none()),
+        versions: Mutex::new(None),
+        exit_confirmed: AtomicBool::new(false),
+        startup_intent: std::sync::Mutex::new(startup.startup_intent.clone()),
+        notifications: Notifier::default(),
+        quick_add: std::sync::Mutex::new(ShortcutStatus::default()),
+        window_state,
+        windows: WindowScopes::default(),
+        session,
+        frontend_log: FrontendLog::default(),
+        usage_metrics,
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize logging
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
-
-    log::info!("Starting Apply Task GUI...");
+    // stderr from here on, the log file once the app log dir is known
+    logging::init();
+    crash::install_hook();
 
     // Capture user's working directory FIRST (before any directory changes)
-    let user_cwd = env::var("APPLY_TASK_USER_CWD")
+    let launch_dir = env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let env_hint = env::var("APPLY_TASK_USER_CWD")
         .map(PathBuf::from)
-        .unwrap_or_else(|_| env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
-
-    let apply_task_root = get_apply_task_root();
+        .ok()
+        .filter(|h| h.is_dir());
+    let args: Vec<String> = env::args().collect();
+    let context = tauri::generate_context!();
+    if args.get(1).map(String::as_str) == Some(headless::FLAG) {
+        let code = headless::run(
+            &args[2..],
+            launch_dir,
+            env_hint,
+            &context.config().identifier,
+        );
+        std::process::exit(code);
+    }
 
-    log::info!("Apply task root: {:?}", apply_task_root);
-    log::info!("User working directory: {:?}", user_cwd);
+    log::info!("Starting Apply Task GUI...");
 
-    let bridge = PythonBridge::new(apply_task_root.clone(), user_cwd.clone());
-    let state = AppState {
-        bridge: Arc::new(Mutex::new(bridge)),
-        apply_task_root,
-        user_cwd,
+    let cli = match cli::parse(args.get(1..).unwrap_or_default()) {
+        Ok(cli) => cli,
+        Err(CliError::Help) => {
+            println!("{}", cli::USAGE);
+            std::process::exit(0);
+        }
+        Err(CliError::Usage(e)) => {
+            eprintln!("apply-task-gui: {}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
     };
+    let startup = Startup::resolve(&cli, launch_dir, env_hint.clone());
+
+    // The single-instance plugin forwards the process cwd to a running
+    // instance, so make it the user's (detection keeps using `detect_dir`)
+    if let Some(hint) = &env_hint {
+        if let Err(e) = env::set_current_dir(hint) {
+            log::warn!("Failed to enter {:?}: {}", hint, e);
+        }
+    }
+
+    let handler: fn(tauri::ipc::Invoke<tauri::Wry>) -> bool = tauri::generate_handler![
+        commands::backend_set_storage_mode,
+        commands::bridge_metrics,
+        commands::bridge_status,
+        commands::bridge_restart,
+        commands::ai_intent,
+        commands::ai_intent_background,
+        commands::job_cancel,
+        commands::job_list,
+        commands::tasks_context,
+        commands::context_refresh,
+        commands::tasks_show,
+        commands::tasks_prefetch,
+        commands::tasks_list,
+        commands::tasks_list_stream,
+        commands::tasks_stream_cancel,
+        commands::tasks_progress_stream_start,
+        commands::tasks_progress_stream_stop,
+        commands::list_autorefresh_start,
+        commands::list_autorefresh_stop,
+        commands::tasks_update_status,
+        commands::tasks_edit,
+        commands::mutation_queue_list,
+        commands::mutation_queue_discard,
+        commands::mutation_queue_retry,
+        commands::tasks_delete,
+        commands::tasks_copy_link,
+        commands::ai_status_subscribe,
+        commands::ai_status_unsubscribe,
+        commands::ai_signal_respond,
+        commands::ai_pause_all,
+        commands::ai_resume_all,
+        commands::tasks_send_signal,
+        commands::tasks_signal_history,
+        commands::tasks_timer_start,
+        commands::tasks_timer_stop,
+        commands::tasks_time_report,
+        commands::tasks_timeline,
+        commands::tasks_report,
+        commands::tasks_search_indexed,
+        commands::tasks_fuzzy,
+        commands::templates_save,
+        commands::templates_list,
+        commands::tasks_template_subtasks,
+        commands::templates_delete,
+        commands::templates_export,
+        commands::templates_import,
+        commands::tasks_create_from_template,
+        commands::tasks_create_from_markdown,
+        commands::scan_todos,
+        commands::tasks_create_from_todos,
+        commands::git_info,
+        commands::tasks_verify,
+        commands::tasks_get_checkpoints,
+        commands::tasks_related_commits,
+        commands::run_verification_command,
+        commands::tasks_find_similar,
+        commands::journal_list,
+        commands::journal_latest_for,
+        commands::tasks_undo,
+        commands::get_read_only,
+        commands::set_read_only,
+        commands::audit_log_query,
+        commands::audit_log_export,
+        commands::session_get,
+        commands::session_update,
+        commands::session_clear,
+        commands::execute_suggestion,
+        commands::tasks_set_due,
+        commands::tasks_due,
+        commands::tasks_export_ics,
+        commands::tasks_board,
+        commands::tasks_board_set_order,
+        commands::tasks_storage,
+        commands::reveal_storage,
+        commands::reveal_task_file,
+        commands::watch_storage_start,
+        commands::watch_storage_stop,
+        commands::projects_list,
+        commands::projects_add,
+        commands::projects_remove,
+        commands::projects_open,
+        commands::get_intent_aliases,
+        commands::set_intent_aliases,
+        commands::detection_report,
+        commands::doctor,
+        commands::get_log_path,
+        commands::open_log_folder,
+        commands::log_from_frontend,
+        commands::get_crash_reports,
+        commands::delete_crash_report,
+        commands::usage_metrics_get,
+        commands::usage_metrics_reset,
+        commands::check_for_updates,
+        commands::versions,
+        commands::check_backend_version,
+        commands::env_info,
+        commands::create_support_bundle,
+        commands::set_fault_injection,
+        commands::get_fault_injection,
+        commands::clear_fault_injection,
+        commands::get_settings,
+        commands::set_settings,
+        commands::confirm_exit,
+        commands::notification_prefs_get,
+        commands::notification_prefs_set,
+        commands::startup_intent,
+        commands::quick_create,
+        commands::parse_quick_task,
+        commands::quick_add_dismiss,
+        commands::quick_add_status,
+        commands::reset_window_state,
+        commands::open_window,
+        commands::window_scope,
+    ];
 
     tauri::Builder::default()
+        // First, so a second launch hands over before anything else starts
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            single_instance::handle_second_launch(app, args, cwd);
+        }))
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
-        .manage(state)
-        .invoke_handler(tauri::generate_handler![
-            commands::backend_set_storage_mode,
-            commands::ai_intent,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .setup(move |app| {
+            let data_dir = app.path().app_data_dir()?;
+            let config_dir = app.path().app_config_dir()?;
+            match app.path().app_log_dir() {
+                Ok(log_dir) => {
+                    logging::attach_dir(&log_dir);
+                    crash::attach(app.handle(), log_dir);
+                }
+                Err(e) => log::warn!("No app log directory, logging to stderr only: {}", e),
+            }
+            log::info!("App data directory: {:?}", data_dir);
+            settings::migrate_legacy(&config_dir, &data_dir);
+            let settings = Settings::load(&config_dir);
+            let shortcut_settings = settings.clone();
+            let state = new_state(&startup, settings, data_dir, config_dir);
+            let detection = state.detection.clone();
+            app.manage(state);
+            usage_metrics::spawn_flusher(app.handle().clone());
+            // Hidden in the config until the saved geometry is applied
+            if let Some(window) = app.get_webview_window("main") {
+                window_state::restore(&window.as_ref().window());
+                if let Err(e) = window.show() {
+                    log::warn!("Failed to show main window: {}", e);
+                }
+            }
+            mutation_queue::spawn_replayer(app.handle().clone());
+            backend_resources::spawn_sampler(app.handle().clone());
+            versions::spawn_startup_check(app.handle().clone());
+            update_check::spawn_startup_check(app.handle().clone());
+            if let Err(e) = tray::init(app.handle()) {
+                log::warn!("Failed to create tray icon: {}", e);
+            }
+            quick_add::apply_shortcut(app.handle(), &shortcut_settings);
+            ai_pause::register_shortcut(app.handle(), &shortcut_settings);
+            if !detection.success {
+                if let Err(e) = app.emit(DETECTION_FAILED_EVENT, &detection) {
+                    log::warn!("Failed to emit {}: {}", DETECTION_FAILED_EVENT, e);
+                }
+            }
+            for warning in &detection.warnings {
+                if let Err(e) = app.emit(CONFIG_WARNING_EVENT, warning) {
+                    log::warn!("Failed to emit {}: {}", CONFIG_WARNING_EVENT, e);
+                }
+            }
+
+            // Deep links: register the scheme where the OS needs it at runtime,
+            // then handle the launch URL and any delivered later
+            #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+            if let Err(e) = app.deep_link().register_all() {
+                log::warn!("Failed to register deep link scheme: {}", e);
+            }
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                deep_link::handle_urls(&handle, event.urls());
+            });
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                deep_link::handle_urls(app.handle(), urls);
+            }
+            Ok(())
+        })
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::CloseRequested { api, .. } => {
+                // Closing the last window quits, unless it goes to the tray
+                let app = window.app_handle();
+                if window.label() == "main" && tray::hide_on_close(app) {
+                    api.prevent_close();
+                    if let Err(e) = window.hide() {
+                        log::warn!("Failed to hide main window: {}", e);
+                    }
+                } else if app.webview_windows().len() <= 1 && !lifecycle::allow_exit(app) {
+                    api.prevent_close();
+                }
+            }
+            tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+                window_state::track(window);
+            }
+            tauri::WindowEvent::Destroyed => {
+                windows::closed(window.app_handle(), window.label());
+            }
+            _ => {}
+        })
+        // Counted before dispatch (`analytics_enabled`)
+        .invoke_handler(move |invoke| {
+            if let Some(state) = invoke.message.webview_ref().try_state::<AppState>() {
+                state.usage_metrics.record_command(invoke.message.command());
+            }
+            handler(invoke)
+        })
+        .build(context)
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            tauri::RunEvent::ExitRequested { api, .. } if !lifecycle::allow_exit(app) => {
+                api.prevent_exit();
+            }
+            tauri::RunEvent::Exit => lifecycle::shutdown(app),
+            _ => {}
+        });
 }
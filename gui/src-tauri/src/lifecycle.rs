@@ -0,0 +1,91 @@
+//! Clean app exit
+//!
+//! Closing the window or quitting stops the background tasks and shuts the
+//! backend down gracefully (stdin closed, bounded wait, then kill), so no
+//! Python process outlives the GUI holding the storage lock. While a call
+//! that writes storage is in flight, exit is held back and the frontend is
+//! asked to confirm with `exit-confirm-requested`.
+
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::AppState;
+use crate::{intents, tray};
+
+/// Event asking the frontend to confirm quitting during a write
+pub const EXIT_CONFIRM_EVENT: &str = "exit-confirm-requested";
+
+/// How long the backend gets to exit on its own
+pub const SHUTDOWN_GRACE: Duration = Duration::from_secs(2);
+
+/// Payload of [`EXIT_CONFIRM_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitConfirmRequest {
+    /// Storage-writing tools still waiting for the backend
+    pub tools: Vec<String>,
+}
+
+/// Storage-writing tool calls in flight
+pub async fn busy_tools(state: &AppState) -> Vec<String> {
+    state
+        .bridge
+        .in_flight_tools()
+        .into_iter()
+        .filter(|tool| intents::writes_storage(tool))
+        .collect()
+}
+
+/// Whether the app may exit now; otherwise asks the frontend to confirm
+///
+/// Called from the (synchronous) window and run event handlers.
+pub fn allow_exit(app: &AppHandle) -> bool {
+    let Some(state) = app.try_state::<AppState>() else {
+        return true;
+    };
+    if state.exit_confirmed.load(Ordering::Relaxed) {
+        return true;
+    }
+    let tools = tauri::async_runtime::block_on(busy_tools(&state));
+    if tools.is_empty() {
+        return true;
+    }
+    log::warn!("Exit held back: {} still running", tools.join(", "));
+    // The question needs a visible window (quit from the tray)
+    tray::show_main_window(app);
+    if let Err(e) = app.emit(EXIT_CONFIRM_EVENT, ExitConfirmRequest { tools }) {
+        log::warn!("Failed to emit {}: {}", EXIT_CONFIRM_EVENT, e);
+        // Nobody can confirm, don't keep the app hostage
+        return true;
+    }
+    false
+}
+
+/// Stop background tasks and the backend (blocks up to [`SHUTDOWN_GRACE`])
+pub fn shutdown(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    state.list_refresh.stop_all();
+    state.progress_streams.stop_all();
+    state.storage_watch.stop();
+    state.ai_status.stop();
+    if let Err(e) = state.usage_metrics.flush() {
+        log::warn!("Failed to save usage metrics: {:#}", e);
+    }
+    // A move/resize may still be waiting for its delayed save
+    if let Err(e) = state.window_state.save() {
+        log::warn!("Failed to save window state: {:#}", e);
+    }
+    tauri::async_runtime::block_on(async {
+        let cancelled = state.jobs.lock().await.cancel_all(Instant::now());
+        if cancelled > 0 {
+            log::info!("Cancelled {} running job(s) on exit", cancelled);
+        }
+        if let Err(e) = state.bridge.shutdown_graceful(SHUTDOWN_GRACE).await {
+            log::warn!("Failed to stop Python bridge: {}", e);
+        }
+    });
+}
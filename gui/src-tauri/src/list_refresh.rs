@@ -0,0 +1,257 @@
+//! Background task-list refresher
+//!
+//! Re-fetches the (filtered) task list on an interval and emits `tasks-diff`
+//! with only what changed since the previous snapshot. Each window has its
+//! own refresher (and filters) and only it gets the events. The first fetch
+//! is the baseline and emits nothing; like the AI status poller it pauses
+//! while the backend process is down.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+
+use crate::backend::{self, ListFilters};
+use crate::projection;
+use crate::python::PythonBridge;
+
+/// Event carrying a [`TasksDiff`]
+pub const LIST_DIFF_EVENT: &str = "tasks-diff";
+/// Lower bound for the refresh interval
+pub const MIN_INTERVAL_MS: u64 = 1000;
+
+/// Old and new value of a changed field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub id: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Changes between two list snapshots (keyed by task id)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TasksDiff {
+    /// New tasks, projected to the `list-view` preset
+    pub added: Vec<Value>,
+    pub removed: Vec<String>,
+    pub status_changed: Vec<FieldChange>,
+    pub title_changed: Vec<FieldChange>,
+}
+
+impl TasksDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.status_changed.is_empty()
+            && self.title_changed.is_empty()
+    }
+}
+
+fn status_of(task: &Value) -> &str {
+    backend::task_str(task, "status_code")
+        .or_else(|| backend::task_str(task, "status"))
+        .unwrap_or("")
+}
+
+/// Diff `next` against `prev`; output follows `next` order (removals `prev` order)
+pub fn diff(prev: &[Value], next: &[Value]) -> TasksDiff {
+    let by_id = |tasks: &[Value]| -> HashMap<String, Value> {
+        tasks
+            .iter()
+            .filter_map(|t| backend::task_str(t, "id").map(|id| (id.to_string(), t.clone())))
+            .collect()
+    };
+    let before = by_id(prev);
+    let after = by_id(next);
+    let list_view = projection::resolve_keys(&["list-view".to_string()]);
+
+    let mut out = TasksDiff::default();
+    for task in next {
+        let Some(id) = backend::task_str(task, "id") else {
+            continue;
+        };
+        let Some(old) = before.get(id) else {
+            out.added
+                .push(projection::project(task.clone(), &list_view));
+            continue;
+        };
+        let change = |from: &str, to: &str| FieldChange {
+            id: id.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        if status_of(old) != status_of(task) {
+            out.status_changed
+                .push(change(status_of(old), status_of(task)));
+        }
+        let (old_title, title) = (
+            backend::task_str(old, "title").unwrap_or(""),
+            backend::task_str(task, "title").unwrap_or(""),
+        );
+        if old_title != title {
+            out.title_changed.push(change(old_title, title));
+        }
+    }
+    out.removed = prev
+        .iter()
+        .filter_map(|t| backend::task_str(t, "id"))
+        .filter(|id| !after.contains_key(*id))
+        .map(String::from)
+        .collect();
+    out
+}
+
+/// List refreshers by window label (off until started)
+#[derive(Default)]
+pub struct ListRefresher {
+    tasks: Mutex<HashMap<String, JoinHandle<()>>>,
+}
+
+impl ListRefresher {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, JoinHandle<()>>> {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// (Re)start refreshing for window `label` with `filters`; returns the
+    /// effective interval
+    pub fn start(
+        &self,
+        app: &AppHandle,
+        bridge: PythonBridge,
+        label: &str,
+        interval_ms: u64,
+        filters: ListFilters,
+    ) -> u64 {
+        let interval_ms = interval_ms.max(MIN_INTERVAL_MS);
+        log::info!(
+            "Starting task list refresher for {} ({} ms)",
+            label,
+            interval_ms
+        );
+        let task = tauri::async_runtime::spawn(refresh_loop(
+            app.clone(),
+            bridge,
+            label.to_string(),
+            Duration::from_millis(interval_ms),
+            filters,
+        ));
+        if let Some(previous) = self.lock().insert(label.to_string(), task) {
+            previous.abort();
+        }
+        interval_ms
+    }
+
+    /// Stop refreshing for `label`; returns whether it was running
+    pub fn stop(&self, label: &str) -> bool {
+        match self.lock().remove(label) {
+            Some(task) => {
+                log::info!("Stopping task list refresher for {}", label);
+                task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stop every window's refresher (app exit)
+    pub fn stop_all(&self) {
+        for (_, task) in self.lock().drain() {
+            task.abort();
+        }
+    }
+}
+
+async fn refresh_loop(
+    app: AppHandle,
+    bridge: PythonBridge,
+    label: String,
+    interval: Duration,
+    filters: ListFilters,
+) {
+    let mut snapshot: Option<Vec<Value>> = None;
+    let mut paused = false;
+    let mut last_error: Option<String> = None;
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        let result = {
+            if !bridge.is_running().await {
+                if !paused {
+                    log::info!("Task list refresher paused (backend not running)");
+                    paused = true;
+                }
+                continue;
+            }
+            if paused {
+                log::info!("Task list refresher resumed");
+                paused = false;
+            }
+            backend::list_tasks(&bridge, Some(filters.params(true))).await
+        };
+
+        let mut tasks = match result {
+            Ok(tasks) => {
+                last_error = None;
+                tasks
+            }
+            Err(e) => {
+                let message = e.to_string();
+                if last_error.as_deref() != Some(message.as_str()) {
+                    log::warn!("Task list refresh failed: {}", message);
+                    last_error = Some(message);
+                }
+                continue;
+            }
+        };
+        filters.retain(&mut tasks);
+
+        if let Some(previous) = &snapshot {
+            let changes = diff(previous, &tasks);
+            if !changes.is_empty() {
+                if let Err(e) = app.emit_to(label.as_str(), LIST_DIFF_EVENT, &changes) {
+                    log::warn!("Failed to emit {}: {}", LIST_DIFF_EVENT, e);
+                }
+            }
+        }
+        snapshot = Some(tasks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_by_id() {
+        let prev = vec![
+            json!({"id": "A", "title": "Alpha", "status_code": "TODO", "steps_count": 1}),
+            json!({"id": "B", "title": "Beta", "status_code": "TODO"}),
+        ];
+        assert!(diff(&prev, &prev).is_empty());
+
+        let next = vec![
+            json!({"id": "A", "title": "Alpha 2", "status_code": "ACTIVE", "steps_count": 1}),
+            json!({"id": "C", "title": "Gamma", "status_code": "TODO", "steps_count": 3}),
+        ];
+        let changes = diff(&prev, &next);
+        assert_eq!(changes.removed, ["B"]);
+        assert_eq!(changes.added.len(), 1);
+        assert_eq!(changes.added[0]["id"], "C");
+        assert!(changes.added[0].get("steps_count").is_none());
+        assert_eq!(
+            changes.status_changed,
+            [FieldChange {
+                id: "A".into(),
+                from: "TODO".into(),
+                to: "ACTIVE".into()
+            }]
+        );
+        assert_eq!(changes.title_changed[0].to, "Alpha 2");
+    }
+}
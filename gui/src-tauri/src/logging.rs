@@ -0,0 +1,459 @@
+//! Logging
+//!
+//! Records go to stderr (`RUST_LOG`, default `info`, as before) and to
+//! `apply-task.log` in the app log directory, which is what a dock launch
+//! leaves behind. The file is written by its own thread fed through a
+//! bounded channel: a record that doesn't fit is dropped rather than
+//! blocking the caller. Files are rotated at `MAX_FILE_BYTES`, keeping
+//! `KEEP_FILES`. The last `RECENT_LINES` lines also stay in memory for the
+//! `doctor` report, including those logged before the directory is known.
+//!
+//! The frontend logs through `log_from_frontend` (target `frontend`, lines
+//! prefixed `[frontend]`), limited per window so a render loop can't flood
+//! the file.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use log::{Level, Log, Metadata, Record};
+use serde_json::Value;
+
+/// Current log file name inside the log directory
+pub const LOG_FILE: &str = "apply-task.log";
+/// Size at which the current file is rotated
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+/// Files kept, the current one included
+const KEEP_FILES: usize = 5;
+/// Lines kept in memory (`doctor`)
+pub const RECENT_LINES: usize = 200;
+/// Records waiting for the writer thread before new ones are dropped
+const QUEUE_LEN: usize = 4096;
+/// Frontend lines accepted per window and `FRONTEND_WINDOW`
+const FRONTEND_BURST: usize = 50;
+const FRONTEND_WINDOW: Duration = Duration::from_secs(10);
+/// Longest frontend message or context kept (characters)
+const FRONTEND_MAX_CHARS: usize = 4000;
+
+/// Size-capped log file: `apply-task.log`, then `apply-task.1.log` (newest)
+/// up to `apply-task.{keep - 1}.log`
+pub struct RotatingFile {
+    dir: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    file: Option<File>,
+    size: u64,
+}
+
+impl RotatingFile {
+    pub fn new(dir: PathBuf, max_bytes: u64, keep: usize) -> Self {
+        Self {
+            dir,
+            max_bytes,
+            keep: keep.max(1),
+            file: None,
+            size: 0,
+        }
+    }
+
+    fn path(&self, index: usize) -> PathBuf {
+        match index {
+            0 => self.dir.join(LOG_FILE),
+            n => self.dir.join(format!("apply-task.{}.log", n)),
+        }
+    }
+
+    fn open(&mut self) -> io::Result<&mut File> {
+        if self.file.is_none() {
+            fs::create_dir_all(&self.dir)?;
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.path(0))?;
+            self.size = file.metadata()?.len();
+            self.file = Some(file);
+        }
+        Ok(self.file.as_mut().expect("opened above"))
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+        let _ = fs::remove_file(self.path(self.keep - 1));
+        for index in (0..self.keep - 1).rev() {
+            let from = self.path(index);
+            if from.exists() {
+                fs::rename(&from, self.path(index + 1))?;
+            }
+        }
+        if self.keep == 1 {
+            let _ = fs::remove_file(self.path(0));
+        }
+        Ok(())
+    }
+
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let len = line.len() as u64 + 1;
+        self.open()?;
+        if self.size > 0 && self.size + len > self.max_bytes {
+            self.rotate()?;
+        }
+        let file = self.open()?;
+        writeln!(file, "{}", line)?;
+        self.size += len;
+        Ok(())
+    }
+}
+
+/// The last `capacity` lines
+#[derive(Debug)]
+struct Recent {
+    lines: VecDeque<String>,
+    capacity: usize,
+}
+
+impl Recent {
+    fn push(&mut self, line: String) {
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+}
+
+struct Logger {
+    stderr: env_logger::Logger,
+    recent: Mutex<Recent>,
+    file: OnceLock<SyncSender<String>>,
+    path: OnceLock<PathBuf>,
+    dropped: AtomicU64,
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+fn format_line(record: &Record) -> String {
+    format!(
+        "{} {:<5} {}: {}",
+        chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f%:z"),
+        record.level(),
+        record.target(),
+        record.args()
+    )
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.stderr.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.stderr.matches(record) {
+            return;
+        }
+        self.stderr.log(record);
+
+        let line = format_line(record);
+        // Under the lock, so `attach_dir` sees each line exactly once
+        let mut recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(file) = self.file.get() {
+            match file.try_send(line.clone()) {
+                Ok(()) | Err(TrySendError::Disconnected(_)) => {}
+                Err(TrySendError::Full(_)) => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        recent.push(line);
+    }
+
+    fn flush(&self) {
+        self.stderr.flush();
+    }
+}
+
+/// Install the logger (stderr and memory until `attach_dir`)
+pub fn init() {
+    let stderr =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).build();
+    let max_level = stderr.filter();
+    let logger = LOGGER.get_or_init(|| Logger {
+        stderr,
+        recent: Mutex::new(Recent {
+            lines: VecDeque::with_capacity(RECENT_LINES),
+            capacity: RECENT_LINES,
+        }),
+        file: OnceLock::new(),
+        path: OnceLock::new(),
+        dropped: AtomicU64::new(0),
+    });
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// Start writing to `dir/apply-task.log`, beginning with what was logged so far
+pub fn attach_dir(dir: &Path) {
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+    let (tx, rx) = mpsc::sync_channel::<String>(QUEUE_LEN);
+    let backlog: Vec<String> = {
+        let recent = logger.recent.lock().unwrap_or_else(|e| e.into_inner());
+        if logger.file.set(tx).is_err() {
+            return;
+        }
+        recent.lines.iter().cloned().collect()
+    };
+    let _ = logger.path.set(dir.join(LOG_FILE));
+    let mut file = RotatingFile::new(dir.to_path_buf(), MAX_FILE_BYTES, KEEP_FILES);
+
+    let spawned = std::thread::Builder::new()
+        .name("log-writer".into())
+        .spawn(move || {
+            let mut failed = false;
+            for line in backlog.into_iter().chain(rx) {
+                let dropped = LOGGER
+                    .get()
+                    .map_or(0, |l| l.dropped.swap(0, Ordering::Relaxed));
+                if dropped > 0 {
+                    let _ = file.write_line(&format!("... {} log record(s) dropped", dropped));
+                }
+                // Reported once on stderr only (logging it would loop)
+                if let Err(e) = file.write_line(&line) {
+                    if !failed {
+                        eprintln!("Failed to write log file: {}", e);
+                        failed = true;
+                    }
+                }
+            }
+        });
+    match spawned {
+        Ok(_) => log::info!("Logging to {:?}", dir.join(LOG_FILE)),
+        Err(e) => log::warn!("Failed to start the log writer: {}", e),
+    }
+}
+
+/// Current log file, once `attach_dir` ran
+pub fn log_path() -> Option<PathBuf> {
+    LOGGER.get()?.path.get().cloned()
+}
+
+/// The last `count` log lines, unless another thread is logging right now
+/// (for the panic hook, which must not wait)
+pub fn try_recent_lines(count: usize) -> Option<Vec<String>> {
+    let recent = LOGGER.get()?.recent.try_lock().ok()?;
+    let skip = recent.lines.len().saturating_sub(count);
+    Some(recent.lines.iter().skip(skip).cloned().collect())
+}
+
+/// The last `RECENT_LINES` log lines, oldest first
+pub fn recent_lines() -> Vec<String> {
+    LOGGER.get().map_or_else(Vec::new, |logger| {
+        logger
+            .recent
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .lines
+            .iter()
+            .cloned()
+            .collect()
+    })
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum FrontendLogError {
+    #[error("Unknown log level '{0}' (use error, warn, info, debug or trace)")]
+    UnknownLevel(String),
+}
+
+pub fn parse_level(level: &str) -> Result<Level, FrontendLogError> {
+    match level.trim().to_lowercase().as_str() {
+        "error" => Ok(Level::Error),
+        "warn" | "warning" => Ok(Level::Warn),
+        "info" | "log" => Ok(Level::Info),
+        "debug" => Ok(Level::Debug),
+        "trace" => Ok(Level::Trace),
+        _ => Err(FrontendLogError::UnknownLevel(level.to_string())),
+    }
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(FRONTEND_MAX_CHARS) {
+        Some((cut, _)) => format!("{}… ({} bytes)", &text[..cut], text.len()),
+        None => text.to_string(),
+    }
+}
+
+/// Lines one window sent in the current period
+#[derive(Debug)]
+struct Budget {
+    started: Instant,
+    used: usize,
+    suppressed: usize,
+}
+
+/// What `FrontendLog::admit` decided
+#[derive(Debug, PartialEq)]
+enum Admit {
+    /// Log it; lines dropped in the previous period are reported first
+    Log {
+        suppressed: usize,
+    },
+    Drop,
+}
+
+/// Per-window limit and error count of frontend log lines
+/// (`AppState::frontend_log`)
+#[derive(Debug, Default)]
+pub struct FrontendLog {
+    windows: Mutex<HashMap<String, Budget>>,
+    errors: AtomicU64,
+}
+
+impl FrontendLog {
+    fn admit(&self, window: &str, now: Instant) -> Admit {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let budget = windows.entry(window.to_string()).or_insert(Budget {
+            started: now,
+            used: 0,
+            suppressed: 0,
+        });
+        let mut suppressed = 0;
+        if now.duration_since(budget.started) >= FRONTEND_WINDOW {
+            suppressed = std::mem::take(&mut budget.suppressed);
+            budget.started = now;
+            budget.used = 0;
+        }
+        if budget.used >= FRONTEND_BURST {
+            budget.suppressed += 1;
+            return Admit::Drop;
+        }
+        budget.used += 1;
+        Admit::Log { suppressed }
+    }
+
+    /// Log a frontend line; `false` when the window is over its limit
+    pub fn log(
+        &self,
+        window: &str,
+        level: &str,
+        message: &str,
+        context: Option<&Value>,
+    ) -> Result<bool, FrontendLogError> {
+        let level = parse_level(level)?;
+        let suppressed = match self.admit(window, Instant::now()) {
+            Admit::Log { suppressed } => suppressed,
+            Admit::Drop => return Ok(false),
+        };
+        if suppressed > 0 {
+            log::warn!(target: "frontend", "[frontend] {}: {} line(s) suppressed (rate limit)", window, suppressed);
+        }
+        if level == Level::Error {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        let context = context
+            .filter(|c| !c.is_null())
+            .map(|c| format!(" {}", truncate(&c.to_string())))
+            .unwrap_or_default();
+        log::log!(target: "frontend", level, "[frontend] {}: {}{}", window, truncate(message), context);
+        Ok(true)
+    }
+
+    /// Frontend errors logged this session
+    pub fn errors(&self) -> u64 {
+        self.errors.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotating_file_keeps_newest_files() {
+        let dir = std::env::temp_dir().join(format!("apply-task-logs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        // 10-byte lines (with newline), three per file
+        let mut file = RotatingFile::new(dir.clone(), 30, 3);
+        for n in 0..10 {
+            file.write_line(&format!("line {:04}", n)).unwrap();
+        }
+        let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+        assert_eq!(read(LOG_FILE), "line 0009\n");
+        assert_eq!(
+            read("apply-task.1.log"),
+            "line 0006\nline 0007\nline 0008\n"
+        );
+        assert_eq!(
+            read("apply-task.2.log"),
+            "line 0003\nline 0004\nline 0005\n"
+        );
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 3);
+
+        // Reopening appends to the current file and respects its size
+        let mut reopened = RotatingFile::new(dir.clone(), 30, 3);
+        reopened.write_line("line 0010").unwrap();
+        assert_eq!(read(LOG_FILE), "line 0009\nline 0010\n");
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_recent_keeps_last_lines() {
+        let mut recent = Recent {
+            lines: VecDeque::new(),
+            capacity: 2,
+        };
+        for line in ["a", "b", "c"] {
+            recent.push(line.to_string());
+        }
+        assert_eq!(recent.lines, ["b", "c"]);
+    }
+
+    #[test]
+    fn test_frontend_levels_and_truncation() {
+        assert_eq!(parse_level("ERROR"), Ok(Level::Error));
+        assert_eq!(parse_level(" warning "), Ok(Level::Warn));
+        assert_eq!(
+            parse_level("fatal"),
+            Err(FrontendLogError::UnknownLevel("fatal".into()))
+        );
+        assert_eq!(truncate("short"), "short");
+        let long = "é".repeat(FRONTEND_MAX_CHARS + 1);
+        assert!(truncate(&long).ends_with(&format!("… ({} bytes)", long.len())));
+
+        let log = FrontendLog::default();
+        assert!(log.log("main", "nope", "x", None).is_err());
+        assert!(log
+            .log(
+                "main",
+                "error",
+                "boom",
+                Some(&serde_json::json!({"line": 3}))
+            )
+            .unwrap());
+        assert_eq!(log.errors(), 1);
+    }
+
+    #[test]
+    fn test_frontend_limit_is_per_window() {
+        let log = FrontendLog::default();
+        let start = Instant::now();
+        for _ in 0..FRONTEND_BURST {
+            assert_eq!(log.admit("main", start), Admit::Log { suppressed: 0 });
+        }
+        assert_eq!(log.admit("main", start), Admit::Drop);
+        assert_eq!(
+            log.admit("main", start + Duration::from_secs(1)),
+            Admit::Drop
+        );
+        assert_eq!(log.admit("quick-add", start), Admit::Log { suppressed: 0 });
+        // Next period: allowed again, the dropped count reported once
+        let later = start + FRONTEND_WINDOW;
+        assert_eq!(log.admit("main", later), Admit::Log { suppressed: 2 });
+        assert_eq!(log.admit("main", later), Admit::Log { suppressed: 0 });
+    }
+}
@@ -0,0 +1,465 @@
+//! Offline mutation queue
+//!
+//! With `offline_queue` enabled, mutations that fail because the backend is
+//! unreachable are appended to `mutation_queue.jsonl` (per project) and
+//! replayed in order once the bridge respawns. A rejected entry stays queued
+//! with its error; replay stops at the first later entry that depends on it
+//! (same task, or anything after a rejected create/global change). Rejected
+//! entries are sent again only when retried explicitly; automatic replays
+//! skip them (still blocking their dependents).
+
+use std::collections::HashSet;
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::ai_response::AIResponse;
+use crate::audit::{self, Audit};
+use crate::journal::Mutation;
+use crate::python::BridgeError;
+use crate::read_cache;
+use crate::read_only;
+use crate::sidecar;
+use crate::AppState;
+
+/// Queue file inside the project sidecar dir
+pub const QUEUE_FILE: &str = "mutation_queue.jsonl";
+/// Emitted with the [`QueuedMutation`] once it reached the backend
+pub const APPLIED_EVENT: &str = "queued-mutation-applied";
+/// Emitted with the [`QueuedMutation`] (and its `last_error`) on rejection
+pub const FAILED_EVENT: &str = "queued-mutation-failed";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedMutation {
+    pub seq: u64,
+    pub intent: String,
+    pub tool: String,
+    pub params: Value,
+    /// Task the mutation targets (`None` for creates and global changes)
+    pub task: Option<String>,
+    pub queued_at: DateTime<Utc>,
+    /// Backend error from the last replay attempt
+    pub last_error: Option<String>,
+}
+
+/// Result of sending one queued mutation
+#[derive(Debug)]
+pub enum ReplayOutcome {
+    Applied,
+    /// The backend answered with an error
+    Rejected(String),
+    /// Still unreachable; replay stops and everything stays queued
+    Offline,
+}
+
+/// What one replay pass did
+#[derive(Debug, Default)]
+pub struct ReplayReport {
+    pub applied: Vec<QueuedMutation>,
+    pub failed: Vec<QueuedMutation>,
+    /// Entries left untried (blocked by a dependency or the backend)
+    pub remaining: usize,
+}
+
+pub struct MutationQueue {
+    path: PathBuf,
+    entries: Vec<QueuedMutation>,
+}
+
+impl MutationQueue {
+    /// Load the queue at `path` (missing or unreadable file -> empty)
+    pub fn load(path: PathBuf) -> Self {
+        let entries = sidecar::read_jsonl(&path).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable mutation queue: {:#}", e);
+            Vec::new()
+        });
+        Self { path, entries }
+    }
+
+    pub fn entries(&self) -> &[QueuedMutation] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether an entry hasn't been rejected yet (one an automatic replay sends)
+    pub fn has_untried(&self) -> bool {
+        self.entries.iter().any(|e| e.last_error.is_none())
+    }
+
+    /// Append a mutation (persisted before returning)
+    pub fn enqueue(&mut self, intent: &str, tool: &str, params: Value) -> Result<QueuedMutation> {
+        let entry = QueuedMutation {
+            seq: self.entries.last().map(|e| e.seq + 1).unwrap_or(1),
+            intent: intent.to_string(),
+            tool: tool.to_string(),
+            task: read_cache::target_task(&params),
+            params,
+            queued_at: Utc::now(),
+            last_error: None,
+        };
+        sidecar::append_jsonl(&self.path, &entry)?;
+        self.entries.push(entry.clone());
+        Ok(entry)
+    }
+
+    /// Drop the entry at `index`
+    pub fn discard(&mut self, index: usize) -> Result<QueuedMutation> {
+        if index >= self.entries.len() {
+            anyhow::bail!("No queued mutation at index {}", index);
+        }
+        let entry = self.entries.remove(index);
+        self.save()?;
+        Ok(entry)
+    }
+
+    /// Send entries in order via `apply` (see the module docs for when it
+    /// stops); already rejected ones only with `retry_rejected`
+    pub async fn replay<F, Fut>(
+        &mut self,
+        retry_rejected: bool,
+        mut apply: F,
+    ) -> Result<ReplayReport>
+    where
+        F: FnMut(QueuedMutation) -> Fut,
+        Fut: Future<Output = ReplayOutcome>,
+    {
+        let mut report = ReplayReport::default();
+        let mut kept = Vec::new();
+        let mut failed_tasks: HashSet<String> = HashSet::new();
+        let mut barrier = false;
+        let mut pending = std::mem::take(&mut self.entries).into_iter();
+
+        for mut entry in pending.by_ref() {
+            let blocked = barrier
+                || entry
+                    .task
+                    .as_ref()
+                    .is_some_and(|t| failed_tasks.contains(t));
+            if blocked {
+                kept.push(entry);
+                break;
+            }
+            if entry.last_error.is_some() && !retry_rejected {
+                match &entry.task {
+                    Some(task) => {
+                        failed_tasks.insert(task.clone());
+                    }
+                    None => barrier = true,
+                }
+                kept.push(entry);
+                continue;
+            }
+            match apply(entry.clone()).await {
+                ReplayOutcome::Applied => report.applied.push(entry),
+                ReplayOutcome::Rejected(error) => {
+                    entry.last_error = Some(error);
+                    match &entry.task {
+                        Some(task) => {
+                            failed_tasks.insert(task.clone());
+                        }
+                        None => barrier = true,
+                    }
+                    report.failed.push(entry.clone());
+                    kept.push(entry);
+                }
+                ReplayOutcome::Offline => {
+                    kept.push(entry);
+                    break;
+                }
+            }
+        }
+        kept.extend(pending);
+        report.remaining = kept.len() - report.failed.len();
+        self.entries = kept;
+        self.save()?;
+        Ok(report)
+    }
+
+    /// Rewrite the file with the current entries
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {:?}", parent))?;
+        }
+        let mut text = String::new();
+        for entry in &self.entries {
+            text.push_str(&serde_json::to_string(entry)?);
+            text.push('\n');
+        }
+        fs::write(&self.path, text).with_context(|| format!("Failed to write {:?}", self.path))
+    }
+}
+
+/// Response for a mutation that was queued instead of sent
+pub fn queued_response(intent: &str, entry: &QueuedMutation) -> AIResponse {
+    AIResponse::queued(
+        intent,
+        format!(
+            "Backend unavailable; queued as #{} for replay on reconnect",
+            entry.seq
+        ),
+    )
+}
+
+/// Queue a mutation that failed with `err` if the error is transport-level
+/// and the offline queue is enabled
+pub async fn queue_if_offline(
+    state: &AppState,
+    err: &anyhow::Error,
+    intent: &str,
+    tool: &str,
+    params: &Value,
+) -> Option<QueuedMutation> {
+    if !BridgeError::is_transport(err) || !state.settings.read().await.offline_queue {
+        return None;
+    }
+    match state
+        .mutation_queue
+        .lock()
+        .await
+        .enqueue(intent, tool, params.clone())
+    {
+        Ok(entry) => Some(entry),
+        Err(e) => {
+            log::warn!("Failed to queue mutation: {:#}", e);
+            None
+        }
+    }
+}
+
+/// Replay queued mutations not rejected before, emitting one event per
+/// applied/failed entry
+pub async fn replay_queue<R: Runtime>(app: &AppHandle<R>) {
+    replay(app, false).await;
+}
+
+/// Replay every queued mutation, rejected ones included
+pub async fn retry_queue<R: Runtime>(app: &AppHandle<R>) {
+    replay(app, true).await;
+}
+
+async fn replay<R: Runtime>(app: &AppHandle<R>, retry_rejected: bool) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    if read_only::enabled(&state).await {
+        log::info!("Read-only mode: queued mutations wait");
+        return;
+    }
+    let mut queue = state.mutation_queue.lock().await;
+    let due = if retry_rejected {
+        !queue.is_empty()
+    } else {
+        queue.has_untried()
+    };
+    if !due {
+        return;
+    }
+    let bridge = &state.bridge;
+
+    log::info!("Replaying {} queued mutations", queue.entries().len());
+    let report = queue
+        .replay(retry_rejected, |entry| {
+            let bridge = bridge.clone();
+            async move {
+                match bridge.call_tool(&entry.tool, entry.params.clone()).await {
+                    Ok(result) => {
+                        let response = AIResponse::from_value(result);
+                        if response.success {
+                            ReplayOutcome::Applied
+                        } else {
+                            ReplayOutcome::Rejected(
+                                response
+                                    .error
+                                    .as_ref()
+                                    .and_then(|e| e.get("message"))
+                                    .and_then(|m| m.as_str())
+                                    .unwrap_or("Backend rejected the mutation")
+                                    .to_string(),
+                            )
+                        }
+                    }
+                    Err(e) if BridgeError::is_transport(&e) => ReplayOutcome::Offline,
+                    Err(e) => ReplayOutcome::Rejected(e.to_string()),
+                }
+            }
+        })
+        .await;
+    drop(queue);
+
+    let report = match report {
+        Ok(report) => report,
+        Err(e) => {
+            log::warn!("Failed to persist mutation queue: {:#}", e);
+            return;
+        }
+    };
+    if !report.applied.is_empty() {
+        state.storage_watch.mark_own_write();
+        state.read_cache.lock().await.clear();
+        let mut journal = state.journal.lock().await;
+        for entry in &report.applied {
+            journal.record(Mutation::new(&entry.tool, entry.task.clone()));
+        }
+    }
+    for (entry, applied) in report
+        .applied
+        .iter()
+        .map(|e| (e, true))
+        .chain(report.failed.iter().map(|e| (e, false)))
+    {
+        let mut audited = Audit::new(&entry.tool, entry.task.clone()).params(&entry.params);
+        if !applied {
+            let error = entry.last_error.as_deref();
+            audited = audited.failed(error.unwrap_or("Backend rejected the mutation"));
+        }
+        audit::record(&state, audited).await;
+    }
+    for (event, entries) in [
+        (APPLIED_EVENT, &report.applied),
+        (FAILED_EVENT, &report.failed),
+    ] {
+        for entry in entries {
+            if let Err(e) = app.emit(event, entry) {
+                log::warn!("Failed to emit {}: {}", event, e);
+            }
+        }
+    }
+}
+
+/// Replay the queue whenever the bridge (re)spawns
+pub fn spawn_replayer(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app.try_state::<AppState>() else {
+            return;
+        };
+        let mut spawns = state.bridge.subscribe_spawns();
+        while spawns.changed().await.is_ok() {
+            replay_queue(&app).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn queue_in(name: &str) -> MutationQueue {
+        let dir =
+            std::env::temp_dir().join(format!("apply-task-queue-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        MutationQueue::load(dir.join(QUEUE_FILE))
+    }
+
+    #[tokio::test]
+    async fn test_replay_stops_at_dependent_entry() {
+        let mut queue = queue_in("replay");
+        queue
+            .enqueue("note", "tasks_note", json!({"task": "T-1", "note": "a"}))
+            .unwrap();
+        queue
+            .enqueue("note", "tasks_note", json!({"task": "T-2", "note": "b"}))
+            .unwrap();
+        queue
+            .enqueue("verify", "tasks_verify", json!({"task": "T-1"}))
+            .unwrap();
+        queue
+            .enqueue("note", "tasks_note", json!({"task": "T-3"}))
+            .unwrap();
+
+        let report = queue
+            .replay(false, |entry| async move {
+                match entry.task.as_deref() {
+                    Some("T-1") => ReplayOutcome::Rejected("not found".into()),
+                    _ => ReplayOutcome::Applied,
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(report.applied.len(), 1);
+        assert_eq!(report.failed[0].last_error.as_deref(), Some("not found"));
+        assert_eq!(report.remaining, 2);
+        let seqs: Vec<u64> = queue.entries().iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, [1, 3, 4]);
+
+        // Persisted: a reload sees the same entries, discard prunes one
+        let mut reloaded = MutationQueue::load(queue.path.clone());
+        assert_eq!(reloaded.entries(), queue.entries());
+        assert_eq!(reloaded.discard(0).unwrap().seq, 1);
+        assert!(reloaded.discard(5).is_err());
+
+        let report = reloaded
+            .replay(false, |_| async { ReplayOutcome::Offline })
+            .await
+            .unwrap();
+        assert_eq!((report.applied.len(), report.remaining), (0, 2));
+        let _ = fs::remove_dir_all(queue.path.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_entries_wait_for_an_explicit_retry() {
+        let mut queue = queue_in("retry");
+        let outcome = |entry: QueuedMutation| async move {
+            match entry.task.as_deref() {
+                Some("T-1") => ReplayOutcome::Rejected("not found".into()),
+                _ => ReplayOutcome::Applied,
+            }
+        };
+        let mut sent = Vec::new();
+        queue
+            .enqueue("note", "tasks_note", json!({"task": "T-1", "note": "a"}))
+            .unwrap();
+        let report = queue
+            .replay(false, |entry| {
+                sent.push(entry.seq);
+                outcome(entry)
+            })
+            .await
+            .unwrap();
+        assert_eq!(report.failed.len(), 1);
+        assert!(!queue.has_untried());
+
+        // Skipped by automatic replays, still blocking its task
+        queue
+            .enqueue("note", "tasks_note", json!({"task": "T-2"}))
+            .unwrap();
+        queue
+            .enqueue("verify", "tasks_verify", json!({"task": "T-1"}))
+            .unwrap();
+        assert!(queue.has_untried());
+        let report = queue
+            .replay(false, |entry| {
+                sent.push(entry.seq);
+                outcome(entry)
+            })
+            .await
+            .unwrap();
+        assert!(report.failed.is_empty());
+        assert_eq!(report.applied.len(), 1);
+        assert_eq!(sent, [1, 2]);
+        let seqs: Vec<u64> = queue.entries().iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, [1, 3]);
+
+        // Sent again only when retried
+        let report = queue
+            .replay(true, |entry| {
+                sent.push(entry.seq);
+                outcome(entry)
+            })
+            .await
+            .unwrap();
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(sent, [1, 2, 1]);
+        let _ = fs::remove_dir_all(queue.path.parent().unwrap());
+    }
+}
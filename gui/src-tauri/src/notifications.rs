@@ -0,0 +1,607 @@
+//! Native OS notifications
+//!
+//! Sent for activity outside the GUI: tasks the storage watcher reports as
+//! changed are re-read and compared with what was last seen, and the AI
+//! status poller reports pauses, errors, backend crashes and the signals the
+//! AI raises when it needs an answer. Each event has a
+//! [`NotificationKind`] that can be switched off in the `notifications`
+//! settings. At most [`BURST`] notifications go out per [`BURST_WINDOW`];
+//! the overflow is folded into one summary. Clicking a notification about a
+//! task focuses the window and emits `navigate-to-task`.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::ai_signals::AiSignal;
+use crate::backend;
+use crate::deep_link::{self, TaskLink};
+use crate::tray;
+use crate::AppState;
+
+/// Notifications allowed per [`BURST_WINDOW`]
+pub const BURST: usize = 3;
+pub const BURST_WINDOW: Duration = Duration::from_secs(30);
+/// Changed tasks re-read per storage batch (the rest only count as changed)
+const MAX_TASKS_PER_BATCH: usize = 20;
+
+const DONE: &str = "DONE";
+/// AI states worth interrupting the user for
+const AI_ATTENTION_STATES: [&str; 3] = ["paused", "waiting", "error"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    TaskCompleted,
+    CheckpointsGreen,
+    AiPaused,
+    /// The AI raised a signal (question, needs input)
+    AiSignal,
+    BackendCrashed,
+}
+
+/// `notifications` in the settings file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationPrefs {
+    pub enabled: bool,
+    pub task_completed: bool,
+    /// Every checkpoint of a step confirmed
+    pub checkpoints_green: bool,
+    /// AI paused, waiting for the user or reporting an error
+    pub ai_paused: bool,
+    /// AI asks for input or a decision
+    pub ai_signal: bool,
+    pub backend_crashed: bool,
+    /// Stay quiet while the main window has focus
+    pub only_when_unfocused: bool,
+}
+
+impl Default for NotificationPrefs {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            task_completed: true,
+            checkpoints_green: true,
+            ai_paused: true,
+            ai_signal: true,
+            backend_crashed: true,
+            only_when_unfocused: true,
+        }
+    }
+}
+
+impl NotificationPrefs {
+    pub fn allows(&self, kind: NotificationKind) -> bool {
+        self.enabled
+            && match kind {
+                NotificationKind::TaskCompleted => self.task_completed,
+                NotificationKind::CheckpointsGreen => self.checkpoints_green,
+                NotificationKind::AiPaused => self.ai_paused,
+                NotificationKind::AiSignal => self.ai_signal,
+                NotificationKind::BackendCrashed => self.backend_crashed,
+            }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notice {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+    /// Task opened when the notification is clicked
+    pub task_id: Option<String>,
+}
+
+/// What was last seen of a task
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TaskSeen {
+    status: String,
+    /// Step path -> title of steps with every checkpoint confirmed (`None`
+    /// until the steps were seen)
+    green: Option<BTreeMap<String, String>>,
+}
+
+fn flag(step: &Value, key: &str) -> bool {
+    step.get(key).and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+/// Every checkpoint of `step` is confirmed (open steps report `ready`)
+fn step_green(step: &Value) -> bool {
+    if let Some(ready) = step.get("ready").and_then(|r| r.as_bool()) {
+        return ready;
+    }
+    let criteria = flag(step, "criteria_confirmed") || flag(step, "criteria_auto_confirmed");
+    let tests = flag(step, "tests_confirmed") || flag(step, "tests_auto_confirmed");
+    let required = step
+        .get("required_checkpoints")
+        .and_then(|r| r.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|c| c.as_str())
+        .all(|c| flag(step, &format!("{}_confirmed", c.trim().to_lowercase())));
+    criteria && tests && required
+}
+
+fn collect_green(steps: &[Value], out: &mut BTreeMap<String, String>) {
+    for step in steps {
+        let path = backend::task_str(step, "path").or_else(|| backend::task_str(step, "id"));
+        if let Some(path) = path.filter(|_| step_green(step)) {
+            let title = backend::task_str(step, "title").unwrap_or(path);
+            out.insert(path.to_string(), title.to_string());
+        }
+        if let Some(children) = step.get("steps").and_then(|s| s.as_array()) {
+            collect_green(children, out);
+        }
+    }
+}
+
+fn task_seen(task: &Value) -> TaskSeen {
+    let status = backend::task_str(task, "status_code")
+        .or_else(|| backend::task_str(task, "status"))
+        .unwrap_or("")
+        .to_uppercase();
+    let green = task.get("steps").and_then(|s| s.as_array()).map(|steps| {
+        let mut green = BTreeMap::new();
+        collect_green(steps, &mut green);
+        green
+    });
+    TaskSeen { status, green }
+}
+
+/// Notices for a task that changed (none on first sight)
+fn task_notices(id: &str, title: &str, prev: Option<&TaskSeen>, next: &TaskSeen) -> Vec<Notice> {
+    let Some(prev) = prev else {
+        return Vec::new();
+    };
+    let notice = |kind, heading: &str, body: String| Notice {
+        kind,
+        title: heading.to_string(),
+        body,
+        task_id: Some(id.to_string()),
+    };
+
+    let mut notices = Vec::new();
+    if next.status == DONE && prev.status != DONE {
+        notices.push(notice(
+            NotificationKind::TaskCompleted,
+            "Task completed",
+            format!("{}: {}", id, title),
+        ));
+    } else if let (Some(before), Some(after)) = (&prev.green, &next.green) {
+        for step in after
+            .iter()
+            .filter(|(path, _)| !before.contains_key(*path))
+            .map(|(_, t)| t)
+        {
+            notices.push(notice(
+                NotificationKind::CheckpointsGreen,
+                "Checkpoints green",
+                format!("{}: {}", id, step),
+            ));
+        }
+    }
+    notices
+}
+
+fn ai_state(status: &Value) -> &str {
+    status.get("status").and_then(|s| s.as_str()).unwrap_or("")
+}
+
+/// Notice for the AI entering a state that needs the user
+fn ai_notice(prev: Option<&Value>, next: &Value) -> Option<Notice> {
+    // The first poll is the baseline (the AI may have been paused for hours)
+    let prev = ai_state(prev?);
+    let state = ai_state(next);
+    if !AI_ATTENTION_STATES.contains(&state) || prev == state {
+        return None;
+    }
+    let title = match state {
+        "paused" => "AI paused",
+        "waiting" => "AI is waiting for you",
+        _ => "AI hit an error",
+    };
+    let current = next.get("current").filter(|c| c.is_object());
+    let op = current
+        .and_then(|c| c.get("op"))
+        .and_then(|o| o.as_str())
+        .unwrap_or("");
+    // `TASK-001.s:0` -> `TASK-001`
+    let task_id = current
+        .and_then(|c| c.get("path"))
+        .and_then(|p| p.as_str())
+        .and_then(|p| p.split('.').next())
+        .filter(|id| !id.is_empty())
+        .map(String::from);
+    Some(Notice {
+        kind: NotificationKind::AiPaused,
+        title: title.to_string(),
+        body: match (&task_id, op) {
+            (Some(id), op) if !op.is_empty() => format!("{} ({})", op, id),
+            (_, op) if !op.is_empty() => op.to_string(),
+            (Some(id), _) => id.clone(),
+            _ => String::new(),
+        },
+        task_id,
+    })
+}
+
+fn signal_notice(signal: &AiSignal) -> Notice {
+    Notice {
+        kind: NotificationKind::AiSignal,
+        title: "AI needs your input".to_string(),
+        body: match &signal.task_id {
+            Some(id) => format!("{} ({})", signal.message, id),
+            None => signal.message.clone(),
+        },
+        task_id: signal.task_id.clone(),
+    }
+}
+
+/// Sliding-window limit with the overflow folded into a summary
+#[derive(Debug, Default)]
+struct RateLimiter {
+    sent: VecDeque<Instant>,
+    /// Notices dropped since the last one that went out
+    muted: usize,
+}
+
+impl RateLimiter {
+    fn admit(&mut self, mut notices: Vec<Notice>, now: Instant) -> Vec<Notice> {
+        while self
+            .sent
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= BURST_WINDOW)
+        {
+            self.sent.pop_front();
+        }
+        let budget = BURST.saturating_sub(self.sent.len());
+        if budget == 0 || notices.is_empty() {
+            self.muted += notices.len();
+            return Vec::new();
+        }
+
+        let pending = notices.len() + usize::from(self.muted > 0);
+        if pending > budget || self.muted > 0 {
+            let keep = if pending > budget {
+                budget - 1
+            } else {
+                notices.len()
+            };
+            let folded = notices.split_off(keep).len() + self.muted;
+            notices.push(Notice {
+                kind: notices
+                    .first()
+                    .map_or(NotificationKind::TaskCompleted, |n| n.kind),
+                title: "Apply Task".to_string(),
+                body: format!("{} more updates", folded),
+                task_id: None,
+            });
+        }
+        self.muted = 0;
+        self.sent.extend(notices.iter().map(|_| now));
+        notices
+    }
+}
+
+/// Last seen tasks and the rate limit (one per app)
+#[derive(Debug, Default)]
+pub struct Notifier {
+    tasks: Mutex<HashMap<String, TaskSeen>>,
+    limiter: Mutex<RateLimiter>,
+}
+
+/// `TASK-001.task` -> `TASK-001`
+fn task_id_of(file: &str) -> Option<String> {
+    let name = Path::new(file).file_name()?.to_str()?;
+    name.strip_suffix(".task").map(String::from)
+}
+
+/// Remember the current tasks so the first change already compares
+/// against something (called when the storage watcher starts)
+pub async fn seed(app: AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let bridge = &state.bridge;
+    let tasks = match backend::list_tasks(bridge, None).await {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            log::warn!("Notifications start without a baseline: {:#}", e);
+            return;
+        }
+    };
+    let seen = tasks
+        .iter()
+        .filter_map(|t| backend::task_str(t, "id").map(|id| (id.to_string(), task_seen(t))))
+        .collect();
+    *state
+        .notifications
+        .tasks
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = seen;
+}
+
+/// Re-read the tasks behind changed storage files and notify about them
+pub async fn storage_changed(app: AppHandle, files: Vec<String>) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let bridge = &state.bridge;
+    let mut notices = Vec::new();
+    for id in files
+        .iter()
+        .filter_map(|f| task_id_of(f))
+        .take(MAX_TASKS_PER_BATCH)
+    {
+        let task = backend::show_task(bridge, &id).await;
+        let mut tasks = state
+            .notifications
+            .tasks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let Ok(task) = task else {
+            tasks.remove(&id);
+            continue;
+        };
+        let next = task_seen(&task);
+        let title = backend::task_str(&task, "title").unwrap_or("");
+        notices.extend(task_notices(&id, title, tasks.get(&id), &next));
+        tasks.insert(id, next);
+    }
+    dispatch(&app, &state, notices).await;
+}
+
+/// Notify about an AI status transition (from the status poller)
+pub fn ai_status_changed(app: &AppHandle, prev: Option<&Value>, next: &Value) {
+    if let Some(notice) = ai_notice(prev, next) {
+        spawn_dispatch(app, vec![notice]);
+    }
+}
+
+/// Notify about signals the AI just raised (from the status poller)
+pub fn ai_signals_raised(app: &AppHandle, signals: &[AiSignal]) {
+    if !signals.is_empty() {
+        spawn_dispatch(app, signals.iter().map(signal_notice).collect());
+    }
+}
+
+/// The backend exited on its own
+pub fn backend_crashed(app: &AppHandle, status: &str) {
+    spawn_dispatch(
+        app,
+        vec![Notice {
+            kind: NotificationKind::BackendCrashed,
+            title: "Backend stopped".to_string(),
+            body: format!(
+                "apply_task exited ({}); it restarts on the next request",
+                status
+            ),
+            task_id: None,
+        }],
+    );
+}
+
+fn spawn_dispatch(app: &AppHandle, notices: Vec<Notice>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Some(state) = app.try_state::<AppState>() {
+            dispatch(&app, &state, notices).await;
+        }
+    });
+}
+
+fn main_window_focused(app: &AppHandle) -> bool {
+    app.get_webview_window("main")
+        .and_then(|w| w.is_focused().ok())
+        .unwrap_or(false)
+}
+
+async fn dispatch(app: &AppHandle, state: &AppState, notices: Vec<Notice>) {
+    let prefs = state.settings.read().await.notifications.clone();
+    let notices: Vec<Notice> = notices
+        .into_iter()
+        .filter(|n| prefs.allows(n.kind))
+        .collect();
+    if notices.is_empty() || (prefs.only_when_unfocused && main_window_focused(app)) {
+        return;
+    }
+    let notices = state
+        .notifications
+        .limiter
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .admit(notices, Instant::now());
+    if notices.is_empty() {
+        log::info!(
+            "Notifications muted (more than {} in {:?})",
+            BURST,
+            BURST_WINDOW
+        );
+        return;
+    }
+    // Links carry the project's namespace, checked again on click
+    let namespace = match notices.iter().any(|n| n.task_id.is_some()) {
+        true => deep_link::current_namespace(state).await,
+        false => None,
+    };
+    for notice in notices {
+        let link = notice
+            .task_id
+            .clone()
+            .zip(namespace.clone())
+            .map(|(task_id, namespace)| TaskLink { namespace, task_id });
+        show(app, notice, link);
+    }
+}
+
+/// Clickable notification: the plugin can't report clicks on desktop, so
+/// these go through notify-rust and wait for the action
+#[cfg(desktop)]
+fn show(app: &AppHandle, notice: Notice, link: Option<TaskLink>) {
+    let Some(link) = link else {
+        return show_plain(app, notice);
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let identifier = app.config().identifier.clone();
+        let mut notification = notify_rust::Notification::new();
+        notification
+            .appname(&identifier)
+            .summary(&notice.title)
+            .body(&notice.body)
+            .action("default", "Open")
+            .auto_icon();
+        #[cfg(windows)]
+        if !tauri::is_dev() {
+            notification.app_id(&identifier);
+        }
+        #[cfg(target_os = "macos")]
+        let _ = notify_rust::set_application(if tauri::is_dev() {
+            "com.apple.Terminal"
+        } else {
+            &identifier
+        });
+        match notification.show() {
+            Ok(handle) => handle.wait_for_action(|action| {
+                if action != "__closed" {
+                    tray::show_main_window(&app);
+                    deep_link::open_link(&app, link);
+                }
+            }),
+            Err(e) => log::warn!("Failed to show notification: {}", e),
+        }
+    });
+}
+
+#[cfg(mobile)]
+fn show(app: &AppHandle, notice: Notice, _link: Option<TaskLink>) {
+    show_plain(app, notice);
+}
+
+fn show_plain(app: &AppHandle, notice: Notice) {
+    let shown = app
+        .notification()
+        .builder()
+        .title(&notice.title)
+        .body(&notice.body)
+        .show();
+    if let Err(e) = shown {
+        log::warn!("Failed to show notification: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn notice(body: &str) -> Notice {
+        Notice {
+            kind: NotificationKind::TaskCompleted,
+            title: "Task completed".into(),
+            body: body.into(),
+            task_id: Some(body.into()),
+        }
+    }
+
+    #[test]
+    fn test_task_notices() {
+        let task = |status: &str, tests_confirmed: bool| {
+            json!({
+                "status_code": status,
+                "steps": [{
+                    "path": "s:0", "title": "Parser", "completed": false, "ready": false,
+                    "steps": [{
+                        "path": "s:0.s:0", "title": "Lexer", "completed": true,
+                        "criteria_confirmed": true, "tests_confirmed": tests_confirmed,
+                        "required_checkpoints": ["docs"], "docs_confirmed": true
+                    }]
+                }]
+            })
+        };
+        let before = task_seen(&task("ACTIVE", false));
+        let after = task_seen(&task("ACTIVE", true));
+        assert_eq!(before.green, Some(BTreeMap::new()));
+
+        assert!(task_notices("T-1", "Build", None, &after).is_empty());
+        let green = task_notices("T-1", "Build", Some(&before), &after);
+        assert_eq!(green.len(), 1);
+        assert_eq!(green[0].kind, NotificationKind::CheckpointsGreen);
+        assert_eq!(green[0].body, "T-1: Lexer");
+        assert!(task_notices("T-1", "Build", Some(&after), &after).is_empty());
+
+        let done = task_seen(&task("DONE", true));
+        let completed = task_notices("T-1", "Build", Some(&before), &done);
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].kind, NotificationKind::TaskCompleted);
+        assert_eq!(completed[0].task_id.as_deref(), Some("T-1"));
+        // Seeded from a list without steps: no checkpoint baseline yet
+        let seeded = task_seen(&json!({"status": "ACTIVE"}));
+        assert!(task_notices("T-1", "Build", Some(&seeded), &after).is_empty());
+    }
+
+    #[test]
+    fn test_ai_notice_on_transition() {
+        let idle = json!({"status": "idle", "current": null});
+        let paused = json!({
+            "status": "paused",
+            "current": {"op": "tasks_edit", "path": "TASK-7.s:1"}
+        });
+        let notice = ai_notice(Some(&idle), &paused).unwrap();
+        assert_eq!(notice.title, "AI paused");
+        assert_eq!(notice.body, "tasks_edit (TASK-7)");
+        assert_eq!(notice.task_id.as_deref(), Some("TASK-7"));
+        assert_eq!(ai_notice(Some(&paused), &paused), None);
+        assert_eq!(ai_notice(None, &paused), None);
+        assert_eq!(ai_notice(Some(&paused), &idle), None);
+
+        let signal = &crate::ai_signals::extract(&json!({
+            "ai_signals": [{ "id": "q1", "message": "Pick a schema", "task": "TASK-7" }]
+        }))[0];
+        let notice = signal_notice(signal);
+        assert_eq!(notice.kind, NotificationKind::AiSignal);
+        assert_eq!(notice.title, "AI needs your input");
+        assert_eq!(notice.body, "Pick a schema (TASK-7)");
+        assert_eq!(notice.task_id.as_deref(), Some("TASK-7"));
+    }
+
+    #[test]
+    fn test_rate_limiter_folds_overflow() {
+        let t0 = Instant::now();
+        let mut limiter = RateLimiter::default();
+        let batch: Vec<Notice> = (1..=5).map(|i| notice(&i.to_string())).collect();
+        let sent = limiter.admit(batch, t0);
+        assert_eq!(sent.len(), BURST);
+        assert_eq!(sent[BURST - 1].body, "3 more updates");
+        assert_eq!(sent[BURST - 1].task_id, None);
+
+        // Budget used up: counted, then reported with the next one
+        assert!(limiter.admit(vec![notice("6")], t0).is_empty());
+        let later = limiter.admit(vec![notice("7")], t0 + BURST_WINDOW);
+        assert_eq!(
+            later.iter().map(|n| n.body.as_str()).collect::<Vec<_>>(),
+            ["7", "1 more updates"]
+        );
+    }
+
+    #[test]
+    fn test_prefs_and_file_names() {
+        let mut prefs = NotificationPrefs::default();
+        assert!(prefs.allows(NotificationKind::BackendCrashed));
+        prefs.backend_crashed = false;
+        assert!(!prefs.allows(NotificationKind::BackendCrashed));
+        prefs.enabled = false;
+        assert!(!prefs.allows(NotificationKind::TaskCompleted));
+
+        assert_eq!(task_id_of("TASK-001.task").as_deref(), Some("TASK-001"));
+        assert_eq!(task_id_of("ns/TASK-002.task").as_deref(), Some("TASK-002"));
+        assert_eq!(task_id_of(".last"), None);
+    }
+}
@@ -0,0 +1,116 @@
+//! Batch detail fetches for `tasks_prefetch`
+//!
+//! Hover previews call `tasks_show` once per row; prefetching the visible
+//! rows fills the read cache first so those calls are hits. A batch is
+//! trimmed, deduplicated (first occurrence wins) and capped at
+//! [`MAX_BATCH`] ids, and at most [`MAX_CONCURRENT_FETCHES`] fetches are in
+//! flight at once so a scroll doesn't flood the backend.
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Most ids one `tasks_prefetch` call fetches
+pub const MAX_BATCH: usize = 50;
+/// Fetches in flight at once
+pub const MAX_CONCURRENT_FETCHES: usize = 4;
+
+/// Ids to fetch from `ids`, and how many distinct ids were over the cap
+pub fn batch(ids: Vec<String>) -> (Vec<String>, usize) {
+    let mut seen = HashSet::new();
+    let mut unique: Vec<String> = ids
+        .into_iter()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty() && seen.insert(id.clone()))
+        .collect();
+    let dropped = unique.len().saturating_sub(MAX_BATCH);
+    unique.truncate(MAX_BATCH);
+    (unique, dropped)
+}
+
+/// `fetch` every id, bounded; results come back in the order of `ids`
+pub async fn fetch_all<F, Fut>(ids: Vec<String>, fetch: F) -> Vec<(String, Result<Value>)>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Value>> + Send + 'static,
+{
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_FETCHES));
+    let mut jobs = JoinSet::new();
+    for (index, id) in ids.iter().enumerate() {
+        let semaphore = semaphore.clone();
+        let request = fetch(id.clone());
+        jobs.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            (index, request.await)
+        });
+    }
+
+    let mut results: Vec<Option<Result<Value>>> = ids.iter().map(|_| None).collect();
+    while let Some(joined) = jobs.join_next().await {
+        match joined {
+            Ok((index, result)) => results[index] = Some(result),
+            Err(e) => log::warn!("Prefetch task failed: {}", e),
+        }
+    }
+    ids.into_iter()
+        .zip(results)
+        .map(|(id, result)| {
+            let result = result.unwrap_or_else(|| Err(anyhow!("Task not fetched")));
+            (id, result)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn test_batch_dedupes_and_caps() {
+        let (ids, dropped) = batch(["a", " b ", "a", "", "b", "c"].map(String::from).to_vec());
+        assert_eq!(ids, ["a", "b", "c"]);
+        assert_eq!(dropped, 0);
+
+        let many: Vec<String> = (0..MAX_BATCH + 7).map(|i| format!("t{}", i % 60)).collect();
+        let (ids, dropped) = batch(many);
+        assert_eq!(ids.len(), MAX_BATCH);
+        assert_eq!(ids[0], "t0");
+        assert_eq!(dropped, 7);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_is_bounded_and_ordered() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let ids: Vec<String> = (0..10).map(|i| format!("t{}", i)).collect();
+
+        let results = fetch_all(ids.clone(), |id| {
+            let (running, peak) = (running.clone(), peak.clone());
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                match id.as_str() {
+                    "t3" => Err(anyhow!("Task not found: t3")),
+                    _ => Ok(json!({ "id": id })),
+                }
+            }
+        })
+        .await;
+
+        assert!(peak.load(Ordering::SeqCst) <= MAX_CONCURRENT_FETCHES);
+        let got: Vec<&str> = results.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(got, ids);
+        assert!(results[3].1.is_err());
+        assert_eq!(results[4].1.as_ref().unwrap()["id"], "t4");
+    }
+}
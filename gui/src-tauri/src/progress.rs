@@ -0,0 +1,188 @@
+//! Canonical checkpoint progress
+//!
+//! Mirrors `Step._effective_required_checkpoints` / `_checkpoint_ok` from
+//! `core/step.py`: every subtask requires its `required_checkpoints`
+//! (default `criteria` + `tests`), and a task rolls up all nested subtasks.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Checkpoints required when a step declares none
+const DEFAULT_CHECKPOINTS: [&str; 2] = ["criteria", "tests"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubtaskProgress {
+    pub path: String,
+    pub title: String,
+    pub done: u32,
+    pub total: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Progress {
+    pub done: u32,
+    pub total: u32,
+    /// Rounded percentage; `None` when there is nothing to check
+    pub percent: Option<u32>,
+    /// Every subtask, depth-first
+    pub subtasks: Vec<SubtaskProgress>,
+}
+
+fn flag(step: &Value, key: &str) -> bool {
+    step.get(key).and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+fn required_checkpoints(step: &Value) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for name in step
+        .get("required_checkpoints")
+        .and_then(|r| r.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+    {
+        let name = name.trim().to_lowercase();
+        if !name.is_empty() && !out.contains(&name) {
+            out.push(name);
+        }
+    }
+    if out.is_empty() {
+        out = DEFAULT_CHECKPOINTS.iter().map(|s| s.to_string()).collect();
+    }
+    out
+}
+
+fn checkpoint_ok(step: &Value, checkpoint: &str) -> bool {
+    match checkpoint {
+        "tests" => flag(step, "tests_confirmed") || flag(step, "tests_auto_confirmed"),
+        other => flag(step, &format!("{}_confirmed", other)),
+    }
+}
+
+fn collect(steps: &Value, out: &mut Vec<SubtaskProgress>) {
+    let Some(steps) = steps.as_array() else {
+        return;
+    };
+    for step in steps {
+        let required = required_checkpoints(step);
+        out.push(SubtaskProgress {
+            path: step
+                .get("path")
+                .and_then(|p| p.as_str())
+                .unwrap_or("")
+                .to_string(),
+            title: step
+                .get("title")
+                .and_then(|t| t.as_str())
+                .unwrap_or("")
+                .to_string(),
+            done: required.iter().filter(|c| checkpoint_ok(step, c)).count() as u32,
+            total: required.len() as u32,
+        });
+
+        if let Some(children) = step.get("steps") {
+            collect(children, out);
+        }
+        for task in step
+            .pointer("/plan/tasks")
+            .and_then(|t| t.as_array())
+            .into_iter()
+            .flatten()
+        {
+            if let Some(children) = task.get("steps") {
+                collect(children, out);
+            }
+        }
+    }
+}
+
+/// Progress of a task payload, or `None` if it carries no `steps` (compact)
+pub fn task_progress(task: &Value) -> Option<Progress> {
+    let steps = task.get("steps").filter(|s| s.is_array())?;
+    let mut subtasks = Vec::new();
+    collect(steps, &mut subtasks);
+
+    let done = subtasks.iter().map(|s| s.done).sum();
+    let total: u32 = subtasks.iter().map(|s| s.total).sum();
+    let percent = (total > 0).then(|| ((done as f64 / total as f64) * 100.0).round() as u32);
+    Some(Progress {
+        done,
+        total,
+        percent,
+        subtasks,
+    })
+}
+
+/// Replace `progress` on a task payload with the computed summary.
+///
+/// The backend's own number is kept as `backend_progress`. Payloads without
+/// steps are left untouched.
+pub fn attach(task: &mut Value) {
+    let Some(progress) = task_progress(task) else {
+        return;
+    };
+    if let Some(obj) = task.as_object_mut() {
+        if let Some(previous) = obj.remove("progress") {
+            obj.insert("backend_progress".to_string(), previous);
+        }
+        if let Ok(progress) = serde_json::to_value(progress) {
+            obj.insert("progress".to_string(), progress);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/tasks_context.json");
+
+    #[test]
+    fn test_captured_task_rolls_up() {
+        let response: Value = serde_json::from_str(FIXTURE).unwrap();
+        let progress = task_progress(&response["result"]["current_task"]).unwrap();
+        assert_eq!((progress.done, progress.total), (2, 4));
+        assert_eq!(progress.percent, Some(50));
+        assert_eq!(progress.subtasks[0].path, "s:0");
+        assert_eq!(progress.subtasks[1].done, 0);
+    }
+
+    #[test]
+    fn test_nested_and_required_checkpoints() {
+        let task = json!({"steps": [
+            {"path": "s:0", "criteria_confirmed": true, "tests_auto_confirmed": true,
+             "steps": [{"path": "s:0.s:0", "required_checkpoints": ["Security", "docs", "security"],
+                        "security_confirmed": true}]},
+            {"path": "s:1", "plan": {"tasks": [{"steps": [{"path": "s:1.t:0.s:0", "criteria_confirmed": true}]}]}}
+        ]});
+        let progress = task_progress(&task).unwrap();
+        assert_eq!(
+            progress
+                .subtasks
+                .iter()
+                .map(|s| (s.path.as_str(), s.done, s.total))
+                .collect::<Vec<_>>(),
+            [
+                ("s:0", 2, 2),
+                ("s:0.s:0", 1, 2),
+                ("s:1", 0, 2),
+                ("s:1.t:0.s:0", 1, 2)
+            ]
+        );
+        assert_eq!((progress.done, progress.total), (4, 8));
+        assert_eq!(progress.percent, Some(50));
+    }
+
+    #[test]
+    fn test_empty_and_compact_tasks() {
+        let empty = task_progress(&json!({"steps": []})).unwrap();
+        assert_eq!((empty.done, empty.total, empty.percent), (0, 0, None));
+        assert!(task_progress(&json!({"id": "T-1", "progress": 0})).is_none());
+
+        let mut task = json!({"id": "T-1", "progress": 40, "steps": []});
+        attach(&mut task);
+        assert_eq!(task["backend_progress"], 40);
+        assert!(task["progress"]["percent"].is_null());
+    }
+}
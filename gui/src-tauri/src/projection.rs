@@ -0,0 +1,85 @@
+//! Task payload projection
+//!
+//! Strips list payloads down to the keys a view actually renders before they
+//! cross the IPC boundary. `id` is always kept.
+
+use serde_json::{Map, Value};
+
+/// Named projection presets
+const PRESETS: &[(&str, &[&str])] = &[(
+    "list-view",
+    &[
+        "id",
+        "title",
+        "status",
+        "status_code",
+        "priority",
+        "tags",
+        "progress",
+    ],
+)];
+
+/// Expand preset names and dedupe; `id` comes first
+pub fn resolve_keys(projection: &[String]) -> Vec<String> {
+    let mut keys = vec!["id".to_string()];
+    for entry in projection {
+        let entry = entry.trim();
+        let expanded: Vec<&str> = match PRESETS.iter().find(|(name, _)| *name == entry) {
+            Some((_, preset)) => preset.to_vec(),
+            None => vec![entry],
+        };
+        for key in expanded {
+            if !key.is_empty() && !keys.iter().any(|k| k == key) {
+                keys.push(key.to_string());
+            }
+        }
+    }
+    keys
+}
+
+/// Keep only `keys` of an object payload
+pub fn project(task: Value, keys: &[String]) -> Value {
+    match task {
+        Value::Object(mut obj) => {
+            let mut out = Map::new();
+            for key in keys {
+                if let Some(value) = obj.remove(key) {
+                    out.insert(key.clone(), value);
+                }
+            }
+            Value::Object(out)
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_resolve_keys_expands_presets() {
+        assert_eq!(resolve_keys(&[]), ["id"]);
+        assert_eq!(
+            resolve_keys(&["title".into(), " list-view ".into(), "domain".into()]),
+            [
+                "id",
+                "title",
+                "status",
+                "status_code",
+                "priority",
+                "tags",
+                "progress",
+                "domain"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_project_keeps_requested_keys() {
+        let keys = resolve_keys(&["title".into(), "missing".into()]);
+        let task = json!({"id": "T-1", "title": "A", "description": "long", "steps": []});
+        assert_eq!(project(task, &keys), json!({"id": "T-1", "title": "A"}));
+    }
+}
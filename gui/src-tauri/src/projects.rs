@@ -0,0 +1,183 @@
+//! Recent projects registry
+//!
+//! `projects.json` in the app config directory remembers every project the
+//! GUI opened, so it can offer them when launched without a working
+//! directory hint (e.g. from a desktop launcher).
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::detection::has_root_markers;
+use crate::sidecar;
+
+/// File name of the registry inside the app config dir
+pub const PROJECTS_FILE: &str = "projects.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectEntry {
+    pub path: PathBuf,
+    pub name: String,
+    pub last_opened: Option<DateTime<Utc>>,
+}
+
+/// Registry entry as returned to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectInfo {
+    pub path: String,
+    pub name: String,
+    pub last_opened: Option<DateTime<Utc>>,
+    /// The directory is still there
+    pub exists: bool,
+    /// The backend currently runs in this project
+    pub current: bool,
+}
+
+fn is_project(dir: &Path) -> bool {
+    dir.is_dir() && (has_root_markers(dir) || dir.join(".tasks").is_dir())
+}
+
+/// The apply_task project at `path` or directly inside it
+///
+/// A directory qualifies with the root markers or a local `.tasks` storage.
+pub fn project_root(path: &Path) -> Option<PathBuf> {
+    let path = path.canonicalize().ok()?;
+    if is_project(&path) {
+        return Some(path);
+    }
+    let mut children: Vec<PathBuf> = std::fs::read_dir(&path)
+        .ok()?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| is_project(p))
+        .collect();
+    children.sort();
+    children.into_iter().next()
+}
+
+fn display_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+/// In-memory registry backed by the config file
+#[derive(Debug, Default)]
+pub struct ProjectRegistry {
+    path: Option<PathBuf>,
+    projects: Vec<ProjectEntry>,
+}
+
+impl ProjectRegistry {
+    /// Load the registry at `path` (missing or unreadable file -> empty)
+    pub fn load(path: PathBuf) -> Self {
+        let projects = sidecar::read_json(&path).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable project registry: {:#}", e);
+            Vec::new()
+        });
+        Self {
+            path: Some(path),
+            projects,
+        }
+    }
+
+    /// Known projects, most recently opened first
+    pub fn list(&self, current: &Path) -> Vec<ProjectInfo> {
+        let mut projects: Vec<&ProjectEntry> = self.projects.iter().collect();
+        projects.sort_by_key(|p| std::cmp::Reverse(p.last_opened));
+        projects
+            .into_iter()
+            .map(|p| ProjectInfo {
+                path: p.path.to_string_lossy().to_string(),
+                name: p.name.clone(),
+                last_opened: p.last_opened,
+                exists: p.path.is_dir(),
+                current: p.path == current,
+            })
+            .collect()
+    }
+
+    /// Validate and remember a project, returning its resolved root
+    pub fn add(&mut self, path: &Path) -> Result<PathBuf> {
+        let Some(root) = project_root(path) else {
+            bail!("{:?} is not an apply_task project", path);
+        };
+        if !self.projects.iter().any(|p| p.path == root) {
+            self.projects.push(ProjectEntry {
+                name: display_name(&root),
+                path: root.clone(),
+                last_opened: None,
+            });
+            self.save()?;
+        }
+        Ok(root)
+    }
+
+    /// Record that `root` was opened (adding it if unknown)
+    pub fn touch(&mut self, root: &Path) -> Result<()> {
+        let now = Some(Utc::now());
+        match self.projects.iter_mut().find(|p| p.path == root) {
+            Some(entry) => entry.last_opened = now,
+            None => self.projects.push(ProjectEntry {
+                path: root.to_path_buf(),
+                name: display_name(root),
+                last_opened: now,
+            }),
+        }
+        self.save()
+    }
+
+    /// Forget a project; `false` if it wasn't registered
+    pub fn remove(&mut self, path: &Path) -> Result<bool> {
+        let resolved = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let before = self.projects.len();
+        self.projects
+            .retain(|p| p.path != resolved && p.path != path);
+        if self.projects.len() == before {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    fn save(&self) -> Result<()> {
+        match &self.path {
+            Some(path) => sidecar::write_json(path, &self.projects),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_touch_remove() {
+        let dir = std::env::temp_dir().join(format!("apply-task-projects-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let project = dir.join("workspace").join("alpha");
+        std::fs::create_dir_all(project.join(".tasks")).unwrap();
+        std::fs::create_dir_all(dir.join("plain")).unwrap();
+        let file = dir.join("config").join(PROJECTS_FILE);
+
+        let mut registry = ProjectRegistry::load(file.clone());
+        assert!(registry.add(&dir.join("plain")).is_err());
+        // A parent containing the project resolves to it
+        let root = registry.add(&dir.join("workspace")).unwrap();
+        assert_eq!(root, project.canonicalize().unwrap());
+        registry.add(&project).unwrap();
+        registry.touch(&root).unwrap();
+
+        let listed = ProjectRegistry::load(file.clone()).list(&root);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].name, "alpha");
+        assert!(listed[0].exists && listed[0].current && listed[0].last_opened.is_some());
+
+        assert!(registry.remove(&project).unwrap());
+        assert!(!registry.remove(&project).unwrap());
+        assert!(ProjectRegistry::load(file).list(&root).is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
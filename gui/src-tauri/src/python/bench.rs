@@ -0,0 +1,40 @@
+//! Entry points for the criterion benchmarks (`benches/bridge.rs`)
+//!
+//! Thin wrappers over the private protocol and content modules; not an API.
+
+use anyhow::Result;
+use serde_json::Value;
+
+use super::content;
+use super::protocol::{self, JsonRpcMessage};
+use crate::backend::{self, ListFilters};
+use crate::commands::TaskListResponse;
+use crate::error_catalog::ResponseError;
+use crate::progress;
+
+pub use super::PythonBridge;
+
+/// One backend line, parsed as the reader thread does (lenient mode)
+pub fn parse_line(line: &str) -> Option<JsonRpcMessage> {
+    protocol::parse_line(line, false).ok().flatten()
+}
+
+/// Payload of a `tools/call` result, as `call_tool` extracts it
+pub fn tool_content(result: Value) -> Result<Value> {
+    Ok(content::extract(result)?.into_value())
+}
+
+/// Unfiltered, uncached `tasks_list` response for a `tasks_context`
+/// `tools/call` result, as `list_response` builds it (not serialized)
+pub fn task_list(result: Value) -> Result<TaskListResponse> {
+    let mut tasks = backend::tasks_of(tool_content(result)?)?;
+    ListFilters::default().retain(&mut tasks);
+    tasks.iter_mut().for_each(progress::attach);
+    Ok(TaskListResponse {
+        success: true,
+        total: tasks.len(),
+        tasks,
+        error: ResponseError::none(),
+        debug: None,
+    })
+}
@@ -1,73 +1,417 @@
 //! Python subprocess bridge
 //!
 //! Manages a persistent Python subprocess for JSON-RPC communication.
-//! Spawns `apply_task mcp` and communicates via stdio.
+//! Spawns `apply_task mcp` and communicates via stdio. A reader thread
+//! routes responses to their pending request by id and broadcasts server
+//! notifications, so several calls can be in flight at once. Messages are
+//! newline-delimited or `Content-Length` framed (see [`Framing`]).
 
-use std::io::{BufRead, BufReader, Write};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io::{self, BufRead, BufReader, Write};
 use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
-use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering};
 use std::sync::Arc;
-
-use anyhow::{anyhow, Context, Result};
-use serde_json::Value;
-use tokio::sync::Mutex;
-
-use super::protocol::{JsonRpcRequest, JsonRpcResponse};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use tokio::sync::{broadcast, oneshot, watch, Mutex};
+
+use crate::backend_resources::ResourceUsage;
+use crate::rate_limit::BucketLevel;
+
+use super::child_env::{ChildEnv, EnvPolicy};
+use super::coalesce::{self, Coalescer, COALESCED_TOOLS};
+use super::content;
+use super::error::{BridgeError, ToolCallError};
+use super::faults::{self, Failure, FaultInjector};
+use super::framing::{ActiveFraming, Frame, Framing};
+use super::launch::{
+    self, ContainerConfig, ContainerProblem, InstallMode, Launch, SshConfig, SshProblem,
+};
+use super::protocol::{self, JsonRpcError, JsonRpcMessage, JsonRpcResponse};
+use super::wire_log::{Direction, WireEntry, WireLog};
 
 const STORAGE_MODE_GLOBAL: u8 = 0;
 const STORAGE_MODE_LOCAL: u8 = 1;
 
+/// Buffered server notifications per subscriber
+const NOTIFICATION_CAPACITY: usize = 64;
+/// Largest backend message read by default (`max_message_bytes` setting)
+pub const DEFAULT_MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Requests of one process awaiting a response, by request id
+#[derive(Default)]
+struct Pending {
+    waiting: HashMap<u64, oneshot::Sender<JsonRpcResponse>>,
+    /// Its stdout hit EOF: nothing more will be answered
+    closed: bool,
+}
+/// [`Pending`] shared by the bridge and that process's reader
+type PendingMap = Arc<std::sync::Mutex<Pending>>;
+/// Tool calls not finished yet, by request id
+type InFlightMap = Arc<std::sync::Mutex<HashMap<u64, String>>>;
+
+/// Time between exit checks in [`PythonBridge::shutdown_graceful`]
+const EXIT_POLL: Duration = Duration::from_millis(50);
+/// Grace `docker stop` gives a backend container on a plain shutdown
+const CONTAINER_STOP: Duration = Duration::from_secs(2);
+/// How long `docker stop` itself may take beyond its grace
+const CONTAINER_STOP_SLACK: Duration = Duration::from_secs(5);
+/// How long a container CLI that lost the handshake gets to exit and
+/// finish its stderr
+const CONTAINER_EXIT_WAIT: Duration = Duration::from_secs(1);
+/// Backend stderr lines kept for startup diagnosis
+const STDERR_TAIL: usize = 20;
+/// Names backend containers apart (with the GUI's pid)
+static CONTAINER_SEQ: AtomicU64 = AtomicU64::new(0);
+/// First wait before reconnecting a dropped SSH backend (doubles per drop)
+const RECONNECT_BASE: Duration = Duration::from_millis(500);
+const RECONNECT_MAX: Duration = Duration::from_secs(30);
+
 /// Python bridge for communicating with apply_task backend
+///
+/// Clones share the same subprocess, so a clone can make calls without
+/// holding the `AppState` lock.
+#[derive(Clone)]
 pub struct PythonBridge {
     /// Python subprocess handle
     process: Arc<Mutex<Option<BridgeProcess>>>,
+    /// Requests awaiting a response from the current process (each spawn
+    /// gets its own map, so a reader's EOF only fails its own requests)
+    pending: Arc<std::sync::Mutex<PendingMap>>,
+    /// Tool names of unfinished `tools/call` requests
+    in_flight: InFlightMap,
+    /// Server notifications (`notifications/progress`, ...)
+    notifications: broadcast::Sender<Value>,
     /// Request ID counter
-    request_id: AtomicU64,
+    request_id: Arc<AtomicU64>,
+    /// Requests written to the backend
+    round_trips: Arc<AtomicU64>,
+    /// Merges identical in-flight read calls
+    coalescer: Arc<Coalescer>,
     /// Storage mode for backend process
-    storage_mode: AtomicU8,
+    storage_mode: Arc<AtomicU8>,
     /// Apply_task package root (for finding Python scripts)
     apply_task_root: PathBuf,
     /// User's working directory (for project detection in Python)
-    user_cwd: PathBuf,
-    /// Python executable path
-    python_path: String,
-    /// Whether MCP is initialized
-    initialized: Arc<Mutex<bool>>,
+    user_cwd: Arc<std::sync::Mutex<PathBuf>>,
+    /// Python executable path (changed by [`PythonBridge::set_python_path`])
+    python_path: Arc<std::sync::Mutex<String>>,
+    /// Entry point resolved for `python_path` (cleared when it changes)
+    launch: Arc<std::sync::Mutex<Option<Launch>>>,
+    /// Process generation the MCP handshake was done for (0 = none)
+    initialized: Arc<Mutex<u64>>,
+    /// Incremented on every spawn (see [`PythonBridge::subscribe_spawns`])
+    generation: Arc<watch::Sender<u64>>,
+    /// Cached `tools/list` result (cleared when the process restarts)
+    tools: Arc<Mutex<Option<Vec<Value>>>>,
+    /// `serverInfo` from the last MCP handshake
+    server_info: Arc<std::sync::Mutex<Option<Value>>>,
+    /// `capabilities` from the last MCP handshake
+    server_capabilities: Arc<std::sync::Mutex<Option<Value>>>,
+    /// Per-clone limit for tool calls (see [`PythonBridge::with_timeout`])
+    call_timeout: Option<Duration>,
+    /// Refuse JSON-RPC deviations (`strict_protocol` setting)
+    strict_protocol: Arc<AtomicBool>,
+    /// `stdio_framing` setting (used from the next spawn)
+    framing: Arc<std::sync::Mutex<Framing>>,
+    /// Inbound messages past this size are skipped (`max_message_bytes`)
+    max_message_bytes: Arc<AtomicUsize>,
+    /// `container` setting (used from the next spawn)
+    container: Arc<std::sync::Mutex<Option<ContainerConfig>>>,
+    /// `ssh` setting (used from the next spawn; wins over `container`)
+    ssh: Arc<std::sync::Mutex<Option<SshConfig>>>,
+    /// Respawn backoff of an SSH backend
+    reconnect: Arc<std::sync::Mutex<Reconnect>>,
+    /// `isolated_env` settings (used from the next spawn)
+    env_policy: Arc<std::sync::Mutex<EnvPolicy>>,
+    /// Environment of the last spawn
+    child_env: Arc<std::sync::Mutex<Option<ChildEnv>>>,
+    /// Developer-mode failures for `call_tool`
+    faults: Arc<FaultInjector>,
+    /// Recent traffic in outline (support bundles)
+    wire: Arc<WireLog>,
 }
 
 struct BridgeProcess {
     child: Child,
+    mode: InstallMode,
+    /// Container the client runs, stopped with the container CLI
+    container: Option<RunningContainer>,
+    /// Last stderr lines
+    stderr: Arc<std::sync::Mutex<VecDeque<String>>>,
+    stderr_reader: Option<std::thread::JoinHandle<()>>,
+    /// Framing the reader detected or was given; the writer follows it
+    framing: Arc<ActiveFraming>,
 }
 
-/// MCP initialization request/response
-#[derive(serde::Serialize)]
-struct McpInitializeParams {
-    #[serde(rename = "protocolVersion")]
-    protocol_version: String,
-    capabilities: serde_json::Value,
-    #[serde(rename = "clientInfo")]
-    client_info: McpClientInfo,
+/// Bridge traffic counters (since app start)
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct BridgeMetrics {
+    /// Requests sent to the backend
+    pub round_trips: u64,
+    /// Read calls answered by joining an identical in-flight call
+    pub coalesced: u64,
+    /// Read cache counters (filled in by `bridge_metrics`)
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    /// Of those, `tasks_context` lookups
+    pub context_cache_hits: u64,
+    pub context_cache_misses: u64,
+    /// Rate limit buckets by tool (developer mode only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limits: Option<BTreeMap<String, BucketLevel>>,
+    /// Backend RSS and CPU (filled in by `bridge_metrics`)
+    #[serde(default)]
+    pub resources: Option<ResourceUsage>,
 }
 
-#[derive(serde::Serialize)]
-struct McpClientInfo {
-    name: String,
-    version: String,
+/// What `bridge_status` returns
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BridgeStatus {
+    /// The backend process is alive
+    pub running: bool,
+    pub pid: Option<u32>,
+    pub install_mode: InstallMode,
+    /// Program and arguments the backend is started with
+    pub command: Vec<String>,
+    /// `PYTHONPATH` given to the backend (none when installed)
+    pub pythonpath: Option<PathBuf>,
+    pub storage_mode: String,
+    /// `user@host:path` of an SSH backend
+    #[serde(default)]
+    pub remote: Option<String>,
+    /// Backend RSS and CPU (filled in by `bridge_status`)
+    #[serde(default)]
+    pub resources: Option<ResourceUsage>,
 }
 
-/// MCP notification (no id, no response expected)
-#[derive(serde::Serialize)]
-struct McpNotification {
-    jsonrpc: String,
-    method: String,
+/// A `tools/call` request that has been sent but not answered yet
+pub struct ToolCall {
+    pub request_id: u64,
+    tool: String,
+    response: oneshot::Receiver<JsonRpcResponse>,
+    _in_flight: InFlightGuard,
 }
 
-/// MCP tools/call params
-#[derive(serde::Serialize)]
-struct McpToolCallParams {
-    name: String,
-    arguments: serde_json::Value,
+/// Removes a call from [`PythonBridge::in_flight_tools`] when it is done
+/// (answered, timed out or abandoned)
+struct InFlightGuard {
+    map: InFlightMap,
+    request_id: u64,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.map
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.request_id);
+    }
+}
+
+impl ToolCall {
+    /// Wait for the response and unwrap the MCP content envelope
+    pub async fn finish(self) -> Result<Value> {
+        let response = self.response.await.map_err(|_| {
+            BridgeError::Disconnected(format!(
+                "request {} was cancelled or the backend exited",
+                self.request_id
+            ))
+        })?;
+        if let Err(error) = &response.result {
+            if error.code == protocol::RESPONSE_TOO_LARGE {
+                let limit = error.data.as_ref().and_then(|d| d["limit"].as_u64());
+                return Err(BridgeError::ResponseTooLarge {
+                    limit: limit.unwrap_or_default(),
+                    tool: self.tool,
+                }
+                .into());
+            }
+            if error.code == protocol::RESOURCE_LIMIT {
+                let mb = |key: &str| {
+                    error
+                        .data
+                        .as_ref()
+                        .and_then(|d| d[key].as_u64())
+                        .unwrap_or_default()
+                };
+                return Err(BridgeError::ResourceLimit {
+                    rss_mb: mb("rss_mb"),
+                    cap_mb: mb("cap_mb"),
+                    tool: self.tool,
+                }
+                .into());
+            }
+        }
+        tool_result(response)
+    }
+}
+
+/// Extract the tool payload from a `tools/call` response
+fn tool_result(response: JsonRpcResponse) -> Result<Value> {
+    let result = response.result.map_err(|error| ToolCallError {
+        code: error.code,
+        message: error.message,
+        data: error.data,
+    })?;
+    if result.is_null() {
+        return Err(anyhow!("Empty tool response"));
+    }
+
+    // MCP returns { content: [{ type: "json", json: {...} }], isError: false }
+    Ok(content::extract(result)?.into_value())
+}
+
+/// Read stdout messages until EOF, dispatching responses and notifications
+///
+/// A malformed response that still names its id fails that request instead
+/// of leaving it waiting. So does one over `limit` bytes, which is skipped
+/// after the limit; its id is looked for in the part that was read, and
+/// when it isn't there every pending request is failed, since any of them
+/// may have been the one answered.
+fn read_loop(
+    stdout: ChildStdout,
+    framing: Arc<ActiveFraming>,
+    pending: PendingMap,
+    notifications: broadcast::Sender<Value>,
+    strict: Arc<AtomicBool>,
+    limit: Arc<AtomicUsize>,
+    wire: Arc<WireLog>,
+) {
+    let mut reader = BufReader::new(stdout);
+    let codec = match framing.resolve(&mut reader) {
+        Ok(detected) => {
+            log::info!("Python bridge framing: {}", detected);
+            detected.codec()
+        }
+        Err(e) => {
+            log::warn!("Python bridge stdout read failed: {}", e);
+            Framing::Auto.codec()
+        }
+    };
+    loop {
+        let limit = limit.load(Ordering::Relaxed);
+        let buf = match codec.read_message(&mut reader, limit) {
+            Ok(Some(Frame::Message(buf))) => buf,
+            Ok(Some(Frame::Oversized(prefix))) => {
+                log::warn!("Skipped a Python bridge message over {} bytes", limit);
+                let ids = match protocol::truncated_id(&prefix) {
+                    Some(id) => vec![id],
+                    None => {
+                        let pending = pending.lock().unwrap_or_else(|e| e.into_inner());
+                        pending.waiting.keys().copied().collect()
+                    }
+                };
+                for id in ids {
+                    fail_pending(
+                        &pending,
+                        id,
+                        JsonRpcError {
+                            code: protocol::RESPONSE_TOO_LARGE,
+                            message: format!("Response exceeds {} bytes", limit),
+                            data: Some(json!({ "limit": limit })),
+                        },
+                    );
+                }
+                continue;
+            }
+            Ok(None) => break,
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                log::warn!("Unreadable frame from Python bridge: {}", e);
+                continue;
+            }
+            Err(e) => {
+                log::warn!("Python bridge stdout read failed: {}", e);
+                break;
+            }
+        };
+        wire.record(Direction::Received, &buf);
+        // Invalid UTF-8 spoils one line, not the stream
+        let line = String::from_utf8_lossy(&buf);
+        let message = match protocol::parse_line(&line, strict.load(Ordering::Relaxed)) {
+            Ok(Some(message)) => message,
+            Ok(None) => continue,
+            Err(e) => {
+                log::warn!("Unparseable line from Python bridge: {}", e);
+                if let Some(id) = e.id {
+                    fail_pending(
+                        &pending,
+                        id,
+                        JsonRpcError {
+                            code: protocol::PARSE_ERROR,
+                            message: format!("Malformed response: {}", e.reason),
+                            data: None,
+                        },
+                    );
+                }
+                continue;
+            }
+        };
+
+        match message {
+            JsonRpcMessage::Response(response) => {
+                let sender = pending
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .waiting
+                    .remove(&response.id);
+                match sender {
+                    Some(sender) => {
+                        let _ = sender.send(response);
+                    }
+                    None => log::info!("Dropping response for abandoned request {}", response.id),
+                }
+            }
+            JsonRpcMessage::Notification { .. } => match serde_json::to_value(&message) {
+                // No subscribers is fine
+                Ok(value) => {
+                    let _ = notifications.send(value);
+                }
+                Err(e) => log::warn!("Failed to forward notification: {}", e),
+            },
+            JsonRpcMessage::Request { id, method, .. } => {
+                log::warn!("Ignoring backend request {} ({})", id, method);
+            }
+        }
+    }
+
+    log::info!("Python bridge stdout closed");
+    // Dropping the senders fails every request this process didn't answer,
+    // and a request sent before the exit is noticed fails at once
+    let mut pending = pending.lock().unwrap_or_else(|e| e.into_inner());
+    pending.closed = true;
+    pending.waiting.clear();
+}
+
+/// Answer request `id` with `error` (if it is still waiting)
+fn fail_pending(pending: &PendingMap, id: u64, error: JsonRpcError) {
+    let sender = pending
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .waiting
+        .remove(&id);
+    if let Some(sender) = sender {
+        let _ = sender.send(JsonRpcResponse {
+            id,
+            result: Err(error),
+        });
+    }
+}
+
+/// `PYTHON_PATH`, then `APPLY_TASK_PYTHON` (skipped when it names a file
+/// that can't run), then `python3`
+pub fn default_python_path() -> String {
+    std::env::var("PYTHON_PATH")
+        .ok()
+        .or_else(|| {
+            std::env::var("APPLY_TASK_PYTHON")
+                .ok()
+                .filter(|p| launch::interpreter_problem(p).is_none())
+        })
+        .unwrap_or_else(|| "python3".to_string())
 }
 
 impl PythonBridge {
@@ -77,22 +421,208 @@ impl PythonBridge {
     /// * `apply_task_root` - Path to apply_task package (for finding Python scripts)
     /// * `user_cwd` - User's working directory (for project detection in Python)
     pub fn new(apply_task_root: PathBuf, user_cwd: PathBuf) -> Self {
-        // Try to find Python in common locations
-        let python_path = std::env::var("PYTHON_PATH")
-            .or_else(|_| std::env::var("APPLY_TASK_PYTHON"))
-            .unwrap_or_else(|_| "python3".to_string());
-
         Self {
             process: Arc::new(Mutex::new(None)),
-            request_id: AtomicU64::new(1),
-            storage_mode: AtomicU8::new(STORAGE_MODE_GLOBAL),
+            pending: Arc::default(),
+            in_flight: InFlightMap::default(),
+            notifications: broadcast::channel(NOTIFICATION_CAPACITY).0,
+            request_id: Arc::new(AtomicU64::new(1)),
+            round_trips: Arc::new(AtomicU64::new(0)),
+            coalescer: Arc::new(Coalescer::default()),
+            storage_mode: Arc::new(AtomicU8::new(STORAGE_MODE_GLOBAL)),
             apply_task_root,
-            user_cwd,
-            python_path,
-            initialized: Arc::new(Mutex::new(false)),
+            user_cwd: Arc::new(std::sync::Mutex::new(user_cwd)),
+            python_path: Arc::new(std::sync::Mutex::new(default_python_path())),
+            launch: Arc::new(std::sync::Mutex::new(None)),
+            initialized: Arc::new(Mutex::new(0)),
+            generation: Arc::new(watch::channel(0).0),
+            tools: Arc::new(Mutex::new(None)),
+            server_info: Arc::new(std::sync::Mutex::new(None)),
+            server_capabilities: Arc::new(std::sync::Mutex::new(None)),
+            call_timeout: None,
+            strict_protocol: Arc::new(AtomicBool::new(false)),
+            framing: Arc::new(std::sync::Mutex::new(Framing::Auto)),
+            max_message_bytes: Arc::new(AtomicUsize::new(DEFAULT_MAX_MESSAGE_BYTES)),
+            container: Arc::new(std::sync::Mutex::new(None)),
+            ssh: Arc::new(std::sync::Mutex::new(None)),
+            reconnect: Arc::new(std::sync::Mutex::new(Reconnect::default())),
+            env_policy: Arc::new(std::sync::Mutex::new(EnvPolicy::default())),
+            child_env: Arc::new(std::sync::Mutex::new(None)),
+            faults: Arc::new(FaultInjector::default()),
+            wire: Arc::new(WireLog::default()),
         }
     }
 
+    /// Skip inbound messages over `bytes` (takes effect with the next one)
+    pub fn set_max_message_bytes(&self, bytes: usize) {
+        self.max_message_bytes.store(bytes, Ordering::Relaxed);
+    }
+
+    /// Frame stdio messages with `framing` (at startup)
+    pub fn with_framing(self, framing: Framing) -> Self {
+        *self.framing.lock().unwrap_or_else(|e| e.into_inner()) = framing;
+        self
+    }
+
+    /// Configured framing (`Auto` until a spawned backend reveals its own)
+    pub fn framing(&self) -> Framing {
+        *self.framing.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Change the framing; restarts the backend when it differs
+    pub async fn set_framing(&self, framing: Framing) -> Result<bool> {
+        {
+            let mut current = self.framing.lock().unwrap_or_else(|e| e.into_inner());
+            if *current == framing {
+                return Ok(false);
+            }
+            *current = framing;
+        }
+        self.shutdown().await?;
+        Ok(true)
+    }
+
+    /// Run the backend in a container per `config` (at startup)
+    pub fn with_container(self, config: Option<ContainerConfig>) -> Self {
+        *self.container.lock().unwrap_or_else(|e| e.into_inner()) = config;
+        self
+    }
+
+    /// Change the `container` setting; restarts the backend when it differs
+    pub async fn set_container(&self, config: Option<ContainerConfig>) -> Result<bool> {
+        {
+            let mut current = self.container.lock().unwrap_or_else(|e| e.into_inner());
+            if *current == config {
+                return Ok(false);
+            }
+            *current = config;
+        }
+        self.shutdown().await?;
+        Ok(true)
+    }
+
+    /// `container` setting in effect
+    pub fn container(&self) -> Option<ContainerConfig> {
+        self.container
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Run the backend on an SSH host per `config` (at startup)
+    pub fn with_ssh(self, config: Option<SshConfig>) -> Self {
+        *self.ssh.lock().unwrap_or_else(|e| e.into_inner()) = config;
+        self
+    }
+
+    /// Change the `ssh` setting; restarts the backend when it differs
+    pub async fn set_ssh(&self, config: Option<SshConfig>) -> Result<bool> {
+        {
+            let mut current = self.ssh.lock().unwrap_or_else(|e| e.into_inner());
+            if *current == config {
+                return Ok(false);
+            }
+            *current = config;
+        }
+        *self.reconnect.lock().unwrap_or_else(|e| e.into_inner()) = Reconnect::default();
+        self.shutdown().await?;
+        Ok(true)
+    }
+
+    /// `ssh` setting in effect
+    pub fn ssh(&self) -> Option<SshConfig> {
+        self.ssh.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Start the backend with `policy`'s environment (at startup)
+    pub fn with_env_policy(self, policy: EnvPolicy) -> Self {
+        *self.env_policy.lock().unwrap_or_else(|e| e.into_inner()) = policy;
+        self
+    }
+
+    /// Change the environment policy; restarts the backend when it differs
+    pub async fn set_env_policy(&self, policy: EnvPolicy) -> Result<bool> {
+        {
+            let mut current = self.env_policy.lock().unwrap_or_else(|e| e.into_inner());
+            if *current == policy {
+                return Ok(false);
+            }
+            *current = policy;
+        }
+        *self.child_env.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        self.shutdown().await?;
+        Ok(true)
+    }
+
+    /// Environment for the next spawn of `launch` (the checkout's root goes
+    /// on `PYTHONPATH` when it asks for it)
+    fn next_child_env(&self, launch: &Launch) -> ChildEnv {
+        let root = launch
+            .pythonpath
+            .then(|| self.apply_task_root.to_string_lossy().into_owned());
+        let overrides: Vec<(&str, String)> = root.map(|r| ("PYTHONPATH", r)).into_iter().collect();
+        let policy = self.env_policy.lock().unwrap_or_else(|e| e.into_inner());
+        match launch.mode {
+            InstallMode::Ssh => policy.for_ssh().build(std::env::vars(), &overrides),
+            _ => policy.build(std::env::vars(), &overrides),
+        }
+    }
+
+    /// Environment of the running (or last) backend; before the first spawn,
+    /// the one it will get
+    pub fn child_env(&self) -> ChildEnv {
+        let last = self
+            .child_env
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        last.unwrap_or_else(|| self.next_child_env(&self.launch()))
+    }
+
+    /// Use `python_path` instead of the environment default (at startup)
+    pub fn with_python_path(self, python_path: Option<String>) -> Self {
+        if let Some(path) = python_path {
+            *self.python_path.lock().unwrap_or_else(|e| e.into_inner()) = path;
+        }
+        self
+    }
+
+    /// Read backend output strictly (takes effect with the next line)
+    pub fn set_strict_protocol(&self, strict: bool) {
+        self.strict_protocol.store(strict, Ordering::Relaxed);
+    }
+
+    /// Change the Python executable (`None` = environment default); restarts
+    /// the backend when it differs
+    pub async fn set_python_path(&self, python_path: Option<String>) -> Result<bool> {
+        let next = python_path.unwrap_or_else(default_python_path);
+        {
+            let mut current = self.python_path.lock().unwrap_or_else(|e| e.into_inner());
+            if *current == next {
+                return Ok(false);
+            }
+            *current = next;
+        }
+        *self.launch.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        self.shutdown().await?;
+        Ok(true)
+    }
+
+    pub fn python_path(&self) -> String {
+        self.python_path
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Clone whose tool calls give up after `timeout` (`None` = no limit)
+    ///
+    /// A call that runs out is cancelled and fails with [`BridgeError::Timeout`].
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.call_timeout = timeout;
+        self
+    }
+
     pub fn storage_mode_str(&self) -> &'static str {
         if self.storage_mode.load(Ordering::Relaxed) == STORAGE_MODE_LOCAL {
             "local"
@@ -118,270 +648,592 @@ impl PythonBridge {
         Ok(true)
     }
 
-    /// Spawn the Python subprocess if not already running
+    /// Run the backend in another project; the next call respawns it there
+    pub async fn set_user_cwd(&self, user_cwd: PathBuf) -> Result<bool> {
+        {
+            let mut current = self.user_cwd.lock().unwrap_or_else(|e| e.into_inner());
+            if *current == user_cwd {
+                return Ok(false);
+            }
+            *current = user_cwd;
+        }
+        self.shutdown().await?;
+        Ok(true)
+    }
+
+    /// Spawn the Python subprocess if not running (respawns after a crash)
     async fn ensure_process(&self) -> Result<()> {
         let mut guard = self.process.lock().await;
 
-        if guard.is_some() {
-            return Ok(());
+        if let Some(process) = guard.as_mut() {
+            // Its stdout can close before the exit shows in `try_wait`
+            let closed = self
+                .pending()
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .closed;
+            match process.child.try_wait() {
+                Ok(None) if !closed => return Ok(()),
+                status => {
+                    log::warn!("Python bridge exited ({:?}), respawning", status);
+                    if process.mode == InstallMode::Ssh {
+                        let delay = self
+                            .reconnect
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .lost();
+                        log::warn!("SSH backend lost, reconnecting in {} ms", delay.as_millis());
+                    }
+                    guard.take();
+                    *self.tools.lock().await = None;
+                }
+            }
+        }
+        if let Some(config) = self.ssh() {
+            let remaining = self
+                .reconnect
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remaining();
+            if let Some(left) = remaining {
+                return Err(BridgeError::Disconnected(format!(
+                    "connection to {} lost; reconnecting in {} ms",
+                    config.host.trim(),
+                    left.as_millis()
+                ))
+                .into());
+            }
         }
 
+        let user_cwd = self
+            .user_cwd
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
         log::info!("Spawning Python bridge subprocess...");
         log::info!("Apply task root: {:?}", self.apply_task_root);
-        log::info!("User working directory: {:?}", self.user_cwd);
+        log::info!("User working directory: {:?}", user_cwd);
 
-        // Find apply_task entry point
-        let args = self.find_apply_task()?;
-        log::info!("Found apply_task args: {:?}", args);
+        let launch = self.launch();
+        log::info!("Install mode: {:?}", launch.mode);
         let use_local_storage = self.storage_mode.load(Ordering::Relaxed) == STORAGE_MODE_LOCAL;
 
-        // Always spawn through Python to avoid relying on executable bits (+x).
-        // This keeps GUI deterministic across platforms/filesystem permissions.
-        let mut cmd = Command::new(&self.python_path);
-        if args.first().map(|s| s.as_str()) == Some("-m") {
-            // Module mode: python3 -m core.desktop.devtools.interface.mcp_server
-            cmd.args(&args);
-            log::info!("Running: {} {:?}", self.python_path, args);
-        } else {
-            // Script mode: python3 /path/to/apply_task mcp
-            let script = args.first().ok_or_else(|| anyhow!("No entrypoint found"))?;
-            cmd.arg(script);
-            cmd.arg("mcp");
-            log::info!("Running: {} {} mcp", self.python_path, script);
-        }
-
+        // Spawn through Python (not the script's +x bit) except for a console
+        // script on PATH, which may belong to another interpreter's venv
+        let mut cmd = Command::new(&launch.program);
+        let container = (launch.mode == InstallMode::Container).then(|| RunningContainer {
+            program: launch.program.clone(),
+            name: format!(
+                "apply-task-{}-{}",
+                std::process::id(),
+                CONTAINER_SEQ.fetch_add(1, Ordering::Relaxed) + 1
+            ),
+        });
+        match (&container, launch.args.split_first()) {
+            // Named so it can be stopped: killing the client doesn't
+            (Some(container), Some((run, rest))) => {
+                cmd.arg(run).args(["--name", &container.name]).args(rest)
+            }
+            _ => cmd.args(&launch.args),
+        };
         if use_local_storage {
             cmd.arg("--local");
         }
-
-        // Set PYTHONPATH to apply_task package root (for imports)
-        cmd.env("PYTHONPATH", &self.apply_task_root);
+        log::info!("Running: {} {:?}", launch.program, launch.args);
+
+        // PYTHONPATH names the apply_task package root (not needed when
+        // installed); with `isolated_env` the rest is trimmed to an allowlist
+        let env = self.next_child_env(&launch);
+        log::info!(
+            "Environment: {:?}, {} variables",
+            env.mode,
+            env.vars().len()
+        );
+        env.apply(&mut cmd);
+        *self.child_env.lock().unwrap_or_else(|e| e.into_inner()) = Some(env);
         // CRITICAL: Run Python in user's working directory (for project detection)
-        cmd.current_dir(&self.user_cwd);
+        cmd.current_dir(&user_cwd);
         cmd.stdin(Stdio::piped());
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
-        let child = cmd.spawn().context("Failed to spawn Python subprocess")?;
+        let child = cmd.spawn().map_err(|e| match launch.mode {
+            InstallMode::Container => {
+                BridgeError::ContainerDaemon(format!("{} could not be run: {}", launch.program, e))
+            }
+            InstallMode::Ssh => {
+                BridgeError::Spawn(format!("{} could not be run: {}", launch.program, e))
+            }
+            _ => BridgeError::Spawn(format!("failed to spawn Python subprocess: {}", e)),
+        })?;
 
         let mut child = child; // Make mutable to take stderr
-        if let Some(stderr) = child.stderr.take() {
+        let stderr_tail = Arc::new(std::sync::Mutex::new(VecDeque::new()));
+        let stderr_reader = child.stderr.take().map(|stderr| {
+            let tail = stderr_tail.clone();
             std::thread::spawn(move || {
                 let reader = BufReader::new(stderr);
-                for line in reader.lines() {
-                    if let Ok(l) = line {
-                        log::error!("[Python Bridge Stderr] {}", l);
+                for l in reader.lines().map_while(Result::ok) {
+                    log::error!("[Python Bridge Stderr] {}", l);
+                    let mut tail = tail.lock().unwrap_or_else(|e| e.into_inner());
+                    if tail.len() == STDERR_TAIL {
+                        tail.pop_front();
                     }
+                    tail.push_back(l);
                 }
-            });
-        }
+            })
+        });
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| BridgeError::Spawn("failed to get stdout".into()))?;
+        let pending = PendingMap::default();
+        *self.pending.lock().unwrap_or_else(|e| e.into_inner()) = pending.clone();
+        let notifications = self.notifications.clone();
+        let strict = self.strict_protocol.clone();
+        let framing = Arc::new(ActiveFraming::new(self.framing()));
+        let reader_framing = framing.clone();
+        let limit = self.max_message_bytes.clone();
+        let wire = self.wire.clone();
+        std::thread::spawn(move || {
+            read_loop(
+                stdout,
+                reader_framing,
+                pending,
+                notifications,
+                strict,
+                limit,
+                wire,
+            )
+        });
 
         log::info!("Python bridge started with PID: {}", child.id());
-        *guard = Some(BridgeProcess { child });
+        *guard = Some(BridgeProcess {
+            child,
+            mode: launch.mode,
+            container,
+            stderr: stderr_tail,
+            stderr_reader,
+            framing,
+        });
+        self.generation.send_modify(|g| *g += 1);
 
         Ok(())
     }
 
-    /// Find the apply_task entry point
-    fn find_apply_task(&self) -> Result<Vec<String>> {
-        // Check APPLY_TASK_PATH environment variable
-        if let Ok(path) = std::env::var("APPLY_TASK_PATH") {
-            let path = PathBuf::from(&path);
-            if path.exists() {
-                return Ok(vec![path.to_string_lossy().to_string()]);
-            }
-        }
-
-        // Prefer local repo entry points (keeps GUI in lockstep with bundled code)
-        let local_apply_task = self.apply_task_root.join("apply_task");
-        if local_apply_task.exists() {
-            return Ok(vec![local_apply_task.to_string_lossy().to_string()]);
-        }
-
-        let tasks_py = self.apply_task_root.join("tasks.py");
-        if tasks_py.exists() {
-            return Ok(vec![tasks_py.to_string_lossy().to_string()]);
+    /// Entry point the backend is (or will be) started with
+    ///
+    /// Resolved on first use per interpreter; an installed package is
+    /// detected by trying to import it.
+    pub fn launch(&self) -> Launch {
+        if let Some(config) = self.ssh() {
+            return launch::ssh(&config);
         }
-
-        // Fallback: apply_task in PATH (installed via pip/uv)
-        if let Ok(output) = Command::new("which").arg("apply_task").output() {
-            if output.status.success() {
-                let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                if !path.is_empty() {
-                    return Ok(vec![path]);
-                }
-            }
+        if let Some(config) = self.container() {
+            let user_cwd = self.user_cwd.lock().unwrap_or_else(|e| e.into_inner());
+            return launch::container(&config, &user_cwd);
         }
-
-        // Use Python module directly: python -m core.desktop.devtools.interface.mcp_server
-        // This works if project root is in PYTHONPATH
-        Ok(vec![
-            "-m".to_string(),
-            "core.desktop.devtools.interface.mcp_server".to_string(),
-        ])
+        let mut cached = self.launch.lock().unwrap_or_else(|e| e.into_inner());
+        cached
+            .get_or_insert_with(|| {
+                let env_path = std::env::var("APPLY_TASK_PATH").ok().map(PathBuf::from);
+                launch::resolve(
+                    &self.apply_task_root,
+                    &self.python_path(),
+                    env_path.as_deref(),
+                )
+            })
+            .clone()
     }
 
     /// Initialize the MCP connection (handshake)
     async fn initialize_mcp(&self) -> Result<()> {
-        {
-            let initialized = self.initialized.lock().await;
-            if *initialized {
-                return Ok(());
-            }
+        // Held throughout so concurrent callers don't handshake twice
+        let mut initialized = self.initialized.lock().await;
+        let generation = *self.generation.borrow();
+        if *initialized == generation {
+            return Ok(());
         }
 
         log::info!("Initializing MCP connection...");
 
-        // Send initialize request
-        let init_params = McpInitializeParams {
-            protocol_version: "2024-11-05".to_string(),
-            capabilities: serde_json::json!({}),
-            client_info: McpClientInfo {
-                name: "apply-task-gui".to_string(),
-                version: "0.1.0".to_string(),
-            },
+        let response = match self.call_raw(JsonRpcMessage::initialize).await {
+            Ok(response) => response,
+            Err(e) => return Err(self.startup_failure().await.map_or(e, Into::into)),
         };
-
-        let response = self
-            .call_raw("initialize", Some(serde_json::to_value(init_params)?))
-            .await?;
-
-        if response.error.is_some() {
-            return Err(anyhow!("MCP initialize failed: {:?}", response.error));
-        }
+        let result = response
+            .result
+            .map_err(|error| anyhow!("MCP initialize failed: {:?}", error))?;
+        *self.server_info.lock().unwrap_or_else(|e| e.into_inner()) =
+            result.get("serverInfo").cloned();
+        *self
+            .server_capabilities
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = result.get("capabilities").cloned();
 
         log::info!("MCP initialized, sending notifications/initialized...");
 
         // Send initialized notification (no response expected)
-        {
-            let mut guard = self.process.lock().await;
-            let process = guard
-                .as_mut()
-                .ok_or_else(|| anyhow!("Process not running"))?;
-
-            let notification = McpNotification {
-                jsonrpc: "2.0".to_string(),
-                method: "notifications/initialized".to_string(),
-            };
+        self.notify(JsonRpcMessage::initialized()).await?;
 
-            let stdin = process
-                .child
-                .stdin
-                .as_mut()
-                .ok_or_else(|| anyhow!("Failed to get stdin"))?;
-
-            let notification_json = serde_json::to_string(&notification)?;
-            writeln!(stdin, "{}", notification_json)?;
-            stdin.flush()?;
-        }
-
-        *self.initialized.lock().await = true;
+        *initialized = generation;
+        *self.reconnect.lock().unwrap_or_else(|e| e.into_inner()) = Reconnect::default();
         log::info!("MCP connection fully initialized");
 
         Ok(())
     }
 
-    /// Call an MCP tool by name
-    pub async fn call_tool(&self, tool_name: &str, arguments: Value) -> Result<Value> {
-        self.ensure_process().await?;
-        self.initialize_mcp().await?;
+    /// Why a container or SSH backend that lost the handshake didn't start,
+    /// when its client said so on stderr
+    async fn startup_failure(&self) -> Option<BridgeError> {
+        let mut guard = self.process.lock().await;
+        let process = guard.as_mut()?;
+        if !matches!(process.mode, InstallMode::Container | InstallMode::Ssh) {
+            return None;
+        }
+        // The client exits right after printing its error
+        let deadline = Instant::now() + CONTAINER_EXIT_WAIT;
+        while Instant::now() < deadline
+            && !process
+                .stderr_reader
+                .as_ref()
+                .is_none_or(|reader| reader.is_finished())
+        {
+            tokio::time::sleep(EXIT_POLL).await;
+        }
+        let stderr = Vec::from(
+            process
+                .stderr
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+        )
+        .join("\n");
+        let last_line = stderr.lines().last().unwrap_or_default().trim().to_string();
+        if process.mode == InstallMode::Ssh {
+            let host = self
+                .ssh()
+                .map(|c| c.host.trim().to_string())
+                .unwrap_or_default();
+            return Some(match launch::ssh_problem(&stderr)? {
+                SshProblem::HostKey => BridgeError::SshHostKey { host },
+                SshProblem::Auth => BridgeError::SshAuth { host },
+                SshProblem::Connect => BridgeError::SshConnect {
+                    host,
+                    detail: last_line,
+                },
+            });
+        }
+        match launch::container_problem(&stderr)? {
+            ContainerProblem::Daemon => Some(BridgeError::ContainerDaemon(last_line)),
+            ContainerProblem::Image => Some(BridgeError::ContainerImage {
+                image: self.container().map(|c| c.image).unwrap_or_default(),
+            }),
+        }
+    }
 
-        let params = McpToolCallParams {
-            name: tool_name.to_string(),
-            arguments,
-        };
+    /// Call an MCP tool by name (identical concurrent read calls are merged)
+    pub async fn call_tool(&self, tool_name: &str, arguments: Value) -> Result<Value> {
+        if let Some(failure) = self.faults.take(tool_name) {
+            return self.inject(failure, tool_name).await;
+        }
+        if let Some(timeout) = self.call_timeout {
+            // Not coalesced: cancelling on timeout must not fail other waiters
+            return self.call_tool_timed(tool_name, arguments, timeout).await;
+        }
+        if !COALESCED_TOOLS.contains(&tool_name) {
+            return self.call_tool_once(tool_name, arguments).await;
+        }
+        let key = coalesce::call_key(tool_name, &arguments);
+        self.coalescer
+            .run(key, || self.call_tool_once(tool_name, arguments))
+            .await
+    }
 
-        let response = self
-            .call_raw("tools/call", Some(serde_json::to_value(params)?))
-            .await?;
+    async fn call_tool_once(&self, tool_name: &str, arguments: Value) -> Result<Value> {
+        self.begin_tool_call(tool_name, arguments, None)
+            .await?
+            .finish()
+            .await
+    }
 
-        if let Some(error) = response.error {
-            return Err(anyhow!("Tool call error {}: {}", error.code, error.message));
+    async fn call_tool_timed(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+        timeout: Duration,
+    ) -> Result<Value> {
+        let started = Instant::now();
+        let call = self.begin_tool_call(tool_name, arguments, None).await?;
+        let request_id = call.request_id;
+        let remaining = timeout.saturating_sub(started.elapsed());
+        match tokio::time::timeout(remaining, call.finish()).await {
+            Ok(result) => result,
+            Err(_) => {
+                if let Err(e) = self.cancel_request(request_id, "timeout").await {
+                    log::warn!("Failed to cancel request {}: {}", request_id, e);
+                }
+                Err(BridgeError::Timeout {
+                    tool: tool_name.to_string(),
+                    waited_ms: started.elapsed().as_millis() as u64,
+                }
+                .into())
+            }
         }
+    }
 
-        // Extract result from MCP content format
-        if let Some(result) = response.result {
-            // MCP returns { content: [{ type: "json", json: {...} }], isError: false }
-            if let Some(content) = result.get("content").and_then(|c| c.as_array()) {
-                if let Some(first) = content.first() {
-                    if let Some(json) = first.get("json") {
-                        return Ok(json.clone());
-                    }
-                    if let Some(text) = first.get("text").and_then(|t| t.as_str()) {
-                        return serde_json::from_str(text)
-                            .context("Failed to parse tool response text as JSON");
-                    }
+    /// Fail a call of `tool_name` with `failure`, as the real one would
+    async fn inject(&self, failure: Failure, tool_name: &str) -> Result<Value> {
+        log::warn!("Injecting {} into {}", failure, tool_name);
+        match failure {
+            Failure::Timeout => {
+                let waited = self.call_timeout.unwrap_or(faults::INJECTED_TIMEOUT);
+                tokio::time::sleep(waited).await;
+                Err(BridgeError::Timeout {
+                    tool: tool_name.to_string(),
+                    waited_ms: waited.as_millis() as u64,
                 }
+                .into())
+            }
+            Failure::ToolError => Err(faults::tool_error(tool_name).into()),
+            Failure::Disconnect => {
+                // Calls already in flight fail with it, as in a crash. The
+                // sentinel is dropped once the reader has seen the end of
+                // stdout, so it can't fail the next process's requests.
+                let (sentinel, reader_done) = oneshot::channel();
+                let id = self.request_id.fetch_add(1, Ordering::SeqCst);
+                if let Some(process) = self.process.lock().await.as_mut() {
+                    self.pending()
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .waiting
+                        .insert(id, sentinel);
+                    let _ = process.child.kill();
+                    let _ = process.child.wait();
+                }
+                let _ = tokio::time::timeout(faults::INJECTED_TIMEOUT, reader_done).await;
+                Err(BridgeError::Disconnected(format!(
+                    "backend killed before {} (injected)",
+                    tool_name
+                ))
+                .into())
             }
-            return Ok(result);
         }
+    }
 
-        Err(anyhow!("Empty tool response"))
+    /// The last messages to and from the backend, in outline
+    pub fn wire_log(&self) -> Vec<WireEntry> {
+        self.wire.entries()
     }
 
-    /// Send a raw JSON-RPC request and wait for response (internal)
-    async fn call_raw(&self, method: &str, params: Option<Value>) -> Result<JsonRpcResponse> {
-        let id = self.request_id.fetch_add(1, Ordering::SeqCst);
-        let request = JsonRpcRequest::new(id, method, params);
+    /// Fault injection rules (developer mode)
+    pub fn faults(&self) -> &FaultInjector {
+        &self.faults
+    }
+
+    /// PID of the backend while it is alive
+    pub async fn pid(&self) -> Option<u32> {
+        self.process
+            .lock()
+            .await
+            .as_mut()
+            .and_then(|p| matches!(p.child.try_wait(), Ok(None)).then(|| p.child.id()))
+    }
+
+    /// Process state and how the backend is launched
+    pub async fn status(&self) -> BridgeStatus {
+        let pid = self.pid().await;
+        let launch = self.launch();
+        BridgeStatus {
+            running: pid.is_some(),
+            pid,
+            install_mode: launch.mode,
+            command: std::iter::once(launch.program).chain(launch.args).collect(),
+            pythonpath: launch.pythonpath.then(|| self.apply_task_root.clone()),
+            storage_mode: self.storage_mode_str().to_string(),
+            remote: self.ssh().map(|c| c.endpoint()),
+            resources: None,
+        }
+    }
+
+    pub fn metrics(&self) -> BridgeMetrics {
+        BridgeMetrics {
+            round_trips: self.round_trips.load(Ordering::Relaxed),
+            coalesced: self.coalescer.coalesced(),
+            ..BridgeMetrics::default()
+        }
+    }
+
+    /// Send a `tools/call` without waiting for it
+    ///
+    /// With a `progress_token`, progress notifications for the call carry that
+    /// token (see [`PythonBridge::subscribe_notifications`]).
+    pub async fn begin_tool_call(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+        progress_token: Option<&str>,
+    ) -> Result<ToolCall> {
+        self.ensure_process().await?;
+        self.initialize_mcp().await?;
+
+        let (request_id, response) = self
+            .send_request(|id| JsonRpcMessage::tool_call(id, tool_name, arguments, progress_token))
+            .await?;
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(request_id, tool_name.to_string());
+        Ok(ToolCall {
+            request_id,
+            tool: tool_name.to_string(),
+            response,
+            _in_flight: InFlightGuard {
+                map: self.in_flight.clone(),
+                request_id,
+            },
+        })
+    }
+
+    /// Cancel an in-flight request (`notifications/cancelled`)
+    ///
+    /// The waiting caller fails immediately; a late response is dropped.
+    pub async fn cancel_request(&self, request_id: u64, reason: &str) -> Result<()> {
+        self.pending()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .waiting
+            .remove(&request_id);
+        self.notify(JsonRpcMessage::cancelled(request_id, reason))
+            .await
+    }
 
-        log::info!("call_raw: method={}, id={}", method, id);
+    /// Server notifications received from now on
+    pub fn subscribe_notifications(&self) -> broadcast::Receiver<Value> {
+        self.notifications.subscribe()
+    }
+
+    /// Write a notification to the backend (no response expected)
+    async fn notify(&self, notification: JsonRpcMessage) -> Result<()> {
+        self.write_message(&serde_json::to_string(&notification)?)
+            .await
+    }
 
+    /// Write one message in the process's framing
+    async fn write_message(&self, message: &str) -> Result<()> {
         let mut guard = self.process.lock().await;
         let process = guard
             .as_mut()
-            .ok_or_else(|| anyhow!("Process not running"))?;
+            .ok_or_else(|| BridgeError::Disconnected("process not running".into()))?;
 
-        // Write request to stdin
         let stdin = process
             .child
             .stdin
             .as_mut()
-            .ok_or_else(|| anyhow!("Failed to get stdin"))?;
-
-        let request_json = serde_json::to_string(&request)?;
-        log::info!("Sending request: {}", request_json);
+            .ok_or_else(|| BridgeError::Disconnected("failed to get stdin".into()))?;
+
+        process
+            .framing
+            .get()
+            .codec()
+            .write_message(stdin, message.as_bytes())
+            .and_then(|_| stdin.flush())
+            .map_err(|e| BridgeError::Disconnected(format!("write failed: {}", e)))?;
+        self.wire.record(Direction::Sent, message.as_bytes());
+        Ok(())
+    }
 
-        writeln!(stdin, "{}", request_json)?;
-        stdin.flush()?;
-        log::info!("Request sent, waiting for response...");
+    /// List tools advertised by the backend (cached per process)
+    pub async fn list_tools(&self) -> Result<Vec<Value>> {
+        if let Some(tools) = self.tools.lock().await.as_ref() {
+            return Ok(tools.clone());
+        }
 
-        // Read response from stdout
-        let stdout = process
-            .child
-            .stdout
-            .as_mut()
-            .ok_or_else(|| anyhow!("Failed to get stdout"))?;
+        self.ensure_process().await?;
+        self.initialize_mcp().await?;
 
-        let mut reader = BufReader::new(stdout);
-        let mut response_line = String::new();
+        let response = self
+            .call_raw(|id| JsonRpcMessage::request(id, "tools/list", None))
+            .await?;
+        let result = response
+            .result
+            .map_err(|error| anyhow!("tools/list error {}: {}", error.code, error.message))?;
+
+        let tools = result
+            .get("tools")
+            .and_then(|t| t.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        *self.tools.lock().await = Some(tools.clone());
+        Ok(tools)
+    }
 
-        log::info!("Reading response line...");
-        let bytes_read = reader.read_line(&mut response_line)?;
-        log::info!("Read {} bytes: {}", bytes_read, response_line.trim());
+    /// Send a raw JSON-RPC request and wait for response (internal)
+    async fn call_raw(
+        &self,
+        request: impl FnOnce(u64) -> JsonRpcMessage,
+    ) -> Result<JsonRpcResponse> {
+        let (id, response) = self.send_request(request).await?;
+        log::info!("Request sent, waiting for response...");
 
-        if response_line.is_empty() {
-            // Check if process is still running
-            if let Some(status) = process.child.try_wait()? {
-                return Err(anyhow!("Python process exited with status: {:?}", status));
+        match response.await {
+            Ok(response) => {
+                log::info!("Parsed response id={}", response.id);
+                Ok(response)
+            }
+            Err(_) => {
+                // Reader hit EOF: check if process is still running
+                if let Some(process) = self.process.lock().await.as_mut() {
+                    if let Some(status) = process.child.try_wait()? {
+                        return Err(BridgeError::Disconnected(format!(
+                            "process exited with status: {:?}",
+                            status
+                        ))
+                        .into());
+                    }
+                }
+                Err(BridgeError::Disconnected(format!("no response for request {}", id)).into())
             }
-            return Err(anyhow!("Empty response from Python"));
         }
+    }
 
-        let response: JsonRpcResponse =
-            serde_json::from_str(&response_line).context("Failed to parse JSON-RPC response")?;
+    /// Register a pending request (built for the next id) and write it to stdin
+    async fn send_request(
+        &self,
+        request: impl FnOnce(u64) -> JsonRpcMessage,
+    ) -> Result<(u64, oneshot::Receiver<JsonRpcResponse>)> {
+        let id = self.request_id.fetch_add(1, Ordering::SeqCst);
+        self.round_trips.fetch_add(1, Ordering::Relaxed);
+        let request = request(id);
 
-        log::info!("Parsed response id={}", response.id);
+        log::info!(
+            "call_raw: method={}, id={}",
+            request.method().unwrap_or_default(),
+            id
+        );
 
-        // Verify response ID matches
-        if response.id != id {
-            return Err(anyhow!(
-                "Response ID mismatch: expected {}, got {}",
-                id,
-                response.id
-            ));
+        let (sender, receiver) = oneshot::channel();
+        {
+            let pending = self.pending();
+            let mut pending = pending.lock().unwrap_or_else(|e| e.into_inner());
+            if pending.closed {
+                return Err(BridgeError::Disconnected("backend stdout closed".into()).into());
+            }
+            pending.waiting.insert(id, sender);
         }
 
-        Ok(response)
+        let request_json = serde_json::to_string(&request)?;
+        log::info!("Sending request: {}", request_json);
+        if let Err(e) = self.write_message(&request_json).await {
+            self.pending()
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .waiting
+                .remove(&id);
+            return Err(e);
+        }
+        Ok((id, receiver))
     }
 
     /// Public method to call MCP tools (main API for commands)
@@ -397,36 +1249,296 @@ impl PythonBridge {
             .await
     }
 
+    /// Tools of the calls still waiting for a response
+    pub fn in_flight_tools(&self) -> Vec<String> {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+
     /// Shutdown the Python subprocess
     pub async fn shutdown(&self) -> Result<()> {
-        let mut guard = self.process.lock().await;
-
-        if let Some(mut process) = guard.take() {
+        // Not held across other locks (initialize_mcp takes process after initialized)
+        let process = self.process.lock().await.take();
+        if let Some(mut process) = process {
             log::info!("Shutting down Python bridge...");
+            if let Some(container) = process.container.take() {
+                container.stop(CONTAINER_STOP).await;
+            }
             let _ = process.child.kill();
             let _ = process.child.wait();
         }
+        self.reset().await;
+        Ok(())
+    }
 
-        *self.initialized.lock().await = false;
+    /// Close the backend's stdin so it exits on its own (releasing the
+    /// storage lock), killing it only if it is still running after `grace`
+    pub async fn shutdown_graceful(&self, grace: Duration) -> Result<()> {
+        let process = self.process.lock().await.take();
+        if let Some(mut process) = process {
+            log::info!("Stopping Python bridge (up to {} ms)...", grace.as_millis());
+            drop(process.child.stdin.take());
+            let deadline = Instant::now() + grace;
+            loop {
+                match process.child.try_wait() {
+                    Ok(Some(status)) => {
+                        log::info!("Python bridge exited ({})", status);
+                        break;
+                    }
+                    Ok(None) if Instant::now() < deadline => tokio::time::sleep(EXIT_POLL).await,
+                    _ => {
+                        match process.container.take() {
+                            Some(container) => {
+                                log::warn!("Backend container did not exit in time, stopping it");
+                                container.stop(grace).await;
+                            }
+                            None => log::warn!("Python bridge did not exit in time, killing it"),
+                        }
+                        let _ = process.child.kill();
+                        let _ = process.child.wait();
+                        break;
+                    }
+                }
+            }
+        }
+        self.reset().await;
         Ok(())
     }
 
-    /// Check if the bridge is running
+    /// Stop backend `pid` for its memory use: the requests in flight fail
+    /// with [`BridgeError::ResourceLimit`], the next call respawns it.
+    /// Returns false when `pid` is no longer the running backend.
+    pub async fn terminate_over_limit(
+        &self,
+        pid: u32,
+        rss_mb: u64,
+        cap_mb: u64,
+        grace: Duration,
+    ) -> Result<bool> {
+        if self.pid().await != Some(pid) {
+            return Ok(false);
+        }
+        let error = JsonRpcError {
+            code: protocol::RESOURCE_LIMIT,
+            message: format!("Backend stopped at {} MB (cap {} MB)", rss_mb, cap_mb),
+            data: Some(json!({ "rss_mb": rss_mb, "cap_mb": cap_mb })),
+        };
+        let ids: Vec<u64> = self
+            .pending()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .waiting
+            .keys()
+            .copied()
+            .collect();
+        for id in ids {
+            fail_pending(&self.pending(), id, error.clone());
+        }
+        self.shutdown_graceful(grace).await?;
+        Ok(true)
+    }
+
+    /// Forget per-process state after the process is gone
+    async fn reset(&self) {
+        *self.tools.lock().await = None;
+        self.pending()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .waiting
+            .clear();
+    }
+
+    /// Pending requests of the current process
+    fn pending(&self) -> PendingMap {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Process generation changes (each spawn, including respawns)
+    pub fn subscribe_spawns(&self) -> watch::Receiver<u64> {
+        self.generation.subscribe()
+    }
+
+    /// Backend version from the last handshake's `serverInfo`
+    pub fn server_version(&self) -> Option<String> {
+        self.server_info
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()?
+            .get("version")?
+            .as_str()
+            .map(String::from)
+    }
+
+    /// Whether the last handshake declared server capability `name`
+    /// (`tools`, `logging`, ...)
+    pub fn server_capability(&self, name: &str) -> bool {
+        self.server_capabilities
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .and_then(|c| c.get(name))
+            .is_some()
+    }
+
+    /// Separate backend with the same configuration (own process and state)
+    ///
+    /// For diagnostics that must not disturb the bridge serving the GUI.
+    pub fn probe(&self) -> Self {
+        let user_cwd = self
+            .user_cwd
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        let probe = Self::new(self.apply_task_root.clone(), user_cwd)
+            .with_python_path(Some(self.python_path()))
+            .with_framing(self.framing());
+        probe
+            .storage_mode
+            .store(self.storage_mode.load(Ordering::Relaxed), Ordering::Relaxed);
+        probe.set_strict_protocol(self.strict_protocol.load(Ordering::Relaxed));
+        probe.set_max_message_bytes(self.max_message_bytes.load(Ordering::Relaxed));
+        *probe.container.lock().unwrap_or_else(|e| e.into_inner()) = self.container();
+        *probe.ssh.lock().unwrap_or_else(|e| e.into_inner()) = self.ssh();
+        *probe.env_policy.lock().unwrap_or_else(|e| e.into_inner()) = self
+            .env_policy
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        *probe.launch.lock().unwrap_or_else(|e| e.into_inner()) = self
+            .launch
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        probe
+    }
+
+    /// Spawn the backend if needed and complete the MCP handshake
+    pub async fn connect(&self) -> Result<()> {
+        self.ensure_process().await?;
+        self.initialize_mcp().await
+    }
+
+    /// Stop the backend and start a fresh one
+    pub async fn restart(&self) -> Result<()> {
+        self.shutdown().await?;
+        self.connect().await
+    }
+
+    /// Exit status of a backend that stopped on its own (`None` while it runs
+    /// and after a deliberate shutdown, which drops the process)
+    pub async fn exit_status(&self) -> Option<std::process::ExitStatus> {
+        self.process
+            .lock()
+            .await
+            .as_mut()?
+            .child
+            .try_wait()
+            .ok()
+            .flatten()
+    }
+
+    /// Check if the bridge is running (spawned and not exited)
     pub async fn is_running(&self) -> bool {
-        self.process.lock().await.is_some()
+        match self.process.lock().await.as_mut() {
+            Some(process) => matches!(process.child.try_wait(), Ok(None)),
+            None => false,
+        }
+    }
+}
+
+/// Respawn backoff for SSH backends, whose connection can drop
+#[derive(Debug, Default)]
+struct Reconnect {
+    /// Connections lost in a row (reset by a handshake)
+    failures: u32,
+    not_before: Option<Instant>,
+}
+
+impl Reconnect {
+    /// Count a lost connection; returns the wait before the next attempt
+    fn lost(&mut self) -> Duration {
+        self.failures += 1;
+        let delay = reconnect_delay(self.failures);
+        self.not_before = Some(Instant::now() + delay);
+        delay
+    }
+
+    /// Time left before the next attempt
+    fn remaining(&self) -> Option<Duration> {
+        self.not_before?
+            .checked_duration_since(Instant::now())
+            .filter(|left| !left.is_zero())
+    }
+}
+
+/// [`RECONNECT_BASE`] doubled per lost connection, up to [`RECONNECT_MAX`]
+fn reconnect_delay(failures: u32) -> Duration {
+    let doublings = failures.saturating_sub(1).min(16);
+    RECONNECT_BASE
+        .saturating_mul(1 << doublings)
+        .min(RECONNECT_MAX)
+}
+
+struct RunningContainer {
+    /// Container CLI
+    program: String,
+    name: String,
+}
+
+impl RunningContainer {
+    /// `docker stop` the container, giving it `grace` before it is killed
+    async fn stop(&self, grace: Duration) {
+        let secs = grace.as_secs().max(1).to_string();
+        let stop = tokio::process::Command::new(&self.program)
+            .args(["stop", "--time", &secs, &self.name])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .status();
+        match tokio::time::timeout(grace + CONTAINER_STOP_SLACK, stop).await {
+            Ok(Ok(status)) if status.success() => log::info!("Stopped container {}", self.name),
+            Ok(Ok(status)) => {
+                log::warn!("{} stop {} exited with {}", self.program, self.name, status)
+            }
+            Ok(Err(e)) => log::warn!("Failed to run {} stop: {}", self.program, e),
+            Err(_) => log::warn!("{} stop {} did not finish in time", self.program, self.name),
+        }
     }
 }
 
 impl Drop for BridgeProcess {
     fn drop(&mut self) {
+        // Killing the client leaves its container running
+        if let Some(container) = self.container.take() {
+            if matches!(self.child.try_wait(), Ok(None)) {
+                let _ = Command::new(&container.program)
+                    .args(["stop", &container.name])
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn();
+            }
+        }
         let _ = self.child.kill();
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::child_env::EnvMode;
+    use super::super::fake_server::FakeServer;
     use super::*;
+    use std::collections::HashSet;
     use std::env;
+    use std::path::Path;
 
     #[tokio::test]
     async fn test_bridge_creation() {
@@ -434,4 +1546,1008 @@ mod tests {
         let bridge = PythonBridge::new(cwd.clone(), cwd);
         assert!(!bridge.is_running().await);
     }
+
+    #[tokio::test]
+    async fn test_timeout_cancels_pending_call() {
+        // Logs every line; answers tools/call only after the timeout
+        let log = env::temp_dir().join(format!("apply-task-timeout-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&log);
+        let server = FakeServer::new(
+            "timeout",
+            &json!({
+                "log": log,
+                "tools": { "tasks_context": { "delay_ms": 10_000 } },
+            }),
+        );
+        let bridge = server
+            .bridge()
+            .with_timeout(Some(Duration::from_millis(200)));
+
+        let err = bridge
+            .call_tool("tasks_context", json!({}))
+            .await
+            .unwrap_err();
+        match err.downcast_ref::<BridgeError>() {
+            Some(BridgeError::Timeout { tool, waited_ms }) => {
+                assert_eq!(tool, "tasks_context");
+                assert!(*waited_ms >= 200);
+            }
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+        assert!(!BridgeError::is_transport(&err));
+        assert!(bridge.pending().lock().unwrap().waiting.is_empty());
+        assert!(bridge.in_flight_tools().is_empty());
+
+        let call = bridge
+            .begin_tool_call("tasks_edit", json!({}), None)
+            .await
+            .unwrap();
+        assert_eq!(bridge.in_flight_tools(), ["tasks_edit"]);
+        drop(call);
+        assert!(bridge.in_flight_tools().is_empty());
+
+        let mut received = String::new();
+        for _ in 0..50 {
+            received = std::fs::read_to_string(&log).unwrap_or_default();
+            if received.contains("notifications/cancelled") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(received.contains(r#""requestId":2"#), "{}", received);
+
+        // Closing stdin ends the server loop before the grace period runs out
+        let started = Instant::now();
+        bridge
+            .shutdown_graceful(Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(!bridge.is_running().await);
+        let _ = std::fs::remove_file(&log);
+    }
+
+    #[tokio::test]
+    async fn test_terminate_over_limit_fails_in_flight_and_respawns() {
+        let server = FakeServer::new(
+            "resource-limit",
+            &json!({ "tools": {
+                "tasks_context": { "delay_ms": 10_000 },
+                "tasks_show": { "echo": true },
+            } }),
+        );
+        let bridge = server.bridge();
+        let call = bridge
+            .begin_tool_call("tasks_context", json!({}), None)
+            .await
+            .unwrap();
+        let pid = bridge.pid().await.unwrap();
+        let grace = Duration::from_secs(5);
+        assert!(!bridge
+            .terminate_over_limit(pid + 1, 2300, 2048, grace)
+            .await
+            .unwrap());
+        assert!(bridge
+            .terminate_over_limit(pid, 2300, 2048, grace)
+            .await
+            .unwrap());
+
+        let err = call.finish().await.unwrap_err();
+        match err.downcast_ref::<BridgeError>() {
+            Some(BridgeError::ResourceLimit {
+                tool,
+                rss_mb,
+                cap_mb,
+            }) => assert_eq!(
+                (tool.as_str(), *rss_mb, *cap_mb),
+                ("tasks_context", 2300, 2048)
+            ),
+            other => panic!("expected a resource limit, got {:?}", other),
+        }
+        assert!(!BridgeError::is_transport(&err));
+        assert!(!bridge.is_running().await);
+
+        // The next call starts a new backend
+        bridge
+            .call_tool("tasks_show", json!({ "task": "TASK-001" }))
+            .await
+            .unwrap();
+        assert_ne!(bridge.pid().await, Some(pid));
+    }
+
+    #[tokio::test]
+    async fn test_isolated_env_spawns_and_reports_its_variables() {
+        let server = FakeServer::new(
+            "isolated-env",
+            &json!({ "tools": { "tasks_show": { "echo": true } } }),
+        );
+        let bridge = server.bridge().with_env_policy(EnvPolicy {
+            isolated: true,
+            allowlist: vec!["APPLY_TASK_TEST_ALLOWED".into()],
+        });
+        assert_eq!(bridge.child_env().mode, EnvMode::Isolated);
+        bridge
+            .call_tool("tasks_show", json!({ "task": "TASK-001" }))
+            .await
+            .unwrap();
+        let env = bridge.child_env();
+        assert_eq!(env.mode, EnvMode::Isolated);
+        assert!(env
+            .names()
+            .iter()
+            .all(|name| !name.contains("SECRET") && name != "SSH_AUTH_SOCK"));
+        if std::env::var_os("PATH").is_some() {
+            assert!(env.vars().contains_key("PATH"));
+        }
+
+        // Back to inheriting: restarts, and the next spawn gets everything
+        assert!(bridge.set_env_policy(EnvPolicy::default()).await.unwrap());
+        assert_eq!(bridge.child_env().mode, EnvMode::Inherit);
+        assert!(bridge.child_env().vars().len() >= std::env::vars().count());
+    }
+
+    /// Container CLI standing in for docker: runs `script` (sh)
+    #[cfg(unix)]
+    fn stub_docker(dir: &Path, script: &str) -> ContainerConfig {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join("docker-stub");
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        ContainerConfig {
+            image: "apply-task:test".into(),
+            program: Some(path.to_string_lossy().into_owned()),
+            ..ContainerConfig::default()
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_container_runs_named_and_is_stopped() {
+        let server = FakeServer::new(
+            "container",
+            &json!({ "tools": { "tasks_show": { "echo": true } } }),
+        );
+        let log = server.dir.join("docker.log");
+        // `run ... mcp` serves MCP, `stop` is logged
+        let script = format!(
+            "echo \"$@\" >> '{}'\n[ \"$1\" = stop ] && exit 0\nexec '{}' '{}'",
+            log.display(),
+            super::super::fake_server::binary().display(),
+            server.dir.join("apply_task").display()
+        );
+        let config = stub_docker(&server.dir, &script);
+        let bridge = server.bridge().with_container(Some(config));
+        assert_eq!(bridge.status().await.install_mode, InstallMode::Container);
+        bridge
+            .call_tool("tasks_show", json!({ "task": "TASK-001" }))
+            .await
+            .unwrap();
+        bridge.shutdown().await.unwrap();
+
+        let calls = std::fs::read_to_string(&log).unwrap();
+        let lines: Vec<&str> = calls.lines().collect();
+        assert_eq!(lines.len(), 2, "{}", calls);
+        let name = lines[0]
+            .strip_prefix("run --name ")
+            .and_then(|rest| rest.split(' ').next())
+            .unwrap();
+        assert!(name.starts_with("apply-task-"));
+        let workspace = format!("-v {}:/workspace -w /workspace", server.dir.display());
+        assert!(lines[0].contains(&workspace), "{}", lines[0]);
+        assert!(lines[0].ends_with("apply-task:test mcp"));
+        assert_eq!(lines[1], format!("stop --time 2 {}", name));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_container_startup_failures_are_named() {
+        let server = FakeServer::new("container-fail", &json!({}));
+        let daemon = stub_docker(
+            &server.dir,
+            "echo 'docker: Cannot connect to the Docker daemon at unix:///var/run/docker.sock. Is the docker daemon running?' >&2\nexit 125",
+        );
+        let err = server
+            .bridge()
+            .with_container(Some(daemon))
+            .connect()
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err.downcast_ref::<BridgeError>(), Some(BridgeError::ContainerDaemon(d)) if d.contains("Is the docker daemon running")),
+            "{:#}",
+            err
+        );
+        assert!(BridgeError::is_transport(&err));
+
+        let image = stub_docker(
+            &server.dir,
+            "echo \"Unable to find image 'apply-task:test' locally\" >&2\necho 'docker: Error response from daemon: pull access denied for apply-task, repository does not exist' >&2\nexit 125",
+        );
+        let err = server
+            .bridge()
+            .with_container(Some(image))
+            .connect()
+            .await
+            .unwrap_err();
+        match err.downcast_ref::<BridgeError>() {
+            Some(BridgeError::ContainerImage { image }) => assert_eq!(image, "apply-task:test"),
+            other => panic!("expected a missing image, got {:?}", other),
+        }
+
+        let missing = ContainerConfig {
+            image: "apply-task:test".into(),
+            program: Some(server.dir.join("no-docker").to_string_lossy().into_owned()),
+            ..ContainerConfig::default()
+        };
+        let err = server
+            .bridge()
+            .with_container(Some(missing))
+            .connect()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BridgeError>(),
+            Some(BridgeError::ContainerDaemon(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    fn stub_ssh(dir: &Path, script: &str) -> SshConfig {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join("ssh-stub");
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        SshConfig {
+            host: "dev.example.com".into(),
+            user: Some("ada".into()),
+            path: "/srv/tasks".into(),
+            program: Some(path.to_string_lossy().into_owned()),
+            ..SshConfig::default()
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_ssh_reconnects_with_backoff() {
+        let server = FakeServer::new(
+            "ssh",
+            &json!({ "tools": { "tasks_show": { "echo": true }, "tasks_crash": { "exit": 3 } } }),
+        );
+        let log = server.dir.join("ssh.log");
+        let script = format!(
+            "echo \"$@\" >> '{}'\nexec '{}' '{}'",
+            log.display(),
+            super::super::fake_server::binary().display(),
+            server.dir.join("apply_task").display()
+        );
+        let bridge = server
+            .bridge()
+            .with_ssh(Some(stub_ssh(&server.dir, &script)));
+        let status = bridge.status().await;
+        assert_eq!(status.install_mode, InstallMode::Ssh);
+        assert_eq!(
+            status.remote.as_deref(),
+            Some("ada@dev.example.com:/srv/tasks")
+        );
+        bridge
+            .call_tool("tasks_show", json!({ "task": "TASK-001" }))
+            .await
+            .unwrap();
+
+        // A dropped connection waits out the backoff before reconnecting
+        assert!(bridge.call_tool("tasks_crash", json!({})).await.is_err());
+        for _ in 0..50 {
+            if bridge.exit_status().await.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let err = bridge
+            .call_tool("tasks_show", json!({ "task": "TASK-001" }))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err.downcast_ref::<BridgeError>(), Some(BridgeError::Disconnected(d)) if d.contains("reconnecting in")),
+            "{:#}",
+            err
+        );
+        tokio::time::sleep(RECONNECT_BASE + Duration::from_millis(100)).await;
+        bridge
+            .call_tool("tasks_show", json!({ "task": "TASK-001" }))
+            .await
+            .unwrap();
+        bridge.shutdown().await.unwrap();
+
+        let calls = std::fs::read_to_string(&log).unwrap();
+        assert_eq!(calls.lines().count(), 2, "{}", calls);
+        assert!(calls.lines().all(
+            |l| l.ends_with("-l ada -- dev.example.com cd '/srv/tasks' && exec apply_task mcp")
+        ));
+        assert_eq!(reconnect_delay(1), RECONNECT_BASE);
+        assert_eq!(reconnect_delay(3), RECONNECT_BASE * 4);
+        assert_eq!(reconnect_delay(40), RECONNECT_MAX);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_ssh_login_failures_are_named() {
+        let server = FakeServer::new("ssh-fail", &json!({}));
+        let host_key = stub_ssh(
+            &server.dir,
+            "echo '@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@' >&2\necho '@    WARNING: REMOTE HOST IDENTIFICATION HAS CHANGED!     @' >&2\necho 'Host key verification failed.' >&2\nexit 255",
+        );
+        let err = server
+            .bridge()
+            .with_ssh(Some(host_key))
+            .connect()
+            .await
+            .unwrap_err();
+        match err.downcast_ref::<BridgeError>() {
+            Some(BridgeError::SshHostKey { host }) => assert_eq!(host, "dev.example.com"),
+            other => panic!("expected a host key failure, got {:?}", other),
+        }
+        assert!(BridgeError::is_transport(&err));
+
+        let auth = stub_ssh(
+            &server.dir,
+            "echo 'ada@dev.example.com: Permission denied (publickey).' >&2\nexit 255",
+        );
+        let err = server
+            .bridge()
+            .with_ssh(Some(auth))
+            .connect()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BridgeError>(),
+            Some(BridgeError::SshAuth { .. })
+        ));
+
+        let unreachable = stub_ssh(
+            &server.dir,
+            "echo 'ssh: connect to host dev.example.com port 22: Connection refused' >&2\nexit 255",
+        );
+        let err = server
+            .bridge()
+            .with_ssh(Some(unreachable))
+            .connect()
+            .await
+            .unwrap_err();
+        match err.downcast_ref::<BridgeError>() {
+            Some(BridgeError::SshConnect { detail, .. }) => {
+                assert!(detail.ends_with("Connection refused"), "{}", detail)
+            }
+            other => panic!("expected a connect failure, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_calls_on_clones_overlap() {
+        // Every tools/call is answered (with its arguments) after 500 ms
+        let server = FakeServer::new(
+            "overlap",
+            &json!({ "tools": { "tasks_show": { "echo": true, "delay_ms": 500 } } }),
+        );
+        let bridge = server.bridge();
+        bridge.connect().await.unwrap();
+
+        let (first, second) = (bridge.clone(), bridge.clone());
+        let started = Instant::now();
+        let (a, b) = tokio::join!(
+            first.call_tool("tasks_show", json!({"task": "TASK-1"})),
+            second.call_tool("tasks_show", json!({"task": "TASK-2"})),
+        );
+        assert_ne!(a.unwrap(), b.unwrap());
+        // Serialized calls would take a second
+        assert!(started.elapsed() < Duration::from_millis(900));
+
+        bridge.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_handshake_and_tool_calls() {
+        let server = FakeServer::new(
+            "calls",
+            &json!({
+                "server_info": { "name": "apply_task", "version": "2.4.0" },
+                "tools": {
+                    "tasks_show": {
+                        "result": { "content": [{ "type": "text", "text": "{\"id\": \"T-1\"}" }] },
+                        "notifications": [{ "method": "notifications/progress", "params": { "progress": 1 } }],
+                    },
+                    "tasks_stats": { "result": { "content": [{ "type": "json", "json": { "total": 3 } }] } },
+                },
+            }),
+        );
+        let bridge = server.bridge();
+        let mut notifications = bridge.subscribe_notifications();
+        bridge.connect().await.unwrap();
+        assert_eq!(bridge.server_version().as_deref(), Some("2.4.0"));
+
+        let task = bridge.call_tool("tasks_show", json!({})).await.unwrap();
+        assert_eq!(task, json!({ "id": "T-1" }));
+        let note = notifications.recv().await.unwrap();
+        assert_eq!(note["method"], "notifications/progress");
+        let stats = bridge.call_tool("tasks_stats", json!({})).await.unwrap();
+        assert_eq!(stats["total"], 3);
+
+        // Handshake, both calls and the notification, as exchanged
+        let traffic: Vec<(Direction, Value)> = bridge
+            .wire_log()
+            .into_iter()
+            .map(|e| (e.direction, e.message["method"].clone()))
+            .collect();
+        assert_eq!(traffic[0], (Direction::Sent, json!("initialize")));
+        assert_eq!(traffic[1], (Direction::Received, Value::Null));
+        assert_eq!(traffic.iter().filter(|(_, m)| m == "tools/call").count(), 2);
+        assert!(traffic.contains(&(Direction::Received, json!("notifications/progress"))));
+        bridge.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_tool_errors() {
+        let server = FakeServer::new(
+            "errors",
+            &json!({
+                "tools": {
+                    "tasks_edit": { "error": { "code": -32000, "message": "Task is locked", "data": { "task": "T-1" } } },
+                    "tasks_show": { "result": { "content": [{ "type": "text", "text": "not json" }] } },
+                },
+            }),
+        );
+        let bridge = server.bridge();
+
+        let err = bridge.call_tool("tasks_edit", json!({})).await.unwrap_err();
+        let call_error = err.downcast_ref::<ToolCallError>().unwrap();
+        assert_eq!(call_error.code, -32000);
+        assert_eq!(call_error.message, "Task is locked");
+        assert_eq!(call_error.data, Some(json!({ "task": "T-1" })));
+
+        let err = bridge
+            .call_tool("tasks_purge", json!({}))
+            .await
+            .unwrap_err();
+        assert_eq!(err.downcast_ref::<ToolCallError>().unwrap().code, -32601);
+        let err = bridge.call_tool("tasks_show", json!({})).await.unwrap_err();
+        assert!(err.to_string().contains("as JSON"), "{}", err);
+        // Errors don't take the process down
+        assert!(bridge.is_running().await);
+        bridge.shutdown().await.unwrap();
+    }
+
+    /// Captured `tools/call` responses in tests/fixtures/tool_results, each
+    /// with the payload `call_tool` must return or its error message
+    ///
+    /// These are the response shapes the GUI accepts; a change to content
+    /// extraction has to update the fixtures along with it.
+    #[tokio::test]
+    async fn test_tool_result_fixtures() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tool_results");
+        let mut fixtures = Vec::new();
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|e| e == "json") {
+                let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+                let fixture: Value =
+                    serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+                fixtures.push((name, fixture));
+            }
+        }
+        fixtures.sort_by(|a, b| a.0.cmp(&b.0));
+        assert!(
+            fixtures.len() >= 8,
+            "fixtures missing from {}",
+            dir.display()
+        );
+
+        // One tool per fixture, answering with the captured result
+        let tools: serde_json::Map<String, Value> = fixtures
+            .iter()
+            .map(|(name, fixture)| {
+                let result = fixture["response"]["result"].clone();
+                (name.clone(), json!({ "result": result }))
+            })
+            .collect();
+        let server = FakeServer::new("tool-results", &json!({ "tools": tools }));
+        let bridge = server.bridge();
+
+        for (name, fixture) in &fixtures {
+            let expected = &fixture["expected"];
+            match bridge.call_tool(name, json!({})).await {
+                Ok(payload) => assert_eq!(&payload, &expected["payload"], "{}", name),
+                Err(err) => assert_eq!(
+                    Some(format!("{:#}", err).as_str()),
+                    expected["error"].as_str(),
+                    "{}",
+                    name
+                ),
+            }
+        }
+        bridge.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_crash_respawns_on_next_call() {
+        let server = FakeServer::new(
+            "crash",
+            &json!({
+                "tools": {
+                    "tasks_crash": { "exit": 3 },
+                    "tasks_show": { "echo": true },
+                },
+            }),
+        );
+        let bridge = server.bridge();
+        let spawns = bridge.subscribe_spawns();
+        bridge.connect().await.unwrap();
+        let first = *spawns.borrow();
+
+        let err = bridge
+            .call_tool("tasks_crash", json!({}))
+            .await
+            .unwrap_err();
+        assert!(BridgeError::is_transport(&err), "{}", err);
+        assert!(bridge.pending().lock().unwrap().waiting.is_empty());
+        let mut exited = None;
+        for _ in 0..50 {
+            exited = bridge.exit_status().await;
+            if exited.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(exited.and_then(|s| s.code()), Some(3));
+
+        // A fresh process, a fresh handshake
+        let echoed = bridge
+            .call_tool("tasks_show", json!({ "task": "T-1" }))
+            .await
+            .unwrap();
+        assert_eq!(echoed, json!({ "task": "T-1" }));
+        assert!(*spawns.borrow() > first);
+        bridge.shutdown().await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_restart_mid_call_spares_the_next_process() {
+        use std::os::unix::fs::PermissionsExt;
+        let server = FakeServer::new(
+            "restart-in-flight",
+            &json!({ "tools": { "tasks_slow": { "echo": true, "delay_ms": 800 } } }),
+        );
+        // The background sleep holds stdout open, so the old reader only
+        // sees EOF while the next process is answering
+        let wrapper = server.dir.join("python");
+        std::fs::write(
+            &wrapper,
+            format!(
+                "#!/bin/sh\n(sleep 0.5) &\nexec '{}' \"$@\"\n",
+                super::super::fake_server::binary().display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&wrapper, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let bridge = server
+            .bridge()
+            .with_python_path(Some(wrapper.to_string_lossy().into_owned()));
+        bridge.connect().await.unwrap();
+
+        let stale = {
+            let bridge = bridge.clone();
+            tokio::spawn(async move { bridge.call_tool("tasks_slow", json!({ "n": 1 })).await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        bridge.restart().await.unwrap();
+        let echoed = bridge
+            .call_tool("tasks_slow", json!({ "n": 2 }))
+            .await
+            .unwrap();
+        assert_eq!(echoed, json!({ "n": 2 }));
+        assert!(stale.await.unwrap().is_err());
+        bridge.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_injected_faults_use_the_real_error_paths() {
+        let server = FakeServer::new(
+            "faults",
+            &json!({ "tools": { "tasks_show": { "echo": true } } }),
+        );
+        let bridge = server.bridge();
+        let spawns = bridge.subscribe_spawns();
+        bridge.connect().await.unwrap();
+        let first = *spawns.borrow();
+        let sent = bridge.round_trips.load(Ordering::Relaxed);
+        let rule = |failure| super::super::FaultRule {
+            tool: "tasks_show".into(),
+            failure,
+            probability: 1.0,
+            count: Some(1),
+            injected: 0,
+        };
+
+        bridge
+            .faults()
+            .set(vec![rule(Failure::ToolError), rule(Failure::Timeout)])
+            .unwrap();
+        let err = bridge.call_tool("tasks_show", json!({})).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<ToolCallError>().unwrap().code, -32000);
+        let err = bridge
+            .clone()
+            .with_timeout(Some(Duration::from_millis(50)))
+            .call_tool("tasks_show", json!({}))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BridgeError>(),
+            Some(BridgeError::Timeout { waited_ms: 50, .. })
+        ));
+        // Only the listed tool, and only `count` times
+        assert_eq!(
+            bridge
+                .call_tool("tasks_show", json!({ "n": 1 }))
+                .await
+                .unwrap()["n"],
+            1
+        );
+        // Injected failures never reach the backend
+        assert_eq!(bridge.round_trips.load(Ordering::Relaxed), sent + 1);
+
+        bridge
+            .faults()
+            .set(vec![rule(Failure::Disconnect)])
+            .unwrap();
+        let err = bridge.call_tool("tasks_show", json!({})).await.unwrap_err();
+        assert!(BridgeError::is_transport(&err), "{}", err);
+        assert!(bridge.exit_status().await.is_some());
+        // The next call respawns the backend
+        bridge.call_tool("tasks_show", json!({})).await.unwrap();
+        assert!(*spawns.borrow() > first);
+        bridge.shutdown().await.unwrap();
+    }
+
+    /// `cargo test -- --ignored --nocapture stress` prints the throughput
+    #[tokio::test]
+    #[ignore = "stress test, run explicitly"]
+    async fn test_stress_leaves_no_pending_entries() {
+        let server = FakeServer::new(
+            "stress",
+            &json!({ "tools": { "tasks_show": { "echo": true } } }),
+        );
+        let bridge = server.bridge();
+        bridge.connect().await.unwrap();
+
+        let started = Instant::now();
+        for i in 0..1_000 {
+            let response = bridge
+                .call_tool("tasks_show", json!({ "task": i }))
+                .await
+                .unwrap();
+            assert_eq!(response["task"], i);
+        }
+        let sequential = started.elapsed();
+
+        let started = Instant::now();
+        let calls = (0..100).map(|i| {
+            let bridge = bridge.clone();
+            tokio::spawn(async move { bridge.call_tool("tasks_show", json!({ "task": i })).await })
+        });
+        let mut ids = HashSet::new();
+        for call in calls.collect::<Vec<_>>() {
+            let response = call.await.unwrap().unwrap();
+            assert!(ids.insert(response["task"].as_u64().unwrap()));
+        }
+        let concurrent = started.elapsed();
+        eprintln!(
+            "1000 sequential calls: {:?} ({:?}/call); 100 concurrent: {:?}",
+            sequential,
+            sequential / 1_000,
+            concurrent
+        );
+
+        assert!(bridge.pending().lock().unwrap().waiting.is_empty());
+        assert!(bridge.in_flight_tools().is_empty());
+        bridge.shutdown().await.unwrap();
+    }
+
+    /// Seed and rounds: fixed and short with `CI` set, otherwise
+    /// `APPLY_TASK_TEST_SEED` or the clock, and ten times the rounds
+    fn correlation_run() -> (u64, usize) {
+        if env::var_os("CI").is_some() {
+            return (0x5EED, 10);
+        }
+        let seed = env::var("APPLY_TASK_TEST_SEED")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_nanos() as u64
+            });
+        (seed, 100)
+    }
+
+    /// Many concurrent calls answered after random delays, so out of order;
+    /// every caller must get the response to its own request id, including
+    /// next to calls that time out and get their answer late
+    #[tokio::test]
+    async fn test_concurrent_calls_get_their_own_responses() {
+        const CALLS_PER_ROUND: u64 = 50;
+        let (seed, rounds) = correlation_run();
+        eprintln!("correlation test seed: {} ({} rounds)", seed, rounds);
+
+        let log =
+            env::temp_dir().join(format!("apply-task-correlation-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&log);
+        let server = FakeServer::new(
+            "correlation",
+            &json!({
+                "log": log,
+                "seed": seed,
+                "tools": { "tasks_show": { "echo": true, "echo_id": true, "jitter_ms": 15 } },
+            }),
+        );
+        let bridge = server.bridge();
+        bridge.connect().await.unwrap();
+        let impatient = bridge.clone().with_timeout(Some(Duration::from_millis(3)));
+
+        let mut answered = HashMap::new();
+        for round in 0..rounds as u64 {
+            let calls: Vec<_> = (0..CALLS_PER_ROUND)
+                .map(|i| {
+                    let caller = round * CALLS_PER_ROUND + i;
+                    let bridge = match caller % 7 {
+                        0 => impatient.clone(),
+                        _ => bridge.clone(),
+                    };
+                    let call = tokio::spawn(async move {
+                        bridge
+                            .call_tool("tasks_show", json!({ "caller": caller }))
+                            .await
+                    });
+                    (caller, call)
+                })
+                .collect();
+            for (caller, call) in calls {
+                match call.await.unwrap() {
+                    Ok(response) => {
+                        assert_eq!(response["caller"], caller, "seed {}", seed);
+                        let id = response["request_id"].as_u64().unwrap();
+                        assert!(answered.insert(id, caller).is_none(), "seed {}", seed);
+                    }
+                    Err(err) => {
+                        assert_eq!(caller % 7, 0, "seed {}: {}", seed, err);
+                        assert!(matches!(
+                            err.downcast_ref::<BridgeError>(),
+                            Some(BridgeError::Timeout { .. })
+                        ));
+                    }
+                }
+            }
+            assert!(
+                bridge.pending().lock().unwrap().waiting.is_empty(),
+                "seed {}",
+                seed
+            );
+        }
+        assert!(bridge.in_flight_tools().is_empty());
+
+        // The id each caller got back is the one its request went out with
+        let sent: HashMap<u64, u64> = std::fs::read_to_string(&log)
+            .unwrap()
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            .filter(|message| message["method"] == "tools/call")
+            .filter_map(|message| {
+                let caller = message["params"]["arguments"]["caller"].as_u64()?;
+                Some((message["id"].as_u64()?, caller))
+            })
+            .collect();
+        for (id, caller) in &answered {
+            assert_eq!(sent.get(id), Some(caller), "seed {}", seed);
+        }
+        bridge.shutdown().await.unwrap();
+        let _ = std::fs::remove_file(&log);
+    }
+
+    #[test]
+    fn test_read_loop_dispatches_by_id() {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(concat!(
+                r#"echo '{"jsonrpc":"2.0","method":"notifications/progress","params":{"progressToken":"job-1","progress":1}}';"#,
+                r#"echo '{"jsonrpc":"2.0","id":2,"result":{"n":2}}';"#,
+                r#"echo '{"jsonrpc":"2.0","id":null,"error":{"code":-32601,"message":"x"}}';"#,
+                r#"echo '{"jsonrpc":"2.0","id":4,"error":{"code":"broken"}}';"#,
+                r#"echo '{"jsonrpc":"2.0","id":1,"result":{"n":1}}'"#,
+            ))
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdout = child.stdout.take().unwrap();
+
+        let pending = PendingMap::default();
+        let (tx1, rx1) = oneshot::channel();
+        let (tx2, rx2) = oneshot::channel();
+        let (tx3, mut rx3) = oneshot::channel();
+        let (tx4, rx4) = oneshot::channel();
+        pending
+            .lock()
+            .unwrap()
+            .waiting
+            .extend([(1, tx1), (2, tx2), (3, tx3), (4, tx4)]);
+        let (notifications, mut notified) = broadcast::channel(4);
+
+        read_loop(
+            stdout,
+            Arc::new(ActiveFraming::new(Framing::Auto)),
+            pending.clone(),
+            notifications,
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicUsize::new(DEFAULT_MAX_MESSAGE_BYTES)),
+            Arc::default(),
+        );
+        let _ = child.wait();
+
+        assert_eq!(rx1.blocking_recv().unwrap().result, Ok(json!({"n": 1})));
+        assert_eq!(rx2.blocking_recv().unwrap().result, Ok(json!({"n": 2})));
+        // A malformed response fails its request instead of leaving it waiting
+        let malformed = rx4.blocking_recv().unwrap().result.unwrap_err();
+        assert_eq!(malformed.code, protocol::PARSE_ERROR);
+        // EOF fails requests that never got an answer
+        assert!(rx3.try_recv().is_err());
+        assert!(pending.lock().unwrap().waiting.is_empty());
+        assert_eq!(
+            notified.try_recv().unwrap()["params"]["progressToken"],
+            "job-1"
+        );
+    }
+
+    #[test]
+    fn test_read_loop_detects_header_framing() {
+        let body = "{\"jsonrpc\":\"2.0\",\n\"id\":1,\"result\":{\"n\":1}}";
+        let script = format!(
+            "printf 'Content-Length: {}\\r\\n\\r\\n%s' '{}'",
+            body.len(),
+            body
+        );
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdout = child.stdout.take().unwrap();
+
+        let pending = PendingMap::default();
+        let (tx, rx) = oneshot::channel();
+        pending.lock().unwrap().waiting.insert(1, tx);
+        let framing = Arc::new(ActiveFraming::new(Framing::Auto));
+        read_loop(
+            stdout,
+            framing.clone(),
+            pending,
+            broadcast::channel(1).0,
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicUsize::new(DEFAULT_MAX_MESSAGE_BYTES)),
+            Arc::default(),
+        );
+        let _ = child.wait();
+
+        assert_eq!(framing.get(), Framing::ContentLength);
+        assert_eq!(rx.blocking_recv().unwrap().result, Ok(json!({"n": 1})));
+    }
+
+    #[test]
+    fn test_read_loop_skips_oversized_messages() {
+        let filler = "x".repeat(200);
+        let script = [
+            format!(r#"{{"jsonrpc":"2.0","id":1,"result":"{}"}}"#, filler),
+            r#"{"jsonrpc":"2.0","id":2,"result":{"n":2}}"#.to_string(),
+            // Id past the limit: the only request still waiting gets the error
+            format!(r#"{{"jsonrpc":"2.0","result":"{}","id":3}}"#, filler),
+        ]
+        .map(|line| format!("echo '{}'", line))
+        .join(";");
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdout = child.stdout.take().unwrap();
+
+        let pending = PendingMap::default();
+        let (tx1, rx1) = oneshot::channel();
+        let (tx2, rx2) = oneshot::channel();
+        let (tx3, rx3) = oneshot::channel();
+        pending
+            .lock()
+            .unwrap()
+            .waiting
+            .extend([(1, tx1), (2, tx2), (3, tx3)]);
+        read_loop(
+            stdout,
+            Arc::new(ActiveFraming::new(Framing::Auto)),
+            pending,
+            broadcast::channel(1).0,
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicUsize::new(64)),
+            Arc::default(),
+        );
+        let _ = child.wait();
+
+        for rx in [rx1, rx3] {
+            let error = rx.blocking_recv().unwrap().result.unwrap_err();
+            assert_eq!(error.code, protocol::RESPONSE_TOO_LARGE);
+            assert_eq!(error.data, Some(json!({"limit": 64})));
+        }
+        assert_eq!(rx2.blocking_recv().unwrap().result, Ok(json!({"n": 2})));
+    }
+
+    #[test]
+    fn test_read_loop_fails_every_pending_on_oversized_without_id() {
+        let script = format!(
+            r#"echo '{{"jsonrpc":"2.0","result":"{}","id":5}}'"#,
+            "x".repeat(200)
+        );
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdout = child.stdout.take().unwrap();
+
+        // Two concurrent calls, either of which the frame may have answered
+        let pending = PendingMap::default();
+        let (tx4, rx4) = oneshot::channel();
+        let (tx5, rx5) = oneshot::channel();
+        pending.lock().unwrap().waiting.extend([(4, tx4), (5, tx5)]);
+        read_loop(
+            stdout,
+            Arc::new(ActiveFraming::new(Framing::Auto)),
+            pending,
+            broadcast::channel(1).0,
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicUsize::new(64)),
+            Arc::default(),
+        );
+        let _ = child.wait();
+
+        for rx in [rx4, rx5] {
+            let error = rx.blocking_recv().unwrap().result.unwrap_err();
+            assert_eq!(error.code, protocol::RESPONSE_TOO_LARGE);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_too_large_response_names_the_tool() {
+        let (tx, rx) = oneshot::channel();
+        let call = ToolCall {
+            request_id: 1,
+            tool: "tasks_list".into(),
+            response: rx,
+            _in_flight: InFlightGuard {
+                map: InFlightMap::default(),
+                request_id: 1,
+            },
+        };
+        tx.send(JsonRpcResponse {
+            id: 1,
+            result: Err(JsonRpcError {
+                code: protocol::RESPONSE_TOO_LARGE,
+                message: "Response exceeds 64 bytes".into(),
+                data: Some(json!({"limit": 64})),
+            }),
+        })
+        .unwrap();
+        let err = call.finish().await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BridgeError>(),
+            Some(BridgeError::ResponseTooLarge { limit: 64, tool }) if tool == "tasks_list"
+        ));
+    }
 }
@@ -0,0 +1,189 @@
+//! Backend process environment
+//!
+//! By default the backend inherits the whole GUI environment. With the
+//! `isolated_env` setting it starts from an empty one holding only
+//! [`BASE_VARS`], the locale variables, `APPLY_TASK_*` and the names in
+//! `isolated_env_allowlist`, so cloud credentials and agent sockets stay
+//! with the GUI. An `ssh` backend also gets `SSH_AUTH_SOCK`, which the
+//! ssh client needs for agent logins. Values the bridge sets itself
+//! (`PYTHONPATH` for a checkout) are added in either mode.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Variables an isolated backend always gets (if the GUI has them)
+pub const BASE_VARS: [&str; 4] = ["PATH", "HOME", "PYTHONPATH", "TZ"];
+/// What Python needs on Windows to start at all
+#[cfg(windows)]
+const PLATFORM_VARS: [&str; 6] = [
+    "SYSTEMROOT",
+    "USERPROFILE",
+    "TEMP",
+    "TMP",
+    "PATHEXT",
+    "LOCALAPPDATA",
+];
+#[cfg(not(windows))]
+const PLATFORM_VARS: [&str; 0] = [];
+const LOCALE_VARS: [&str; 2] = ["LANG", "LANGUAGE"];
+const LOCALE_PREFIX: &str = "LC_";
+const APP_PREFIX: &str = "APPLY_TASK_";
+/// ssh-agent socket an `ssh` backend is passed even when isolated
+pub const SSH_AGENT_VAR: &str = "SSH_AUTH_SOCK";
+
+/// `isolated_env` and `isolated_env_allowlist`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvPolicy {
+    pub isolated: bool,
+    /// Further variables passed to an isolated backend
+    pub allowlist: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvMode {
+    Inherit,
+    Isolated,
+}
+
+/// Environment a backend is started with
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChildEnv {
+    pub mode: EnvMode,
+    vars: BTreeMap<String, String>,
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    // Windows variable names ignore case
+    match cfg!(windows) {
+        true => a.eq_ignore_ascii_case(b),
+        false => a == b,
+    }
+}
+
+impl EnvPolicy {
+    fn passes(&self, name: &str) -> bool {
+        let upper = name.to_ascii_uppercase();
+        let listed = |names: &[&str]| names.iter().any(|n| same_name(n, name));
+        listed(&BASE_VARS)
+            || listed(&PLATFORM_VARS)
+            || listed(&LOCALE_VARS)
+            || upper.starts_with(LOCALE_PREFIX)
+            || upper.starts_with(APP_PREFIX)
+            || self.allowlist.iter().any(|n| same_name(n.trim(), name))
+    }
+
+    /// This policy for an `ssh` backend: the agent socket passes too
+    pub fn for_ssh(&self) -> EnvPolicy {
+        let mut policy = self.clone();
+        policy.allowlist.push(SSH_AGENT_VAR.to_string());
+        policy
+    }
+
+    /// Environment for a backend from the GUI's `parent` one, with
+    /// `overrides` set on top
+    pub fn build(
+        &self,
+        parent: impl IntoIterator<Item = (String, String)>,
+        overrides: &[(&str, String)],
+    ) -> ChildEnv {
+        let mut vars: BTreeMap<String, String> = parent
+            .into_iter()
+            .filter(|(name, _)| !self.isolated || self.passes(name))
+            .collect();
+        for (name, value) in overrides {
+            vars.insert(name.to_string(), value.clone());
+        }
+        ChildEnv {
+            mode: match self.isolated {
+                true => EnvMode::Isolated,
+                false => EnvMode::Inherit,
+            },
+            vars,
+        }
+    }
+}
+
+impl ChildEnv {
+    pub fn vars(&self) -> &BTreeMap<String, String> {
+        &self.vars
+    }
+
+    /// Names of the variables passed, sorted
+    pub fn names(&self) -> Vec<String> {
+        self.vars.keys().cloned().collect()
+    }
+
+    /// Start `cmd` with this environment (nothing else when isolated)
+    pub fn apply(&self, cmd: &mut std::process::Command) {
+        if self.mode == EnvMode::Isolated {
+            cmd.env_clear();
+        }
+        cmd.envs(&self.vars);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parent() -> Vec<(String, String)> {
+        [
+            ("PATH", "/usr/bin"),
+            ("HOME", "/home/ada"),
+            ("LC_ALL", "C.UTF-8"),
+            ("LANG", "en_US.UTF-8"),
+            ("APPLY_TASK_PROJECT_ROOT", "/src/apply_task"),
+            ("AWS_SECRET_ACCESS_KEY", "nope"),
+            ("SSH_AUTH_SOCK", "/tmp/agent.sock"),
+            ("PYENV_ROOT", "/home/ada/.pyenv"),
+            ("PYTHONPATH", "/home/ada/lib"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .into()
+    }
+
+    #[test]
+    fn test_isolated_passes_only_the_allowlist() {
+        let overrides = [("PYTHONPATH", "/src/apply_task".to_string())];
+        let policy = EnvPolicy {
+            isolated: true,
+            allowlist: vec![" PYENV_ROOT ".into()],
+        };
+        let env = policy.build(parent(), &overrides);
+        assert_eq!(env.mode, EnvMode::Isolated);
+        assert_eq!(
+            env.names(),
+            [
+                "APPLY_TASK_PROJECT_ROOT",
+                "HOME",
+                "LANG",
+                "LC_ALL",
+                "PATH",
+                "PYENV_ROOT",
+                "PYTHONPATH"
+            ]
+        );
+        assert_eq!(env.vars()["PYTHONPATH"], "/src/apply_task");
+
+        let env = EnvPolicy::default().build(parent(), &overrides);
+        assert_eq!(env.mode, EnvMode::Inherit);
+        assert_eq!(env.vars().len(), parent().len());
+        assert!(env.vars().contains_key("SSH_AUTH_SOCK"));
+        assert_eq!(env.vars()["PYTHONPATH"], "/src/apply_task");
+    }
+
+    #[test]
+    fn test_isolated_ssh_backend_keeps_the_agent_socket() {
+        let policy = EnvPolicy {
+            isolated: true,
+            allowlist: vec![],
+        };
+        let env = policy.for_ssh().build(parent(), &[]);
+        assert_eq!(env.mode, EnvMode::Isolated);
+        assert_eq!(env.vars()[SSH_AGENT_VAR], "/tmp/agent.sock");
+        assert!(!env.vars().contains_key("AWS_SECRET_ACCESS_KEY"));
+        assert!(policy.allowlist.is_empty());
+    }
+}
@@ -0,0 +1,165 @@
+//! Coalescing of identical in-flight read calls
+//!
+//! Callers with the same key while a call is in flight share its result
+//! instead of issuing another round trip. Only whitelisted read-only tools
+//! go through here; results are not kept once the call completes. Each
+//! waiter gets its own copy of a failure, still a [`BridgeError`] or
+//! [`ToolCallError`] when it was one.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use tokio::sync::OnceCell;
+
+use super::error::{BridgeError, ToolCallError};
+
+/// Read-only tools whose identical concurrent calls are merged
+pub const COALESCED_TOOLS: &[&str] = &[
+    "tasks_context",
+    "tasks_resume",
+    "tasks_storage",
+    "tasks_history",
+    "tasks_radar",
+    "tasks_templates_list",
+];
+
+type Shared = Arc<OnceCell<std::result::Result<Value, Arc<anyhow::Error>>>>;
+
+#[derive(Default)]
+pub struct Coalescer {
+    inflight: Mutex<HashMap<String, Shared>>,
+    coalesced: AtomicU64,
+}
+
+/// Key for `tool` + `arguments`
+///
+/// serde_json maps are ordered by key, so equal params serialize equally.
+pub fn call_key(tool: &str, arguments: &Value) -> String {
+    format!("{}:{}", tool, arguments)
+}
+
+impl Coalescer {
+    /// Callers that joined an in-flight call instead of making their own
+    pub fn coalesced(&self) -> u64 {
+        self.coalesced.load(Ordering::Relaxed)
+    }
+
+    /// Run `call` unless an identical one is in flight, then share its result
+    pub async fn run<F, Fut>(&self, key: String, call: F) -> Result<Value>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Value>>,
+    {
+        let (cell, joined) = {
+            let mut inflight = self.inflight.lock().unwrap_or_else(|e| e.into_inner());
+            match inflight.get(&key) {
+                Some(cell) => (cell.clone(), true),
+                None => {
+                    let cell = Shared::default();
+                    inflight.insert(key.clone(), cell.clone());
+                    (cell, false)
+                }
+            }
+        };
+        if joined {
+            self.coalesced.fetch_add(1, Ordering::Relaxed);
+        }
+
+        // If the caller running the call is dropped, a waiter takes over
+        let result = cell
+            .get_or_init(|| async { call().await.map_err(Arc::new) })
+            .await
+            .clone();
+
+        {
+            let mut inflight = self.inflight.lock().unwrap_or_else(|e| e.into_inner());
+            if inflight.get(&key).is_some_and(|c| Arc::ptr_eq(c, &cell)) {
+                inflight.remove(&key);
+            }
+        }
+        result.map_err(|e| rebuild(&e))
+    }
+}
+
+/// A waiter's copy of `err`: the typed error, under the outer message
+fn rebuild(err: &anyhow::Error) -> anyhow::Error {
+    let typed = err
+        .downcast_ref::<BridgeError>()
+        .cloned()
+        .map(anyhow::Error::new)
+        .or_else(|| {
+            err.downcast_ref::<ToolCallError>()
+                .cloned()
+                .map(anyhow::Error::new)
+        });
+    match typed {
+        Some(typed) if typed.to_string() == err.to_string() => typed,
+        Some(typed) => typed.context(err.to_string()),
+        None => anyhow!(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_identical_concurrent_calls_share_one_round_trip() {
+        let coalescer = Coalescer::default();
+        let round_trips = AtomicU64::new(0);
+        let params = json!({"compact": true, "include_all": true});
+        let call = || async {
+            round_trips.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok(json!({"tasks": []}))
+        };
+
+        let (a, b, c) = tokio::join!(
+            coalescer.run(call_key("tasks_context", &params), call),
+            coalescer.run(call_key("tasks_context", &params), call),
+            coalescer.run(call_key("tasks_context", &params), call),
+        );
+        assert_eq!(round_trips.load(Ordering::SeqCst), 1);
+        assert_eq!(coalescer.coalesced(), 2);
+        for result in [a, b, c] {
+            assert_eq!(result.unwrap(), json!({"tasks": []}));
+        }
+
+        // Finished calls are not cached, other params are separate calls
+        coalescer
+            .run(call_key("tasks_context", &params), call)
+            .await
+            .unwrap();
+        coalescer
+            .run(call_key("tasks_context", &json!({})), call)
+            .await
+            .unwrap();
+        assert_eq!(round_trips.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_every_waiter_gets_the_typed_error() {
+        let coalescer = Coalescer::default();
+        let call = || async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Err(
+                anyhow::Error::new(BridgeError::Disconnected("stdout closed".into()))
+                    .context("tools/call tasks_context"),
+            )
+        };
+        let (a, b) = tokio::join!(
+            coalescer.run(call_key("tasks_context", &json!({})), call),
+            coalescer.run(call_key("tasks_context", &json!({})), call),
+        );
+        for err in [a.unwrap_err(), b.unwrap_err()] {
+            assert!(BridgeError::is_transport(&err));
+            assert_eq!(err.to_string(), "tools/call tasks_context");
+        }
+    }
+}
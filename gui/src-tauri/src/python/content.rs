@@ -0,0 +1,176 @@
+//! MCP content parts of a `tools/call` result
+//!
+//! The payload is the first `json` or `text` part. `image` parts (`data`
+//! in base64, `mimeType`) become `data:` URIs the webview can show as is;
+//! one that is corrupt, too large or not an image is dropped with a
+//! warning instead of failing the call. Callers get the images as an
+//! `images` list on the payload object.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde_json::Value;
+
+/// Largest decoded image kept
+pub const MAX_IMAGE_BYTES: usize = 8 * 1024 * 1024;
+
+/// A tool result taken apart
+#[derive(Debug, Default, PartialEq)]
+pub struct ToolContent {
+    pub payload: Value,
+    /// `data:<mime>;base64,...`
+    pub images: Vec<String>,
+    /// Image parts that were dropped, and why
+    pub warnings: Vec<String>,
+}
+
+impl ToolContent {
+    /// Payload with `images` added (objects only, never replacing a key the
+    /// tool set itself); warnings are logged
+    pub fn into_value(self) -> Value {
+        for warning in &self.warnings {
+            log::warn!("{}", warning);
+        }
+        let mut payload = self.payload;
+        if self.images.is_empty() {
+            return payload;
+        }
+        match payload.as_object_mut() {
+            Some(obj) if !obj.contains_key("images") => {
+                obj.insert("images".to_string(), self.images.into());
+            }
+            _ => log::warn!(
+                "Dropped {} image(s): the tool payload has no room for them",
+                self.images.len()
+            ),
+        }
+        payload
+    }
+}
+
+/// `data:` URI of an image part
+fn image_uri(part: &Value) -> Result<String, String> {
+    let mime = part
+        .get("mimeType")
+        .and_then(|m| m.as_str())
+        .unwrap_or_default()
+        .trim();
+    if !mime.starts_with("image/") || mime.contains([';', ',']) {
+        return Err(format!("unsupported mimeType '{}'", mime));
+    }
+    let data: String = part
+        .get("data")
+        .and_then(|d| d.as_str())
+        .ok_or("no data")?
+        .split_ascii_whitespace()
+        .collect();
+    // Checked before decoding; base64 is 4 characters per 3 bytes
+    if data.len() / 4 * 3 > MAX_IMAGE_BYTES {
+        return Err(format!("larger than {} bytes", MAX_IMAGE_BYTES));
+    }
+    base64::engine::general_purpose::STANDARD
+        .decode(&data)
+        .map_err(|e| format!("invalid base64 ({})", e))?;
+    Ok(format!("data:{};base64,{}", mime, data))
+}
+
+fn is_image(part: &Value) -> bool {
+    part.get("type").and_then(|t| t.as_str()) == Some("image")
+}
+
+/// Payload and images of a `tools/call` `result` (a result without a
+/// content list is the payload itself)
+///
+/// Takes the payload out of `result` instead of copying it; task lists can
+/// run to megabytes.
+pub fn extract(mut result: Value) -> Result<ToolContent> {
+    let Some(parts) = result.get_mut("content").and_then(|c| c.as_array_mut()) else {
+        return Ok(ToolContent {
+            payload: result,
+            ..ToolContent::default()
+        });
+    };
+
+    let mut content = ToolContent::default();
+    for (index, part) in parts.iter().enumerate().filter(|(_, p)| is_image(p)) {
+        match image_uri(part) {
+            Ok(uri) => content.images.push(uri),
+            Err(reason) => content
+                .warnings
+                .push(format!("Skipped image content #{}: {}", index, reason)),
+        }
+    }
+    let payload = parts
+        .iter_mut()
+        .filter(|part| !is_image(part))
+        .find(|part| part.get("json").is_some() || part["text"].is_string());
+    content.payload = match payload {
+        Some(part) => match part.get_mut("json") {
+            Some(json) => json.take(),
+            None => serde_json::from_str(part["text"].as_str().unwrap_or_default())
+                .context("Failed to parse tool response text as JSON")?,
+        },
+        None if content.images.is_empty() => result,
+        None => Value::Object(Default::default()),
+    };
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // 1x1 transparent PNG
+    const PNG: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII=";
+
+    #[test]
+    fn test_images_next_to_the_payload() {
+        let result = json!({"content": [
+            {"type": "text", "text": "{\"report\": \"velocity\"}"},
+            {"type": "image", "data": PNG, "mimeType": "image/png"},
+            {"type": "image", "data": "not base64!", "mimeType": "image/png"},
+            {"type": "image", "data": PNG, "mimeType": "text/html"},
+            {"type": "text", "text": "ignored"},
+        ]});
+        let content = extract(result).unwrap();
+        assert_eq!(content.payload, json!({"report": "velocity"}));
+        assert_eq!(content.images, [format!("data:image/png;base64,{}", PNG)]);
+        assert_eq!(content.warnings.len(), 2);
+        assert!(content.warnings[0].starts_with("Skipped image content #2: invalid base64"));
+        assert!(content.warnings[1].contains("text/html"));
+    }
+
+    #[test]
+    fn test_payload_shapes() {
+        let only_image =
+            json!({"content": [{"type": "image", "data": PNG, "mimeType": "image/png"}]});
+        let content = extract(only_image).unwrap();
+        assert_eq!(content.payload, json!({}));
+        assert_eq!(content.images.len(), 1);
+
+        let bare = json!({"tasks": []});
+        assert_eq!(extract(bare.clone()).unwrap().payload, bare);
+        let json_part = json!({"content": [{"type": "json", "json": {"n": 1}}]});
+        assert_eq!(extract(json_part).unwrap().payload, json!({"n": 1}));
+        assert!(extract(json!({"content": [{"type": "text", "text": "{"}]})).is_err());
+
+        let with_images = ToolContent {
+            payload: json!({"n": 1}),
+            images: vec!["data:image/png;base64,AA==".into()],
+            warnings: vec![],
+        };
+        assert_eq!(
+            with_images.into_value(),
+            json!({"n": 1, "images": ["data:image/png;base64,AA=="]})
+        );
+        let taken = ToolContent {
+            payload: json!({"images": 3}),
+            images: vec!["data:image/png;base64,AA==".into()],
+            warnings: vec![],
+        };
+        assert_eq!(taken.into_value(), json!({"images": 3}));
+
+        let huge = json!({"type": "image", "data": "A".repeat(MAX_IMAGE_BYTES / 3 * 4 + 8), "mimeType": "image/png"});
+        assert!(image_uri(&huge).unwrap_err().starts_with("larger than"));
+    }
+}
@@ -0,0 +1,118 @@
+//! Typed bridge failures
+//!
+//! Bridge calls return `anyhow::Result`; failures that callers need to tell
+//! apart are raised as [`BridgeError`] (or [`ToolCallError`] for a JSON-RPC
+//! error answer) and can be recovered by downcasting.
+
+/// Failure kinds of the bridge itself (not tool-level errors)
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum BridgeError {
+    /// The backend process could not be started
+    #[error("Failed to start Python backend: {0}")]
+    Spawn(String),
+    /// The process is gone or its pipes are closed
+    #[error("Python backend unavailable: {0}")]
+    Disconnected(String),
+    /// The caller's `timeout_ms` ran out; the request was cancelled
+    #[error("{tool} timed out after {waited_ms} ms")]
+    Timeout { tool: String, waited_ms: u64 },
+    /// The response passed `max_message_bytes` and was skipped
+    #[error("{tool} returned more than {limit} bytes; page through the results or ask for compact output")]
+    ResponseTooLarge { limit: u64, tool: String },
+    /// The container CLI can't reach its daemon (or isn't installed)
+    #[error("Docker is not available ({0}); start the Docker daemon or Docker Desktop, or clear the container setting to run the backend with Python")]
+    ContainerDaemon(String),
+    /// The `container` image is neither local nor pullable
+    #[error("Docker image {image} was not found; build it or pull it (docker pull {image}), or change container.image")]
+    ContainerImage { image: String },
+    /// The SSH host key is unknown or changed
+    #[error("The SSH host key of {host} is unknown or has changed; run ssh {host} once in a terminal to verify it, or fix ~/.ssh/known_hosts")]
+    SshHostKey { host: String },
+    /// No SSH key was accepted (password prompts can't be answered)
+    #[error(
+        "SSH login to {host} failed; load a key into ssh-agent or pass one in ssh.args (-i <key>)"
+    )]
+    SshAuth { host: String },
+    /// The SSH host can't be reached
+    #[error("Could not connect to {host} over SSH: {detail}")]
+    SshConnect { host: String, detail: String },
+    /// The backend was stopped for staying over `backend_rss_cap_mb`
+    #[error("{tool} was cut off: the backend used {rss_mb} MB, over its {cap_mb} MB memory cap, and was stopped; it restarts on the next request")]
+    ResourceLimit {
+        tool: String,
+        rss_mb: u64,
+        cap_mb: u64,
+    },
+}
+
+/// JSON-RPC error answer to a `tools/call`
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Tool call error {code}: {message}")]
+pub struct ToolCallError {
+    pub code: i32,
+    pub message: String,
+    /// Structured detail the backend attached (failed fields, blocking checkpoint, ...)
+    pub data: Option<serde_json::Value>,
+}
+
+impl ToolCallError {
+    /// Tool named in an `Unknown tool` answer (the backend doesn't have it)
+    pub fn missing_tool(&self) -> Option<&str> {
+        self.message
+            .strip_prefix("Unknown tool: ")
+            .filter(|_| self.code == -32602)
+    }
+}
+
+impl BridgeError {
+    /// Whether `err` means the backend couldn't be reached at all
+    pub fn is_transport(err: &anyhow::Error) -> bool {
+        matches!(
+            err.downcast_ref::<BridgeError>(),
+            Some(
+                BridgeError::Spawn(_)
+                    | BridgeError::Disconnected(_)
+                    | BridgeError::ContainerDaemon(_)
+                    | BridgeError::ContainerImage { .. }
+                    | BridgeError::SshHostKey { .. }
+                    | BridgeError::SshAuth { .. }
+                    | BridgeError::SshConnect { .. }
+            )
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_transport_survives_context() {
+        let err = anyhow::Error::new(BridgeError::Disconnected("stdin closed".into()))
+            .context("tools/call tasks_note");
+        assert!(BridgeError::is_transport(&err));
+        assert!(!BridgeError::is_transport(&anyhow::anyhow!(
+            "Tool call error -32602: x"
+        )));
+    }
+
+    #[test]
+    fn test_missing_tool() {
+        let missing = ToolCallError {
+            code: -32602,
+            message: "Unknown tool: tasks_set_due".into(),
+            data: None,
+        };
+        assert_eq!(missing.missing_tool(), Some("tasks_set_due"));
+        assert_eq!(
+            missing.to_string(),
+            "Tool call error -32602: Unknown tool: tasks_set_due"
+        );
+        let invalid = ToolCallError {
+            code: -32602,
+            message: "arguments must be an object".into(),
+            data: None,
+        };
+        assert_eq!(invalid.missing_tool(), None);
+    }
+}
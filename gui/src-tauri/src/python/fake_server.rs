@@ -0,0 +1,59 @@
+//! Fake MCP server for bridge tests
+//!
+//! Runs the `fake-mcp-server` binary (tests/support/fake_mcp_server.rs,
+//! where the scenario format is documented) in place of Python: the
+//! scenario is written as the root's `apply_task` entry point and the
+//! binary is set as the interpreter, so the bridge spawns it over real
+//! pipes. `cargo build` / `cargo test` build the binary next to the test
+//! executables.
+
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use super::PythonBridge;
+
+/// `target/<profile>/fake-mcp-server`
+pub(crate) fn binary() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    // Test executables live in `target/<profile>/deps`
+    let path = exe
+        .parent()
+        .and_then(|deps| deps.parent())
+        .unwrap()
+        .join(format!("fake-mcp-server{}", std::env::consts::EXE_SUFFIX));
+    assert!(
+        path.exists(),
+        "{} is missing; run `cargo build --bin fake-mcp-server`",
+        path.display()
+    );
+    path
+}
+
+/// A scenario in its own temp dir (removed on drop)
+pub struct FakeServer {
+    pub dir: PathBuf,
+}
+
+impl FakeServer {
+    /// Write `scenario` into a fresh `apply-task-<name>-<pid>` dir
+    pub fn new(name: &str, scenario: &Value) -> Self {
+        let dir = std::env::temp_dir().join(format!("apply-task-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("apply_task"), scenario.to_string()).unwrap();
+        Self { dir }
+    }
+
+    /// Bridge that spawns this server (not yet connected)
+    pub fn bridge(&self) -> PythonBridge {
+        PythonBridge::new(self.dir.clone(), self.dir.clone())
+            .with_python_path(Some(binary().to_string_lossy().into_owned()))
+    }
+}
+
+impl Drop for FakeServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
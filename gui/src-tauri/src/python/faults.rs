@@ -0,0 +1,238 @@
+//! Fault injection for manual QA
+//!
+//! With developer mode on, `set_fault_injection` arms rules that make
+//! [`PythonBridge::call_tool`](super::PythonBridge::call_tool) fail before
+//! the call is dispatched. The failures are the bridge's own typed errors
+//! (a disconnect really kills the backend), so commands, events, the
+//! offline queue and respawns react as they would to a real one. No rules
+//! means a single atomic load per call; turning developer mode off clears
+//! them.
+
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::error::ToolCallError;
+
+/// Rule `tool` matching every tool
+pub const ANY_TOOL: &str = "*";
+/// How long an injected timeout takes on a bridge without a call timeout
+pub const INJECTED_TIMEOUT: Duration = Duration::from_secs(5);
+/// JSON-RPC code of an injected tool error
+pub const INJECTED_ERROR_CODE: i32 = -32000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Failure {
+    /// Waits out the call timeout, then fails as `BRIDGE_TIMEOUT`
+    Timeout,
+    /// A JSON-RPC error answer from the tool
+    ToolError,
+    /// Kills the backend; the call fails as `BRIDGE_DISCONNECTED` and the
+    /// next one respawns it
+    Disconnect,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Timeout => "timeout",
+            Self::ToolError => "tool_error",
+            Self::Disconnect => "disconnect",
+        })
+    }
+}
+
+fn certain() -> f64 {
+    1.0
+}
+
+/// One rule of `set_fault_injection`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FaultRule {
+    /// Tool name, or `*` for every tool
+    pub tool: String,
+    pub failure: Failure,
+    /// Chance that a matching call fails (default 1)
+    #[serde(default = "certain")]
+    pub probability: f64,
+    /// Failures left (unlimited when absent)
+    #[serde(default)]
+    pub count: Option<u32>,
+    /// Failures injected so far
+    #[serde(default)]
+    pub injected: u32,
+}
+
+impl FaultRule {
+    fn matches(&self, tool: &str) -> bool {
+        self.count != Some(0) && (self.tool == ANY_TOOL || self.tool == tool)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FaultError {
+    #[error("Fault injection needs developer_mode in settings")]
+    DeveloperModeOff,
+    #[error("Fault rule {index}: {detail}")]
+    InvalidRule { index: usize, detail: String },
+}
+
+/// Armed rules, shared by the bridge's clones
+#[derive(Debug, Default)]
+pub struct FaultInjector {
+    armed: AtomicBool,
+    rules: Mutex<Vec<FaultRule>>,
+}
+
+impl FaultInjector {
+    /// Replace the rules (counters start from zero)
+    pub fn set(&self, rules: Vec<FaultRule>) -> Result<Vec<FaultRule>> {
+        for (index, rule) in rules.iter().enumerate() {
+            let detail = if rule.tool.trim().is_empty() {
+                "tool must not be empty"
+            } else if !(0.0..=1.0).contains(&rule.probability) {
+                "probability must be between 0 and 1"
+            } else {
+                continue;
+            };
+            return Err(FaultError::InvalidRule {
+                index,
+                detail: detail.to_string(),
+            }
+            .into());
+        }
+        let rules: Vec<FaultRule> = rules
+            .into_iter()
+            .map(|rule| FaultRule {
+                tool: rule.tool.trim().to_string(),
+                injected: 0,
+                ..rule
+            })
+            .collect();
+        let mut current = self.rules.lock().unwrap_or_else(|e| e.into_inner());
+        self.armed.store(!rules.is_empty(), Ordering::Release);
+        *current = rules.clone();
+        Ok(rules)
+    }
+
+    pub fn rules(&self) -> Vec<FaultRule> {
+        self.rules.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn clear(&self) {
+        let _ = self.set(Vec::new());
+    }
+
+    /// Failure to inject into a call of `tool` (first matching rule that
+    /// rolls it), counting it against the rule
+    pub fn take(&self, tool: &str) -> Option<Failure> {
+        if !self.armed.load(Ordering::Acquire) {
+            return None;
+        }
+        let mut rules = self.rules.lock().unwrap_or_else(|e| e.into_inner());
+        let rule = rules
+            .iter_mut()
+            .filter(|rule| rule.matches(tool))
+            .find(|rule| roll() < rule.probability)?;
+        rule.count = rule.count.map(|left| left - 1);
+        rule.injected += 1;
+        Some(rule.failure)
+    }
+}
+
+/// Uniform in `[0, 1)` (std's per-instance random hash keys; QA needs no
+/// better)
+fn roll() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// The error answer an injected `tool_error` stands for
+pub fn tool_error(tool: &str) -> ToolCallError {
+    ToolCallError {
+        code: INJECTED_ERROR_CODE,
+        message: format!("Injected failure for {}", tool),
+        data: Some(json!({ "injected": true })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(tool: &str, failure: Failure, probability: f64, count: Option<u32>) -> FaultRule {
+        FaultRule {
+            tool: tool.into(),
+            failure,
+            probability,
+            count,
+            injected: 0,
+        }
+    }
+
+    #[test]
+    fn test_rules_match_count_and_roll() {
+        let faults = FaultInjector::default();
+        assert_eq!(faults.take("tasks_list"), None);
+
+        faults
+            .set(vec![
+                rule("tasks_list", Failure::Timeout, 1.0, Some(2)),
+                rule("tasks_show", Failure::ToolError, 0.0, None),
+                rule(ANY_TOOL, Failure::Disconnect, 1.0, Some(1)),
+            ])
+            .unwrap();
+        assert_eq!(faults.take("tasks_list"), Some(Failure::Timeout));
+        assert_eq!(faults.take("tasks_list"), Some(Failure::Timeout));
+        // Exhausted: the wildcard takes over, once
+        assert_eq!(faults.take("tasks_list"), Some(Failure::Disconnect));
+        assert_eq!(faults.take("tasks_list"), None);
+        // Probability 0 never fires
+        assert_eq!(faults.take("tasks_show"), None);
+
+        let rules = faults.rules();
+        assert_eq!(rules[0].injected, 2);
+        assert_eq!(rules[0].count, Some(0));
+        assert_eq!(rules[2].injected, 1);
+
+        faults.clear();
+        assert!(faults.rules().is_empty());
+        assert!(!faults.armed.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_invalid_rules_are_rejected() {
+        let faults = FaultInjector::default();
+        faults
+            .set(vec![rule("tasks_list", Failure::Timeout, 1.0, None)])
+            .unwrap();
+        let err = faults
+            .set(vec![
+                rule("tasks_list", Failure::Timeout, 1.0, None),
+                rule("tasks_show", Failure::Timeout, 1.5, None),
+            ])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Fault rule 1: probability must be between 0 and 1"
+        );
+        assert!(faults
+            .set(vec![rule(" ", Failure::Timeout, 1.0, None)])
+            .is_err());
+        // A rejected set keeps the rules that were armed
+        assert_eq!(faults.rules().len(), 1);
+
+        let parsed: FaultRule =
+            serde_json::from_value(json!({ "tool": "tasks_list", "failure": "tool_error" }))
+                .unwrap();
+        assert_eq!(parsed, rule("tasks_list", Failure::ToolError, 1.0, None));
+    }
+}
@@ -0,0 +1,371 @@
+//! Message framing on the backend's stdio
+//!
+//! MCP stdio servers normally write one JSON message per line. Some frame
+//! each message LSP-style instead, with a `Content-Length` header block in
+//! front of the body. [`Framing::Auto`] takes whichever the backend's first
+//! bytes use; until the backend has written something, outgoing messages
+//! are newline-delimited, so a header-framed server that waits for the
+//! client to speak first needs `content_length` set explicitly.
+//!
+//! Both framings stop buffering a message at the caller's size limit and
+//! skip the rest of it, so one runaway message can't exhaust memory or
+//! desynchronize the stream.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+const CONTENT_LENGTH: &str = "content-length";
+
+/// `stdio_framing` setting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Framing {
+    /// Detect from the first bytes the backend writes
+    #[default]
+    Auto,
+    /// One JSON message per line
+    Newline,
+    /// `Content-Length: N` headers, a blank line, then N bytes of JSON
+    ContentLength,
+}
+
+impl Framing {
+    const ALL: [Framing; 3] = [Self::Auto, Self::Newline, Self::ContentLength];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Newline => "newline",
+            Self::ContentLength => "content_length",
+        }
+    }
+
+    /// Codec for this framing (newline-delimited while `Auto` is undecided)
+    pub fn codec(self) -> &'static dyn Codec {
+        match self {
+            Self::ContentLength => &HeaderFramed,
+            Self::Auto | Self::Newline => &NewlineDelimited,
+        }
+    }
+
+    /// Framing of a stream that starts with `first` (its first
+    /// non-whitespace byte): JSON starts with `{` or `[`, headers with a name
+    fn detect(first: u8) -> Self {
+        match first.to_ascii_lowercase() {
+            b'c' => Self::ContentLength,
+            _ => Self::Newline,
+        }
+    }
+}
+
+impl fmt::Display for Framing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One message read from the stream
+#[derive(Debug, PartialEq, Eq)]
+pub enum Frame {
+    Message(Vec<u8>),
+    /// Longer than the limit: the first `limit` bytes, the rest was skipped
+    Oversized(Vec<u8>),
+}
+
+/// Reads and writes whole messages in one framing
+pub trait Codec: Send + Sync {
+    /// Next message (body at most `limit` bytes), `None` at a clean end of
+    /// stream
+    ///
+    /// `InvalidData` errors spoil one message; the stream can be read on.
+    fn read_message(&self, reader: &mut dyn BufRead, limit: usize) -> io::Result<Option<Frame>>;
+    /// Write `body` as one message (not flushed)
+    fn write_message(&self, writer: &mut dyn Write, body: &[u8]) -> io::Result<()>;
+}
+
+/// One message per `\n`-terminated line
+pub struct NewlineDelimited;
+
+impl Codec for NewlineDelimited {
+    fn read_message(&self, reader: &mut dyn BufRead, limit: usize) -> io::Result<Option<Frame>> {
+        let mut buf = Vec::new();
+        let mut read_any = false;
+        let mut oversized = false;
+        loop {
+            let available = reader.fill_buf()?;
+            if available.is_empty() {
+                break;
+            }
+            read_any = true;
+            let (line, used, done) = match available.iter().position(|&b| b == b'\n') {
+                Some(at) => (&available[..at], at + 1, true),
+                None => (available, available.len(), false),
+            };
+            let room = limit - buf.len();
+            oversized |= line.len() > room;
+            buf.extend_from_slice(&line[..line.len().min(room)]);
+            reader.consume(used);
+            if done {
+                break;
+            }
+        }
+        Ok(match (read_any, oversized) {
+            (false, _) => None,
+            (true, false) => Some(Frame::Message(buf)),
+            (true, true) => Some(Frame::Oversized(buf)),
+        })
+    }
+
+    fn write_message(&self, writer: &mut dyn Write, body: &[u8]) -> io::Result<()> {
+        // serde_json escapes newlines inside strings, so only pretty-printed
+        // bodies could break a line apart
+        if body.contains(&b'\n') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "message contains a newline",
+            ));
+        }
+        writer.write_all(body)?;
+        writer.write_all(b"\n")
+    }
+}
+
+/// `Content-Length` header block, blank line, body
+pub struct HeaderFramed;
+
+impl HeaderFramed {
+    /// `Content-Length` from a header block, or why there is none
+    fn content_length(reader: &mut dyn BufRead) -> io::Result<Option<usize>> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let mut length = None;
+        let mut started = false;
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                return match started {
+                    false => Ok(None),
+                    true => Err(io::ErrorKind::UnexpectedEof.into()),
+                };
+            }
+            let header = String::from_utf8_lossy(&line);
+            let header = header.trim();
+            if header.is_empty() {
+                // Blank lines between messages are not a header block
+                if !started {
+                    continue;
+                }
+                break;
+            }
+            started = true;
+            let Some((name, value)) = header.split_once(':') else {
+                return Err(invalid(format!("malformed header: {}", header)));
+            };
+            if name.trim().eq_ignore_ascii_case(CONTENT_LENGTH) {
+                let value = value.trim();
+                length = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| invalid(format!("bad Content-Length: {}", value)))?,
+                );
+            }
+        }
+        length
+            .map(Some)
+            .ok_or_else(|| invalid("header block without Content-Length".to_string()))
+    }
+}
+
+impl Codec for HeaderFramed {
+    fn read_message(&self, reader: &mut dyn BufRead, limit: usize) -> io::Result<Option<Frame>> {
+        let Some(length) = Self::content_length(reader)? else {
+            return Ok(None);
+        };
+        let mut body = vec![0; length.min(limit)];
+        reader.read_exact(&mut body)?;
+        if length <= limit {
+            return Ok(Some(Frame::Message(body)));
+        }
+        let mut rest = length - limit;
+        while rest > 0 {
+            let available = reader.fill_buf()?;
+            if available.is_empty() {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let skipped = available.len().min(rest);
+            reader.consume(skipped);
+            rest -= skipped;
+        }
+        Ok(Some(Frame::Oversized(body)))
+    }
+
+    fn write_message(&self, writer: &mut dyn Write, body: &[u8]) -> io::Result<()> {
+        write!(writer, "Content-Length: {}\r\n\r\n", body.len())?;
+        writer.write_all(body)
+    }
+}
+
+/// Framing of one backend process, shared by its reader and writer
+///
+/// Starts as the setting; `Auto` is replaced by the detected framing once
+/// the reader has seen the first bytes.
+#[derive(Debug)]
+pub struct ActiveFraming(AtomicU8);
+
+impl ActiveFraming {
+    pub fn new(framing: Framing) -> Self {
+        Self(AtomicU8::new(framing as u8))
+    }
+
+    pub fn get(&self) -> Framing {
+        let value = self.0.load(Ordering::Acquire);
+        Framing::ALL
+            .into_iter()
+            .find(|f| *f as u8 == value)
+            .unwrap_or_default()
+    }
+
+    /// Decide `Auto` from the first non-whitespace byte of `reader`
+    /// (leading whitespace is consumed); end of stream leaves it undecided
+    pub fn resolve(&self, reader: &mut dyn BufRead) -> io::Result<Framing> {
+        let current = self.get();
+        if current != Framing::Auto {
+            return Ok(current);
+        }
+        loop {
+            let buf = reader.fill_buf()?;
+            if buf.is_empty() {
+                return Ok(Framing::Auto);
+            }
+            match buf.iter().position(|b| !b.is_ascii_whitespace()) {
+                Some(at) => {
+                    let detected = Framing::detect(buf[at]);
+                    reader.consume(at);
+                    self.0.store(detected as u8, Ordering::Release);
+                    return Ok(detected);
+                }
+                None => {
+                    let len = buf.len();
+                    reader.consume(len);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn round_trip(framing: Framing, bodies: &[&str]) -> Vec<String> {
+        let mut wire = Vec::new();
+        for body in bodies {
+            framing
+                .codec()
+                .write_message(&mut wire, body.as_bytes())
+                .unwrap();
+        }
+        let active = ActiveFraming::new(Framing::Auto);
+        let mut reader = Cursor::new(wire);
+        let detected = active.resolve(&mut reader).unwrap();
+        assert_eq!(detected, framing);
+        assert_eq!(active.get(), framing);
+
+        let mut out = Vec::new();
+        while let Some(frame) = detected.codec().read_message(&mut reader, 1024).unwrap() {
+            let Frame::Message(body) = frame else {
+                panic!("{:?} over the limit", frame);
+            };
+            out.push(String::from_utf8(body).unwrap());
+        }
+        out
+    }
+
+    #[test]
+    fn test_round_trip_both_framings() {
+        let bodies = [
+            r#"{"jsonrpc":"2.0","id":1,"result":{}}"#,
+            r#"{"jsonrpc":"2.0","method":"notifications/progress"}"#,
+        ];
+        assert_eq!(round_trip(Framing::Newline, &bodies), bodies);
+        assert_eq!(round_trip(Framing::ContentLength, &bodies), bodies);
+
+        // Pretty-printed bodies only survive header framing
+        let pretty = "{\n  \"jsonrpc\": \"2.0\",\n  \"id\": 7,\n  \"result\": \"a\\nb\"\n}";
+        assert_eq!(
+            round_trip(Framing::ContentLength, &[pretty, "{}"]),
+            [pretty, "{}"]
+        );
+        let err = NewlineDelimited
+            .write_message(&mut Vec::new(), pretty.as_bytes())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_header_parsing() {
+        let wire = "\r\ncontent-type: application/json\r\nCONTENT-LENGTH:  2\r\n\r\n{}\
+                    Content-Length: x\r\n\r\n\
+                    Content-Type: a\r\n\r\n\
+                    Content-Length: 5\r\n\r\n{}";
+        let mut reader = Cursor::new(wire.as_bytes());
+        let codec = HeaderFramed;
+        assert_eq!(
+            codec.read_message(&mut reader, 1024).unwrap(),
+            Some(Frame::Message(b"{}".to_vec()))
+        );
+        for _ in 0..2 {
+            let err = codec.read_message(&mut reader, 1024).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+        // Truncated body
+        let err = codec.read_message(&mut reader, 1024).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let active = ActiveFraming::new(Framing::Auto);
+        assert_eq!(
+            active.resolve(&mut Cursor::new(b"  \n".to_vec())).unwrap(),
+            Framing::Auto
+        );
+        let fixed = ActiveFraming::new(Framing::ContentLength);
+        assert_eq!(
+            fixed.resolve(&mut Cursor::new(b"{}".to_vec())).unwrap(),
+            Framing::ContentLength
+        );
+    }
+
+    #[test]
+    fn test_oversized_messages_are_skipped() {
+        let big = format!(
+            r#"{{"jsonrpc":"2.0","id":3,"result":"{}"}}"#,
+            "x".repeat(100)
+        );
+        for framing in [Framing::Newline, Framing::ContentLength] {
+            let mut wire = Vec::new();
+            for body in [big.as_str(), "{}"] {
+                framing
+                    .codec()
+                    .write_message(&mut wire, body.as_bytes())
+                    .unwrap();
+            }
+            let mut reader = Cursor::new(wire);
+            let codec = framing.codec();
+            assert_eq!(
+                codec.read_message(&mut reader, 32).unwrap(),
+                Some(Frame::Oversized(big.as_bytes()[..32].to_vec())),
+                "{}",
+                framing
+            );
+            // The stream picks up at the next message
+            assert_eq!(
+                codec.read_message(&mut reader, 32).unwrap(),
+                Some(Frame::Message(b"{}".to_vec()))
+            );
+            assert_eq!(codec.read_message(&mut reader, 32).unwrap(), None);
+        }
+    }
+}
@@ -0,0 +1,530 @@
+//! apply_task installation modes
+//!
+//! Decides how the backend is started: scripts of a source checkout (with
+//! `PYTHONPATH` at the root), an installed package (`python -m apply_task`),
+//! a console script on PATH, or the bare module as a last resort. With the
+//! `container` setting none of these is tried: the backend runs in a
+//! container (`docker run -i --rm`) from an image whose entry point is
+//! apply_task, with the project mounted read-write at [`CONTAINER_WORKSPACE`]
+//! as its working directory. With `ssh` the backend runs on another host
+//! through `ssh -T`, in the configured remote project directory.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+
+/// Prints the location of an importable `apply_task` module
+pub const IMPORT_PROBE: &str = "import apply_task, sys; print(apply_task.__file__)";
+/// Container CLI unless `container.program` names another
+pub const DOCKER: &str = "docker";
+/// Mount point and working directory of the project in the container
+pub const CONTAINER_WORKSPACE: &str = "/workspace";
+/// SSH client unless `ssh.program` names another
+pub const SSH: &str = "ssh";
+/// Remote backend command unless `ssh.command` names another
+pub const SSH_COMMAND: &str = "apply_task mcp";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallMode {
+    /// `APPLY_TASK_PATH` names the entry script
+    EnvPath,
+    /// `apply_task` or `tasks.py` in the apply_task root
+    Checkout,
+    /// Importable by the interpreter (pip/uv install)
+    Package,
+    /// `apply_task` found on PATH (e.g. `uv tool install`)
+    ConsoleScript,
+    /// `python -m core.desktop.devtools.interface.mcp_server` with `PYTHONPATH`
+    Module,
+    /// `docker run` of the `container` image
+    Container,
+    /// `ssh -T` to the `ssh` host
+    Ssh,
+}
+
+/// `container` setting
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContainerConfig {
+    /// Image whose entry point is apply_task (`apply-task:latest`)
+    pub image: String,
+    /// Further `-v` mounts (`host:container[:ro]`)
+    pub mounts: Vec<String>,
+    /// Further `docker run` arguments, placed before the image
+    pub args: Vec<String>,
+    /// Container CLI (default `docker`; `podman` takes the same arguments)
+    pub program: Option<String>,
+}
+
+impl ContainerConfig {
+    pub fn program(&self) -> &str {
+        self.program
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .unwrap_or(DOCKER)
+    }
+}
+
+/// `ssh` setting
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SshConfig {
+    pub host: String,
+    pub user: Option<String>,
+    /// Project directory on the host (the backend's working directory)
+    pub path: String,
+    /// Backend command on the host (default `apply_task mcp`)
+    pub command: Option<String>,
+    /// Further ssh arguments (`-p 2222`, `-i ~/.ssh/key`), before the host
+    pub args: Vec<String>,
+    /// SSH client (default `ssh`)
+    pub program: Option<String>,
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+impl SshConfig {
+    pub fn program(&self) -> &str {
+        non_empty(self.program.as_deref()).unwrap_or(SSH)
+    }
+
+    /// `user@host:path`
+    pub fn endpoint(&self) -> String {
+        let user = non_empty(self.user.as_deref())
+            .map(|u| format!("{}@", u))
+            .unwrap_or_default();
+        format!("{}{}:{}", user, self.host.trim(), self.path.trim())
+    }
+}
+
+/// Why an SSH backend didn't start
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SshProblem {
+    /// Unknown or changed host key
+    HostKey,
+    /// No key or password accepted
+    Auth,
+    /// Host unreachable
+    Connect,
+}
+
+/// Why a backend container didn't start
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerProblem {
+    /// The daemon is not running or not reachable
+    Daemon,
+    /// The image is neither local nor pullable
+    Image,
+}
+
+/// Resolved backend command line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Launch {
+    pub mode: InstallMode,
+    /// Interpreter, or the console script itself
+    pub program: String,
+    /// Arguments before the storage flag
+    pub args: Vec<String>,
+    /// `PYTHONPATH` is set to the apply_task root
+    pub pythonpath: bool,
+}
+
+/// File of the `apply_task` module `python` imports, if any
+///
+/// Runs outside any project so a checkout in the working directory doesn't
+/// count as an installation.
+pub fn package_file(python: &str) -> Option<PathBuf> {
+    let output = Command::new(python)
+        .args(["-c", IMPORT_PROBE])
+        .current_dir(std::env::temp_dir())
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// Why `python` can't be run, when it names a file (a bare command name is
+/// left to `PATH`)
+pub fn interpreter_problem(python: &str) -> Option<&'static str> {
+    if !python.contains(['/', '\\']) {
+        return None;
+    }
+    let path = Path::new(python);
+    if !path.exists() {
+        return Some("does not exist");
+    }
+    if !path.is_file() {
+        return Some("is not a file");
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let executable = path
+            .metadata()
+            .is_ok_and(|m| m.permissions().mode() & 0o111 != 0);
+        if !executable {
+            return Some("is not executable");
+        }
+    }
+    None
+}
+
+/// What the container CLI's `stderr` says went wrong, if it is one of the
+/// startup failures with their own advice
+pub fn container_problem(stderr: &str) -> Option<ContainerProblem> {
+    let text = stderr.to_lowercase();
+    let daemon = [
+        "cannot connect to the docker daemon",
+        "is the docker daemon running",
+        "error during connect",
+        "permission denied while trying to connect to the docker daemon",
+    ];
+    let image = [
+        "pull access denied",
+        "repository does not exist",
+        "manifest unknown",
+        "no such image",
+    ];
+    if daemon.iter().any(|p| text.contains(p)) {
+        return Some(ContainerProblem::Daemon);
+    }
+    let missing = image.iter().any(|p| text.contains(p))
+        || (text.contains("unable to find image") && text.contains("not found"));
+    missing.then_some(ContainerProblem::Image)
+}
+
+/// `docker run` of `config` for the project at `project`; the caller adds
+/// `--name` after `run`
+pub fn container(config: &ContainerConfig, project: &Path) -> Launch {
+    let mut args: Vec<String> = ["run", "-i", "--rm", "-v"].map(String::from).to_vec();
+    args.push(format!("{}:{}", project.display(), CONTAINER_WORKSPACE));
+    args.extend(["-w".to_string(), CONTAINER_WORKSPACE.to_string()]);
+    for mount in config.mounts.iter().filter(|m| !m.trim().is_empty()) {
+        args.extend(["-v".to_string(), mount.trim().to_string()]);
+    }
+    args.extend(config.args.iter().cloned());
+    args.extend([config.image.trim().to_string(), "mcp".to_string()]);
+    Launch {
+        mode: InstallMode::Container,
+        program: config.program().to_string(),
+        args,
+        pythonpath: false,
+    }
+}
+
+/// What the SSH client's `stderr` says went wrong, if it failed to
+/// connect or log in
+pub fn ssh_problem(stderr: &str) -> Option<SshProblem> {
+    let text = stderr.to_lowercase();
+    let host_key = [
+        "host key verification failed",
+        "remote host identification has changed",
+    ];
+    let auth = [
+        "permission denied (",
+        "too many authentication failures",
+        "no more authentication methods",
+    ];
+    let connect = [
+        "could not resolve hostname",
+        "connection refused",
+        "connection timed out",
+        "operation timed out",
+        "no route to host",
+        "network is unreachable",
+    ];
+    let found = |patterns: &[&str]| patterns.iter().any(|p| text.contains(p));
+    if found(&host_key) {
+        Some(SshProblem::HostKey)
+    } else if found(&auth) {
+        Some(SshProblem::Auth)
+    } else if found(&connect) {
+        Some(SshProblem::Connect)
+    } else {
+        None
+    }
+}
+
+/// `path` quoted for the remote shell, leaving a leading `~/` outside the
+/// quotes so it still expands to the remote home
+fn remote_path(path: &str) -> String {
+    let quote = |text: &str| format!("'{}'", text.replace('\'', "'\\''"));
+    match path.trim() {
+        "~" => "~".to_string(),
+        path => match path.strip_prefix("~/") {
+            Some(rest) => format!("~/{}", quote(rest)),
+            None => quote(path),
+        },
+    }
+}
+
+/// `ssh -T` running the backend command in the remote project directory
+///
+/// Batch mode: a password or host key prompt fails instead of waiting for
+/// a terminal that isn't there. `--` ends the options, so the host can't
+/// be read as one.
+pub fn ssh(config: &SshConfig) -> Launch {
+    let mut args: Vec<String> = ["-T", "-o", "BatchMode=yes"].map(String::from).to_vec();
+    args.extend(config.args.iter().cloned());
+    if let Some(user) = non_empty(config.user.as_deref()) {
+        args.extend(["-l".to_string(), user.to_string()]);
+    }
+    let command = non_empty(config.command.as_deref()).unwrap_or(SSH_COMMAND);
+    args.extend([
+        "--".to_string(),
+        config.host.trim().to_string(),
+        format!("cd {} && exec {}", remote_path(&config.path), command),
+    ]);
+    Launch {
+        mode: InstallMode::Ssh,
+        program: config.program().to_string(),
+        args,
+        pythonpath: false,
+    }
+}
+
+fn console_script() -> Option<String> {
+    let output = Command::new("which").arg("apply_task").output().ok()?;
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !path.is_empty()).then_some(path)
+}
+
+/// Entry point for `root` with `python`; `env_path` is `APPLY_TASK_PATH`
+pub fn resolve(root: &Path, python: &str, env_path: Option<&Path>) -> Launch {
+    let script = |mode, script: &Path| Launch {
+        mode,
+        program: python.to_string(),
+        args: vec![script.to_string_lossy().to_string(), "mcp".to_string()],
+        pythonpath: true,
+    };
+
+    if let Some(path) = env_path.filter(|p| p.is_file()) {
+        return script(InstallMode::EnvPath, path);
+    }
+
+    // Prefer local repo entry points (keeps GUI in lockstep with bundled code)
+    for name in ["apply_task", "tasks.py"] {
+        let path = root.join(name);
+        if path.exists() {
+            return script(InstallMode::Checkout, &path);
+        }
+    }
+
+    if package_file(python).is_some() {
+        return Launch {
+            mode: InstallMode::Package,
+            program: python.to_string(),
+            args: ["-m", "apply_task", "mcp"].map(String::from).to_vec(),
+            pythonpath: false,
+        };
+    }
+
+    // Installed for another interpreter: the script's shebang knows which
+    if let Some(path) = console_script() {
+        return Launch {
+            mode: InstallMode::ConsoleScript,
+            program: path,
+            args: vec!["mcp".to_string()],
+            pythonpath: false,
+        };
+    }
+
+    Launch {
+        mode: InstallMode::Module,
+        program: python.to_string(),
+        args: ["-m", "core.desktop.devtools.interface.mcp_server"]
+            .map(String::from)
+            .to_vec(),
+        pythonpath: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Executable standing in for the interpreter: prints `stdout`, exits `code`
+    #[cfg(unix)]
+    fn stub_python(dir: &Path, stdout: &str, code: i32) -> String {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join(format!("python-stub-{}", code));
+        std::fs::write(
+            &path,
+            format!("#!/bin/sh\necho '{}'\nexit {}\n", stdout, code),
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_modes() {
+        let dir = std::env::temp_dir().join(format!("apply-task-launch-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("checkout")).unwrap();
+        std::fs::create_dir_all(dir.join("empty")).unwrap();
+        std::fs::write(dir.join("checkout/tasks.py"), "").unwrap();
+        let installed = stub_python(&dir, "/site-packages/apply_task.py", 0);
+        let missing = stub_python(&dir, "", 1);
+
+        assert_eq!(
+            package_file(&installed),
+            Some(PathBuf::from("/site-packages/apply_task.py"))
+        );
+        assert_eq!(package_file(&missing), None);
+
+        // A checkout wins over an installed package
+        let launch = resolve(&dir.join("checkout"), &installed, None);
+        assert_eq!(launch.mode, InstallMode::Checkout);
+        assert!(launch.pythonpath && launch.args[0].ends_with("tasks.py"));
+
+        let launch = resolve(&dir.join("empty"), &installed, None);
+        assert_eq!(launch.mode, InstallMode::Package);
+        assert_eq!(launch.program, installed);
+        assert_eq!(launch.args, ["-m", "apply_task", "mcp"]);
+        assert!(!launch.pythonpath);
+
+        // Not importable: a console script on PATH or the module fallback
+        let launch = resolve(&dir.join("empty"), &missing, None);
+        assert!(matches!(
+            launch.mode,
+            InstallMode::ConsoleScript | InstallMode::Module
+        ));
+
+        let env_path = dir.join("checkout/tasks.py");
+        let launch = resolve(&dir.join("empty"), &missing, Some(&env_path));
+        assert_eq!(launch.mode, InstallMode::EnvPath);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_container_launch_and_problems() {
+        let config = ContainerConfig {
+            image: " apply-task:latest ".into(),
+            mounts: vec!["/home/ada/.tasks:/root/.tasks".into(), " ".into()],
+            args: vec!["--network=none".into()],
+            program: None,
+        };
+        let launch = container(&config, Path::new("/home/ada/project"));
+        assert_eq!(launch.mode, InstallMode::Container);
+        assert_eq!(launch.program, "docker");
+        assert_eq!(
+            launch.args,
+            [
+                "run",
+                "-i",
+                "--rm",
+                "-v",
+                "/home/ada/project:/workspace",
+                "-w",
+                "/workspace",
+                "-v",
+                "/home/ada/.tasks:/root/.tasks",
+                "--network=none",
+                "apply-task:latest",
+                "mcp"
+            ]
+        );
+        assert!(!launch.pythonpath);
+        let podman = ContainerConfig {
+            program: Some("podman".into()),
+            ..config
+        };
+        assert_eq!(container(&podman, Path::new("/p")).program, "podman");
+
+        assert_eq!(
+            container_problem(
+                "docker: Cannot connect to the Docker daemon at unix:///var/run/docker.sock. Is the docker daemon running?"
+            ),
+            Some(ContainerProblem::Daemon)
+        );
+        assert_eq!(
+            container_problem(
+                "Unable to find image 'apply-task:latest' locally\ndocker: Error response from daemon: pull access denied for apply-task, repository does not exist"
+            ),
+            Some(ContainerProblem::Image)
+        );
+        assert_eq!(
+            container_problem("Traceback (most recent call last):"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ssh_launch_and_problems() {
+        let config = SshConfig {
+            host: "dev.example.com".into(),
+            user: Some("ada".into()),
+            path: "/srv/ada's tasks".into(),
+            command: None,
+            args: vec!["-p".into(), "2222".into()],
+            program: None,
+        };
+        assert_eq!(config.endpoint(), "ada@dev.example.com:/srv/ada's tasks");
+        let launch = ssh(&config);
+        assert_eq!(launch.mode, InstallMode::Ssh);
+        assert_eq!(launch.program, "ssh");
+        assert_eq!(
+            launch.args,
+            [
+                "-T",
+                "-o",
+                "BatchMode=yes",
+                "-p",
+                "2222",
+                "-l",
+                "ada",
+                "--",
+                "dev.example.com",
+                "cd '/srv/ada'\\''s tasks' && exec apply_task mcp"
+            ]
+        );
+        let custom = SshConfig {
+            user: Some(" ".into()),
+            command: Some("~/.local/bin/apply_task mcp".into()),
+            ..config
+        };
+        assert!(custom.endpoint().starts_with("dev.example.com:"));
+        let home = SshConfig {
+            path: " ~/work/ada's proj ".into(),
+            ..custom.clone()
+        };
+        assert!(ssh(&home)
+            .args
+            .last()
+            .unwrap()
+            .starts_with("cd ~/'work/ada'\\''s proj' && exec "));
+        assert_eq!(remote_path("~"), "~");
+        assert_eq!(remote_path("~ada/x"), "'~ada/x'");
+        assert!(ssh(&custom)
+            .args
+            .last()
+            .unwrap()
+            .ends_with("exec ~/.local/bin/apply_task mcp"));
+
+        assert_eq!(
+            ssh_problem(
+                "Host key verification failed.
+"
+            ),
+            Some(SshProblem::HostKey)
+        );
+        assert_eq!(
+            ssh_problem("ada@dev.example.com: Permission denied (publickey,password)."),
+            Some(SshProblem::Auth)
+        );
+        assert_eq!(
+            ssh_problem("ssh: Could not resolve hostname dev: Name or service not known"),
+            Some(SshProblem::Connect)
+        );
+        assert_eq!(ssh_problem("bash: apply_task: command not found"), None);
+    }
+}
@@ -2,7 +2,28 @@
 //!
 //! Manages communication with Python backend via JSON-RPC 2.0 over stdio.
 
+#[doc(hidden)]
+pub mod bench;
 mod bridge;
+mod child_env;
+mod coalesce;
+mod content;
+mod error;
+#[cfg(test)]
+pub(crate) mod fake_server;
+mod faults;
+mod framing;
+mod launch;
 mod protocol;
+mod wire_log;
 
-pub use bridge::PythonBridge;
+pub use bridge::{
+    default_python_path, BridgeMetrics, BridgeStatus, PythonBridge, DEFAULT_MAX_MESSAGE_BYTES,
+};
+pub use child_env::{ChildEnv, EnvMode, EnvPolicy};
+pub use error::{BridgeError, ToolCallError};
+pub use faults::{FaultError, FaultRule};
+pub use framing::Framing;
+pub use launch::{
+    interpreter_problem, package_file, ContainerConfig, InstallMode, SshConfig, IMPORT_PROBE,
+};
@@ -1,31 +1,144 @@
 //! JSON-RPC 2.0 protocol types and serialization
+//!
+//! Every line on the bridge's stdio is one [`JsonRpcMessage`]: a request
+//! (`id` and `method`), a notification (`method` only) or a response (`id`
+//! with `result` or `error`). Unknown fields are ignored when reading, and
+//! `jsonrpc` is always written as `"2.0"`.
+//!
+//! [`parse_line`] reads one line. Unless `strict`, it also accepts a missing
+//! or wrong `jsonrpc` and numeric ids sent as strings. A line it can't read
+//! is a [`ProtocolError`] with an excerpt of the line, and the id of the
+//! response it was meant to be (when that much is readable). A message too
+//! large to read whole is matched to its request by [`truncated_id`].
 
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 
-/// JSON-RPC 2.0 request
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct JsonRpcRequest {
-    pub jsonrpc: String,
-    pub id: u64,
-    pub method: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub params: Option<Value>,
+pub const JSONRPC_VERSION: &str = "2.0";
+/// MCP revision sent in `initialize`
+pub const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+/// JSON-RPC code for an unreadable message
+pub const PARSE_ERROR: i32 = -32700;
+/// Code the reader puts on a response it skipped for its size (never sent
+/// by the backend; `data.limit` is the limit in bytes)
+pub const RESPONSE_TOO_LARGE: i32 = -32099;
+/// Code the bridge puts on requests in flight when it stops a backend over
+/// its memory cap (never sent by the backend; `data` has `rss_mb`, `cap_mb`)
+pub const RESOURCE_LIMIT: i32 = -32098;
+/// Characters of an unreadable line kept in a [`ProtocolError`]
+const EXCERPT_CHARS: usize = 200;
+
+/// A line that is not a usable JSON-RPC message
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("{reason}: {excerpt}")]
+pub struct ProtocolError {
+    /// Response id, if readable (its waiter can be failed right away)
+    pub id: Option<u64>,
+    pub reason: String,
+    /// Start of the line (`EXCERPT_CHARS`, then `…`)
+    pub excerpt: String,
 }
 
-impl JsonRpcRequest {
-    pub fn new(id: u64, method: &str, params: Option<Value>) -> Self {
-        Self {
-            jsonrpc: "2.0".to_string(),
-            id,
-            method: method.to_string(),
-            params,
+fn excerpt(line: &str) -> String {
+    let mut chars = line.chars();
+    let mut out: String = chars.by_ref().take(EXCERPT_CHARS).collect();
+    if chars.next().is_some() {
+        out.push('…');
+    }
+    out
+}
+
+/// Id of a message cut off after `prefix`, from its top-level `"id"` key
+///
+/// `None` when the id isn't within the prefix (or isn't a number).
+pub fn truncated_id(prefix: &[u8]) -> Option<u64> {
+    let mut depth = 0usize;
+    let mut string_start = None;
+    let mut escaped = false;
+    for (at, &b) in prefix.iter().enumerate() {
+        if let Some(start) = string_start {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => {
+                    string_start = None;
+                    if depth == 1 && &prefix[start..at] == b"id" {
+                        if let Some(id) = id_value(&prefix[at + 1..]) {
+                            return Some(id);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b'"' => string_start = Some(at + 1),
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Number after a key's `:` (quoted digits too), if `rest` starts with one
+fn id_value(rest: &[u8]) -> Option<u64> {
+    let rest = String::from_utf8_lossy(&rest[..rest.len().min(32)]);
+    let value = rest.trim_start().strip_prefix(':')?.trim_start();
+    let value = value.strip_prefix('"').unwrap_or(value);
+    let digits = value.len() - value.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    value[..digits].parse().ok()
+}
+
+/// Read one line from the backend; `Ok(None)` for a blank line
+pub fn parse_line(line: &str, strict: bool) -> Result<Option<JsonRpcMessage>, ProtocolError> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    let error = |id: Option<u64>, reason: String| ProtocolError {
+        id,
+        reason,
+        excerpt: excerpt(line),
+    };
+    let mut obj = match serde_json::from_str::<Value>(line) {
+        Ok(Value::Object(obj)) => obj,
+        Ok(_) => return Err(error(None, "not a JSON object".to_string())),
+        Err(e) => return Err(error(None, format!("not JSON ({})", e))),
+    };
+
+    let id = match obj.get("id") {
+        None | Some(Value::Null) => None,
+        Some(Value::Number(n)) => n.as_u64(),
+        Some(Value::String(s)) if !strict => s.trim().parse::<u64>().ok(),
+        Some(_) => None,
+    };
+    match id {
+        Some(id) => {
+            obj.insert("id".to_string(), id.into());
+        }
+        None if obj.get("id").is_some_and(|id| !id.is_null()) => {
+            return Err(error(None, format!("unsupported id {}", obj["id"])));
         }
+        None => {}
     }
+    // An id on something with a method is the backend's own request
+    let response_id = id.filter(|_| !obj.contains_key("method"));
+
+    if strict && obj.get("jsonrpc").and_then(|v| v.as_str()) != Some(JSONRPC_VERSION) {
+        return Err(error(response_id, "jsonrpc must be \"2.0\"".to_string()));
+    }
+    if !strict {
+        obj.remove("jsonrpc");
+    }
+    serde_json::from_value(Value::Object(obj))
+        .map(Some)
+        .map_err(|e| error(response_id, e.to_string()))
 }
 
 /// JSON-RPC 2.0 error object
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct JsonRpcError {
     pub code: i32,
     pub message: String,
@@ -34,53 +147,508 @@ pub struct JsonRpcError {
 }
 
 /// JSON-RPC 2.0 response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct JsonRpcResponse {
-    pub jsonrpc: String,
     pub id: u64,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub result: Option<Value>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<JsonRpcError>,
+    pub result: Result<Value, JsonRpcError>,
+}
+
+/// Any message on the bridge's stdio
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawMessage", into = "RawMessage")]
+pub enum JsonRpcMessage {
+    Request {
+        id: u64,
+        method: String,
+        params: Option<Value>,
+    },
+    Notification {
+        method: String,
+        params: Option<Value>,
+    },
+    Response(JsonRpcResponse),
+}
+
+/// Wire shape shared by all three kinds
+#[derive(Serialize, Deserialize)]
+struct RawMessage {
+    #[serde(default)]
+    jsonrpc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    method: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    params: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<JsonRpcError>,
+}
+
+impl TryFrom<RawMessage> for JsonRpcMessage {
+    type Error = String;
+
+    fn try_from(raw: RawMessage) -> Result<Self, String> {
+        match (raw.id, raw.method) {
+            (Some(id), Some(method)) => Ok(Self::Request {
+                id,
+                method,
+                params: raw.params,
+            }),
+            (None, Some(method)) => Ok(Self::Notification {
+                method,
+                params: raw.params,
+            }),
+            (Some(id), None) => Ok(Self::Response(JsonRpcResponse {
+                id,
+                result: match raw.error {
+                    Some(error) => Err(error),
+                    // `"result": null` reads as a missing result
+                    None => Ok(raw.result.unwrap_or(Value::Null)),
+                },
+            })),
+            (None, None) => Err("message has neither id nor method".to_string()),
+        }
+    }
+}
+
+impl From<JsonRpcMessage> for RawMessage {
+    fn from(message: JsonRpcMessage) -> Self {
+        let raw = Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: None,
+            method: None,
+            params: None,
+            result: None,
+            error: None,
+        };
+        match message {
+            JsonRpcMessage::Request { id, method, params } => Self {
+                id: Some(id),
+                method: Some(method),
+                params,
+                ..raw
+            },
+            JsonRpcMessage::Notification { method, params } => Self {
+                method: Some(method),
+                params,
+                ..raw
+            },
+            JsonRpcMessage::Response(response) => match response.result {
+                Ok(result) => Self {
+                    id: Some(response.id),
+                    result: Some(result),
+                    ..raw
+                },
+                Err(error) => Self {
+                    id: Some(response.id),
+                    error: Some(error),
+                    ..raw
+                },
+            },
+        }
+    }
+}
+
+impl JsonRpcMessage {
+    pub fn request(id: u64, method: &str, params: Option<Value>) -> Self {
+        Self::Request {
+            id,
+            method: method.to_string(),
+            params,
+        }
+    }
+
+    pub fn notification(method: &str, params: Option<Value>) -> Self {
+        Self::Notification {
+            method: method.to_string(),
+            params,
+        }
+    }
+
+    /// MCP handshake request
+    pub fn initialize(id: u64) -> Self {
+        Self::request(
+            id,
+            "initialize",
+            Some(json!({
+                "protocolVersion": MCP_PROTOCOL_VERSION,
+                "capabilities": {},
+                "clientInfo": {
+                    "name": "apply-task-gui",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+        )
+    }
+
+    /// Sent once the `initialize` response arrived
+    pub fn initialized() -> Self {
+        Self::notification("notifications/initialized", None)
+    }
+
+    /// `tools/call`; progress notifications carry `progress_token`
+    pub fn tool_call(id: u64, name: &str, arguments: Value, progress_token: Option<&str>) -> Self {
+        let mut params = json!({ "name": name, "arguments": arguments });
+        if let Some(token) = progress_token {
+            params["_meta"] = json!({ "progressToken": token });
+        }
+        Self::request(id, "tools/call", Some(params))
+    }
+
+    /// `notifications/cancelled` for request `request_id`
+    pub fn cancelled(request_id: u64, reason: &str) -> Self {
+        Self::notification(
+            "notifications/cancelled",
+            Some(json!({ "requestId": request_id, "reason": reason })),
+        )
+    }
+
+    /// Method of a request or notification
+    pub fn method(&self) -> Option<&str> {
+        match self {
+            Self::Request { method, .. } | Self::Notification { method, .. } => Some(method),
+            Self::Response(_) => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::json;
+
+    fn round_trip(message: &JsonRpcMessage) -> JsonRpcMessage {
+        let line = serde_json::to_string(message).unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
 
     #[test]
     fn test_request_serialization() {
-        let req = JsonRpcRequest::new(1, "tasks.list", Some(json!({"compact": true})));
-        let json = serde_json::to_string(&req).unwrap();
-        assert!(json.contains("\"jsonrpc\":\"2.0\""));
-        assert!(json.contains("\"method\":\"tasks.list\""));
+        let req = JsonRpcMessage::request(1, "tasks.list", Some(json!({"compact": true})));
+        let value = serde_json::to_value(&req).unwrap();
+        assert_eq!(
+            value,
+            json!({"jsonrpc": "2.0", "id": 1, "method": "tasks.list", "params": {"compact": true}})
+        );
+        assert_eq!(round_trip(&req), req);
+
+        let bare = JsonRpcMessage::request(2, "tools/list", None);
+        assert_eq!(
+            serde_json::to_value(&bare).unwrap(),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"})
+        );
+        assert_eq!(round_trip(&bare), bare);
     }
 
     #[test]
-    fn test_response_success() {
-        let resp = JsonRpcResponse {
-            jsonrpc: "2.0".to_string(),
-            id: 1,
-            result: Some(json!({"tasks": []})),
-            error: None,
-        };
-        assert!(resp.error.is_none());
-        assert!(resp.result.is_some());
+    fn test_notification_serialization() {
+        let cancelled = JsonRpcMessage::cancelled(7, "timeout");
+        assert_eq!(
+            serde_json::to_value(&cancelled).unwrap(),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/cancelled",
+                "params": {"requestId": 7, "reason": "timeout"},
+            })
+        );
+        assert_eq!(round_trip(&cancelled), cancelled);
+        let initialized = JsonRpcMessage::initialized();
+        assert_eq!(initialized.method(), Some("notifications/initialized"));
+        assert_eq!(round_trip(&initialized), initialized);
     }
 
     #[test]
-    fn test_response_error() {
-        let resp = JsonRpcResponse {
-            jsonrpc: "2.0".to_string(),
+    fn test_response_success_and_error() {
+        let ok = JsonRpcMessage::Response(JsonRpcResponse {
             id: 1,
-            result: None,
-            error: Some(JsonRpcError {
+            result: Ok(json!({"tasks": []})),
+        });
+        assert_eq!(
+            serde_json::to_value(&ok).unwrap(),
+            json!({"jsonrpc": "2.0", "id": 1, "result": {"tasks": []}})
+        );
+        assert_eq!(round_trip(&ok), ok);
+
+        let err = JsonRpcMessage::Response(JsonRpcResponse {
+            id: 1,
+            result: Err(JsonRpcError {
                 code: -32603,
                 message: "test error".to_string(),
-                data: None,
+                data: Some(json!({"field": "title"})),
             }),
-        };
-        assert!(resp.error.is_some());
+        });
+        assert_eq!(
+            serde_json::to_value(&err).unwrap()["error"],
+            json!({"code": -32603, "message": "test error", "data": {"field": "title"}})
+        );
+        assert_eq!(round_trip(&err), err);
+
+        let null: JsonRpcMessage =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","id":3,"result":null}"#).unwrap();
+        assert_eq!(
+            null,
+            JsonRpcMessage::Response(JsonRpcResponse {
+                id: 3,
+                result: Ok(Value::Null)
+            })
+        );
+    }
+
+    #[test]
+    fn test_constructors_used_by_the_bridge() {
+        let init = serde_json::to_value(JsonRpcMessage::initialize(1)).unwrap();
+        assert_eq!(init["method"], "initialize");
+        assert_eq!(init["params"]["protocolVersion"], MCP_PROTOCOL_VERSION);
+        assert_eq!(init["params"]["clientInfo"]["name"], "apply-task-gui");
+
+        let call = serde_json::to_value(JsonRpcMessage::tool_call(
+            4,
+            "tasks_list",
+            json!({"compact": true}),
+            Some("job-1"),
+        ))
+        .unwrap();
+        assert_eq!(
+            call["params"],
+            json!({
+                "name": "tasks_list",
+                "arguments": {"compact": true},
+                "_meta": {"progressToken": "job-1"},
+            })
+        );
+        let plain =
+            serde_json::to_value(JsonRpcMessage::tool_call(5, "tasks_list", json!({}), None))
+                .unwrap();
+        assert!(plain["params"].get("_meta").is_none());
+    }
+
+    #[test]
+    fn test_unknown_fields_are_tolerated() {
+        let lines = [
+            (
+                r#"{"jsonrpc":"2.0","id":9,"method":"ping","params":{},"trace":"x"}"#,
+                JsonRpcMessage::request(9, "ping", Some(json!({}))),
+            ),
+            (
+                r#"{"jsonrpc":"2.0","method":"notifications/progress","params":{"progress":1},"_debug":[1]}"#,
+                JsonRpcMessage::notification(
+                    "notifications/progress",
+                    Some(json!({"progress": 1})),
+                ),
+            ),
+            (
+                r#"{"jsonrpc":"2.0","id":2,"result":{"n":2},"elapsed_ms":12}"#,
+                JsonRpcMessage::Response(JsonRpcResponse {
+                    id: 2,
+                    result: Ok(json!({"n": 2})),
+                }),
+            ),
+            (
+                r#"{"id":2,"error":{"code":-32602,"message":"bad","hint":"y"}}"#,
+                JsonRpcMessage::Response(JsonRpcResponse {
+                    id: 2,
+                    result: Err(JsonRpcError {
+                        code: -32602,
+                        message: "bad".into(),
+                        data: None,
+                    }),
+                }),
+            ),
+        ];
+        for (line, expected) in lines {
+            assert_eq!(
+                serde_json::from_str::<JsonRpcMessage>(line).unwrap(),
+                expected,
+                "{}",
+                line
+            );
+        }
+
+        // Neither kind, or an id we never send
+        for line in [
+            r#"{"jsonrpc":"2.0","id":null,"error":{"code":-32700,"message":"parse"}}"#,
+            r#"{"jsonrpc":"2.0"}"#,
+            r#"{"jsonrpc":"2.0","id":"a","result":{}}"#,
+            r#"[1]"#,
+        ] {
+            assert!(
+                serde_json::from_str::<JsonRpcMessage>(line).is_err(),
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_lenient_and_strict_parsing() {
+        let loose = r#"{"id":"4","result":{"ok":true}}"#;
+        let expected = JsonRpcMessage::Response(JsonRpcResponse {
+            id: 4,
+            result: Ok(json!({"ok": true})),
+        });
+        assert_eq!(parse_line(loose, false).unwrap(), Some(expected));
+        assert!(parse_line(loose, true).is_err());
+        assert!(parse_line(r#"{"jsonrpc":2,"method":"ping"}"#, false).is_ok());
+        let err = parse_line(r#"{"id":4,"result":{}}"#, true).unwrap_err();
+        assert_eq!(err.id, Some(4));
+        assert!(err.reason.contains("jsonrpc"));
+        assert_eq!(parse_line("  \r", true), Ok(None));
+
+        // A broken response still names its id; a broken request doesn't
+        let broken = r#"{"jsonrpc":"2.0","id":5,"error":{"code":"x"}}"#;
+        assert_eq!(parse_line(broken, false).unwrap_err().id, Some(5));
+        let request = r#"{"jsonrpc":"2.0","id":5,"method":7}"#;
+        assert_eq!(parse_line(request, false).unwrap_err().id, None);
+        assert!(parse_line(r#"{"id":"abc","result":1}"#, false).is_err());
+        assert!(parse_line(r#"{"id":-1,"result":1}"#, false).is_err());
+
+        let long = format!("Traceback: {}", "x".repeat(500));
+        let err = parse_line(&long, false).unwrap_err();
+        assert!(err.reason.starts_with("not JSON"));
+        assert_eq!(err.excerpt.chars().count(), EXCERPT_CHARS + 1);
+        assert!(err.excerpt.ends_with('…'));
+    }
+
+    #[test]
+    fn test_truncated_id() {
+        let cut = |s: &str| truncated_id(s.as_bytes());
+        assert_eq!(
+            cut(r#"{"jsonrpc":"2.0","id":42,"result":{"tasks":[{"#),
+            Some(42)
+        );
+        assert_eq!(cut(r#"{"id" : "7", "result""#), Some(7));
+        // Nested ids and string values named "id" are not the message id
+        assert_eq!(cut(r#"{"result":{"id":1,"x":"id"},"id":9}"#), Some(9));
+        assert_eq!(cut(r#"{"result":{"id":1,"title":"a \"id\": 3"#), None);
+        assert_eq!(cut(r#"{"method":"id","params""#), None);
+        assert_eq!(cut(r#"{"id":"#), None);
+    }
+
+    /// xorshift64*, seeded so a failure reproduces
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+
+        fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+            &items[self.below(items.len())]
+        }
+    }
+
+    const FIXTURES: [&str; 6] = [
+        r#"{"jsonrpc":"2.0","id":1,"result":{"content":[{"type":"json","json":{"tasks":[]}}]}}"#,
+        r#"{"jsonrpc":"2.0","id":2,"error":{"code":-32602,"message":"bad","data":{"errors":[{"field":"title","reason":"empty"}]}}}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/progress","params":{"progressToken":"job-1","progress":1,"total":3}}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"ping"}"#,
+        r#"{"jsonrpc":"2.0","id":4,"result":null}"#,
+        r#"{"jsonrpc":"2.0","id":"5","result":"ok"}"#,
+    ];
+    const KEYS: [&str; 10] = [
+        "jsonrpc", "id", "method", "params", "result", "error", "code", "message", "data", "extra",
+    ];
+    const NOISE: [char; 16] = [
+        '{', '}', '[', ']', '"', ',', ':', '\\', '\n', ' ', '0', '9', '-', 'e', 'n', 'é',
+    ];
+
+    fn random_value(rng: &mut Rng, depth: usize) -> Value {
+        match rng.below(if depth == 0 { 5 } else { 7 }) {
+            0 => Value::Null,
+            1 => json!(rng.below(2) == 0),
+            2 => json!(rng.next() as i64 >> rng.below(64)),
+            3 => json!(rng.pick(&["", "2.0", "7", "tools/call", "é\n"])),
+            4 => json!(rng.next() as f64 / 3.0),
+            5 => Value::Array(
+                (0..rng.below(4))
+                    .map(|_| random_value(rng, depth - 1))
+                    .collect(),
+            ),
+            _ => Value::Object(
+                (0..rng.below(5))
+                    .map(|_| (rng.pick(&KEYS).to_string(), random_value(rng, depth - 1)))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn mutate(rng: &mut Rng, line: &str) -> String {
+        let mut chars: Vec<char> = line.chars().collect();
+        match rng.below(5) {
+            0 => chars.truncate(rng.below(chars.len() + 1)),
+            1 if !chars.is_empty() => {
+                chars.remove(rng.below(chars.len()));
+            }
+            2 => chars.insert(rng.below(chars.len() + 1), *rng.pick(&NOISE)),
+            3 if !chars.is_empty() => {
+                let at = rng.below(chars.len());
+                chars[at] = *rng.pick(&NOISE);
+            }
+            _ => {
+                // Swap a field for any value, or drop it
+                let mut value: Value = serde_json::from_str(line).unwrap_or(json!({}));
+                if let Some(obj) = value.as_object_mut() {
+                    let key = rng.pick(&KEYS).to_string();
+                    match rng.below(3) {
+                        0 => {
+                            obj.remove(&key);
+                        }
+                        _ => {
+                            obj.insert(key, random_value(rng, 3));
+                        }
+                    }
+                }
+                return value.to_string();
+            }
+        }
+        chars.into_iter().collect()
+    }
+
+    /// Every line is a message, blank, or an error with a bounded excerpt;
+    /// whatever is accepted writes back as strict JSON-RPC
+    fn assert_classified(line: &str) {
+        for strict in [true, false] {
+            match parse_line(line, strict) {
+                Ok(None) => assert!(line.trim().is_empty(), "{:?}", line),
+                Ok(Some(message)) => {
+                    let written = serde_json::to_string(&message).unwrap();
+                    assert_eq!(parse_line(&written, true), Ok(Some(message)), "{:?}", line);
+                }
+                Err(e) => {
+                    assert!(!e.reason.is_empty());
+                    assert!(e.excerpt.chars().count() <= EXCERPT_CHARS + 1);
+                }
+            }
+        }
+        if let Ok(message) = parse_line(line, true) {
+            assert_eq!(parse_line(line, false), Ok(message), "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_parser_classifies_mutated_and_random_input() {
+        let mut rng = Rng(0x5eed_1234_abcd_0001);
+        for fixture in FIXTURES {
+            assert!(parse_line(fixture, false).unwrap().is_some(), "{}", fixture);
+            let mut line = fixture.to_string();
+            for _ in 0..400 {
+                line = mutate(&mut rng, &line);
+                assert_classified(&line);
+                if rng.below(8) == 0 {
+                    line = fixture.to_string();
+                }
+            }
+        }
+        for _ in 0..2000 {
+            assert_classified(&random_value(&mut rng, 4).to_string());
+        }
     }
 }
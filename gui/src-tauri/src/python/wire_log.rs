@@ -0,0 +1,178 @@
+//! Recent JSON-RPC traffic, for support bundles
+//!
+//! The bridge keeps the last [`CAPACITY`] messages it wrote or read, in
+//! outline: ids, methods, params and errors are kept, but task bodies
+//! (descriptions, notes, attachments, ...) are dropped from tool arguments,
+//! and a result is reduced to its content part types or top-level keys.
+//! Secrets and the home directory are redacted when a bundle is written.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// Messages kept
+pub const CAPACITY: usize = 200;
+/// Tool argument keys whose values are task content
+const BODY_KEYS: [&str; 9] = [
+    "description",
+    "notes",
+    "note",
+    "contract",
+    "contract_data",
+    "attachments",
+    "content",
+    "text",
+    "message",
+];
+/// Stands in for a dropped value
+pub const OMITTED: &str = "[omitted]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WireEntry {
+    /// RFC 3339, UTC
+    pub at: String,
+    pub direction: Direction,
+    /// Size of the message as framed on the wire (body only)
+    pub bytes: usize,
+    pub message: Value,
+}
+
+/// The last [`CAPACITY`] messages, shared by the bridge's clones
+#[derive(Debug, Default)]
+pub struct WireLog {
+    entries: Mutex<VecDeque<WireEntry>>,
+}
+
+impl WireLog {
+    pub fn record(&self, direction: Direction, raw: &[u8]) {
+        let entry = WireEntry {
+            at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            direction,
+            bytes: raw.len(),
+            message: outline(raw),
+        };
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() == CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Oldest first
+    pub fn entries(&self) -> Vec<WireEntry> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+fn strip_bodies(value: &mut Value) {
+    match value {
+        Value::Object(obj) => {
+            for (key, value) in obj.iter_mut() {
+                match BODY_KEYS.contains(&key.as_str()) {
+                    true => *value = json!(OMITTED),
+                    false => strip_bodies(value),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_bodies),
+        _ => {}
+    }
+}
+
+/// Content part types and `isError` of a tool result, or the keys of any
+/// other result
+fn outline_result(result: &Value) -> Value {
+    match result.get("content").and_then(|c| c.as_array()) {
+        Some(parts) => json!({
+            "content": parts.iter().map(|p| p["type"].clone()).collect::<Vec<_>>(),
+            "isError": result.get("isError").cloned().unwrap_or(Value::Null),
+        }),
+        None => match result {
+            Value::Object(obj) => json!({ "keys": obj.keys().collect::<Vec<_>>() }),
+            _ => json!(OMITTED),
+        },
+    }
+}
+
+fn outline(raw: &[u8]) -> Value {
+    let Ok(Value::Object(message)) = serde_json::from_slice::<Value>(raw) else {
+        return json!({ "unparsed": true });
+    };
+    let mut out = Map::new();
+    for (key, value) in message {
+        let value = match key.as_str() {
+            "result" => outline_result(&value),
+            "params" => {
+                let mut params = value;
+                if let Some(arguments) = params.get_mut("arguments") {
+                    strip_bodies(arguments);
+                }
+                params
+            }
+            _ => value,
+        };
+        out.insert(key, value);
+    }
+    Value::Object(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outline_and_capacity() {
+        let log = WireLog::default();
+        let call = json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "tools/call",
+            "params": { "name": "tasks_edit", "arguments": {
+                "task": "TASK-1",
+                "changes": { "title": "Ship", "description": "secret plans" },
+                "attachments": [{ "path": "a.png" }],
+            } },
+        });
+        log.record(Direction::Sent, call.to_string().as_bytes());
+        let answer = json!({ "jsonrpc": "2.0", "id": 4, "result": {
+            "content": [{ "type": "json", "json": { "task": { "notes": "x" } } }],
+            "isError": false,
+        } });
+        log.record(Direction::Received, answer.to_string().as_bytes());
+        log.record(Direction::Received, b"Traceback");
+
+        let entries = log.entries();
+        let arguments = &entries[0].message["params"]["arguments"];
+        assert_eq!(arguments["task"], "TASK-1");
+        assert_eq!(arguments["changes"]["title"], "Ship");
+        assert_eq!(arguments["changes"]["description"], OMITTED);
+        assert_eq!(arguments["attachments"], OMITTED);
+        assert_eq!(
+            entries[1].message,
+            json!({ "jsonrpc": "2.0", "id": 4, "result": { "content": ["json"], "isError": false } })
+        );
+        assert_eq!(entries[2].message, json!({ "unparsed": true }));
+        assert_eq!(entries[2].bytes, 9);
+
+        for _ in 0..CAPACITY {
+            log.record(Direction::Sent, b"{}");
+        }
+        let entries = log.entries();
+        assert_eq!(entries.len(), CAPACITY);
+        assert!(entries.iter().all(|e| e.message == json!({})));
+    }
+}
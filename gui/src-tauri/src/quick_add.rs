@@ -0,0 +1,262 @@
+//! Quick task capture
+//!
+//! A global shortcut (`quick_add_shortcut`, default `CmdOrCtrl+Shift+T`)
+//! toggles a small always-on-top window whose single line becomes a task
+//! without the main window showing up. The line is parsed by
+//! [`quick_parse`](crate::quick_parse): `Fix login redirect #auth !high
+//! @backend` is the title plus tags, priority and domain. The backend's
+//! `tasks_create` takes title and priority; tags and domain follow with
+//! `tasks_edit`, and a `due:` date is stored like `tasks_set_due` does.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::audit::{self, Audit};
+use crate::backend;
+use crate::commands;
+use crate::error_catalog::{CatalogError, ErrorCode, ResponseError};
+use crate::journal::Mutation;
+use crate::mutation_queue;
+use crate::quick_parse::ParsedTask;
+use crate::read_only::{self, ReadOnlyError};
+use crate::settings::Settings;
+use crate::AppState;
+
+/// Label (and route) of the quick-add window
+pub const WINDOW_LABEL: &str = "quick-add";
+pub const DEFAULT_SHORTCUT: &str = "CmdOrCtrl+Shift+T";
+/// Event emitted after a quick-added task was created
+pub const QUICK_TASK_CREATED_EVENT: &str = "quick-task-created";
+
+/// A created quick-add task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickCreated {
+    /// `PLAN-###` without a parent (the backend's default kind)
+    pub task_id: String,
+    pub task: ParsedTask,
+    /// Created, but tags, domain or due date could not be set
+    pub warning: Option<String>,
+}
+
+/// Create the parsed task (tags, domain and due date in later calls);
+/// without `app` (`--headless`) nothing is replayed or emitted
+pub async fn create(
+    app: Option<&AppHandle>,
+    state: &AppState,
+    task: ParsedTask,
+) -> anyhow::Result<QuickCreated> {
+    let mut params = json!({ "title": task.title });
+    if let Some(priority) = task.priority {
+        params["priority"] = json!(priority);
+    }
+    let (task_id, mut warning) =
+        create_task(app, state, params, &task.tags, task.domain.as_deref()).await?;
+    if let Some(due) = task.due {
+        if let (_, Err(e)) = commands::write_due(state, &task_id, Some(due)).await {
+            log::warn!("Created {} without its due date: {:#}", task_id, e);
+            let unset = format!("the due date was not set: {}", e);
+            warning = Some(match warning {
+                Some(warning) => format!("{}; {}", warning, unset),
+                None => format!("Created {}, but {}", task_id, unset),
+            });
+        }
+    }
+
+    let created = QuickCreated {
+        task_id,
+        task,
+        warning,
+    };
+    if let Some(Err(e)) = app.map(|app| app.emit(QUICK_TASK_CREATED_EVENT, &created)) {
+        log::warn!("Failed to emit {}: {}", QUICK_TASK_CREATED_EVENT, e);
+    }
+    Ok(created)
+}
+
+/// `tasks_create` with `params`, then `tasks_edit` for `tags` and `domain`
+/// (which it doesn't take); returns the new id and, when the edit failed, a
+/// warning. Pending offline edits are replayed first when there is an `app`.
+pub(crate) async fn create_task(
+    app: Option<&AppHandle>,
+    state: &AppState,
+    params: serde_json::Value,
+    tags: &[String],
+    domain: Option<&str>,
+) -> anyhow::Result<(String, Option<String>)> {
+    if read_only::enabled(state).await {
+        return Err(ReadOnlyError.into());
+    }
+    let bridge = &state.bridge;
+    if let Some(app) = app {
+        if state.mutation_queue.lock().await.has_untried() {
+            mutation_queue::replay_queue(app).await;
+        }
+    }
+    state.storage_watch.mark_own_write();
+    let created = bridge
+        .call_tool("tasks_create", params.clone())
+        .await
+        .and_then(backend::into_result);
+    state.storage_watch.mark_own_write();
+    let created = match created {
+        Ok(created) => created,
+        Err(e) => {
+            let failed = Audit::new("tasks_create", Vec::new())
+                .params(&params)
+                .failed(&e);
+            audit::record(state, failed).await;
+            return Err(e);
+        }
+    };
+    let task_id = ["task_id", "plan_id"]
+        .iter()
+        .find_map(|key| backend::task_str(&created, key))
+        .map(String::from)
+        .ok_or_else(|| anyhow::anyhow!("tasks_create returned no id"))?;
+
+    let mut warning = None;
+    if !tags.is_empty() || domain.is_some() {
+        let mut edit = json!({ "task": task_id });
+        if !tags.is_empty() {
+            edit["tags"] = json!(tags);
+        }
+        if let Some(domain) = domain {
+            edit["new_domain"] = json!(domain);
+        }
+        let edited = bridge.call_tool("tasks_edit", edit).await;
+        state.storage_watch.mark_own_write();
+        if let Err(e) = edited.and_then(backend::into_result) {
+            log::warn!("Created {} without tags/domain: {:#}", task_id, e);
+            warning = Some(format!(
+                "Created {}, but tags/domain were not set: {}",
+                task_id, e
+            ));
+        }
+    }
+    // A new task changes every list
+    state.read_cache.lock().await.invalidate(&[]);
+    state
+        .journal
+        .lock()
+        .await
+        .record(Mutation::new("tasks_create", Some(task_id.clone())));
+    let audited = Audit::new("tasks_create", [task_id.clone()]).params(&params);
+    audit::record(state, audited).await;
+    Ok((task_id, warning))
+}
+
+/// Registration state of the shortcut (`quick_add_status`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShortcutStatus {
+    /// `None` when disabled
+    pub shortcut: Option<String>,
+    pub registered: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Configured shortcut: unset means the default, an empty string disables it
+pub fn configured_shortcut(settings: &Settings) -> Option<String> {
+    match settings.quick_add_shortcut.as_deref().map(str::trim) {
+        None => Some(DEFAULT_SHORTCUT.to_string()),
+        Some("") => None,
+        Some(shortcut) => Some(shortcut.to_string()),
+    }
+}
+
+/// Show the window, or hide it when it already has focus
+fn toggle_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        if window.is_focused().unwrap_or(false) {
+            let _ = window.close();
+        } else {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        return;
+    }
+    let built = WebviewWindowBuilder::new(app, WINDOW_LABEL, WebviewUrl::App(WINDOW_LABEL.into()))
+        .title("Quick add")
+        .inner_size(560.0, 112.0)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .center()
+        .focused(true)
+        .build();
+    if let Err(e) = built {
+        log::warn!("Failed to open the quick-add window: {}", e);
+    }
+}
+
+/// (Re)register the shortcut from `settings`, replacing the previous one
+///
+/// The result is also kept in `AppState::quick_add`; a shortcut the OS
+/// refuses (usually taken by another app) is an error, not a silent no-op.
+pub fn apply_shortcut(app: &AppHandle, settings: &Settings) -> ShortcutStatus {
+    let shortcuts = app.global_shortcut();
+    if let Err(e) = shortcuts.unregister_all() {
+        log::warn!("Failed to unregister global shortcuts: {}", e);
+    }
+
+    let shortcut = configured_shortcut(settings);
+    let error = shortcut.as_deref().and_then(|shortcut| {
+        let registered = shortcut
+            .parse::<Shortcut>()
+            .map_err(|e| {
+                CatalogError::new(ErrorCode::ShortcutInvalid)
+                    .with("shortcut", shortcut)
+                    .with("detail", e.to_string())
+            })
+            .and_then(|parsed| {
+                shortcuts
+                    .on_shortcut(parsed, |app, _, event| {
+                        if event.state == ShortcutState::Pressed {
+                            toggle_window(app);
+                        }
+                    })
+                    .map_err(|e| {
+                        CatalogError::new(ErrorCode::ShortcutUnavailable)
+                            .with("shortcut", shortcut)
+                            .with("detail", e.to_string())
+                    })
+            });
+        registered.err()
+    });
+
+    let status = ShortcutStatus {
+        registered: shortcut.is_some() && error.is_none(),
+        shortcut,
+        error: error.into(),
+    };
+    match (&status.shortcut, &status.error.message) {
+        (_, Some(e)) => log::warn!("Quick add shortcut: {}", e),
+        (Some(shortcut), None) => log::info!("Quick add shortcut: {}", shortcut),
+        (None, None) => log::info!("Quick add shortcut disabled"),
+    }
+    if let Some(state) = app.try_state::<AppState>() {
+        *state.quick_add.lock().unwrap_or_else(|e| e.into_inner()) = status.clone();
+    }
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured_shortcut() {
+        let mut settings = Settings::default();
+        assert_eq!(
+            configured_shortcut(&settings).as_deref(),
+            Some(DEFAULT_SHORTCUT)
+        );
+        settings.quick_add_shortcut = Some(" Alt+Space ".into());
+        assert_eq!(configured_shortcut(&settings).as_deref(), Some("Alt+Space"));
+        settings.quick_add_shortcut = Some(String::new());
+        assert_eq!(configured_shortcut(&settings), None);
+    }
+}
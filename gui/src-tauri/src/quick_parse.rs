@@ -0,0 +1,366 @@
+//! Quick-add line syntax
+//!
+//! Shared by the quick-add window and the inline new-task field: one line
+//! such as `Refactor auth middleware #backend !high @api due:2025-03-01` is
+//! the title plus its markers. Markers are whole words and may appear
+//! anywhere:
+//!
+//! - `#tag`, repeatable (`#123` stays in the title as an issue reference)
+//! - `!priority`, the last one wins; an unknown word stays in the title with
+//!   a warning
+//! - `@domain` and `ns:namespace`, at most one each
+//! - `due:YYYY-MM-DD`, the last one wins; a date that doesn't parse stays in
+//!   the title with a warning
+//!
+//! A leading backslash (`\#literal`, `\due:soon`) keeps a word in the title
+//! as typed, minus the backslash.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::due;
+
+/// Prefix of the due-date marker
+const DUE_PREFIX: &str = "due:";
+/// Prefix of the namespace marker
+const NAMESPACE_PREFIX: &str = "ns:";
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum QuickParseError {
+    #[error("The task needs a title")]
+    EmptyTitle,
+    #[error("Only one @domain per task (got @{0} and @{1})")]
+    MultipleDomains(String, String),
+    #[error("Only one ns: per task (got ns:{0} and ns:{1})")]
+    MultipleNamespaces(String, String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+/// A parsed quick-add line
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParsedTask {
+    pub title: String,
+    pub tags: Vec<String>,
+    pub priority: Option<Priority>,
+    pub domain: Option<String>,
+    pub due: Option<NaiveDate>,
+    pub namespace: Option<String>,
+    /// Markers that were not understood (and were kept in the title)
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+fn marker_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_' | '/' | '.')
+}
+
+/// `auth,` -> `auth`: a word without trailing punctuation
+fn trim_punctuation(word: &str) -> &str {
+    word.trim_end_matches([',', ';', ':', '.', '!', '?', ')'])
+}
+
+/// `#auth,` -> `auth`: the marker name without trailing punctuation, if
+/// that leaves a valid name
+fn marker_name(word: &str) -> Option<&str> {
+    let name = trim_punctuation(word);
+    (!name.is_empty() && name.chars().all(marker_char)).then_some(name)
+}
+
+fn priority(name: &str) -> Option<Priority> {
+    match name.to_lowercase().as_str() {
+        "low" | "l" => Some(Priority::Low),
+        "medium" | "med" | "m" => Some(Priority::Medium),
+        "high" | "h" => Some(Priority::High),
+        _ => None,
+    }
+}
+
+/// The value after a `key:` prefix (case-insensitive), if there is one
+fn prefixed<'a>(word: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = word.get(..prefix.len())?;
+    let value = trim_punctuation(&word[prefix.len()..]);
+    (head.eq_ignore_ascii_case(prefix) && !value.is_empty()).then_some(value)
+}
+
+/// Set a marker that may appear once (repeating the same value is fine)
+fn set_once(
+    slot: &mut Option<String>,
+    value: &str,
+    conflict: fn(String, String) -> QuickParseError,
+) -> Result<(), QuickParseError> {
+    match slot {
+        Some(first) if first != value => Err(conflict(first.clone(), value.to_string())),
+        _ => {
+            *slot = Some(value.to_string());
+            Ok(())
+        }
+    }
+}
+
+/// Parse a quick-add line (see the module docs for the syntax)
+pub fn quick_parse(input: &str) -> Result<ParsedTask, QuickParseError> {
+    let mut task = ParsedTask::default();
+    let mut title: Vec<&str> = Vec::new();
+
+    for word in input.split_whitespace() {
+        if let Some(literal) = word.strip_prefix('\\') {
+            if !literal.is_empty() {
+                title.push(literal);
+            }
+            continue;
+        }
+        if let Some(raw) = prefixed(word, DUE_PREFIX) {
+            match due::parse_due(raw) {
+                Some(date) => task.due = Some(date),
+                None => {
+                    task.warnings.push(format!(
+                        "Unknown due date '{}' kept in the title (use due:YYYY-MM-DD)",
+                        raw
+                    ));
+                    title.push(word);
+                }
+            }
+            continue;
+        }
+        if let Some(namespace) =
+            prefixed(word, NAMESPACE_PREFIX).filter(|n| marker_name(n).is_some())
+        {
+            set_once(
+                &mut task.namespace,
+                namespace,
+                QuickParseError::MultipleNamespaces,
+            )?;
+            continue;
+        }
+        let (marker, rest) = word.split_at(word.chars().next().map_or(0, char::len_utf8));
+        match (marker, marker_name(rest)) {
+            ("#", Some(tag)) if !tag.chars().all(|c| c.is_ascii_digit()) => {
+                if !task.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                    task.tags.push(tag.to_string());
+                }
+            }
+            ("!", Some(name)) => match priority(name) {
+                Some(priority) => task.priority = Some(priority),
+                None => {
+                    task.warnings.push(format!(
+                        "Unknown priority '!{}' kept in the title (use !low, !medium or !high)",
+                        name
+                    ));
+                    title.push(word);
+                }
+            },
+            ("@", Some(domain)) => set_once(
+                &mut task.domain,
+                domain.trim_matches('/'),
+                QuickParseError::MultipleDomains,
+            )?,
+            _ => title.push(word),
+        }
+    }
+
+    task.title = title.join(" ");
+    if task.title.is_empty() {
+        return Err(QuickParseError::EmptyTitle);
+    }
+    Ok(task)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn task(
+        title: &str,
+        tags: &[&str],
+        priority: Option<Priority>,
+        domain: Option<&str>,
+    ) -> ParsedTask {
+        ParsedTask {
+            title: title.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            priority,
+            domain: domain.map(String::from),
+            ..ParsedTask::default()
+        }
+    }
+
+    fn date(s: &str) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()
+    }
+
+    #[test]
+    fn test_parse_example_line() {
+        assert_eq!(
+            quick_parse("Fix login redirect #auth !high @backend").unwrap(),
+            task(
+                "Fix login redirect",
+                &["auth"],
+                Some(Priority::High),
+                Some("backend")
+            )
+        );
+        assert_eq!(
+            quick_parse("Refactor auth middleware #backend #tech-debt !high @api due:2025-03-01")
+                .unwrap(),
+            ParsedTask {
+                due: date("2025-03-01"),
+                ..task(
+                    "Refactor auth middleware",
+                    &["backend", "tech-debt"],
+                    Some(Priority::High),
+                    Some("api")
+                )
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_markers_anywhere() {
+        assert_eq!(
+            quick_parse("  !low #ui   Tidy  #ui-kit the   header @gui/web/ #UI ").unwrap(),
+            task(
+                "Tidy the header",
+                &["ui", "ui-kit"],
+                Some(Priority::Low),
+                Some("gui/web")
+            )
+        );
+        // Last priority wins, aliases and case are accepted
+        assert_eq!(
+            quick_parse("Ship !l !H").unwrap().priority,
+            Some(Priority::High)
+        );
+        assert_eq!(
+            quick_parse("Ship !Med").unwrap().priority,
+            Some(Priority::Medium)
+        );
+        // Trailing punctuation isn't part of a marker
+        assert_eq!(
+            quick_parse("Review #api, then merge @core.").unwrap(),
+            task("Review then merge", &["api"], None, Some("core"))
+        );
+    }
+
+    #[test]
+    fn test_parse_due_and_namespace() {
+        let parsed = quick_parse("ns:work Ship due:2026-10-01 DUE:2026-10-20, ns:work").unwrap();
+        assert_eq!(parsed.title, "Ship");
+        // Last due date wins
+        assert_eq!(parsed.due, date("2026-10-20"));
+        assert_eq!(parsed.namespace.as_deref(), Some("work"));
+        assert!(parsed.warnings.is_empty());
+        assert_eq!(
+            quick_parse("Ship due:2026-10-20T09:00:00+02:00")
+                .unwrap()
+                .due,
+            date("2026-10-20")
+        );
+        // Bare prefixes and mid-word lookalikes are title text
+        assert_eq!(
+            quick_parse("due: ns: overdue:2026-01-01").unwrap().title,
+            "due: ns: overdue:2026-01-01"
+        );
+    }
+
+    #[test]
+    fn test_parse_plain_text_stays_in_title() {
+        // Issue references, lone markers, mid-word markers, escapes, non-ASCII
+        assert_eq!(
+            quick_parse(
+                "Fix #123 ! now, mail a@b.c \\#literal \\!high \\@home \\due:2026-01-01 ёлка"
+            )
+            .unwrap(),
+            task(
+                "Fix #123 ! now, mail a@b.c #literal !high @home due:2026-01-01 ёлка",
+                &[],
+                None,
+                None
+            )
+        );
+        assert_eq!(quick_parse("Wow #!!").unwrap().title, "Wow #!!");
+        assert_eq!(quick_parse("Tag #ёлка").unwrap().tags, ["ёлка"]);
+        assert_eq!(
+            quick_parse("Same @core @core").unwrap().domain.as_deref(),
+            Some("core")
+        );
+    }
+
+    #[test]
+    fn test_parse_unicode_and_whitespace() {
+        assert_eq!(
+            quick_parse("\t Починить вход 🔐 #безопасность !h @веб\u{3000}\n").unwrap(),
+            task(
+                "Починить вход 🔐",
+                &["безопасность"],
+                Some(Priority::High),
+                Some("веб")
+            )
+        );
+        assert_eq!(
+            quick_parse("日本語のタスク   ").unwrap().title,
+            "日本語のタスク"
+        );
+        // A multi-byte first character isn't mistaken for a marker
+        assert_eq!(quick_parse("ñ#tag").unwrap().title, "ñ#tag");
+    }
+
+    #[test]
+    fn test_parse_ambiguities_are_warnings() {
+        let parsed = quick_parse("Ship !urgent !high").unwrap();
+        assert_eq!(parsed.title, "Ship !urgent");
+        assert_eq!(parsed.priority, Some(Priority::High));
+        assert_eq!(parsed.warnings.len(), 1);
+        assert!(parsed.warnings[0].contains("!low, !medium or !high"));
+
+        let parsed = quick_parse("Ship due:soon").unwrap();
+        assert_eq!(parsed.title, "Ship due:soon");
+        assert_eq!(parsed.due, None);
+        assert!(parsed.warnings[0].contains("'soon'"));
+
+        // An unknown word alone still makes a title
+        assert_eq!(quick_parse("!urgent").unwrap().title, "!urgent");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(quick_parse(""), Err(QuickParseError::EmptyTitle));
+        assert_eq!(quick_parse(" \n\t "), Err(QuickParseError::EmptyTitle));
+        assert_eq!(
+            quick_parse("  #auth !high @backend due:2026-01-01 ns:work "),
+            Err(QuickParseError::EmptyTitle)
+        );
+        assert_eq!(quick_parse("\\"), Err(QuickParseError::EmptyTitle));
+        assert_eq!(
+            quick_parse("Move @core @gui"),
+            Err(QuickParseError::MultipleDomains(
+                "core".into(),
+                "gui".into()
+            ))
+        );
+        assert_eq!(
+            quick_parse("Move ns:work ns:home"),
+            Err(QuickParseError::MultipleNamespaces(
+                "work".into(),
+                "home".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_priority_serializes_for_backend() {
+        assert_eq!(json!(Priority::High), json!("HIGH"));
+        let parsed = json!(quick_parse("Do it !m due:2026-10-20").unwrap());
+        assert_eq!(parsed["priority"], json!("MEDIUM"));
+        assert_eq!(parsed["due"], json!("2026-10-20"));
+        assert_eq!(parsed["domain"], Value::Null);
+        assert_eq!(parsed["warnings"], json!([]));
+    }
+}
@@ -0,0 +1,150 @@
+//! Rate limits for expensive calls
+//!
+//! One token bucket per tool: it holds up to the tool's per-minute limit
+//! (`rate_limits_per_min` in settings, else [`DEFAULT_PER_MIN`] for
+//! mutating tools; reads are cached and unlimited) and refills
+//! continuously at that rate, so a burst of the full limit is allowed
+//! after a quiet minute. A limit of 0 turns it off. Buckets are per
+//! project: switching projects starts them full again.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::AppState;
+
+/// Calls per minute of a mutating tool without a `rate_limits_per_min`
+/// entry
+pub const DEFAULT_PER_MIN: u32 = 30;
+/// Largest accepted `rate_limits_per_min` value
+pub const MAX_PER_MIN: u32 = 6000;
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Too many {tool} calls; retry in {retry_after_ms} ms")]
+pub struct RateLimitError {
+    pub tool: String,
+    pub retry_after_ms: u64,
+}
+
+/// Fill of one bucket (`bridge_metrics` in developer mode)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BucketLevel {
+    /// Calls that could be made right now
+    pub tokens: f64,
+    pub per_min: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Bucket {
+    tokens: f64,
+    per_min: u32,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, per_min: u32, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * per_min as f64 / 60.0).min(per_min as f64);
+        self.per_min = per_min;
+        self.updated = now;
+    }
+}
+
+/// Buckets by tool (kept in `AppState`)
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    buckets: HashMap<String, Bucket>,
+}
+
+impl RateLimiter {
+    /// Take a token for `tool`, or say how long until one is back
+    pub fn acquire(&mut self, tool: &str, per_min: u32, now: Instant) -> Result<(), Duration> {
+        if per_min == 0 {
+            return Ok(());
+        }
+        let bucket = self.buckets.entry(tool.to_string()).or_insert(Bucket {
+            tokens: per_min as f64,
+            per_min,
+            updated: now,
+        });
+        bucket.refill(per_min, now);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        let missing = 1.0 - bucket.tokens;
+        Err(Duration::from_secs_f64(missing * 60.0 / per_min as f64))
+    }
+
+    /// Current fill of every bucket used so far
+    pub fn levels(&self, now: Instant) -> BTreeMap<String, BucketLevel> {
+        self.buckets
+            .iter()
+            .map(|(tool, bucket)| {
+                let mut bucket = bucket.clone();
+                bucket.refill(bucket.per_min, now);
+                let level = BucketLevel {
+                    tokens: (bucket.tokens * 100.0).floor() / 100.0,
+                    per_min: bucket.per_min,
+                };
+                (tool.clone(), level)
+            })
+            .collect()
+    }
+
+    pub fn reset(&mut self) {
+        self.buckets.clear();
+    }
+}
+
+/// Take a token for a call of `tool` under the configured limit
+pub async fn check(state: &AppState, tool: &str) -> Result<(), RateLimitError> {
+    let per_min = state.settings.read().await.rate_limit(tool);
+    let acquired = state
+        .rate_limiter
+        .lock()
+        .await
+        .acquire(tool, per_min, Instant::now());
+    acquired.map_err(|wait| RateLimitError {
+        tool: tool.to_string(),
+        retry_after_ms: (wait.as_millis() as u64).max(1),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_bursts_then_refills() {
+        let mut limiter = RateLimiter::default();
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.acquire("tasks_decompose", 3, start).is_ok());
+        }
+        let wait = limiter.acquire("tasks_decompose", 3, start).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(20));
+        // Other tools have their own bucket
+        assert!(limiter.acquire("tasks_define", 3, start).is_ok());
+
+        let later = start + Duration::from_secs(10);
+        let wait = limiter.acquire("tasks_decompose", 3, later).unwrap_err();
+        assert_eq!(wait.as_secs(), 10);
+        assert!(limiter
+            .acquire("tasks_decompose", 3, later + Duration::from_secs(10))
+            .is_ok());
+
+        // Never more than a full bucket, however long the pause
+        let much_later = start + Duration::from_secs(3600);
+        let levels = limiter.levels(much_later);
+        assert_eq!(levels["tasks_decompose"].tokens, 3.0);
+        assert_eq!(levels["tasks_define"].per_min, 3);
+
+        for _ in 0..100 {
+            assert!(limiter.acquire("tasks_verify", 0, start).is_ok());
+        }
+        limiter.reset();
+        assert!(limiter.levels(start).is_empty());
+    }
+}
@@ -0,0 +1,282 @@
+//! Read-through cache for list/show/context results
+//!
+//! Entries expire after the configured TTL and are dropped explicitly when
+//! a mutation succeeds: entries scoped to the mutated task ids plus every
+//! unscoped entry (lists and context include all tasks), or everything when
+//! the task is unknown. `tasks_context` entries live longer
+//! ([`DEFAULT_CONTEXT_TTL`]) and are also dropped when the AI status
+//! changes, since the context reports what the agent is doing. Hits and
+//! misses are counted per command.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+/// TTL used when the settings don't set `cache_ttl_ms`
+pub const DEFAULT_TTL: Duration = Duration::from_secs(5);
+/// TTL of context entries when the settings don't set `context_cache_ttl_ms`
+pub const DEFAULT_CONTEXT_TTL: Duration = Duration::from_secs(30);
+
+const CONTEXT: &str = "tasks_context";
+
+struct Entry {
+    value: Value,
+    /// Task ids the value is about; `None` = any task (lists, context)
+    scope: Option<Vec<String>>,
+    stored_at: Instant,
+}
+
+#[derive(Default)]
+pub struct ReadCache {
+    entries: HashMap<String, Entry>,
+    hits: u64,
+    misses: u64,
+    /// command -> (hits, misses)
+    by_command: HashMap<String, (u64, u64)>,
+    /// Bumped by every invalidation (derived data such as the search index
+    /// is stale once it moves)
+    generation: u64,
+}
+
+/// Cache key for a read command and its params
+pub fn key(command: &str, params: &Value) -> String {
+    format!("{}:{}", command, params)
+}
+
+/// Key of a `tasks_context` call: the target task (`task` or `task_id`)
+/// and `include_all` (default false), plus any other params as given
+pub fn context_key(params: &Value) -> String {
+    let mut normalized = match params {
+        Value::Object(obj) => obj.clone(),
+        _ => Default::default(),
+    };
+    normalized.remove("task_id");
+    normalized.insert("task".to_string(), target_task(params).into());
+    let include_all = params.get("include_all").and_then(|v| v.as_bool());
+    normalized.insert(
+        "include_all".to_string(),
+        include_all.unwrap_or(false).into(),
+    );
+    key(CONTEXT, &Value::Object(normalized))
+}
+
+fn command_of(key: &str) -> &str {
+    key.split_once(':').map_or(key, |(command, _)| command)
+}
+
+impl ReadCache {
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Fresh value for `key` (counts a hit or a miss)
+    pub fn get(&mut self, key: &str, ttl: Duration, now: Instant) -> Option<Value> {
+        let fresh = self
+            .entries
+            .get(key)
+            .filter(|e| now.saturating_duration_since(e.stored_at) < ttl)
+            .map(|e| e.value.clone());
+        let counts = self
+            .by_command
+            .entry(command_of(key).to_string())
+            .or_default();
+        match fresh {
+            Some(_) => {
+                self.hits += 1;
+                counts.0 += 1;
+            }
+            None => {
+                self.misses += 1;
+                counts.1 += 1;
+            }
+        }
+        fresh
+    }
+
+    /// (hits, misses) of one read command, e.g. `tasks_context`
+    pub fn counts(&self, command: &str) -> (u64, u64) {
+        self.by_command.get(command).copied().unwrap_or_default()
+    }
+
+    pub fn put(&mut self, key: String, value: Value, scope: Option<Vec<String>>, now: Instant) {
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                scope,
+                stored_at: now,
+            },
+        );
+    }
+
+    /// Drop what a mutation of `task_ids` may have changed (all if empty)
+    pub fn invalidate(&mut self, task_ids: &[String]) {
+        self.generation += 1;
+        if task_ids.is_empty() {
+            self.entries.clear();
+            return;
+        }
+        self.entries.retain(|_, e| {
+            e.scope
+                .as_ref()
+                .is_some_and(|scope| !scope.iter().any(|id| task_ids.contains(id)))
+        });
+    }
+
+    /// Drop context entries about `task` (and those about every task), or
+    /// all of them when `task` is `None`
+    pub fn invalidate_context(&mut self, task: Option<&str>) {
+        self.entries.retain(|key, e| {
+            if command_of(key) != CONTEXT {
+                return true;
+            }
+            match (task, &e.scope) {
+                (Some(task), Some(scope)) => !scope.iter().any(|id| id == task),
+                _ => false,
+            }
+        });
+    }
+
+    /// Any cached payload of task `id`, fresh or not (show entries and lists)
+    pub fn find_task(&self, id: &str) -> Option<Value> {
+        let is_task = |t: &&Value| t.get("id").and_then(|i| i.as_str()) == Some(id);
+        let mut entries: Vec<&Entry> = self.entries.values().collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.stored_at));
+        entries.into_iter().find_map(|e| match &e.value {
+            Value::Array(tasks) => tasks.iter().find(is_task).cloned(),
+            task if is_task(&task) => Some(task.clone()),
+            _ => None,
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.generation += 1;
+        self.entries.clear();
+    }
+}
+
+/// Every `id` in a task payload and its nested `children`
+pub fn scope_of(task: &Value) -> Vec<String> {
+    let mut ids = Vec::new();
+    let mut stack = vec![task];
+    while let Some(node) = stack.pop() {
+        if let Some(id) = node.get("id").and_then(|i| i.as_str()) {
+            ids.push(id.to_string());
+        }
+        if let Some(children) = node.get("children").and_then(|c| c.as_array()) {
+            stack.extend(children);
+        }
+    }
+    ids
+}
+
+/// Task id targeted by tool params (`task` or `task_id`), if any
+pub fn target_task(params: &Value) -> Option<String> {
+    ["task", "task_id"]
+        .iter()
+        .find_map(|k| params.get(*k).and_then(|v| v.as_str()))
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_ttl_counters_and_invalidation() {
+        let mut cache = ReadCache::default();
+        let now = Instant::now();
+        let list = key("tasks_list", &json!({"compact": true}));
+        let show_a = key("tasks_show", &json!({"task_id": "A"}));
+        let show_b = key("tasks_show", &json!({"task_id": "B"}));
+
+        assert_eq!(cache.get(&list, DEFAULT_TTL, now), None);
+        cache.put(list.clone(), json!([1]), None, now);
+        cache.put(
+            show_a.clone(),
+            json!({"id": "A"}),
+            Some(vec!["A".into()]),
+            now,
+        );
+        cache.put(
+            show_b.clone(),
+            json!({"id": "B"}),
+            Some(vec!["B".into(), "C".into()]),
+            now,
+        );
+        assert_eq!(cache.get(&list, DEFAULT_TTL, now), Some(json!([1])));
+        assert_eq!(cache.get(&list, DEFAULT_TTL, now + DEFAULT_TTL), None);
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+
+        // A change to C drops the list and B's subtree, keeps A
+        assert_eq!(cache.generation(), 0);
+        cache.invalidate(&["C".to_string()]);
+        assert_eq!(cache.generation(), 1);
+        assert!(cache.get(&show_a, DEFAULT_TTL, now).is_some());
+        assert!(cache.get(&show_b, DEFAULT_TTL, now).is_none());
+        assert!(cache.get(&list, DEFAULT_TTL, now).is_none());
+
+        cache.put(
+            list.clone(),
+            json!([{"id": "A", "status_code": "TODO"}]),
+            None,
+            now + Duration::from_millis(1),
+        );
+        // Newest entry wins
+        assert_eq!(cache.find_task("A").unwrap()["status_code"], "TODO");
+        assert_eq!(cache.find_task("Z"), None);
+
+        cache.invalidate(&[]);
+        assert!(cache.get(&show_a, DEFAULT_TTL, now).is_none());
+    }
+
+    #[test]
+    fn test_context_entries() {
+        let mut cache = ReadCache::default();
+        let now = Instant::now();
+        let a = context_key(&json!({"task_id": "A"}));
+        assert_eq!(a, context_key(&json!({"task": "A", "include_all": false})));
+        let all = context_key(&json!({"include_all": true}));
+        let b = context_key(&json!({"task": "B"}));
+        let list = key("tasks_list", &json!({}));
+        cache.put(a.clone(), json!(1), Some(vec!["A".into()]), now);
+        cache.put(b.clone(), json!(2), Some(vec!["B".into()]), now);
+        cache.put(all.clone(), json!(3), None, now);
+        cache.put(list.clone(), json!([]), None, now);
+
+        assert!(cache.get(&a, DEFAULT_CONTEXT_TTL, now).is_some());
+        assert!(cache.get(&list, DEFAULT_TTL, now).is_some());
+        assert_eq!(cache.counts(CONTEXT), (1, 0));
+        cache.invalidate_context(Some("A"));
+        assert!(cache.get(&a, DEFAULT_CONTEXT_TTL, now).is_none());
+        assert!(cache.get(&all, DEFAULT_CONTEXT_TTL, now).is_none());
+        assert!(cache.get(&b, DEFAULT_CONTEXT_TTL, now).is_some());
+        assert_eq!(cache.counts(CONTEXT), (2, 2));
+
+        // AI status change: every context entry goes, lists stay
+        cache.invalidate_context(None);
+        assert!(cache.get(&b, DEFAULT_CONTEXT_TTL, now).is_none());
+        assert!(cache.get(&list, DEFAULT_TTL, now).is_some());
+        assert_eq!(cache.counts("tasks_list"), (2, 0));
+    }
+
+    #[test]
+    fn test_scope_and_target() {
+        let tree = json!({"id": "A", "children": [{"id": "B", "children": [{"id": "C"}]}]});
+        let mut scope = scope_of(&tree);
+        scope.sort();
+        assert_eq!(scope, ["A", "B", "C"]);
+        assert_eq!(target_task(&json!({"task": "T-1"})), Some("T-1".into()));
+        assert_eq!(target_task(&json!({"title": "x"})), None);
+    }
+}
@@ -0,0 +1,51 @@
+//! Read-only mode
+//!
+//! While the `read_only` setting is on, mutating commands refuse with
+//! `READ_ONLY_MODE` before calling the backend, and queued mutations wait;
+//! reads work as usual. Whether a tool mutates is
+//! [`intents::writes_storage`], the list that also decides cache
+//! invalidation and offline queueing.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::error_catalog::CatalogError;
+use crate::intents;
+use crate::AppState;
+
+/// Emitted with [`ReadOnlyChanged`] when the mode is turned on or off
+pub const READ_ONLY_CHANGED_EVENT: &str = "read-only-changed";
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Read-only mode is on; turn it off to make changes")]
+pub struct ReadOnlyError;
+
+/// Payload of [`READ_ONLY_CHANGED_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadOnlyChanged {
+    pub read_only: bool,
+}
+
+pub async fn enabled(state: &AppState) -> bool {
+    state.settings.read().await.read_only
+}
+
+/// `READ_ONLY_MODE` for a mutating command while the mode is on
+pub async fn refusal(state: &AppState) -> Option<CatalogError> {
+    enabled(state).await.then(|| (&ReadOnlyError).into())
+}
+
+/// [`refusal`] if calling `tool` would mutate
+pub async fn refusal_for(state: &AppState, tool: &str) -> Option<CatalogError> {
+    if !intents::writes_storage(tool) {
+        return None;
+    }
+    refusal(state).await
+}
+
+/// Tell every window
+pub fn emit_changed(app: &AppHandle, read_only: bool) {
+    if let Err(e) = app.emit(READ_ONLY_CHANGED_EVENT, &ReadOnlyChanged { read_only }) {
+        log::warn!("Failed to emit {}: {}", READ_ONLY_CHANGED_EVENT, e);
+    }
+}
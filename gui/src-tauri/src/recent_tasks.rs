@@ -0,0 +1,60 @@
+//! Recently viewed tasks
+//!
+//! `recent_tasks.json` in the project sidecar keeps the last
+//! [`MAX_RECENT`] tasks opened with `tasks_show`, newest first. The
+//! quick-switcher offers them before anything is typed.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// File name of the list inside the project sidecar dir
+pub const RECENT_TASKS_FILE: &str = "recent_tasks.json";
+/// Tasks remembered
+pub const MAX_RECENT: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentTask {
+    pub task_id: String,
+    pub viewed_at: DateTime<Utc>,
+}
+
+/// Move `task_id` to the front (adding it, dropping the oldest past
+/// [`MAX_RECENT`]); false when it already was the newest
+pub fn touch(recent: &mut Vec<RecentTask>, task_id: &str, now: DateTime<Utc>) -> bool {
+    if recent.first().is_some_and(|r| r.task_id == task_id) {
+        return false;
+    }
+    recent.retain(|r| r.task_id != task_id);
+    recent.insert(
+        0,
+        RecentTask {
+            task_id: task_id.to_string(),
+            viewed_at: now,
+        },
+    );
+    recent.truncate(MAX_RECENT);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_touch_moves_to_front_and_caps() {
+        let now = Utc::now();
+        let mut recent = Vec::new();
+        assert!(touch(&mut recent, "A", now));
+        assert!(touch(&mut recent, "B", now));
+        assert!(!touch(&mut recent, "B", now));
+        assert!(touch(&mut recent, "A", now));
+        let ids: Vec<&str> = recent.iter().map(|r| r.task_id.as_str()).collect();
+        assert_eq!(ids, ["A", "B"]);
+
+        for i in 0..MAX_RECENT + 5 {
+            touch(&mut recent, &format!("T-{}", i), now);
+        }
+        assert_eq!(recent.len(), MAX_RECENT);
+        assert_eq!(recent[0].task_id, format!("T-{}", MAX_RECENT + 4));
+    }
+}
@@ -0,0 +1,427 @@
+//! Markdown activity report
+//!
+//! `tasks_report` lists, for a week, a month or a date range, the tasks
+//! completed and created in it and the ones still in progress, each group
+//! split by domain. When and whether a task was completed or created comes
+//! from [`crate::timeline`]'s milestones (history first, then the task's
+//! fields). The progress delta of a task in progress is its checkpoint
+//! percentage now minus the last one a history operation recorded before
+//! the period (0 for tasks created in it); without either it is left out.
+//! Tasks tagged `highlight` or of priority HIGH are repeated under
+//! Highlights. Commits of the period, when the caller adds them, close the
+//! report.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::backend::task_str;
+use crate::board;
+use crate::git::Commit;
+use crate::timeline;
+use crate::timer;
+
+/// Heading of tasks without a domain
+const NO_DOMAIN: &str = "No domain";
+/// Where history results keep a task's progress
+const PROGRESS_POINTERS: [&str; 2] = ["/result/result/task/progress", "/result/task/progress"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NamedPeriod {
+    /// Monday of this week until now
+    Week,
+    /// First of this month until now
+    Month,
+}
+
+/// `"week"`, `"month"` or `{from, to}` (RFC3339 or `YYYY-MM-DD`, `to`
+/// inclusive when a date)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ReportPeriod {
+    Named(NamedPeriod),
+    Range { from: String, to: String },
+}
+
+impl ReportPeriod {
+    /// `from..to` of the period ending at `now`; `Err` holds an unparsable
+    /// bound
+    pub fn bounds<Tz: TimeZone>(
+        &self,
+        now: DateTime<Tz>,
+    ) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+        let tz = now.timezone();
+        let midnight = |date: NaiveDate| {
+            tz.from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default())
+                .earliest()
+                .map(|d| d.with_timezone(&Utc))
+                .ok_or_else(|| date.to_string())
+        };
+        let today = now.date_naive();
+        match self {
+            Self::Named(NamedPeriod::Week) => {
+                let monday = today - Duration::days(today.weekday().num_days_from_monday().into());
+                Ok((midnight(monday)?, now.with_timezone(&Utc)))
+            }
+            Self::Named(NamedPeriod::Month) => {
+                let first = today.with_day(1).unwrap_or(today);
+                Ok((midnight(first)?, now.with_timezone(&Utc)))
+            }
+            Self::Range { from, to } => {
+                let start = timer::parse_bound(from, &tz).ok_or_else(|| from.clone())?;
+                let end = match NaiveDate::parse_from_str(to.trim(), "%Y-%m-%d") {
+                    Ok(date) => midnight(date + Duration::days(1))?,
+                    Err(_) => timer::parse_bound(to, &tz).ok_or_else(|| to.clone())?,
+                };
+                Ok((start, end))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportTask {
+    pub task_id: String,
+    pub title: String,
+    pub domain: String,
+    /// Checkpoint percentage now
+    pub progress: Option<f64>,
+    /// Percentage points gained in the period
+    pub delta: Option<f64>,
+    pub highlight: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub completed: Vec<ReportTask>,
+    pub in_progress: Vec<ReportTask>,
+    pub created: Vec<ReportTask>,
+    /// Commits in the period, newest first; no section when `None`
+    pub commits: Option<Vec<Commit>>,
+}
+
+/// Percentage of a `progress` value: a number, or `{percent}` as
+/// `progress::attach` leaves it
+fn percent(progress: &Value) -> Option<f64> {
+    progress
+        .as_f64()
+        .or_else(|| progress.get("percent").and_then(Value::as_f64))
+}
+
+fn is_highlight(task: &Value) -> bool {
+    let tagged = task
+        .get("tags")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .any(|tag| {
+            tag.trim()
+                .trim_start_matches('#')
+                .eq_ignore_ascii_case("highlight")
+        });
+    tagged || task_str(task, "priority").is_some_and(|p| p.trim().eq_ignore_ascii_case("HIGH"))
+}
+
+/// Last progress each task had in history before `before`
+fn progress_before<Tz: TimeZone>(
+    operations: &[Value],
+    before: DateTime<Utc>,
+    tz: &Tz,
+) -> HashMap<String, f64> {
+    let mut out = HashMap::new();
+    for op in operations {
+        let (Some(task_id), Some(at)) = (
+            task_str(op, "task_id"),
+            op.get("timestamp")
+                .and_then(|t| timeline::parse_timestamp(t, tz)),
+        ) else {
+            continue;
+        };
+        if at >= before || op.get("undone").and_then(Value::as_bool) == Some(true) {
+            continue;
+        }
+        if let Some(progress) = PROGRESS_POINTERS
+            .iter()
+            .find_map(|pointer| op.pointer(pointer).and_then(percent))
+        {
+            out.insert(task_id.to_string(), progress);
+        }
+    }
+    out
+}
+
+/// Sort tasks into the report's groups (`operations` oldest first, empty
+/// without `tasks_history`)
+pub fn gather<Tz: TimeZone>(
+    tasks: &[Value],
+    operations: &[Value],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    tz: &Tz,
+) -> Report {
+    let history = timeline::from_history(operations, tz);
+    let baselines = progress_before(operations, from, tz);
+    let within = |at: Option<DateTime<Utc>>| at.is_some_and(|at| at >= from && at < to);
+
+    let mut report = Report {
+        from,
+        to,
+        completed: Vec::new(),
+        in_progress: Vec::new(),
+        created: Vec::new(),
+        commits: None,
+    };
+    for task in tasks {
+        let Some(task_id) = task_str(task, "id") else {
+            continue;
+        };
+        let milestones = timeline::milestones(task, history.get(task_id), tz);
+        let created = within(milestones.created);
+        let progress = task.get("progress").and_then(percent);
+        let baseline = baselines.get(task_id).copied().or(created.then_some(0.0));
+        let entry = ReportTask {
+            task_id: task_id.to_string(),
+            title: task_str(task, "title").unwrap_or_default().to_string(),
+            domain: task_str(task, "domain")
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            progress,
+            delta: progress.zip(baseline).map(|(now, then)| now - then),
+            highlight: is_highlight(task),
+        };
+        if within(milestones.completed) {
+            report.completed.push(entry.clone());
+        } else if board::status_of(task) == "ACTIVE" {
+            report.in_progress.push(entry.clone());
+        }
+        if created {
+            report.created.push(entry);
+        }
+    }
+    report
+}
+
+/// `tasks` grouped by domain (alphabetical, no domain last)
+fn by_domain(tasks: &[ReportTask]) -> Vec<(&str, Vec<&ReportTask>)> {
+    let mut groups: BTreeMap<(bool, &str), Vec<&ReportTask>> = BTreeMap::new();
+    for task in tasks {
+        let domain = task.domain.as_str();
+        groups
+            .entry((domain.is_empty(), domain))
+            .or_default()
+            .push(task);
+    }
+    groups
+        .into_iter()
+        .map(|((_, domain), mut tasks)| {
+            tasks.sort_by(|a, b| a.task_id.cmp(&b.task_id));
+            (if domain.is_empty() { NO_DOMAIN } else { domain }, tasks)
+        })
+        .collect()
+}
+
+fn progress_note(task: &ReportTask) -> String {
+    match (task.progress, task.delta) {
+        (Some(now), Some(delta)) => format!(" ({:.0}%, {:+.0})", now, delta),
+        (Some(now), None) => format!(" ({:.0}%)", now),
+        _ => String::new(),
+    }
+}
+
+fn section(out: &mut String, title: &str, tasks: &[ReportTask], progress: bool) {
+    let _ = writeln!(out, "## {} ({})\n", title, tasks.len());
+    if tasks.is_empty() {
+        out.push_str("_None_\n\n");
+        return;
+    }
+    for (domain, tasks) in by_domain(tasks) {
+        let _ = writeln!(out, "### {} ({})\n", domain, tasks.len());
+        for task in tasks {
+            let note = if progress {
+                progress_note(task)
+            } else {
+                String::new()
+            };
+            let _ = writeln!(out, "- **{}** {}{}", task.task_id, task.title, note);
+        }
+        out.push('\n');
+    }
+}
+
+/// The report as Markdown, dates in `tz`
+pub fn render<Tz: TimeZone>(report: &Report, tz: &Tz) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let first = report.from.with_timezone(tz).date_naive();
+    // `to` is exclusive
+    let last = (report.to - Duration::seconds(1))
+        .with_timezone(tz)
+        .date_naive()
+        .max(first);
+    let mut out = String::new();
+    let _ = writeln!(out, "# Report {} to {}\n", first, last);
+    let _ = writeln!(
+        out,
+        "Completed: {} | In progress: {} | Created: {}\n",
+        report.completed.len(),
+        report.in_progress.len(),
+        report.created.len()
+    );
+
+    let mut highlights: Vec<(&ReportTask, &str)> = Vec::new();
+    for (tasks, label) in [
+        (&report.completed, "completed"),
+        (&report.in_progress, "in progress"),
+        (&report.created, "created"),
+    ] {
+        for task in tasks.iter().filter(|t| t.highlight) {
+            if !highlights.iter().any(|(t, _)| t.task_id == task.task_id) {
+                highlights.push((task, label));
+            }
+        }
+    }
+    out.push_str("## Highlights\n\n");
+    if highlights.is_empty() {
+        out.push_str("_None_\n\n");
+    } else {
+        for (task, label) in highlights {
+            let _ = writeln!(out, "- **{}** {} ({})", task.task_id, task.title, label);
+        }
+        out.push('\n');
+    }
+
+    section(&mut out, "Completed", &report.completed, false);
+    section(&mut out, "In progress", &report.in_progress, true);
+    section(&mut out, "Created", &report.created, false);
+    if let Some(commits) = &report.commits {
+        let _ = writeln!(out, "## Commits ({})\n", commits.len());
+        if commits.is_empty() {
+            out.push_str("_None_\n");
+        }
+        for commit in commits {
+            let _ = writeln!(
+                out,
+                "- `{}` {} — {} ({})",
+                commit.short_hash,
+                commit.subject,
+                commit.author,
+                commit.date.with_timezone(tz).date_naive()
+            );
+        }
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const DATASET: &str = include_str!("../tests/fixtures/report/dataset.json");
+    const GOLDEN_WEEK: &str = include_str!("../tests/fixtures/report/week.md");
+    const GOLDEN_EMPTY: &str = include_str!("../tests/fixtures/report/empty.md");
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn dataset() -> (Vec<Value>, Vec<Value>, DateTime<Utc>) {
+        let data: Value = serde_json::from_str(DATASET).unwrap();
+        (
+            data["tasks"].as_array().unwrap().clone(),
+            data["operations"].as_array().unwrap().clone(),
+            ts(data["now"].as_str().unwrap()),
+        )
+    }
+
+    #[test]
+    fn test_period_bounds() {
+        // A Friday
+        let now = ts("2026-10-16T15:30:00Z");
+        let week: ReportPeriod = serde_json::from_value(json!("week")).unwrap();
+        assert_eq!(week.bounds(now), Ok((ts("2026-10-12T00:00:00Z"), now)));
+        let month: ReportPeriod = serde_json::from_value(json!("month")).unwrap();
+        assert_eq!(month.bounds(now), Ok((ts("2026-10-01T00:00:00Z"), now)));
+
+        let range: ReportPeriod =
+            serde_json::from_value(json!({ "from": "2026-09-01", "to": "2026-09-30" })).unwrap();
+        assert_eq!(
+            range.bounds(now),
+            Ok((ts("2026-09-01T00:00:00Z"), ts("2026-10-01T00:00:00Z")))
+        );
+        let range = ReportPeriod::Range {
+            from: "2026-09-01T12:00:00Z".into(),
+            to: "whenever".into(),
+        };
+        assert_eq!(range.bounds(now), Err("whenever".to_string()));
+        assert!(serde_json::from_value::<ReportPeriod>(json!("year")).is_err());
+    }
+
+    /// Rendering of the fixture dataset, compared with week.md
+    #[test]
+    fn test_golden_week_report() {
+        let (tasks, operations, now) = dataset();
+        let (from, to) = ReportPeriod::Named(NamedPeriod::Week).bounds(now).unwrap();
+        let report = gather(&tasks, &operations, from, to, &Utc);
+
+        let ids = |tasks: &[ReportTask]| -> Vec<String> {
+            tasks.iter().map(|t| t.task_id.clone()).collect()
+        };
+        assert_eq!(ids(&report.completed), ["TASK-001", "TASK-002", "TASK-003"]);
+        assert_eq!(ids(&report.in_progress), ["TASK-004", "TASK-005"]);
+        assert_eq!(ids(&report.created), ["TASK-003", "TASK-005", "TASK-007"]);
+        assert_eq!(render(&report, &Utc), GOLDEN_WEEK);
+    }
+
+    #[test]
+    fn test_golden_empty_report() {
+        let (tasks, operations, _) = dataset();
+        let report = gather(
+            &tasks,
+            &operations,
+            ts("2025-01-01T00:00:00Z"),
+            ts("2025-01-08T00:00:00Z"),
+            &Utc,
+        );
+        assert_eq!(render(&report, &Utc), GOLDEN_EMPTY);
+    }
+
+    #[test]
+    fn test_commits_section() {
+        let (tasks, operations, _) = dataset();
+        let mut report = gather(
+            &tasks,
+            &operations,
+            ts("2025-01-01T00:00:00Z"),
+            ts("2025-01-08T00:00:00Z"),
+            &Utc,
+        );
+        report.commits = Some(Vec::new());
+        let empty = render(&report, &Utc);
+        assert!(empty.starts_with(GOLDEN_EMPTY.trim_end()), "{}", empty);
+        assert!(empty.ends_with("## Commits (0)\n\n_None_\n"), "{}", empty);
+
+        report.commits = Some(vec![Commit {
+            hash: "1a2b3c4d5e6f".into(),
+            short_hash: "1a2b3c4".into(),
+            author: "Ann Lee".into(),
+            date: ts("2025-01-03T23:30:00Z"),
+            subject: "Fix the parser (TASK-002)".into(),
+        }]);
+        let tz = chrono::FixedOffset::east_opt(3600).unwrap();
+        assert!(render(&report, &tz).ends_with(
+            "## Commits (1)\n\n- `1a2b3c4` Fix the parser (TASK-002) — Ann Lee (2025-01-04)\n"
+        ));
+    }
+}
@@ -0,0 +1,97 @@
+//! Task revisions (optimistic concurrency)
+//!
+//! The backend keeps an integer `revision` on every task and checks an
+//! `expected_revision` argument inside the write itself, answering
+//! `REVISION_MISMATCH` when the task moved on. Mutating commands forward
+//! the caller's `expected_revision` and report that answer as
+//! `REVISION_CONFLICT`; without one nothing is checked.
+
+use serde_json::{json, Value};
+
+use crate::error_catalog::{CatalogError, ErrorCode};
+
+/// Backend error code for a stale `expected_revision`
+const MISMATCH_CODE: &str = "REVISION_MISMATCH";
+
+/// The backend's revision of a task payload
+pub fn of(task: &Value) -> Option<u64> {
+    task.get("revision").and_then(Value::as_u64)
+}
+
+/// Add `expected_revision` to tool arguments when the caller passed one
+pub fn expect(params: &mut Value, expected_revision: Option<u64>) {
+    if let (Some(expected), Some(params)) = (expected_revision, params.as_object_mut()) {
+        params.insert("expected_revision".to_string(), json!(expected));
+    }
+}
+
+/// `REVISION_CONFLICT` when `task` (loaded by the command anyway) is no
+/// longer at `expected_revision`
+pub fn check(task_id: &str, task: &Value, expected_revision: Option<u64>) -> Option<CatalogError> {
+    let expected = expected_revision?;
+    let current = of(task).unwrap_or_default();
+    (current != expected).then(|| conflict_error(task_id, expected, current))
+}
+
+/// `REVISION_CONFLICT` for a backend `REVISION_MISMATCH` envelope (its
+/// `error` and `result`)
+pub fn conflict(error: Option<&Value>, result: &Value) -> Option<CatalogError> {
+    let code = error.and_then(|e| e.get("code")).and_then(Value::as_str);
+    if code != Some(MISMATCH_CODE) {
+        return None;
+    }
+    let task_id = result
+        .get("task")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let field = |key: &str| result.get(key).and_then(Value::as_u64).unwrap_or_default();
+    Some(conflict_error(
+        task_id,
+        field("expected_revision"),
+        field("current_revision"),
+    ))
+}
+
+fn conflict_error(task_id: &str, expected: u64, current: u64) -> CatalogError {
+    CatalogError::new(ErrorCode::RevisionConflict)
+        .with("task_id", task_id)
+        .with("expected", expected)
+        .with("current", current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expect_and_check() {
+        let mut params = json!({ "task": "TASK-001" });
+        expect(&mut params, None);
+        assert!(params.get("expected_revision").is_none());
+        expect(&mut params, Some(3));
+        assert_eq!(params["expected_revision"], 3);
+
+        let task = json!({ "id": "TASK-001", "revision": 4 });
+        assert_eq!(of(&task), Some(4));
+        assert!(check("TASK-001", &task, None).is_none());
+        assert!(check("TASK-001", &task, Some(4)).is_none());
+        let stale = check("TASK-001", &task, Some(3)).unwrap();
+        assert_eq!(stale.code, ErrorCode::RevisionConflict);
+        assert_eq!(stale.params["current"], 4);
+    }
+
+    #[test]
+    fn test_conflict_from_backend_mismatch() {
+        let error = json!({ "code": "REVISION_MISMATCH", "message": "revision не совпадает" });
+        let result = json!({ "task": "TASK-001", "expected_revision": 2, "current_revision": 5 });
+        let conflict = conflict(Some(&error), &result).unwrap();
+        assert_eq!(conflict.code, ErrorCode::RevisionConflict);
+        assert_eq!(conflict.params["task_id"], "TASK-001");
+        assert_eq!(conflict.params["expected"], 2);
+        assert_eq!(conflict.params["current"], 5);
+
+        let other = json!({ "code": "NOT_FOUND", "message": "Не найдено" });
+        assert!(super::conflict(Some(&other), &result).is_none());
+        assert!(super::conflict(None, &result).is_none());
+    }
+}
@@ -0,0 +1,579 @@
+//! Full-text task search
+//!
+//! An inverted index over each task's title, description and step progress
+//! notes, built from the full task list. Words are split on anything that
+//! isn't a letter or digit, lowercased and lightly stemmed (`fixes`,
+//! `fixed` and `fixing` are all `fix`). Hits are ranked by the weight of the
+//! fields they match in (titles count most) times how rare each term is,
+//! and carry the ranges to highlight.
+//!
+//! The index is built off the async workers and stamped with the read
+//! cache's generation, which every mutation and storage change bumps; a
+//! stale or missing index answers with [`substring`] instead (the title/id
+//! filter the list view uses) while a new one is built.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::backend::task_str;
+
+/// Hits returned when the caller doesn't set a limit
+pub const DEFAULT_LIMIT: usize = 20;
+
+/// Field weights: a title match outranks several description matches
+const TITLE_WEIGHT: f64 = 3.0;
+const DESCRIPTION_WEIGHT: f64 = 1.0;
+const NOTE_WEIGHT: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchSource {
+    Index,
+    Fallback,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchField {
+    Title,
+    Description,
+    Note,
+}
+
+impl SearchField {
+    fn weight(self) -> f64 {
+        match self {
+            SearchField::Title => TITLE_WEIGHT,
+            SearchField::Description => DESCRIPTION_WEIGHT,
+            SearchField::Note => NOTE_WEIGHT,
+        }
+    }
+}
+
+/// A field that matched, with the ranges to highlight as `[start, end)` in
+/// UTF-16 code units (how JavaScript strings index)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldMatch {
+    pub field: SearchField,
+    pub text: String,
+    pub ranges: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub task_id: String,
+    pub title: String,
+    pub score: f64,
+    pub matches: Vec<FieldMatch>,
+}
+
+/// A word of a text: its index term and where it is
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub term: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// `word` without a common English suffix (ASCII words only)
+pub fn stem(word: &str) -> String {
+    if word.len() < 4 || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+        return word.to_string();
+    }
+    let stem = if let Some(stem) = word.strip_suffix("ies") {
+        return format!("{}y", stem);
+    } else if let Some(stem) = word.strip_suffix("ing").filter(|s| s.len() >= 3) {
+        stem
+    } else if let Some(stem) = word.strip_suffix("ed").filter(|s| s.len() >= 3) {
+        stem
+    } else if let Some(stem) = word.strip_suffix("es").filter(|s| {
+        ["s", "x", "z", "ch", "sh"]
+            .iter()
+            .any(|end| s.ends_with(end))
+    }) {
+        stem
+    } else if let Some(stem) = word.strip_suffix('s').filter(|s| !s.ends_with('s')) {
+        stem
+    } else {
+        word
+    };
+    // `cache`, `cached` and `caching` meet at `cach`
+    stem.strip_suffix('e')
+        .filter(|s| s.len() >= 3)
+        .unwrap_or(stem)
+        .to_string()
+}
+
+/// Words of `text`, lowercased and stemmed, with UTF-16 offsets
+pub fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let (mut start, mut offset) = (0, 0);
+    for c in text.chars().chain(std::iter::once(' ')) {
+        if c.is_alphanumeric() {
+            if word.is_empty() {
+                start = offset;
+            }
+            word.extend(c.to_lowercase());
+        } else if !word.is_empty() {
+            tokens.push(Token {
+                term: stem(&word),
+                start,
+                end: offset,
+            });
+            word.clear();
+        }
+        offset += c.len_utf16();
+    }
+    tokens
+}
+
+/// Searchable texts of a task: title, description, then progress notes of
+/// its steps (nested ones included)
+fn fields(task: &Value) -> Vec<(SearchField, String)> {
+    let mut out = Vec::new();
+    for (field, key) in [
+        (SearchField::Title, "title"),
+        (SearchField::Description, "description"),
+    ] {
+        if let Some(text) = task_str(task, key).filter(|t| !t.trim().is_empty()) {
+            out.push((field, text.to_string()));
+        }
+    }
+    let mut steps: Vec<&Value> = task
+        .get("steps")
+        .and_then(Value::as_array)
+        .map(|s| s.iter().collect())
+        .unwrap_or_default();
+    while let Some(step) = steps.pop() {
+        let notes = step.get("progress_notes").and_then(Value::as_array);
+        for note in notes.into_iter().flatten().filter_map(Value::as_str) {
+            if !note.trim().is_empty() {
+                out.push((SearchField::Note, note.to_string()));
+            }
+        }
+        if let Some(nested) = step.get("steps").and_then(Value::as_array) {
+            steps.extend(nested);
+        }
+    }
+    out
+}
+
+struct Doc {
+    task_id: String,
+    title: String,
+    fields: Vec<(SearchField, String)>,
+}
+
+/// One occurrence of a term
+struct Posting {
+    doc: u32,
+    field: u32,
+    start: u32,
+    end: u32,
+}
+
+/// A document's matches while searching
+#[derive(Default)]
+struct Found {
+    score: f64,
+    /// Query terms matched
+    terms: usize,
+    /// field -> ranges
+    ranges: HashMap<u32, Vec<(usize, usize)>>,
+}
+
+pub struct SearchIndex {
+    /// Read cache generation the tasks were listed at
+    pub generation: u64,
+    docs: Vec<Doc>,
+    postings: HashMap<String, Vec<Posting>>,
+}
+
+impl SearchIndex {
+    pub fn build(tasks: &[Value], generation: u64) -> Self {
+        let mut index = SearchIndex {
+            generation,
+            docs: Vec::with_capacity(tasks.len()),
+            postings: HashMap::new(),
+        };
+        for task in tasks {
+            let Some(task_id) = task_str(task, "id") else {
+                continue;
+            };
+            let doc = index.docs.len() as u32;
+            let fields = fields(task);
+            for (field, (_, text)) in fields.iter().enumerate() {
+                for token in tokenize(text) {
+                    index.postings.entry(token.term).or_default().push(Posting {
+                        doc,
+                        field: field as u32,
+                        start: token.start as u32,
+                        end: token.end as u32,
+                    });
+                }
+            }
+            index.docs.push(Doc {
+                task_id: task_id.to_string(),
+                title: task_str(task, "title").unwrap_or_default().to_string(),
+                fields,
+            });
+        }
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    /// Best `limit` tasks for `query`. Every query term adds its weighted,
+    /// IDF-scaled matches; tasks matching only some terms are scaled down
+    /// by the share they match.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let mut terms: Vec<String> = tokenize(query).into_iter().map(|t| t.term).collect();
+        terms.sort();
+        terms.dedup();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let total = self.docs.len() as f64;
+        let mut found: HashMap<u32, Found> = HashMap::new();
+        for term in &terms {
+            let Some(postings) = self.postings.get(term) else {
+                continue;
+            };
+            let mut docs: Vec<u32> = postings.iter().map(|p| p.doc).collect();
+            docs.dedup();
+            let idf = (1.0 + total / docs.len() as f64).ln();
+            let mut last_doc = None;
+            for posting in postings {
+                let entry = found.entry(posting.doc).or_default();
+                if last_doc != Some(posting.doc) {
+                    entry.terms += 1;
+                    last_doc = Some(posting.doc);
+                }
+                let doc = &self.docs[posting.doc as usize];
+                entry.score += idf * doc.fields[posting.field as usize].0.weight();
+                entry
+                    .ranges
+                    .entry(posting.field)
+                    .or_default()
+                    .push((posting.start as usize, posting.end as usize));
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = found
+            .into_iter()
+            .map(|(doc, found)| {
+                let doc = &self.docs[doc as usize];
+                let mut fields: Vec<(u32, Vec<(usize, usize)>)> =
+                    found.ranges.into_iter().collect();
+                fields.sort_by_key(|(field, _)| *field);
+                SearchHit {
+                    task_id: doc.task_id.clone(),
+                    title: doc.title.clone(),
+                    score: found.score * found.terms as f64 / terms.len() as f64,
+                    matches: fields
+                        .into_iter()
+                        .map(|(field, mut ranges)| {
+                            ranges.sort();
+                            let (field, text) = &doc.fields[field as usize];
+                            FieldMatch {
+                                field: *field,
+                                text: text.clone(),
+                                ranges,
+                            }
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.task_id.cmp(&b.task_id))
+        });
+        hits.truncate(limit);
+        hits
+    }
+}
+
+/// Case-insensitive substring match on title or id, in list order (used
+/// while the index is cold)
+pub fn substring(tasks: &[Value], query: &str, limit: usize) -> Vec<SearchHit> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let utf16_len = |s: &str| s.encode_utf16().count();
+    tasks
+        .iter()
+        .filter_map(|task| {
+            let task_id = task_str(task, "id")?;
+            let title = task_str(task, "title").unwrap_or_default();
+            // Lowercase char by char, remembering which title char each
+            // lowered chunk came from: lowercasing can change byte lengths
+            let mut lowered = String::with_capacity(title.len());
+            let mut chunks: Vec<(usize, usize, usize)> = Vec::new();
+            for (at, c) in title.char_indices() {
+                chunks.push((lowered.len(), at, at + c.len_utf8()));
+                lowered.extend(c.to_lowercase());
+            }
+            // The title char a lowered byte offset falls in
+            let chunk =
+                |offset: usize| chunks[chunks.partition_point(|&(l, _, _)| l <= offset) - 1];
+            let ranges: Vec<(usize, usize)> = lowered
+                .match_indices(&query)
+                .map(|(at, found)| {
+                    let (_, from, _) = chunk(at);
+                    let (_, _, to) = chunk(at + found.len() - 1);
+                    let start = utf16_len(&title[..from]);
+                    (start, start + utf16_len(&title[from..to]))
+                })
+                .collect();
+            let in_title = lowered.contains(&query);
+            if !in_title && !task_id.to_lowercase().contains(&query) {
+                return None;
+            }
+            Some(SearchHit {
+                task_id: task_id.to_string(),
+                title: title.to_string(),
+                score: 0.0,
+                matches: if in_title {
+                    vec![FieldMatch {
+                        field: SearchField::Title,
+                        text: title.to_string(),
+                        ranges,
+                    }]
+                } else {
+                    Vec::new()
+                },
+            })
+        })
+        .take(limit)
+        .collect()
+}
+
+/// The current index and the generation being built, if any
+#[derive(Default)]
+pub struct IndexSlot {
+    index: Option<Arc<SearchIndex>>,
+    building: Option<u64>,
+}
+
+impl IndexSlot {
+    /// The index, if it was built at `generation`
+    pub fn fresh(&self, generation: u64) -> Option<Arc<SearchIndex>> {
+        self.index
+            .clone()
+            .filter(|index| index.generation == generation)
+    }
+
+    /// Claim the build for `generation`; false when one is already running
+    pub fn start_build(&mut self, generation: u64) -> bool {
+        if self.building == Some(generation) {
+            return false;
+        }
+        self.building = Some(generation);
+        true
+    }
+
+    /// Store a finished index (or clear the claim after a failed build)
+    pub fn finish(&mut self, generation: u64, index: Option<SearchIndex>) {
+        if self.building == Some(generation) {
+            self.building = None;
+        }
+        if let Some(index) = index {
+            let newer = self
+                .index
+                .as_ref()
+                .is_some_and(|current| current.generation > index.generation);
+            if !newer {
+                self.index = Some(Arc::new(index));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Instant;
+
+    fn tasks() -> Vec<Value> {
+        vec![
+            json!({"id": "TASK-001", "title": "Fix login redirect",
+                   "description": "Users bounce back to the login page after SSO."}),
+            json!({"id": "TASK-002", "title": "Cache auth tokens",
+                   "description": "Caching avoids a round trip on every redirect.",
+                   "steps": [{"title": "Measure", "progress_notes": ["Cached tokens expire too early"],
+                              "steps": [{"progress_notes": ["Fixed the expiry caching"]}]}]}),
+            json!({"id": "TASK-003", "title": "Ёлка: праздничная тема", "description": ""}),
+            json!({"title": "No id, not indexed"}),
+        ]
+    }
+
+    #[test]
+    fn test_stem() {
+        for (word, stemmed) in [
+            ("fixes", "fix"),
+            ("fixed", "fix"),
+            ("fixing", "fix"),
+            ("fix", "fix"),
+            ("cache", "cach"),
+            ("caches", "cach"),
+            ("cached", "cach"),
+            ("caching", "cach"),
+            ("stories", "story"),
+            ("class", "class"),
+            ("tokens", "token"),
+            ("bring", "bring"),
+            ("ёлки", "ёлки"),
+            ("v2", "v2"),
+        ] {
+            assert_eq!(stem(word), stemmed, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_tokenize_offsets_are_utf16() {
+        let tokens = tokenize("Fix 🔐 Ёлка, re-login!");
+        let terms: Vec<(&str, usize, usize)> = tokens
+            .iter()
+            .map(|t| (t.term.as_str(), t.start, t.end))
+            .collect();
+        // The emoji is two UTF-16 units
+        assert_eq!(
+            terms,
+            [
+                ("fix", 0, 3),
+                ("ёлка", 7, 11),
+                ("re", 13, 15),
+                ("login", 16, 21)
+            ]
+        );
+        assert!(tokenize("  ,.;  ").is_empty());
+    }
+
+    #[test]
+    fn test_search_ranks_and_highlights() {
+        let index = SearchIndex::build(&tasks(), 7);
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.generation, 7);
+
+        // Title matches outrank description ones
+        let hits = index.search("redirect", 10);
+        let ids: Vec<&str> = hits.iter().map(|h| h.task_id.as_str()).collect();
+        assert_eq!(ids, ["TASK-001", "TASK-002"]);
+        assert_eq!(hits[0].matches[0].field, SearchField::Title);
+        assert_eq!(hits[0].matches[0].ranges, [(10, 18)]);
+
+        // Stemmed terms reach nested progress notes
+        let hits = index.search("caches", 10);
+        assert_eq!(hits.len(), 1);
+        let fields: Vec<(SearchField, usize)> = hits[0]
+            .matches
+            .iter()
+            .map(|m| (m.field, m.ranges.len()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                (SearchField::Title, 1),
+                (SearchField::Description, 1),
+                (SearchField::Note, 1),
+                (SearchField::Note, 1),
+            ]
+        );
+        let note = hits[0]
+            .matches
+            .iter()
+            .find(|m| m.text.starts_with("Cached"))
+            .unwrap();
+        assert_eq!(note.ranges, [(0, 6)]);
+
+        // Matching every term beats matching one
+        let hits = index.search("login tokens", 10);
+        assert_eq!(hits.len(), 2);
+        assert!(hits[0].score > 0.0);
+        assert_eq!(index.search("праздничная", 10)[0].task_id, "TASK-003");
+        assert!(index.search("nothing-here", 10).is_empty());
+        assert!(index.search("   ", 10).is_empty());
+        assert_eq!(index.search("redirect", 1).len(), 1);
+    }
+
+    #[test]
+    fn test_substring_fallback() {
+        let hits = substring(&tasks(), "LOGIN", 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].matches[0].ranges, [(4, 9)]);
+        // Ids match too, without highlights
+        let hits = substring(&tasks(), "task-00", 2);
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|h| h.matches.is_empty()));
+        assert_eq!(
+            substring(&tasks(), "ёлка", 10)[0].matches[0].ranges,
+            [(0, 4)]
+        );
+        assert!(substring(&tasks(), " ", 10).is_empty());
+        // Lowercasing keeps the byte length but moves char boundaries
+        let tasks = [json!({"id": "TASK-004", "title": "\u{212A}\u{130}\u{130} x"})];
+        assert_eq!(
+            substring(&tasks, "i", 10)[0].matches[0].ranges,
+            [(1, 2), (2, 3)]
+        );
+        assert_eq!(substring(&tasks, "k", 10)[0].matches[0].ranges, [(0, 1)]);
+    }
+
+    #[test]
+    fn test_index_slot_generations() {
+        let mut slot = IndexSlot::default();
+        assert!(slot.fresh(0).is_none());
+        assert!(slot.start_build(1));
+        assert!(!slot.start_build(1));
+        slot.finish(1, Some(SearchIndex::build(&tasks(), 1)));
+        assert_eq!(slot.fresh(1).unwrap().len(), 3);
+        // A mutation bumped the generation: stale until rebuilt
+        assert!(slot.fresh(2).is_none());
+        assert!(slot.start_build(2));
+        // An older build finishing late doesn't replace a newer index
+        slot.finish(2, Some(SearchIndex::build(&[], 2)));
+        slot.finish(1, Some(SearchIndex::build(&tasks(), 1)));
+        assert_eq!(slot.fresh(2).unwrap().len(), 0);
+        // A failed build releases the claim
+        assert!(slot.start_build(3));
+        slot.finish(3, None);
+        assert!(slot.start_build(3));
+    }
+
+    /// `cargo test --release -- --ignored --nocapture index_build` prints the
+    /// build time
+    #[test]
+    #[ignore = "timing test, run explicitly in release"]
+    fn test_index_build_time() {
+        let tasks: Vec<Value> = (0..3_000)
+            .map(|i| {
+                json!({
+                    "id": format!("TASK-{:04}", i),
+                    "title": format!("Refactor module {} for the caching layer", i),
+                    "description": "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(8),
+                    "steps": [{"progress_notes": ["Measured the baseline", "Fixed flaky test"]}],
+                })
+            })
+            .collect();
+        let started = Instant::now();
+        let index = SearchIndex::build(&tasks, 1);
+        let elapsed = started.elapsed();
+        println!("indexed {} tasks in {:?}", index.len(), elapsed);
+        assert!(elapsed.as_millis() < 200, "{:?}", elapsed);
+    }
+}
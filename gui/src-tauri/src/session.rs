@@ -0,0 +1,183 @@
+//! Session state
+//!
+//! Where the user was when the app closed (project, namespace, task, view),
+//! in `session.json` in the app data dir. The frontend reports navigation
+//! with `session_update`; changes are saved once they settle, so clicking
+//! through tasks doesn't write on every step. `session_get` checks the saved
+//! state against what exists now before the frontend restores it.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::sidecar;
+use crate::AppState;
+
+/// File name of the session inside the app data dir
+pub const SESSION_FILE: &str = "session.json";
+/// Quiet period after the last update before writing the file
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, thiserror::Error)]
+#[error("Session update must be an object of project, namespace, task_id and view (text or null)")]
+pub struct SessionPatchError;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Session {
+    /// Project root
+    pub project: Option<String>,
+    pub namespace: Option<String>,
+    /// Last viewed task
+    pub task_id: Option<String>,
+    /// Frontend view (`board`, `list`, ...)
+    pub view: Option<String>,
+}
+
+impl Session {
+    /// `patch` applied: text sets a key, `null` clears it, missing keys stay
+    pub fn merged(&self, patch: &Value) -> Result<Session, SessionPatchError> {
+        let Value::Object(patch) = patch else {
+            return Err(SessionPatchError);
+        };
+        let Ok(Value::Object(mut merged)) = serde_json::to_value(self) else {
+            return Err(SessionPatchError);
+        };
+        for (key, value) in patch {
+            match value {
+                Value::Null => {
+                    merged.remove(key);
+                }
+                Value::String(text) if !text.trim().is_empty() => {
+                    merged.insert(key.clone(), Value::String(text.trim().to_string()));
+                }
+                _ => return Err(SessionPatchError),
+            }
+        }
+        serde_json::from_value(Value::Object(merged)).map_err(|_| SessionPatchError)
+    }
+}
+
+/// The saved session (kept in `AppState`)
+pub struct SessionStore {
+    path: PathBuf,
+    saved: Mutex<Session>,
+    /// Bumped on every change; a pending save only writes if still current
+    generation: AtomicU64,
+}
+
+impl SessionStore {
+    /// Read the file (missing or unreadable: an empty session)
+    pub fn load(path: PathBuf) -> Self {
+        let saved = sidecar::read_json(&path).unwrap_or_else(|e| {
+            log::warn!("Ignoring saved session {:?}: {:#}", path, e);
+            Session::default()
+        });
+        Self {
+            path,
+            saved: Mutex::new(saved),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self) -> Session {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Session> {
+        self.saved.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replace the session; returns the generation a delayed save should
+    /// match (`None` when nothing changed)
+    pub fn set(&self, session: Session) -> Option<u64> {
+        let mut saved = self.lock();
+        if *saved == session {
+            return None;
+        }
+        *saved = session;
+        Some(self.generation.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        sidecar::write_json(&self.path, &*self.lock())
+    }
+
+    /// Forget the session (the file too)
+    pub fn clear(&self) -> Result<()> {
+        *self.lock() = Session::default();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.save()
+    }
+}
+
+/// Save the session once `generation` has stayed current for a while
+pub fn save_later<R: Runtime>(app: &AppHandle<R>, generation: u64) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DELAY).await;
+        let Some(state) = app.try_state::<AppState>() else {
+            return;
+        };
+        if state.session.generation.load(Ordering::SeqCst) == generation {
+            if let Err(e) = state.session.save() {
+                log::warn!("Failed to save session: {:#}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_sets_clears_and_rejects() {
+        let session = Session::default()
+            .merged(&json!({ "project": "/work/app", "task_id": " TASK-042 ", "view": "board" }))
+            .unwrap();
+        assert_eq!(session.task_id.as_deref(), Some("TASK-042"));
+
+        let next = session
+            .merged(&json!({ "task_id": null, "view": "list" }))
+            .unwrap();
+        assert_eq!(next.project.as_deref(), Some("/work/app"));
+        assert_eq!(next.task_id, None);
+        assert_eq!(next.view.as_deref(), Some("list"));
+
+        assert!(session.merged(&json!({ "tab": "x" })).is_err());
+        assert!(session.merged(&json!({ "view": 3 })).is_err());
+        assert!(session.merged(&json!(["board"])).is_err());
+    }
+
+    #[test]
+    fn test_store_counts_changes_and_persists() {
+        let dir = std::env::temp_dir().join(format!("apply-task-session-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let store = SessionStore::load(dir.join(SESSION_FILE));
+        assert_eq!(store.get(), Session::default());
+
+        let session = Session {
+            view: Some("board".into()),
+            ..Session::default()
+        };
+        assert_eq!(store.set(session.clone()), Some(1));
+        assert_eq!(store.set(session.clone()), None);
+        store.save().unwrap();
+        assert_eq!(SessionStore::load(dir.join(SESSION_FILE)).get(), session);
+
+        store.clear().unwrap();
+        assert_eq!(
+            SessionStore::load(dir.join(SESSION_FILE)).get(),
+            Session::default()
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
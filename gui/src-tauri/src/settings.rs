@@ -0,0 +1,498 @@
+//! GUI settings file
+//!
+//! `settings.json` in the app config directory. Keys this version doesn't
+//! know about are preserved on save; a file that fails to parse is moved
+//! aside and replaced by defaults.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::audit;
+use crate::intents;
+use crate::notifications::NotificationPrefs;
+use crate::python::{ContainerConfig, EnvPolicy, Framing, SshConfig, DEFAULT_MAX_MESSAGE_BYTES};
+use crate::rate_limit;
+use crate::read_cache;
+use crate::sidecar;
+
+/// File name of the settings document inside the app config dir
+pub const SETTINGS_FILE: &str = "settings.json";
+/// Shortest caller timeout honored for a tool call
+pub const MIN_CALL_TIMEOUT_MS: u64 = 500;
+/// Longest caller timeout for tools without a `tool_timeout_max_ms` entry
+pub const DEFAULT_MAX_CALL_TIMEOUT_MS: u64 = 30_000;
+/// Largest accepted `tool_timeout_max_ms` value
+pub const MAX_CALL_TIMEOUT_MS: u64 = 600_000;
+/// Largest accepted `cache_ttl_ms` and `context_cache_ttl_ms`
+pub const MAX_CACHE_TTL_MS: u64 = 600_000;
+/// Smallest accepted `max_message_bytes`
+pub const MIN_MESSAGE_BYTES: usize = 64 * 1024;
+
+/// Rejected settings patches
+#[derive(Debug, thiserror::Error)]
+pub enum SettingsError {
+    #[error("Settings patch must be an object")]
+    NotAnObject,
+    #[error("Invalid settings patch")]
+    InvalidPatch(#[source] serde_json::Error),
+    #[error("{setting} must be at most {max}")]
+    CacheTtl { setting: &'static str, max: u64 },
+    #[error("tool_timeout_max_ms.{tool} must be between {min} and {max}")]
+    ToolTimeout { tool: String, min: u64, max: u64 },
+    #[error("python_path must not be empty")]
+    EmptyPythonPath,
+    #[error("container.image must not be empty")]
+    EmptyContainerImage,
+    #[error("ssh.host and ssh.path must not be empty")]
+    IncompleteSsh,
+    #[error("ssh.host must not start with '-'")]
+    SshHostOption,
+    #[error("max_message_bytes must be at least {min}")]
+    MessageLimit { min: usize },
+    #[error("rate_limits_per_min.{tool} must be at most {max}")]
+    RateLimit { tool: String, max: u32 },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// User intent aliases: `"ship" -> "tasks_complete"`
+    pub intent_aliases: BTreeMap<String, String>,
+    /// Adds debug fields (payload sizes, ...) to command responses
+    pub developer_mode: bool,
+    /// Delete immediately instead of the two-step token protocol
+    pub skip_confirmation: bool,
+    /// Create tasks without first looking for ones with a similar title
+    /// (for high-volume automation)
+    pub skip_duplicate_check: bool,
+    /// Refuse every mutation (screen sharing a planning session)
+    pub read_only: bool,
+    /// Read cache TTL (default 5 s, 0 disables caching)
+    pub cache_ttl_ms: Option<u64>,
+    /// TTL of `tasks_context` entries (default 30 s, 0 disables caching them)
+    pub context_cache_ttl_ms: Option<u64>,
+    /// Queue mutations while the backend is unreachable and replay them later
+    pub offline_queue: bool,
+    /// Upper bound for caller `timeout_ms`, per tool
+    pub tool_timeout_max_ms: BTreeMap<String, u64>,
+    /// Calls per minute of `ai_intent` tools, verification and background
+    /// jobs, per tool (default 30 for mutating tools, unlimited for reads;
+    /// 0 means unlimited)
+    pub rate_limits_per_min: BTreeMap<String, u32>,
+    /// Months of audit log kept (default 12, 0 keeps all)
+    pub audit_retention_months: Option<u32>,
+    /// Python executable for the backend (before `PYTHON_PATH`/`APPLY_TASK_PYTHON`)
+    pub python_path: Option<String>,
+    /// Run the backend in a container instead (image, extra mounts and
+    /// `docker run` arguments)
+    pub container: Option<ContainerConfig>,
+    /// Run the backend on another host over `ssh -T` instead (host, user,
+    /// remote project path and command); skips project root detection
+    pub ssh: Option<SshConfig>,
+    /// Namespace the frontend selects at startup
+    pub default_namespace: Option<String>,
+    /// Closing the main window hides it to the tray instead of quitting
+    pub minimize_to_tray: bool,
+    /// Which OS notifications are sent
+    pub notifications: NotificationPrefs,
+    /// Global quick-add shortcut (unset: the default, empty: disabled)
+    pub quick_add_shortcut: Option<String>,
+    /// Global shortcut toggling pause all / resume all (unset or empty: none)
+    pub pause_all_shortcut: Option<String>,
+    /// Backend RSS that emits `backend-resource-warning` (unset: 1024, 0: never)
+    pub backend_rss_warn_mb: Option<u64>,
+    /// Backend RSS it is stopped over, after a few samples in a row
+    /// (unset or 0: no cap)
+    pub backend_rss_cap_mb: Option<u64>,
+    /// Count command and intent usage locally (`usage-metrics.json`)
+    pub analytics_enabled: bool,
+    /// Look for a newer GUI release at startup (default on)
+    pub update_check_enabled: Option<bool>,
+    /// Reject backend lines that bend JSON-RPC (no `jsonrpc`, string ids)
+    pub strict_protocol: bool,
+    /// Start the backend with a minimal environment instead of the GUI's
+    /// whole one (`PATH`, `HOME`, `PYTHONPATH`, locale, `APPLY_TASK_*`)
+    pub isolated_env: bool,
+    /// Further variables an isolated backend gets (`PYENV_ROOT`, ...)
+    pub isolated_env_allowlist: Vec<String>,
+    /// Message framing on the backend's stdio (`auto`, `newline`,
+    /// `content_length`)
+    pub stdio_framing: Framing,
+    /// Backend messages larger than this are skipped and their request
+    /// fails (default 16 MB)
+    pub max_message_bytes: Option<usize>,
+    /// Commands `run_verification_command` may run, by project directory
+    /// (an entry ending in ` *` also allows further arguments)
+    pub verification_commands: BTreeMap<String, Vec<String>>,
+    #[serde(flatten)]
+    pub rest: Map<String, Value>,
+}
+
+pub fn settings_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(SETTINGS_FILE)
+}
+
+/// Copy a `settings.json` left in `legacy_dir` by older versions
+pub fn migrate_legacy(config_dir: &Path, legacy_dir: &Path) {
+    let path = settings_path(config_dir);
+    let legacy = settings_path(legacy_dir);
+    if path.exists() || !legacy.exists() || path == legacy {
+        return;
+    }
+    let copied = std::fs::create_dir_all(config_dir).and_then(|_| std::fs::copy(&legacy, &path));
+    if let Err(e) = copied {
+        log::warn!("Failed to migrate settings from {:?}: {}", legacy, e);
+    }
+}
+
+impl Settings {
+    /// Load settings; a file that does not parse is backed up and replaced
+    /// by defaults, one that cannot be read is left alone (defaults for this
+    /// run only)
+    pub fn load(config_dir: &Path) -> Self {
+        let path = settings_path(config_dir);
+        match sidecar::read_json(&path) {
+            Ok(settings) => settings,
+            Err(e) if e.downcast_ref::<serde_json::Error>().is_none() => {
+                log::warn!("Unreadable settings ({:#}), using defaults", e);
+                Self::default()
+            }
+            Err(e) => {
+                let backup = path.with_extension(format!(
+                    "json.corrupt-{}",
+                    chrono::Utc::now().format("%Y%m%d%H%M%S")
+                ));
+                log::warn!("Broken settings ({:#}), moved to {:?}", e, backup);
+                if let Err(e) = std::fs::rename(&path, &backup) {
+                    log::warn!("Failed to back up settings: {}", e);
+                }
+                Self::default()
+            }
+        }
+    }
+
+    /// Apply a patch: top-level keys replace the current value, `null` resets
+    pub fn merged(&self, patch: &Value) -> Result<Settings> {
+        let Value::Object(patch) = patch else {
+            return Err(SettingsError::NotAnObject.into());
+        };
+        let Value::Object(mut merged) = serde_json::to_value(self)? else {
+            bail!("Settings did not serialize to an object");
+        };
+        for (key, value) in patch {
+            if value.is_null() {
+                merged.remove(key);
+            } else {
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+        let settings: Settings =
+            serde_json::from_value(Value::Object(merged)).map_err(SettingsError::InvalidPatch)?;
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        for (setting, ttl) in [
+            ("cache_ttl_ms", self.cache_ttl_ms),
+            ("context_cache_ttl_ms", self.context_cache_ttl_ms),
+        ] {
+            if ttl.is_some_and(|ttl| ttl > MAX_CACHE_TTL_MS) {
+                return Err(SettingsError::CacheTtl {
+                    setting,
+                    max: MAX_CACHE_TTL_MS,
+                }
+                .into());
+            }
+        }
+        for (tool, ms) in &self.tool_timeout_max_ms {
+            if !(MIN_CALL_TIMEOUT_MS..=MAX_CALL_TIMEOUT_MS).contains(ms) {
+                return Err(SettingsError::ToolTimeout {
+                    tool: tool.clone(),
+                    min: MIN_CALL_TIMEOUT_MS,
+                    max: MAX_CALL_TIMEOUT_MS,
+                }
+                .into());
+            }
+        }
+        if let Some((tool, _)) = self
+            .rate_limits_per_min
+            .iter()
+            .find(|(_, n)| **n > rate_limit::MAX_PER_MIN)
+        {
+            return Err(SettingsError::RateLimit {
+                tool: tool.clone(),
+                max: rate_limit::MAX_PER_MIN,
+            }
+            .into());
+        }
+        if self
+            .python_path
+            .as_deref()
+            .is_some_and(|p| p.trim().is_empty())
+        {
+            return Err(SettingsError::EmptyPythonPath.into());
+        }
+        if self
+            .container
+            .as_ref()
+            .is_some_and(|c| c.image.trim().is_empty())
+        {
+            return Err(SettingsError::EmptyContainerImage.into());
+        }
+        if self
+            .ssh
+            .as_ref()
+            .is_some_and(|s| s.host.trim().is_empty() || s.path.trim().is_empty())
+        {
+            return Err(SettingsError::IncompleteSsh.into());
+        }
+        // ssh would read such a host as an option (`-oProxyCommand=...`)
+        if self
+            .ssh
+            .as_ref()
+            .is_some_and(|s| s.host.trim().starts_with('-'))
+        {
+            return Err(SettingsError::SshHostOption.into());
+        }
+        if self
+            .max_message_bytes
+            .is_some_and(|n| n < MIN_MESSAGE_BYTES)
+        {
+            return Err(SettingsError::MessageLimit {
+                min: MIN_MESSAGE_BYTES,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Whether switching to `other` needs a backend respawn
+    pub fn spawn_changed(&self, other: &Settings) -> bool {
+        self.python_path != other.python_path
+            || self.stdio_framing != other.stdio_framing
+            || self.env_policy() != other.env_policy()
+            || self.container != other.container
+            || self.ssh != other.ssh
+    }
+
+    pub fn env_policy(&self) -> EnvPolicy {
+        EnvPolicy {
+            isolated: self.isolated_env,
+            allowlist: self.isolated_env_allowlist.clone(),
+        }
+    }
+
+    pub fn cache_ttl(&self) -> Duration {
+        self.cache_ttl_ms
+            .map(Duration::from_millis)
+            .unwrap_or(read_cache::DEFAULT_TTL)
+    }
+
+    pub fn context_cache_ttl(&self) -> Duration {
+        self.context_cache_ttl_ms
+            .map(Duration::from_millis)
+            .unwrap_or(read_cache::DEFAULT_CONTEXT_TTL)
+    }
+
+    pub fn max_message_bytes(&self) -> usize {
+        self.max_message_bytes.unwrap_or(DEFAULT_MAX_MESSAGE_BYTES)
+    }
+
+    pub fn update_check_enabled(&self) -> bool {
+        self.update_check_enabled.unwrap_or(true)
+    }
+
+    pub fn audit_retention_months(&self) -> u32 {
+        self.audit_retention_months
+            .unwrap_or(audit::DEFAULT_RETENTION_MONTHS)
+    }
+
+    /// Caller timeout for `tool`, clamped to the configured bounds
+    pub fn call_timeout(&self, tool: &str, timeout_ms: Option<u64>) -> Option<Duration> {
+        let max = self
+            .tool_timeout_max_ms
+            .get(tool)
+            .copied()
+            .unwrap_or(DEFAULT_MAX_CALL_TIMEOUT_MS)
+            .max(MIN_CALL_TIMEOUT_MS);
+        timeout_ms.map(|ms| Duration::from_millis(ms.clamp(MIN_CALL_TIMEOUT_MS, max)))
+    }
+
+    /// Calls per minute allowed for `tool` (0: unlimited)
+    pub fn rate_limit(&self, tool: &str) -> u32 {
+        match self.rate_limits_per_min.get(tool) {
+            Some(per_min) => *per_min,
+            None if intents::writes_storage(tool) => rate_limit::DEFAULT_PER_MIN,
+            None => 0,
+        }
+    }
+
+    /// Verification allowlist of the project at `root`
+    pub fn verification_commands(&self, root: &Path) -> &[String] {
+        self.verification_commands
+            .iter()
+            .find(|(dir, _)| Path::new(dir.as_str()) == root)
+            .map_or(&[], |(_, commands)| commands.as_slice())
+    }
+
+    pub fn save(&self, config_dir: &Path) -> Result<()> {
+        sidecar::write_json(&settings_path(config_dir), self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_keeps_unknown_keys() {
+        let dir = std::env::temp_dir().join(format!("apply-task-settings-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            settings_path(&dir),
+            r#"{"theme": "dark", "intent_aliases": {"ship": "tasks_complete"}}"#,
+        )
+        .unwrap();
+
+        let mut settings = Settings::load(&dir);
+        assert_eq!(settings.intent_aliases["ship"], "tasks_complete");
+        settings
+            .intent_aliases
+            .insert("split".into(), "tasks_decompose".into());
+        settings.save(&dir).unwrap();
+
+        let reloaded = Settings::load(&dir);
+        assert_eq!(reloaded.intent_aliases.len(), 2);
+        assert_eq!(reloaded.rest["theme"], "dark");
+
+        std::fs::write(settings_path(&dir), "{not json").unwrap();
+        assert!(Settings::load(&dir).intent_aliases.is_empty());
+        // The broken file is kept next to the (now missing) settings
+        let backups: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().contains(".corrupt-"))
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert!(!settings_path(&dir).exists());
+
+        // A read error is not a broken file: nothing is moved
+        std::fs::create_dir(settings_path(&dir)).unwrap();
+        assert!(Settings::load(&dir).intent_aliases.is_empty());
+        assert!(settings_path(&dir).is_dir());
+        assert_eq!(
+            std::fs::read_dir(&dir)
+                .unwrap()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_name().to_string_lossy().contains(".corrupt-"))
+                .count(),
+            1
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_patch_merges_validates_and_keeps_unknown_keys() {
+        let current: Settings = serde_json::from_value(serde_json::json!({
+            "theme": "dark",
+            "developer_mode": true,
+            "python_path": "/usr/bin/python3",
+        }))
+        .unwrap();
+
+        let patched = current
+            .merged(&serde_json::json!({"cache_ttl_ms": 1000, "python_path": null}))
+            .unwrap();
+        assert_eq!(patched.cache_ttl_ms, Some(1000));
+        assert!(patched.developer_mode);
+        assert_eq!(patched.python_path, None);
+        assert_eq!(patched.rest["theme"], "dark");
+        assert!(current.spawn_changed(&patched));
+        assert!(!patched.spawn_changed(&patched.clone()));
+        let isolated = patched
+            .merged(&serde_json::json!({"isolated_env": true}))
+            .unwrap();
+        assert!(patched.spawn_changed(&isolated));
+        let contained = patched
+            .merged(&serde_json::json!({"container": {"image": "apply-task:latest"}}))
+            .unwrap();
+        assert!(patched.spawn_changed(&contained));
+        assert_eq!(contained.container.unwrap().mounts.len(), 0);
+        let err = patched
+            .merged(&serde_json::json!({"container": {"image": " "}}))
+            .unwrap_err();
+        assert!(err.to_string().contains("container.image"));
+        let remote = patched
+            .merged(&serde_json::json!({"ssh": {"host": "dev", "path": "/srv/tasks"}}))
+            .unwrap();
+        assert!(patched.spawn_changed(&remote));
+        assert!(patched
+            .merged(&serde_json::json!({"ssh": {"host": "dev"}}))
+            .is_err());
+        let err = patched
+            .merged(&serde_json::json!({"ssh": {"host": " -oProxyCommand=sh", "path": "/srv"}}))
+            .unwrap_err();
+        assert!(err.to_string().contains("ssh.host"));
+
+        let too_short = serde_json::json!({"tool_timeout_max_ms": {"tasks_stats": 10}});
+        assert!(current.merged(&too_short).is_err());
+        assert!(current
+            .merged(&serde_json::json!({"cache_ttl_ms": "x"}))
+            .is_err());
+        let err = current
+            .merged(&serde_json::json!({"context_cache_ttl_ms": 3_600_000}))
+            .unwrap_err();
+        assert!(
+            err.to_string().starts_with("context_cache_ttl_ms"),
+            "{}",
+            err
+        );
+        assert!(current.merged(&serde_json::json!([1])).is_err());
+    }
+
+    #[test]
+    fn test_call_timeout_is_clamped() {
+        let mut settings = Settings::default();
+        settings
+            .tool_timeout_max_ms
+            .insert("tasks_stats".into(), 2_000);
+        let ms = |tool, ms| settings.call_timeout(tool, ms).map(|d| d.as_millis());
+
+        assert_eq!(ms("tasks_stats", None), None);
+        assert_eq!(ms("tasks_stats", Some(10)), Some(500));
+        assert_eq!(ms("tasks_stats", Some(60_000)), Some(2_000));
+        assert_eq!(ms("tasks_context", Some(60_000)), Some(30_000));
+        assert_eq!(ms("tasks_context", Some(1_200)), Some(1_200));
+    }
+
+    #[test]
+    fn test_rate_limit_defaults_to_mutating_tools() {
+        let mut settings = Settings::default();
+        assert_eq!(
+            settings.rate_limit("tasks_decompose"),
+            rate_limit::DEFAULT_PER_MIN
+        );
+        assert_eq!(settings.rate_limit("tasks_context"), 0);
+        settings
+            .rate_limits_per_min
+            .insert("tasks_context".into(), 5);
+        settings
+            .rate_limits_per_min
+            .insert("tasks_verify".into(), 0);
+        assert_eq!(settings.rate_limit("tasks_context"), 5);
+        assert_eq!(settings.rate_limit("tasks_verify"), 0);
+
+        settings
+            .rate_limits_per_min
+            .insert("tasks_define".into(), rate_limit::MAX_PER_MIN + 1);
+        assert!(settings.validate().is_err());
+    }
+}
@@ -0,0 +1,178 @@
+//! Per-project sidecar storage
+//!
+//! GUI-only data (timers, due dates, ...) that the Python backend doesn't know
+//! about lives in the app data directory, one folder per project root.
+
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// FNV-1a of `bytes`: stable across Rust versions, unlike DefaultHasher,
+/// so it can be persisted
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Stable key for a project root: `<dir name>-<fnv1a hash of full path>`
+pub fn project_key(project_root: &Path) -> String {
+    let root = project_root
+        .canonicalize()
+        .unwrap_or_else(|_| project_root.to_path_buf());
+    let hash = fnv1a(root.to_string_lossy().as_bytes());
+
+    let name: String = root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if name.is_empty() {
+        format!("{:016x}", hash)
+    } else {
+        format!("{}-{:016x}", name, hash)
+    }
+}
+
+/// Directory holding sidecar files for a project
+pub fn project_dir(data_dir: &Path, project_root: &Path) -> PathBuf {
+    data_dir.join("projects").join(project_key(project_root))
+}
+
+/// Append one record as a JSON line
+pub fn append_jsonl<T: Serialize>(path: &Path, record: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {:?}", parent))?;
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {:?}", path))?;
+
+    let line = serde_json::to_string(record)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Read all records from a JSONL file (missing file = empty, bad lines are skipped)
+pub fn read_jsonl<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let file = fs::File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let mut records = Vec::new();
+
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(record) => records.push(record),
+            Err(e) => log::warn!("Skipping malformed line {} in {:?}: {}", index + 1, path, e),
+        }
+    }
+
+    Ok(records)
+}
+
+/// Read a JSON document, falling back to `T::default()` when the file is missing
+pub fn read_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {:?}", path))
+}
+
+/// Write a JSON document atomically (temp file + rename)
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {:?}", parent))?;
+    }
+
+    let tmp = path.with_extension("json.tmp");
+    let content = serde_json::to_string_pretty(value)?;
+    fs::write(&tmp, content).with_context(|| format!("Failed to write {:?}", tmp))?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to replace {:?}", path))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "apply-task-sidecar-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_project_key_is_stable() {
+        let a = project_key(Path::new("/nonexistent/work/my project"));
+        let b = project_key(Path::new("/nonexistent/work/my project"));
+        assert_eq!(a, b);
+        assert!(a.starts_with("my_project-"));
+        assert_ne!(a, project_key(Path::new("/nonexistent/other/my project")));
+    }
+
+    #[test]
+    fn test_jsonl_roundtrip_skips_bad_lines() {
+        let dir = temp_dir("jsonl");
+        let path = dir.join("nested").join("records.jsonl");
+        append_jsonl(&path, &json!({"n": 1})).unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+        append_jsonl(&path, &json!({"n": 2})).unwrap();
+
+        let records: Vec<Value> = read_jsonl(&path).unwrap();
+        assert_eq!(records, vec![json!({"n": 1}), json!({"n": 2})]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_json_missing_file_is_default() {
+        let dir = temp_dir("json");
+        let path = dir.join("doc.json");
+        let empty: Vec<String> = read_json(&path).unwrap();
+        assert!(empty.is_empty());
+
+        write_json(&path, &vec!["a".to_string()]).unwrap();
+        let back: Vec<String> = read_json(&path).unwrap();
+        assert_eq!(back, vec!["a".to_string()]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
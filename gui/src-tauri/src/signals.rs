@@ -0,0 +1,242 @@
+//! User → AI signal history
+//!
+//! Every signal sent through `tasks_send_signal` (command or tray menu) is recorded with the
+//! backend's response and persisted in the project sidecar. The AI status
+//! poller marks entries acknowledged once the backend reports the pending
+//! signal as consumed.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error_catalog::CatalogError;
+use crate::sidecar;
+use crate::AppState;
+
+/// File name of the signal history inside the project sidecar dir
+pub const SIGNALS_FILE: &str = "signals.json";
+/// Event emitted with the entries acknowledged by a status poll
+pub const SIGNAL_ACK_EVENT: &str = "signal-acknowledged";
+/// Signals the backend understands (`ai_state.UserSignal`)
+pub const KNOWN_SIGNALS: [&str; 5] = ["pause", "resume", "stop", "skip", "message"];
+
+/// Entries kept on disk
+const HISTORY_LIMIT: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignalEntry {
+    pub id: u64,
+    pub signal: String,
+    pub message: String,
+    pub sent_at: DateTime<Utc>,
+    /// Whether the backend accepted the signal
+    pub delivered: bool,
+    /// Backend response (or bridge error)
+    pub response: Value,
+    pub acknowledged: bool,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SignalError {
+    #[error("Unknown signal '{0}'. Valid signals: {valid}", valid = KNOWN_SIGNALS.join(", "))]
+    Unknown(String),
+    #[error("No pending AI signal {0}")]
+    NotPending(String),
+}
+
+/// Validate a signal name, returning it lowercased
+pub fn validate_signal(signal: &str) -> Result<String> {
+    let signal = signal.trim().to_lowercase();
+    if !KNOWN_SIGNALS.contains(&signal.as_str()) {
+        return Err(SignalError::Unknown(signal).into());
+    }
+    Ok(signal)
+}
+
+/// True when an `ai_status` payload reports no pending signal
+fn signal_consumed(status: &Value) -> bool {
+    status
+        .pointer("/signal/pending")
+        .and_then(|p| p.as_str())
+        .is_some_and(|p| p == "none")
+}
+
+/// Send a validated signal through the bridge and record it
+///
+/// Returns the history entry and, when it wasn't delivered, the reason.
+pub async fn send(
+    state: &AppState,
+    signal: &str,
+    message: &str,
+) -> (SignalEntry, Option<CatalogError>) {
+    let args = json!({ "signal": signal, "message": message });
+    send_with(state, signal, message, args).await
+}
+
+/// [`send`] with the tool arguments spelled out (an answer to an AI signal
+/// carries its correlation id)
+pub async fn send_with(
+    state: &AppState,
+    signal: &str,
+    message: &str,
+    args: Value,
+) -> (SignalEntry, Option<CatalogError>) {
+    let sent_at = Utc::now();
+    let result = state.bridge.call_tool("tasks_send_signal", args).await;
+
+    let (delivered, response, error) = match result {
+        Ok(response) => {
+            let ok = response
+                .get("success")
+                .and_then(|s| s.as_bool())
+                .unwrap_or(true);
+            let error = response
+                .pointer("/error/message")
+                .is_some()
+                .then(|| CatalogError::from_envelope(response.get("error"), ""));
+            (ok, response, error)
+        }
+        Err(e) => (
+            false,
+            json!({ "error": e.to_string() }),
+            Some(CatalogError::from_anyhow(&e)),
+        ),
+    };
+
+    let entry = state
+        .signals
+        .lock()
+        .await
+        .record(signal, message, sent_at, delivered, response);
+    (entry, if delivered { None } else { error })
+}
+
+/// In-memory history backed by the sidecar file
+#[derive(Debug, Default)]
+pub struct SignalLog {
+    path: Option<PathBuf>,
+    entries: Vec<SignalEntry>,
+}
+
+impl SignalLog {
+    /// Load the history at `path` (missing or unreadable file -> empty)
+    pub fn load(path: PathBuf) -> Self {
+        let entries = sidecar::read_json(&path).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable signal history: {:#}", e);
+            Vec::new()
+        });
+        Self {
+            path: Some(path),
+            entries,
+        }
+    }
+
+    fn persist(&self) {
+        if let Some(path) = &self.path {
+            if let Err(e) = sidecar::write_json(path, &self.entries) {
+                log::warn!("Failed to persist signal history: {:#}", e);
+            }
+        }
+    }
+
+    /// Record a sent signal and return the stored entry
+    pub fn record(
+        &mut self,
+        signal: &str,
+        message: &str,
+        sent_at: DateTime<Utc>,
+        delivered: bool,
+        response: Value,
+    ) -> SignalEntry {
+        let id = self.entries.last().map(|e| e.id + 1).unwrap_or(1);
+        let entry = SignalEntry {
+            id,
+            signal: signal.to_string(),
+            message: message.to_string(),
+            sent_at,
+            delivered,
+            response,
+            acknowledged: false,
+            acknowledged_at: None,
+        };
+        self.entries.push(entry.clone());
+        let overflow = self.entries.len().saturating_sub(HISTORY_LIMIT);
+        self.entries.drain(..overflow);
+        self.persist();
+        entry
+    }
+
+    /// Newest first, at most `limit` entries
+    pub fn recent(&self, limit: usize) -> Vec<SignalEntry> {
+        self.entries.iter().rev().take(limit).cloned().collect()
+    }
+
+    /// Acknowledge delivered entries sent before `polled_at` if `status`
+    /// shows the pending signal was consumed. Returns the newly acknowledged.
+    pub fn acknowledge(&mut self, status: &Value, polled_at: DateTime<Utc>) -> Vec<SignalEntry> {
+        if !signal_consumed(status) {
+            return Vec::new();
+        }
+
+        let mut acked = Vec::new();
+        for entry in self.entries.iter_mut() {
+            if entry.delivered && !entry.acknowledged && entry.sent_at < polled_at {
+                entry.acknowledged = true;
+                entry.acknowledged_at = Some(polled_at);
+                acked.push(entry.clone());
+            }
+        }
+        if !acked.is_empty() {
+            self.persist();
+        }
+        acked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_signal() {
+        assert_eq!(validate_signal(" Pause ").unwrap(), "pause");
+        let err = validate_signal("reboot").unwrap_err().to_string();
+        assert!(
+            err.contains("pause, resume, stop, skip, message"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_acknowledge_only_consumed_and_earlier() {
+        let mut log = SignalLog::default();
+        let t0 = Utc::now();
+        log.record("pause", "", t0, true, json!({}));
+        log.record("stop", "", t0, false, json!({"error": "boom"}));
+        log.record("skip", "", t0 + Duration::seconds(10), true, json!({}));
+
+        let pending = json!({"signal": {"pending": "pause", "message": ""}});
+        assert!(log
+            .acknowledge(&pending, t0 + Duration::seconds(5))
+            .is_empty());
+
+        let consumed = json!({"signal": {"pending": "none", "message": ""}});
+        let acked = log.acknowledge(&consumed, t0 + Duration::seconds(5));
+        assert_eq!(acked.iter().map(|e| e.id).collect::<Vec<_>>(), [1]);
+        assert!(log
+            .acknowledge(&consumed, t0 + Duration::seconds(5))
+            .is_empty());
+
+        let recent = log.recent(2);
+        assert_eq!(recent[0].signal, "skip");
+        assert!(!recent[0].acknowledged);
+        assert_eq!(recent.len(), 2);
+    }
+}
@@ -0,0 +1,106 @@
+//! Single-instance handoff
+//!
+//! A second launch exits right away instead of starting another backend on
+//! the same storage. Its working directory and arguments reach the running
+//! instance, which focuses its window and emits `open-project-request`; the
+//! frontend answers with the usual project switch (`projects_open`) and opens
+//! `--task` if one was given.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::{cli, projects, tray};
+
+/// Event emitted when another launch asks to open a project
+pub const OPEN_PROJECT_EVENT: &str = "open-project-request";
+
+/// Payload of [`OPEN_PROJECT_EVENT`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenProjectRequest {
+    /// Working directory of the second launch (`APPLY_TASK_USER_CWD` if set)
+    pub cwd: String,
+    pub args: Vec<String>,
+    /// Requested directory: `--project` or the path argument (relative to
+    /// `cwd`), else `cwd`
+    pub path: String,
+    /// apply_task project at or directly inside `path`
+    pub project: Option<String>,
+    pub task: Option<String>,
+    pub namespace: Option<String>,
+}
+
+pub fn open_request(args: Vec<String>, cwd: String) -> OpenProjectRequest {
+    // Already validated by the second launch, which exits on bad arguments
+    let cli = cli::parse(args.get(1..).unwrap_or_default()).unwrap_or_else(|e| {
+        log::warn!("Ignoring arguments of second launch: {}", e);
+        cli::CliArgs::default()
+    });
+    let path = cli
+        .project_dir(Path::new(&cwd))
+        .unwrap_or_else(|| PathBuf::from(&cwd));
+    OpenProjectRequest {
+        project: projects::project_root(&path).map(|p| p.to_string_lossy().to_string()),
+        path: path.to_string_lossy().to_string(),
+        task: cli.task,
+        namespace: cli.namespace,
+        cwd,
+        args,
+    }
+}
+
+/// Callback of the single-instance plugin (runs in the first instance)
+pub fn handle_second_launch(app: &AppHandle, args: Vec<String>, cwd: String) {
+    log::info!("Second launch from {} ({:?})", cwd, args);
+    tray::show_main_window(app);
+
+    let request = open_request(args, cwd);
+    if let Err(e) = app.emit(OPEN_PROJECT_EVENT, &request) {
+        log::warn!("Failed to emit {}: {}", OPEN_PROJECT_EVENT, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_request_from_args() {
+        let dir = std::env::temp_dir().join(format!("apply-task-instance-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("other/.tasks")).unwrap();
+        std::fs::create_dir_all(dir.join("plain")).unwrap();
+        let cwd = dir.to_string_lossy().to_string();
+        let args = |rest: &[&str]| {
+            std::iter::once("apply-task-gui")
+                .chain(rest.iter().copied())
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+
+        let request = open_request(args(&["other", "--task", "T-7"]), cwd.clone());
+        assert_eq!(PathBuf::from(&request.path), dir.join("other"));
+        assert_eq!(
+            request.project.map(PathBuf::from),
+            dir.join("other").canonicalize().ok()
+        );
+        assert_eq!(request.task.as_deref(), Some("T-7"));
+        let request = open_request(args(&["--project=other"]), cwd.clone());
+        assert_eq!(PathBuf::from(&request.path), dir.join("other"));
+
+        // No usable path argument: the launch directory (not a project here)
+        let plain = dir.join("plain").to_string_lossy().to_string();
+        for rest in [
+            &[][..],
+            &["apply-task://task/ns/T-1"],
+            &["missing"],
+            &["--verbose"],
+        ] {
+            let request = open_request(args(rest), plain.clone());
+            assert_eq!(request.path, plain);
+            assert_eq!(request.project, None);
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,253 @@
+//! Typed `tasks_storage` result and on-disk usage
+//!
+//! The backend reports locations and namespaces; size and file counts are
+//! computed here by walking the storage directory (bounded, no symlinks).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Deepest directory level visited by the size walk
+pub const MAX_WALK_DEPTH: usize = 8;
+/// Entries visited before the size walk gives up
+pub const MAX_WALK_ENTRIES: u64 = 50_000;
+
+/// Directories the backend excludes from task counts
+const NON_TASK_DIRS: [&str; 2] = [".snapshots", ".trash"];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Namespace {
+    pub namespace: String,
+    pub path: String,
+    pub task_count: u64,
+}
+
+/// Storage location of the current project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageInfo {
+    /// `local` (`<project>/.tasks`) or `global` (`~/.tasks/<namespace>`)
+    pub kind: String,
+    pub path: String,
+    pub namespace: Option<String>,
+    pub namespaces: Vec<Namespace>,
+    /// Task files in `path` (backend count, else from the disk walk)
+    pub task_count: Option<u64>,
+    pub disk_bytes: Option<u64>,
+    pub file_count: Option<u64>,
+    /// The walk hit its depth or entry cap, sizes are lower bounds
+    pub size_truncated: bool,
+    /// Full backend `result` object
+    pub raw: Value,
+}
+
+/// Totals gathered by [`disk_usage`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiskUsage {
+    pub bytes: u64,
+    pub files: u64,
+    pub task_files: u64,
+    pub truncated: bool,
+}
+
+impl StorageInfo {
+    /// Parse the backend `result` object
+    pub fn from_result(raw: Value) -> Self {
+        let text = |key: &str| raw.get(key).and_then(|v| v.as_str()).map(String::from);
+
+        let path = text("current_storage").unwrap_or_default();
+        let kind = if text("local_storage").as_deref() == Some(path.as_str()) {
+            "local"
+        } else {
+            "global"
+        };
+        let namespace = text("current_namespace");
+        let namespaces: Vec<Namespace> = raw
+            .get("namespaces")
+            .and_then(|n| serde_json::from_value(n.clone()).ok())
+            .unwrap_or_default();
+        let task_count = namespaces
+            .iter()
+            .find(|ns| ns.path == path)
+            .map(|ns| ns.task_count);
+
+        Self {
+            kind: kind.to_string(),
+            path,
+            namespace,
+            namespaces,
+            task_count,
+            raw,
+            ..Self::default()
+        }
+    }
+
+    /// Fill size fields from a disk walk
+    pub fn apply_usage(&mut self, usage: DiskUsage) {
+        self.disk_bytes = Some(usage.bytes);
+        self.file_count = Some(usage.files);
+        self.size_truncated = usage.truncated;
+        if self.task_count.is_none() {
+            self.task_count = Some(usage.task_files);
+        }
+    }
+}
+
+/// Walk `root` (blocking) summing file sizes, capped by depth and entry count
+pub fn disk_usage(root: &Path, max_depth: usize, max_entries: u64) -> DiskUsage {
+    let mut usage = DiskUsage::default();
+    let mut seen = 0u64;
+    let mut stack = vec![(root.to_path_buf(), 0usize, false)];
+
+    while let Some((dir, depth, excluded)) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            seen += 1;
+            if seen > max_entries {
+                usage.truncated = true;
+                return usage;
+            }
+            let Ok(meta) = entry.path().symlink_metadata() else {
+                continue;
+            };
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if meta.is_dir() {
+                if depth + 1 > max_depth {
+                    usage.truncated = true;
+                    continue;
+                }
+                let excluded = excluded || NON_TASK_DIRS.contains(&name.as_ref());
+                stack.push((entry.path(), depth + 1, excluded));
+            } else if meta.is_file() {
+                usage.bytes += meta.len();
+                usage.files += 1;
+                if !excluded && name.ends_with(".task") {
+                    usage.task_files += 1;
+                }
+            }
+        }
+    }
+    usage
+}
+
+/// Whether `path` resolves to somewhere inside `root` (both must exist)
+pub fn within_root(root: &Path, path: &Path) -> bool {
+    match (root.canonicalize(), path.canonicalize()) {
+        (Ok(root), Ok(path)) => path.starts_with(root),
+        _ => false,
+    }
+}
+
+/// Locate `<task_id>.task` under `root` (top level first, then domain folders)
+pub fn find_task_file(root: &Path, task_id: &str) -> Option<PathBuf> {
+    let valid_id = !task_id.is_empty()
+        && task_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_id {
+        return None;
+    }
+    let file_name = format!("{}.task", task_id);
+
+    let direct = root.join(&file_name);
+    if direct.is_file() {
+        return Some(direct);
+    }
+
+    let mut stack = vec![(root.to_path_buf(), 0usize)];
+    while let Some((dir, depth)) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(meta) = entry.path().symlink_metadata() else {
+                continue;
+            };
+            let name = entry.file_name();
+            if meta.is_dir() {
+                let skip = NON_TASK_DIRS.contains(&name.to_string_lossy().as_ref());
+                if !skip && depth < MAX_WALK_DEPTH {
+                    stack.push((entry.path(), depth + 1));
+                }
+            } else if meta.is_file() && name.to_string_lossy() == file_name {
+                return Some(entry.path());
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_result_local_and_global() {
+        let local = StorageInfo::from_result(json!({
+            "local_storage": "/p/.tasks",
+            "current_storage": "/p/.tasks",
+            "current_namespace": "p",
+            "namespaces": []
+        }));
+        assert_eq!(local.kind, "local");
+        assert_eq!(local.task_count, None);
+
+        let global = StorageInfo::from_result(json!({
+            "local_storage": "/p/.tasks",
+            "current_storage": "/h/.tasks/p",
+            "current_namespace": "p",
+            "namespaces": [{"namespace": "p", "path": "/h/.tasks/p", "task_count": 4}]
+        }));
+        assert_eq!(global.kind, "global");
+        assert_eq!(global.task_count, Some(4));
+        assert_eq!(global.namespaces.len(), 1);
+    }
+
+    #[test]
+    fn test_disk_usage_counts_and_caps() {
+        let root = std::env::temp_dir().join(format!("apply-task-storage-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join(".trash")).unwrap();
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::write(root.join("TASK-001.task"), "12345").unwrap();
+        fs::write(root.join(".trash/TASK-002.task"), "123").unwrap();
+        fs::write(root.join("a/b/notes.txt"), "12").unwrap();
+
+        let usage = disk_usage(&root, MAX_WALK_DEPTH, MAX_WALK_ENTRIES);
+        assert_eq!(
+            usage,
+            DiskUsage {
+                bytes: 10,
+                files: 3,
+                task_files: 1,
+                truncated: false
+            }
+        );
+
+        let shallow = disk_usage(&root, 1, MAX_WALK_ENTRIES);
+        assert!(shallow.truncated);
+        assert_eq!(shallow.files, 2);
+
+        fs::write(root.join("a/TASK-003.task"), "").unwrap();
+        assert_eq!(
+            find_task_file(&root, "TASK-001"),
+            Some(root.join("TASK-001.task"))
+        );
+        assert_eq!(
+            find_task_file(&root, "TASK-003"),
+            Some(root.join("a/TASK-003.task"))
+        );
+        assert_eq!(find_task_file(&root, "TASK-002"), None);
+        assert_eq!(find_task_file(&root, "../TASK-001"), None);
+        assert!(within_root(&root, &root.join("a/b")));
+        assert!(!within_root(&root.join("a"), &root.join("TASK-001.task")));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
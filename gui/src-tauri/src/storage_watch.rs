@@ -0,0 +1,220 @@
+//! Storage directory watcher
+//!
+//! Watches the task storage (notify crate) and emits `tasks-storage-changed`
+//! with the changed files, debounced. Changes shortly after a GUI mutation
+//! are treated as our own writes and not re-announced. Announced changes
+//! also feed the notification service.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::mpsc;
+
+use crate::notifications;
+use crate::AppState;
+
+/// Event emitted with the changed files
+pub const STORAGE_CHANGED_EVENT: &str = "tasks-storage-changed";
+/// Quiet period before a batch of changes is emitted
+pub const DEBOUNCE: Duration = Duration::from_millis(500);
+/// Changes this soon after a GUI mutation are considered ours
+pub const OWN_WRITE_WINDOW: Duration = Duration::from_millis(1500);
+
+/// Payload of [`STORAGE_CHANGED_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageChanged {
+    pub root: String,
+    /// Paths relative to `root`
+    pub files: Vec<String>,
+}
+
+/// Time of the last mutation issued by the GUI itself
+#[derive(Debug, Default)]
+pub struct OwnWrites {
+    last: Mutex<Option<Instant>>,
+}
+
+impl OwnWrites {
+    pub fn mark(&self, now: Instant) {
+        *self.last.lock().unwrap_or_else(|e| e.into_inner()) = Some(now);
+    }
+
+    pub fn suppressed(&self, now: Instant) -> bool {
+        self.last
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some_and(|last| now.saturating_duration_since(last) < OWN_WRITE_WINDOW)
+    }
+}
+
+/// Relative, de-duplicated paths worth announcing (temp files dropped)
+pub fn relevant_changes(root: &Path, paths: &[PathBuf]) -> BTreeSet<String> {
+    paths
+        .iter()
+        .filter_map(|p| p.strip_prefix(root).ok())
+        .filter(|p| {
+            let name = p.to_string_lossy();
+            !name.is_empty() && !name.ends_with(".tmp") && !name.ends_with('~')
+        })
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
+}
+
+struct ActiveWatch {
+    root: PathBuf,
+    // Dropping the watcher stops notifications
+    _watcher: RecommendedWatcher,
+    task: JoinHandle<()>,
+}
+
+/// Storage watcher (one per app)
+#[derive(Default)]
+pub struct StorageWatcher {
+    active: Mutex<Option<ActiveWatch>>,
+    own_writes: Arc<OwnWrites>,
+}
+
+impl StorageWatcher {
+    /// Record a GUI mutation so its file changes aren't re-announced
+    pub fn mark_own_write(&self) {
+        self.own_writes.mark(Instant::now());
+    }
+
+    /// Currently watched root
+    pub fn root(&self) -> Option<PathBuf> {
+        self.active
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(|a| a.root.clone())
+    }
+
+    /// Watch `root` (replacing any previous root). Errors leave the watcher off.
+    pub fn start(&self, app: &AppHandle, root: PathBuf) -> Result<()> {
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        if active.as_ref().is_some_and(|a| a.root == root) {
+            return Ok(());
+        }
+        if let Some(previous) = active.take() {
+            previous.task.abort();
+        }
+
+        let (tx, rx) = mpsc::unbounded_channel::<Vec<PathBuf>>();
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+                Ok(event) => {
+                    let _ = tx.send(event.paths);
+                }
+                Err(e) => log::warn!("Storage watcher error: {}", e),
+            })
+            .context("File notifications are not available")?;
+        watcher
+            .watch(&root, RecursiveMode::Recursive)
+            .with_context(|| format!("Cannot watch {:?}", root))?;
+
+        log::info!("Watching task storage {:?}", root);
+        tauri::async_runtime::spawn(notifications::seed(app.clone()));
+        let task = tauri::async_runtime::spawn(debounce_loop(
+            app.clone(),
+            root.clone(),
+            rx,
+            self.own_writes.clone(),
+        ));
+        *active = Some(ActiveWatch {
+            root,
+            _watcher: watcher,
+            task,
+        });
+        Ok(())
+    }
+
+    /// Stop watching; returns whether a watch was active
+    pub fn stop(&self) -> bool {
+        match self.active.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            Some(previous) => {
+                log::info!("Stopped watching {:?}", previous.root);
+                previous.task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+async fn debounce_loop(
+    app: AppHandle,
+    root: PathBuf,
+    mut rx: mpsc::UnboundedReceiver<Vec<PathBuf>>,
+    own_writes: Arc<OwnWrites>,
+) {
+    while let Some(first) = rx.recv().await {
+        let mut paths = first;
+        // Keep collecting until the storage has been quiet for DEBOUNCE
+        while let Ok(Some(more)) = tokio::time::timeout(DEBOUNCE, rx.recv()).await {
+            paths.extend(more);
+        }
+
+        if own_writes.suppressed(Instant::now()) {
+            continue;
+        }
+        let files = relevant_changes(&root, &paths);
+        if files.is_empty() {
+            continue;
+        }
+
+        // Changed outside the GUI: cached reads may be stale
+        if let Some(state) = app.try_state::<AppState>() {
+            state.read_cache.lock().await.clear();
+        }
+
+        let payload = StorageChanged {
+            root: root.to_string_lossy().to_string(),
+            files: files.into_iter().collect(),
+        };
+        if let Err(e) = app.emit(STORAGE_CHANGED_EVENT, &payload) {
+            log::warn!("Failed to emit {}: {}", STORAGE_CHANGED_EVENT, e);
+        }
+        tauri::async_runtime::spawn(notifications::storage_changed(app.clone(), payload.files));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relevant_changes_filters_and_relativizes() {
+        let root = PathBuf::from("/s/.tasks");
+        let paths = vec![
+            root.join("TASK-001.task"),
+            root.join("TASK-001.task"),
+            root.join("TASK-002.task.tmp"),
+            root.join(".snapshots/TASK-001-1.task"),
+            PathBuf::from("/elsewhere/TASK-003.task"),
+            root.clone(),
+        ];
+        assert_eq!(
+            relevant_changes(&root, &paths)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            [".snapshots/TASK-001-1.task", "TASK-001.task"]
+        );
+    }
+
+    #[test]
+    fn test_own_writes_window() {
+        let own = OwnWrites::default();
+        let now = Instant::now();
+        assert!(!own.suppressed(now));
+        own.mark(now);
+        assert!(own.suppressed(now + Duration::from_millis(200)));
+        assert!(!own.suppressed(now + OWN_WRITE_WINDOW));
+    }
+}
@@ -0,0 +1,78 @@
+//! Subtask plans
+//!
+//! The steps a backend template proposes (`tasks_template_subtasks`) and
+//! the steps of a task after `tasks_decompose` share one shape, so the
+//! preview dialog renders both the same way.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One step of a subtask plan (what `tasks_decompose` takes as `steps`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlannedStep {
+    /// Where it sits in the task, once added (`s:0`)
+    pub path: Option<String>,
+    pub title: String,
+    pub success_criteria: Vec<String>,
+    pub tests: Vec<String>,
+    pub blockers: Vec<String>,
+}
+
+/// Steps a template proposes (`tasks_template_subtasks`), or the task's
+/// steps after `tasks_decompose` added to them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubtaskPlan {
+    /// Task the steps belong to (none for a template)
+    pub task_id: Option<String>,
+    /// Template kind the backend used
+    pub kind: Option<String>,
+    pub steps: Vec<PlannedStep>,
+}
+
+impl SubtaskPlan {
+    /// Plan in a `tasks_decompose` or `tasks_template_subtasks` result
+    pub fn from_result(tool: &str, result: &Value) -> Option<Self> {
+        let (task_id, steps) = match tool {
+            "tasks_decompose" => (
+                result.get("task_id").and_then(Value::as_str),
+                result.pointer("/task/steps"),
+            ),
+            _ => (None, result.get("subtasks").or_else(|| result.get("steps"))),
+        };
+        Some(Self {
+            task_id: task_id.map(String::from),
+            kind: result.get("kind").and_then(Value::as_str).map(String::from),
+            steps: serde_json::from_value(steps?.clone()).ok()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_decompose_and_template_plans() {
+        let envelope: Value =
+            serde_json::from_str(include_str!("../tests/fixtures/tasks_decompose.json")).unwrap();
+        let plan = SubtaskPlan::from_result("tasks_decompose", &envelope["result"]).unwrap();
+        assert_eq!(plan.task_id.as_deref(), Some("TASK-001"));
+        let titles: Vec<&str> = plan.steps.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["Reproduce", "Write the failing test"]);
+        assert_eq!(plan.steps[1].path.as_deref(), Some("s:1"));
+        assert_eq!(plan.steps[1].success_criteria, ["test fails on main"]);
+
+        let template = json!({
+            "kind": "bugfix",
+            "subtasks": [{ "title": "Reproduce", "success_criteria": ["bug reproduced"] }]
+        });
+        let plan = SubtaskPlan::from_result("tasks_template_subtasks", &template).unwrap();
+        assert_eq!(plan.kind.as_deref(), Some("bugfix"));
+        assert_eq!(plan.task_id, None);
+        assert_eq!(plan.steps[0].title, "Reproduce");
+        assert!(SubtaskPlan::from_result("tasks_template_subtasks", &json!({})).is_none());
+    }
+}
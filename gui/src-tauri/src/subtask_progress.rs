@@ -0,0 +1,473 @@
+//! Rolling progress log of a subtask
+//!
+//! `tasks_progress_stream_start` follows one subtask and emits what happens
+//! to it as `subtask-progress` events, numbered per subtask (`seq` from 1).
+//! A backend declaring the MCP `logging` capability pushes: its
+//! `notifications/message` and `notifications/progress` naming the task
+//! (and path) are passed on. Otherwise the task is polled every
+//! [`POLL_INTERVAL`] and new progress notes become entries; the first poll
+//! brings the notes already there. Either way, AI status changes whose
+//! `current.path` is the subtask are entries too (the shared AI status
+//! poller's latest payload, no extra calls). Entries are kept for the app
+//! session (the last [`BACKLOG_LIMIT`]), and a window that starts following
+//! later gets them in its start response. A stream runs while any window
+//! follows it; like the other pollers it pauses while the backend is down.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::backend;
+use crate::evidence;
+use crate::AppState;
+
+/// Event carrying a [`SubtaskProgress`]
+pub const SUBTASK_PROGRESS_EVENT: &str = "subtask-progress";
+/// Between two polls of the task (poll mode)
+pub const POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// Entries kept per subtask
+pub const BACKLOG_LIMIT: usize = 200;
+/// Between two looks at the AI status
+const STATUS_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamMode {
+    /// Backend notifications
+    Push,
+    /// `tasks_show` every [`POLL_INTERVAL`], progress notes diffed
+    Poll,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressSource {
+    /// A progress note of the subtask
+    Note,
+    /// A backend notification
+    Notification,
+    AiStatus,
+}
+
+/// Payload of [`SUBTASK_PROGRESS_EVENT`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubtaskProgress {
+    pub task_id: String,
+    pub path: String,
+    /// 1-based, per subtask, without gaps
+    pub seq: u64,
+    pub source: ProgressSource,
+    pub message: String,
+    pub at: DateTime<Utc>,
+    /// Notification params or AI status `current`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+#[derive(Default)]
+struct Stream {
+    seq: u64,
+    backlog: VecDeque<SubtaskProgress>,
+    /// Labels of the windows following
+    windows: HashSet<String>,
+    task: Option<(StreamMode, JoinHandle<()>)>,
+}
+
+impl Stream {
+    fn stop_if_unfollowed(&mut self) {
+        if self.windows.is_empty() {
+            if let Some((_, task)) = self.task.take() {
+                task.abort();
+            }
+        }
+    }
+}
+
+type Key = (String, String);
+
+/// Followed subtasks (kept in `AppState`)
+#[derive(Default)]
+pub struct ProgressStreams {
+    streams: Mutex<HashMap<Key, Stream>>,
+}
+
+impl ProgressStreams {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Key, Stream>> {
+        self.streams.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Add window `label` to the followers of `path` of `task_id`; `spawn`
+    /// starts the stream when it isn't running. Returns the backlog and the
+    /// running stream's mode.
+    pub fn follow(
+        &self,
+        task_id: &str,
+        path: &str,
+        label: &str,
+        mode: StreamMode,
+        spawn: impl FnOnce() -> JoinHandle<()>,
+    ) -> (Vec<SubtaskProgress>, StreamMode) {
+        let mut streams = self.lock();
+        let stream = streams
+            .entry((task_id.to_string(), path.to_string()))
+            .or_default();
+        stream.windows.insert(label.to_string());
+        let mode = match &stream.task {
+            Some((running, _)) => *running,
+            None => {
+                log::info!("Following {} {} ({:?})", task_id, path, mode);
+                stream.task = Some((mode, spawn()));
+                mode
+            }
+        };
+        (stream.backlog.iter().cloned().collect(), mode)
+    }
+
+    /// Remove `label` from the followers; returns whether it was one
+    pub fn unfollow(&self, task_id: &str, path: &str, label: &str) -> bool {
+        let mut streams = self.lock();
+        let Some(stream) = streams.get_mut(&(task_id.to_string(), path.to_string())) else {
+            return false;
+        };
+        let followed = stream.windows.remove(label);
+        stream.stop_if_unfollowed();
+        followed
+    }
+
+    /// Drop a closed window from every stream
+    pub fn closed(&self, label: &str) {
+        for stream in self.lock().values_mut() {
+            stream.windows.remove(label);
+            stream.stop_if_unfollowed();
+        }
+    }
+
+    /// Stop every stream (app exit)
+    pub fn stop_all(&self) {
+        for stream in self.lock().values_mut() {
+            stream.windows.clear();
+            stream.stop_if_unfollowed();
+        }
+    }
+
+    /// Number and keep an entry; returns it with the windows to send it to
+    pub fn record(
+        &self,
+        task_id: &str,
+        path: &str,
+        source: ProgressSource,
+        message: String,
+        data: Option<Value>,
+    ) -> (SubtaskProgress, Vec<String>) {
+        let mut streams = self.lock();
+        let stream = streams
+            .entry((task_id.to_string(), path.to_string()))
+            .or_default();
+        stream.seq += 1;
+        let entry = SubtaskProgress {
+            task_id: task_id.to_string(),
+            path: path.to_string(),
+            seq: stream.seq,
+            source,
+            message,
+            at: Utc::now(),
+            data,
+        };
+        stream.backlog.push_back(entry.clone());
+        while stream.backlog.len() > BACKLOG_LIMIT {
+            stream.backlog.pop_front();
+        }
+        (entry, stream.windows.iter().cloned().collect())
+    }
+}
+
+/// Notes in `current` that weren't in `previous` (appended ones, or all new
+/// ones when the list was rewritten)
+pub fn new_notes(previous: &[String], current: &[String]) -> Vec<String> {
+    if current.starts_with(previous) {
+        return current[previous.len()..].to_vec();
+    }
+    current
+        .iter()
+        .filter(|note| !previous.contains(note))
+        .cloned()
+        .collect()
+}
+
+/// Progress notes of the subtask at `path` of a `tasks_show` payload
+fn notes_of(task: &Value, path: &str) -> Option<Vec<String>> {
+    let node = evidence::node(task, Some(path)).ok()?;
+    Some(
+        node.get("progress_notes")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|n| n.as_str().map(String::from))
+            .collect(),
+    )
+}
+
+/// Message of a backend notification about `path` of `task_id`
+pub fn from_notification(notification: &Value, task_id: &str, path: &str) -> Option<String> {
+    let method = notification.get("method").and_then(Value::as_str)?;
+    if method != "notifications/message" && method != "notifications/progress" {
+        return None;
+    }
+    let params = notification.get("params")?;
+    // `notifications/message` carries its fields in `data`
+    let fields = params
+        .get("data")
+        .filter(|d| d.is_object())
+        .unwrap_or(params);
+    let text = |key: &str| fields.get(key).and_then(Value::as_str);
+    if text("task").or_else(|| text("task_id")) != Some(task_id) {
+        return None;
+    }
+    if text("path").is_some_and(|p| p != path) {
+        return None;
+    }
+    let message = text("message").or_else(|| params.get("message").and_then(Value::as_str));
+    Some(match message {
+        Some(message) => message.to_string(),
+        None => match (params.get("progress"), params.get("total")) {
+            (Some(progress), Some(total)) => format!("{} / {}", progress, total),
+            (Some(progress), None) => progress.to_string(),
+            _ => return None,
+        },
+    })
+}
+
+/// `state: op` of an AI status working on `path` of `task_id`
+/// (`current.path` is `TASK-001.s:0`)
+pub fn from_ai_status(status: &Value, task_id: &str, path: &str) -> Option<String> {
+    let current = status.get("current")?;
+    if current.get("path").and_then(Value::as_str)? != format!("{}.{}", task_id, path) {
+        return None;
+    }
+    let state = status.get("state").and_then(Value::as_str).unwrap_or("");
+    let op = current.get("op").and_then(Value::as_str).unwrap_or("");
+    Some(match (state, op) {
+        ("", op) => op.to_string(),
+        (state, "") => state.to_string(),
+        (state, op) => format!("{}: {}", state, op),
+    })
+}
+
+fn emit(app: &AppHandle, (entry, windows): (SubtaskProgress, Vec<String>)) {
+    for label in windows {
+        if let Err(e) = app.emit_to(label.as_str(), SUBTASK_PROGRESS_EVENT, &entry) {
+            log::warn!(
+                "Failed to emit {} to {}: {}",
+                SUBTASK_PROGRESS_EVENT,
+                label,
+                e
+            );
+        }
+    }
+}
+
+/// Follow `path` of `task_id` until aborted
+pub async fn run(app: AppHandle, task_id: String, path: String, mode: StreamMode) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let bridge = state.bridge.clone();
+    let mut notifications = (mode == StreamMode::Push).then(|| bridge.subscribe_notifications());
+    let mut poll = tokio::time::interval(POLL_INTERVAL);
+    let mut status = tokio::time::interval(STATUS_INTERVAL);
+    let mut notes: Option<Vec<String>> = None;
+    let mut last_status: Option<String> = None;
+    let mut last_error: Option<String> = None;
+
+    loop {
+        tokio::select! {
+            _ = poll.tick(), if mode == StreamMode::Poll => {
+                if !bridge.is_running().await {
+                    continue;
+                }
+                let current = match backend::show_task(&bridge, &task_id).await {
+                    Ok(task) => {
+                        last_error = None;
+                        notes_of(&task, &path).unwrap_or_default()
+                    }
+                    Err(e) => {
+                        let message = e.to_string();
+                        if last_error.as_deref() != Some(message.as_str()) {
+                            log::warn!("Progress poll of {} failed: {}", task_id, message);
+                            last_error = Some(message);
+                        }
+                        continue;
+                    }
+                };
+                let previous = notes.as_deref().unwrap_or_default();
+                for note in new_notes(previous, &current) {
+                    let recorded = state.progress_streams.record(&task_id, &path, ProgressSource::Note, note, None);
+                    emit(&app, recorded);
+                }
+                notes = Some(current);
+            }
+            received = async {
+                match notifications.as_mut() {
+                    Some(receiver) => receiver.recv().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                let notification = match received {
+                    Ok(notification) => notification,
+                    Err(RecvError::Lagged(skipped)) => {
+                        log::warn!("Progress stream of {} skipped {} notifications", task_id, skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return,
+                };
+                if let Some(message) = from_notification(&notification, &task_id, &path) {
+                    let data = notification.get("params").cloned();
+                    let recorded = state.progress_streams.record(&task_id, &path, ProgressSource::Notification, message, data);
+                    emit(&app, recorded);
+                }
+            }
+            _ = status.tick() => {
+                let Some(payload) = state.ai_status.latest() else {
+                    continue;
+                };
+                let message = from_ai_status(&payload, &task_id, &path);
+                if message.is_some() && message != last_status {
+                    let data = payload.get("current").cloned();
+                    let recorded = state.progress_streams.record(
+                        &task_id,
+                        &path,
+                        ProgressSource::AiStatus,
+                        message.clone().unwrap_or_default(),
+                        data,
+                    );
+                    emit(&app, recorded);
+                }
+                last_status = message;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_new_notes() {
+        assert_eq!(new_notes(&[], &strings(&["a", "b"])), strings(&["a", "b"]));
+        assert_eq!(
+            new_notes(&strings(&["a"]), &strings(&["a", "b", "c"])),
+            strings(&["b", "c"])
+        );
+        assert!(new_notes(&strings(&["a", "b"]), &strings(&["a", "b"])).is_empty());
+        // Rewritten list: only what wasn't there
+        assert_eq!(
+            new_notes(&strings(&["a", "b"]), &strings(&["b", "d"])),
+            strings(&["d"])
+        );
+    }
+
+    #[test]
+    fn test_notifications_for_the_subtask() {
+        let message = json!({
+            "method": "notifications/message",
+            "params": { "level": "info", "data": { "task": "TASK-001", "path": "s:1", "message": "Running tests" } }
+        });
+        assert_eq!(
+            from_notification(&message, "TASK-001", "s:1").as_deref(),
+            Some("Running tests")
+        );
+        assert_eq!(from_notification(&message, "TASK-001", "s:0"), None);
+        assert_eq!(from_notification(&message, "TASK-002", "s:1"), None);
+
+        // No path: the whole task
+        let progress = json!({
+            "method": "notifications/progress",
+            "params": { "progressToken": "t", "task_id": "TASK-001", "progress": 2, "total": 5 }
+        });
+        assert_eq!(
+            from_notification(&progress, "TASK-001", "s:1").as_deref(),
+            Some("2 / 5")
+        );
+        let other =
+            json!({ "method": "notifications/cancelled", "params": { "task": "TASK-001" } });
+        assert_eq!(from_notification(&other, "TASK-001", "s:1"), None);
+    }
+
+    #[test]
+    fn test_ai_status_for_the_subtask() {
+        let status =
+            json!({ "state": "running", "current": { "path": "TASK-001.s:1", "op": "edit" } });
+        assert_eq!(
+            from_ai_status(&status, "TASK-001", "s:1").as_deref(),
+            Some("running: edit")
+        );
+        assert_eq!(from_ai_status(&status, "TASK-001", "s:0"), None);
+        assert_eq!(
+            from_ai_status(&json!({ "state": "idle" }), "TASK-001", "s:1"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_records_are_numbered_and_kept() {
+        let streams = ProgressStreams::default();
+        let (first, windows) =
+            streams.record("TASK-001", "s:0", ProgressSource::Note, "a".into(), None);
+        assert_eq!(first.seq, 1);
+        assert!(windows.is_empty());
+        for i in 0..BACKLOG_LIMIT {
+            streams.record("TASK-001", "s:0", ProgressSource::Note, i.to_string(), None);
+        }
+        let (other, _) = streams.record("TASK-001", "s:1", ProgressSource::Note, "b".into(), None);
+        assert_eq!(other.seq, 1);
+
+        // A late follower gets the backlog; the running stream isn't restarted
+        let spawn = || tauri::async_runtime::spawn(std::future::pending());
+        let (backlog, mode) = streams.follow("TASK-001", "s:0", "main", StreamMode::Poll, spawn);
+        assert_eq!(mode, StreamMode::Poll);
+        assert_eq!(backlog.len(), BACKLOG_LIMIT);
+        assert_eq!(backlog.first().unwrap().seq, 2);
+        assert_eq!(backlog.last().unwrap().seq, BACKLOG_LIMIT as u64 + 1);
+        let (_, mode) = streams.follow("TASK-001", "s:0", "window-1", StreamMode::Push, || {
+            panic!("already running")
+        });
+        assert_eq!(mode, StreamMode::Poll);
+        let (next, mut windows) =
+            streams.record("TASK-001", "s:0", ProgressSource::Note, "c".into(), None);
+        windows.sort();
+        assert_eq!(windows, ["main", "window-1"]);
+        assert_eq!(next.seq, BACKLOG_LIMIT as u64 + 2);
+
+        assert!(streams.unfollow("TASK-001", "s:0", "main"));
+        assert!(!streams.unfollow("TASK-001", "s:0", "main"));
+        streams.closed("window-1");
+        let key = ("TASK-001".to_string(), "s:0".to_string());
+        assert!(streams.lock()[&key].task.is_none());
+    }
+
+    #[test]
+    fn test_parses_notes_of_the_subtask() {
+        let task = json!({
+            "id": "TASK-001",
+            "steps": [{ "path": "s:0", "progress_notes": ["Started", "Half way"] }]
+        });
+        assert_eq!(
+            notes_of(&task, "s:0"),
+            Some(strings(&["Started", "Half way"]))
+        );
+        assert_eq!(notes_of(&task, "s:3"), None);
+    }
+}
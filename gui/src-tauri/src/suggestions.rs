@@ -0,0 +1,231 @@
+//! Suggestion actions
+//!
+//! Backend suggestions are often plain sentences. `recognize` maps the ones
+//! that name something to do ("run tasks_verify on subtask 2", "Resume
+//! TASK-004") to an intent and params with a small keyword table, so the UI
+//! can offer them as buttons; anything else stays a label. A `tasks_<intent>`
+//! tool name wins over a keyword. `execute_suggestion` only runs an action
+//! that passes [`validate`]: it needs an intent, object params, and must not
+//! resolve to a destructive tool (those keep their own confirmation flow).
+
+use serde_json::{json, Map, Value};
+
+use crate::ai_response::SuggestionAction;
+use crate::intents::{self, UserAliases};
+
+/// Leading word -> intent
+const KEYWORDS: &[(&str, &str)] = &[
+    ("confirm", "verify"),
+    ("context", "context"),
+    ("continue", "resume"),
+    ("decompose", "decompose"),
+    ("radar", "radar"),
+    ("redo", "redo"),
+    ("resume", "resume"),
+    ("split", "decompose"),
+    ("undo", "undo"),
+    ("verify", "verify"),
+];
+
+/// Words skipped before the keyword ("please run", "now open", ...)
+const FILLER: &[&str] = &[
+    "check", "next", "now", "open", "please", "run", "show", "then",
+];
+
+/// Checkpoint names `tasks_verify` accepts (`test` counts as `tests`)
+const CHECKPOINTS: &[&str] = &["criteria", "docs", "perf", "security", "tests"];
+
+/// Words that introduce a 1-based step number
+const STEP_WORDS: &[&str] = &["step", "subtask"];
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Cannot run this suggestion: {reason}")]
+pub struct SuggestionError {
+    pub reason: String,
+}
+
+impl SuggestionError {
+    fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
+fn words(label: &str) -> Vec<String> {
+    label
+        .split_whitespace()
+        .map(|w| {
+            w.trim_matches(|c: char| ",.;!?()\"'`".contains(c))
+                .to_string()
+        })
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn task_id(word: &str) -> Option<String> {
+    let upper = word.to_uppercase();
+    let (prefix, number) = upper.split_once('-')?;
+    let known = prefix == "TASK" || prefix == "PLAN";
+    (known && !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())).then_some(upper)
+}
+
+/// `s:1`, `s:0.t:2.s:1`: a step path as the backend writes it
+fn is_path(word: &str) -> bool {
+    word.split('.').all(|segment| {
+        segment.split_once(':').is_some_and(|(kind, index)| {
+            (kind == "s" || kind == "t")
+                && !index.is_empty()
+                && index.chars().all(|c| c.is_ascii_digit())
+        })
+    })
+}
+
+/// Intent and params of a plain-text suggestion (`default_task` fills in
+/// the task when the text doesn't name one)
+pub fn recognize(label: &str, default_task: Option<&str>) -> Option<(String, Value)> {
+    let words = words(label);
+    let lower: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+
+    let named = lower.iter().find_map(|w| {
+        w.strip_prefix("tasks_")
+            .filter(|intent| intents::alias(intent).is_some())
+            .map(String::from)
+    });
+    let intent = named.or_else(|| {
+        let first = lower.iter().find(|w| !FILLER.contains(&w.as_str()))?;
+        KEYWORDS
+            .iter()
+            .find(|(keyword, _)| keyword == first)
+            .map(|(_, intent)| intent.to_string())
+    })?;
+
+    let mut params = Map::new();
+    let task = words
+        .iter()
+        .find_map(|w| task_id(w))
+        .or_else(|| default_task.map(String::from));
+    if let Some(task) = task {
+        params.insert("task".to_string(), json!(task));
+    }
+    let path = words.iter().find(|w| is_path(w)).cloned().or_else(|| {
+        lower.windows(2).find_map(|pair| {
+            let number: usize = pair[1].parse().ok()?;
+            (STEP_WORDS.contains(&pair[0].as_str()) && number > 0)
+                .then(|| format!("s:{}", number - 1))
+        })
+    });
+    if intent == "verify" {
+        let checkpoints: Map<String, Value> = lower
+            .iter()
+            .map(|w| if w == "test" { "tests" } else { w.as_str() })
+            .filter(|w| CHECKPOINTS.contains(w))
+            .map(|w| (w.to_string(), json!({ "confirmed": true })))
+            .collect();
+        if !checkpoints.is_empty() {
+            params.insert("checkpoints".to_string(), Value::Object(checkpoints));
+        }
+        if path.is_some() {
+            params.insert("kind".to_string(), json!("step"));
+        }
+    }
+    if let Some(path) = path {
+        params.insert("path".to_string(), json!(path));
+    }
+    Some((intent, Value::Object(params)))
+}
+
+/// The intent to run for `action`, if it may be run from a suggestion
+pub fn validate(action: &SuggestionAction, user: &UserAliases) -> Result<String, SuggestionError> {
+    let intent = action
+        .intent
+        .as_deref()
+        .map(intents::normalize)
+        .filter(|i| !i.is_empty())
+        .ok_or_else(|| SuggestionError::new("it has no intent"))?;
+    if action.params.as_ref().is_some_and(|p| !p.is_object()) {
+        return Err(SuggestionError::new("params must be an object"));
+    }
+    let tool = intents::pinned(&intent, user).unwrap_or_else(|| format!("tasks_{}", intent));
+    if intents::is_destructive(&tool) {
+        return Err(SuggestionError::new(format!("{} needs confirmation", tool)));
+    }
+    Ok(intent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recognizer_table() {
+        let cases: Vec<(&str, Option<(&str, Value)>)> = vec![
+            (
+                "run tasks_verify on subtask 2",
+                Some(("verify", json!({ "kind": "step", "path": "s:1" }))),
+            ),
+            (
+                "Confirm tests for s:0.t:1 of TASK-007.",
+                Some((
+                    "verify",
+                    json!({
+                        "task": "TASK-007",
+                        "kind": "step",
+                        "path": "s:0.t:1",
+                        "checkpoints": { "tests": { "confirmed": true } }
+                    }),
+                )),
+            ),
+            (
+                "Resume plan-3",
+                Some(("resume", json!({ "task": "PLAN-3" }))),
+            ),
+            (
+                "Please split step 1",
+                Some(("decompose", json!({ "path": "s:0" }))),
+            ),
+            ("Call tasks_radar", Some(("radar", json!({})))),
+            ("undo", Some(("undo", json!({})))),
+            // A keyword only counts up front
+            ("Write docs, then verify", None),
+            ("Run the test suite", None),
+            ("tasks_nonsense for TASK-1", None),
+            ("", None),
+        ];
+        for (label, expected) in cases {
+            let got = recognize(label, None);
+            let expected = expected.map(|(intent, params)| (intent.to_string(), params));
+            assert_eq!(got, expected, "label: {:?}", label);
+        }
+
+        // The response's task fills in, a named one wins
+        assert_eq!(
+            recognize("Verify criteria", Some("TASK-002")).unwrap().1,
+            json!({ "task": "TASK-002", "checkpoints": { "criteria": { "confirmed": true } } })
+        );
+        assert_eq!(
+            recognize("Resume TASK-009", Some("TASK-002")).unwrap().1["task"],
+            "TASK-009"
+        );
+    }
+
+    #[test]
+    fn test_validate_refuses_what_cannot_run() {
+        let action = |intent: Option<&str>, params: Option<Value>| SuggestionAction {
+            label: "x".into(),
+            intent: intent.map(String::from),
+            params,
+        };
+        let user = UserAliases::new();
+        assert_eq!(
+            validate(&action(Some(" Verify "), Some(json!({}))), &user),
+            Ok("verify".to_string())
+        );
+        assert!(validate(&action(None, None), &user).is_err());
+        assert!(validate(&action(Some("verify"), Some(json!(["x"]))), &user).is_err());
+        assert!(validate(&action(Some("delete"), None), &user).is_err());
+
+        let user = UserAliases::from([("wipe".to_string(), "tasks_delete".to_string())]);
+        assert!(validate(&action(Some("wipe"), None), &user).is_err());
+    }
+}
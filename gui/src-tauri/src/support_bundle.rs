@@ -0,0 +1,325 @@
+//! Support bundle
+//!
+//! `create_support_bundle` zips what a bug report needs into the temp
+//! directory: the end of the current log file, the bridge's recent JSON-RPC
+//! traffic (there is no session recording, so the wire log's ring buffer),
+//! the backend's resource peaks and recent samples, and the `doctor`,
+//! `env_info` and `versions` reports. Every part goes through `env_info`'s
+//! secret redaction (secret-looking JSON fields, and `KEY=value` or
+//! `"key": "value"` pairs in text such as logged command lines) and home
+//! directory anonymization. Task bodies never reach the wire log and
+//! attachments are not read. A bundle over [`MAX_BUNDLE_BYTES`] is refused.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, Local, Timelike};
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::AppHandle;
+
+use crate::crash;
+use crate::env_info;
+use crate::{doctor, logging, versions, AppState};
+
+/// Largest bundle written (compressed)
+pub const MAX_BUNDLE_BYTES: usize = 8 * 1024 * 1024;
+/// Tail of the log file included
+const MAX_LOG_BYTES: u64 = 2 * 1024 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    #[error("Support bundle is over {max} bytes")]
+    TooLarge { max: usize },
+}
+
+/// What `create_support_bundle` wrote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupportBundle {
+    pub path: String,
+    pub bytes: usize,
+    /// Entries in the zip
+    pub files: Vec<String>,
+}
+
+/// One file of the bundle
+pub struct Part {
+    pub name: &'static str,
+    pub data: Vec<u8>,
+}
+
+impl Part {
+    /// Redacted, pretty-printed `value`
+    fn json(name: &'static str, value: impl Serialize, home: Option<&Path>) -> Self {
+        let mut value = serde_json::to_value(value).unwrap_or(Value::Null);
+        env_info::redact_value(&mut value, home);
+        Self {
+            name,
+            data: serde_json::to_vec_pretty(&value).unwrap_or_default(),
+        }
+    }
+}
+
+/// Redacted log `text`
+fn log_part(text: &str, home: Option<&Path>) -> Part {
+    Part {
+        name: logging::LOG_FILE,
+        data: crash::redact_home(&env_info::redact_secrets(text), home).into_bytes(),
+    }
+}
+
+/// Last `max` bytes of `path`, from the first whole line
+fn tail(path: &Path, max: u64) -> io::Result<String> {
+    let bytes = fs::read(path)?;
+    let start = bytes.len().saturating_sub(max as usize);
+    let text = String::from_utf8_lossy(&bytes[start..]);
+    Ok(match start {
+        0 => text.into_owned(),
+        _ => text
+            .split_once('\n')
+            .map_or_else(String::new, |(_, rest)| rest.to_string()),
+    })
+}
+
+/// Every part of the bundle, redacted
+pub async fn gather(app: &AppHandle, state: &AppState) -> Vec<Part> {
+    let home = crash::home_dir();
+    let home = home.as_deref();
+    let mut parts = Vec::new();
+    if let Some(path) = logging::log_path() {
+        match tail(&path, MAX_LOG_BYTES) {
+            Ok(text) => parts.push(log_part(&text, home)),
+            Err(e) => log::warn!("Support bundle without the log ({:?}): {}", path, e),
+        }
+    }
+    parts.push(Part::json("wire-log.json", state.bridge.wire_log(), home));
+    parts.push(Part::json(
+        "backend-resources.json",
+        state.backend_resources.usage(),
+        home,
+    ));
+    parts.push(Part::json(
+        "doctor.json",
+        doctor::run_checks(state).await,
+        home,
+    ));
+    parts.push(Part::json(
+        "env-info.json",
+        env_info::collect(state).await,
+        home,
+    ));
+    parts.push(Part::json(
+        "versions.json",
+        versions::versions(app, state).await,
+        home,
+    ));
+    let manifest = json!({
+        "created": Local::now().to_rfc3339(),
+        "files": parts.iter().map(|p| p.name).collect::<Vec<_>>(),
+    });
+    parts.push(Part::json("manifest.json", manifest, home));
+    parts
+}
+
+/// Zip `parts` into `dir` (refused over [`MAX_BUNDLE_BYTES`])
+pub fn write(parts: &[Part], dir: &Path) -> Result<SupportBundle> {
+    let zip = zip(parts)?;
+    if zip.len() > MAX_BUNDLE_BYTES {
+        return Err(BundleError::TooLarge {
+            max: MAX_BUNDLE_BYTES,
+        }
+        .into());
+    }
+    let path: PathBuf = dir.join(format!(
+        "apply-task-support-{}.zip",
+        Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    fs::write(&path, &zip).with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(SupportBundle {
+        path: path.to_string_lossy().into_owned(),
+        bytes: zip.len(),
+        files: parts.iter().map(|p| p.name.to_string()).collect(),
+    })
+}
+
+/// DOS time and date of now (zip timestamps)
+fn dos_now() -> (u16, u16) {
+    let now = Local::now();
+    let time = (now.hour() << 11) | (now.minute() << 5) | (now.second() / 2);
+    let date = ((now.year().max(1980) - 1980) as u32) << 9 | (now.month() << 5) | now.day();
+    (time as u16, date as u16)
+}
+
+/// A deflated zip archive of `parts`
+fn zip(parts: &[Part]) -> io::Result<Vec<u8>> {
+    let (time, date) = dos_now();
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+    for part in parts {
+        let mut crc = Crc::new();
+        crc.update(&part.data);
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&part.data)?;
+        let compressed = encoder.finish()?;
+        // Version 2.0, UTF-8 names, deflate
+        let common = |buf: &mut Vec<u8>| {
+            for field in [20u16, 0x0800, 8, time, date] {
+                buf.extend_from_slice(&field.to_le_bytes());
+            }
+            for field in [crc.sum(), compressed.len() as u32, part.data.len() as u32] {
+                buf.extend_from_slice(&field.to_le_bytes());
+            }
+            buf.extend_from_slice(&(part.name.len() as u16).to_le_bytes());
+        };
+
+        let offset = out.len() as u32;
+        out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        common(&mut out);
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(part.name.as_bytes());
+        out.extend_from_slice(&compressed);
+
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&20u16.to_le_bytes());
+        common(&mut central);
+        // Extra, comment, disk, internal and external attributes
+        for field in [0u16, 0, 0, 0] {
+            central.extend_from_slice(&field.to_le_bytes());
+        }
+        central.extend_from_slice(&0u32.to_le_bytes());
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(part.name.as_bytes());
+    }
+
+    let central_offset = out.len() as u32;
+    out.extend_from_slice(&central);
+    out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    let count = parts.len() as u16;
+    for field in [0u16, 0, count, count] {
+        out.extend_from_slice(&field.to_le_bytes());
+    }
+    out.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    out.extend_from_slice(&central_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::DeflateDecoder;
+    use std::io::Read;
+
+    fn u16_at(buf: &[u8], at: usize) -> usize {
+        u16::from_le_bytes([buf[at], buf[at + 1]]) as usize
+    }
+
+    fn u32_at(buf: &[u8], at: usize) -> usize {
+        u32::from_le_bytes(buf[at..at + 4].try_into().unwrap()) as usize
+    }
+
+    /// `(name, contents)` of every entry, read through the central directory
+    fn unzip(zip: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let end = zip.len() - 22;
+        assert_eq!(u32_at(zip, end), 0x0605_4b50);
+        let mut at = u32_at(zip, end + 16);
+        let mut out = Vec::new();
+        for _ in 0..u16_at(zip, end + 10) {
+            assert_eq!(u32_at(zip, at), 0x0201_4b50);
+            let (size, name_len) = (u32_at(zip, at + 20), u16_at(zip, at + 28));
+            let name = String::from_utf8(zip[at + 46..at + 46 + name_len].to_vec()).unwrap();
+            let local = u32_at(zip, at + 42);
+            let data_at = local + 30 + u16_at(zip, local + 26) + u16_at(zip, local + 28);
+            let mut data = Vec::new();
+            DeflateDecoder::new(&zip[data_at..data_at + size])
+                .read_to_end(&mut data)
+                .unwrap();
+            let mut crc = Crc::new();
+            crc.update(&data);
+            assert_eq!(crc.sum() as usize, u32_at(zip, at + 16));
+            out.push((name, data));
+            at += 46 + name_len;
+        }
+        out
+    }
+
+    #[test]
+    fn test_log_secrets_are_redacted() {
+        let log = "[INFO] Running: ssh [\"-o\", \"SetEnv=APPLY_TASK_TOKEN=ghp_abc123\"]\n\
+                   [WARN] backend stderr: {\"password\": \"hunter2\"} in /home/ada/p\n";
+        let part = log_part(log, Some(Path::new("/home/ada")));
+        let text = String::from_utf8(part.data).unwrap();
+        assert!(
+            !text.contains("ghp_abc123") && !text.contains("hunter2"),
+            "{}",
+            text
+        );
+        assert!(text.contains("APPLY_TASK_TOKEN=[redacted]"), "{}", text);
+        assert!(text.contains("in ~/p"), "{}", text);
+    }
+
+    #[test]
+    fn test_zip_round_trip() {
+        let home = Some(Path::new("/home/ada"));
+        let parts = vec![
+            Part {
+                name: "apply-task.log",
+                data: b"one\ntwo\n".to_vec(),
+            },
+            Part::json(
+                "env-info.json",
+                json!({ "api_token": "abc", "root": "/home/ada/p" }),
+                home,
+            ),
+        ];
+        let entries = unzip(&zip(&parts).unwrap());
+        assert_eq!(
+            entries[0],
+            ("apply-task.log".into(), b"one\ntwo\n".to_vec())
+        );
+        let env: Value = serde_json::from_slice(&entries[1].1).unwrap();
+        assert_eq!(
+            env,
+            json!({ "api_token": env_info::REDACTED, "root": "~/p" })
+        );
+
+        let dir = std::env::temp_dir().join(format!("apply-task-bundle-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let bundle = write(&parts, &dir).unwrap();
+        assert_eq!(fs::read(&bundle.path).unwrap().len(), bundle.bytes);
+        assert_eq!(bundle.files, ["apply-task.log", "env-info.json"]);
+
+        // Random bytes don't deflate
+        let mut noise = Vec::with_capacity(MAX_BUNDLE_BYTES + 1);
+        let mut x = 0x2545_f491_4f6c_dd1du64;
+        while noise.len() <= MAX_BUNDLE_BYTES {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            noise.extend_from_slice(&x.to_le_bytes());
+        }
+        let err = write(
+            &[Part {
+                name: "noise",
+                data: noise,
+            }],
+            &dir,
+        )
+        .unwrap_err();
+        assert!(err.downcast_ref::<BundleError>().is_some());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_log_tail_starts_at_a_line() {
+        let path = std::env::temp_dir().join(format!("apply-task-tail-{}.log", std::process::id()));
+        fs::write(&path, "first line\nsecond\nthird\n").unwrap();
+        assert_eq!(tail(&path, 100).unwrap(), "first line\nsecond\nthird\n");
+        assert_eq!(tail(&path, 10).unwrap(), "third\n");
+        let _ = fs::remove_file(&path);
+    }
+}
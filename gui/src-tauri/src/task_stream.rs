@@ -0,0 +1,128 @@
+//! Chunked task-list delivery
+//!
+//! `tasks_list_stream` answers with a stream id at once; the list then
+//! arrives as `tasks-chunk` events of at most `chunk_size` tasks and ends
+//! with one `tasks-stream-end`. Chunks are [`CHUNK_PACING`] apart so the
+//! webview can deserialize one before the next arrives. A cancelled stream
+//! stops before its next chunk and still sends the end event.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error_catalog::ResponseError;
+
+/// Event carrying a [`TasksChunk`]
+pub const TASKS_CHUNK_EVENT: &str = "tasks-chunk";
+/// Event carrying a [`TasksStreamEnd`]
+pub const TASKS_STREAM_END_EVENT: &str = "tasks-stream-end";
+/// Tasks per chunk when the caller gives no size
+pub const DEFAULT_CHUNK_SIZE: usize = 500;
+/// Bounds for a requested chunk size
+pub const MIN_CHUNK_SIZE: usize = 50;
+pub const MAX_CHUNK_SIZE: usize = 5000;
+/// Pause between two chunks
+pub const CHUNK_PACING: Duration = Duration::from_millis(16);
+
+/// Payload of [`TASKS_CHUNK_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TasksChunk {
+    pub stream_id: String,
+    /// 0-based position of this chunk
+    pub index: usize,
+    pub tasks: Vec<Value>,
+}
+
+/// Payload of [`TASKS_STREAM_END_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TasksStreamEnd {
+    pub stream_id: String,
+    pub success: bool,
+    /// Tasks in the whole list (also when cancelled part way)
+    pub total: usize,
+    /// Tasks actually sent
+    pub sent: usize,
+    pub cancelled: bool,
+    #[serde(flatten)]
+    pub error: ResponseError,
+}
+
+/// Chunk size for a request (`None` = [`DEFAULT_CHUNK_SIZE`])
+pub fn chunk_size(requested: Option<usize>) -> usize {
+    requested
+        .unwrap_or(DEFAULT_CHUNK_SIZE)
+        .clamp(MIN_CHUNK_SIZE, MAX_CHUNK_SIZE)
+}
+
+/// Streams still sending, by id (kept in `AppState`)
+#[derive(Default)]
+pub struct TaskStreams {
+    running: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    issued: Mutex<u64>,
+}
+
+impl TaskStreams {
+    /// Register a stream; the flag turns true when it is cancelled
+    pub fn open(&self) -> (String, Arc<AtomicBool>) {
+        let mut issued = self.issued.lock().unwrap_or_else(|e| e.into_inner());
+        *issued += 1;
+        let id = format!("stream-{}", *issued);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id.clone(), cancelled.clone());
+        (id, cancelled)
+    }
+
+    /// Ask stream `id` to stop; false if it isn't running
+    pub fn cancel(&self, id: &str) -> bool {
+        match self
+            .running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(id)
+        {
+            Some(cancelled) => {
+                cancelled.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget stream `id` once its end event is out
+    pub fn close(&self, id: &str) {
+        self.running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_lifecycle() {
+        let streams = TaskStreams::default();
+        let (first, cancelled) = streams.open();
+        let (second, _) = streams.open();
+        assert_ne!(first, second);
+
+        assert!(streams.cancel(&first));
+        assert!(cancelled.load(Ordering::Relaxed));
+        streams.close(&first);
+        assert!(!streams.cancel(&first));
+        assert!(!streams.cancel("stream-99"));
+
+        assert_eq!(chunk_size(None), DEFAULT_CHUNK_SIZE);
+        assert_eq!(chunk_size(Some(1)), MIN_CHUNK_SIZE);
+        assert_eq!(chunk_size(Some(1_000_000)), MAX_CHUNK_SIZE);
+    }
+}
@@ -0,0 +1,317 @@
+//! Child-task subtree assembly for `tasks_show`
+//!
+//! Child ids come from explicit payload fields (`children`, `child_ids`,
+//! `subtasks`) or, when a payload has none, from tasks whose `parent` is the
+//! node. Each level is fetched concurrently (bounded); failures become stubs
+//! and already visited ids are never fetched twice, so cycles terminate.
+//! The same relation drives cascade deletes (descendants first).
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::Arc;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::backend::task_str;
+
+/// Deepest subtree `tasks_show` will assemble
+pub const MAX_DEPTH: u8 = 8;
+/// Child fetches in flight at once
+pub const MAX_CONCURRENT_FETCHES: usize = 4;
+
+/// Payload fields that may list child tasks (ids or objects with `id`)
+const CHILD_FIELDS: [&str; 3] = ["children", "child_ids", "subtasks"];
+
+/// Child ids of `node`, from its own fields or from `all_tasks` parents
+pub fn child_ids(node: &Value, all_tasks: &[Value]) -> Vec<String> {
+    for field in CHILD_FIELDS {
+        if let Some(items) = node.get(field).and_then(|v| v.as_array()) {
+            return items
+                .iter()
+                .filter_map(|item| item.as_str().or_else(|| task_str(item, "id")))
+                .map(String::from)
+                .collect();
+        }
+    }
+
+    let Some(id) = task_str(node, "id") else {
+        return Vec::new();
+    };
+    all_tasks
+        .iter()
+        .filter(|t| task_str(t, "parent") == Some(id))
+        .filter_map(|t| task_str(t, "id").map(String::from))
+        .collect()
+}
+
+fn stub(id: &str, error: String) -> Value {
+    json!({ "id": id, "error": error })
+}
+
+/// Fetch the subtree below `root` (down to `depth` levels) and nest it
+/// under `children`.
+pub async fn build_tree<F, Fut>(root: Value, depth: u8, all_tasks: &[Value], fetch: F) -> Value
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Value>> + Send + 'static,
+{
+    let Some(root_id) = task_str(&root, "id").map(String::from) else {
+        return root;
+    };
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_FETCHES));
+    let mut visited: HashSet<String> = HashSet::from([root_id.clone()]);
+    let mut nodes: HashMap<String, Value> = HashMap::from([(root_id.clone(), root)]);
+    let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+    let mut frontier = vec![root_id.clone()];
+
+    for _ in 0..depth.min(MAX_DEPTH) {
+        let mut jobs = JoinSet::new();
+        for parent in &frontier {
+            let ids: Vec<String> = child_ids(&nodes[parent], all_tasks)
+                .into_iter()
+                .filter(|id| visited.insert(id.clone()))
+                .collect();
+            for id in &ids {
+                let semaphore = semaphore.clone();
+                let request = fetch(id.clone());
+                let id = id.clone();
+                jobs.spawn(async move {
+                    let _permit = semaphore.acquire_owned().await;
+                    (id, request.await)
+                });
+            }
+            edges.insert(parent.clone(), ids);
+        }
+
+        let mut next = Vec::new();
+        while let Some(joined) = jobs.join_next().await {
+            let (id, result) = match joined {
+                Ok(done) => done,
+                Err(e) => {
+                    log::warn!("Child fetch task failed: {}", e);
+                    continue;
+                }
+            };
+            match result {
+                Ok(node) => {
+                    nodes.insert(id.clone(), node);
+                    next.push(id);
+                }
+                Err(e) => {
+                    nodes.insert(id.clone(), stub(&id, e.to_string()));
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    assemble(&root_id, &mut nodes, &edges)
+}
+
+fn assemble(
+    id: &str,
+    nodes: &mut HashMap<String, Value>,
+    edges: &HashMap<String, Vec<String>>,
+) -> Value {
+    let mut node = nodes
+        .remove(id)
+        .unwrap_or_else(|| stub(id, "Task not fetched".to_string()));
+    if let (Some(children), Some(obj)) = (edges.get(id), node.as_object_mut()) {
+        let children: Vec<Value> = children
+            .iter()
+            .map(|child| assemble(child, nodes, edges))
+            .collect();
+        obj.insert("children".to_string(), Value::Array(children));
+    }
+    node
+}
+
+/// Ids to delete for `root`, descendants depth-first and `root` last
+pub fn delete_order(root: &Value, all_tasks: &[Value]) -> Vec<String> {
+    let Some(root_id) = task_str(root, "id") else {
+        return Vec::new();
+    };
+    let by_id: HashMap<&str, &Value> = all_tasks
+        .iter()
+        .filter_map(|t| task_str(t, "id").map(|id| (id, t)))
+        .collect();
+
+    fn visit(
+        node: &Value,
+        id: &str,
+        by_id: &HashMap<&str, &Value>,
+        all_tasks: &[Value],
+        visited: &mut HashSet<String>,
+        out: &mut Vec<String>,
+    ) {
+        for child in child_ids(node, all_tasks) {
+            if !visited.insert(child.clone()) {
+                continue;
+            }
+            let fallback = json!({ "id": child });
+            let child_node = by_id.get(child.as_str()).copied().unwrap_or(&fallback);
+            visit(child_node, &child, by_id, all_tasks, visited, out);
+        }
+        out.push(id.to_string());
+    }
+
+    let mut visited = HashSet::from([root_id.to_string()]);
+    let mut out = Vec::new();
+    visit(root, root_id, &by_id, all_tasks, &mut visited, &mut out);
+    out
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeleteFailure {
+    pub id: String,
+    pub error: String,
+}
+
+/// Outcome of deleting a sequence of ids
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CascadeReport {
+    /// Deleted, in order
+    pub deleted: Vec<String>,
+    /// Not deleted (the failed id and everything after it)
+    pub not_deleted: Vec<String>,
+    pub failure: Option<DeleteFailure>,
+}
+
+/// Delete `order` one by one, stopping at the first failure so no parent
+/// is removed while one of its descendants is still there.
+pub async fn delete_in_order<F, Fut>(order: Vec<String>, delete: F) -> CascadeReport
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let mut report = CascadeReport::default();
+    let mut remaining = order.into_iter();
+    for id in remaining.by_ref() {
+        match delete(id.clone()).await {
+            Ok(()) => report.deleted.push(id),
+            Err(e) => {
+                report.failure = Some(DeleteFailure {
+                    id: id.clone(),
+                    error: e.to_string(),
+                });
+                report.not_deleted.push(id);
+                break;
+            }
+        }
+    }
+    report.not_deleted.extend(remaining);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    fn fetcher(
+        store: HashMap<&'static str, Value>,
+    ) -> impl Fn(String) -> std::future::Ready<Result<Value>> {
+        move |id: String| {
+            std::future::ready(
+                store
+                    .get(id.as_str())
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Task {} not found", id)),
+            )
+        }
+    }
+
+    #[test]
+    fn test_child_ids_sources() {
+        let all = vec![
+            json!({"id": "T-2", "parent": "T-1"}),
+            json!({"id": "T-3", "parent": "T-1"}),
+            json!({"id": "T-4", "parent": "PLAN-1"}),
+        ];
+        assert_eq!(child_ids(&json!({"id": "T-1"}), &all), ["T-2", "T-3"]);
+        assert_eq!(
+            child_ids(
+                &json!({"id": "T-1", "children": ["T-9", {"id": "T-8"}]}),
+                &all
+            ),
+            ["T-9", "T-8"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_tree_nests_stubs_and_stops_cycles() {
+        let store = HashMap::from([
+            ("T-2", json!({"id": "T-2", "children": ["T-1", "T-4"]})),
+            ("T-4", json!({"id": "T-4", "children": ["T-5"]})),
+        ]);
+        let root = json!({"id": "T-1", "children": ["T-2", "T-3"]});
+
+        let tree = build_tree(root, 2, &[], fetcher(store)).await;
+
+        let children = tree["children"].as_array().unwrap();
+        assert_eq!(children.len(), 2);
+        // T-1 is already visited: the back edge is dropped
+        assert_eq!(children[0]["children"].as_array().unwrap().len(), 1);
+        assert_eq!(children[0]["children"][0]["id"], "T-4");
+        // depth 2 stops before T-4's children: left as the backend sent them
+        assert_eq!(children[0]["children"][0]["children"], json!(["T-5"]));
+        assert!(children[1]["error"].as_str().unwrap().contains("not found"));
+    }
+
+    fn three_levels() -> (Value, Vec<Value>) {
+        let all = vec![
+            json!({"id": "T-1"}),
+            json!({"id": "T-2", "parent": "T-1"}),
+            json!({"id": "T-3", "parent": "T-2"}),
+            json!({"id": "T-4", "parent": "T-1"}),
+            json!({"id": "T-5", "parent": "T-3", "children": ["T-1"]}),
+        ];
+        (json!({"id": "T-1"}), all)
+    }
+
+    #[test]
+    fn test_delete_order_is_depth_first_root_last() {
+        let (root, all) = three_levels();
+        assert_eq!(
+            delete_order(&root, &all),
+            ["T-5", "T-3", "T-2", "T-4", "T-1"]
+        );
+        assert_eq!(delete_order(&json!({"id": "T-4"}), &all), ["T-4"]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_in_order_reports_partial_failure() {
+        let (root, all) = three_levels();
+        let order = delete_order(&root, &all);
+
+        let report = delete_in_order(order.clone(), |id| async move {
+            if id == "T-4" {
+                Err(anyhow!("locked"))
+            } else {
+                Ok(())
+            }
+        })
+        .await;
+        assert_eq!(report.deleted, ["T-5", "T-3", "T-2"]);
+        assert_eq!(report.not_deleted, ["T-4", "T-1"]);
+        assert_eq!(
+            report.failure,
+            Some(DeleteFailure {
+                id: "T-4".into(),
+                error: "locked".into()
+            })
+        );
+
+        let report = delete_in_order(order, |_| async { Ok(()) }).await;
+        assert_eq!(report.deleted.len(), 5);
+        assert!(report.not_deleted.is_empty() && report.failure.is_none());
+    }
+}
@@ -0,0 +1,381 @@
+//! User task templates
+//!
+//! The backend's templates are built in; these are the user's own, kept in
+//! app data (shared by every project) as a name -> template map. A template
+//! expands into one `tasks_create` call for a task under a plan, with a step
+//! per subtask whose checkpoints become the step's success criteria, so each
+//! subtask needs at least one. `{title}` in the title pattern, description,
+//! subtask titles and checkpoints is replaced by the title given at creation.
+//! One template can be written to or read from a JSON file for sharing.
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::quick_parse::Priority;
+
+/// File name of the template map inside the app data dir
+pub const TEMPLATES_FILE: &str = "templates.json";
+/// `apply_task_template` of the files `templates_export` writes
+pub const FILE_VERSION: u32 = 1;
+/// Placeholder replaced by the task title
+const TITLE_PLACEHOLDER: &str = "{title}";
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum TemplateError {
+    #[error("Invalid template name: \"{0}\"")]
+    InvalidName(String),
+    #[error("No template named \"{0}\"")]
+    NotFound(String),
+    #[error("A template named \"{0}\" already exists")]
+    Exists(String),
+    #[error("Subtask {index} has no title")]
+    EmptySubtaskTitle { index: usize },
+    #[error("Subtask \"{subtask}\" needs at least one checkpoint")]
+    NoCheckpoints { subtask: String },
+    #[error("Subtask \"{subtask}\" lists checkpoint \"{checkpoint}\" twice")]
+    DuplicateCheckpoint { subtask: String, checkpoint: String },
+    #[error("Tasks from templates need a parent plan (PLAN-###)")]
+    ParentRequired,
+    #[error("Not a task template file: {0}")]
+    InvalidFile(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SubtaskTemplate {
+    pub title: String,
+    pub checkpoints: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TaskTemplate {
+    /// Task title, `{title}` standing for the one given (empty: just it)
+    pub title_pattern: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub priority: Option<Priority>,
+    pub subtasks: Vec<SubtaskTemplate>,
+}
+
+/// name -> template
+pub type Templates = BTreeMap<String, TaskTemplate>;
+
+/// What `tasks_create_from_template` may change for one task
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TemplateOverrides {
+    /// Plan the task goes under (required)
+    pub parent: Option<String>,
+    pub description: Option<String>,
+    /// Replace the template's tags
+    pub tags: Option<Vec<String>>,
+    pub priority: Option<Priority>,
+    pub domain: Option<String>,
+}
+
+/// A template expanded for one task
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expanded {
+    /// `tasks_create` arguments
+    pub params: Value,
+    pub tags: Vec<String>,
+    pub domain: Option<String>,
+}
+
+/// One exported template
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct TemplateFile {
+    apply_task_template: u32,
+    name: String,
+    #[serde(flatten)]
+    template: TaskTemplate,
+}
+
+/// Trimmed `name`, if it's a valid template name (no whitespace or `/`)
+pub fn normalize_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == '/' || c == '\\') {
+        return Err(TemplateError::InvalidName(name.to_string()).into());
+    }
+    Ok(name.to_string())
+}
+
+fn trimmed(list: &[String]) -> Vec<String> {
+    list.iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// `template` trimmed, or why it can't be saved
+pub fn validate(template: &TaskTemplate) -> Result<TaskTemplate> {
+    let mut subtasks = Vec::with_capacity(template.subtasks.len());
+    for (index, subtask) in template.subtasks.iter().enumerate() {
+        let title = subtask.title.trim().to_string();
+        if title.is_empty() {
+            return Err(TemplateError::EmptySubtaskTitle { index: index + 1 }.into());
+        }
+        let checkpoints = trimmed(&subtask.checkpoints);
+        if checkpoints.is_empty() {
+            return Err(TemplateError::NoCheckpoints { subtask: title }.into());
+        }
+        let mut seen = HashSet::new();
+        for checkpoint in &checkpoints {
+            if !seen.insert(checkpoint.to_lowercase()) {
+                return Err(TemplateError::DuplicateCheckpoint {
+                    subtask: title,
+                    checkpoint: checkpoint.clone(),
+                }
+                .into());
+            }
+        }
+        subtasks.push(SubtaskTemplate { title, checkpoints });
+    }
+
+    let mut seen = HashSet::new();
+    let mut tags = trimmed(&template.tags);
+    tags.retain(|tag| seen.insert(tag.clone()));
+    Ok(TaskTemplate {
+        title_pattern: template.title_pattern.trim().to_string(),
+        description: template.description.trim().to_string(),
+        tags,
+        priority: template.priority,
+        subtasks,
+    })
+}
+
+/// `template` for a task called `title`
+pub fn expand(
+    template: &TaskTemplate,
+    title: &str,
+    overrides: TemplateOverrides,
+) -> Result<Expanded> {
+    let title = title.trim();
+    let fill = |text: &str| text.replace(TITLE_PLACEHOLDER, title);
+    let full_title = match template.title_pattern.as_str() {
+        "" => title.to_string(),
+        pattern => fill(pattern).trim().to_string(),
+    };
+    if full_title.is_empty() {
+        return Err(crate::quick_parse::QuickParseError::EmptyTitle.into());
+    }
+    let parent = overrides
+        .parent
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .ok_or(TemplateError::ParentRequired)?;
+
+    let steps: Vec<Value> = template
+        .subtasks
+        .iter()
+        .map(|subtask| {
+            json!({
+                "title": fill(&subtask.title),
+                "success_criteria": subtask.checkpoints.iter().map(|c| fill(c)).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    let mut params = json!({
+        "kind": "task",
+        "title": full_title,
+        "parent": parent,
+        "steps": steps,
+    });
+    let description = overrides
+        .description
+        .unwrap_or_else(|| fill(&template.description));
+    if !description.trim().is_empty() {
+        params["description"] = json!(description.trim());
+    }
+    if let Some(priority) = overrides.priority.or(template.priority) {
+        params["priority"] = json!(priority);
+    }
+    Ok(Expanded {
+        params,
+        tags: overrides
+            .tags
+            .map(|tags| trimmed(&tags))
+            .unwrap_or_else(|| template.tags.clone()),
+        domain: overrides
+            .domain
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty()),
+    })
+}
+
+/// Contents of an exported template file
+pub fn to_file(name: &str, template: &TaskTemplate) -> Result<String> {
+    let file = TemplateFile {
+        apply_task_template: FILE_VERSION,
+        name: name.to_string(),
+        template: template.clone(),
+    };
+    Ok(serde_json::to_string_pretty(&file)? + "\n")
+}
+
+/// Name and validated template of an exported file
+pub fn from_file(text: &str) -> Result<(String, TaskTemplate)> {
+    let file: TemplateFile =
+        serde_json::from_str(text).map_err(|e| TemplateError::InvalidFile(e.to_string()))?;
+    if file.apply_task_template != FILE_VERSION {
+        return Err(TemplateError::InvalidFile(format!(
+            "version {} (this app reads {})",
+            file.apply_task_template, FILE_VERSION
+        ))
+        .into());
+    }
+    Ok((normalize_name(&file.name)?, validate(&file.template)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bugfix() -> TaskTemplate {
+        TaskTemplate {
+            title_pattern: "Bugfix: {title}".into(),
+            description: "Reported as: {title}".into(),
+            tags: vec!["bug".into(), " ".into()],
+            priority: Some(Priority::High),
+            subtasks: vec![
+                SubtaskTemplate {
+                    title: " Reproduce {title} ".into(),
+                    checkpoints: vec!["Failing test".into(), "".into()],
+                },
+                SubtaskTemplate {
+                    title: "Fix".into(),
+                    checkpoints: vec!["Test passes".into(), "Reviewed".into()],
+                },
+            ],
+        }
+    }
+
+    fn error(result: Result<impl std::fmt::Debug>) -> TemplateError {
+        result.unwrap_err().downcast::<TemplateError>().unwrap()
+    }
+
+    #[test]
+    fn test_validate() {
+        let template = validate(&bugfix()).unwrap();
+        assert_eq!(template.tags, ["bug"]);
+        assert_eq!(template.subtasks[0].title, "Reproduce {title}");
+        assert_eq!(template.subtasks[0].checkpoints, ["Failing test"]);
+
+        let mut empty_title = bugfix();
+        empty_title.subtasks[1].title = "  ".into();
+        assert_eq!(
+            error(validate(&empty_title)),
+            TemplateError::EmptySubtaskTitle { index: 2 }
+        );
+
+        let mut duplicate = bugfix();
+        duplicate.subtasks[1]
+            .checkpoints
+            .push(" test PASSES".into());
+        assert_eq!(
+            error(validate(&duplicate)),
+            TemplateError::DuplicateCheckpoint {
+                subtask: "Fix".into(),
+                checkpoint: "test PASSES".into(),
+            }
+        );
+
+        let mut bare = bugfix();
+        bare.subtasks[1].checkpoints.clear();
+        assert_eq!(
+            error(validate(&bare)),
+            TemplateError::NoCheckpoints {
+                subtask: "Fix".into()
+            }
+        );
+
+        assert_eq!(normalize_name(" spike ").unwrap(), "spike");
+        assert_eq!(
+            error(normalize_name("two words")),
+            TemplateError::InvalidName("two words".into())
+        );
+        assert!(normalize_name("../x").is_err());
+    }
+
+    #[test]
+    fn test_expand() {
+        let template = validate(&bugfix()).unwrap();
+        let overrides = TemplateOverrides {
+            parent: Some("PLAN-001".into()),
+            domain: Some("auth".into()),
+            ..TemplateOverrides::default()
+        };
+        let expanded = expand(&template, " Login loop ", overrides.clone()).unwrap();
+        assert_eq!(
+            expanded.params,
+            json!({
+                "kind": "task",
+                "title": "Bugfix: Login loop",
+                "parent": "PLAN-001",
+                "description": "Reported as: Login loop",
+                "priority": "HIGH",
+                "steps": [
+                    { "title": "Reproduce Login loop", "success_criteria": ["Failing test"] },
+                    { "title": "Fix", "success_criteria": ["Test passes", "Reviewed"] },
+                ],
+            })
+        );
+        assert_eq!(expanded.tags, ["bug"]);
+        assert_eq!(expanded.domain.as_deref(), Some("auth"));
+
+        // Overrides win; the title is used as is without a pattern
+        let plain = TaskTemplate {
+            title_pattern: String::new(),
+            ..template.clone()
+        };
+        let expanded = expand(
+            &plain,
+            "Login loop",
+            TemplateOverrides {
+                tags: Some(vec!["urgent".into()]),
+                priority: Some(Priority::Low),
+                description: Some("Custom".into()),
+                ..overrides
+            },
+        )
+        .unwrap();
+        assert_eq!(expanded.params["title"], "Login loop");
+        assert_eq!(expanded.params["priority"], "LOW");
+        assert_eq!(expanded.params["description"], "Custom");
+        assert_eq!(expanded.tags, ["urgent"]);
+
+        assert_eq!(
+            error(expand(&template, "x", TemplateOverrides::default())),
+            TemplateError::ParentRequired
+        );
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let template = validate(&bugfix()).unwrap();
+        let text = to_file("bugfix", &template).unwrap();
+        let value: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(value["apply_task_template"], FILE_VERSION);
+        assert_eq!(value["name"], "bugfix");
+        assert_eq!(from_file(&text).unwrap(), ("bugfix".to_string(), template));
+
+        assert!(matches!(
+            error(from_file(r#"{"name": "x", "subtasks": "no"}"#)),
+            TemplateError::InvalidFile(_)
+        ));
+        let future = text.replace("\"apply_task_template\": 1", "\"apply_task_template\": 9");
+        assert!(matches!(
+            error(from_file(&future)),
+            TemplateError::InvalidFile(_)
+        ));
+        let broken = text.replace("\"Fix\"", "\"\"");
+        assert_eq!(
+            error(from_file(&broken)),
+            TemplateError::EmptySubtaskTitle { index: 2 }
+        );
+    }
+}
@@ -0,0 +1,307 @@
+//! Task timeline
+//!
+//! `tasks_timeline` turns each task into a bar from when it was started to
+//! when it was completed. Milestones come from the backend's operation
+//! history when it has `tasks_history` (a `create` operation, then the first
+//! `complete` to `ACTIVE` and the last one to `DONE`), falling back to the
+//! task's own `created_at`/`updated_at` fields. A task with no start falls
+//! back to its creation time, and one with neither is unscheduled.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::backend::task_str;
+use crate::board;
+use crate::timer;
+
+/// Operations asked of `tasks_history` (its own maximum)
+pub const HISTORY_LIMIT: u64 = 500;
+
+/// Local date-time layouts the backend writes (`2026-10-14 08:19`)
+const NAIVE_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimelineBar {
+    pub task_id: String,
+    pub title: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub status: String,
+    /// Not completed, so `end` is now (or the range end)
+    pub open: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UnscheduledTask {
+    pub task_id: String,
+    pub title: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Timeline {
+    pub bars: Vec<TimelineBar>,
+    pub unscheduled: Vec<UnscheduledTask>,
+}
+
+/// When a task was created, first started and last completed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Milestones {
+    pub created: Option<DateTime<Utc>>,
+    pub started: Option<DateTime<Utc>>,
+    pub completed: Option<DateTime<Utc>>,
+}
+
+/// Parse a timestamp: RFC3339, a local date-time, `YYYY-MM-DD` (midnight in
+/// `tz`) or epoch seconds
+pub fn parse_timestamp<Tz: TimeZone>(value: &Value, tz: &Tz) -> Option<DateTime<Utc>> {
+    let raw = match value {
+        Value::Number(n) => return DateTime::from_timestamp_millis((n.as_f64()? * 1000.0) as i64),
+        Value::String(s) => s.trim(),
+        _ => return None,
+    };
+    if raw.is_empty() {
+        return None;
+    }
+    NAIVE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok())
+        .and_then(|naive| tz.from_local_datetime(&naive).earliest())
+        .map(|ts| ts.with_timezone(&Utc))
+        .or_else(|| timer::parse_bound(raw, tz))
+}
+
+/// Milestones by task id from `tasks_history` operations (oldest first)
+pub fn from_history<Tz: TimeZone>(operations: &[Value], tz: &Tz) -> HashMap<String, Milestones> {
+    let mut out: HashMap<String, Milestones> = HashMap::new();
+    for op in operations {
+        if op.get("undone").and_then(Value::as_bool) == Some(true) {
+            continue;
+        }
+        let (Some(task_id), Some(at)) = (
+            task_str(op, "task_id"),
+            op.get("timestamp").and_then(|t| parse_timestamp(t, tz)),
+        ) else {
+            continue;
+        };
+        let milestones = out.entry(task_id.to_string()).or_default();
+        match task_str(op, "intent") {
+            Some("create") => milestones.created = Some(at),
+            Some("complete") => {
+                let status = op
+                    .pointer("/data/status")
+                    .and_then(Value::as_str)
+                    .unwrap_or("DONE")
+                    .trim()
+                    .to_uppercase();
+                match status.as_str() {
+                    "ACTIVE" | "IN_PROGRESS" => {
+                        milestones.started.get_or_insert(at);
+                        milestones.completed = None;
+                    }
+                    "DONE" => milestones.completed = Some(at),
+                    // Reopened
+                    _ => milestones.completed = None,
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// History milestones, gaps filled from the task's own fields
+pub fn milestones<Tz: TimeZone>(task: &Value, history: Option<&Milestones>, tz: &Tz) -> Milestones {
+    let field = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| task.get(*key).and_then(|v| parse_timestamp(v, tz)))
+    };
+    let mut out = history.cloned().unwrap_or_default();
+    if out.created.is_none() {
+        out.created = field(&["created_at", "created"]);
+    }
+    if out.started.is_none() {
+        out.started = field(&["started_at"]);
+    }
+    if board::status_of(task) == "DONE" {
+        if out.completed.is_none() {
+            out.completed = field(&["completed_at", "updated_at", "updated"]);
+        }
+    } else {
+        out.completed = None;
+    }
+    out
+}
+
+/// Bars of `tasks` clipped to `from..to` (tasks outside it are left out)
+pub fn build<Tz: TimeZone>(
+    tasks: &[Value],
+    history: &HashMap<String, Milestones>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    tz: &Tz,
+) -> Timeline {
+    let mut timeline = Timeline::default();
+    for task in tasks {
+        let Some(task_id) = task_str(task, "id") else {
+            continue;
+        };
+        let title = task_str(task, "title").unwrap_or_default().to_string();
+        let status = board::status_of(task);
+        let milestones = milestones(task, history.get(task_id), tz);
+        let Some(start) = milestones.started.or(milestones.created) else {
+            timeline.unscheduled.push(UnscheduledTask {
+                task_id: task_id.to_string(),
+                title,
+                status,
+            });
+            continue;
+        };
+        let open = milestones.completed.is_none();
+        let end = milestones.completed.unwrap_or(now).max(start);
+
+        if from.is_some_and(|from| end < from) || to.is_some_and(|to| start > to) {
+            continue;
+        }
+        timeline.bars.push(TimelineBar {
+            task_id: task_id.to_string(),
+            title,
+            start: from.map_or(start, |from| start.max(from)),
+            end: to.map_or(end, |to| end.min(to)),
+            status,
+            open,
+        });
+    }
+    timeline.bars.sort_by_key(|bar| bar.start);
+    timeline
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn epoch(s: &str) -> Value {
+        json!(ts(s).timestamp() as f64)
+    }
+
+    #[test]
+    fn test_parse_timestamp_formats() {
+        let noon = ts("2026-10-14T12:00:00Z");
+        assert_eq!(
+            parse_timestamp(&json!("2026-10-14T12:00:00Z"), &Utc),
+            Some(noon)
+        );
+        assert_eq!(
+            parse_timestamp(&json!("2026-10-14T14:00:00+02:00"), &Utc),
+            Some(noon)
+        );
+        assert_eq!(
+            parse_timestamp(&json!("2026-10-14 12:00"), &Utc),
+            Some(noon)
+        );
+        assert_eq!(
+            parse_timestamp(&json!("2026-10-14"), &Utc),
+            Some(ts("2026-10-14T00:00:00Z"))
+        );
+        assert_eq!(
+            parse_timestamp(&epoch("2026-10-14T12:00:00Z"), &Utc),
+            Some(noon)
+        );
+        assert_eq!(parse_timestamp(&json!("soon"), &Utc), None);
+        assert_eq!(parse_timestamp(&json!(""), &Utc), None);
+        assert_eq!(parse_timestamp(&Value::Null, &Utc), None);
+    }
+
+    #[test]
+    fn test_history_milestones() {
+        let ops = vec![
+            json!({"intent": "create", "task_id": "T-1", "timestamp": epoch("2026-10-01T09:00:00Z")}),
+            json!({"intent": "complete", "task_id": "T-1", "data": {"status": "ACTIVE"},
+                   "timestamp": epoch("2026-10-02T09:00:00Z")}),
+            json!({"intent": "complete", "task_id": "T-1", "data": {},
+                   "timestamp": epoch("2026-10-03T09:00:00Z")}),
+            json!({"intent": "complete", "task_id": "T-1", "data": {"status": "ACTIVE"},
+                   "timestamp": epoch("2026-10-04T09:00:00Z")}),
+            json!({"intent": "complete", "task_id": "T-1", "data": {"status": "DONE"},
+                   "timestamp": epoch("2026-10-05T09:00:00Z")}),
+            json!({"intent": "complete", "task_id": "T-2", "data": {"status": "DONE"},
+                   "timestamp": epoch("2026-10-05T09:00:00Z"), "undone": true}),
+        ];
+        let history = from_history(&ops, &Utc);
+        assert_eq!(
+            history["T-1"],
+            Milestones {
+                created: Some(ts("2026-10-01T09:00:00Z")),
+                // First start, last completion
+                started: Some(ts("2026-10-02T09:00:00Z")),
+                completed: Some(ts("2026-10-05T09:00:00Z")),
+            }
+        );
+        assert!(!history.contains_key("T-2"));
+    }
+
+    #[test]
+    fn test_build_clips_and_falls_back() {
+        let tasks = vec![
+            json!({"id": "T-1", "title": "History", "status_code": "DONE"}),
+            json!({"id": "T-2", "title": "Fields", "status_code": "DONE",
+                   "created_at": "2026-10-06 10:00", "updated_at": "2026-10-08 10:00"}),
+            json!({"id": "T-3", "title": "Open", "status_code": "ACTIVE",
+                   "created_at": "2026-10-09"}),
+            json!({"id": "T-4", "title": "Old", "status_code": "DONE",
+                   "created_at": "2026-09-01", "updated_at": "2026-09-02"}),
+            json!({"id": "T-5", "title": "Nothing", "status_code": "TODO"}),
+        ];
+        let history = HashMap::from([(
+            "T-1".to_string(),
+            Milestones {
+                created: Some(ts("2026-09-28T09:00:00Z")),
+                started: Some(ts("2026-10-02T09:00:00Z")),
+                completed: Some(ts("2026-10-05T09:00:00Z")),
+            },
+        )]);
+        let (from, to) = (ts("2026-10-03T00:00:00Z"), ts("2026-10-10T00:00:00Z"));
+        let now = ts("2026-10-14T12:00:00Z");
+        let timeline = build(&tasks, &history, Some(from), Some(to), now, &Utc);
+
+        let bars: Vec<(&str, DateTime<Utc>, DateTime<Utc>, bool)> = timeline
+            .bars
+            .iter()
+            .map(|b| (b.task_id.as_str(), b.start, b.end, b.open))
+            .collect();
+        assert_eq!(
+            bars,
+            [
+                ("T-1", from, ts("2026-10-05T09:00:00Z"), false),
+                (
+                    "T-2",
+                    ts("2026-10-06T10:00:00Z"),
+                    ts("2026-10-08T10:00:00Z"),
+                    false
+                ),
+                ("T-3", ts("2026-10-09T00:00:00Z"), to, true),
+            ]
+        );
+        assert_eq!(timeline.unscheduled.len(), 1);
+        assert_eq!(timeline.unscheduled[0].task_id, "T-5");
+
+        // Unbounded: open bars run to now
+        let timeline = build(&tasks, &history, None, None, now, &Utc);
+        assert_eq!(timeline.bars.len(), 4);
+        assert_eq!(timeline.bars.last().unwrap().end, now);
+    }
+}
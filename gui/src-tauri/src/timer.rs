@@ -0,0 +1,304 @@
+//! Per-task time tracking
+//!
+//! One timer runs at a time; completed intervals are appended to a
+//! per-project JSONL file and aggregated on demand.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// File name of the completed-interval log inside the project sidecar dir
+pub const TIME_ENTRIES_FILE: &str = "time_entries.jsonl";
+
+/// Currently running timer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunningTimer {
+    pub task_id: String,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Completed time interval
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub task_id: String,
+    pub started_at: DateTime<Utc>,
+    pub stopped_at: DateTime<Utc>,
+    pub seconds: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl TimeEntry {
+    pub fn new(timer: &RunningTimer, stopped_at: DateTime<Utc>, note: Option<String>) -> Self {
+        let seconds = (stopped_at - timer.started_at).num_seconds().max(0);
+        Self {
+            task_id: timer.task_id.clone(),
+            started_at: timer.started_at,
+            stopped_at,
+            seconds,
+            note,
+        }
+    }
+}
+
+/// Timer state held in `AppState`
+#[derive(Debug, Default)]
+pub struct TimerState {
+    pub running: Option<RunningTimer>,
+}
+
+impl TimerState {
+    /// Start a timer, returning the previously running one (if any) as a completed entry
+    pub fn start(&mut self, task_id: &str, now: DateTime<Utc>) -> Option<TimeEntry> {
+        let previous = self
+            .running
+            .take()
+            .map(|timer| TimeEntry::new(&timer, now, Some("auto-stopped".to_string())));
+
+        self.running = Some(RunningTimer {
+            task_id: task_id.to_string(),
+            started_at: now,
+        });
+        previous
+    }
+
+    /// Stop the timer for `task_id`; `None` when that task has no running timer
+    pub fn stop(
+        &mut self,
+        task_id: &str,
+        now: DateTime<Utc>,
+        note: Option<String>,
+    ) -> Option<TimeEntry> {
+        match &self.running {
+            Some(timer) if timer.task_id == task_id => {
+                let entry = TimeEntry::new(timer, now, note);
+                self.running = None;
+                Some(entry)
+            }
+            _ => None,
+        }
+    }
+
+    /// Stop whatever timer is running
+    pub fn stop_running(&mut self, now: DateTime<Utc>, note: &str) -> Option<TimeEntry> {
+        let timer = self.running.take()?;
+        Some(TimeEntry::new(&timer, now, Some(note.to_string())))
+    }
+}
+
+/// Per-task total
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskTotal {
+    pub task_id: String,
+    pub seconds: i64,
+}
+
+/// Per-day total (day in the report's time zone)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayTotal {
+    pub date: NaiveDate,
+    pub seconds: i64,
+    pub tasks: Vec<TaskTotal>,
+}
+
+/// Aggregated time report
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimeReport {
+    pub total_seconds: i64,
+    pub by_task: Vec<TaskTotal>,
+    pub by_day: Vec<DayTotal>,
+}
+
+/// Aggregate entries over `[from, to)` (either bound optional).
+///
+/// Intervals are clipped to the range and split at midnight in `tz`,
+/// so an interval spanning two days contributes to both.
+pub fn aggregate<Tz: TimeZone>(
+    entries: &[TimeEntry],
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    tz: &Tz,
+) -> TimeReport {
+    let mut by_task: BTreeMap<String, i64> = BTreeMap::new();
+    let mut by_day: BTreeMap<NaiveDate, BTreeMap<String, i64>> = BTreeMap::new();
+
+    for entry in entries {
+        let mut start = entry.started_at;
+        let mut end = entry.stopped_at;
+        if let Some(from) = from {
+            start = start.max(from);
+        }
+        if let Some(to) = to {
+            end = end.min(to);
+        }
+        if end <= start {
+            continue;
+        }
+
+        while start < end {
+            let day = start.with_timezone(tz).date_naive();
+            let next_midnight = day
+                .succ_opt()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .and_then(|d| tz.from_local_datetime(&d).earliest())
+                .map(|d| d.with_timezone(&Utc))
+                .unwrap_or(end);
+            let chunk_end = if next_midnight > start {
+                end.min(next_midnight)
+            } else {
+                end
+            };
+            let seconds = (chunk_end - start).num_seconds();
+
+            *by_task.entry(entry.task_id.clone()).or_default() += seconds;
+            *by_day
+                .entry(day)
+                .or_default()
+                .entry(entry.task_id.clone())
+                .or_default() += seconds;
+
+            start = chunk_end;
+        }
+    }
+
+    let mut by_task: Vec<TaskTotal> = by_task
+        .into_iter()
+        .map(|(task_id, seconds)| TaskTotal { task_id, seconds })
+        .collect();
+    by_task.sort_by(|a, b| {
+        b.seconds
+            .cmp(&a.seconds)
+            .then_with(|| a.task_id.cmp(&b.task_id))
+    });
+
+    let by_day = by_day
+        .into_iter()
+        .map(|(date, tasks)| DayTotal {
+            date,
+            seconds: tasks.values().sum(),
+            tasks: tasks
+                .into_iter()
+                .map(|(task_id, seconds)| TaskTotal { task_id, seconds })
+                .collect(),
+        })
+        .collect();
+
+    TimeReport {
+        total_seconds: by_task.iter().map(|t| t.seconds).sum(),
+        by_task,
+        by_day,
+    }
+}
+
+/// Parse a range bound: RFC3339 timestamp or `YYYY-MM-DD` (midnight in `tz`)
+pub fn parse_bound<Tz: TimeZone>(value: &str, tz: &Tz) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Some(ts.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    tz.from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn entry(task: &str, start: &str, stop: &str) -> TimeEntry {
+        TimeEntry::new(
+            &RunningTimer {
+                task_id: task.to_string(),
+                started_at: ts(start),
+            },
+            ts(stop),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_start_auto_stops_previous() {
+        let mut state = TimerState::default();
+        assert!(state.start("T-1", ts("2025-01-01T10:00:00Z")).is_none());
+
+        let stopped = state.start("T-2", ts("2025-01-01T10:30:00Z")).unwrap();
+        assert_eq!(stopped.task_id, "T-1");
+        assert_eq!(stopped.seconds, 1800);
+        assert_eq!(state.running.as_ref().unwrap().task_id, "T-2");
+    }
+
+    #[test]
+    fn test_stop_requires_matching_task() {
+        let mut state = TimerState::default();
+        state.start("T-1", ts("2025-01-01T10:00:00Z"));
+        assert!(state
+            .stop("T-2", ts("2025-01-01T11:00:00Z"), None)
+            .is_none());
+
+        let entry = state
+            .stop("T-1", ts("2025-01-01T11:00:00Z"), Some("done".to_string()))
+            .unwrap();
+        assert_eq!(entry.seconds, 3600);
+        assert_eq!(entry.note.as_deref(), Some("done"));
+        assert!(state.running.is_none());
+
+        state.start("T-3", ts("2025-01-01T12:00:00Z"));
+        let entry = state
+            .stop_running(ts("2025-01-01T12:01:00Z"), "project switched")
+            .unwrap();
+        assert_eq!((entry.task_id.as_str(), entry.seconds), ("T-3", 60));
+        assert!(state
+            .stop_running(ts("2025-01-01T12:02:00Z"), "x")
+            .is_none());
+    }
+
+    #[test]
+    fn test_aggregate_splits_days_and_clips_range() {
+        let entries = vec![
+            entry("T-1", "2025-01-01T23:00:00Z", "2025-01-02T01:00:00Z"),
+            entry("T-2", "2025-01-02T09:00:00Z", "2025-01-02T09:30:00Z"),
+            entry("T-1", "2025-01-05T09:00:00Z", "2025-01-05T10:00:00Z"),
+        ];
+
+        let report = aggregate(&entries, None, None, &Utc);
+        assert_eq!(report.total_seconds, 2 * 3600 + 1800 + 3600);
+        assert_eq!(
+            report.by_task[0],
+            TaskTotal {
+                task_id: "T-1".into(),
+                seconds: 3 * 3600
+            }
+        );
+        assert_eq!(report.by_day.len(), 3);
+        assert_eq!(report.by_day[0].seconds, 3600);
+        assert_eq!(report.by_day[1].seconds, 3600 + 1800);
+
+        let clipped = aggregate(
+            &entries,
+            Some(ts("2025-01-02T00:00:00Z")),
+            Some(ts("2025-01-03T00:00:00Z")),
+            &Utc,
+        );
+        assert_eq!(clipped.total_seconds, 3600 + 1800);
+        assert_eq!(clipped.by_day.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_bound_accepts_date_and_rfc3339() {
+        assert_eq!(
+            parse_bound("2025-01-02", &Utc),
+            Some(ts("2025-01-02T00:00:00Z"))
+        );
+        assert_eq!(
+            parse_bound("2025-01-02T10:00:00+02:00", &Utc),
+            Some(ts("2025-01-02T08:00:00Z"))
+        );
+        assert!(parse_bound("yesterday", &Utc).is_none());
+    }
+}
@@ -0,0 +1,482 @@
+//! TODO comments as tasks
+//!
+//! Walks the project the way git sees it (`.gitignore`, `.ignore` and hidden
+//! files are skipped) for `TODO`, `FIXME` and `HACK` markers inside
+//! comments, with a few lines of surrounding code. Files over
+//! [`MAX_FILE_BYTES`] and binary files are skipped. Each comment is
+//! identified by a hash of its file and normalized text, so a comment that
+//! was imported as a task is not proposed again after lines move around it.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+
+use crate::sidecar;
+
+/// Sidecar file of imported comments (hash -> [`ImportedTodo`])
+pub const IMPORTED_TODOS_FILE: &str = "imported_todos.json";
+/// Files larger than this are not read
+pub const MAX_FILE_BYTES: u64 = 512 * 1024;
+/// Comments returned when the scan gives no limit
+pub const DEFAULT_LIMIT: usize = 500;
+/// Tag of tasks created from comments
+pub const TODO_TAG: &str = "from-todo";
+/// Lines of code kept before and after a comment
+const CONTEXT_LINES: usize = 2;
+/// Bytes checked for a NUL when telling binary files apart
+const BINARY_PROBE_BYTES: usize = 8 * 1024;
+/// Longer comment texts are cut for the task title
+const MAX_TITLE_CHARS: usize = 120;
+/// Where a line comment starts, in the languages a project is likely to mix
+const COMMENT_STARTS: [&str; 6] = ["//", "/*", "#", "<!--", "--", ";"];
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum TodoError {
+    #[error("Not a directory: {0}")]
+    NotADirectory(String),
+    #[error("Invalid glob \"{glob}\": {detail}")]
+    InvalidGlob { glob: String, detail: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TodoKind {
+    Todo,
+    Fixme,
+    Hack,
+}
+
+impl TodoKind {
+    const ALL: [(TodoKind, &'static str); 3] = [
+        (TodoKind::Todo, "TODO"),
+        (TodoKind::Fixme, "FIXME"),
+        (TodoKind::Hack, "HACK"),
+    ];
+
+    pub fn marker(self) -> &'static str {
+        Self::ALL
+            .iter()
+            .find(|(kind, _)| *kind == self)
+            .map_or("TODO", |(_, marker)| marker)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextLine {
+    /// 1-based
+    pub line: usize,
+    pub text: String,
+}
+
+/// A comment found by the scan; sent back as is to create tasks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TodoRef {
+    /// Relative to the scanned root, `/`-separated
+    pub file: String,
+    /// 1-based
+    pub line: usize,
+    pub kind: TodoKind,
+    /// The comment after its marker
+    pub text: String,
+    /// The comment's line and those around it
+    #[serde(default)]
+    pub context: Vec<ContextLine>,
+}
+
+impl TodoRef {
+    /// Stable id of the comment: its file and normalized text
+    pub fn hash(&self) -> String {
+        let normalized = self
+            .text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        let key = format!("{}\n{}", self.file, normalized);
+        format!("{:016x}", sidecar::fnv1a(key.as_bytes()))
+    }
+
+    pub fn title(&self) -> String {
+        let text = self.text.trim();
+        if text.is_empty() {
+            return format!("{} in {}:{}", self.kind.marker(), self.file, self.line);
+        }
+        match text.char_indices().nth(MAX_TITLE_CHARS) {
+            Some((cut, _)) => format!("{}…", text[..cut].trim_end()),
+            None => text.to_string(),
+        }
+    }
+
+    /// Where the comment is, and the code around it
+    pub fn description(&self) -> String {
+        let mut description = format!(
+            "{} comment at `{}:{}`",
+            self.kind.marker(),
+            self.file,
+            self.line
+        );
+        if !self.context.is_empty() {
+            let language = Path::new(&self.file)
+                .extension()
+                .map(|e| e.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            description.push_str(&format!("\n\n```{}\n", language));
+            for line in &self.context {
+                description.push_str(&line.text);
+                description.push('\n');
+            }
+            description.push_str("```");
+        }
+        description
+    }
+}
+
+/// A file's comments, in line order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TodoFile {
+    pub file: String,
+    pub todos: Vec<TodoRef>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TodoScan {
+    /// By path
+    pub files: Vec<TodoFile>,
+    /// Comments returned
+    pub total: usize,
+    /// Stopped at the limit; there may be more
+    pub truncated: bool,
+    /// Comments left out because they were imported before
+    pub already_imported: usize,
+    pub skipped_large: usize,
+    pub skipped_binary: usize,
+}
+
+/// An imported comment, by hash in [`IMPORTED_TODOS_FILE`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportedTodo {
+    pub task_id: String,
+    pub file: String,
+    pub line: usize,
+    pub imported_at: DateTime<Utc>,
+}
+
+pub type ImportedTodos = BTreeMap<String, ImportedTodo>;
+
+fn word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The marker and text of a TODO-style comment on `line`, if it has one:
+/// `// TODO(ann): retry` -> `(Todo, "retry")`
+pub fn extract(line: &str) -> Option<(TodoKind, String)> {
+    let trimmed = line.trim_start();
+    // Inside a block comment (` * TODO ...`), or after a comment start
+    let comment = if trimmed.starts_with('*') {
+        trimmed
+    } else {
+        let start = COMMENT_STARTS.iter().filter_map(|s| line.find(s)).min()?;
+        &line[start..]
+    };
+
+    let (at, kind, marker) = TodoKind::ALL
+        .iter()
+        .flat_map(|(kind, marker)| {
+            comment
+                .match_indices(marker)
+                .map(move |(at, _)| (at, *kind, *marker))
+        })
+        .filter(|(at, _, marker)| {
+            let before = comment[..*at].chars().next_back();
+            let after = comment[at + marker.len()..].chars().next();
+            !before.is_some_and(word_char) && !after.is_some_and(word_char)
+        })
+        .min_by_key(|(at, _, _)| *at)?;
+
+    let mut rest = &comment[at + marker.len()..];
+    // `TODO(owner)`
+    if rest.starts_with('(') {
+        if let Some(close) = rest.find(')') {
+            rest = &rest[close + 1..];
+        }
+    }
+    let text = rest
+        .trim_start_matches([':', '-', ' ', '\t'])
+        .trim_end()
+        .trim_end_matches("-->")
+        .trim_end_matches("*/")
+        .trim();
+    Some((kind, text.to_string()))
+}
+
+/// Comments of one file's `text`, with their context
+fn file_todos(file: &str, text: &str) -> Vec<TodoRef> {
+    let lines: Vec<&str> = text.lines().collect();
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let (kind, comment) = extract(line)?;
+            let from = index.saturating_sub(CONTEXT_LINES);
+            let to = (index + CONTEXT_LINES + 1).min(lines.len());
+            Some(TodoRef {
+                file: file.to_string(),
+                line: index + 1,
+                kind,
+                text: comment,
+                context: (from..to)
+                    .map(|i| ContextLine {
+                        line: i + 1,
+                        text: lines[i].to_string(),
+                    })
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Up to `limit` comments under `root` not in `imported` (hashes); `globs`
+/// narrow the files (`src/**/*.rs`; `!` excludes)
+pub fn scan(
+    root: &Path,
+    globs: &[String],
+    limit: usize,
+    imported: &HashSet<String>,
+) -> Result<TodoScan> {
+    if !root.is_dir() {
+        return Err(TodoError::NotADirectory(root.to_string_lossy().into_owned()).into());
+    }
+    let mut overrides = OverrideBuilder::new(root);
+    for glob in globs.iter().map(|g| g.trim()).filter(|g| !g.is_empty()) {
+        overrides.add(glob).map_err(|e| TodoError::InvalidGlob {
+            glob: glob.to_string(),
+            detail: e.to_string(),
+        })?;
+    }
+    let overrides = overrides.build().map_err(|e| TodoError::InvalidGlob {
+        glob: globs.join(" "),
+        detail: e.to_string(),
+    })?;
+
+    let walk = WalkBuilder::new(root)
+        .require_git(false)
+        .overrides(overrides)
+        .sort_by_file_path(|a, b| a.cmp(b))
+        .build();
+    let mut out = TodoScan::default();
+    for entry in walk {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                log::debug!("TODO scan skipped an entry: {}", e);
+                continue;
+            }
+        };
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let path = entry.path();
+        if entry.metadata().map(|m| m.len()).unwrap_or(0) > MAX_FILE_BYTES {
+            out.skipped_large += 1;
+            continue;
+        }
+        let bytes = fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+        let probe = &bytes[..bytes.len().min(BINARY_PROBE_BYTES)];
+        let text = match std::str::from_utf8(&bytes) {
+            Ok(text) if !probe.contains(&0) => text,
+            _ => {
+                out.skipped_binary += 1;
+                continue;
+            }
+        };
+
+        let file = path
+            .strip_prefix(root)
+            .unwrap_or(path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let mut todos = Vec::new();
+        for todo in file_todos(&file, text) {
+            if imported.contains(&todo.hash()) {
+                out.already_imported += 1;
+            } else if out.total == limit {
+                out.truncated = true;
+                break;
+            } else {
+                out.total += 1;
+                todos.push(todo);
+            }
+        }
+        if !todos.is_empty() {
+            out.files.push(TodoFile { file, todos });
+        }
+        if out.truncated {
+            break;
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(kind: TodoKind, text: &str) -> Option<(TodoKind, String)> {
+        Some((kind, text.to_string()))
+    }
+
+    #[test]
+    fn test_extract_markers() {
+        assert_eq!(
+            extract("    // TODO: retry on timeout"),
+            found(TodoKind::Todo, "retry on timeout")
+        );
+        assert_eq!(
+            extract("x = 1  # FIXME(ann) - off by one"),
+            found(TodoKind::Fixme, "off by one")
+        );
+        assert_eq!(
+            extract("/* HACK until v2 */"),
+            found(TodoKind::Hack, "until v2")
+        );
+        assert_eq!(
+            extract(" * TODO document the flags"),
+            found(TodoKind::Todo, "document the flags")
+        );
+        assert_eq!(
+            extract("<!-- TODO: alt text -->"),
+            found(TodoKind::Todo, "alt text")
+        );
+        assert_eq!(extract("-- TODO"), found(TodoKind::Todo, ""));
+        // The first marker wins
+        assert_eq!(
+            extract("// FIXME: TODO later"),
+            found(TodoKind::Fixme, "TODO later")
+        );
+    }
+
+    #[test]
+    fn test_extract_ignores_code_and_words() {
+        assert_eq!(extract("let todo = TODO_LIST;"), None);
+        assert_eq!(extract("println!(\"TODO\");"), None);
+        assert_eq!(extract("// TODOS are tracked elsewhere"), None);
+        assert_eq!(extract("// mastodon HACKER news"), None);
+        assert_eq!(extract("// todo: lowercase is prose"), None);
+    }
+
+    #[test]
+    fn test_ref_hash_title_and_description() {
+        let todo = TodoRef {
+            file: "src/net.rs".into(),
+            line: 3,
+            kind: TodoKind::Todo,
+            text: "Retry  on timeout".into(),
+            context: vec![
+                ContextLine {
+                    line: 2,
+                    text: "fn get() {".into(),
+                },
+                ContextLine {
+                    line: 3,
+                    text: "    // TODO: Retry  on timeout".into(),
+                },
+            ],
+        };
+        let moved = TodoRef {
+            line: 40,
+            text: "retry on TIMEOUT".into(),
+            context: Vec::new(),
+            ..todo.clone()
+        };
+        assert_eq!(todo.hash(), moved.hash());
+        let elsewhere = TodoRef {
+            file: "src/db.rs".into(),
+            ..todo.clone()
+        };
+        assert_ne!(todo.hash(), elsewhere.hash());
+
+        assert_eq!(todo.title(), "Retry  on timeout");
+        let bare = TodoRef {
+            text: String::new(),
+            ..todo.clone()
+        };
+        assert_eq!(bare.title(), "TODO in src/net.rs:3");
+        let long = TodoRef {
+            text: "x".repeat(200),
+            ..todo.clone()
+        };
+        assert_eq!(long.title().chars().count(), MAX_TITLE_CHARS + 1);
+        assert_eq!(
+            todo.description(),
+            "TODO comment at `src/net.rs:3`\n\n```rs\nfn get() {\n    // TODO: Retry  on timeout\n```"
+        );
+    }
+
+    #[test]
+    fn test_scan_respects_gitignore_limits_and_imports() {
+        let dir = std::env::temp_dir().join(format!("apply-task-todos-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::create_dir_all(dir.join("target")).unwrap();
+        fs::write(dir.join(".gitignore"), "target/\n").unwrap();
+        fs::write(
+            dir.join("src/main.rs"),
+            "fn main() {\n    // TODO: parse args\n    run();\n}\n// FIXME: exit code\n",
+        )
+        .unwrap();
+        fs::write(dir.join("src/lib.py"), "# HACK: global state\n").unwrap();
+        fs::write(dir.join("target/out.rs"), "// TODO: ignored\n").unwrap();
+        fs::write(dir.join("logo.png"), b"\x89PNG\0\0// TODO: not text").unwrap();
+        fs::write(
+            dir.join("big.txt"),
+            "# TODO: too big\n".repeat(MAX_FILE_BYTES as usize / 10),
+        )
+        .unwrap();
+
+        let all = scan(&dir, &[], DEFAULT_LIMIT, &HashSet::new()).unwrap();
+        let found: Vec<(&str, usize)> = all
+            .files
+            .iter()
+            .flat_map(|f| f.todos.iter().map(|t| (t.file.as_str(), t.line)))
+            .collect();
+        assert_eq!(
+            found,
+            [("src/lib.py", 1), ("src/main.rs", 2), ("src/main.rs", 5)]
+        );
+        assert_eq!((all.skipped_binary, all.skipped_large), (1, 1));
+        let main = &all.files[1].todos[0];
+        assert_eq!(main.text, "parse args");
+        assert_eq!(
+            main.context.iter().map(|c| c.line).collect::<Vec<_>>(),
+            [1, 2, 3, 4]
+        );
+
+        // Globs narrow the walk
+        let rust = scan(&dir, &["*.rs".to_string()], DEFAULT_LIMIT, &HashSet::new()).unwrap();
+        assert_eq!(rust.total, 2);
+        // Imported comments drop out; the limit truncates
+        let imported: HashSet<String> = [all.files[0].todos[0].hash()].into();
+        let rest = scan(&dir, &[], 1, &imported).unwrap();
+        assert_eq!(rest.already_imported, 1);
+        assert_eq!(rest.total, 1);
+        assert!(rest.truncated);
+        assert_eq!(rest.files[0].todos[0].text, "parse args");
+
+        assert!(matches!(
+            scan(&dir, &["[".to_string()], 1, &HashSet::new())
+                .unwrap_err()
+                .downcast_ref::<TodoError>(),
+            Some(TodoError::InvalidGlob { .. })
+        ));
+        assert!(scan(&dir.join("missing"), &[], 1, &HashSet::new()).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,184 @@
+//! System tray
+//!
+//! The tray icon keeps the app reachable while the main window is hidden
+//! (`minimize_to_tray`), so AI status polling and signal acknowledgement go
+//! on in the background. Its menu shows the window, sends pause/resume/stop
+//! signals through the bridge, runs pause all / resume all and quits through
+//! the normal exit path; the tooltip follows the AI status poller.
+
+use serde_json::Value;
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager};
+
+use crate::ai_pause::{self, PauseAction};
+use crate::signals;
+use crate::AppState;
+
+const TRAY_ID: &str = "main";
+const APP_NAME: &str = "Apply Task";
+
+const SHOW_ID: &str = "show";
+const QUIT_ID: &str = "quit";
+/// Menu id -> signal, in menu order
+const SIGNAL_ITEMS: [(&str, &str, &str); 3] = [
+    ("pause-ai", "Pause AI", "pause"),
+    ("resume-ai", "Resume AI", "resume"),
+    ("stop-ai", "Stop AI", "stop"),
+];
+/// Menu id -> pause all action
+const PAUSE_ALL_ITEMS: [(&str, &str, PauseAction); 2] = [
+    ("pause-all", "Pause all AI activity", PauseAction::Pause),
+    ("resume-all", "Resume what was paused", PauseAction::Resume),
+];
+
+/// Tooltip for an AI status payload (`ai_state.to_dict`)
+fn tooltip(status: Option<&Value>) -> String {
+    let Some(status) = status else {
+        return APP_NAME.to_string();
+    };
+    let state = status
+        .get("status")
+        .and_then(|s| s.as_str())
+        .unwrap_or("unknown");
+    let mut text = format!("{} — AI {}", APP_NAME, state);
+    if let Some(op) = status.pointer("/current/op").and_then(|o| o.as_str()) {
+        text.push_str(&format!(": {}", op));
+    }
+    match status.pointer("/signal/pending").and_then(|p| p.as_str()) {
+        Some("none") | None => {}
+        Some(pending) => text.push_str(&format!(" ({} pending)", pending)),
+    }
+    text
+}
+
+/// Bring the main window back (from the tray, a minimized state or behind
+/// other windows)
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        if let Err(e) = window.set_focus() {
+            log::warn!("Failed to focus main window: {}", e);
+        }
+    }
+}
+
+/// Whether closing the main window should hide it instead (only with a
+/// tray icon to bring it back)
+pub fn hide_on_close(app: &AppHandle) -> bool {
+    app.tray_by_id(TRAY_ID).is_some()
+        && app.try_state::<AppState>().is_some_and(|state| {
+            tauri::async_runtime::block_on(state.settings.read()).minimize_to_tray
+        })
+}
+
+/// Reflect the latest AI status in the tooltip
+pub fn update_status(app: &AppHandle, status: &Value) {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        if let Err(e) = tray.set_tooltip(Some(tooltip(Some(status)))) {
+            log::warn!("Failed to update tray tooltip: {}", e);
+        }
+    }
+}
+
+fn send_signal(app: &AppHandle, signal: &'static str) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app.try_state::<AppState>() else {
+            return;
+        };
+        match signals::send(&state, signal, "").await {
+            (_, None) => log::info!("Sent {} signal from the tray", signal),
+            (_, Some(e)) => log::warn!("Tray {} signal not delivered: {}", signal, e.render()),
+        }
+    });
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        SHOW_ID => show_main_window(app),
+        // Same path as any other quit: confirmation during writes, then
+        // the graceful backend shutdown on `RunEvent::Exit`
+        QUIT_ID => app.exit(0),
+        id => {
+            if let Some((_, _, signal)) = SIGNAL_ITEMS.iter().find(|(item, _, _)| *item == id) {
+                send_signal(app, signal);
+            }
+            if let Some((_, _, action)) = PAUSE_ALL_ITEMS.iter().find(|(item, _, _)| *item == id) {
+                ai_pause::spawn(app, *action);
+            }
+        }
+    }
+}
+
+/// Create the tray icon (called once from `setup`)
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let menu = Menu::new(app)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        SHOW_ID,
+        "Show window",
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    for (id, label, _) in SIGNAL_ITEMS {
+        menu.append(&MenuItem::with_id(app, id, label, true, None::<&str>)?)?;
+    }
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    for (id, label, _) in PAUSE_ALL_ITEMS {
+        menu.append(&MenuItem::with_id(app, id, label, true, None::<&str>)?)?;
+    }
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        QUIT_ID,
+        "Quit",
+        true,
+        None::<&str>,
+    )?)?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(tooltip(None))
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(on_menu_event)
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_tooltip_from_status() {
+        assert_eq!(tooltip(None), "Apply Task");
+        let idle = json!({"status": "idle", "current": null, "signal": {"pending": "none"}});
+        assert_eq!(tooltip(Some(&idle)), "Apply Task — AI idle");
+        let busy = json!({
+            "status": "executing",
+            "current": {"op": "tasks_edit"},
+            "signal": {"pending": "pause", "message": ""}
+        });
+        assert_eq!(
+            tooltip(Some(&busy)),
+            "Apply Task — AI executing: tasks_edit (pause pending)"
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! GUI update check
+//!
+//! Asks the GitHub releases API for the latest release and compares its tag
+//! with this build's version. There are no retries, and the request gives up
+//! after `REQUEST_TIMEOUT`. It goes through the system `curl` (macOS, Linux
+//! and Windows 10+ ship it), so the GUI doesn't carry its own TLS stack.
+//! At startup the check runs once, if `update_check_enabled` is on. A result
+//! younger than `CACHE_TTL` is read from `update-check.json` in the app data
+//! dir instead. A newer release is announced with `update-available`, and
+//! failures there are only logged.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::doctor;
+use crate::sidecar;
+use crate::AppState;
+
+/// Event emitted when a newer release exists
+pub const UPDATE_AVAILABLE_EVENT: &str = "update-available";
+/// File name of the cached result inside the app data dir
+pub const UPDATE_CHECK_FILE: &str = "update-check.json";
+/// Latest release of the project
+const RELEASES_URL: &str = "https://api.github.com/repos/AmirTlinov/apply_task-mcp/releases/latest";
+/// Startup checks reuse a result this recent
+const CACHE_TTL: chrono::Duration = chrono::Duration::hours(24);
+/// The whole request, connection included
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Characters of release notes kept
+const NOTES_EXCERPT_CHARS: usize = 400;
+
+/// What `check_for_updates` returns
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateInfo {
+    pub current: String,
+    pub latest: String,
+    pub update_available: bool,
+    pub release_url: Option<String>,
+    pub notes_excerpt: Option<String>,
+}
+
+/// `update-check.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedCheck {
+    checked_at: DateTime<Utc>,
+    info: UpdateInfo,
+}
+
+fn parse_version(raw: &str) -> Result<semver::Version> {
+    let trimmed = raw.trim();
+    let version = trimmed.strip_prefix(['v', 'V']).unwrap_or(trimmed);
+    semver::Version::parse(version).with_context(|| format!("Invalid version: {}", raw))
+}
+
+/// First paragraph-ish part of the release notes
+fn excerpt(notes: &str) -> Option<String> {
+    let notes = notes.trim();
+    if notes.is_empty() {
+        return None;
+    }
+    let mut out: String = notes.chars().take(NOTES_EXCERPT_CHARS).collect();
+    if out.len() < notes.len() {
+        out = out.trim_end().to_string();
+        out.push('…');
+    }
+    Some(out)
+}
+
+/// Compare a `releases/latest` payload with `current`
+pub fn compare(release: &Value, current: &str) -> Result<UpdateInfo> {
+    let tag = release
+        .get("tag_name")
+        .and_then(|t| t.as_str())
+        .ok_or_else(|| anyhow!("Release has no tag_name"))?;
+    let latest = parse_version(tag)?;
+    let update_available = latest > parse_version(current)?;
+    Ok(UpdateInfo {
+        current: current.to_string(),
+        latest: latest.to_string(),
+        update_available,
+        release_url: release
+            .get("html_url")
+            .and_then(|u| u.as_str())
+            .map(String::from),
+        notes_excerpt: release
+            .get("body")
+            .and_then(|b| b.as_str())
+            .and_then(excerpt),
+    })
+}
+
+async fn fetch_latest_release() -> Result<Value> {
+    let mut cmd = tokio::process::Command::new("curl");
+    cmd.args([
+        "--fail",
+        "--silent",
+        "--show-error",
+        "--location",
+        "--max-time",
+        &REQUEST_TIMEOUT.as_secs().to_string(),
+        "--header",
+        "Accept: application/vnd.github+json",
+        "--user-agent",
+        &format!("apply-task-gui/{}", env!("CARGO_PKG_VERSION")),
+        RELEASES_URL,
+    ]);
+    // curl's own limit normally fires first
+    let body = doctor::output(cmd, REQUEST_TIMEOUT + Duration::from_secs(1))
+        .await
+        .context("Update check failed")?;
+    serde_json::from_str(&body).context("Unexpected releases API response")
+}
+
+/// Query GitHub now and store the result for later startups
+pub async fn check(data_dir: &Path) -> Result<UpdateInfo> {
+    let release = fetch_latest_release().await?;
+    let info = compare(&release, env!("CARGO_PKG_VERSION"))?;
+    let cached = CachedCheck {
+        checked_at: Utc::now(),
+        info: info.clone(),
+    };
+    if let Err(e) = sidecar::write_json(&cache_path(data_dir), &cached) {
+        log::warn!("Failed to cache the update check: {:#}", e);
+    }
+    Ok(info)
+}
+
+fn cache_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(UPDATE_CHECK_FILE)
+}
+
+/// Cached result if it's recent and for this build
+fn cached(data_dir: &Path, now: DateTime<Utc>) -> Option<UpdateInfo> {
+    let cached: Option<CachedCheck> = sidecar::read_json(&cache_path(data_dir)).ok()?;
+    cached
+        .filter(|c| now - c.checked_at < CACHE_TTL && c.info.current == env!("CARGO_PKG_VERSION"))
+        .map(|c| c.info)
+}
+
+/// Check once at startup (cached for `CACHE_TTL`) and emit `update-available`
+pub fn spawn_startup_check(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app.try_state::<AppState>() else {
+            return;
+        };
+        if !state.settings.read().await.update_check_enabled() {
+            return;
+        }
+        let info = match cached(&state.data_dir, Utc::now()) {
+            Some(info) => info,
+            None => match check(&state.data_dir).await {
+                Ok(info) => info,
+                Err(e) => {
+                    log::info!("Skipping update check: {:#}", e);
+                    return;
+                }
+            },
+        };
+        if info.update_available {
+            log::info!(
+                "GUI {} is available (running {})",
+                info.latest,
+                info.current
+            );
+            if let Err(e) = app.emit(UPDATE_AVAILABLE_EVENT, &info) {
+                log::warn!("Failed to emit {}: {}", UPDATE_AVAILABLE_EVENT, e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compare_uses_semver() {
+        let release = json!({
+            "tag_name": "v0.10.0",
+            "html_url": "https://github.com/AmirTlinov/apply_task-mcp/releases/tag/v0.10.0",
+            "body": "  Faster lists\n\nDetails follow  ",
+        });
+        let info = compare(&release, "0.9.3").unwrap();
+        assert!(info.update_available);
+        assert_eq!(info.latest, "0.10.0");
+        assert_eq!(
+            info.notes_excerpt.as_deref(),
+            Some("Faster lists\n\nDetails follow")
+        );
+
+        assert!(!compare(&release, "0.10.0").unwrap().update_available);
+        assert!(!compare(&release, "1.0.0-beta.1").unwrap().update_available);
+        let pre = json!({"tag_name": "1.0.0-rc.1"});
+        assert!(compare(&pre, "1.0.0-beta.2").unwrap().update_available);
+        assert!(compare(&json!({"tag_name": "nightly"}), "0.1.0").is_err());
+        assert!(compare(&json!({}), "0.1.0").is_err());
+
+        let long = "x".repeat(NOTES_EXCERPT_CHARS + 10);
+        let cut = excerpt(&long).unwrap();
+        assert!(cut.ends_with('…'));
+        assert_eq!(cut.chars().count(), NOTES_EXCERPT_CHARS + 1);
+        assert_eq!(excerpt("  "), None);
+    }
+
+    #[test]
+    fn test_cache_expires_and_follows_the_build() {
+        let dir = std::env::temp_dir().join(format!("apply-task-update-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let now = Utc::now();
+        assert_eq!(cached(&dir, now), None);
+
+        let info = UpdateInfo {
+            current: env!("CARGO_PKG_VERSION").to_string(),
+            latest: "99.0.0".into(),
+            update_available: true,
+            release_url: None,
+            notes_excerpt: None,
+        };
+        let entry = CachedCheck {
+            checked_at: now - chrono::Duration::hours(1),
+            info: info.clone(),
+        };
+        sidecar::write_json(&cache_path(&dir), &entry).unwrap();
+        assert_eq!(cached(&dir, now), Some(info.clone()));
+        assert_eq!(cached(&dir, now + CACHE_TTL), None);
+
+        let other_build = CachedCheck {
+            checked_at: now,
+            info: UpdateInfo {
+                current: "0.0.1".into(),
+                ..info
+            },
+        };
+        sidecar::write_json(&cache_path(&dir), &other_build).unwrap();
+        assert_eq!(cached(&dir, now), None);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,268 @@
+//! Local usage metrics
+//!
+//! Opt-in (`analytics_enabled`, off by default) counters of how often each
+//! Tauri command is invoked and how it answered, and how each `ai_intent`
+//! tool call ended. They stay in `usage-metrics.json` in the app data dir,
+//! plain JSON so users can read exactly what they would attach to an issue;
+//! nothing is ever uploaded. Calls are counted at the IPC layer, which
+//! doesn't see results; commands answered by a shared `*_response` helper
+//! record the response's `success` where they get it back. Disabled means
+//! nothing is recorded and the file is not written.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::sidecar;
+use crate::AppState;
+
+/// File name of the metrics inside the app data dir
+pub const USAGE_METRICS_FILE: &str = "usage-metrics.json";
+/// How often recorded changes are written
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How the calls of one command or `ai_intent` tool ended
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Outcomes {
+    pub success: u64,
+    pub failure: u64,
+}
+
+impl Outcomes {
+    fn add(&mut self, success: bool) {
+        if success {
+            self.success += 1;
+        } else {
+            self.failure += 1;
+        }
+    }
+}
+
+/// One Tauri command
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandMetrics {
+    /// Invocations
+    pub calls: u64,
+    /// Responses by `success` (zero for commands that don't record them)
+    #[serde(flatten)]
+    pub outcomes: Outcomes,
+}
+
+/// The whole file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Metrics {
+    /// RFC 3339 time of the first record since the last reset
+    pub since: Option<String>,
+    /// Calls and responses per Tauri command
+    pub commands: BTreeMap<String, CommandMetrics>,
+    /// Results per backend tool run through `ai_intent`
+    pub intents: BTreeMap<String, Outcomes>,
+}
+
+impl Metrics {
+    fn touch(&mut self) {
+        if self.since.is_none() {
+            self.since = Some(chrono::Utc::now().to_rfc3339());
+        }
+    }
+
+    fn command(&mut self, command: &str) -> &mut CommandMetrics {
+        self.touch();
+        self.commands.entry(command.to_string()).or_default()
+    }
+
+    fn intent(&mut self, tool: &str, success: bool) {
+        self.touch();
+        self.intents
+            .entry(tool.to_string())
+            .or_default()
+            .add(success);
+    }
+}
+
+/// Recorder held in `AppState::usage_metrics`
+#[derive(Debug, Default)]
+pub struct UsageMetrics {
+    path: PathBuf,
+    enabled: AtomicBool,
+    metrics: Mutex<Metrics>,
+    /// Recorded since the last write
+    dirty: AtomicBool,
+}
+
+impl UsageMetrics {
+    pub fn load(path: PathBuf, enabled: bool) -> Self {
+        let metrics = sidecar::read_json(&path).unwrap_or_else(|e| {
+            log::warn!("Ignoring usage metrics {:?}: {:#}", path, e);
+            Metrics::default()
+        });
+        Self {
+            path,
+            enabled: AtomicBool::new(enabled),
+            metrics: Mutex::new(metrics),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Follow the `analytics_enabled` setting
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    fn record(&self, update: impl FnOnce(&mut Metrics)) {
+        if !self.enabled() {
+            return;
+        }
+        update(&mut self.metrics.lock().unwrap_or_else(|e| e.into_inner()));
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    pub fn record_command(&self, command: &str) {
+        self.record(|m| m.command(command).calls += 1);
+    }
+
+    /// How `command` answered (its response's `success`)
+    pub fn record_outcome(&self, command: &str, success: bool) {
+        self.record(|m| m.command(command).outcomes.add(success));
+    }
+
+    pub fn record_intent(&self, tool: &str, success: bool) {
+        self.record(|m| m.intent(tool, success));
+    }
+
+    pub fn snapshot(&self) -> Metrics {
+        self.metrics
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Write pending records (only while enabled)
+    pub fn flush(&self) -> Result<()> {
+        if !self.enabled() || !self.dirty.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+        let metrics = self.snapshot();
+        sidecar::write_json(&self.path, &metrics).inspect_err(|_| {
+            self.dirty.store(true, Ordering::Relaxed);
+        })
+    }
+
+    /// Drop all counts and the file
+    pub fn reset(&self) -> Result<()> {
+        *self.metrics.lock().unwrap_or_else(|e| e.into_inner()) = Metrics::default();
+        self.dirty.store(false, Ordering::Relaxed);
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to delete {:?}", self.path))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Write recorded counts every `FLUSH_INTERVAL` (and on exit, from `shutdown`)
+pub fn spawn_flusher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+        loop {
+            interval.tick().await;
+            let Some(state) = app.try_state::<AppState>() else {
+                return;
+            };
+            if let Err(e) = state.usage_metrics.flush() {
+                log::warn!("Failed to save usage metrics: {:#}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics_in(name: &str, enabled: bool) -> UsageMetrics {
+        let dir = std::env::temp_dir().join(format!(
+            "apply-task-metrics-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        UsageMetrics::load(dir.join(USAGE_METRICS_FILE), enabled)
+    }
+
+    #[test]
+    fn test_disabled_records_and_writes_nothing() {
+        let metrics = metrics_in("off", false);
+        metrics.record_command("tasks_list");
+        metrics.record_outcome("tasks_list", true);
+        metrics.record_intent("tasks_edit", true);
+        metrics.flush().unwrap();
+        assert_eq!(metrics.snapshot(), Metrics::default());
+        assert!(!metrics.path().exists());
+    }
+
+    #[test]
+    fn test_counts_persist_and_reset() {
+        let metrics = metrics_in("on", true);
+        metrics.record_command("tasks_list");
+        metrics.record_command("tasks_list");
+        metrics.record_outcome("tasks_list", true);
+        metrics.record_outcome("tasks_list", false);
+        metrics.record_intent("tasks_edit", true);
+        metrics.record_intent("tasks_edit", false);
+        metrics.flush().unwrap();
+
+        let reloaded = UsageMetrics::load(metrics.path().to_path_buf(), true);
+        let snapshot = reloaded.snapshot();
+        assert!(snapshot.since.is_some());
+        assert_eq!(
+            snapshot.commands["tasks_list"],
+            CommandMetrics {
+                calls: 2,
+                outcomes: Outcomes {
+                    success: 1,
+                    failure: 1
+                }
+            }
+        );
+        assert_eq!(
+            snapshot.intents["tasks_edit"],
+            Outcomes {
+                success: 1,
+                failure: 1
+            }
+        );
+        // Plain JSON, readable as is
+        let raw: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(metrics.path()).unwrap()).unwrap();
+        assert_eq!(raw["intents"]["tasks_edit"]["failure"], 1);
+        assert_eq!(
+            raw["commands"]["tasks_list"],
+            serde_json::json!({ "calls": 2, "success": 1, "failure": 1 })
+        );
+
+        reloaded.reset().unwrap();
+        assert_eq!(reloaded.snapshot(), Metrics::default());
+        assert!(!reloaded.path().exists());
+        reloaded.reset().unwrap();
+        let _ = std::fs::remove_dir_all(metrics.path().parent().unwrap());
+    }
+}
@@ -0,0 +1,401 @@
+//! Verification commands
+//!
+//! A command run in the project root whose exit status confirms a
+//! checkpoint (`cargo test` for "tests"). It is split into arguments and
+//! started directly, or handed to the platform shell when asked to; only
+//! commands on the project's allowlist in settings run at all. Output
+//! lines are passed on as they arrive (a line longer than [`OUTPUT_CAP`]
+//! as several) and the last [`OUTPUT_CAP`] bytes are kept.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
+use tokio::sync::mpsc;
+
+/// Event carrying one [`OutputLine`] of a running command
+pub const VERIFY_OUTPUT_EVENT: &str = "verify-output";
+/// Output kept for the response
+pub const OUTPUT_CAP: usize = 64 * 1024;
+/// Output tail kept in the checkpoint note
+pub const NOTE_TAIL_BYTES: usize = 2000;
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+pub const MAX_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum VerificationError {
+    #[error("Command is empty")]
+    Empty,
+    #[error("Unbalanced quotes in command: {0}")]
+    Syntax(String),
+    #[error("Command is not in this project's verification allowlist: {0}")]
+    NotAllowed(String),
+    #[error("Failed to start {command}: {detail}")]
+    Spawn { command: String, detail: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Payload of [`VERIFY_OUTPUT_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputLine {
+    pub task_id: String,
+    pub path: String,
+    pub checkpoint: String,
+    pub stream: OutputStream,
+    /// Without the line break
+    pub line: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunOutcome {
+    /// `None` when killed (timeout, signal)
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    /// Both streams in arrival order, the last [`OUTPUT_CAP`] bytes
+    pub output: String,
+    /// Earlier output was dropped
+    pub truncated: bool,
+    pub timed_out: bool,
+}
+
+impl RunOutcome {
+    pub fn passed(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    /// Checkpoint note: what ran, how it ended and the output's last
+    /// [`NOTE_TAIL_BYTES`]
+    pub fn note(&self, command: &str) -> String {
+        let ended = match (self.timed_out, self.exit_code) {
+            (true, _) => "timed out".to_string(),
+            (false, Some(code)) => format!("exited with {}", code),
+            (false, None) => "was killed".to_string(),
+        };
+        let mut note = format!(
+            "`{}` {} after {:.1} s",
+            command,
+            ended,
+            self.duration_ms as f64 / 1000.0
+        );
+        let output = self.output.trim_end();
+        if !output.is_empty() {
+            note.push_str("\n\n");
+            note.push_str(tail(output, NOTE_TAIL_BYTES));
+        }
+        note
+    }
+}
+
+/// The last `max` bytes of `text`, cut at a char boundary
+fn tail(text: &str, max: usize) -> &str {
+    let mut start = text.len().saturating_sub(max);
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    &text[start..]
+}
+
+/// Whether `command` matches an allowlist entry: the same command, or for
+/// an entry ending in ` *`, the same program and leading arguments followed
+/// by any others. Through a shell, only exact entries count (extra text
+/// could chain other commands).
+pub fn is_allowed(allowlist: &[String], command: &str, use_shell: bool) -> bool {
+    let command = command.trim();
+    allowlist.iter().map(|entry| entry.trim()).any(|entry| {
+        if entry == command {
+            return true;
+        }
+        match entry.strip_suffix(" *") {
+            Some(prefix) if !use_shell => {
+                command == prefix.trim_end()
+                    || command
+                        .strip_prefix(prefix.trim_end())
+                        .is_some_and(|rest| rest.starts_with(char::is_whitespace))
+            }
+            _ => false,
+        }
+    })
+}
+
+/// Program and arguments of `command`, split like a POSIX shell would
+/// (quotes, backslashes) but without expansion
+pub fn split(command: &str) -> Result<Vec<String>, VerificationError> {
+    let args = shlex::split(command).ok_or_else(|| VerificationError::Syntax(command.into()))?;
+    if args.is_empty() {
+        return Err(VerificationError::Empty);
+    }
+    Ok(args)
+}
+
+fn command_for(
+    command: &str,
+    use_shell: bool,
+) -> Result<tokio::process::Command, VerificationError> {
+    if command.trim().is_empty() {
+        return Err(VerificationError::Empty);
+    }
+    if use_shell {
+        let (shell, flag) = if cfg!(windows) {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
+        };
+        let mut cmd = tokio::process::Command::new(shell);
+        cmd.arg(flag).arg(command);
+        return Ok(cmd);
+    }
+    let args = split(command)?;
+    let mut cmd = tokio::process::Command::new(&args[0]);
+    cmd.args(&args[1..]);
+    Ok(cmd)
+}
+
+/// Bytes at the end of `bytes` that start a UTF-8 character they don't finish
+fn incomplete_char(bytes: &[u8]) -> usize {
+    for back in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - back];
+        if byte & 0xC0 != 0x80 {
+            let width = match byte {
+                0xC0..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF7 => 4,
+                _ => 1,
+            };
+            return if width > back { back } else { 0 };
+        }
+    }
+    0
+}
+
+/// Send the lines of `reader` (lossily decoded) to `lines`, at most
+/// [`OUTPUT_CAP`] bytes each: a longer one goes on in the next
+async fn forward<R: AsyncRead + Unpin>(
+    reader: Option<R>,
+    stream: OutputStream,
+    lines: mpsc::UnboundedSender<(OutputStream, String)>,
+) {
+    let Some(reader) = reader else {
+        return;
+    };
+    let mut reader = BufReader::new(reader);
+    // Holds the start of a character split by the cap between reads
+    let mut buf = Vec::new();
+    loop {
+        let limit = (OUTPUT_CAP - buf.len()) as u64;
+        let read = (&mut reader).take(limit).read_until(b'\n', &mut buf).await;
+        let rest = match read {
+            Ok(0) | Err(_) if buf.is_empty() => break,
+            Ok(0) | Err(_) => Vec::new(),
+            Ok(_) if buf.ends_with(b"\n") => Vec::new(),
+            Ok(_) => buf.split_off(buf.len() - incomplete_char(&buf)),
+        };
+        let line = String::from_utf8_lossy(&buf);
+        let line = line.trim_end_matches(['\n', '\r']).to_string();
+        if lines.send((stream, line)).is_err() {
+            break;
+        }
+        buf = rest;
+    }
+}
+
+/// Run `command` in `root`, handing each output line to `on_line`; killed
+/// after `timeout`
+pub async fn run(
+    root: &Path,
+    command: &str,
+    use_shell: bool,
+    timeout: Duration,
+    mut on_line: impl FnMut(OutputStream, &str),
+) -> Result<RunOutcome, VerificationError> {
+    let mut cmd = command_for(command, use_shell)?;
+    let started = Instant::now();
+    let mut child = cmd
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| VerificationError::Spawn {
+            command: command.to_string(),
+            detail: e.to_string(),
+        })?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let stdout = forward(child.stdout.take(), OutputStream::Stdout, tx.clone());
+    let stderr = forward(child.stderr.take(), OutputStream::Stderr, tx);
+
+    let mut output = String::new();
+    let mut truncated = false;
+    let finished = tokio::time::timeout(timeout, async {
+        // Ends once both streams are closed
+        let collect = async {
+            while let Some((stream, line)) = rx.recv().await {
+                on_line(stream, &line);
+                output.push_str(&line);
+                output.push('\n');
+                if output.len() > OUTPUT_CAP {
+                    let keep = tail(&output, OUTPUT_CAP).len();
+                    output.drain(..output.len() - keep);
+                    truncated = true;
+                }
+            }
+        };
+        tokio::join!(stdout, stderr, collect);
+        child.wait().await
+    })
+    .await;
+
+    let (exit_code, timed_out) = match finished {
+        Ok(Ok(status)) => (status.code(), false),
+        Ok(Err(e)) => {
+            log::warn!("Failed to wait for {}: {}", command, e);
+            (None, false)
+        }
+        Err(_) => {
+            if let Err(e) = child.kill().await {
+                log::warn!("Failed to kill {}: {}", command, e);
+            }
+            (None, true)
+        }
+    };
+    Ok(RunOutcome {
+        exit_code,
+        duration_ms: started.elapsed().as_millis() as u64,
+        output,
+        truncated,
+        timed_out,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowlist() {
+        let allowlist = vec!["cargo test *".to_string(), "make check".to_string()];
+        assert!(is_allowed(&allowlist, "cargo test", false));
+        assert!(is_allowed(&allowlist, " cargo test --lib ", false));
+        assert!(!is_allowed(&allowlist, "cargo testify", false));
+        assert!(is_allowed(&allowlist, "make check", true));
+        assert!(!is_allowed(&allowlist, "make check extra", false));
+        // Through a shell only exact entries count
+        assert!(!is_allowed(&allowlist, "cargo test; rm -rf /", true));
+        assert!(!is_allowed(&[], "make check", false));
+    }
+
+    #[test]
+    fn test_split() {
+        assert_eq!(
+            split(r#"pytest -k "slow and not db" tests/"#).unwrap(),
+            ["pytest", "-k", "slow and not db", "tests/"]
+        );
+        assert_eq!(split("   "), Err(VerificationError::Empty));
+        assert!(matches!(
+            split("echo 'open"),
+            Err(VerificationError::Syntax(_))
+        ));
+    }
+
+    #[test]
+    fn test_note_keeps_the_tail() {
+        let outcome = RunOutcome {
+            exit_code: Some(1),
+            duration_ms: 1250,
+            output: format!("{}\nFAILED test_b\n", "x".repeat(NOTE_TAIL_BYTES)),
+            truncated: false,
+            timed_out: false,
+        };
+        let note = outcome.note("pytest");
+        assert!(
+            note.starts_with("`pytest` exited with 1 after 1.2 s\n\n"),
+            "{}",
+            note
+        );
+        assert!(note.ends_with("xx\nFAILED test_b"), "{}", note);
+        assert!(note.len() < NOTE_TAIL_BYTES + 60);
+        assert_eq!(tail("añb", 2), "b");
+    }
+
+    #[tokio::test]
+    async fn test_forward_splits_long_lines() {
+        // The cap falls inside a two-byte character
+        let long = format!("a{}", "é".repeat(OUTPUT_CAP / 2));
+        let input = format!("{}\nend\r\n", long);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        forward(Some(input.as_bytes()), OutputStream::Stdout, tx).await;
+        let mut lines = Vec::new();
+        while let Some((_, line)) = rx.recv().await {
+            lines.push(line);
+        }
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.len() <= OUTPUT_CAP));
+        assert_eq!(lines[0].len(), OUTPUT_CAP - 1);
+        assert_eq!(lines[..2].concat(), long);
+        assert_eq!(lines[2], "end");
+        assert_eq!(incomplete_char("añ".as_bytes()), 0);
+        assert_eq!(incomplete_char(&"añ".as_bytes()[..2]), 1);
+        assert_eq!(incomplete_char(&"a€".as_bytes()[..3]), 2);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_streams_and_caps_output() {
+        let dir = std::env::temp_dir();
+        let mut lines = Vec::new();
+        let outcome = run(
+            &dir,
+            "printf 'one\\ntwo\\n'; printf 'oops\\n' >&2; exit 3",
+            true,
+            DEFAULT_TIMEOUT,
+            |stream, line| lines.push((stream, line.to_string())),
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome.exit_code, Some(3));
+        assert!(!outcome.passed());
+        assert!(lines.contains(&(OutputStream::Stdout, "two".to_string())));
+        assert!(lines.contains(&(OutputStream::Stderr, "oops".to_string())));
+        assert_eq!(outcome.output.lines().count(), 3);
+
+        let big = run(&dir, "seq 1 20000", false, DEFAULT_TIMEOUT, |_, _| {})
+            .await
+            .unwrap();
+        assert!(big.passed());
+        assert!(big.truncated);
+        assert!(big.output.len() <= OUTPUT_CAP);
+        assert!(big.output.ends_with("19999\n20000\n"));
+
+        let slow = run(
+            &dir,
+            "sleep 5",
+            false,
+            Duration::from_millis(200),
+            |_, _| {},
+        )
+        .await
+        .unwrap();
+        assert!(slow.timed_out);
+        assert_eq!(slow.exit_code, None);
+        assert!(slow.duration_ms < 5000);
+
+        let missing = run(
+            &dir,
+            "apply-task-no-such-tool",
+            false,
+            DEFAULT_TIMEOUT,
+            |_, _| {},
+        )
+        .await;
+        assert!(matches!(missing, Err(VerificationError::Spawn { .. })));
+    }
+}
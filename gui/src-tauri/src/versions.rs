@@ -0,0 +1,306 @@
+//! GUI, runtime, Python and backend versions
+//!
+//! The backend version comes from the MCP handshake's `serverInfo`, falling
+//! back to `apply_task --version`. A backend whose major version is outside
+//! [`COMPATIBLE_BACKEND_MAJORS`] gets a `compat_warning` (and a one-time
+//! `version-mismatch` event at startup). [`check_backend`] compares it with
+//! [`MIN_BACKEND_VERSION`] and says how to upgrade for the install mode.
+
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::doctor::{self, PROCESS_TIMEOUT};
+use crate::python::InstallMode;
+use crate::AppState;
+
+/// Event emitted once at startup when the backend is out of range
+pub const VERSION_MISMATCH_EVENT: &str = "version-mismatch";
+
+/// Backend major versions this GUI speaks to
+const COMPATIBLE_BACKEND_MAJORS: RangeInclusive<u64> = 0..=1;
+/// Oldest backend release with every tool this GUI calls
+pub const MIN_BACKEND_VERSION: &str = "0.1.0";
+/// Appended to errors about tools the backend lacks
+pub const VERSION_CHECK_HINT: &str =
+    "The backend may be older than this GUI; run check_backend_version for upgrade steps";
+/// Limit for the handshake that reports `serverInfo`
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What `versions` returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Versions {
+    pub gui: String,
+    pub tauri: String,
+    /// `python --version` of the configured interpreter
+    pub python: Option<String>,
+    pub backend: Option<String>,
+    /// `server_info` or `cli` (`apply_task --version`)
+    pub backend_source: Option<String>,
+    pub compat_warning: Option<String>,
+}
+
+fn major(version: &str) -> Option<u64> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Warning for a backend outside the supported major versions (an
+/// unparseable version, e.g. a dev build, is given the benefit of the doubt)
+fn compat_warning(backend: &str) -> Option<String> {
+    let range = &COMPATIBLE_BACKEND_MAJORS;
+    match major(backend) {
+        Some(major) if !range.contains(&major) => Some(format!(
+            "Backend {} is not supported by GUI {} (expects major {}-{}); update both to matching releases",
+            backend,
+            env!("CARGO_PKG_VERSION"),
+            range.start(),
+            range.end()
+        )),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendVersionStatus {
+    Ok,
+    Outdated,
+    /// No version reported, or not a release version (e.g. a dev build)
+    Unknown,
+}
+
+/// What `check_backend_version` returns
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackendVersionCheck {
+    pub status: BackendVersionStatus,
+    pub backend: Option<String>,
+    pub minimum: String,
+    pub install_mode: InstallMode,
+    /// Command that upgrades this installation (`None` for checkouts)
+    pub upgrade_command: Option<String>,
+    pub guidance: String,
+}
+
+/// `1.2`, `v1.2.3` and `1.2.3.dev0` are read as far as they go
+fn release_version(version: &str) -> Option<semver::Version> {
+    let mut parts = version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .map_while(|p| p.parse::<u64>().ok());
+    let major = parts.next()?;
+    Some(semver::Version::new(
+        major,
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    ))
+}
+
+/// Upgrade step for `mode`: the command, if there is one, and what to tell the user
+fn upgrade_step(mode: InstallMode) -> (Option<&'static str>, &'static str) {
+    match mode {
+        InstallMode::Package => (
+            Some("pip install -U apply_task"),
+            "Upgrade the package in the backend's Python environment",
+        ),
+        InstallMode::ConsoleScript => (
+            Some("uv tool upgrade apply_task"),
+            "Upgrade the apply_task tool installation",
+        ),
+        InstallMode::Checkout | InstallMode::Module => (
+            None,
+            "Update your checkout (git pull) and restart the backend",
+        ),
+        InstallMode::EnvPath => (
+            None,
+            "Update the apply_task script APPLY_TASK_PATH points to",
+        ),
+        InstallMode::Container => (
+            None,
+            "Rebuild or pull the container image and restart the backend",
+        ),
+        InstallMode::Ssh => (
+            None,
+            "Upgrade apply_task on the SSH host and restart the backend",
+        ),
+    }
+}
+
+/// Compare `backend` with [`MIN_BACKEND_VERSION`]
+pub fn check_backend(backend: Option<&str>, mode: InstallMode) -> BackendVersionCheck {
+    let minimum = release_version(MIN_BACKEND_VERSION).expect("valid MIN_BACKEND_VERSION");
+    let (upgrade_command, step) = upgrade_step(mode);
+    let (status, guidance) = match backend.and_then(release_version) {
+        Some(version) if version >= minimum => (
+            BackendVersionStatus::Ok,
+            format!("Backend {} meets the minimum {}", version, minimum),
+        ),
+        Some(version) => (
+            BackendVersionStatus::Outdated,
+            format!(
+                "Backend {} is older than {}, the minimum for GUI {}. {}",
+                version,
+                minimum,
+                env!("CARGO_PKG_VERSION"),
+                upgrade_command.map_or_else(|| step.to_string(), |c| format!("{}: {}", step, c))
+            ),
+        ),
+        None => (
+            BackendVersionStatus::Unknown,
+            format!(
+                "Backend version unknown; if tools are missing, make sure it is at least {}",
+                minimum
+            ),
+        ),
+    };
+    BackendVersionCheck {
+        status,
+        backend: backend.map(String::from),
+        minimum: minimum.to_string(),
+        install_mode: mode,
+        upgrade_command: upgrade_command.map(String::from),
+        guidance,
+    }
+}
+
+/// `apply_task --version` run the way the backend is launched
+async fn cli_version(state: &AppState) -> Option<String> {
+    let launch = state.bridge.launch();
+    let mut args = launch.args.clone();
+    match args.last().map(String::as_str) {
+        Some("mcp") => *args.last_mut()? = "--version".to_string(),
+        _ if launch.mode == InstallMode::Module => {
+            args = ["-m", "apply_task", "--version"].map(String::from).to_vec()
+        }
+        _ => return None,
+    }
+    let mut cmd = tokio::process::Command::new(&launch.program);
+    cmd.args(&args).current_dir(std::env::temp_dir());
+    if launch.pythonpath {
+        cmd.env("PYTHONPATH", &state.apply_task_root);
+    }
+    let version = doctor::output(cmd, PROCESS_TIMEOUT).await.ok()?;
+    // Printed by a checkout that isn't pip-installed
+    (!version.is_empty() && version != "0.0.0").then_some(version)
+}
+
+async fn probe(app: &AppHandle, state: &AppState) -> Versions {
+    let bridge = &state.bridge;
+    let mut cmd = tokio::process::Command::new(bridge.python_path());
+    cmd.arg("--version");
+    // `Python 3.12.1`
+    let python = doctor::output(cmd, PROCESS_TIMEOUT)
+        .await
+        .ok()
+        .and_then(|out| out.split_whitespace().nth(1).map(String::from));
+
+    if let Ok(Err(e)) = tokio::time::timeout(HANDSHAKE_TIMEOUT, bridge.connect()).await {
+        log::warn!("Backend handshake for versions failed: {:#}", e);
+    }
+    let (backend, backend_source) = match bridge.server_version() {
+        Some(version) => (Some(version), Some("server_info")),
+        None => match cli_version(state).await {
+            Some(version) => (Some(version), Some("cli")),
+            None => (None, None),
+        },
+    };
+
+    Versions {
+        gui: app.package_info().version.to_string(),
+        tauri: tauri::VERSION.to_string(),
+        python,
+        compat_warning: backend.as_deref().and_then(compat_warning),
+        backend,
+        backend_source: backend_source.map(String::from),
+    }
+}
+
+/// Cached versions (probed on first use and after the interpreter changes)
+pub async fn versions(app: &AppHandle, state: &AppState) -> Versions {
+    let mut cached = state.versions.lock().await;
+    if let Some(versions) = cached.as_ref() {
+        return versions.clone();
+    }
+    let versions = probe(app, state).await;
+    *cached = Some(versions.clone());
+    versions
+}
+
+/// Probe once at startup and emit `version-mismatch` if out of range
+pub fn spawn_startup_check(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app.try_state::<AppState>() else {
+            return;
+        };
+        let versions = versions(&app, &state).await;
+        if let Some(warning) = &versions.compat_warning {
+            log::warn!("{}", warning);
+            if let Err(e) = app.emit(VERSION_MISMATCH_EVENT, &versions) {
+                log::warn!("Failed to emit {}: {}", VERSION_MISMATCH_EVENT, e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compat_warning() {
+        assert_eq!(major("1.0.0"), Some(1));
+        assert_eq!(major("v0.1"), Some(0));
+        assert_eq!(major("dev"), None);
+        assert_eq!(compat_warning("1.0.0"), None);
+        assert_eq!(compat_warning("0.1.0"), None);
+        assert!(compat_warning("2.0.0")
+            .unwrap()
+            .contains("expects major 0-1"));
+        assert_eq!(compat_warning("dev"), None);
+    }
+
+    #[test]
+    fn test_check_backend_guidance() {
+        let outdated = check_backend(Some("0.0.9"), InstallMode::Package);
+        assert_eq!(outdated.status, BackendVersionStatus::Outdated);
+        assert_eq!(
+            outdated.upgrade_command.as_deref(),
+            Some("pip install -U apply_task")
+        );
+        assert!(outdated.guidance.contains("pip install -U apply_task"));
+
+        let uv = check_backend(Some("v0.0.1"), InstallMode::ConsoleScript);
+        assert_eq!(
+            uv.upgrade_command.as_deref(),
+            Some("uv tool upgrade apply_task")
+        );
+        let checkout = check_backend(Some("0.0.1"), InstallMode::Checkout);
+        assert_eq!(checkout.upgrade_command, None);
+        assert!(checkout.guidance.contains("Update your checkout"));
+
+        for ok in ["0.1.0", "1.0", "1.2.3.dev0"] {
+            assert_eq!(
+                check_backend(Some(ok), InstallMode::Package).status,
+                BackendVersionStatus::Ok,
+                "{}",
+                ok
+            );
+        }
+        assert_eq!(
+            check_backend(Some("dev"), InstallMode::Module).status,
+            BackendVersionStatus::Unknown
+        );
+        assert_eq!(
+            check_backend(None, InstallMode::Module).status,
+            BackendVersionStatus::Unknown
+        );
+    }
+}
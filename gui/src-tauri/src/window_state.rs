@@ -0,0 +1,376 @@
+//! Window geometry
+//!
+//! Size, position and maximized state of each window, keyed by label, in
+//! `window-state.json` in the app config dir. Moves and resizes are saved
+//! once they settle; on startup the saved geometry is applied before the
+//! window is shown, moved onto the nearest connected monitor when it was
+//! saved on one that is gone. The quick-add popup always opens centered.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tauri::{LogicalSize, Manager, PhysicalPosition, PhysicalSize, Runtime, Window};
+
+use crate::quick_add;
+use crate::sidecar;
+use crate::windows;
+use crate::AppState;
+
+/// File name of the saved geometry inside the app config dir
+pub const WINDOW_STATE_FILE: &str = "window-state.json";
+/// Quiet period after the last move/resize before writing the file
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+/// Size from `tauri.conf.json`, used by `reset`
+const DEFAULT_SIZE: LogicalSize<f64> = LogicalSize::new(1200.0, 800.0);
+
+/// Outer position and inner size in physical pixels; a maximized window
+/// keeps the geometry it had before maximizing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+}
+
+/// A monitor's work area in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Area {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Area {
+    fn right(&self) -> i64 {
+        self.x as i64 + self.width as i64
+    }
+
+    fn bottom(&self) -> i64 {
+        self.y as i64 + self.height as i64
+    }
+
+    fn overlap(&self, other: &Area) -> i64 {
+        let w = self.right().min(other.right()) - (self.x.max(other.x) as i64);
+        let h = self.bottom().min(other.bottom()) - (self.y.max(other.y) as i64);
+        w.max(0) * h.max(0)
+    }
+
+    /// Squared distance from a point to the area (0 inside)
+    fn distance(&self, (x, y): (i64, i64)) -> i64 {
+        let dx = (self.x as i64 - x).max(x - self.right()).max(0);
+        let dy = (self.y as i64 - y).max(y - self.bottom()).max(0);
+        dx * dx + dy * dy
+    }
+}
+
+impl WindowGeometry {
+    fn area(&self) -> Area {
+        Area {
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+/// Fit `geometry` onto the monitor it overlaps most (or the nearest one):
+/// shrunk to the work area if larger, moved inside it otherwise
+pub fn clamp(geometry: WindowGeometry, monitors: &[Area]) -> WindowGeometry {
+    let window = geometry.area();
+    let center = (
+        window.x as i64 + window.width as i64 / 2,
+        window.y as i64 + window.height as i64 / 2,
+    );
+    let best = monitors
+        .iter()
+        .max_by_key(|m| m.overlap(&window))
+        .filter(|m| m.overlap(&window) > 0)
+        .or_else(|| monitors.iter().min_by_key(|m| m.distance(center)));
+    let Some(monitor) = best else {
+        return geometry;
+    };
+
+    let width = geometry.width.min(monitor.width);
+    let height = geometry.height.min(monitor.height);
+    let max_x = monitor.right() - width as i64;
+    let max_y = monitor.bottom() - height as i64;
+    WindowGeometry {
+        x: (geometry.x as i64).clamp(monitor.x as i64, max_x) as i32,
+        y: (geometry.y as i64).clamp(monitor.y as i64, max_y) as i32,
+        width,
+        height,
+        maximized: geometry.maximized,
+    }
+}
+
+/// Secondary windows come and go with fresh labels; only fixed ones are saved
+fn tracked(label: &str) -> bool {
+    label != quick_add::WINDOW_LABEL && !windows::is_scoped(label)
+}
+
+/// Saved geometry of every window (`AppState::window_state`)
+#[derive(Debug, Default)]
+pub struct WindowStates {
+    path: PathBuf,
+    saved: Mutex<BTreeMap<String, WindowGeometry>>,
+    /// Bumped on every change; a pending save only writes if still current
+    generation: AtomicU64,
+}
+
+impl WindowStates {
+    /// Read the file (missing or unreadable: nothing saved)
+    pub fn load(path: PathBuf) -> Self {
+        let saved = sidecar::read_json(&path).unwrap_or_else(|e| {
+            log::warn!("Ignoring saved window state {:?}: {:#}", path, e);
+            BTreeMap::new()
+        });
+        Self {
+            path,
+            saved: Mutex::new(saved),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self, label: &str) -> Option<WindowGeometry> {
+        self.lock().get(label).copied()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, WindowGeometry>> {
+        self.saved.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record a change; returns the generation a delayed save should match
+    fn update(&self, label: &str, geometry: WindowGeometry) -> Option<u64> {
+        let mut saved = self.lock();
+        if saved.get(label) == Some(&geometry) {
+            return None;
+        }
+        saved.insert(label.to_string(), geometry);
+        Some(self.generation.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        sidecar::write_json(&self.path, &*self.lock())
+    }
+
+    /// Forget everything saved (the file too)
+    pub fn clear(&self) -> Result<()> {
+        self.lock().clear();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.save()
+    }
+}
+
+fn monitor_areas<R: Runtime>(window: &Window<R>) -> Vec<Area> {
+    window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|m| {
+            let area = m.work_area();
+            Area {
+                x: area.position.x,
+                y: area.position.y,
+                width: area.size.width,
+                height: area.size.height,
+            }
+        })
+        .collect()
+}
+
+/// Current geometry; while maximized the previously saved size/position
+/// are kept (a minimized window reports nothing useful)
+fn current<R: Runtime>(
+    window: &Window<R>,
+    previous: Option<WindowGeometry>,
+) -> Option<WindowGeometry> {
+    if window.is_minimized().unwrap_or(false) {
+        return None;
+    }
+    let maximized = window.is_maximized().unwrap_or(false);
+    if maximized {
+        if let Some(previous) = previous {
+            return Some(WindowGeometry {
+                maximized,
+                ..previous
+            });
+        }
+    }
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+    Some(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized,
+    })
+}
+
+/// Remember a moved/resized window and save once it has settled
+pub fn track<R: Runtime>(window: &Window<R>) {
+    if !tracked(window.label()) {
+        return;
+    }
+    let Some(state) = window.try_state::<AppState>() else {
+        return;
+    };
+    let states = &state.window_state;
+    let Some(geometry) = current(window, states.get(window.label())) else {
+        return;
+    };
+    let Some(generation) = states.update(window.label(), geometry) else {
+        return;
+    };
+
+    let app = window.app_handle().clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DELAY).await;
+        let Some(state) = app.try_state::<AppState>() else {
+            return;
+        };
+        if state.window_state.generation.load(Ordering::SeqCst) == generation {
+            if let Err(e) = state.window_state.save() {
+                log::warn!("Failed to save window state: {:#}", e);
+            }
+        }
+    });
+}
+
+/// Apply the saved geometry, fitted to the connected monitors
+pub fn restore<R: Runtime>(window: &Window<R>) {
+    let Some(state) = window.try_state::<AppState>() else {
+        return;
+    };
+    let Some(saved) = state.window_state.get(window.label()) else {
+        return;
+    };
+    let geometry = clamp(saved, &monitor_areas(window));
+    if geometry != saved {
+        log::info!(
+            "Saved {} window geometry is off-screen; moved to {:?}",
+            window.label(),
+            geometry
+        );
+    }
+    let applied = window
+        .set_size(PhysicalSize::new(geometry.width, geometry.height))
+        .and_then(|_| window.set_position(PhysicalPosition::new(geometry.x, geometry.y)))
+        .and_then(|_| {
+            if geometry.maximized {
+                window.maximize()
+            } else {
+                Ok(())
+            }
+        });
+    if let Err(e) = applied {
+        log::warn!(
+            "Failed to restore {} window geometry: {}",
+            window.label(),
+            e
+        );
+    }
+}
+
+/// Forget saved geometry and put open windows back to the default size
+pub fn reset<R: Runtime, M: Manager<R>>(manager: &M) -> Result<()> {
+    if let Some(state) = manager.try_state::<AppState>() {
+        state.window_state.clear()?;
+    }
+    for (label, window) in manager.webview_windows() {
+        if !tracked(&label) {
+            continue;
+        }
+        window.unmaximize()?;
+        window.set_size(DEFAULT_SIZE)?;
+        window.center()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geometry(x: i32, y: i32, width: u32, height: u32) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+            maximized: false,
+        }
+    }
+
+    const LEFT: Area = Area {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+    const RIGHT: Area = Area {
+        x: 1920,
+        y: 0,
+        width: 2560,
+        height: 1440,
+    };
+
+    #[test]
+    fn test_clamp_keeps_visible_windows() {
+        let on_right = geometry(2000, 100, 1200, 800);
+        assert_eq!(clamp(on_right, &[LEFT, RIGHT]), on_right);
+        // No monitor information: leave it alone
+        let far = geometry(9000, 9000, 1200, 800);
+        assert_eq!(clamp(far, &[]), far);
+    }
+
+    #[test]
+    fn test_clamp_moves_windows_onto_a_monitor() {
+        // Saved on a third monitor that is gone: nearest is RIGHT
+        let mut maximized = geometry(5000, 200, 1200, 800);
+        maximized.maximized = true;
+        let mut expected = geometry(3280, 200, 1200, 800);
+        expected.maximized = true;
+        assert_eq!(clamp(maximized, &[LEFT, RIGHT]), expected);
+
+        // Straddling: stays on the monitor it overlaps most
+        assert_eq!(
+            clamp(geometry(1500, -50, 1200, 800), &[LEFT, RIGHT]),
+            geometry(1920, 0, 1200, 800)
+        );
+        // Larger than the work area: shrunk to it
+        assert_eq!(
+            clamp(geometry(-100, -100, 3000, 2000), &[LEFT]),
+            geometry(0, 0, 1920, 1080)
+        );
+    }
+
+    #[test]
+    fn test_states_persist_by_label() {
+        let dir = std::env::temp_dir().join(format!("apply-task-window-{}", std::process::id()));
+        let path = dir.join(WINDOW_STATE_FILE);
+        let states = WindowStates::load(path.clone());
+        assert_eq!(states.get("main"), None);
+
+        let main = geometry(10, 20, 1200, 800);
+        let first = states.update("main", main).unwrap();
+        assert_eq!(states.update("main", main), None, "unchanged");
+        assert!(states.update("other", geometry(0, 0, 800, 600)).unwrap() > first);
+        states.save().unwrap();
+
+        let reloaded = WindowStates::load(path.clone());
+        assert_eq!(reloaded.get("main"), Some(main));
+        reloaded.clear().unwrap();
+        assert_eq!(WindowStates::load(path).get("other"), None);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,129 @@
+//! Secondary windows
+//!
+//! `open_window` opens another main-style window pinned to a namespace and
+//! view. The scope is kept here by window label; the frontend reads it with
+//! `window_scope` and passes the namespace to commands itself, so a scoped
+//! window hits the same namespace checks as a deep link. The main window has
+//! no entry (its scope is whatever the open project is). Background services
+//! emit to the windows that subscribed, and closing a secondary window drops
+//! its subscriptions along with its scope.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, WebviewUrl, WebviewWindowBuilder};
+
+use crate::AppState;
+
+/// Label prefix of secondary windows (`window-1`, `window-2`, ...)
+pub const LABEL_PREFIX: &str = "window-";
+
+/// What a secondary window shows
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WindowScope {
+    pub namespace: Option<String>,
+    /// Frontend view (`board`, `list`, ...)
+    pub view: Option<String>,
+}
+
+/// Whether `label` is a window opened by `open_window`
+pub fn is_scoped(label: &str) -> bool {
+    label.starts_with(LABEL_PREFIX)
+}
+
+/// Scopes of open secondary windows (kept in `AppState`)
+#[derive(Debug, Default)]
+pub struct WindowScopes {
+    scopes: Mutex<HashMap<String, WindowScope>>,
+    next: AtomicU64,
+}
+
+impl WindowScopes {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, WindowScope>> {
+        self.scopes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Register `scope` under a fresh label
+    pub fn insert(&self, scope: WindowScope) -> String {
+        let label = format!(
+            "{}{}",
+            LABEL_PREFIX,
+            self.next.fetch_add(1, Ordering::SeqCst) + 1
+        );
+        self.lock().insert(label.clone(), scope);
+        label
+    }
+
+    /// Scope of `label` (unscoped windows: empty)
+    pub fn get(&self, label: &str) -> WindowScope {
+        self.lock().get(label).cloned().unwrap_or_default()
+    }
+
+    pub fn remove(&self, label: &str) -> Option<WindowScope> {
+        self.lock().remove(label)
+    }
+}
+
+/// Open a secondary window on `scope`; returns its label
+pub fn open(app: &AppHandle, state: &AppState, scope: WindowScope) -> Result<String> {
+    let title = match &scope.namespace {
+        Some(namespace) => format!("Apply Task — {}", namespace),
+        None => "Apply Task".to_string(),
+    };
+    let label = state.windows.insert(scope);
+    let built = WebviewWindowBuilder::new(app, &label, WebviewUrl::App("index.html".into()))
+        .title(title)
+        .inner_size(1200.0, 800.0)
+        .min_inner_size(800.0, 600.0)
+        .focused(true)
+        .build();
+    if let Err(e) = built {
+        state.windows.remove(&label);
+        return Err(e).with_context(|| format!("Failed to open window {}", label));
+    }
+    log::info!("Opened window {}", label);
+    Ok(label)
+}
+
+/// Drop what a destroyed window left behind: its background subscriptions,
+/// and for a secondary window its scope
+pub fn closed<R: Runtime>(app: &AppHandle<R>, label: &str) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    state.ai_status.unsubscribe(label);
+    state.list_refresh.stop(label);
+    state.progress_streams.closed(label);
+    if state.windows.remove(label).is_some() {
+        log::info!("Closed window {}", label);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes_by_label() {
+        let scopes = WindowScopes::default();
+        let work = WindowScope {
+            namespace: Some("work".into()),
+            view: Some("board".into()),
+        };
+        let first = scopes.insert(work.clone());
+        let second = scopes.insert(WindowScope::default());
+        assert_eq!(first, "window-1");
+        assert_ne!(first, second);
+        assert!(is_scoped(&first));
+        assert!(!is_scoped("main"));
+
+        assert_eq!(scopes.get(&first), work);
+        assert_eq!(scopes.get("main"), WindowScope::default());
+        assert_eq!(scopes.remove(&first), Some(work));
+        assert_eq!(scopes.remove(&first), None);
+        assert_eq!(scopes.remove(&second), Some(WindowScope::default()));
+    }
+}
@@ -0,0 +1,95 @@
+//! The scripted server the bridge tests run in place of Python
+
+use std::io::{BufRead, BufReader, Write};
+use std::process::{ChildStdin, ChildStdout, Command, Stdio};
+
+use serde_json::{json, Value};
+
+fn receive(stdout: &mut BufReader<ChildStdout>) -> Value {
+    let mut line = String::new();
+    stdout.read_line(&mut line).unwrap();
+    serde_json::from_str(&line).unwrap()
+}
+
+fn exchange(stdin: &mut ChildStdin, stdout: &mut BufReader<ChildStdout>, request: Value) -> Value {
+    writeln!(stdin, "{}", request).unwrap();
+    receive(stdout)
+}
+
+fn call(id: u64, name: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "tools/call",
+        "params": { "name": name, "arguments": { "task": "T-1" } },
+    })
+}
+
+#[test]
+fn test_answers_from_the_scenario() {
+    let path =
+        std::env::temp_dir().join(format!("apply-task-scenario-{}.json", std::process::id()));
+    let scenario = json!({
+        "server_info": { "name": "fake", "version": "9.9.9" },
+        "tools": {
+            "tasks_show": {
+                "result": { "id": "T-1" },
+                "notifications": [{ "method": "notifications/progress", "params": { "progress": 1 } }]
+            },
+            "tasks_echo": { "echo": true },
+            "tasks_tagged": { "echo": true, "echo_id": true, "jitter_ms": 5 },
+            "tasks_fail": { "error": { "code": -32000, "message": "boom" } }
+        }
+    });
+    std::fs::write(&path, scenario.to_string()).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_fake-mcp-server"))
+        .args([path.to_str().unwrap(), "mcp"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let init = exchange(
+        &mut stdin,
+        &mut stdout,
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+    );
+    assert_eq!(init["result"]["serverInfo"]["version"], "9.9.9");
+    let tools = exchange(
+        &mut stdin,
+        &mut stdout,
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+    );
+    assert_eq!(tools["result"]["tools"].as_array().unwrap().len(), 4);
+
+    // The notification comes before the answer
+    let progress = exchange(&mut stdin, &mut stdout, call(3, "tasks_show"));
+    assert_eq!(progress["method"], "notifications/progress");
+    assert!(progress.get("id").is_none());
+    let shown = receive(&mut stdout);
+    assert_eq!(shown["id"], 3);
+    assert_eq!(shown["result"]["id"], "T-1");
+
+    assert_eq!(
+        exchange(&mut stdin, &mut stdout, call(4, "tasks_echo"))["result"],
+        json!({ "task": "T-1" })
+    );
+    assert_eq!(
+        exchange(&mut stdin, &mut stdout, call(7, "tasks_tagged"))["result"],
+        json!({ "task": "T-1", "request_id": 7 })
+    );
+    assert_eq!(
+        exchange(&mut stdin, &mut stdout, call(5, "tasks_fail"))["error"]["message"],
+        "boom"
+    );
+    assert_eq!(
+        exchange(&mut stdin, &mut stdout, call(6, "tasks_nope"))["error"]["code"],
+        -32601
+    );
+
+    drop(stdin);
+    assert!(child.wait().unwrap().success());
+    let _ = std::fs::remove_file(&path);
+}
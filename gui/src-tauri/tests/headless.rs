@@ -0,0 +1,172 @@
+//! `apply-task-gui --headless` against the scripted server
+//!
+//! Runs the real binary with `fake-mcp-server` as the interpreter and a
+//! scenario as the checkout's entry point. HOME and the XDG directories
+//! point into the scenario dir so no real settings are read (Windows keeps
+//! its known folders whatever the environment says, hence unix only).
+
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::{json, Value};
+
+const RESUME_TASK: &str = include_str!("fixtures/tasks_resume_task.json");
+const CONTEXT: &str = include_str!("fixtures/tasks_context.json");
+
+fn answer(envelope: Value) -> Value {
+    json!({ "result": { "content": [{ "type": "json", "json": envelope }] } })
+}
+
+fn ok(result: Value) -> Value {
+    answer(json!({ "success": true, "result": result }))
+}
+
+struct Project {
+    dir: PathBuf,
+}
+
+impl Project {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!(
+            "apply-task-headless-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let scenario = json!({
+            "log": dir.join("calls.log"),
+            "tools": {
+                "tasks_context": answer(serde_json::from_str(CONTEXT).unwrap()),
+                "tasks_resume": answer(serde_json::from_str(RESUME_TASK).unwrap()),
+                "tasks_storage": ok(json!({ "current_namespace": "work" })),
+                "tasks_create": ok(json!({ "task_id": "TASK-009" })),
+                "tasks_complete": ok(json!({ "task": { "id": "TASK-001", "status": "DONE" } })),
+                "tasks_delete": ok(json!({ "deleted": true })),
+            },
+        });
+        std::fs::write(dir.join("apply_task"), scenario.to_string()).unwrap();
+        Self { dir }
+    }
+
+    /// Exit status and stdout JSON of `apply-task-gui --headless <args>`
+    fn run(&self, args: &[&str]) -> (i32, Value) {
+        let home = self.dir.join("home");
+        let output = Command::new(env!("CARGO_BIN_EXE_apply-task-gui"))
+            .arg("--headless")
+            .args(args)
+            .current_dir(&self.dir)
+            .env("HOME", &home)
+            .env("XDG_CONFIG_HOME", home.join(".config"))
+            .env("XDG_DATA_HOME", home.join(".local/share"))
+            .env("PYTHON_PATH", env!("CARGO_BIN_EXE_fake-mcp-server"))
+            .env("APPLY_TASK_PROJECT_ROOT", &self.dir)
+            .env("RUST_LOG", "warn")
+            .env_remove("APPLY_TASK_PATH")
+            .env_remove("APPLY_TASK_USER_CWD")
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let response = serde_json::from_str(&stdout).unwrap_or(Value::Null);
+        (output.status.code().unwrap(), response)
+    }
+
+    /// Tools the server was called with, in order
+    fn tools(&self) -> Vec<String> {
+        read_calls(&self.dir.join("calls.log"))
+            .iter()
+            .map(|call| call["params"]["name"].as_str().unwrap().to_string())
+            .collect()
+    }
+}
+
+fn read_calls(log: &Path) -> Vec<Value> {
+    std::fs::read_to_string(log)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|message| message["method"] == "tools/call")
+        .collect()
+}
+
+impl Drop for Project {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[test]
+fn test_headless_commands() {
+    let project = Project::new("commands");
+
+    let (code, listed) = project.run(&["--namespace", "work", "tasks_list", "--status", "TODO"]);
+    assert_eq!(code, 0, "{}", listed);
+    assert!(listed["total"].as_u64().unwrap() > 0);
+    assert_eq!(project.tools(), ["tasks_storage", "tasks_context"]);
+
+    let (code, shown) = project.run(&["tasks_show", "--task-id", "TASK-001"]);
+    assert_eq!(code, 0, "{}", shown);
+    assert_eq!(shown["task"]["id"], "TASK-001");
+
+    let (code, created) = project.run(&["quick_create", "--text", "Ship it !high"]);
+    assert_eq!(code, 0, "{}", created);
+    assert_eq!(created["task_id"], "TASK-009");
+    // A title that looks like a number is still text
+    let (code, created) = project.run(&["quick_create", "--text", "42"]);
+    assert_eq!(code, 0, "{}", created);
+    let create = read_calls(&project.dir.join("calls.log"))
+        .into_iter()
+        .rfind(|call| call["params"]["name"] == "tasks_create")
+        .unwrap();
+    assert_eq!(create["params"]["arguments"]["title"], "42");
+
+    let (code, updated) = project.run(&[
+        "tasks_update_status",
+        "--task-id",
+        "TASK-001",
+        "--status",
+        "done",
+    ]);
+    assert_eq!(code, 0, "{}", updated);
+    assert_eq!(updated["optimistic"]["requested_status"], "DONE");
+
+    // Without --yes only the confirmation comes back
+    let (code, pending) = project.run(&["tasks_delete", "--task-id", "TASK-001", "--cascade"]);
+    assert_eq!(code, 1);
+    assert_eq!(pending["needs_confirmation"], true);
+    let (code, deleted) = project.run(&[
+        "tasks_delete",
+        "--task-id",
+        "TASK-001",
+        "--cascade",
+        "--yes",
+    ]);
+    assert_eq!(code, 0, "{}", deleted);
+    assert_eq!(deleted["deleted"], true);
+    assert_eq!(
+        project.tools().last().map(String::as_str),
+        Some("tasks_delete")
+    );
+
+    let (_, report) = project.run(&["doctor"]);
+    assert!(report["checks"].as_array().is_some_and(|c| !c.is_empty()));
+}
+
+#[test]
+fn test_headless_failures() {
+    let project = Project::new("failures");
+
+    let (code, refused) = project.run(&["--namespace", "home", "tasks_list"]);
+    assert_eq!(code, 1);
+    assert_eq!(refused["error_code"], "PROJECT_NAMESPACE_MISMATCH");
+    // Refused before anything but the namespace lookup
+    assert_eq!(project.tools(), ["tasks_storage"]);
+
+    // Usage errors: a missing argument, an unsupported command
+    let (code, _) = project.run(&["tasks_show"]);
+    assert_eq!(code, 2);
+    let (code, _) = project.run(&["tasks_edit", "--task-id", "TASK-001"]);
+    assert_eq!(code, 2);
+}
@@ -0,0 +1,187 @@
+//! Scripted MCP stdio server for bridge tests
+//!
+//! Speaks newline-delimited JSON-RPC and answers from a scenario file given
+//! as the first argument, so it can stand in for Python: with
+//! `python_path` set to this binary, the bridge runs
+//! `fake-mcp-server <entry point> mcp`, and the entry point (`APPLY_TASK_PATH`
+//! or `apply_task` in the root) is the scenario.
+//!
+//! Scenario format (every key optional):
+//!
+//! ```json
+//! {
+//!   "server_info": { "name": "fake", "version": "1.2.3" },
+//!   "log": "/tmp/received.log",
+//!   "seed": 42,
+//!   "tools": {
+//!     "tasks_show": {
+//!       "result": { "content": [{ "type": "json", "json": { "id": "T-1" } }] },
+//!       "delay_ms": 100,
+//!       "notifications": [{ "method": "notifications/progress", "params": {} }]
+//!     },
+//!     "tasks_echo": { "echo": true, "echo_id": true, "jitter_ms": 20 },
+//!     "tasks_verify": { "echo": true, "input_schema": { "type": "object", "properties": {} } },
+//!     "tasks_fail": { "error": { "code": -32000, "message": "boom", "data": {} } },
+//!     "tasks_crash": { "exit": 3 }
+//!   }
+//! }
+//! ```
+//!
+//! - `server_info` is sent back from `initialize`; `tools/list` lists the
+//!   keys of `tools`, with their `input_schema` (default `{"type": "object"}`).
+//! - `log` gets every received line appended (notifications included).
+//! - A `tools/call` is answered by its tool's entry: `notifications` are
+//!   written first, then after `delay_ms` the `result` (or the call's
+//!   `arguments` with `echo`), the `error`, or nothing but an exit with
+//!   code `exit`. Calls are answered concurrently, so a delayed call
+//!   doesn't hold up the next one.
+//! - `jitter_ms` adds a pseudo-random 0..=N ms to the delay, drawn from
+//!   `seed` and the request id, so the same seed replays the same order.
+//! - `echo_id` adds the call's JSON-RPC id to echoed arguments as
+//!   `request_id`.
+//! - Unknown tools and methods get a `-32601` error; notifications from
+//!   the client get no answer.
+
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, Write};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+fn send(out: &Mutex<io::Stdout>, message: &Value) {
+    let mut out = out.lock().unwrap_or_else(|e| e.into_inner());
+    let _ = writeln!(out, "{}", message);
+    let _ = out.flush();
+}
+
+fn error(id: &Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// splitmix64 step
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+fn call(out: &Mutex<io::Stdout>, id: Value, params: &Value, entry: &Value, seed: u64) {
+    for notification in entry["notifications"].as_array().into_iter().flatten() {
+        let mut notification = notification.clone();
+        notification["jsonrpc"] = json!("2.0");
+        send(out, &notification);
+    }
+    let jitter = match entry["jitter_ms"].as_u64() {
+        Some(max) => mix(seed ^ id.as_u64().unwrap_or_default()) % (max + 1),
+        None => 0,
+    };
+    let delay = entry["delay_ms"].as_u64().unwrap_or_default() + jitter;
+    if delay > 0 {
+        thread::sleep(Duration::from_millis(delay));
+    }
+    if let Some(code) = entry["exit"].as_i64() {
+        std::process::exit(code as i32);
+    }
+    let response = if let Some(error) = entry.get("error") {
+        json!({ "jsonrpc": "2.0", "id": id, "error": error })
+    } else if entry["echo"].as_bool() == Some(true) {
+        let mut result = params["arguments"].clone();
+        if entry["echo_id"].as_bool() == Some(true) {
+            result["request_id"] = id.clone();
+        }
+        json!({ "jsonrpc": "2.0", "id": id, "result": result })
+    } else {
+        json!({ "jsonrpc": "2.0", "id": id, "result": entry.get("result").cloned().unwrap_or(json!({})) })
+    };
+    send(out, &response);
+}
+
+fn main() {
+    let scenario: Value = match std::env::args().nth(1) {
+        Some(path) => {
+            let text = std::fs::read_to_string(&path).expect("readable scenario file");
+            serde_json::from_str(&text).expect("scenario is JSON")
+        }
+        None => json!({}),
+    };
+    let scenario = Arc::new(scenario);
+    let out = Arc::new(Mutex::new(io::stdout()));
+    let mut log = scenario["log"].as_str().map(|path| {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .expect("writable log file")
+    });
+
+    for line in io::stdin().lock().lines().map_while(Result::ok) {
+        if let Some(log) = log.as_mut() {
+            let _ = writeln!(log, "{}", line);
+        }
+        let Ok(request) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        let Some(id) = request.get("id").cloned() else {
+            continue;
+        };
+        match request["method"].as_str().unwrap_or_default() {
+            "initialize" => send(
+                &out,
+                &json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": {
+                        "protocolVersion": "2024-11-05",
+                        "capabilities": { "tools": {} },
+                        "serverInfo": scenario.get("server_info").cloned().unwrap_or(json!({ "name": "fake-mcp-server" })),
+                    },
+                }),
+            ),
+            "tools/list" => {
+                let tools: Vec<Value> = scenario["tools"]
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, entry)| {
+                        let schema = entry.get("input_schema").cloned();
+                        json!({ "name": name, "inputSchema": schema.unwrap_or(json!({ "type": "object" })) })
+                    })
+                    .collect();
+                send(
+                    &out,
+                    &json!({ "jsonrpc": "2.0", "id": id, "result": { "tools": tools } }),
+                );
+            }
+            "tools/call" => {
+                let name = request["params"]["name"].as_str().unwrap_or_default();
+                match scenario["tools"].get(name) {
+                    Some(_) => {
+                        let (out, scenario) = (out.clone(), scenario.clone());
+                        let name = name.to_string();
+                        thread::spawn(move || {
+                            let seed = scenario["seed"].as_u64().unwrap_or_default();
+                            call(
+                                &out,
+                                id,
+                                &request["params"],
+                                &scenario["tools"][&name],
+                                seed,
+                            )
+                        });
+                    }
+                    None => send(
+                        &out,
+                        &error(&id, -32601, &format!("Unknown tool: {}", name)),
+                    ),
+                }
+            }
+            method => send(
+                &out,
+                &error(&id, -32601, &format!("Method not found: {}", method)),
+            ),
+        }
+    }
+}
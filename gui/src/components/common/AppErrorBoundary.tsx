@@ -1,5 +1,6 @@
 import React from "react";
 import { FatalErrorScreen } from "@/components/common/FatalErrorScreen";
+import { logFromFrontend } from "@/lib/tauri";
 
 interface AppErrorBoundaryProps {
   children: React.ReactNode;
@@ -19,8 +20,12 @@ export class AppErrorBoundary extends React.Component<
     return { error };
   }
 
-  componentDidCatch(error: unknown) {
-    void error;
+  componentDidCatch(error: unknown, info: React.ErrorInfo) {
+    const message = error instanceof Error ? error.message : String(error);
+    void logFromFrontend("error", `Render error: ${message}`, {
+      stack: error instanceof Error ? error.stack : undefined,
+      component_stack: info.componentStack ?? undefined,
+    });
   }
 
   render() {
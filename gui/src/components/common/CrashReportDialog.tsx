@@ -0,0 +1,86 @@
+import { useState } from "react";
+import { Copy, Trash2, AlertTriangle } from "lucide-react";
+import type { CrashReport } from "@/lib/tauri";
+import { Dialog, DialogContent, DialogDescription, DialogHeader, DialogTitle } from "@/components/ui/dialog";
+import { Button } from "@/components/ui/button";
+import { cn } from "@/lib/utils";
+
+interface CrashReportDialogProps {
+  isOpen: boolean;
+  onClose: () => void;
+  /** Newest first */
+  reports: CrashReport[];
+  onCopy: (report: CrashReport) => void;
+  onDiscard: (report: CrashReport) => void;
+}
+
+function formatCreated(created: string | null): string {
+  if (!created) return "";
+  const d = new Date(created);
+  return Number.isNaN(d.getTime()) ? created : d.toLocaleString();
+}
+
+export function CrashReportDialog({ isOpen, onClose, reports, onCopy, onDiscard }: CrashReportDialogProps) {
+  const [selectedId, setSelectedId] = useState<string | null>(null);
+  const selected = reports.find((r) => r.id === selectedId) ?? reports[0];
+
+  return (
+    <Dialog open={isOpen && reports.length > 0} onOpenChange={(open) => !open && onClose()}>
+      <DialogContent className="max-w-[740px]">
+        <DialogHeader>
+          <DialogTitle className="flex items-center gap-2">
+            <AlertTriangle className="h-4 w-4 text-status-warn" />
+            The app crashed last time
+          </DialogTitle>
+          <DialogDescription>
+            A crash report was saved (home directory redacted). Copy it into an issue, or discard it.
+          </DialogDescription>
+        </DialogHeader>
+
+        {reports.length > 1 && (
+          <div className="flex flex-wrap gap-1.5">
+            {reports.map((r) => (
+              <button
+                key={r.id}
+                type="button"
+                onClick={() => setSelectedId(r.id)}
+                className={cn(
+                  "rounded-md border px-2 py-1 text-xs",
+                  r.id === selected?.id
+                    ? "border-primary text-foreground"
+                    : "border-border text-foreground-muted hover:bg-background-hover"
+                )}
+              >
+                {formatCreated(r.created) || r.id}
+              </button>
+            ))}
+          </div>
+        )}
+
+        {selected && (
+          <>
+            <div className="text-sm text-foreground">
+              <span className="font-mono">{selected.summary || "Unknown panic"}</span>
+              {selected.created && (
+                <span className="ml-2 text-xs text-foreground-subtle">{formatCreated(selected.created)}</span>
+              )}
+            </div>
+            <pre className="max-h-[360px] overflow-auto whitespace-pre-wrap rounded-md border border-border bg-background-muted p-3 font-mono text-xs text-foreground-muted">
+              {selected.text}
+            </pre>
+            <div className="flex justify-end gap-2">
+              <Button variant="outline" size="sm" className="h-8 gap-2" onClick={() => onCopy(selected)}>
+                <Copy className="h-4 w-4" aria-hidden />
+                Copy
+              </Button>
+              <Button variant="destructive" size="sm" className="h-8 gap-2" onClick={() => onDiscard(selected)}>
+                <Trash2 className="h-4 w-4" aria-hidden />
+                Discard
+              </Button>
+            </div>
+          </>
+        )}
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -27,7 +27,7 @@ interface KanbanBoardProps {
   onTaskClick?: (taskId: string) => void;
   onNewTask?: () => void;
   onStatusChange?: (taskId: string, newStatus: TaskStatus) => void;
-  onDelete?: (taskId: string) => void;
+  onDelete?: (taskId: string, confirmToken: string) => void;
   isLoading?: boolean;
 }
 
@@ -266,7 +266,7 @@ interface KanbanColumnProps {
   hideDoneTasks: boolean;
   onToggleHideDone: () => void;
   onStatusChange?: (taskId: string, newStatus: TaskStatus) => void;
-  onDelete?: (taskId: string) => void;
+  onDelete?: (taskId: string, confirmToken: string) => void;
 }
 
 function KanbanColumn({
@@ -418,7 +418,7 @@ function KanbanColumn({
               }
               onClick={() => onTaskClick(task.id)}
               onStatusChange={(status) => onStatusChange?.(task.id, status)}
-              onDelete={() => onDelete?.(task.id)}
+              onDelete={(confirmToken) => onDelete?.(task.id, confirmToken)}
             />
           </div>
         ))}
@@ -25,7 +25,7 @@ import {
 import { Button } from "@/components/ui/button";
 import { ProgressBar } from "@/components/common/ProgressBar";
 import { cn } from "@/lib/utils";
-import { openPath } from "@/lib/tauri";
+import { openPath, type RegisteredProject } from "@/lib/tauri";
 import { toast } from "@/components/common/toast";
 import { useSettingsStore } from "@/stores/settingsStore";
 
@@ -38,6 +38,10 @@ interface ProjectsViewProps {
   onOpenProject?: () => void;
   onRefresh?: () => void;
   onSelectNamespace?: (namespace: string | null) => void;
+  /** Registry of project folders opened before */
+  recentProjects?: RegisteredProject[];
+  onSwitchProject?: (path: string) => void;
+  onForgetProject?: (path: string) => void;
 }
 
 interface Project {
@@ -94,6 +98,9 @@ export function ProjectsView({
   onOpenProject,
   onRefresh,
   onSelectNamespace,
+  recentProjects = [],
+  onSwitchProject,
+  onForgetProject,
 }: ProjectsViewProps) {
   const archivedNamespaces = useSettingsStore((s) => s.archivedNamespaces);
   const archiveNamespace = useSettingsStore((s) => s.archiveNamespace);
@@ -141,6 +148,62 @@ export function ProjectsView({
         )}
       </div>
 
+      {/* Recent project folders (registry) */}
+      {recentProjects.length > 0 && (
+        <section>
+          <div className="mb-3 flex items-center gap-2">
+            <Clock className="h-4 w-4 text-foreground-muted" />
+            <h3 className="text-sm font-semibold text-foreground-muted">
+              Recent Folders
+            </h3>
+          </div>
+
+          <div className="flex flex-col gap-2">
+            {recentProjects.map((entry) => (
+              <div
+                key={entry.path}
+                className={cn(
+                  "flex items-center justify-between gap-3 rounded-lg border px-3 py-2",
+                  entry.current ? "border-primary/40 bg-primary/5" : "border-border bg-background",
+                  !entry.exists && "opacity-60"
+                )}
+              >
+                <div className="min-w-0">
+                  <div className="truncate text-sm font-semibold text-foreground">{entry.name}</div>
+                  <div className="truncate font-mono text-[11px] text-foreground-muted">
+                    {entry.exists ? entry.path : `${entry.path} (missing)`}
+                  </div>
+                </div>
+                <div className="flex shrink-0 items-center gap-1">
+                  {entry.last_opened && (
+                    <span className="mr-2 text-xs text-foreground-muted">
+                      {formatDate(new Date(entry.last_opened))}
+                    </span>
+                  )}
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    disabled={entry.current || !entry.exists}
+                    onClick={() => onSwitchProject?.(entry.path)}
+                  >
+                    Open
+                  </Button>
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    className="h-8 w-8"
+                    disabled={entry.current}
+                    onClick={() => onForgetProject?.(entry.path)}
+                  >
+                    <Trash2 className="h-4 w-4 text-foreground-subtle" />
+                  </Button>
+                </div>
+              </div>
+            ))}
+          </div>
+        </section>
+      )}
+
       {/* Current Project */}
       {currentProject && (
         <section>
@@ -22,7 +22,24 @@ import {
   RefreshCw,
 } from "lucide-react";
 import { useSettingsStore, formatBytes, type ThemeMode, type StorageMode } from "@/stores/settingsStore";
-import { setBackendStorageMode } from "@/lib/tauri";
+import {
+  checkForUpdates,
+  getNotificationPrefs,
+  getUpdateCheckEnabled,
+  getQuickAddStatus,
+  getUsageMetrics,
+  resetUsageMetrics,
+  setAnalyticsEnabled,
+  type UsageMetricsResponse,
+  openLogFolder,
+  resetWindowState,
+  setBackendStorageMode,
+  setNotificationPrefs,
+  setQuickAddShortcut,
+  setUpdateCheckEnabled,
+  type NotificationPrefs,
+  type QuickAddStatus,
+} from "@/lib/tauri";
 import {
   Dialog,
   DialogContent,
@@ -208,6 +225,13 @@ export function SettingsView({ isLoading = false }: SettingsViewProps) {
   const [isClearing, setIsClearing] = useState(false);
   const [isExporting, setIsExporting] = useState(false);
   const [storageModeSaving, setStorageModeSaving] = useState(false);
+  const [osNotifications, setOsNotifications] = useState<NotificationPrefs | null>(null);
+  const [quickAdd, setQuickAdd] = useState<QuickAddStatus | null>(null);
+  const [shortcutDraft, setShortcutDraft] = useState("");
+  const [usageMetrics, setUsageMetrics] = useState<UsageMetricsResponse | null>(null);
+  const [updateCheckEnabled, setUpdateCheckEnabledState] = useState<boolean | null>(null);
+  const [updateStatus, setUpdateStatus] = useState<string | null>(null);
+  const [checkingUpdates, setCheckingUpdates] = useState(false);
 
   // Get settings from store
   const {
@@ -261,6 +285,113 @@ export function SettingsView({ isLoading = false }: SettingsViewProps) {
     }
   }, [storageMode, setStorageMode]);
 
+  // OS notifications are sent by the backend (desktop only)
+  useEffect(() => {
+    getNotificationPrefs()
+      .then((resp) => {
+        if (resp?.success) setOsNotifications(resp.prefs);
+      })
+      .catch(() => {});
+  }, []);
+
+  const updateOsNotifications = useCallback(async (patch: Partial<NotificationPrefs>) => {
+    if (!osNotifications) return;
+    const resp = await setNotificationPrefs({ ...osNotifications, ...patch });
+    if (!resp?.success) {
+      toast.error(resp?.error || "Failed to save notification preferences");
+      return;
+    }
+    setOsNotifications(resp.prefs);
+  }, [osNotifications]);
+
+  // Global quick-add shortcut (desktop only)
+  const loadQuickAdd = useCallback(async () => {
+    const status = await getQuickAddStatus().catch(() => null);
+    setQuickAdd(status);
+    if (status?.shortcut) setShortcutDraft(status.shortcut);
+  }, []);
+
+  useEffect(() => {
+    void loadQuickAdd();
+  }, [loadQuickAdd]);
+
+  const updateQuickAddShortcut = useCallback(async (shortcut: string | null) => {
+    const resp = await setQuickAddShortcut(shortcut);
+    if (!resp.success) toast.error(resp.error || "Failed to save the shortcut");
+    await loadQuickAdd();
+  }, [loadQuickAdd]);
+
+  const handleResetWindow = useCallback(async () => {
+    try {
+      await resetWindowState();
+    } catch (err) {
+      toast.error(err instanceof Error ? err.message : String(err));
+    }
+  }, []);
+
+  // Local usage metrics (desktop only, opt-in)
+  useEffect(() => {
+    getUsageMetrics()
+      .then(setUsageMetrics)
+      .catch(() => {});
+  }, []);
+
+  const updateAnalytics = useCallback(async (enabled: boolean) => {
+    const resp = await setAnalyticsEnabled(enabled);
+    if (!resp.success) toast.error(resp.error || "Failed to save the setting");
+    setUsageMetrics(await getUsageMetrics().catch(() => null));
+  }, []);
+
+  const handleResetUsageMetrics = useCallback(async () => {
+    const resp = await resetUsageMetrics().catch(() => null);
+    if (resp && !resp.success) toast.error(resp.error || "Failed to reset usage metrics");
+    if (resp) setUsageMetrics(resp);
+  }, []);
+
+  // Update check against GitHub releases (desktop only)
+  useEffect(() => {
+    getUpdateCheckEnabled()
+      .then(setUpdateCheckEnabledState)
+      .catch(() => {});
+  }, []);
+
+  const updateUpdateCheck = useCallback(async (enabled: boolean) => {
+    const resp = await setUpdateCheckEnabled(enabled);
+    if (!resp.success) {
+      toast.error(resp.error || "Failed to save the setting");
+      return;
+    }
+    setUpdateCheckEnabledState(enabled);
+  }, []);
+
+  const handleCheckForUpdates = useCallback(async () => {
+    setCheckingUpdates(true);
+    try {
+      const resp = await checkForUpdates();
+      if (!resp.success) {
+        toast.error(resp.error || "Could not check for updates");
+        return;
+      }
+      if (resp.update_available) {
+        setUpdateStatus(`${resp.latest} available`);
+        toast.info(`Version ${resp.latest} is available (you have ${resp.current})`);
+        if (resp.release_url) window.open(resp.release_url, "_blank", "noopener,noreferrer");
+      } else {
+        setUpdateStatus("Up to date");
+      }
+    } finally {
+      setCheckingUpdates(false);
+    }
+  }, []);
+
+  const handleOpenLogFolder = useCallback(async () => {
+    try {
+      await openLogFolder();
+    } catch (err) {
+      toast.error(err instanceof Error ? err.message : String(err));
+    }
+  }, []);
+
   const handleClearCache = useCallback(async () => {
     setIsClearing(true);
     try {
@@ -356,6 +487,7 @@ export function SettingsView({ isLoading = false }: SettingsViewProps) {
               checked={compactMode}
               onChange={setCompactMode}
             />
+            <SettingsRow label="Reset window size and position" icon={Monitor} onClick={handleResetWindow} />
           </SettingsSection>
 
           {/* Notifications */}
@@ -376,6 +508,56 @@ export function SettingsView({ isLoading = false }: SettingsViewProps) {
               checked={soundEffects}
               onChange={setSoundEffects}
             />
+            {osNotifications && (
+              <>
+                <Toggle
+                  label="System notifications"
+                  description="Notify about AI activity while you are in another app"
+                  checked={osNotifications.enabled}
+                  onChange={(enabled) => void updateOsNotifications({ enabled })}
+                />
+                {osNotifications.enabled && (
+                  <>
+                    <Toggle
+                      label="Task completed"
+                      description="A task was marked done"
+                      checked={osNotifications.task_completed}
+                      onChange={(task_completed) => void updateOsNotifications({ task_completed })}
+                    />
+                    <Toggle
+                      label="Checkpoints green"
+                      description="Every checkpoint of a step is confirmed"
+                      checked={osNotifications.checkpoints_green}
+                      onChange={(checkpoints_green) => void updateOsNotifications({ checkpoints_green })}
+                    />
+                    <Toggle
+                      label="AI paused or blocked"
+                      description="The AI paused, waits for you or hit an error"
+                      checked={osNotifications.ai_paused}
+                      onChange={(ai_paused) => void updateOsNotifications({ ai_paused })}
+                    />
+                    <Toggle
+                      label="AI needs your input"
+                      description="The AI asks a question or waits on a decision"
+                      checked={osNotifications.ai_signal}
+                      onChange={(ai_signal) => void updateOsNotifications({ ai_signal })}
+                    />
+                    <Toggle
+                      label="Backend crashed"
+                      description="The apply_task process exited unexpectedly"
+                      checked={osNotifications.backend_crashed}
+                      onChange={(backend_crashed) => void updateOsNotifications({ backend_crashed })}
+                    />
+                    <Toggle
+                      label="Only when unfocused"
+                      description="Stay quiet while this window is active"
+                      checked={osNotifications.only_when_unfocused}
+                      onChange={(only_when_unfocused) => void updateOsNotifications({ only_when_unfocused })}
+                    />
+                  </>
+                )}
+              </>
+            )}
           </SettingsSection>
 
           {/* Keyboard */}
@@ -395,6 +577,39 @@ export function SettingsView({ isLoading = false }: SettingsViewProps) {
               checked={vimMode}
               onChange={setVimMode}
             />
+            {quickAdd && (
+              <>
+                <Toggle
+                  label="Quick add shortcut"
+                  description="Global shortcut that opens a one-line task capture window"
+                  checked={quickAdd.shortcut !== null}
+                  onChange={(enabled) => void updateQuickAddShortcut(enabled ? null : "")}
+                />
+                {quickAdd.shortcut !== null && (
+                  <div className="flex items-center gap-2 py-2">
+                    <input
+                      value={shortcutDraft}
+                      onChange={(e) => setShortcutDraft(e.target.value)}
+                      onKeyDown={(e) => {
+                        if (e.key === "Enter") void updateQuickAddShortcut(shortcutDraft.trim() || null);
+                      }}
+                      placeholder="CmdOrCtrl+Shift+T"
+                      aria-label="Quick add shortcut"
+                      className="flex-1 rounded-md border border-border bg-background px-3 py-1.5 text-sm text-foreground outline-none focus:border-primary"
+                    />
+                    <button
+                      type="button"
+                      disabled={shortcutDraft.trim() === quickAdd.shortcut}
+                      onClick={() => void updateQuickAddShortcut(shortcutDraft.trim() || null)}
+                      className="rounded-md border border-border px-3 py-1.5 text-sm text-foreground hover:bg-background-muted disabled:opacity-50"
+                    >
+                      Save
+                    </button>
+                  </div>
+                )}
+                {quickAdd.error && <div className="pb-2 text-xs text-status-fail">{quickAdd.error}</div>}
+              </>
+            )}
           </SettingsSection>
 
           {/* Data */}
@@ -438,12 +653,45 @@ export function SettingsView({ isLoading = false }: SettingsViewProps) {
               value={formatBytes(cacheSize)}
               onClick={handleClearCache}
             />
+            <SettingsRow label="Open log folder" icon={ExternalLink} onClick={handleOpenLogFolder} />
+            {usageMetrics && (
+              <>
+                <Toggle
+                  label="Local usage metrics"
+                  description={`Count which commands and intents you use, in ${usageMetrics.path}. Never uploaded.`}
+                  checked={usageMetrics.enabled}
+                  onChange={(enabled) => void updateAnalytics(enabled)}
+                />
+                <SettingsRow
+                  label="Reset usage metrics"
+                  icon={Trash2}
+                  value={`${Object.values(usageMetrics.metrics.commands).reduce((a, b) => a + b.calls, 0)} calls`}
+                  onClick={() => void handleResetUsageMetrics()}
+                />
+              </>
+            )}
           </SettingsSection>
 
           {/* About */}
           <div className="lg:col-span-2">
             <SettingsSection title="About" description="Application information" icon={Info}>
               <SettingsRow label="Version" value={APP_VERSION} />
+              {updateCheckEnabled !== null && (
+                <>
+                  <SettingsRow
+                    label="Check for updates"
+                    icon={checkingUpdates ? RefreshCw : Download}
+                    value={updateStatus ?? undefined}
+                    onClick={() => void handleCheckForUpdates()}
+                  />
+                  <Toggle
+                    label="Check for updates at startup"
+                    description="Ask GitHub for the latest release once a day"
+                    checked={updateCheckEnabled}
+                    onChange={(enabled) => void updateUpdateCheck(enabled)}
+                  />
+                </>
+              )}
               <SettingsRow
                 label="View on GitHub"
                 icon={ExternalLink}
@@ -0,0 +1,88 @@
+import { useEffect, useState } from "react";
+import { ConfirmDialog } from "@/components/common/ConfirmDialog";
+import { toast } from "@/components/common/toast";
+import type { DeleteConfirmation, DeleteResult, DeleteSummary } from "@/lib/tauri";
+
+interface DeleteConfirmDialogProps {
+  isOpen: boolean;
+  title: string;
+  /** The delete without a token: answers with what it would remove */
+  preview: () => Promise<DeleteResult>;
+  isLoading?: boolean;
+  /** Delete with the token of the summary shown */
+  onConfirm: (confirmToken: string) => void;
+  /** The preview deleted right away (`skip_confirmation`) */
+  onDeleted: () => void;
+  onCancel: () => void;
+}
+
+function plural(count: number, word: string): string {
+  return `${count} ${word}${count === 1 ? "" : "s"}`;
+}
+
+export function describeDeleteSummary(summary: DeleteSummary): string {
+  const removed = [plural(summary.subtask_count, "nested step")];
+  if (summary.descendants.length > 0) {
+    removed.push(`${plural(summary.descendants.length, "subtask")} (${summary.descendants.join(", ")})`);
+  }
+  let description = `This will permanently remove "${summary.title}" with ${removed.join(" and ")}.`;
+  if (summary.confirmed_checkpoints > 0) {
+    description += ` ${plural(summary.confirmed_checkpoints, "confirmed checkpoint")} will be lost.`;
+  }
+  return description;
+}
+
+export function DeleteConfirmDialog({
+  isOpen,
+  title,
+  preview,
+  isLoading = false,
+  onConfirm,
+  onDeleted,
+  onCancel,
+}: DeleteConfirmDialogProps) {
+  const [confirmation, setConfirmation] = useState<DeleteConfirmation | null>(null);
+
+  useEffect(() => {
+    setConfirmation(null);
+    if (!isOpen) return;
+    let cancelled = false;
+    preview()
+      .then((resp) => {
+        if (cancelled) return;
+        if (resp.confirmation) {
+          setConfirmation(resp.confirmation);
+        } else if (resp.success && resp.deleted) {
+          onDeleted();
+        } else {
+          toast.error(resp.error || "Failed to delete");
+          onCancel();
+        }
+      })
+      .catch((err) => {
+        if (cancelled) return;
+        toast.error(err instanceof Error ? err.message : "Failed to delete");
+        onCancel();
+      });
+    return () => {
+      cancelled = true;
+    };
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [isOpen]);
+
+  return (
+    <ConfirmDialog
+      isOpen={isOpen}
+      title={title}
+      description={confirmation ? describeDeleteSummary(confirmation.summary) : "Checking what will be deleted…"}
+      confirmLabel="Delete"
+      cancelLabel="Cancel"
+      danger
+      isLoading={isLoading || !confirmation}
+      onCancel={onCancel}
+      onConfirm={() => {
+        if (confirmation) onConfirm(confirmation.token);
+      }}
+    />
+  );
+}
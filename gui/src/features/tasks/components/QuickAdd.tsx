@@ -0,0 +1,102 @@
+/**
+ * Quick Add
+ *
+ * Content of the window opened by the global quick-add shortcut: one line
+ * becomes a task. `#tag`, `!low|!medium|!high` and `@domain` are parsed by
+ * the backend; Escape or focus loss closes the window.
+ */
+
+import { useCallback, useEffect, useRef, useState } from "react";
+import { Check, Loader2, Plus } from "lucide-react";
+import { dismissQuickAdd, quickCreate } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+/** How long the confirmation stays before the window closes */
+const CONFIRM_MS = 900;
+
+export function QuickAdd() {
+  const inputRef = useRef<HTMLInputElement>(null);
+  const [text, setText] = useState("");
+  const [isSaving, setIsSaving] = useState(false);
+  const [created, setCreated] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const dismiss = useCallback(() => {
+    void dismissQuickAdd().catch(() => {});
+  }, []);
+
+  useEffect(() => {
+    const focus = () => inputRef.current?.focus();
+    focus();
+    window.addEventListener("focus", focus);
+    window.addEventListener("blur", dismiss);
+    return () => {
+      window.removeEventListener("focus", focus);
+      window.removeEventListener("blur", dismiss);
+    };
+  }, [dismiss]);
+
+  const submit = useCallback(async () => {
+    if (!text.trim() || isSaving) return;
+    setIsSaving(true);
+    setError(null);
+    try {
+      const resp = await quickCreate(text);
+      if (!resp.success || !resp.task_id) {
+        setError(resp.error || "Failed to create the task");
+        return;
+      }
+      setCreated(resp.warning || `Created ${resp.task_id}`);
+      setText("");
+      window.setTimeout(dismiss, resp.warning ? CONFIRM_MS * 3 : CONFIRM_MS);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsSaving(false);
+    }
+  }, [text, isSaving, dismiss]);
+
+  return (
+    <div className="flex h-screen w-screen flex-col justify-center gap-1.5 rounded-xl border border-border bg-background px-4">
+      <div className="flex items-center gap-3">
+        {isSaving ? (
+          <Loader2 className="h-5 w-5 shrink-0 animate-spin text-foreground-muted" />
+        ) : created ? (
+          <Check className="h-5 w-5 shrink-0 text-status-ok" />
+        ) : (
+          <Plus className="h-5 w-5 shrink-0 text-foreground-muted" />
+        )}
+        <input
+          ref={inputRef}
+          value={text}
+          onChange={(e) => {
+            setText(e.target.value);
+            setError(null);
+            setCreated(null);
+          }}
+          onKeyDown={(e) => {
+            if (e.key === "Escape") {
+              e.preventDefault();
+              dismiss();
+            } else if (e.key === "Enter") {
+              e.preventDefault();
+              void submit();
+            }
+          }}
+          placeholder="Fix login redirect #auth !high @backend"
+          aria-label="New task"
+          disabled={isSaving}
+          className="flex-1 bg-transparent text-lg text-foreground outline-none placeholder:text-foreground-subtle"
+        />
+      </div>
+      <div
+        className={cn(
+          "truncate pl-8 text-xs",
+          error ? "text-status-fail" : created ? "text-status-ok" : "text-foreground-subtle"
+        )}
+      >
+        {error || created || "Enter to create · Esc to close · #tag !priority @domain"}
+      </div>
+    </div>
+  );
+}
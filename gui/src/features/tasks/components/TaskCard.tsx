@@ -1,19 +1,24 @@
 import { useState } from "react";
+import { useQueryClient } from "@tanstack/react-query";
 import { CheckCircle2, Circle, ChevronRight, Check, Clock, Trash2 } from "lucide-react";
 import type { TaskListItem, TaskStatus } from "@/types/task";
-import { ConfirmDialog } from "@/components/common/ConfirmDialog";
 import { CheckpointMarks } from "@/components/common/CheckpointMarks";
+import { toast } from "@/components/common/toast";
 import { Badge } from "@/components/ui/badge";
 import { Button } from "@/components/ui/button";
 import { cn } from "@/lib/utils";
 import { TASK_STATUS_UI } from "@/lib/taskStatus";
 import { countStepTree } from "@/features/tasks/lib/stepCounts";
+import { DeleteConfirmDialog } from "@/features/tasks/components/DeleteConfirmDialog";
+import { deleteTask } from "@/lib/tauri";
+import { getApiTaskId } from "@/lib/taskId";
 
 interface TaskCardProps {
   task: TaskListItem;
   onClick?: () => void;
   onStatusChange?: (status: TaskStatus) => void;
-  onDelete?: () => void;
+  /** Delete with the token of the confirmed summary */
+  onDelete?: (confirmToken: string) => void;
   isSelected?: boolean;
 }
 
@@ -33,6 +38,7 @@ function formatRelativeTime(date: string): string {
 }
 
 export function TaskCard({ task, onClick, onStatusChange, onDelete, isSelected = false }: TaskCardProps) {
+  const queryClient = useQueryClient();
   const [showDeleteConfirm, setShowDeleteConfirm] = useState(false);
   const statusUi = TASK_STATUS_UI[task.status];
   const progress = task.progress || 0;
@@ -241,18 +247,20 @@ export function TaskCard({ task, onClick, onStatusChange, onDelete, isSelected =
       </div>
 
       {onDelete && (
-        <ConfirmDialog
+        <DeleteConfirmDialog
           isOpen={showDeleteConfirm}
           title={`Delete task "${task.title}"?`}
-          description="This will permanently remove the task and all its steps."
-          confirmLabel="Delete"
-          cancelLabel="Cancel"
-          danger
+          preview={() => deleteTask(getApiTaskId(task))}
           onCancel={() => setShowDeleteConfirm(false)}
-          onConfirm={() => {
-            onDelete();
+          onConfirm={(confirmToken) => {
+            onDelete(confirmToken);
             setShowDeleteConfirm(false);
           }}
+          onDeleted={() => {
+            toast.success("Task deleted");
+            setShowDeleteConfirm(false);
+            queryClient.invalidateQueries({ queryKey: ["tasks"] });
+          }}
         />
       )}
     </>
@@ -25,7 +25,6 @@ import {
   MoreHorizontal,
   FileText as NotesIcon,
 } from "lucide-react";
-import { ConfirmDialog } from "@/components/common/ConfirmDialog";
 import { CheckpointMarks } from "@/components/common/CheckpointMarks";
 import { ProgressBar } from "@/components/common/ProgressBar";
 import { StepStatusBadge } from "@/components/common/StepStatusBadge";
@@ -70,6 +69,7 @@ import {
 } from "@/lib/tauri";
 import type { Step, StepStatus, Task, TaskNode, TaskStatus } from "@/types/task";
 import type { MirrorItem, RadarData, Suggestion } from "@/types/api";
+import { DeleteConfirmDialog } from "@/features/tasks/components/DeleteConfirmDialog";
 import { TaskContractSection } from "@/features/tasks/components/TaskContractSection";
 import { TaskPlanSection } from "@/features/tasks/components/TaskPlanSection";
 import { TaskNotesSection } from "@/features/tasks/components/TaskNotesSection";
@@ -783,26 +783,27 @@ export function TaskDetailView({
     setDeleteDialog({ path, title, kind });
   };
 
-  const handleConfirmDeleteSubtask = async () => {
-    if (!task || !deleteDialog) return;
+  const deleteSubtask = (confirmToken?: string) => {
+    if (!task || !deleteDialog) return Promise.reject(new Error("Nothing to delete"));
+    const params = { taskId: task.id, path: deleteDialog.path, confirmToken };
+    return deleteDialog.kind === "task" ? deleteTaskNode(params) : deleteStep(params);
+  };
+
+  const finishDeleteSubtask = () => {
+    toast.success(deleteDialog?.kind === "task" ? "Task deleted" : "Step deleted");
+    setDeleteDialog(null);
+    queryClient.invalidateQueries({ queryKey: taskQueryKey });
+    queryClient.invalidateQueries({ queryKey: ["tasks"] });
+  };
+
+  const handleConfirmDeleteSubtask = async (confirmToken: string) => {
     setIsDeletingSubtask(true);
     try {
-      if (deleteDialog.kind === "task") {
-        const resp = await deleteTaskNode({ taskId: task.id, path: deleteDialog.path });
-        if (!resp.success) {
-          throw new Error(resp.error || "Failed to delete task");
-        }
-        toast.success("Task deleted");
-      } else {
-        const resp = await deleteStep({ taskId: task.id, path: deleteDialog.path });
-        if (!resp.success) {
-          throw new Error(resp.error || "Failed to delete step");
-        }
-        toast.success("Step deleted");
+      const resp = await deleteSubtask(confirmToken);
+      if (!resp.success || !resp.deleted) {
+        throw new Error(resp.error || `Failed to delete ${deleteDialog?.kind ?? "step"}`);
       }
-      setDeleteDialog(null);
-      queryClient.invalidateQueries({ queryKey: taskQueryKey });
-      queryClient.invalidateQueries({ queryKey: ["tasks"] });
+      finishDeleteSubtask();
     } catch (err) {
       toast.error(err instanceof Error ? err.message : "Failed to delete");
     } finally {
@@ -907,19 +908,24 @@ export function TaskDetailView({
     setTaskDeleteOpen(true);
   };
 
-  const handleConfirmDeleteTask = async () => {
+  const finishDeleteTask = () => {
+    if (!task) return;
+    toast.success("Task deleted");
+    onDelete?.(task.id);
+    setTaskDeleteOpen(false);
+    queryClient.invalidateQueries({ queryKey: ["tasks"] });
+    onClose();
+  };
+
+  const handleConfirmDeleteTask = async (confirmToken: string) => {
     if (!task) return;
     setIsDeletingTask(true);
     try {
-      const resp = await apiDeleteTask(task.id);
-      if (!resp.success) {
+      const resp = await apiDeleteTask(task.id, confirmToken);
+      if (!resp.success || !resp.deleted) {
         throw new Error(resp.error || "Failed to delete task");
       }
-      toast.success("Task deleted");
-      onDelete?.(task.id);
-      setTaskDeleteOpen(false);
-      queryClient.invalidateQueries({ queryKey: ["tasks"] });
-      onClose();
+      finishDeleteTask();
     } catch (err) {
       toast.error(err instanceof Error ? err.message : "Failed to delete task");
     } finally {
@@ -1091,22 +1097,16 @@ export function TaskDetailView({
       </Dialog>
 
       {deleteDialog && (
-        <ConfirmDialog
+        <DeleteConfirmDialog
           isOpen
           title={`Delete ${deleteDialog.kind} "${deleteDialog.title}"?`}
-          description={
-            deleteDialog.kind === "task"
-              ? "This will remove the task and all nested steps. This action cannot be undone."
-              : "This will remove the step and all nested children. This action cannot be undone."
-          }
-          confirmLabel="Delete"
-          cancelLabel="Cancel"
-          danger
+          preview={() => deleteSubtask()}
           isLoading={isDeletingSubtask}
           onCancel={() => setDeleteDialog(null)}
-          onConfirm={() => {
-            void handleConfirmDeleteSubtask();
+          onConfirm={(confirmToken) => {
+            void handleConfirmDeleteSubtask(confirmToken);
           }}
+          onDeleted={finishDeleteSubtask}
         />
       )}
 
@@ -1352,21 +1352,19 @@ export function TaskDetailView({
         </div>
       </div>
 
-      <ConfirmDialog
+      <DeleteConfirmDialog
         isOpen={taskDeleteOpen}
         title={`Delete task "${task.title}"?`}
-        description="This will permanently remove the task and all its nested steps."
-        confirmLabel="Delete"
-        cancelLabel="Cancel"
-        danger
+        preview={() => apiDeleteTask(task.id)}
         isLoading={isDeletingTask}
         onCancel={() => {
           if (isDeletingTask) return;
           setTaskDeleteOpen(false);
         }}
-        onConfirm={() => {
-          void handleConfirmDeleteTask();
+        onConfirm={(confirmToken) => {
+          void handleConfirmDeleteTask(confirmToken);
         }}
+        onDeleted={finishDeleteTask}
       />
     </div>
   );
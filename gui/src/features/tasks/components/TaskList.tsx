@@ -14,7 +14,7 @@ interface TaskListProps {
   onFocusChange?: (taskId: string | null) => void;
   onNewTask?: () => void;
   onStatusChange?: (taskId: string, status: TaskStatus) => void;
-  onDelete?: (taskId: string) => void;
+  onDelete?: (taskId: string, confirmToken: string) => void;
   isLoading?: boolean;
   searchQuery?: string;
 }
@@ -118,7 +118,7 @@ export function TaskList({
                 task={task}
                 onClick={() => handleClick(task.id)}
                 onStatusChange={onStatusChange ? (status) => onStatusChange(task.id, status) : undefined}
-                onDelete={onDelete ? () => onDelete(task.id) : undefined}
+                onDelete={onDelete ? (confirmToken) => onDelete(task.id, confirmToken) : undefined}
                 isSelected={
                   effectiveSelectedId === task.id ||
                   (detailPanelTaskId ? getApiTaskId(task) === detailPanelTaskId : false)
@@ -13,7 +13,7 @@ interface UseTasksResult {
   namespaces: Namespace[];
   refresh: () => Promise<void>;
   updateTaskStatus: (taskId: string, newStatus: TaskStatus) => void;
-  deleteTask: (taskId: string) => void;
+  deleteTask: (taskId: string, confirmToken: string) => void;
 }
 
 interface UseTasksParams {
@@ -97,16 +97,16 @@ export function useTasks(params?: UseTasksParams): UseTasksResult {
   });
 
 	  const deleteMutation = useMutation({
-	    mutationFn: async (taskId: string) => {
+	    mutationFn: async ({ taskId, confirmToken }: { taskId: string; confirmToken: string }) => {
       const actualTaskId = getApiTaskIdFromUiTaskId(taskId);
-      const response = await apiDeleteTask(actualTaskId);
-      if (!response.success) throw new Error(response.error);
+      const response = await apiDeleteTask(actualTaskId, confirmToken);
+      if (!response.success || !response.deleted) throw new Error(response.error || "Failed to delete task");
 	      return response;
 	    },
 	    onSuccess: () => {
       toast.success("Task deleted");
 	    },
-	    onMutate: async (taskId) => {
+	    onMutate: async ({ taskId }) => {
       await queryClient.cancelQueries({ queryKey });
       const previousTasks = queryClient.getQueryData<TaskListItem[]>(queryKey);
 
@@ -139,6 +139,6 @@ export function useTasks(params?: UseTasksParams): UseTasksResult {
       await Promise.all([tasksQuery.refetch(), storageQuery.refetch()]);
     },
     updateTaskStatus: (taskId, newStatus) => updateStatusMutation.mutate({ taskId, newStatus }),
-    deleteTask: (taskId) => deleteMutation.mutate(taskId),
+    deleteTask: (taskId, confirmToken) => deleteMutation.mutate({ taskId, confirmToken }),
   };
 }
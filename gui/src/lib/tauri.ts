@@ -6,7 +6,16 @@
  * - Frontend types match Python serializers (plan_to_dict/task_to_dict/step_to_dict).
  */
 
-import type { AIResponse, ContextData, MirrorData, ResumeData, RadarData, Suggestion } from "@/types/api";
+import type {
+  AIResponse,
+  ContextData,
+  MirrorData,
+  ResumeData,
+  RadarData,
+  SimilarTask,
+  Suggestion,
+  SuggestionAction,
+} from "@/types/api";
 import type { Plan, PlanListItem, StorageInfo, Task, TaskListItem, TaskStatus, Step } from "@/types/task";
 
 // Check if we're running inside Tauri (Tauri 2.0 uses __TAURI_INTERNALS__)
@@ -26,6 +35,13 @@ function extractAIError(raw: unknown): string | null {
   return msg && msg.trim().length > 0 ? msg : null;
 }
 
+/** Subscribe to a backend event; resolves to the unsubscribe function (no-op in browser mode) */
+async function listenEvent<T>(event: string, handler: (payload: T) => void): Promise<() => void> {
+  if (!isTauri) return () => {};
+  const { listen } = await import("@tauri-apps/api/event");
+  return listen<T>(event, (e) => handler(e.payload));
+}
+
 async function invokeCommand<T>(command: string, args?: Record<string, unknown>): Promise<T> {
   await tauriInitPromise;
   if (!isTauri || !tauriInvoke) {
@@ -76,6 +92,11 @@ export async function aiIntent<T = unknown>(intent: string, params?: Record<stri
   return invokeCommand<AIResponse<T>>("ai_intent", { intent, params: params ?? {} });
 }
 
+/** Run a suggestion from `suggestion_items` (refused without an intent) */
+export async function executeSuggestion<T = unknown>(action: SuggestionAction): Promise<AIResponse<T>> {
+  return invokeCommand<AIResponse<T>>("execute_suggestion", { action });
+}
+
 export interface TaskListResponse {
   success: boolean;
   tasks: TaskListItem[];
@@ -113,6 +134,753 @@ export async function listTasks(params?: {
   return { success: true, tasks, total: tasks.length };
 }
 
+export interface TaskListFilters {
+  status?: string;
+  domain?: string;
+  parent?: string;
+}
+
+export interface TasksChunk {
+  stream_id: string;
+  index: number;
+  tasks: TaskListItem[];
+}
+
+export interface TasksStreamEnd extends CatalogErrorFields {
+  stream_id: string;
+  success: boolean;
+  total: number;
+  sent: number;
+  cancelled: boolean;
+}
+
+/** Start a chunked task list; chunks arrive as `tasks-chunk`, then one `tasks-stream-end` */
+export async function listTasksStream(params?: {
+  filters?: TaskListFilters;
+  compact?: boolean;
+  projection?: string[];
+  chunkSize?: number;
+}): Promise<{ success: boolean; stream_id: string; chunk_size: number }> {
+  return invokeCommand<{ success: boolean; stream_id: string; chunk_size: number }>("tasks_list_stream", {
+    filters: params?.filters,
+    compact: params?.compact,
+    projection: params?.projection,
+    chunkSize: params?.chunkSize,
+  });
+}
+
+/** Stop a stream before its next chunk; false if it had already ended */
+export async function cancelTasksStream(streamId: string): Promise<boolean> {
+  const resp = await invokeCommand<{ success: boolean; cancelled: boolean }>("tasks_stream_cancel", { streamId });
+  return resp.cancelled;
+}
+
+export function onTasksChunk(handler: (chunk: TasksChunk) => void): Promise<() => void> {
+  return listenEvent<TasksChunk>("tasks-chunk", handler);
+}
+
+export function onTasksStreamEnd(handler: (end: TasksStreamEnd) => void): Promise<() => void> {
+  return listenEvent<TasksStreamEnd>("tasks-stream-end", handler);
+}
+
+export interface SubtaskProgress {
+  task_id: string;
+  path: string;
+  /** 1-based per subtask, without gaps: drop what the backlog already had */
+  seq: number;
+  source: "note" | "notification" | "ai_status";
+  message: string;
+  at: string;
+  data?: unknown;
+}
+
+export interface ProgressStreamResponse extends CatalogErrorFields {
+  success: boolean;
+  task_id: string;
+  path: string;
+  /** `poll` when the backend can't push: the task is re-read every few seconds */
+  mode?: "push" | "poll" | null;
+  /** This session's entries so far, oldest first */
+  backlog: SubtaskProgress[];
+  stopped: boolean;
+}
+
+/** Follow a subtask in this window; new entries arrive through `onSubtaskProgress` */
+export async function startProgressStream(taskId: string, path: string): Promise<ProgressStreamResponse> {
+  return invokeCommand<ProgressStreamResponse>("tasks_progress_stream_start", { taskId, path });
+}
+
+/** Stop following a subtask in this window */
+export async function stopProgressStream(taskId: string, path: string): Promise<ProgressStreamResponse> {
+  return invokeCommand<ProgressStreamResponse>("tasks_progress_stream_stop", { taskId, path });
+}
+
+export function onSubtaskProgress(handler: (entry: SubtaskProgress) => void): Promise<() => void> {
+  return listenEvent<SubtaskProgress>("subtask-progress", handler);
+}
+
+/** What `pauseAllAi` stopped */
+export interface PauseRecord {
+  paused_at: string;
+  /** AI status before the pause */
+  status: string;
+  tasks: string[];
+  signal_id: number;
+}
+
+export interface AiPauseResponse extends CatalogErrorFields {
+  success: boolean;
+  /** Already paused, or nothing left to resume */
+  no_op: boolean;
+  paused: PauseRecord | null;
+  entry: unknown | null;
+}
+
+/** Pause all AI activity, remembering what was running */
+export async function pauseAllAi(): Promise<AiPauseResponse> {
+  return invokeCommand<AiPauseResponse>("ai_pause_all");
+}
+
+/** Resume only what `pauseAllAi` paused */
+export async function resumeAllAi(): Promise<AiPauseResponse> {
+  return invokeCommand<AiPauseResponse>("ai_resume_all");
+}
+
+/** A signal the AI raised and waits on */
+export interface AiSignal {
+  /** Backend id, or a content hash when the backend sends none */
+  id: string;
+  kind: string;
+  message: string;
+  task_id: string | null;
+  path: string | null;
+  correlation_id: string;
+}
+
+export interface AiSignalRespondResponse extends CatalogErrorFields {
+  success: boolean;
+  signal: AiSignal | null;
+  entry: unknown | null;
+  /** Set when the correlation id had to go into the message */
+  warning: string | null;
+}
+
+/** Answer a pending AI signal */
+export async function respondToAiSignal(signalId: string, response: string): Promise<AiSignalRespondResponse> {
+  return invokeCommand<AiSignalRespondResponse>("ai_signal_respond", { signalId, response });
+}
+
+/** Signals the AI raises (windows subscribed to the AI status get them) */
+export function onAiSignal(handler: (signal: AiSignal) => void): Promise<() => void> {
+  return listenEvent<AiSignal>("ai-signal", handler);
+}
+
+export interface PrefetchOutcome extends CatalogErrorFields {
+  task_id: string;
+  success: boolean;
+  /** Already cached, so not fetched */
+  cached: boolean;
+}
+
+/** Warm the cache for `tasks_show` of visible rows (at most 50 ids per call) */
+export async function prefetchTasks(
+  taskIds: string[],
+  namespace?: string,
+): Promise<{ success: boolean; results: PrefetchOutcome[]; dropped: number } & CatalogErrorFields> {
+  if (!isTauri) return { success: true, results: [], dropped: 0 };
+  return invokeCommand("tasks_prefetch", { taskIds, namespace });
+}
+
+export interface BoardColumn {
+  status: string;
+  tasks: TaskListItem[];
+}
+
+export interface BoardResponse extends CatalogErrorFields {
+  success: boolean;
+  /** TODO, ACTIVE, DONE, then any other status; cards in the saved order */
+  columns: BoardColumn[];
+  total: number;
+}
+
+/** Tasks grouped into kanban columns (move cards with `tasks_update_status`) */
+export async function getBoard(params: {
+  namespace?: string;
+  domain?: string;
+  forceRefresh?: boolean;
+} = {}): Promise<BoardResponse> {
+  if (!isTauri) return { success: false, columns: [], total: 0, error: "The board needs the desktop app" };
+  return invokeCommand<BoardResponse>("tasks_board", params);
+}
+
+/** Save the card order of one board column after a drag and drop */
+export async function setBoardOrder(
+  status: string,
+  orderedIds: string[],
+): Promise<{ success: boolean; status: string; ordered_ids: string[] } & CatalogErrorFields> {
+  return invokeCommand("tasks_board_set_order", { status, orderedIds });
+}
+
+export interface TimelineBar {
+  task_id: string;
+  title: string;
+  /** RFC3339, clipped to the range */
+  start: string;
+  end: string;
+  status: string;
+  /** Not completed yet, so `end` is now (or the range end) */
+  open: boolean;
+}
+
+export interface TimelineResponse extends CatalogErrorFields {
+  success: boolean;
+  range_start?: string | null;
+  range_end?: string | null;
+  bars: TimelineBar[];
+  /** Tasks with no timestamps at all */
+  unscheduled: { task_id: string; title: string; status: string }[];
+  /** Milestones came from `tasks_history` rather than task timestamps */
+  from_history: boolean;
+}
+
+/** Start-to-completion bars of every task (bounds are RFC3339 or `YYYY-MM-DD`) */
+export async function getTimeline(params: {
+  rangeStart?: string;
+  rangeEnd?: string;
+  namespace?: string;
+} = {}): Promise<TimelineResponse> {
+  if (!isTauri) {
+    return { success: false, bars: [], unscheduled: [], from_history: false, error: "The timeline needs the desktop app" };
+  }
+  return invokeCommand<TimelineResponse>("tasks_timeline", params);
+}
+
+/** Write tasks with a due date to an `.ics` file (the downloads folder without `path`) */
+export async function exportIcs(
+  path?: string,
+  filter?: "overdue" | "today" | "week",
+): Promise<{ success: boolean; path?: string | null; count: number } & CatalogErrorFields> {
+  return invokeCommand("tasks_export_ics", { path, filter });
+}
+
+export type ReportPeriod = "week" | "month" | { from: string; to: string };
+
+export interface ReportResponse extends CatalogErrorFields {
+  success: boolean;
+  from?: string | null;
+  to?: string | null;
+  markdown: string;
+  path?: string | null;
+  completed: number;
+  in_progress: number;
+  created: number;
+  /** Commits were asked for but git history is not available */
+  git_unavailable?: string | null;
+}
+
+/**
+ * Markdown report of completed, in-progress and new tasks (also written to `path` when given),
+ * with the period's commits when `includeCommits`
+ */
+export async function getReport(
+  period: ReportPeriod,
+  namespace?: string,
+  path?: string,
+  includeCommits?: boolean,
+): Promise<ReportResponse> {
+  return invokeCommand<ReportResponse>("tasks_report", { period, namespace, path, includeCommits });
+}
+
+export interface TaskTemplate {
+  /** `{title}` stands for the title given at creation */
+  title_pattern?: string;
+  description?: string;
+  tags?: string[];
+  priority?: "LOW" | "MEDIUM" | "HIGH" | null;
+  /** Each becomes a step; its checkpoints are the step's success criteria */
+  subtasks: { title: string; checkpoints: string[] }[];
+}
+
+export interface TemplateResponse extends CatalogErrorFields {
+  success: boolean;
+  name: string;
+  template?: TaskTemplate | null;
+}
+
+export interface TemplateOverrides {
+  /** Plan the task goes under (required) */
+  parent?: string;
+  description?: string;
+  tags?: string[];
+  priority?: "LOW" | "MEDIUM" | "HIGH";
+  domain?: string;
+}
+
+/** Save (or replace) a local task template */
+export async function saveTemplate(name: string, definition: TaskTemplate): Promise<TemplateResponse> {
+  return invokeCommand<TemplateResponse>("templates_save", { name, definition });
+}
+
+export async function listTemplates(): Promise<
+  { success: boolean; templates: Record<string, TaskTemplate> } & CatalogErrorFields
+> {
+  if (!isTauri) return { success: true, templates: {} };
+  return invokeCommand("templates_list");
+}
+
+export async function deleteTemplate(
+  name: string,
+): Promise<{ success: boolean; name: string; deleted: boolean } & CatalogErrorFields> {
+  return invokeCommand("templates_delete", { name });
+}
+
+/** Write one template to a JSON file for sharing */
+export async function exportTemplate(
+  name: string,
+  path: string,
+): Promise<{ success: boolean; name: string; path: string } & CatalogErrorFields> {
+  return invokeCommand("templates_export", { name, path });
+}
+
+/** Save a shared template file (as `name` when given; `replace` overwrites an existing one) */
+export async function importTemplate(path: string, name?: string, replace?: boolean): Promise<TemplateResponse> {
+  return invokeCommand<TemplateResponse>("templates_import", { path, name, replace });
+}
+
+/** Create a task under `overrides.parent` from a saved template */
+export async function createFromTemplate(
+  name: string,
+  title: string,
+  overrides: TemplateOverrides,
+): Promise<{ success: boolean; task_id?: string | null; title?: string | null; warning?: string | null } & CatalogErrorFields> {
+  return invokeCommand("tasks_create_from_template", { name, title, overrides });
+}
+
+export interface PlannedStep {
+  /** Set once the step is in a task (`s:0`) */
+  path?: string | null;
+  title: string;
+  success_criteria: string[];
+  tests: string[];
+  blockers: string[];
+}
+
+export interface SubtaskPlan {
+  task_id?: string | null;
+  kind?: string | null;
+  steps: PlannedStep[];
+}
+
+export interface TemplateSubtasksResponse extends CatalogErrorFields {
+  success: boolean;
+  plan?: SubtaskPlan | null;
+  /** `kind`/`context` the backend does not take, so they were dropped */
+  warnings: string[];
+}
+
+/** `count` subtasks from the backend's templates, of `kind` and fitted to `context` where supported */
+export async function templateSubtasks(
+  count: number,
+  kind?: string,
+  context?: string,
+): Promise<TemplateSubtasksResponse> {
+  return invokeCommand<TemplateSubtasksResponse>("tasks_template_subtasks", { count, kind, context });
+}
+
+export interface SearchHit {
+  task_id: string;
+  title: string;
+  score: number;
+  /** Matched fields; `ranges` are `[start, end)` string indices to highlight */
+  matches: { field: "title" | "description" | "note"; text: string; ranges: [number, number][] }[];
+}
+
+export interface SearchResponse extends CatalogErrorFields {
+  success: boolean;
+  query: string;
+  hits: SearchHit[];
+  /** `fallback`: title/id substring match while the index is rebuilt */
+  source: "index" | "fallback";
+}
+
+/** Ranked full-text search over titles, descriptions and progress notes */
+export async function searchTasks(query: string, limit?: number): Promise<SearchResponse> {
+  return invokeCommand<SearchResponse>("tasks_search_indexed", { query, limit });
+}
+
+export interface FuzzyMatch {
+  task_id: string;
+  title: string;
+  score: number;
+  /** Which text `indices` point into */
+  field: "title" | "id";
+  /** Matched characters, as string indices */
+  indices: number[];
+}
+
+/** Quick-switcher matches; a blank query lists recently viewed tasks */
+export async function fuzzyFindTasks(
+  query: string,
+  limit?: number,
+): Promise<{ success: boolean; query: string; matches: FuzzyMatch[] } & CatalogErrorFields> {
+  return invokeCommand("tasks_fuzzy", { query, limit });
+}
+
+/** What a line of a pasted checklist became; `path` is its subtask's step path */
+export type ChecklistLine = { line: number; path?: string | null } & (
+  | { role: "title" }
+  | { role: "subtask"; subtask: number }
+  | { role: "checkpoint"; subtask: number; checkpoint: number }
+  | { role: "continuation"; item_line: number }
+);
+
+export interface MarkdownCreateResponse extends CatalogErrorFields {
+  success: boolean;
+  task_id?: string | null;
+  title?: string | null;
+  lines: ChecklistLine[];
+  /** Lines that were skipped or only partly understood */
+  warnings: { line: number; text: string; reason: string }[];
+  /** Step paths of checked subtasks, confirmed after creation */
+  confirmed: string[];
+  warning?: string | null;
+}
+
+/** Create a task under `parent` from a Markdown checklist (heading = title, items = subtasks) */
+export async function createFromMarkdown(
+  markdown: string,
+  parent: string,
+  title?: string,
+  namespace?: string,
+): Promise<MarkdownCreateResponse> {
+  return invokeCommand<MarkdownCreateResponse>("tasks_create_from_markdown", {
+    markdown,
+    title,
+    namespace,
+    parent,
+  });
+}
+
+/** A TODO/FIXME/HACK comment found by `scanTodos` */
+export interface TodoRef {
+  /** Relative to the scanned root */
+  file: string;
+  line: number;
+  kind: "TODO" | "FIXME" | "HACK";
+  text: string;
+  /** The comment's line and those around it */
+  context: { line: number; text: string }[];
+}
+
+export interface TodoScanResponse extends CatalogErrorFields {
+  success: boolean;
+  root: string;
+  files: { file: string; todos: TodoRef[] }[];
+  total: number;
+  /** Stopped at `limit`; there may be more */
+  truncated: boolean;
+  /** Left out: imported as tasks before */
+  already_imported: number;
+  skipped_large: number;
+  skipped_binary: number;
+}
+
+/** TODO comments under `root` (the project when unset), honouring .gitignore */
+export async function scanTodos(
+  root?: string,
+  globs?: string[],
+  limit?: number,
+): Promise<TodoScanResponse> {
+  return invokeCommand<TodoScanResponse>("scan_todos", { root, globs, limit });
+}
+
+/** One `from-todo` task per selected comment; `duplicate` ones were imported before */
+export async function createFromTodos(selected: TodoRef[]): Promise<
+  {
+    success: boolean;
+    results: ({
+      file: string;
+      line: number;
+      task_id?: string | null;
+      duplicate: boolean;
+      warning?: string | null;
+    } & CatalogErrorFields)[];
+  } & CatalogErrorFields
+> {
+  return invokeCommand("tasks_create_from_todos", { selected });
+}
+
+export interface GitInfoResponse extends CatalogErrorFields {
+  success: boolean;
+  /** `branch` is null on a detached HEAD */
+  info?: { branch?: string | null; commit: string } | null;
+  /** `branch@commit` */
+  label?: string | null;
+  /** Why there is no info: git missing, not a repository, ... */
+  unavailable?: string | null;
+}
+
+/** Branch and commit of the open project, for the header */
+export async function gitInfo(): Promise<GitInfoResponse> {
+  return invokeCommand<GitInfoResponse>("git_info");
+}
+
+export type EvidenceKind = "file" | "command_output" | "url" | "text";
+
+export interface EvidenceItem {
+  kind: EvidenceKind;
+  /** Path relative to the project root for files; the output, URL or text otherwise */
+  value: string;
+  label?: string | null;
+  /** Copy of a file in the attachments store */
+  stored?: string | null;
+}
+
+export interface Checkpoint {
+  name: string;
+  confirmed: boolean;
+  auto_confirmed: boolean;
+  notes: string[];
+  evidence: EvidenceItem[];
+}
+
+/**
+ * Confirm checkpoints (`args`: `path`, `checkpoints`, ... of `tasks_verify`); with
+ * `attachGitInfo`, `branch@commit` is appended to each checkpoint note when git can tell;
+ * with `expectedRevision` (the task's `revision`), fails with `REVISION_CONFLICT` if the
+ * task changed since. `evidence` is attached to the confirmed checkpoints (`copyEvidence`
+ * copies file items into the attachments store); a backend without attachments gets it in
+ * the notes, with `evidence_warning` set
+ */
+export async function verifyCheckpoints(
+  taskId: string,
+  args: Record<string, unknown>,
+  attachGitInfo?: boolean,
+  expectedRevision?: number,
+  evidence?: EvidenceItem[],
+  copyEvidence?: boolean,
+): Promise<
+  {
+    success: boolean;
+    task_id: string;
+    result?: AIResponse | null;
+    queued: boolean;
+    git?: string | null;
+    git_unavailable?: string | null;
+    evidence: EvidenceItem[];
+    evidence_warning?: string | null;
+  } & CatalogErrorFields
+> {
+  return invokeCommand("tasks_verify", {
+    taskId,
+    args,
+    attachGitInfo,
+    expectedRevision,
+    evidence,
+    copyEvidence,
+  });
+}
+
+/** Checkpoints of a task (or of its subtask at `path`) with their evidence */
+export async function getCheckpoints(
+  taskId: string,
+  path?: string,
+): Promise<
+  { success: boolean; task_id: string; path?: string | null; checkpoints: Checkpoint[] } & CatalogErrorFields
+> {
+  return invokeCommand("tasks_get_checkpoints", { taskId, path });
+}
+
+export interface GitCommit {
+  hash: string;
+  short_hash: string;
+  author: string;
+  date: string;
+  subject: string;
+}
+
+export interface RelatedCommitsResponse extends CatalogErrorFields {
+  success: boolean;
+  task_id: string;
+  /** When the task was created; the latest commits are listed when unknown */
+  since?: string | null;
+  /** Newest first */
+  commits: GitCommit[];
+  git_available: boolean;
+  /** Why there is no history: git missing, not a repository, ... */
+  unavailable?: string | null;
+}
+
+/** Commits since the task was created (at most `limit`, 50); `mentioning` keeps those naming the task */
+export async function relatedCommits(
+  taskId: string,
+  limit?: number,
+  mentioning?: boolean,
+): Promise<RelatedCommitsResponse> {
+  return invokeCommand<RelatedCommitsResponse>("tasks_related_commits", { taskId, limit, mentioning });
+}
+
+/** Tasks with a title like `title`, best first (`threshold` 0..1, default 0.75) */
+export async function findSimilarTasks(
+  title: string,
+  threshold?: number,
+): Promise<{ success: boolean; matches: SimilarTask[]; threshold: number } & CatalogErrorFields> {
+  return invokeCommand("tasks_find_similar", { title, threshold });
+}
+
+export interface VerificationCommandResponse extends CatalogErrorFields {
+  success: boolean;
+  task_id: string;
+  /** The command exited with 0 and the checkpoint was confirmed */
+  confirmed: boolean;
+  /** Unset when the command didn't run; null when killed */
+  exit_code?: number | null;
+  duration_ms?: number;
+  /** Both streams, the last 64 KB */
+  output?: string;
+  truncated?: boolean;
+  timed_out?: boolean;
+  verify?: { success: boolean; queued: boolean; result?: AIResponse | null } | null;
+}
+
+/**
+ * Run an allowlisted command in the project root and confirm `checkpoint` of the subtask at
+ * `path` when it exits with 0 (the output tail becomes the note); `useShell` runs it through
+ * the shell, where only exact allowlist entries match
+ */
+export async function runVerificationCommand(
+  taskId: string,
+  path: string,
+  checkpoint: string,
+  command: string,
+  options: { timeoutMs?: number; useShell?: boolean } = {},
+): Promise<VerificationCommandResponse> {
+  return invokeCommand<VerificationCommandResponse>("run_verification_command", {
+    taskId,
+    path,
+    checkpoint,
+    command,
+    timeoutMs: options.timeoutMs,
+    useShell: options.useShell,
+  });
+}
+
+export interface VerifyOutputLine {
+  task_id: string;
+  path: string;
+  checkpoint: string;
+  stream: "stdout" | "stderr";
+  line: string;
+}
+
+/** A line of output from a running verification command */
+export function onVerifyOutput(handler: (line: VerifyOutputLine) => void): Promise<() => void> {
+  return listenEvent<VerifyOutputLine>("verify-output", handler);
+}
+
+export interface JournalEntry {
+  seq: number;
+  tool: string;
+  task_id?: string | null;
+  /** Summary of the replaced state (`ACTIVE`) */
+  before?: string | null;
+  /** Summary of the resulting state (`DONE`) */
+  after?: string | null;
+  at: string;
+  /** Backend history operation id */
+  history_id?: string | null;
+}
+
+/** Mutations this app made in the open project, newest first */
+export async function journalList(
+  limit?: number,
+): Promise<{ success: boolean; entries: JournalEntry[] } & CatalogErrorFields> {
+  return invokeCommand("journal_list", { limit });
+}
+
+/** This app's latest change to `taskId` */
+export async function journalLatestFor(taskId: string): Promise<
+  {
+    success: boolean;
+    task_id: string;
+    entry?: JournalEntry | null;
+    description?: string | null;
+  } & CatalogErrorFields
+> {
+  return invokeCommand("journal_latest_for", { taskId });
+}
+
+export interface UndoTarget {
+  operation_id: string;
+  intent?: string | null;
+  task_id?: string | null;
+  timestamp?: string | null;
+  /** `Status change on TASK-042 (ACTIVE → DONE)` */
+  description: string;
+  /** Made by this app (else by another client) */
+  from_this_window: boolean;
+}
+
+export interface UndoResponse extends CatalogErrorFields {
+  success: boolean;
+  preview: boolean;
+  /** Null when there is nothing to undo */
+  target?: UndoTarget | null;
+  undone: boolean;
+  result?: AIResponse | null;
+}
+
+/** What an undo would revert, without undoing it */
+export async function undoPreview(): Promise<UndoResponse> {
+  return invokeCommand<UndoResponse>("tasks_undo", { preview: true });
+}
+
+/**
+ * Undo the latest operation; with the `operationId` of a preview, fails with
+ * `UNDO_TARGET_CHANGED` if another change took its place
+ */
+export async function undo(operationId?: string): Promise<UndoResponse> {
+  return invokeCommand<UndoResponse>("tasks_undo", { preview: false, operationId });
+}
+
+export interface AuditEntry {
+  at: string;
+  /** OS account the app ran as */
+  user: string;
+  /** Backend tool (`tasks_delete`) */
+  command: string;
+  task_ids: string[];
+  /** Arguments, secrets redacted */
+  params: unknown;
+  outcome: "applied" | "queued" | "failed";
+  error?: string | null;
+}
+
+/** Audited mutations of the open project, newest first (dates are RFC3339 or `YYYY-MM-DD`) */
+export async function auditLogQuery(options: {
+  rangeStart?: string;
+  rangeEnd?: string;
+  taskId?: string;
+  limit?: number;
+} = {}): Promise<{ success: boolean; entries: AuditEntry[] } & CatalogErrorFields> {
+  return invokeCommand("audit_log_query", options);
+}
+
+/** Copy the audit log to `path` (JSON lines, relative to the project directory) */
+export async function auditLogExport(
+  path: string,
+): Promise<{ success: boolean; path: string; count: number } & CatalogErrorFields> {
+  return invokeCommand("audit_log_export", { path });
+}
+
+/** Refetch the context of `task` (the project when unset), bypassing the 30 s context cache */
+export async function refreshContext(
+  task?: string,
+  includeAll?: boolean,
+): Promise<{ success: boolean; tasks: TaskListItem[]; raw: ContextData } & CatalogErrorFields> {
+  return invokeCommand("context_refresh", { task, includeAll });
+}
+
 export interface PlanListResponse {
   success: boolean;
   plans: PlanListItem[];
@@ -260,7 +1028,9 @@ export async function createEntity(params: {
   context?: string;
   contract?: string;
   steps?: unknown[];
-}): Promise<{ success: boolean; plan?: Plan; task?: Task; error?: string }> {
+  /** Create even when tasks with a similar title exist */
+  allowDuplicate?: boolean;
+}): Promise<{ success: boolean; plan?: Plan; task?: Task; error?: string; possible_duplicates?: SimilarTask[] }> {
   const resp = await aiIntent<Record<string, unknown>>("create", {
     title: params.title,
     kind: params.kind,
@@ -270,9 +1040,14 @@ export async function createEntity(params: {
     context: params.context,
     contract: params.contract,
     steps: params.steps,
+    allow_duplicate: params.allowDuplicate,
   });
   if (!resp.success) {
-    return { success: false, error: extractAIError(resp) || "Failed to create" };
+    return {
+      success: false,
+      error: extractAIError(resp) || "Failed to create",
+      possible_duplicates: resp.possible_duplicates,
+    };
   }
   const plan = (resp.result?.plan as unknown as Plan | undefined) ?? undefined;
   const task = (resp.result?.task as unknown as Task | undefined) ?? undefined;
@@ -303,10 +1078,71 @@ export async function closeTask(params: {
   return { success: true, result: resp.result };
 }
 
-export async function deleteTask(taskId: string): Promise<{ success: boolean; error?: string }> {
-  const resp = await aiIntent("delete", { task: taskId });
-  if (!resp.success) return { success: false, error: extractAIError(resp) || "Failed to delete task" };
-  return { success: true };
+/** What a delete would remove */
+export interface DeleteSummary {
+  title: string;
+  subtask_count: number;
+  confirmed_checkpoints: number;
+  /** Descendant tasks a cascade deletes too */
+  descendants: string[];
+}
+
+export interface TaskDeleteResponse extends CatalogErrorFields {
+  success: boolean;
+  task_id: string;
+  deleted: boolean;
+  /** Nothing deleted yet: show `summary`, then send `confirm_token` back */
+  needs_confirmation: boolean;
+  confirm_token?: string | null;
+  expires_in_secs?: number | null;
+  summary?: DeleteSummary | null;
+  /** Refused because the task has children and `cascade` is off */
+  has_children: boolean;
+  children: string[];
+  report?: unknown;
+}
+
+/**
+ * Delete a task, or the step or nested task at `path` of it. Without
+ * `confirmToken` nothing is deleted (unless `skip_confirmation` is set): the
+ * answer has `needs_confirmation`, a `summary` and the token to send back.
+ */
+export async function tasksDelete(params: {
+  taskId: string;
+  confirmToken?: string;
+  cascade?: boolean;
+  expectedRevision?: number;
+  path?: string;
+  kind?: "step" | "task";
+}): Promise<TaskDeleteResponse> {
+  return invokeCommand<TaskDeleteResponse>("tasks_delete", params);
+}
+
+/** A delete waiting for the user: what it removes and the token confirming it */
+export interface DeleteConfirmation {
+  token: string;
+  summary: DeleteSummary;
+}
+
+export interface DeleteResult {
+  success: boolean;
+  deleted: boolean;
+  confirmation?: DeleteConfirmation;
+  error?: string;
+}
+
+function deleteResult(resp: TaskDeleteResponse, fallback: string): DeleteResult {
+  if (resp.needs_confirmation && resp.confirm_token && resp.summary) {
+    return { success: true, deleted: false, confirmation: { token: resp.confirm_token, summary: resp.summary } };
+  }
+  if (!resp.success) return { success: false, deleted: false, error: resp.error || fallback };
+  return { success: true, deleted: resp.deleted };
+}
+
+/** Delete a task and its subtasks; the first call (no token) asks for confirmation */
+export async function deleteTask(taskId: string, confirmToken?: string): Promise<DeleteResult> {
+  const resp = await tasksDelete({ taskId, confirmToken, cascade: true });
+  return deleteResult(resp, "Failed to delete task");
 }
 
 export async function editTask(params: {
@@ -440,10 +1276,10 @@ export async function updateTaskNode(params: {
   return { success: true };
 }
 
-export async function deleteTaskNode(params: { taskId: string; path: string }): Promise<{ success: boolean; error?: string }> {
-  const resp = await aiIntent("task_delete", { task: params.taskId, path: params.path });
-  if (!resp.success) return { success: false, error: extractAIError(resp) || "Failed to delete task node" };
-  return { success: true };
+/** Delete the nested task at `path`; the first call (no token) asks for confirmation */
+export async function deleteTaskNode(params: { taskId: string; path: string; confirmToken?: string }): Promise<DeleteResult> {
+  const resp = await tasksDelete({ ...params, kind: "task" });
+  return deleteResult(resp, "Failed to delete task node");
 }
 
 export async function verifyStep(params: {
@@ -517,10 +1353,10 @@ export async function defineStep(params: {
   return { success: true };
 }
 
-export async function deleteStep(params: { taskId: string; path: string }): Promise<{ success: boolean; error?: string }> {
-  const resp = await aiIntent("delete", { task: params.taskId, path: params.path });
-  if (!resp.success) return { success: false, error: extractAIError(resp) || "Failed to delete step" };
-  return { success: true };
+/** Delete the step at `path`; the first call (no token) asks for confirmation */
+export async function deleteStep(params: { taskId: string; path: string; confirmToken?: string }): Promise<DeleteResult> {
+  const resp = await tasksDelete({ ...params, kind: "step" });
+  return deleteResult(resp, "Failed to delete step");
 }
 
 export async function updatePlan(params: {
@@ -612,10 +1448,827 @@ export async function getTaskTimelineEvents(params: {
   return { success: true, events: Array.isArray(events) ? events : [] };
 }
 
+/** Stable failure code from the error catalog (`error` is its English text) */
+export type ErrorCode =
+  | "BRIDGE_SPAWN"
+  | "BRIDGE_DISCONNECTED"
+  | "BRIDGE_TIMEOUT"
+  | "BRIDGE_RESPONSE_TOO_LARGE"
+  | "BRIDGE_RESOURCE_LIMIT"
+  | "BRIDGE_CONTAINER_DAEMON"
+  | "BRIDGE_CONTAINER_IMAGE"
+  | "BRIDGE_SSH_HOST_KEY"
+  | "BRIDGE_SSH_AUTH"
+  | "BRIDGE_SSH_CONNECT"
+  | "BACKEND"
+  | "BACKEND_TOOL_MISSING"
+  | "TASK_NOT_FOUND"
+  | "TASK_FILE_NOT_FOUND"
+  | "SUBTASK_NOT_FOUND"
+  | "JOB_NOT_FOUND"
+  | "AI_SIGNAL_NOT_FOUND"
+  | "TIMER_NOT_RUNNING"
+  | "PROJECT_NOT_FOUND"
+  | "PROJECT_NOT_REGISTERED"
+  | "PROJECT_NAMESPACE_UNKNOWN"
+  | "PROJECT_NAMESPACE_MISMATCH"
+  | "PROJECT_NAMESPACE_NOT_FOUND"
+  | "ROOT_NOT_FOUND"
+  | "STORAGE_MISSING"
+  | "DELETE_FAILED"
+  | "DELETE_HAS_CHILDREN"
+  | "CONFIRM_TOKEN_INVALID"
+  | "CONFIRMATION_REQUIRED"
+  | "UNDO_TARGET_CHANGED"
+  | "REVISION_CONFLICT"
+  | "READ_ONLY_MODE"
+  | "RATE_LIMITED"
+  | "PATH_OUTSIDE_STORAGE"
+  | "OPEN_FAILED"
+  | "CLIPBOARD_FAILED"
+  | "UNKNOWN_INTENT"
+  | "SHORTCUT_INVALID"
+  | "SHORTCUT_UNAVAILABLE"
+  | "SAVED_SHORTCUT_FAILED"
+  | "SAVED_RESTART_FAILED"
+  | "FAULT_INJECTION_DISABLED"
+  | "SUPPORT_BUNDLE_TOO_LARGE"
+  | "TEMPLATE_NOT_FOUND"
+  | "TEMPLATE_EXISTS"
+  | "VALIDATION_TITLE_EMPTY"
+  | "VALIDATION_MULTIPLE_DOMAINS"
+  | "VALIDATION_MULTIPLE_NAMESPACES"
+  | "VALIDATION_LOG_LEVEL"
+  | "VALIDATION_TASK_ID_REQUIRED"
+  | "VALIDATION_INVALID_DATE"
+  | "VALIDATION_INVALID_DUE_DATE"
+  | "VALIDATION_UNKNOWN_SIGNAL"
+  | "VALIDATION_ALIAS_NAME"
+  | "VALIDATION_ALIAS_TOOL"
+  | "VALIDATION_ALIAS_DESTRUCTIVE"
+  | "VALIDATION_SETTINGS_NOT_OBJECT"
+  | "VALIDATION_SETTINGS_PATCH"
+  | "VALIDATION_CACHE_TTL"
+  | "VALIDATION_TOOL_TIMEOUT"
+  | "VALIDATION_PYTHON_PATH"
+  | "VALIDATION_CONTAINER_IMAGE"
+  | "VALIDATION_SSH"
+  | "VALIDATION_SSH_HOST"
+  | "VALIDATION_MESSAGE_LIMIT"
+  | "VALIDATION_RATE_LIMIT"
+  | "VALIDATION_SESSION_PATCH"
+  | "VALIDATION_SUGGESTION_ACTION"
+  | "VALIDATION_FAULT_RULE"
+  | "VALIDATION_BOARD_STATUS"
+  | "VALIDATION_TEMPLATE_NAME"
+  | "VALIDATION_TEMPLATE_SUBTASK_TITLE"
+  | "VALIDATION_TEMPLATE_CHECKPOINTS"
+  | "VALIDATION_TEMPLATE_DUPLICATE_CHECKPOINT"
+  | "VALIDATION_TEMPLATE_PARENT"
+  | "VALIDATION_TEMPLATE_FILE"
+  | "VALIDATION_CHECKLIST_EMPTY"
+  | "VALIDATION_CHECKLIST_PARENT"
+  | "VALIDATION_TODO_ROOT"
+  | "VALIDATION_TODO_GLOB"
+  | "VALIDATION_COMMAND_EMPTY"
+  | "VALIDATION_COMMAND_SYNTAX"
+  | "COMMAND_NOT_ALLOWED"
+  | "COMMAND_SPAWN_FAILED"
+  | "VALIDATION_EVIDENCE_EMPTY"
+  | "EVIDENCE_FILE_NOT_FOUND"
+  | "EVIDENCE_OUTSIDE_PROJECT"
+  | "EVIDENCE_COPY_FAILED"
+  | "VALIDATION_FIELDS"
+  | "INTERNAL";
+
+/** `error` plus its catalog code and params, as sent by the GUI backend commands */
+export interface CatalogErrorFields {
+  error?: string | null;
+  error_code?: ErrorCode | null;
+  error_params?: Record<string, unknown> | null;
+  /** `data` of the backend's JSON-RPC error, as sent */
+  error_detail?: unknown;
+}
+
+/** One rejected field of a `VALIDATION_FIELDS` error (`error_params.fields`) */
+export interface FieldError {
+  field: string;
+  reason: string;
+}
+
+export interface RegisteredProject {
+  path: string;
+  name: string;
+  last_opened: string | null;
+  exists: boolean;
+  current: boolean;
+}
+
+export interface ProjectsResponse extends CatalogErrorFields {
+  success: boolean;
+  projects: RegisteredProject[];
+  current: string;
+  /** Launched without a project hint: show the registry */
+  needs_selection: boolean;
+}
+
+export interface DetectionProbe {
+  strategy: "env_var" | "exe_relative" | "cwd_ancestor" | "package" | "remote";
+  path: string | null;
+  matched: boolean;
+  detail: string;
+}
+
+export interface DetectionReport extends CatalogErrorFields {
+  success: boolean;
+  root: string;
+  strategy: DetectionProbe["strategy"] | null;
+  probes: DetectionProbe[];
+  install_mode: InstallMode | null;
+  /** Environment variables that were set but ignored */
+  warnings: ConfigWarning[];
+}
+
+export interface ConfigWarning {
+  variable: "APPLY_TASK_PROJECT_ROOT" | "APPLY_TASK_PATH" | "APPLY_TASK_PYTHON";
+  path: string;
+  reason: string;
+  message: string;
+}
+
+/** An environment variable names a path that can't be used (startup, once per variable) */
+export function onConfigWarning(handler: (warning: ConfigWarning) => void): Promise<() => void> {
+  return listenEvent<ConfigWarning>("config-warning", handler);
+}
+
+export type InstallMode = "env_path" | "checkout" | "package" | "console_script" | "module" | "container" | "ssh";
+
+export interface BridgeStatus {
+  running: boolean;
+  pid: number | null;
+  install_mode: InstallMode;
+  /** Program and arguments the backend is started with */
+  command: string[];
+  pythonpath: string | null;
+  storage_mode: "global" | "local";
+  /** `user@host:path` of an SSH backend */
+  remote: string | null;
+  resources: BackendResources | null;
+}
+
+export interface ResourceSample {
+  at: string;
+  pid: number;
+  rss_bytes: number;
+  /** Of one core, so over 100 when several are busy */
+  cpu_percent: number;
+}
+
+/** Backend process RSS and CPU, sampled every few seconds */
+export interface BackendResources {
+  /** The backend process can be sampled (per-process stats on this
+   * platform, and the backend runs here) */
+  supported: boolean;
+  /** Why it can't be, when `supported` is false */
+  unavailable: string | null;
+  current: ResourceSample | null;
+  peak_rss_bytes: number;
+  peak_cpu_percent: number;
+  /** Oldest first */
+  history: ResourceSample[];
+}
+
+export interface ResourceWarning {
+  pid: number;
+  rss_bytes: number;
+  threshold_bytes: number;
+  at: string;
+}
+
+/** The backend's RSS crossed `backend_rss_warn_mb` */
+export function onBackendResourceWarning(handler: (warning: ResourceWarning) => void): Promise<() => void> {
+  return listenEvent<ResourceWarning>("backend-resource-warning", handler);
+}
+
+export interface ResourceKill {
+  pid: number;
+  /** RSS of the last sample */
+  rss_bytes: number;
+  cap_bytes: number;
+  peak_rss_bytes: number;
+  peak_cpu_percent: number;
+  /** Samples in a row over the cap */
+  samples_over: number;
+  at: string;
+}
+
+/** The backend was stopped for staying over `backend_rss_cap_mb`; calls in
+ * flight failed with BRIDGE_RESOURCE_LIMIT, the next one restarts it */
+export function onBackendKilledResourceLimit(handler: (kill: ResourceKill) => void): Promise<() => void> {
+  return listenEvent<ResourceKill>("backend-killed-resource-limit", handler);
+}
+
+/** Backend process state and how it is launched */
+export async function getBridgeStatus(): Promise<BridgeStatus | null> {
+  if (!isTauri) return null;
+  return invokeCommand<BridgeStatus>("bridge_status");
+}
+
+export interface DoctorCheck {
+  check: string;
+  status: "ok" | "warn" | "fail";
+  detail: string;
+  suggestion: string | null;
+}
+
+export interface DoctorReport {
+  /** No check failed (warnings allowed) */
+  success: boolean;
+  checks: DoctorCheck[];
+  elapsed_ms: number;
+  /** Current log file (null when logging to stderr only) */
+  log_path: string | null;
+  /** Last 200 log lines, oldest first */
+  recent_log: string[];
+  /** Errors the frontend logged this session */
+  frontend_errors: number;
+}
+
+/** Support checklist (takes up to ~10 s; uses a throwaway backend) */
+export async function runDoctor(): Promise<DoctorReport | null> {
+  if (!isTauri) return null;
+  return invokeCommand<DoctorReport>("doctor");
+}
+
+/** Current log file (`apply-task.log` in the app log directory) */
+export async function getLogPath(): Promise<string | null> {
+  if (!isTauri) return null;
+  return invokeCommand<string>("get_log_path");
+}
+
+export type FrontendLogLevel = "error" | "warn" | "info" | "debug" | "trace";
+
+/** Write to the app log file (`[frontend]`); never throws */
+export async function logFromFrontend(
+  level: FrontendLogLevel,
+  message: string,
+  context?: Record<string, unknown>
+): Promise<void> {
+  if (!isTauri) return;
+  try {
+    await invokeCommand<boolean>("log_from_frontend", { level, message, context: context ?? null });
+  } catch {
+    // Logging must not become another error
+  }
+}
+
+/** A crash report left by an earlier run */
+export interface CrashReport {
+  id: string;
+  /** First line of the panic message */
+  summary: string;
+  created: string | null;
+  text: string;
+}
+
+/** Crash reports from earlier runs, newest first */
+export async function getCrashReports(): Promise<CrashReport[]> {
+  if (!isTauri) return [];
+  return invokeCommand<CrashReport[]>("get_crash_reports");
+}
+
+export async function deleteCrashReport(id: string): Promise<void> {
+  await invokeCommand<void>("delete_crash_report", { id });
+}
+
+/** Crash reports were found at startup */
+export function onCrashReportAvailable(
+  handler: (info: { count: number; latest: string }) => void
+): Promise<() => void> {
+  return listenEvent<{ count: number; latest: string }>("crash-report-available", handler);
+}
+
+/** Local usage counters (`usage-metrics.json`, never uploaded) */
+export interface UsageMetrics {
+  since: string | null;
+  /** Calls per Tauri command, and responses by `success` for commands that record them */
+  commands: Record<string, { calls: number; success: number; failure: number }>;
+  /** Results per `ai_intent` tool */
+  intents: Record<string, { success: number; failure: number }>;
+}
+
+export interface UsageMetricsResponse extends CatalogErrorFields {
+  success: boolean;
+  enabled: boolean;
+  path: string;
+  metrics: UsageMetrics;
+}
+
+export async function getUsageMetrics(): Promise<UsageMetricsResponse | null> {
+  if (!isTauri) return null;
+  return invokeCommand<UsageMetricsResponse>("usage_metrics_get");
+}
+
+export async function resetUsageMetrics(): Promise<UsageMetricsResponse | null> {
+  if (!isTauri) return null;
+  return invokeCommand<UsageMetricsResponse>("usage_metrics_reset");
+}
+
+/** Turn local usage counting on or off (`analytics_enabled`) */
+export async function setAnalyticsEnabled(enabled: boolean): Promise<{ success: boolean } & CatalogErrorFields> {
+  if (!isTauri) return { success: false, error: "Usage metrics need the desktop app" };
+  return invokeCommand<{ success: boolean } & CatalogErrorFields>("set_settings", {
+    patch: { analytics_enabled: enabled },
+  });
+}
+
+/** Latest GitHub release compared with this build */
+export interface UpdateInfo {
+  current: string;
+  latest: string;
+  update_available: boolean;
+  release_url: string | null;
+  notes_excerpt: string | null;
+}
+
+export interface UpdateCheckResponse extends Partial<UpdateInfo>, CatalogErrorFields {
+  success: boolean;
+}
+
+/** Ask GitHub for the latest release now (errors are reported, unlike at startup) */
+export async function checkForUpdates(): Promise<UpdateCheckResponse> {
+  if (!isTauri) return { success: false, error: "Update checks need the desktop app" };
+  return invokeCommand<UpdateCheckResponse>("check_for_updates");
+}
+
+/** A newer release was found by the startup check */
+export function onUpdateAvailable(handler: (info: UpdateInfo) => void): Promise<() => void> {
+  return listenEvent<UpdateInfo>("update-available", handler);
+}
+
+/** `update_check_enabled` (unset means on) */
+export async function getUpdateCheckEnabled(): Promise<boolean | null> {
+  if (!isTauri) return null;
+  const resp = await invokeCommand<{ success: boolean; settings: { update_check_enabled?: boolean | null } }>(
+    "get_settings"
+  );
+  return resp.settings.update_check_enabled ?? true;
+}
+
+export async function setUpdateCheckEnabled(enabled: boolean): Promise<{ success: boolean } & CatalogErrorFields> {
+  if (!isTauri) return { success: false, error: "Update checks need the desktop app" };
+  return invokeCommand<{ success: boolean } & CatalogErrorFields>("set_settings", {
+    patch: { update_check_enabled: enabled },
+  });
+}
+
+/** Open the log directory in the file manager */
+export async function openLogFolder(): Promise<void> {
+  if (!isTauri) return;
+  await invokeCommand<void>("open_log_folder");
+}
+
+export interface Versions {
+  gui: string;
+  tauri: string;
+  python: string | null;
+  backend: string | null;
+  backend_source: "server_info" | "cli" | null;
+  /** Backend major version outside the range this GUI supports */
+  compat_warning: string | null;
+}
+
+/** App, runtime, Python and backend versions (probed once, then cached) */
+export async function getVersions(): Promise<Versions | null> {
+  if (!isTauri) return null;
+  return invokeCommand<Versions>("versions");
+}
+
+export interface BackendVersionCheck {
+  status: "ok" | "outdated" | "unknown";
+  backend: string | null;
+  minimum: string;
+  install_mode: InstallMode;
+  /** Command that upgrades this installation (none for checkouts) */
+  upgrade_command: string | null;
+  guidance: string;
+}
+
+/** Backend version against the minimum this GUI needs, with upgrade steps */
+export async function checkBackendVersion(): Promise<BackendVersionCheck | null> {
+  if (!isTauri) return null;
+  return invokeCommand<BackendVersionCheck>("check_backend_version");
+}
+
+export interface ConfigEntry {
+  key: string;
+  value: string | null;
+  /** `env:<NAME>`, `cli`, `settings`, `detected`, `selected` or `default` */
+  source: string;
+}
+
+export interface EnvInfo {
+  settings: ConfigEntry[];
+  /** `APPLY_TASK_*` and Python variables as the GUI sees them */
+  environment: ConfigEntry[];
+  /** What the backend was started with (variable names only) */
+  backend_env: { mode: "inherit" | "isolated"; variables: string[] };
+}
+
+/** Effective bridge configuration with the source of each value (home as `~`, secrets redacted) */
+export async function getEnvInfo(): Promise<EnvInfo | null> {
+  if (!isTauri) return null;
+  return invokeCommand<EnvInfo>("env_info");
+}
+
+export interface SupportBundleResponse extends CatalogErrorFields {
+  success: boolean;
+  bundle?: { path: string; bytes: number; files: string[] } | null;
+  /** Whether the file manager was opened on the zip */
+  revealed: boolean;
+}
+
+/** Zip logs, recent backend traffic and diagnostics (redacted) for a bug report */
+export async function createSupportBundle(reveal = false): Promise<SupportBundleResponse> {
+  if (!isTauri) return { success: false, revealed: false, error: "Support bundles need the desktop app" };
+  return invokeCommand<SupportBundleResponse>("create_support_bundle", { reveal });
+}
+
+export type InjectedFailure = "timeout" | "tool_error" | "disconnect";
+
+/** Fault injection rule (developer mode) */
+export interface FaultRule {
+  /** Tool name, or `*` for every tool */
+  tool: string;
+  failure: InjectedFailure;
+  /** Chance that a matching call fails (default 1) */
+  probability?: number;
+  /** Failures left (unlimited when absent) */
+  count?: number | null;
+  /** Failures injected so far (set by the backend) */
+  injected?: number;
+}
+
+type FaultInjectionResponse = { success: boolean; rules: FaultRule[] } & CatalogErrorFields;
+
+/** Make matching `call_tool`s fail like real timeouts, tool errors or crashes (developer mode only) */
+export async function setFaultInjection(rules: FaultRule[]): Promise<FaultInjectionResponse> {
+  if (!isTauri) return { success: false, rules: [], error: "Fault injection needs the desktop app" };
+  return invokeCommand<FaultInjectionResponse>("set_fault_injection", { rules });
+}
+
+export async function getFaultInjection(): Promise<FaultInjectionResponse> {
+  if (!isTauri) return { success: true, rules: [] };
+  return invokeCommand<FaultInjectionResponse>("get_fault_injection");
+}
+
+export async function clearFaultInjection(): Promise<FaultInjectionResponse> {
+  if (!isTauri) return { success: true, rules: [] };
+  return invokeCommand<FaultInjectionResponse>("clear_fault_injection");
+}
+
+export interface OpenProjectRequest {
+  /** Working directory of the other launch */
+  cwd: string;
+  args: string[];
+  path: string;
+  /** apply_task project at (or directly inside) `path` */
+  project: string | null;
+  /** `--task` / `--namespace` of the other launch */
+  task: string | null;
+  namespace: string | null;
+}
+
+/** `navigate-to-task` (deep link or notification click) */
+export interface NavigateToTask {
+  namespace: string;
+  task_id: string;
+  /** The task is in another project than the open one */
+  requires_project_switch: boolean;
+  current_namespace: string | null;
+}
+
+export function onNavigateToTask(handler: (target: NavigateToTask) => void): Promise<() => void> {
+  return listenEvent<NavigateToTask>("navigate-to-task", handler);
+}
+
+/** Which OS notifications the backend sends */
+export interface NotificationPrefs {
+  enabled: boolean;
+  task_completed: boolean;
+  checkpoints_green: boolean;
+  ai_paused: boolean;
+  ai_signal: boolean;
+  backend_crashed: boolean;
+  only_when_unfocused: boolean;
+}
+
+export interface NotificationPrefsResponse extends CatalogErrorFields {
+  success: boolean;
+  prefs: NotificationPrefs;
+}
+
+export async function getNotificationPrefs(): Promise<NotificationPrefsResponse | null> {
+  if (!isTauri) return null;
+  return invokeCommand<NotificationPrefsResponse>("notification_prefs_get");
+}
+
+export async function setNotificationPrefs(prefs: NotificationPrefs): Promise<NotificationPrefsResponse | null> {
+  if (!isTauri) return null;
+  return invokeCommand<NotificationPrefsResponse>("notification_prefs_set", { prefs });
+}
+
+export interface ReadOnlyResponse extends CatalogErrorFields {
+  success: boolean;
+  read_only: boolean;
+}
+
+/** Whether mutations are refused (`READ_ONLY_MODE`) */
+export async function getReadOnly(): Promise<ReadOnlyResponse | null> {
+  if (!isTauri) return null;
+  return invokeCommand<ReadOnlyResponse>("get_read_only");
+}
+
+export async function setReadOnly(enabled: boolean): Promise<ReadOnlyResponse | null> {
+  if (!isTauri) return null;
+  return invokeCommand<ReadOnlyResponse>("set_read_only", { enabled });
+}
+
+/** Read-only mode was turned on or off (in any window) */
+export function onReadOnlyChanged(handler: (change: { read_only: boolean }) => void): Promise<() => void> {
+  return listenEvent<{ read_only: boolean }>("read-only-changed", handler);
+}
+
+/** Global quick-add shortcut and whether the OS accepted it */
+export interface QuickAddStatus extends CatalogErrorFields {
+  shortcut: string | null;
+  registered: boolean;
+}
+
+export interface QuickCreateResponse extends CatalogErrorFields {
+  success: boolean;
+  task_id?: string | null;
+  title?: string | null;
+  /** Created, but tags, domain or due date could not be set */
+  warning?: string | null;
+  /** Markers the parser kept in the title */
+  warnings?: string[];
+}
+
+/** A parsed quick-add line */
+export interface ParsedTask {
+  title: string;
+  tags: string[];
+  priority: "LOW" | "MEDIUM" | "HIGH" | null;
+  domain: string | null;
+  /** `YYYY-MM-DD` */
+  due: string | null;
+  namespace: string | null;
+  /** Markers that were not understood (and were kept in the title) */
+  warnings: string[];
+}
+
+/** How a quick-add line parses, for a live preview (nothing is created) */
+export async function parseQuickTask(
+  text: string,
+): Promise<{ success: boolean; task?: ParsedTask | null } & CatalogErrorFields> {
+  return invokeCommand("parse_quick_task", { text });
+}
+
+/** Create a task from a quick-add line (`Fix login #auth !high @backend due:2025-03-01`) */
+export async function quickCreate(text: string): Promise<QuickCreateResponse> {
+  if (!isTauri) return { success: false, error: "Quick add needs the desktop app" };
+  return invokeCommand<QuickCreateResponse>("quick_create", { text });
+}
+
+/** Close the quick-add window */
+export async function dismissQuickAdd(): Promise<void> {
+  if (!isTauri) return;
+  await invokeCommand<void>("quick_add_dismiss");
+}
+
+export async function getQuickAddStatus(): Promise<QuickAddStatus | null> {
+  if (!isTauri) return null;
+  return invokeCommand<QuickAddStatus>("quick_add_status");
+}
+
+/** Change the quick-add shortcut (`null`: the default, `""`: disabled) */
+export async function setQuickAddShortcut(
+  shortcut: string | null
+): Promise<{ success: boolean } & CatalogErrorFields> {
+  if (!isTauri) return { success: false, error: "Shortcuts need the desktop app" };
+  return invokeCommand<{ success: boolean } & CatalogErrorFields>("set_settings", {
+    patch: { quick_add_shortcut: shortcut },
+  });
+}
+
+/** Change the pause all / resume all shortcut (`null` or `""`: none) */
+export async function setPauseAllShortcut(
+  shortcut: string | null
+): Promise<{ success: boolean } & CatalogErrorFields> {
+  if (!isTauri) return { success: false, error: "Shortcuts need the desktop app" };
+  return invokeCommand<{ success: boolean } & CatalogErrorFields>("set_settings", {
+    patch: { pause_all_shortcut: shortcut },
+  });
+}
+
+/** `container` setting: the backend runs in `image` with the project at `/workspace` */
+export interface ContainerConfig {
+  image: string;
+  /** Further `-v` mounts (`host:container[:ro]`) */
+  mounts?: string[];
+  /** Further `docker run` arguments, before the image */
+  args?: string[];
+  /** Container CLI (default `docker`) */
+  program?: string | null;
+}
+
+/** Run the backend in a container (`null`: with Python again); restarts the backend */
+export async function setBackendContainer(
+  container: ContainerConfig | null
+): Promise<{ success: boolean; restarted: boolean } & CatalogErrorFields> {
+  if (!isTauri) return { success: false, restarted: false, error: "Needs the desktop app" };
+  return invokeCommand<{ success: boolean; restarted: boolean } & CatalogErrorFields>("set_settings", {
+    patch: { container },
+  });
+}
+
+/** `ssh` setting: the backend runs `command` in `path` on `host` over `ssh -T` */
+export interface SshConfig {
+  host: string;
+  user?: string | null;
+  /** Project directory on the host */
+  path: string;
+  /** Backend command on the host (default `apply_task mcp`) */
+  command?: string | null;
+  /** Further ssh arguments (`-p 2222`, `-i ~/.ssh/key`) */
+  args?: string[];
+  /** SSH client (default `ssh`) */
+  program?: string | null;
+}
+
+/** Run the backend on an SSH host (`null`: locally again); restarts the backend */
+export async function setBackendSsh(
+  ssh: SshConfig | null
+): Promise<{ success: boolean; restarted: boolean } & CatalogErrorFields> {
+  if (!isTauri) return { success: false, restarted: false, error: "Needs the desktop app" };
+  return invokeCommand<{ success: boolean; restarted: boolean } & CatalogErrorFields>("set_settings", {
+    patch: { ssh },
+  });
+}
+
+/** Start the backend with a minimal environment (`allowlist`: further
+ * variables it gets); restarts the backend */
+export async function setIsolatedEnv(
+  isolated: boolean,
+  allowlist?: string[]
+): Promise<{ success: boolean; restarted: boolean } & CatalogErrorFields> {
+  if (!isTauri) return { success: false, restarted: false, error: "Needs the desktop app" };
+  const patch: Record<string, unknown> = { isolated_env: isolated };
+  if (allowlist) patch.isolated_env_allowlist = allowlist;
+  return invokeCommand<{ success: boolean; restarted: boolean } & CatalogErrorFields>("set_settings", { patch });
+}
+
+/** A task was created from the quick-add window */
+export function onQuickTaskCreated(handler: (created: { task_id: string }) => void): Promise<() => void> {
+  return listenEvent<{ task_id: string }>("quick-task-created", handler);
+}
+
+/** Forget saved window geometry; open windows go back to the default size, centered */
+export async function resetWindowState(): Promise<void> {
+  if (!isTauri) return;
+  await invokeCommand<void>("reset_window_state");
+}
+
+/** Namespace and view a window was opened on (`open_window`) */
+export interface WindowScope {
+  namespace: string | null;
+  view: string | null;
+}
+
+export interface WindowScopeResponse extends CatalogErrorFields {
+  success: boolean;
+  label: string;
+  scope: WindowScope;
+}
+
+/** Open another window pinned to `namespace` and `view` */
+export async function openWindow(namespace?: string, view?: string): Promise<WindowScopeResponse> {
+  return invokeCommand<WindowScopeResponse>("open_window", { namespace, view });
+}
+
+let windowScopeCache: Promise<WindowScope> | null = null;
+
+/** Scope of this window (fetched once; empty for the main window) */
+export function getWindowScope(): Promise<WindowScope> {
+  if (!isTauri) return Promise.resolve({ namespace: null, view: null });
+  if (!windowScopeCache) {
+    windowScopeCache = invokeCommand<WindowScopeResponse>("window_scope").then((r) => r.scope);
+  }
+  return windowScopeCache;
+}
+
+/** `namespace` if given, else the one this window is pinned to */
+export async function scopedNamespace(namespace?: string): Promise<string | undefined> {
+  return namespace ?? (await getWindowScope()).namespace ?? undefined;
+}
+
+/** Task named on the command line (`--task`, `--namespace`) */
+export interface StartupIntent {
+  task: string;
+  namespace: string | null;
+}
+
+/** The launch's `--task`, if any (returned only once) */
+export async function getStartupIntent(): Promise<StartupIntent | null> {
+  if (!isTauri) return null;
+  return invokeCommand<StartupIntent | null>("startup_intent");
+}
+
+export interface SessionState {
+  project?: string | null;
+  namespace?: string | null;
+  task_id?: string | null;
+  view?: string | null;
+}
+
+export interface SessionResponse extends CatalogErrorFields {
+  success: boolean;
+  session: SessionState;
+  /** Stale parts (gone project, namespace or task) were dropped */
+  restored_partial: boolean;
+  /** The saved project was reopened */
+  project_opened: boolean;
+}
+
+/** The saved session to restore at startup, checked against what exists now */
+export async function sessionGet(): Promise<SessionResponse> {
+  return invokeCommand<SessionResponse>("session_get");
+}
+
+/** Record navigation (`null` clears a key); saved once it settles */
+export async function sessionUpdate(partialState: SessionState): Promise<SessionResponse> {
+  return invokeCommand<SessionResponse>("session_update", { partialState });
+}
+
+export async function sessionClear(): Promise<SessionResponse> {
+  return invokeCommand<SessionResponse>("session_clear");
+}
+
+/** Another launch (`apply-task-gui <dir>`) asked this window to open a project */
+export function onOpenProjectRequest(handler: (request: OpenProjectRequest) => void): Promise<() => void> {
+  return listenEvent<OpenProjectRequest>("open-project-request", handler);
+}
+
+/** Quit was requested while these storage-writing tools were still running */
+export function onExitConfirmRequested(handler: (tools: string[]) => void): Promise<() => void> {
+  return listenEvent<{ tools: string[] }>("exit-confirm-requested", (payload) => handler(payload.tools));
+}
+
+/** Quit anyway (answer to `exit-confirm-requested`) */
+export async function confirmExit(): Promise<void> {
+  if (!isTauri) return;
+  await invokeCommand<void>("confirm_exit");
+}
+
+/** How the apply_task root was found at startup (every probed path) */
+export async function getDetectionReport(): Promise<DetectionReport | null> {
+  if (!isTauri) return null;
+  return invokeCommand<DetectionReport>("detection_report");
+}
+
+export async function listProjects(): Promise<ProjectsResponse> {
+  if (!isTauri) {
+    return { success: true, projects: [], current: "", needs_selection: false };
+  }
+  return invokeCommand<ProjectsResponse>("projects_list");
+}
+
+export async function removeProject(path: string): Promise<ProjectsResponse> {
+  return invokeCommand<ProjectsResponse>("projects_remove", { path });
+}
+
+/** Restart the backend in another project (registers it if needed) */
+export async function switchProject(
+  path: string
+): Promise<{ success: boolean; path?: string; restarted?: boolean; error?: string }> {
+  const resp = await invokeCommand<{ success: boolean; path?: string | null; restarted: boolean } & CatalogErrorFields>(
+    "projects_open",
+    { path }
+  );
+  return {
+    success: Boolean(resp?.success),
+    path: resp?.path ?? undefined,
+    restarted: Boolean(resp?.restarted),
+    error: resp?.error ?? undefined,
+  };
+}
+
 export async function openProject(): Promise<{ success: boolean; path?: string; error?: string }> {
   const path = window.prompt("Enter project folder path:", "");
   if (!path) return { success: false, error: "Cancelled" };
-  return { success: true, path };
+  if (!isTauri) return { success: true, path };
+  return switchProject(path);
 }
 
 export async function openPath(path: string): Promise<{ success: boolean; error?: string }> {
@@ -4,6 +4,7 @@ import "@/styles/globals.css";
 import { QueryClient, QueryClientProvider } from "@tanstack/react-query";
 import { FatalErrorScreen } from "@/components/common/FatalErrorScreen";
 import { AppErrorBoundary } from "@/components/common/AppErrorBoundary";
+import { logFromFrontend } from "@/lib/tauri";
 
 const queryClient = new QueryClient();
 
@@ -29,11 +30,25 @@ renderApp(
   </div>
 );
 
+function errorText(error: unknown): { message: string; stack?: string } {
+  if (error instanceof Error) return { message: error.message, stack: error.stack };
+  return { message: String(error) };
+}
+
 window.addEventListener("error", (e) => {
+  const { message, stack } = errorText(e.error || e.message);
+  void logFromFrontend("error", `Unhandled error: ${message}`, {
+    source: e.filename,
+    line: e.lineno,
+    column: e.colno,
+    stack,
+  });
   renderApp(<FatalErrorScreen error={e.error || e.message} />);
 });
 
 window.addEventListener("unhandledrejection", (e) => {
+  const { message, stack } = errorText(e.reason);
+  void logFromFrontend("error", `Unhandled rejection: ${message}`, { stack });
   renderApp(<FatalErrorScreen error={e.reason} />);
 });
 
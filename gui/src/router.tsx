@@ -9,6 +9,7 @@ import { Route as timelineRoute } from './routes/timeline'
 import { Route as dashboardRoute } from './routes/dashboard'
 import { Route as projectsRoute } from './routes/projects'
 import { Route as settingsRoute } from './routes/settings'
+import { Route as quickAddRoute } from './routes/quick-add'
 
 // Build the route tree
 const routeTree = rootRoute.addChildren([
@@ -21,6 +22,7 @@ const routeTree = rootRoute.addChildren([
     dashboardRoute,
     projectsRoute,
     settingsRoute,
+    quickAddRoute,
 ])
 
 // Create the router
@@ -1,4 +1,4 @@
-import { useCallback, useEffect } from 'react'
+import { useCallback, useEffect, useState } from 'react'
 import { createRootRoute, Outlet, useLocation, useNavigate } from '@tanstack/react-router'
 import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query'
 import { Sidebar } from '@/components/layout/Sidebar'
@@ -13,8 +13,10 @@ import { ToastContainer } from '@/components/common/Toast'
 import { toast } from '@/components/common/toast'
 import { isEditableTarget, isPlainKeypress } from '@/lib/keyboard'
 import { useMediaQuery } from '@/hooks/useMediaQuery'
-import { getOperationHistory, redoLastOperation, undoLastOperation } from '@/lib/tauri'
+import { confirmExit, deleteCrashReport, getCrashReports, getDetectionReport, getOperationHistory, getQuickAddStatus, getStartupIntent, listProjects, onConfigWarning, onCrashReportAvailable, onExitConfirmRequested, onNavigateToTask, onOpenProjectRequest, onQuickTaskCreated, onUpdateAvailable, redoLastOperation, switchProject, undoLastOperation } from '@/lib/tauri'
 import { OperationHistoryDialog } from '@/components/common/OperationHistoryDialog'
+import { CrashReportDialog } from '@/components/common/CrashReportDialog'
+import type { CrashReport } from '@/lib/tauri'
 import '../styles/globals.css';
 
 interface RootSearch {
@@ -31,6 +33,21 @@ export const Route = createRootRoute({
 })
 
 function RootComponent() {
+    const location = useLocation()
+    // The quick-add window renders only its form, without the app shell
+    // (and its listeners, which belong to the main window)
+    if (location.pathname === '/quick-add') {
+        return (
+            <>
+                <Outlet />
+                <ToastContainer />
+            </>
+        )
+    }
+    return <AppShell />
+}
+
+function AppShell() {
     const {
         sidebarOpen,
         setSidebarOpen,
@@ -166,6 +183,168 @@ function RootComponent() {
         }
     }
 
+    // `--task` on the command line (this launch or a later one)
+    const openLaunchTask = (taskId: string, namespace: string | null) => {
+        navigate({
+            to: "/task/$taskId",
+            params: { taskId },
+            search: { namespace: namespace || undefined },
+        })
+    }
+
+    // Launched without a project hint (or detection failed): start on the project registry
+    useEffect(() => {
+        let cancelled = false
+        Promise.all([listProjects(), getDetectionReport(), getStartupIntent()])
+            .then(([projects, detection, intent]) => {
+                if (cancelled) return
+                if (detection && !detection.success) {
+                    toast.error(detection.error || 'apply_task root not found')
+                }
+                if (projects.needs_selection || (detection && !detection.success)) {
+                    navigate({ to: '/projects' })
+                } else if (intent) {
+                    openLaunchTask(intent.task, intent.namespace)
+                }
+            })
+            .catch(() => {})
+        return () => {
+            cancelled = true
+        }
+        // eslint-disable-next-line react-hooks/exhaustive-deps
+    }, [])
+
+    // A second launch hands its directory over instead of starting another backend
+    useEffect(() => {
+        const unlisten = onOpenProjectRequest(async (request) => {
+            if (!request.project) {
+                toast.error(`Not an apply_task project: ${request.path}`)
+                navigate({ to: '/projects' })
+                return
+            }
+            const result = await switchProject(request.project)
+            if (result.success) {
+                toast.success(`Opened project: ${result.path ?? request.project}`)
+                queryClient.invalidateQueries()
+                refresh()
+                if (request.task) {
+                    openLaunchTask(request.task, request.namespace)
+                } else {
+                    navigate({ to: '/' })
+                }
+            } else {
+                toast.error(result.error || 'Failed to open project')
+            }
+        })
+        return () => {
+            void unlisten.then((stop) => stop())
+        }
+        // eslint-disable-next-line react-hooks/exhaustive-deps
+    }, [])
+
+    // Deep links and notification clicks
+    useEffect(() => {
+        const unlisten = onNavigateToTask((target) => {
+            if (target.requires_project_switch) {
+                toast.info(`${target.task_id} is in project ${target.namespace}; open it first`)
+                navigate({ to: '/projects' })
+                return
+            }
+            openLaunchTask(target.task_id, target.namespace)
+        })
+        return () => {
+            void unlisten.then((stop) => stop())
+        }
+        // eslint-disable-next-line react-hooks/exhaustive-deps
+    }, [])
+
+    // Crash reports from earlier runs: offer view / copy / discard
+    const [crashReports, setCrashReports] = useState<CrashReport[]>([])
+    const [crashDialogOpen, setCrashDialogOpen] = useState(false)
+    useEffect(() => {
+        const load = () => {
+            getCrashReports()
+                .then((reports) => {
+                    setCrashReports(reports)
+                    if (reports.length > 0) setCrashDialogOpen(true)
+                })
+                .catch(() => {})
+        }
+        // The startup event may fire before this listener exists; ask as well
+        const unlisten = onCrashReportAvailable(load)
+        load()
+        return () => {
+            void unlisten.then((stop) => stop())
+        }
+    }, [])
+
+    const copyCrashReport = useCallback(async (report: CrashReport) => {
+        try {
+            await navigator.clipboard.writeText(report.text)
+            toast.success('Crash report copied')
+        } catch {
+            toast.error('Failed to copy the crash report')
+        }
+    }, [])
+
+    const discardCrashReport = useCallback(async (report: CrashReport) => {
+        try {
+            await deleteCrashReport(report.id)
+            setCrashReports((reports) => reports.filter((r) => r.id !== report.id))
+        } catch (err) {
+            toast.error(err instanceof Error ? err.message : String(err))
+        }
+    }, [])
+
+    // Tasks captured in the quick-add window; a shortcut the OS refused
+    useEffect(() => {
+        const unlisten = onQuickTaskCreated(() => {
+            void queryClient.invalidateQueries()
+            refresh()
+        })
+        getQuickAddStatus()
+            .then((status) => {
+                if (status?.error) toast.error(`Quick add shortcut unavailable: ${status.error}`)
+            })
+            .catch(() => {})
+        return () => {
+            void unlisten.then((stop) => stop())
+        }
+        // eslint-disable-next-line react-hooks/exhaustive-deps
+    }, [])
+
+    // An APPLY_TASK_* variable was set to an unusable path and ignored
+    useEffect(() => {
+        const unlisten = onConfigWarning((warning) => {
+            toast.warning(warning.message)
+        })
+        return () => {
+            void unlisten.then((stop) => stop())
+        }
+    }, [])
+
+    // Startup update check found a newer release
+    useEffect(() => {
+        const unlisten = onUpdateAvailable((info) => {
+            toast.info(`Apply Task ${info.latest} is available (you have ${info.current}); see Settings → About`)
+        })
+        return () => {
+            void unlisten.then((stop) => stop())
+        }
+    }, [])
+
+    // Quitting during a write: the backend holds exit until the user confirms
+    useEffect(() => {
+        const unlisten = onExitConfirmRequested((tools) => {
+            if (window.confirm(`A change is still being saved (${tools.join(', ')}). Quit anyway?`)) {
+                void confirmExit()
+            }
+        })
+        return () => {
+            void unlisten.then((stop) => stop())
+        }
+    }, [])
+
     useEffect(() => {
         if (!isMobile) {
             setSidebarOpen(false)
@@ -418,6 +597,14 @@ function RootComponent() {
                 onRefresh={() => operationHistoryQuery.refetch()}
             />
 
+            <CrashReportDialog
+                isOpen={crashDialogOpen}
+                onClose={() => setCrashDialogOpen(false)}
+                reports={crashReports}
+                onCopy={(report) => void copyCrashReport(report)}
+                onDiscard={(report) => void discardCrashReport(report)}
+            />
+
             <ToastContainer />
         </div>
     )
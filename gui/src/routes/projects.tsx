@@ -1,9 +1,10 @@
 import { createRoute, useNavigate } from '@tanstack/react-router'
+import { useQuery, useQueryClient } from '@tanstack/react-query'
 import { Route as rootRoute } from './__root'
 import { ProjectsView } from '@/features/projects/components/ProjectsView'
 import { useTasks } from '@/features/tasks/hooks/useTasks'
 import { useUIStore } from '@/stores/uiStore'
-import { openProject } from '@/lib/tauri'
+import { listProjects, openProject, removeProject, switchProject } from '@/lib/tauri'
 import { toast } from '@/components/common/toast'
 
 export const Route = createRoute({
@@ -24,17 +25,42 @@ function Projects() {
 
     const { setSelectedNamespace } = useUIStore()
     const navigate = useNavigate()
+    const queryClient = useQueryClient()
+    const registryQuery = useQuery({ queryKey: ['projects'], queryFn: listProjects })
+
+    const afterSwitch = (path: string) => {
+        toast.success(`Opened project: ${path}`);
+        queryClient.invalidateQueries();
+        refresh();
+    }
 
     const handleOpenProject = async () => {
         const result = await openProject();
         if (result.success && result.path) {
-            toast.success(`Opened project: ${result.path}`);
-            refresh();
+            afterSwitch(result.path);
         } else if (result.error) {
             toast.error(result.error);
         }
     }
 
+    const handleSwitchProject = async (path: string) => {
+        const result = await switchProject(path);
+        if (result.success) {
+            afterSwitch(result.path ?? path);
+            navigate({ to: '/' })
+        } else {
+            toast.error(result.error || 'Failed to open project');
+        }
+    }
+
+    const handleForgetProject = async (path: string) => {
+        const result = await removeProject(path);
+        if (!result.success && result.error) {
+            toast.error(result.error);
+        }
+        queryClient.invalidateQueries({ queryKey: ['projects'] })
+    }
+
     return (
         <div className="flex flex-1 w-full min-h-0 flex-col bg-background overflow-hidden relative">
             <ProjectsView
@@ -44,6 +70,9 @@ function Projects() {
                 namespaces={namespaces}
                 isLoading={isLoading}
                 onOpenProject={handleOpenProject}
+                recentProjects={registryQuery.data?.projects ?? []}
+                onSwitchProject={handleSwitchProject}
+                onForgetProject={handleForgetProject}
                 onRefresh={refresh}
                 onSelectNamespace={(ns) => {
                     setSelectedNamespace(ns);
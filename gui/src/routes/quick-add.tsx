@@ -0,0 +1,9 @@
+import { createRoute } from '@tanstack/react-router'
+import { Route as rootRoute } from './__root'
+import { QuickAdd } from '@/features/tasks/components/QuickAdd'
+
+export const Route = createRoute({
+    getParentRoute: () => rootRoute,
+    path: '/quick-add',
+    component: QuickAdd,
+})
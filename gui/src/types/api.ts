@@ -24,6 +24,13 @@ export interface Suggestion {
   params?: Record<string, unknown>;
 }
 
+/** Suggestion the UI can run; without `intent` it is only a label */
+export interface SuggestionAction {
+  label: string;
+  intent: string | null;
+  params: Record<string, unknown> | null;
+}
+
 export interface AIError {
   code: string;
   message: string;
@@ -41,9 +48,70 @@ export interface AIResponse<T = unknown> {
   warnings: string[];
   context: Record<string, unknown>;
   suggestions: Suggestion[];
+  /** Every suggestion as an action, plain text recognized (set by the Tauri bridge) */
+  suggestion_items?: SuggestionAction[];
   meta: Record<string, unknown>;
   error: AIError | null;
   timestamp: string;
+  /** MCP tool that actually ran (set by the Tauri bridge) */
+  resolved_tool?: string | null;
+  /** Image parts of the tool result as `data:` URIs (set by the Tauri bridge) */
+  images?: string[];
+  /** Creation refused (`POSSIBLE_DUPLICATE`): tasks with similar titles (set by the Tauri bridge) */
+  possible_duplicates?: SimilarTask[];
+  /** `result` parsed for the tool that ran, on success (set by the Tauri bridge) */
+  typed?: IntentResult;
+}
+
+export interface CheckpointState {
+  confirmed: boolean;
+  auto_confirmed: boolean;
+  notes_count: number;
+  evidence_refs_count: number;
+}
+
+export interface HistoryEntry {
+  id: string;
+  /** Unix seconds */
+  timestamp: number;
+  intent: string;
+  task_id: string | null;
+  stream: string;
+  effect: string;
+  task_file: string | null;
+  undone: boolean;
+}
+
+/** `tasks_context` as parsed by the Tauri bridge */
+export interface TypedContext {
+  success: boolean;
+  focus_id: string | null;
+  active_task: Task | Plan | null;
+  current_subtask_path: string | null;
+  pending_checkpoints: Array<{ path: string; title: string; checkpoint: string }>;
+  recent_history: TaskEvent[];
+  summary: { plans: number; tasks: number; by_status: Record<string, number> };
+  tasks: TaskListItem[];
+  plans: PlanListItem[];
+  warnings: string[];
+  raw: ContextData;
+}
+
+/** Typed intent result, by the tool that ran (`raw` when it has no shape) */
+export type IntentResult =
+  | { kind: "context"; data: TypedContext }
+  | { kind: "created"; data: { task_id: string } }
+  | { kind: "verified"; data: { checkpoints: Record<string, CheckpointState> } }
+  | { kind: "history"; data: HistoryEntry[] }
+  | { kind: "next"; data: Suggestion }
+  | { kind: "raw"; data: unknown };
+
+export interface SimilarTask {
+  id: string;
+  title: string;
+  status: string;
+  /** 0..1 */
+  score: number;
 }
 
 export interface ContextData {